@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::changelog::timestamp_now;
+use crate::timing;
+
+/// What's appended to a file's name for its backup when no suffix is chosen
+pub const DEFAULT_SUFFIX: &str = ".bak";
+
+/// Replaced by the time of the backup in a suffix, to keep every backup
+pub const TIMESTAMP: &str = "{timestamp}";
+
+/// The suffix for timestamped backups the README suggests, whose backups are
+/// listed whichever suffix is chosen
+pub const TIMESTAMPED_SUFFIX: &str = ".{timestamp}.bak";
+
+#[derive(Default)]
+struct Backups {
+    /// Set by --backup-suffix or "backupSuffix" in the config
+    suffix: Option<String>,
+    /// Files backed up during this run
+    written: Vec<PathBuf>,
+}
+
+thread_local! {
+    static BACKUPS: RefCell<Backups> = RefCell::new(Backups::default());
+}
+
+/// Name backups with `suffix` and keep one for every edit, not just those that
+/// always make one
+pub fn set_suffix(suffix: &str) {
+    BACKUPS.with(|backups| backups.borrow_mut().suffix = Some(suffix.to_string()));
+}
+
+/// Whether a suffix was chosen, so every edit leaves a backup
+pub fn keeps_history() -> bool {
+    BACKUPS.with(|backups| backups.borrow().suffix.is_some())
+}
+
+fn suffix() -> String {
+    BACKUPS
+        .with(|backups| backups.borrow().suffix.clone())
+        .unwrap_or_else(|| DEFAULT_SUFFIX.to_string())
+}
+
+/// Whether `file` was backed up during this run
+pub fn was_backed_up(file: &Path) -> bool {
+    BACKUPS.with(|backups| backups.borrow().written.iter().any(|f| f == file))
+}
+
+/// Where a backup of `file` made now goes: its name with the suffix appended, the
+/// time in place of `{timestamp}`, and a number added if that's taken
+pub fn backup_path(file: &Path) -> PathBuf {
+    let time = timestamp_now().trim_end_matches('Z').replace(':', "-");
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    let path = file.with_file_name(format!("{}{}", name, suffix().replace(TIMESTAMP, &time)));
+    if !suffix().contains(TIMESTAMP) || !path.exists() {
+        return path;
+    }
+    (2..)
+        .map(|n| {
+            let stamped = format!("{}-{}", time, n);
+            file.with_file_name(format!("{}{}", name, suffix().replace(TIMESTAMP, &stamped)))
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+/// Write `content`, the content of `file` before an edit, to a backup of it
+pub fn write_backup(file: &Path, content: &str) -> Result<PathBuf> {
+    let path = backup_path(file);
+    timing::write(&path, content)
+        .with_context(|| format!("Failed to create backup at {}", path.display()))?;
+    BACKUPS.with(|backups| backups.borrow_mut().written.push(file.to_path_buf()));
+    Ok(path)
+}
+
+/// Back up `file` as it is before it's edited
+pub fn back_up(file: &Path) -> Result<PathBuf> {
+    let content =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    write_backup(file, &content)
+}
+
+/// The backups of `file` next to it, named with the default, the timestamped or
+/// the chosen suffix, newest first
+pub fn list_backups(file: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    let pattern = |suffix: &str| {
+        regex::escape(suffix).replace(
+            &regex::escape(TIMESTAMP),
+            r"\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}(?:-\d+)?",
+        )
+    };
+    let names = Regex::new(&format!(
+        "^{}(?:{}|{}|{})$",
+        regex::escape(&name),
+        pattern(DEFAULT_SUFFIX),
+        pattern(TIMESTAMPED_SUFFIX),
+        pattern(&suffix())
+    ))
+    .unwrap();
+
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut backups: Vec<(PathBuf, SystemTime)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| names.is_match(&entry.file_name().to_string_lossy()))
+        .map(|entry| {
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (file.with_file_name(entry.file_name()), modified)
+        })
+        .collect();
+    backups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    backups
+}
+
+/// The .gitignore patterns for backups named with the default, the timestamped
+/// and the chosen suffix
+pub fn gitignore_patterns() -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+    for suffix in [
+        DEFAULT_SUFFIX.to_string(),
+        TIMESTAMPED_SUFFIX.to_string(),
+        suffix(),
+    ] {
+        let pattern = format!("*.elm{}", suffix.replace(TIMESTAMP, "*"));
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+    patterns
+}
+
+/// The patterns of [`gitignore_patterns`] that the .gitignore `content` lacks
+pub fn missing_gitignore_patterns(content: &str) -> Vec<String> {
+    gitignore_patterns()
+        .into_iter()
+        .filter(|pattern| !content.lines().any(|line| line.trim() == pattern))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_lists_timestamped_backups() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("I18n.elm");
+        fs::write(&file, "v3").unwrap();
+
+        assert_eq!(backup_path(&file), dir.path().join("I18n.elm.bak"));
+        fs::write(dir.path().join("I18n.elm.bak"), "v0").unwrap();
+
+        set_suffix(TIMESTAMPED_SUFFIX);
+        let first = write_backup(&file, "v1").unwrap();
+        let name = first.file_name().unwrap().to_string_lossy().to_string();
+        assert!(
+            Regex::new(r"^I18n\.elm\.\d{4}-\d\d-\d\dT\d\d-\d\d-\d\d\.bak$")
+                .unwrap()
+                .is_match(&name),
+            "{}",
+            name
+        );
+        // A second backup in the same second doesn't replace the first
+        let second = write_backup(&file, "v2").unwrap();
+        assert_ne!(first, second);
+        assert!(was_backed_up(&file));
+
+        fs::write(dir.path().join("Other.elm.bak"), "").unwrap();
+        fs::write(dir.path().join("I18n.elm.orig"), "").unwrap();
+        let listed: Vec<PathBuf> = list_backups(&file).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(listed.len(), 3);
+        assert!(listed.contains(&first) && listed.contains(&second));
+        assert!(listed.contains(&dir.path().join("I18n.elm.bak")));
+    }
+
+    #[test]
+    fn lists_the_gitignore_patterns_a_file_lacks() {
+        set_suffix(".orig");
+        assert_eq!(
+            gitignore_patterns(),
+            vec!["*.elm.bak", "*.elm.*.bak", "*.elm.orig"]
+        );
+        assert_eq!(
+            missing_gitignore_patterns("node_modules\n*.elm.bak\n  *.elm.orig\n"),
+            vec!["*.elm.*.bak"]
+        );
+    }
+}
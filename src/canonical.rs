@@ -0,0 +1,300 @@
+use anyhow::Result;
+
+use crate::escaping::{escape, unescape};
+use crate::fix::reorder_record;
+use crate::layout::{layout_translation_blocks, LayoutOutcome};
+use crate::parser::parse_i18n_content_with_record_name;
+use crate::types::RecordField;
+
+/// The file in one deterministic form, so two files with the same translations and
+/// the same code around them come out byte-identical
+///
+/// It composes what `format`, `lint --fix` and the editing commands already do: the
+/// blocks get `format`'s layout, the type's fields are sorted by name with every
+/// record in the same order, each plain String value is written as a `"…"` literal
+/// the way elm-i18n writes new ones (escapes normalized, `"""` strings included),
+/// and blank lines follow elm-format: two before a top-level declaration, one at
+/// most inside one. Trailing whitespace goes, and the file ends with one newline.
+pub fn canonicalize(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<LayoutOutcome> {
+    let laid_out = layout_translation_blocks(source, record_name, languages)?;
+    let sorted = sort_fields(&laid_out.content, record_name, languages)?;
+    let literals = normalize_literals(&sorted, record_name, languages)?;
+    Ok(LayoutOutcome {
+        content: normalize_blank_lines(&literals),
+        skipped: laid_out.skipped,
+    })
+}
+
+/// The type's fields sorted by name, and every record's fields in that order
+fn sort_fields(source: &str, record_name: &str, languages: &[String]) -> Result<String> {
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let mut order: Vec<&str> = parse_result
+        .type_fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    order.sort_unstable();
+
+    let type_fields: Vec<RecordField> = parse_result
+        .type_fields
+        .iter()
+        .map(|field| RecordField {
+            name: field.name.clone(),
+            value: field.type_annotation.clone(),
+            line: field.line,
+            end_line: field.end_line,
+        })
+        .collect();
+    let mut blocks: Vec<(usize, usize, &[RecordField])> = vec![(
+        parse_result.type_start_line,
+        parse_result.type_end_line,
+        &type_fields,
+    )];
+    for (lang, start, end) in &parse_result.lang_bounds {
+        blocks.push((*start, *end, &parse_result.record_fields[lang]));
+    }
+    // From the bottom of the file up so earlier line numbers stay valid
+    blocks.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    for (start, end, fields) in blocks {
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        let mut sorted = names.clone();
+        sorted.sort_by_key(|name| order.iter().position(|o| o == name).unwrap_or(usize::MAX));
+        if names != sorted {
+            reorder_record(&mut lines, start, end, fields, &order);
+        }
+    }
+    Ok(join(&lines, source.ends_with('\n')))
+}
+
+/// Each record field whose value is a single String literal, rewritten as the
+/// `"…"` literal `escape` makes of its text
+fn normalize_literals(source: &str, record_name: &str, languages: &[String]) -> Result<String> {
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+    for fields in parse_result.record_fields.values() {
+        for field in fields {
+            let line_start = line_starts[field.line];
+            let Some(equals) = source[line_start..].find('=').map(|i| line_start + i) else {
+                continue;
+            };
+            let Some((body, literal_end)) = string_literal_at(source, equals + 1) else {
+                continue;
+            };
+            // Only a literal that is the whole value, not the start of `"…" ++ name`
+            let after = source[literal_end..].trim_start();
+            if !(after.starts_with(',') || after.starts_with('}') || after.starts_with("--")) {
+                continue;
+            }
+            let canonical = format!(" \"{}\"", escape(&unescape(body)));
+            if source[equals + 1..literal_end] != canonical {
+                replacements.push((equals + 1, literal_end, canonical));
+            }
+        }
+    }
+    replacements.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    let mut content = source.to_string();
+    for (start, end, canonical) in replacements {
+        content.replace_range(start..end, &canonical);
+    }
+    Ok(content)
+}
+
+/// The body of the string literal after the whitespace at `from`, if that's what
+/// comes next, and where the literal ends
+fn string_literal_at(source: &str, from: usize) -> Option<(&str, usize)> {
+    let rest = &source[from..];
+    let start = from + (rest.len() - rest.trim_start().len());
+    let quotes = if source[start..].starts_with("\"\"\"") {
+        "\"\"\""
+    } else if source[start..].starts_with('"') {
+        "\""
+    } else {
+        return None;
+    };
+    let mut chars = source[start + quotes.len()..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        let at = start + quotes.len() + i;
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' if quotes == "\"" => return None,
+            _ if source[at..].starts_with(quotes) => {
+                return Some((&source[start + quotes.len()..at], at + quotes.len()))
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Two blank lines before each top-level declaration and at most one inside one,
+/// as elm-format lays them out, leaving multi-line strings and block comments alone
+fn normalize_blank_lines(source: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let mut in_string = false;
+    let mut comment_depth = 0usize;
+    let mut blanks = 0;
+    for line in source.lines() {
+        let verbatim = in_string || comment_depth > 0;
+        in_string ^= line.matches("\"\"\"").count() % 2 == 1;
+        if !in_string {
+            comment_depth = (comment_depth + line.matches("{-").count())
+                .saturating_sub(line.matches("-}").count());
+        }
+        if verbatim {
+            output.extend(std::iter::repeat_n("", blanks));
+            blanks = 0;
+            output.push(line);
+            continue;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            blanks += 1;
+            continue;
+        }
+
+        let previous = output.iter().rev().find(|l| !l.trim().is_empty());
+        let top_level = !line.starts_with(char::is_whitespace);
+        let wanted = match previous {
+            None => 0,
+            Some(_) if !top_level => blanks.min(1),
+            Some(previous) if line.starts_with("import ") => {
+                if previous.starts_with("import ") {
+                    0
+                } else {
+                    1
+                }
+            }
+            Some(previous) if is_module_line(previous) && line.starts_with("{-|") => 1,
+            // A definition right under its annotation, and a comment or doc
+            // comment right above what it's about, stay attached
+            Some(previous) if blanks == 0 && is_attached(previous, line) => 0,
+            Some(_) => 2,
+        };
+        output.extend(std::iter::repeat_n("", wanted));
+        blanks = 0;
+        output.push(line);
+    }
+    let mut content = output.join("\n");
+    content.push('\n');
+    content
+}
+
+fn is_module_line(line: &str) -> bool {
+    ["module ", "port module ", "effect module "]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+fn is_attached(previous: &str, line: &str) -> bool {
+    let name = |line: &str| {
+        line.split(|c: char| !c.is_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+    let is_annotation = previous
+        .split_once(':')
+        .is_some_and(|(name, _)| !name.trim().is_empty() && !name.trim().contains(' '));
+    (is_annotation && !previous.starts_with(char::is_whitespace) && name(previous) == name(line))
+        || previous.starts_with("--")
+        || previous.trim_end().ends_with("-}")
+}
+
+fn join(lines: &[String], trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(source: &str) -> String {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        canonicalize(source, "Translations", &languages)
+            .unwrap()
+            .content
+    }
+
+    #[test]
+    fn gives_files_with_the_same_translations_the_same_bytes() {
+        let tidy = "module I18n exposing (..)
+
+
+type Language
+    = EN
+    | FR
+
+
+type alias Translations =
+    { cancel : String
+    , itemCount : Int -> String
+    , save : String
+    }
+
+
+-- English
+translationsEn : Translations
+translationsEn =
+    { cancel = \"Cancel\"
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" items\"
+    , save = \"Save \\\"all\\\"\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { cancel = \"Annuler\"
+    , itemCount = \\n -> String.fromInt n ++ \" articles\"
+    , save = \"Tout\\nenregistrer\"
+    }
+";
+        let messy = "module I18n exposing (..)
+type Language
+    = EN
+    | FR
+type alias Translations =
+  {   save:String,
+      cancel  :  String,
+      itemCount : Int -> String
+  }
+
+-- English
+translationsEn : Translations
+translationsEn =
+      { itemCount =
+          \\n ->
+              String.fromInt n ++ \" items\"
+      , save = \"\"\"Save \"all\\\"\"\"\"
+      , cancel = \"Cancel\"
+      }
+translationsFr : Translations
+translationsFr =
+    { save = \"\"\"Tout
+enregistrer\"\"\"
+    , itemCount = \\n -> String.fromInt n ++ \" articles\"
+    , cancel = \"Annu\\u{006C}er\"
+    }
+
+
+";
+        assert_eq!(canonical(messy), tidy);
+        assert_eq!(canonical(tidy), tidy);
+    }
+}
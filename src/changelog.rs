@@ -0,0 +1,247 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::types::ParseResult;
+
+/// What happened to one key in one language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A value that differs between two versions of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationChange {
+    pub key: String,
+    pub language: String,
+    pub change: ChangeKind,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// One line of the changelog
+///
+/// Values are the Elm expressions as they appear in the file, so a plain string
+/// keeps its quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangeRecord {
+    pub timestamp: String,
+    pub command: String,
+    pub file: String,
+    pub key: String,
+    pub language: String,
+    pub change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+}
+
+/// Every value that differs between two parses of a file, ordered by key then language
+///
+/// `before` is `None` for a file the command created.
+pub fn translation_changes(
+    before: Option<&ParseResult>,
+    after: &ParseResult,
+    languages: &[String],
+) -> Vec<TranslationChange> {
+    let value = |result: Option<&ParseResult>, key: &str, lang: &str| {
+        result
+            .and_then(|r| r.translations.get(key))
+            .and_then(|t| t.values.get(lang))
+            .filter(|v| !v.is_empty())
+            .cloned()
+    };
+
+    let mut keys: Vec<&String> = after.translations.keys().collect();
+    if let Some(before) = before {
+        keys.extend(before.translations.keys());
+    }
+    keys.sort();
+    keys.dedup();
+
+    let mut changes = Vec::new();
+    for key in keys {
+        for lang in languages {
+            let old = value(before, key, lang);
+            let new = value(Some(after), key, lang);
+            let change = match (&old, &new) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(o), Some(n)) if o != n => ChangeKind::Modified,
+                _ => continue,
+            };
+            changes.push(TranslationChange {
+                key: key.clone(),
+                language: lang.clone(),
+                change,
+                old,
+                new,
+            });
+        }
+    }
+    changes
+}
+
+/// Append records to a JSON Lines file, creating it if needed
+pub fn append_records(path: &Path, records: &[ChangeRecord]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open changelog {}", path.display()))?;
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("Failed to write changelog {}", path.display()))
+}
+
+/// The current time as an RFC 3339 UTC timestamp (e.g., "2024-05-01T09:30:00Z")
+pub fn timestamp_now() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_timestamp(seconds)
+}
+
+/// `unix_seconds` as a UTC time like 2024-05-01T09:30:00Z
+pub fn format_timestamp(unix_seconds: u64) -> String {
+    let days = (unix_seconds / 86_400) as i64;
+    let secs = unix_seconds % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    fn parse(source: &str) -> ParseResult {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        parse_i18n_content_with_record_name(source, "Translations", &languages).unwrap()
+    }
+
+    #[test]
+    fn lists_added_removed_and_modified_values() {
+        let before = parse(
+            "type alias Translations =
+    { save : String
+    , cancel : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    , cancel = \"Cancel\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Sauver\"
+    , cancel = \"Annuler\"
+    }
+",
+        );
+        let after = parse(
+            "type alias Translations =
+    { save : String
+    , close : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    , close = \"Close\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , close = \"Fermer\"
+    }
+",
+        );
+        let languages = vec!["en".to_string(), "fr".to_string()];
+
+        let changes: Vec<(String, String, ChangeKind)> =
+            translation_changes(Some(&before), &after, &languages)
+                .into_iter()
+                .map(|c| (c.key, c.language, c.change))
+                .collect();
+        let expected = [
+            ("cancel", "en", ChangeKind::Removed),
+            ("cancel", "fr", ChangeKind::Removed),
+            ("close", "en", ChangeKind::Added),
+            ("close", "fr", ChangeKind::Added),
+            ("save", "fr", ChangeKind::Modified),
+        ];
+        assert_eq!(
+            changes,
+            expected
+                .iter()
+                .map(|(k, l, c)| (k.to_string(), l.to_string(), *c))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn serializes_one_record_per_line() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_251_199), "2024-02-29T23:59:59Z");
+
+        let record = ChangeRecord {
+            timestamp: format_timestamp(0),
+            command: "modify".to_string(),
+            file: "src/I18n.elm".to_string(),
+            key: "save".to_string(),
+            language: "fr".to_string(),
+            change: ChangeKind::Modified,
+            old: Some("\"Sauver\"".to_string()),
+            new: Some("\"Enregistrer\"".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"timestamp":"1970-01-01T00:00:00Z","command":"modify","file":"src/I18n.elm","key":"save","language":"fr","change":"modified","old":"\"Sauver\"","new":"\"Enregistrer\""}"#
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::config::Severity;
+use crate::lint::{field_line, LintFinding};
+use crate::lock::{lock_drift, lock_entries, DriftKind, Lock};
+use crate::types::ParseResult;
+use crate::validate::{consistency_issues, value_status, ValueStatus};
+
+/// A check `ci` runs, configured by name under "ci.checks"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Everything `lint` reports
+    Lint,
+    /// Values that are missing, empty or still start with the TODO marker
+    Untranslated,
+    /// Function arguments and `{placeholder}`s that differ between languages
+    Placeholders,
+    /// Values that changed since `elm-i18n.lock` was written
+    Lock,
+    /// Keys the Elm code never reads
+    Unused,
+}
+
+/// Every check, in the order `ci` runs and reports them
+pub const CHECKS: [Check; 5] = [
+    Check::Lint,
+    Check::Untranslated,
+    Check::Placeholders,
+    Check::Lock,
+    Check::Unused,
+];
+
+/// Rule names findings are reported under, besides the `lint` ones
+pub const UNTRANSLATED: &str = "untranslated";
+pub const PLACEHOLDERS: &str = "placeholders";
+pub const LOCK_DRIFT: &str = "lock-drift";
+pub const UNUSED_KEY: &str = "unused-key";
+
+impl Check {
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Lint => "lint",
+            Check::Untranslated => "untranslated",
+            Check::Placeholders => "placeholders",
+            Check::Lock => "lock",
+            Check::Unused => "unused",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Check> {
+        CHECKS.into_iter().find(|check| check.name() == name)
+    }
+}
+
+/// `findings` with none more serious than `severity`, so a team can make `lint`
+/// a warning in CI while it cleans up
+pub fn capped(findings: Vec<LintFinding>, severity: Severity) -> Vec<LintFinding> {
+    findings
+        .into_iter()
+        .map(|finding| LintFinding {
+            severity: finding.severity.min(severity),
+            ..finding
+        })
+        .collect()
+}
+
+/// A finding for every value that isn't translated yet
+pub fn untranslated(
+    parse_result: &ParseResult,
+    languages: &[String],
+    todo_marker: &str,
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for field in &parse_result.type_fields {
+        let Some(translation) = parse_result.translations.get(&field.name) else {
+            continue;
+        };
+        for lang in languages {
+            let message = match value_status(translation.values.get(lang), todo_marker) {
+                ValueStatus::Translated => continue,
+                ValueStatus::Missing => "has no value".to_string(),
+                ValueStatus::Empty => "is empty".to_string(),
+                ValueStatus::Todo => format!("still has the `{}` marker", todo_marker),
+            };
+            findings.push(LintFinding {
+                rule: UNTRANSLATED,
+                severity,
+                key: field.name.clone(),
+                language: Some(lang.clone()),
+                line: field_line(parse_result, lang, &field.name).or(Some(field.line + 1)),
+                message,
+            });
+        }
+    }
+    findings
+}
+
+/// A finding for every argument or `{placeholder}` mismatch between languages
+pub fn placeholders(
+    parse_result: &ParseResult,
+    languages: &[String],
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for field in &parse_result.type_fields {
+        let Some(translation) = parse_result.translations.get(&field.name) else {
+            continue;
+        };
+        for issue in consistency_issues(&field.name, translation, languages) {
+            let line = issue
+                .language
+                .as_ref()
+                .and_then(|lang| field_line(parse_result, lang, &field.name))
+                .or(Some(field.line + 1));
+            findings.push(LintFinding {
+                rule: PLACEHOLDERS,
+                severity,
+                key: issue.key,
+                language: issue.language,
+                line,
+                message: issue.message,
+            });
+        }
+    }
+    findings
+}
+
+/// A finding for every value of `file` added, removed or changed since `locked`
+pub fn lock_changes(
+    file: &str,
+    parse_result: &ParseResult,
+    locked: &Lock,
+    languages: &[String],
+    severity: Severity,
+) -> Vec<LintFinding> {
+    // The lock covers every translation file; compare this one's entries only
+    let locked: Lock = locked
+        .iter()
+        .filter(|(entry, _)| entry.file == file)
+        .map(|(entry, hash)| (entry.clone(), hash.clone()))
+        .collect();
+    let current = lock_entries(file, parse_result, languages);
+    lock_drift(&locked, &current)
+        .into_iter()
+        .map(|drift| {
+            let message = match drift.kind {
+                DriftKind::Added => "was added since the last `elm-i18n lock`",
+                DriftKind::Removed => "was removed since the last `elm-i18n lock`",
+                DriftKind::Changed => "changed since the last `elm-i18n lock`",
+            };
+            LintFinding {
+                rule: LOCK_DRIFT,
+                severity,
+                line: field_line(parse_result, &drift.entry.language, &drift.entry.key),
+                key: drift.entry.key,
+                language: Some(drift.entry.language),
+                message: message.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// A finding on the type field of each key in `unused`
+pub fn unused(
+    parse_result: &ParseResult,
+    unused: &[String],
+    severity: Severity,
+) -> Vec<LintFinding> {
+    parse_result
+        .type_fields
+        .iter()
+        .filter(|field| unused.contains(&field.name))
+        .map(|field| LintFinding {
+            rule: UNUSED_KEY,
+            severity,
+            key: field.name.clone(),
+            language: None,
+            line: Some(field.line + 1),
+            message: "is never read by the Elm code".to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lock::lock_entries;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    const SOURCE: &str = r#"module I18n exposing (..)
+
+type alias Translations =
+    { greeting : String
+    , farewell : String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { greeting = "Hello {name}"
+    , farewell = "Bye"
+    }
+
+translationsFr : Translations
+translationsFr =
+    { greeting = "Bonjour {nom}"
+    , farewell = "TODO: Au revoir"
+    }
+"#;
+
+    #[test]
+    fn reports_each_check_on_the_lines_of_the_fields() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result =
+            parse_i18n_content_with_record_name(SOURCE, "Translations", &languages).unwrap();
+
+        let found = untranslated(&parse_result, &languages, "TODO", Severity::Error);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (
+                found[0].key.as_str(),
+                found[0].language.as_deref(),
+                found[0].line
+            ),
+            ("farewell", Some("fr"), Some(17))
+        );
+
+        let found = placeholders(&parse_result, &languages, Severity::Warning);
+        assert!(!found.is_empty());
+        assert!(found
+            .iter()
+            .all(|f| f.key == "greeting" && f.severity == Severity::Warning));
+
+        let locked = lock_entries("src/I18n.elm", &parse_result, &languages);
+        let edited = SOURCE.replace("\"Bye\"", "\"Goodbye\"");
+        let edited =
+            parse_i18n_content_with_record_name(&edited, "Translations", &languages).unwrap();
+        let found = lock_changes(
+            "src/I18n.elm",
+            &edited,
+            &locked,
+            &languages,
+            Severity::Error,
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (
+                found[0].key.as_str(),
+                found[0].language.as_deref(),
+                found[0].line
+            ),
+            ("farewell", Some("en"), Some(11))
+        );
+        // Entries of other files in the lock don't count as removed
+        assert!(lock_changes(
+            "src/Other.elm",
+            &edited,
+            &locked,
+            &languages,
+            Severity::Error
+        )
+        .iter()
+        .all(|f| f.message.starts_with("was added")));
+
+        let found = unused(&parse_result, &["farewell".to_string()], Severity::Error);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line, Some(5));
+
+        let capped = capped(found, Severity::Warning);
+        assert_eq!(capped[0].severity, Severity::Warning);
+    }
+}
@@ -5,8 +5,14 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::errors::{self, Code, Coded};
+use crate::extract::DEFAULT_FUNCTIONS;
+use crate::naming::NamingRules;
+use crate::scan::DEFAULT_VARIABLES;
+
 const CONFIG_FILE_NAME: &str = "elm-i18n/config.json";
 const ELM_I18N_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_TODO_MARKER: &str = "TODO";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "mode")]
@@ -21,6 +27,46 @@ pub enum Config {
         file: PathBuf,
         #[serde(rename = "recordName")]
         record_name: String,
+        #[serde(default, skip_serializing_if = "LintConfig::is_empty")]
+        lint: LintConfig,
+        #[serde(default, skip_serializing_if = "ScanConfig::is_empty")]
+        scan: ScanConfig,
+        #[serde(default, skip_serializing_if = "CiConfig::is_empty")]
+        ci: CiConfig,
+        #[serde(default, skip_serializing_if = "ChangelogConfig::is_empty")]
+        changelog: ChangelogConfig,
+        #[serde(
+            default,
+            rename = "postEditHook",
+            skip_serializing_if = "Option::is_none"
+        )]
+        post_edit_hook: Option<String>,
+        #[serde(
+            default,
+            rename = "elmFormat",
+            skip_serializing_if = "std::ops::Not::not"
+        )]
+        elm_format: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        indent: Option<usize>,
+        #[serde(
+            default,
+            rename = "backupSuffix",
+            skip_serializing_if = "Option::is_none"
+        )]
+        backup_suffix: Option<String>,
+        #[serde(
+            default,
+            rename = "keyStyle",
+            skip_serializing_if = "KeyStyle::is_flat"
+        )]
+        key_style: KeyStyle,
+        #[serde(
+            default,
+            rename = "placeholderStyle",
+            skip_serializing_if = "Option::is_none"
+        )]
+        placeholder_style: Option<PlaceholderStyle>,
     },
     #[serde(rename = "multi-file")]
     MultiFile {
@@ -30,9 +76,77 @@ pub enum Config {
         #[serde(rename = "sourceDir")]
         source_dir: PathBuf,
         files: HashMap<String, FileConfig>,
+        #[serde(default, skip_serializing_if = "LintConfig::is_empty")]
+        lint: LintConfig,
+        #[serde(default, skip_serializing_if = "ScanConfig::is_empty")]
+        scan: ScanConfig,
+        #[serde(default, skip_serializing_if = "CiConfig::is_empty")]
+        ci: CiConfig,
+        #[serde(default, skip_serializing_if = "ChangelogConfig::is_empty")]
+        changelog: ChangelogConfig,
+        #[serde(
+            default,
+            rename = "postEditHook",
+            skip_serializing_if = "Option::is_none"
+        )]
+        post_edit_hook: Option<String>,
+        #[serde(
+            default,
+            rename = "elmFormat",
+            skip_serializing_if = "std::ops::Not::not"
+        )]
+        elm_format: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        indent: Option<usize>,
+        #[serde(
+            default,
+            rename = "backupSuffix",
+            skip_serializing_if = "Option::is_none"
+        )]
+        backup_suffix: Option<String>,
+        #[serde(
+            default,
+            rename = "keyStyle",
+            skip_serializing_if = "KeyStyle::is_flat"
+        )]
+        key_style: KeyStyle,
+        #[serde(
+            default,
+            rename = "placeholderStyle",
+            skip_serializing_if = "Option::is_none"
+        )]
+        placeholder_style: Option<PlaceholderStyle>,
     },
 }
 
+/// How keys are grouped, stored as "keyStyle" in the config file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStyle {
+    /// By a camelCase prefix: `checkoutTitle` and `checkoutPay` are the `checkout` group
+    #[default]
+    Flat,
+    /// In nested records: `checkout.title` is read as `t.checkout.title`
+    Nested,
+}
+
+impl KeyStyle {
+    pub fn is_flat(&self) -> bool {
+        *self == KeyStyle::Flat
+    }
+}
+
+/// How values write their placeholders, stored as "placeholderStyle" in the config
+/// file; `validate` flags the values that write them another way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderStyle {
+    /// `{name}` and nothing else
+    Braces,
+    /// ICU MessageFormat: `{name}`, and arguments like `{count, plural, ...}`
+    Icu,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileConfig {
     pub path: PathBuf,
@@ -40,6 +154,188 @@ pub struct FileConfig {
     pub record_name: String,
 }
 
+/// How seriously a lint rule's findings are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+/// Lint settings stored under "lint" in the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Per-rule severity overrides (e.g., {"unbalanced-markup": "warning"})
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rules: HashMap<String, Severity>,
+    /// Length budgets by key pattern (e.g., {"nav*": 12}); `*` matches any characters
+    #[serde(
+        default,
+        rename = "maxLength",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub max_length: HashMap<String, usize>,
+    /// CSV file of terms that must always be translated the same way
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glossary: Option<PathBuf>,
+    /// Rules every key name must follow
+    #[serde(default, skip_serializing_if = "NamingConfig::is_empty")]
+    pub naming: NamingConfig,
+    /// Text that marks a value as not translated yet (defaults to "TODO")
+    #[serde(
+        default,
+        rename = "todoMarker",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub todo_marker: Option<String>,
+    /// Soft limit on how many keys one file's type may have
+    #[serde(default, rename = "maxKeys", skip_serializing_if = "Option::is_none")]
+    pub max_keys: Option<usize>,
+}
+
+/// Settings for `scan`, stored under "scan" in the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanConfig {
+    /// Keys to count as used even with no usage in the code, such as keys read
+    /// through a dynamic lookup (e.g., ["errorCode*"]); `*` matches any characters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// Names of variables that hold translations, for `scan --missing` (defaults to
+    /// ["t", "translations"]); variables annotated with the record type always count
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<String>,
+    /// Elm files not to search (e.g., ["src/Generated/**"])
+    #[serde(default, rename = "ignorePaths", skip_serializing_if = "Vec::is_empty")]
+    pub ignore_paths: Vec<String>,
+    /// Functions whose string argument `extract` reports as hardcoded copy (defaults
+    /// to ["text", "placeholder", "title", "alt"])
+    #[serde(
+        default,
+        rename = "extractFunctions",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub extract_functions: Vec<String>,
+}
+
+/// Settings for `ci`, stored under "ci" in the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CiConfig {
+    /// Per-check severity (e.g., {"untranslated": "warning", "lock": "off"}); checks
+    /// not listed are errors
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub checks: HashMap<String, Severity>,
+}
+
+impl CiConfig {
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+}
+
+/// Settings for the `changelog` command, stored under "changelog" in the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangelogConfig {
+    /// Keys to leave out of release notes, such as internal or debug strings (e.g.,
+    /// ["debug*"]); `*` matches any characters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+}
+
+impl ChangelogConfig {
+    pub fn is_empty(&self) -> bool {
+        self.ignore.is_empty()
+    }
+}
+
+/// Key naming rules stored under "lint.naming" in the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamingConfig {
+    /// A built-in set of rules to apply on top of the others
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<NamingPreset>,
+    /// Regex every key must match (e.g., "^[a-z][a-zA-Z0-9]*$")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Longest key allowed, in characters
+    #[serde(default, rename = "maxLength", skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Namespaces a key must start with (e.g., ["checkout", "home"])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+    /// Text no key may contain (e.g., ["Temp", "_"])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NamingPreset {
+    /// Lowercase first letter, no underscores, no runs of capitals (`saveUrl`, not `saveURL`)
+    #[serde(rename = "camelCase-strict")]
+    CamelCaseStrict,
+    /// A namespace word before the rest of the key (`checkoutTitle`, not `title`)
+    #[serde(rename = "prefix-required")]
+    PrefixRequired,
+}
+
+impl NamingConfig {
+    pub fn is_empty(&self) -> bool {
+        self.preset.is_none()
+            && self.pattern.is_none()
+            && self.max_length.is_none()
+            && self.prefixes.is_empty()
+            && self.banned.is_empty()
+    }
+}
+
+impl ScanConfig {
+    pub fn is_empty(&self) -> bool {
+        self.ignore.is_empty()
+            && self.variables.is_empty()
+            && self.ignore_paths.is_empty()
+            && self.extract_functions.is_empty()
+    }
+
+    /// The configured translation variables, or `t` and `translations`
+    pub fn variables(&self) -> Vec<String> {
+        if self.variables.is_empty() {
+            DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect()
+        } else {
+            self.variables.clone()
+        }
+    }
+
+    /// The configured extract functions, or `text`, `placeholder`, `title` and `alt`
+    pub fn extract_functions(&self) -> Vec<String> {
+        if self.extract_functions.is_empty() {
+            DEFAULT_FUNCTIONS.iter().map(|f| f.to_string()).collect()
+        } else {
+            self.extract_functions.clone()
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+            && self.max_length.is_empty()
+            && self.glossary.is_none()
+            && self.naming.is_empty()
+            && self.todo_marker.is_none()
+            && self.max_keys.is_none()
+    }
+
+    /// The configured TODO marker, or "TODO"
+    pub fn todo_marker(&self) -> &str {
+        self.todo_marker.as_deref().unwrap_or(DEFAULT_TODO_MARKER)
+    }
+
+    /// Severity configured for a rule, falling back to the rule's default
+    pub fn severity(&self, rule: &str, default: Severity) -> Severity {
+        self.rules.get(rule).copied().unwrap_or(default)
+    }
+}
+
 impl Config {
     /// Load config from current directory
     pub fn load() -> Result<Option<Self>> {
@@ -49,17 +345,26 @@ impl Config {
             return Ok(None);
         }
 
-        let content = fs::read_to_string(config_path)
-            .with_context(|| format!("Failed to read {}", config_path.display()))?;
+        let content = fs::read_to_string(config_path).with_context(|| {
+            Coded::new(
+                Code::Config,
+                format!("Failed to read {}", config_path.display()),
+            )
+        })?;
 
         let config: Config = serde_json::from_str(&content).with_context(|| {
-            format!(
-                "Failed to parse {}. Please check the JSON syntax.",
-                config_path.display()
+            Coded::new(
+                Code::Config,
+                format!(
+                    "Failed to parse {}. Please check the JSON syntax.",
+                    config_path.display()
+                ),
             )
         })?;
 
-        config.validate()?;
+        config
+            .validate()
+            .map_err(|e| errors::coded(Code::Config, e))?;
 
         Ok(Some(config))
     }
@@ -152,6 +457,44 @@ impl Config {
             }
         }
 
+        NamingRules::new(&self.lint().naming)
+            .with_context(|| format!("Invalid lint.naming in {}", CONFIG_FILE_NAME))?;
+
+        if self.lint().todo_marker().trim().is_empty() {
+            bail!("lint.todoMarker in {} can't be empty", CONFIG_FILE_NAME);
+        }
+
+        if self.lint().max_keys == Some(0) {
+            bail!("lint.maxKeys in {} must be at least 1", CONFIG_FILE_NAME);
+        }
+
+        let scan = self.scan();
+        for (name, entries) in [
+            ("ignore", &scan.ignore),
+            ("variables", &scan.variables),
+            ("ignorePaths", &scan.ignore_paths),
+            ("extractFunctions", &scan.extract_functions),
+        ] {
+            if entries.iter().any(|entry| entry.trim().is_empty()) {
+                bail!(
+                    "scan.{} in {} can't have empty entries",
+                    name,
+                    CONFIG_FILE_NAME
+                );
+            }
+        }
+        if self
+            .changelog()
+            .ignore
+            .iter()
+            .any(|pattern| pattern.trim().is_empty())
+        {
+            bail!(
+                "changelog.ignore in {} can't have empty entries",
+                CONFIG_FILE_NAME
+            );
+        }
+
         Ok(())
     }
 
@@ -171,6 +514,107 @@ impl Config {
         }
     }
 
+    /// Get lint settings
+    pub fn lint(&self) -> &LintConfig {
+        match self {
+            Config::SingleFile { lint, .. } => lint,
+            Config::MultiFile { lint, .. } => lint,
+        }
+    }
+
+    /// Get scan settings
+    pub fn scan(&self) -> &ScanConfig {
+        match self {
+            Config::SingleFile { scan, .. } => scan,
+            Config::MultiFile { scan, .. } => scan,
+        }
+    }
+
+    /// Get ci settings
+    pub fn ci(&self) -> &CiConfig {
+        match self {
+            Config::SingleFile { ci, .. } => ci,
+            Config::MultiFile { ci, .. } => ci,
+        }
+    }
+
+    /// Get changelog settings
+    pub fn changelog(&self) -> &ChangelogConfig {
+        match self {
+            Config::SingleFile { changelog, .. } => changelog,
+            Config::MultiFile { changelog, .. } => changelog,
+        }
+    }
+
+    /// Get the command to run after each edit (e.g., "elm-format --yes {file}")
+    pub fn post_edit_hook(&self) -> Option<&str> {
+        match self {
+            Config::SingleFile { post_edit_hook, .. } => post_edit_hook.as_deref(),
+            Config::MultiFile { post_edit_hook, .. } => post_edit_hook.as_deref(),
+        }
+    }
+
+    /// Whether edited files are run through elm-format
+    pub fn elm_format(&self) -> bool {
+        match self {
+            Config::SingleFile { elm_format, .. } => *elm_format,
+            Config::MultiFile { elm_format, .. } => *elm_format,
+        }
+    }
+
+    /// Spaces to indent written fields with, when the project doesn't use four
+    pub fn indent(&self) -> Option<usize> {
+        match self {
+            Config::SingleFile { indent, .. } => *indent,
+            Config::MultiFile { indent, .. } => *indent,
+        }
+    }
+
+    /// What to name backups with, when every edit should keep one
+    pub fn backup_suffix(&self) -> Option<&str> {
+        match self {
+            Config::SingleFile { backup_suffix, .. } => backup_suffix.as_deref(),
+            Config::MultiFile { backup_suffix, .. } => backup_suffix.as_deref(),
+        }
+    }
+
+    /// How the project groups its keys
+    pub fn key_style(&self) -> KeyStyle {
+        match self {
+            Config::SingleFile { key_style, .. } => *key_style,
+            Config::MultiFile { key_style, .. } => *key_style,
+        }
+    }
+
+    /// How the project writes placeholders, when it has said
+    pub fn placeholder_style(&self) -> Option<PlaceholderStyle> {
+        match self {
+            Config::SingleFile {
+                placeholder_style, ..
+            } => *placeholder_style,
+            Config::MultiFile {
+                placeholder_style, ..
+            } => *placeholder_style,
+        }
+    }
+
+    /// Get every configured translation file with its record name
+    pub fn translation_files(&self) -> Vec<(PathBuf, String)> {
+        match self {
+            Config::SingleFile {
+                file, record_name, ..
+            } => vec![(file.clone(), record_name.clone())],
+            Config::MultiFile { files, .. } => {
+                let mut all: Vec<_> = files
+                    .values()
+                    .map(|fc| (fc.path.clone(), fc.record_name.clone()))
+                    .collect();
+                all.sort();
+                all
+            }
+        }
+    }
+
     /// Check if in multi-file mode
     pub fn is_multi_file(&self) -> bool {
         matches!(self, Config::MultiFile { .. })
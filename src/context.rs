@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::timing;
+use crate::types::ParseResult;
+
+/// What replaces a translation file's extension to name its context file:
+/// the notes of `src/I18n.elm` are in `src/I18n.context.json`
+pub const CONTEXT_SUFFIX: &str = ".context.json";
+
+/// What a translator should know about a key, kept out of the Elm module
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Path or URL of a screenshot showing where the text appears
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<String>,
+}
+
+/// The notes of each key, sorted by key
+pub type Contexts = BTreeMap<String, KeyContext>;
+
+thread_local! {
+    /// Set by --context-file
+    static CONTEXT_FILE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Keep the notes of every translation file in `path` rather than next to each
+pub fn set_file(path: &Path) {
+    CONTEXT_FILE.with(|file| *file.borrow_mut() = Some(path.to_path_buf()));
+}
+
+/// Where the notes of the translation file `file` are kept
+pub fn context_path(file: &Path) -> PathBuf {
+    CONTEXT_FILE
+        .with(|path| path.borrow().clone())
+        .unwrap_or_else(|| {
+            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+            file.with_file_name(format!("{}{}", stem, CONTEXT_SUFFIX))
+        })
+}
+
+/// The notes of `file`, or none when it has no context file
+pub fn load(file: &Path) -> Result<Contexts> {
+    let path = context_path(file);
+    if !path.exists() {
+        return Ok(Contexts::new());
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Invalid context file {}", path.display()))
+}
+
+/// Apply `edit` to the notes of `file`, writing them back if it changed them;
+/// returns the context file then
+pub fn update(file: &Path, edit: impl FnOnce(&mut Contexts)) -> Result<Option<PathBuf>> {
+    let before = load(file)?;
+    let mut contexts = before.clone();
+    edit(&mut contexts);
+    if contexts == before {
+        return Ok(None);
+    }
+
+    let path = context_path(file);
+    let mut json = serde_json::to_string_pretty(&contexts)?;
+    json.push('\n');
+    timing::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(Some(path))
+}
+
+/// The description of `key`, unless it has none or an empty one
+pub fn description<'a>(contexts: &'a Contexts, key: &str) -> Option<&'a str> {
+    contexts
+        .get(key)
+        .and_then(|context| context.description.as_deref())
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+}
+
+/// The keys of `parse_result` without a description, in type order
+pub fn undescribed_keys(contexts: &Contexts, parse_result: &ParseResult) -> Vec<String> {
+    parse_result
+        .type_fields
+        .iter()
+        .filter(|field| description(contexts, &field.name).is_none())
+        .map(|field| field.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn keeps_notes_next_to_the_file_and_lists_keys_without_one() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("I18n.elm");
+        assert_eq!(context_path(&file), dir.path().join("I18n.context.json"));
+        assert!(load(&file).unwrap().is_empty());
+
+        let describe = |key: &str, description: &str| {
+            update(&file, |contexts| {
+                contexts.entry(key.to_string()).or_default().description =
+                    Some(description.to_string());
+            })
+            .unwrap()
+        };
+        assert!(describe("save", "Button under the profile form").is_some());
+        assert!(describe("save", "Button under the profile form").is_none());
+        describe("cancel", " ");
+        assert_eq!(
+            fs::read_to_string(context_path(&file)).unwrap(),
+            "{\n  \"cancel\": {\n    \"description\": \" \"\n  },\n  \"save\": {\n    \"description\": \"Button under the profile form\"\n  }\n}\n"
+        );
+
+        let languages = vec!["en".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            "type alias Translations =\n    { save : String\n    , cancel : String\n    , title : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    , title = \"Profile\"\n    }\n",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(
+            undescribed_keys(&load(&file).unwrap(), &parse_result),
+            vec!["cancel", "title"]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+
+use crate::types::ParseResult;
+use crate::validate::{value_status, ValueStatus};
+
+/// How many of the keys of one language are translated, over every file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageCoverage {
+    pub language: String,
+    pub translated: usize,
+    pub total: usize,
+}
+
+impl LanguageCoverage {
+    /// The translated share as a percentage, rounded down to a tenth so a language
+    /// missing one key of thousands never shows as 100%
+    pub fn percent(&self) -> f64 {
+        match (self.translated * 1000).checked_div(self.total) {
+            Some(tenths) => tenths as f64 / 10.0,
+            None => 100.0,
+        }
+    }
+
+    /// Whether the language reaches `threshold` percent, compared exactly
+    pub fn meets(&self, threshold: f64) -> bool {
+        self.total == 0 || self.translated as f64 * 100.0 >= threshold * self.total as f64
+    }
+}
+
+/// The coverage of each of `languages` over the keys of `parse_results`
+///
+/// A value counts as translated when it's neither missing, empty nor still
+/// marked with `todo_marker`; a function counts when its body is written out.
+pub fn coverage(
+    parse_results: &[&ParseResult],
+    languages: &[String],
+    todo_marker: &str,
+) -> Vec<LanguageCoverage> {
+    languages
+        .iter()
+        .map(|lang| {
+            let mut covered = LanguageCoverage {
+                language: lang.clone(),
+                translated: 0,
+                total: 0,
+            };
+            for parse_result in parse_results {
+                for translation in parse_result.translations.values() {
+                    covered.total += 1;
+                    let status = value_status(translation.values.get(lang), todo_marker);
+                    if status == ValueStatus::Translated {
+                        covered.translated += 1;
+                    }
+                }
+            }
+            covered
+        })
+        .collect()
+}
+
+/// The languages of `coverage` under `threshold` percent, which make
+/// `coverage --fail-under` exit non-zero
+pub fn below(coverage: &[LanguageCoverage], threshold: f64) -> Vec<&LanguageCoverage> {
+    coverage
+        .iter()
+        .filter(|covered| !covered.meets(threshold))
+        .collect()
+}
+
+/// The coverage under which a badge is red, and under which it's yellow
+pub const BADGE_RED_UNDER: f64 = 70.0;
+pub const BADGE_YELLOW_UNDER: f64 = 95.0;
+
+/// A language's coverage as a shields.io endpoint badge
+/// (<https://shields.io/badges/endpoint-badge>)
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+/// The badge of `covered`: red under `red_under` percent, yellow under
+/// `yellow_under`, green otherwise
+pub fn badge(covered: &LanguageCoverage, red_under: f64, yellow_under: f64) -> Badge {
+    let color = if !covered.meets(red_under) {
+        "red"
+    } else if !covered.meets(yellow_under) {
+        "yellow"
+    } else {
+        "green"
+    };
+    Badge {
+        schema_version: 1,
+        label: format!("i18n {}", covered.language),
+        message: format!("{}%", covered.percent()),
+        color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    const SOURCE: &str = r#"module I18n exposing (..)
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    , greeting : String -> String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { save = "Save"
+    , cancel = "Cancel"
+    , greeting = \name -> "Hello " ++ name
+    }
+
+translationsDe : Translations
+translationsDe =
+    { save = "Speichern"
+    , cancel = "TODO: Abbrechen"
+    , greeting = \name -> "Hallo " ++ name
+    }
+
+translationsFr : Translations
+translationsFr =
+    { save = ""
+    , cancel = "TODO: Annuler"
+    , greeting = \_ -> "TODO"
+    }
+"#;
+
+    #[test]
+    fn counts_translated_values_and_applies_the_threshold() {
+        let languages: Vec<String> = ["en", "de", "fr"].iter().map(|l| l.to_string()).collect();
+        let parse_result =
+            parse_i18n_content_with_record_name(SOURCE, "Translations", &languages).unwrap();
+
+        let covered = coverage(&[&parse_result, &parse_result], &languages, "TODO");
+        let counts: Vec<(&str, usize, usize)> = covered
+            .iter()
+            .map(|c| (c.language.as_str(), c.translated, c.total))
+            .collect();
+        assert_eq!(counts, vec![("en", 6, 6), ("de", 4, 6), ("fr", 0, 6)]);
+        assert_eq!(covered[1].percent(), 66.6);
+
+        // 4 of 6 is 66.67%: enough for 66.6, not for 66.7
+        assert!(covered[1].meets(66.6));
+        assert!(!covered[1].meets(66.7));
+        let failing: Vec<&str> = below(&covered, 50.0)
+            .iter()
+            .map(|c| c.language.as_str())
+            .collect();
+        assert_eq!(failing, vec!["fr"]);
+
+        // Only the languages asked for are checked
+        let german = coverage(&[&parse_result], &languages[1..2], "TODO");
+        assert!(below(&german, 95.0).len() == 1 && below(&german, 60.0).is_empty());
+        assert!(below(&coverage(&[], &languages, "TODO"), 100.0).is_empty());
+    }
+
+    #[test]
+    fn colors_badges_by_threshold() {
+        let covered = |translated| LanguageCoverage {
+            language: "de".to_string(),
+            translated,
+            total: 200,
+        };
+        let color = |translated| badge(&covered(translated), 70.0, 95.0).color;
+        assert_eq!(color(139), "red");
+        assert_eq!(color(140), "yellow");
+        assert_eq!(color(189), "yellow");
+        assert_eq!(color(190), "green");
+        assert_eq!(badge(&covered(139), 50.0, 60.0).color, "green");
+
+        assert_eq!(
+            serde_json::to_string(&badge(&covered(175), 70.0, 95.0)).unwrap(),
+            r#"{"schemaVersion":1,"label":"i18n de","message":"87.5%","color":"yellow"}"#
+        );
+    }
+}
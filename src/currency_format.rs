@@ -0,0 +1,261 @@
+use regex::Regex;
+
+use crate::generated::{begin_marker, end_marker};
+use crate::languages::language_variant;
+use crate::number_format::separators;
+
+/// The name of the block `generate currency-format` writes, in its marker comments
+pub const BLOCK: &str = "currency-format";
+
+/// The currencies every `Currency` type has; `--currency` adds to them
+pub const DEFAULT_CURRENCIES: [&str; 3] = ["USD", "EUR", "GBP"];
+
+const NO_BREAK_SPACE: &str = "\\u{00A0}";
+
+/// The symbol of an ISO 4217 currency code, as Elm string contents
+pub fn symbol(code: &str) -> Option<&'static str> {
+    let symbol = match code {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "CHF" => "CHF",
+        "CAD" => "CA$",
+        "AUD" => "A$",
+        "NZD" => "NZ$",
+        "MXN" => "MX$",
+        "BRL" => "R$",
+        "INR" => "₹",
+        "SEK" | "NOK" | "DKK" => "kr",
+        "PLN" => "zł",
+        "CZK" => "Kč",
+        "HUF" => "Ft",
+        "TRY" => "₺",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+/// Whether a language writes the symbol before the amount, whether a space
+/// separates them, and whether the table has the language (English's placement
+/// is used when it doesn't)
+///
+/// A region wins over its language: Brazil writes `R$ 1.234,56` where Portugal
+/// writes `1234,56 €`.
+pub fn placement(code: &str) -> (bool, bool, bool) {
+    let lower = code.to_ascii_lowercase();
+    let region = match lower.as_str() {
+        "pt-br" | "de-ch" | "it-ch" | "fr-ch" => Some((true, true)),
+        "en-za" | "es-mx" | "es-us" | "es-419" => Some((true, false)),
+        "fr-ca" => Some((false, true)),
+        _ => None,
+    };
+    if let Some((first, spaced)) = region {
+        return (first, spaced, true);
+    }
+    let base = lower.split('-').next().unwrap_or_default();
+    let (first, spaced) = match base {
+        "en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ga" | "mt" | "ms" | "fil" | "tl"
+        | "tr" | "cy" => (true, false),
+        "nl" => (true, true),
+        "fr" | "de" | "es" | "it" | "pt" | "ca" | "pl" | "cs" | "sk" | "ru" | "uk" | "be"
+        | "fi" | "sv" | "nb" | "no" | "nn" | "da" | "hu" | "ro" | "el" | "lt" | "lv" | "et"
+        | "hr" | "sl" | "sr" | "bs" | "bg" | "is" | "vi" => (false, true),
+        _ => return (true, false, false),
+    };
+    (first, spaced, true)
+}
+
+/// The currencies of the `Currency` type in a block `helpers_block` wrote, so a
+/// refresh keeps the ones added with `--currency`
+pub fn currencies_in(block: &str) -> Vec<String> {
+    let Some(start) = block.find("\ntype Currency\n") else {
+        return Vec::new();
+    };
+    let variant = Regex::new(r"^    [=|] ([A-Z]{3})$").unwrap();
+    block[start + "\ntype Currency\n".len()..]
+        .lines()
+        .map_while(|line| variant.captures(line).map(|c| c[1].to_string()))
+        .collect()
+}
+
+/// The `Currency` type and `formatCurrency` for `languages` and `currencies`,
+/// between the markers, using only elm/core. A currency without a known symbol
+/// is written as its code.
+pub fn helpers_block(languages: &[String], currencies: &[String]) -> String {
+    let variants: Vec<String> = currencies
+        .iter()
+        .enumerate()
+        .map(|(i, code)| format!("    {} {}", if i == 0 { "=" } else { "|" }, code))
+        .collect();
+    let symbols: Vec<String> = currencies
+        .iter()
+        .map(|code| {
+            format!(
+                "        {} ->\n            \"{}\"",
+                code,
+                symbol(code).unwrap_or(code)
+            )
+        })
+        .collect();
+    let styles: Vec<String> = languages
+        .iter()
+        .map(|lang| {
+            let (thousands, decimal, _) = separators(lang);
+            let (first, spaced, _) = placement(lang);
+            format!(
+                "        {} ->\n            {{ thousands = \"{}\", decimal = \"{}\", symbolFirst = {}, space = \"{}\" }}",
+                language_variant(lang),
+                thousands,
+                decimal,
+                if first { "True" } else { "False" },
+                if spaced { NO_BREAK_SPACE } else { "" }
+            )
+        })
+        .collect();
+    format!(
+        r#"{begin}
+-- Written by `elm-i18n generate currency-format`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this.
+
+
+{{-| The currencies `formatCurrency` writes
+-}}
+type Currency
+{variants}
+
+
+{{-| The symbol of a currency: $ for USD, € for EUR
+-}}
+currencySymbol : Currency -> String
+currencySymbol currency =
+    case currency of
+{symbols}
+
+
+{{-| How a language writes an amount of money: its separators, and where the
+symbol goes
+-}}
+currencyStyle : Language -> {{ thousands : String, decimal : String, symbolFirst : Bool, space : String }}
+currencyStyle lang =
+    case lang of
+{styles}
+
+
+{{-| An amount of money in the language's format: $1,234.00 in English, 1 234,00 €
+in French
+
+The amount is rounded to cents, halves away from zero on the digits it's written
+with, so 2.005 is 2.01 even though the Float is a little under it.
+
+-}}
+formatCurrency : Language -> {{ amount : Float, currency : Currency }} -> String
+formatCurrency lang {{ amount, currency }} =
+    let
+        style =
+            currencyStyle lang
+
+        cents =
+            roundedCents (abs amount)
+
+        number =
+            groupCurrencyDigits style.thousands (String.fromInt (cents // 100))
+                ++ style.decimal
+                ++ String.padLeft 2 '0' (String.fromInt (modBy 100 cents))
+
+        sign =
+            if amount < 0 && cents > 0 then
+                "-"
+
+            else
+                ""
+    in
+    if style.symbolFirst then
+        sign ++ currencySymbol currency ++ style.space ++ number
+
+    else
+        sign ++ number ++ style.space ++ currencySymbol currency
+
+
+roundedCents : Float -> Int
+roundedCents amount =
+    case String.split "." (String.fromFloat amount) of
+        [ whole ] ->
+            case String.toInt whole of
+                Just units ->
+                    units * 100
+
+                Nothing ->
+                    round (amount * 100)
+
+        [ whole, fraction ] ->
+            case ( String.toInt whole, String.toInt (String.left 2 (fraction ++ "00")) ) of
+                ( Just units, Just hundredths ) ->
+                    if String.slice 2 3 fraction >= "5" then
+                        units * 100 + hundredths + 1
+
+                    else
+                        units * 100 + hundredths
+
+                _ ->
+                    round (amount * 100)
+
+        _ ->
+            round (amount * 100)
+
+
+groupCurrencyDigits : String -> String -> String
+groupCurrencyDigits separator digits =
+    if String.length digits <= 3 then
+        digits
+
+    else
+        groupCurrencyDigits separator (String.dropRight 3 digits) ++ separator ++ String.right 3 digits
+
+
+{end}"#,
+        begin = begin_marker(BLOCK),
+        end = end_marker(BLOCK),
+        variants = variants.join("\n"),
+        symbols = symbols.join("\n\n"),
+        styles = styles.join("\n\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::upsert_block;
+
+    const SOURCE: &str = "module I18n exposing (..)\n\n\ntype Language\n    = EN\n    | FR\n    | DE_CH\n    | PT_BR\n";
+
+    #[test]
+    fn writes_the_block_the_snapshot_has() {
+        let languages: Vec<String> = ["en", "fr", "de-CH", "pt-BR"]
+            .iter()
+            .map(|lang| lang.to_string())
+            .collect();
+        let currencies: Vec<String> = ["USD", "EUR", "GBP", "CHF", "XTS"]
+            .iter()
+            .map(|code| code.to_string())
+            .collect();
+        let generated =
+            upsert_block(SOURCE, BLOCK, &helpers_block(&languages, &currencies)).unwrap();
+        assert_eq!(
+            generated,
+            include_str!("../tests/fixtures/currency-format/I18n.elm")
+        );
+        assert_eq!(placement("xx"), (true, false, false));
+    }
+
+    #[test]
+    fn keeps_the_added_currencies_on_a_refresh() {
+        let languages = vec!["en".to_string()];
+        let currencies: Vec<String> = ["USD", "EUR", "GBP", "SEK"]
+            .iter()
+            .map(|code| code.to_string())
+            .collect();
+        let block = helpers_block(&languages, &currencies);
+        assert_eq!(currencies_in(&block), currencies);
+        assert!(currencies_in("-- no type here").is_empty());
+    }
+}
@@ -0,0 +1,856 @@
+use crate::generated::{begin_marker, end_marker};
+use crate::languages::{language_variant, translations_record_name};
+
+/// The name of the block `generate date-format` writes, in its marker comments
+pub const BLOCK: &str = "date-format";
+
+/// How a language names months and weekdays and writes a date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateNames {
+    /// January to December, as they're named on their own
+    pub months: [&'static str; 12],
+    /// As they're written in a date, where some languages inflect them
+    /// (genitive "марта" for "март")
+    pub months_in_dates: [&'static str; 12],
+    /// Monday to Sunday
+    pub weekdays: [&'static str; 7],
+    /// The date with `{d}`, `{m}` and `{y}` for the day, month and year
+    pub pattern: &'static str,
+}
+
+const fn names(
+    months: [&'static str; 12],
+    weekdays: [&'static str; 7],
+    pattern: &'static str,
+) -> DateNames {
+    DateNames {
+        months,
+        months_in_dates: months,
+        weekdays,
+        pattern,
+    }
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const ENGLISH_WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const NUMBERED_MONTHS_CJK: [&str; 12] = [
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+
+/// The built-in names for a language, by region first ("en-GB" writes the day
+/// first) and then by language; None for a language the table doesn't have
+pub fn date_names(code: &str) -> Option<DateNames> {
+    let lower = code.to_ascii_lowercase();
+    if matches!(
+        lower.as_str(),
+        "en-gb" | "en-ie" | "en-au" | "en-nz" | "en-za" | "en-in"
+    ) {
+        return Some(names(ENGLISH_MONTHS, ENGLISH_WEEKDAYS, "{d} {m} {y}"));
+    }
+    let names = match lower.split('-').next().unwrap_or_default() {
+        "en" => names(ENGLISH_MONTHS, ENGLISH_WEEKDAYS, "{m} {d}, {y}"),
+        "fr" => names(
+            [
+                "janvier",
+                "février",
+                "mars",
+                "avril",
+                "mai",
+                "juin",
+                "juillet",
+                "août",
+                "septembre",
+                "octobre",
+                "novembre",
+                "décembre",
+            ],
+            [
+                "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+            ],
+            "{d} {m} {y}",
+        ),
+        "de" => names(
+            [
+                "Januar",
+                "Februar",
+                "März",
+                "April",
+                "Mai",
+                "Juni",
+                "Juli",
+                "August",
+                "September",
+                "Oktober",
+                "November",
+                "Dezember",
+            ],
+            [
+                "Montag",
+                "Dienstag",
+                "Mittwoch",
+                "Donnerstag",
+                "Freitag",
+                "Samstag",
+                "Sonntag",
+            ],
+            "{d}. {m} {y}",
+        ),
+        "es" => names(
+            [
+                "enero",
+                "febrero",
+                "marzo",
+                "abril",
+                "mayo",
+                "junio",
+                "julio",
+                "agosto",
+                "septiembre",
+                "octubre",
+                "noviembre",
+                "diciembre",
+            ],
+            [
+                "lunes",
+                "martes",
+                "miércoles",
+                "jueves",
+                "viernes",
+                "sábado",
+                "domingo",
+            ],
+            "{d} de {m} de {y}",
+        ),
+        "it" => names(
+            [
+                "gennaio",
+                "febbraio",
+                "marzo",
+                "aprile",
+                "maggio",
+                "giugno",
+                "luglio",
+                "agosto",
+                "settembre",
+                "ottobre",
+                "novembre",
+                "dicembre",
+            ],
+            [
+                "lunedì",
+                "martedì",
+                "mercoledì",
+                "giovedì",
+                "venerdì",
+                "sabato",
+                "domenica",
+            ],
+            "{d} {m} {y}",
+        ),
+        "pt" => names(
+            [
+                "janeiro",
+                "fevereiro",
+                "março",
+                "abril",
+                "maio",
+                "junho",
+                "julho",
+                "agosto",
+                "setembro",
+                "outubro",
+                "novembro",
+                "dezembro",
+            ],
+            [
+                "segunda-feira",
+                "terça-feira",
+                "quarta-feira",
+                "quinta-feira",
+                "sexta-feira",
+                "sábado",
+                "domingo",
+            ],
+            "{d} de {m} de {y}",
+        ),
+        "nl" => names(
+            [
+                "januari",
+                "februari",
+                "maart",
+                "april",
+                "mei",
+                "juni",
+                "juli",
+                "augustus",
+                "september",
+                "oktober",
+                "november",
+                "december",
+            ],
+            [
+                "maandag",
+                "dinsdag",
+                "woensdag",
+                "donderdag",
+                "vrijdag",
+                "zaterdag",
+                "zondag",
+            ],
+            "{d} {m} {y}",
+        ),
+        "sv" => names(
+            [
+                "januari",
+                "februari",
+                "mars",
+                "april",
+                "maj",
+                "juni",
+                "juli",
+                "augusti",
+                "september",
+                "oktober",
+                "november",
+                "december",
+            ],
+            [
+                "måndag", "tisdag", "onsdag", "torsdag", "fredag", "lördag", "söndag",
+            ],
+            "{d} {m} {y}",
+        ),
+        "da" => names(
+            [
+                "januar",
+                "februar",
+                "marts",
+                "april",
+                "maj",
+                "juni",
+                "juli",
+                "august",
+                "september",
+                "oktober",
+                "november",
+                "december",
+            ],
+            [
+                "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag", "søndag",
+            ],
+            "{d}. {m} {y}",
+        ),
+        "nb" | "no" | "nn" => names(
+            [
+                "januar",
+                "februar",
+                "mars",
+                "april",
+                "mai",
+                "juni",
+                "juli",
+                "august",
+                "september",
+                "oktober",
+                "november",
+                "desember",
+            ],
+            [
+                "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag", "søndag",
+            ],
+            "{d}. {m} {y}",
+        ),
+        "fi" => DateNames {
+            months: [
+                "tammikuu",
+                "helmikuu",
+                "maaliskuu",
+                "huhtikuu",
+                "toukokuu",
+                "kesäkuu",
+                "heinäkuu",
+                "elokuu",
+                "syyskuu",
+                "lokakuu",
+                "marraskuu",
+                "joulukuu",
+            ],
+            months_in_dates: [
+                "tammikuuta",
+                "helmikuuta",
+                "maaliskuuta",
+                "huhtikuuta",
+                "toukokuuta",
+                "kesäkuuta",
+                "heinäkuuta",
+                "elokuuta",
+                "syyskuuta",
+                "lokakuuta",
+                "marraskuuta",
+                "joulukuuta",
+            ],
+            weekdays: [
+                "maanantai",
+                "tiistai",
+                "keskiviikko",
+                "torstai",
+                "perjantai",
+                "lauantai",
+                "sunnuntai",
+            ],
+            pattern: "{d}. {m} {y}",
+        },
+        "pl" => DateNames {
+            months: [
+                "styczeń",
+                "luty",
+                "marzec",
+                "kwiecień",
+                "maj",
+                "czerwiec",
+                "lipiec",
+                "sierpień",
+                "wrzesień",
+                "październik",
+                "listopad",
+                "grudzień",
+            ],
+            months_in_dates: [
+                "stycznia",
+                "lutego",
+                "marca",
+                "kwietnia",
+                "maja",
+                "czerwca",
+                "lipca",
+                "sierpnia",
+                "września",
+                "października",
+                "listopada",
+                "grudnia",
+            ],
+            weekdays: [
+                "poniedziałek",
+                "wtorek",
+                "środa",
+                "czwartek",
+                "piątek",
+                "sobota",
+                "niedziela",
+            ],
+            pattern: "{d} {m} {y}",
+        },
+        "ru" => DateNames {
+            months: [
+                "январь",
+                "февраль",
+                "март",
+                "апрель",
+                "май",
+                "июнь",
+                "июль",
+                "август",
+                "сентябрь",
+                "октябрь",
+                "ноябрь",
+                "декабрь",
+            ],
+            months_in_dates: [
+                "января",
+                "февраля",
+                "марта",
+                "апреля",
+                "мая",
+                "июня",
+                "июля",
+                "августа",
+                "сентября",
+                "октября",
+                "ноября",
+                "декабря",
+            ],
+            weekdays: [
+                "понедельник",
+                "вторник",
+                "среда",
+                "четверг",
+                "пятница",
+                "суббота",
+                "воскресенье",
+            ],
+            pattern: "{d} {m} {y} г.",
+        },
+        "uk" => DateNames {
+            months: [
+                "січень",
+                "лютий",
+                "березень",
+                "квітень",
+                "травень",
+                "червень",
+                "липень",
+                "серпень",
+                "вересень",
+                "жовтень",
+                "листопад",
+                "грудень",
+            ],
+            months_in_dates: [
+                "січня",
+                "лютого",
+                "березня",
+                "квітня",
+                "травня",
+                "червня",
+                "липня",
+                "серпня",
+                "вересня",
+                "жовтня",
+                "листопада",
+                "грудня",
+            ],
+            weekdays: [
+                "понеділок",
+                "вівторок",
+                "середа",
+                "четвер",
+                "пʼятниця",
+                "субота",
+                "неділя",
+            ],
+            pattern: "{d} {m} {y} р.",
+        },
+        "cs" => DateNames {
+            months: [
+                "leden",
+                "únor",
+                "březen",
+                "duben",
+                "květen",
+                "červen",
+                "červenec",
+                "srpen",
+                "září",
+                "říjen",
+                "listopad",
+                "prosinec",
+            ],
+            months_in_dates: [
+                "ledna",
+                "února",
+                "března",
+                "dubna",
+                "května",
+                "června",
+                "července",
+                "srpna",
+                "září",
+                "října",
+                "listopadu",
+                "prosince",
+            ],
+            weekdays: [
+                "pondělí",
+                "úterý",
+                "středa",
+                "čtvrtek",
+                "pátek",
+                "sobota",
+                "neděle",
+            ],
+            pattern: "{d}. {m} {y}",
+        },
+        "tr" => names(
+            [
+                "Ocak", "Şubat", "Mart", "Nisan", "Mayıs", "Haziran", "Temmuz", "Ağustos", "Eylül",
+                "Ekim", "Kasım", "Aralık",
+            ],
+            [
+                "Pazartesi",
+                "Salı",
+                "Çarşamba",
+                "Perşembe",
+                "Cuma",
+                "Cumartesi",
+                "Pazar",
+            ],
+            "{d} {m} {y}",
+        ),
+        "ja" => names(
+            NUMBERED_MONTHS_CJK,
+            [
+                "月曜日",
+                "火曜日",
+                "水曜日",
+                "木曜日",
+                "金曜日",
+                "土曜日",
+                "日曜日",
+            ],
+            "{y}年{m}{d}日",
+        ),
+        "zh" => DateNames {
+            months: [
+                "一月",
+                "二月",
+                "三月",
+                "四月",
+                "五月",
+                "六月",
+                "七月",
+                "八月",
+                "九月",
+                "十月",
+                "十一月",
+                "十二月",
+            ],
+            months_in_dates: NUMBERED_MONTHS_CJK,
+            weekdays: [
+                "星期一",
+                "星期二",
+                "星期三",
+                "星期四",
+                "星期五",
+                "星期六",
+                "星期日",
+            ],
+            pattern: "{y}年{m}{d}日",
+        },
+        "ko" => names(
+            [
+                "1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월",
+                "12월",
+            ],
+            [
+                "월요일",
+                "화요일",
+                "수요일",
+                "목요일",
+                "금요일",
+                "토요일",
+                "일요일",
+            ],
+            "{y}년 {m} {d}일",
+        ),
+        _ => return None,
+    };
+    Some(names)
+}
+
+/// The record a language's names go in: "fr" → `dateNamesFr`
+pub fn names_record(code: &str) -> String {
+    translations_record_name(code).replacen("translations", "dateNames", 1)
+}
+
+/// The date helpers for `languages`, between the markers, using only elm/core
+/// (and elm/time with `use_time`)
+///
+/// Languages `date_names` doesn't have are picked from records outside the
+/// block, which `todo_records` writes once for the developer to fill in.
+pub fn helpers_block(languages: &[String], use_time: bool) -> String {
+    let mut branches = Vec::new();
+    let mut records = Vec::new();
+    for lang in languages {
+        let record = names_record(lang);
+        branches.push(format!(
+            "        {} ->\n            {}",
+            language_variant(lang),
+            record
+        ));
+        if let Some(names) = date_names(lang) {
+            records.push(names_record_definition(&record, &names, ""));
+        }
+    }
+
+    let mut block = format!(
+        r#"{begin}
+-- Written by `elm-i18n generate date-format`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this.
+
+
+{{-| How a language names months (January first) and weekdays (Monday first), and
+how it writes a date from its day, month name and year
+-}}
+type alias DateNames =
+    {{ months : List String
+    , monthsInDates : List String
+    , weekdays : List String
+    , date : String -> String -> String -> String
+    }}
+
+
+dateNames : Language -> DateNames
+dateNames lang =
+    case lang of
+{branches}
+
+
+{{-| The name of a month, 1 for January to 12 for December; "" for other numbers
+-}}
+monthName : Language -> Int -> String
+monthName lang month =
+    dateNameAt (month - 1) (dateNames lang).months
+
+
+{{-| The name of a weekday, 1 for Monday to 7 for Sunday; "" for other numbers
+-}}
+weekdayName : Language -> Int -> String
+weekdayName lang weekday =
+    dateNameAt (weekday - 1) (dateNames lang).weekdays
+
+
+{{-| A date the way the language writes it: March 5, 2024 in English, 5 mars 2024 in
+French
+-}}
+formatDate : Language -> {{ year : Int, month : Int, day : Int }} -> String
+formatDate lang {{ year, month, day }} =
+    let
+        names =
+            dateNames lang
+    in
+    names.date (String.fromInt day) (dateNameAt (month - 1) names.monthsInDates) (String.fromInt year)
+"#,
+        begin = begin_marker(BLOCK),
+        branches = branches.join("\n\n"),
+    );
+    if use_time {
+        block.push_str(
+            r#"
+
+{-| `formatDate` for a moment, on the day it is in `zone`
+-}
+formatPosix : Language -> Time.Zone -> Time.Posix -> String
+formatPosix lang zone posix =
+    formatDate lang
+        { year = Time.toYear zone posix
+        , month = monthNumber (Time.toMonth zone posix)
+        , day = Time.toDay zone posix
+        }
+
+
+{-| `weekdayName` for the day a moment is in `zone`
+-}
+posixWeekdayName : Language -> Time.Zone -> Time.Posix -> String
+posixWeekdayName lang zone posix =
+    weekdayName lang (weekdayNumber (Time.toWeekday zone posix))
+
+
+monthNumber : Time.Month -> Int
+monthNumber month =
+    case month of
+        Time.Jan ->
+            1
+
+        Time.Feb ->
+            2
+
+        Time.Mar ->
+            3
+
+        Time.Apr ->
+            4
+
+        Time.May ->
+            5
+
+        Time.Jun ->
+            6
+
+        Time.Jul ->
+            7
+
+        Time.Aug ->
+            8
+
+        Time.Sep ->
+            9
+
+        Time.Oct ->
+            10
+
+        Time.Nov ->
+            11
+
+        Time.Dec ->
+            12
+
+
+weekdayNumber : Time.Weekday -> Int
+weekdayNumber weekday =
+    case weekday of
+        Time.Mon ->
+            1
+
+        Time.Tue ->
+            2
+
+        Time.Wed ->
+            3
+
+        Time.Thu ->
+            4
+
+        Time.Fri ->
+            5
+
+        Time.Sat ->
+            6
+
+        Time.Sun ->
+            7
+"#,
+        );
+    }
+    block.push_str(
+        r#"
+
+dateNameAt : Int -> List String -> String
+dateNameAt index names =
+    List.drop index names
+        |> List.head
+        |> Maybe.withDefault ""
+"#,
+    );
+    for record in records {
+        block.push_str("\n\n");
+        block.push_str(&record);
+        block.push('\n');
+    }
+    block.push_str("\n\n");
+    block.push_str(&end_marker(BLOCK));
+    block
+}
+
+/// Whether the date block in `source` has the elm/time helpers, which refreshing
+/// it keeps
+pub fn uses_time(block: &str) -> bool {
+    block.contains("\nformatPosix :")
+}
+
+/// A record to fill in for each language the table doesn't have and `source`
+/// doesn't define yet, with English's names behind `todo_marker`
+pub fn todo_records(source: &str, languages: &[String], todo_marker: &str) -> Vec<String> {
+    languages
+        .iter()
+        .filter(|lang| date_names(lang).is_none())
+        .filter_map(|lang| {
+            let record = names_record(lang);
+            let defined = source
+                .lines()
+                .any(|line| line.starts_with(&format!("{} =", record)));
+            if defined {
+                return None;
+            }
+            let names = names(ENGLISH_MONTHS, ENGLISH_WEEKDAYS, "{m} {d}, {y}");
+            let prefix = format!("{} ", todo_marker);
+            Some(format!(
+                "{{-| {} The month and weekday names of {}, and how it writes a date.\n`generate date-format` writes this once and leaves it to you.\n-}}\n{}",
+                todo_marker,
+                lang,
+                names_record_definition(&record, &names, &prefix)
+            ))
+        })
+        .collect()
+}
+
+fn names_record_definition(record: &str, names: &DateNames, prefix: &str) -> String {
+    let list = |items: &[&str]| {
+        let quoted: Vec<String> = items
+            .iter()
+            .map(|item| format!("\"{}{}\"", prefix, item))
+            .collect();
+        format!("[ {} ]", quoted.join(", "))
+    };
+    format!(
+        "{record} : DateNames\n{record} =\n    {{ months = {}\n    , monthsInDates = {}\n    , weekdays = {}\n    , date = \\day month year -> {}\n    }}",
+        list(&names.months),
+        list(&names.months_in_dates),
+        list(&names.weekdays),
+        pattern_expression(names.pattern),
+        record = record,
+    )
+}
+
+/// "{d} de {m} de {y}" → `day ++ " de " ++ month ++ " de " ++ year`
+fn pattern_expression(pattern: &str) -> String {
+    let mut parts = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(format!("\"{}\"", &rest[..start]));
+        }
+        let (name, after) = match &rest[start..] {
+            r if r.starts_with("{d}") => ("day", &r[3..]),
+            r if r.starts_with("{m}") => ("month", &r[3..]),
+            r if r.starts_with("{y}") => ("year", &r[3..]),
+            r => {
+                parts.push(format!("\"{}\"", r));
+                ("", "")
+            }
+        };
+        if !name.is_empty() {
+            parts.push(name.to_string());
+        }
+        rest = after;
+    }
+    if !rest.is_empty() {
+        parts.push(format!("\"{}\"", rest));
+    }
+    parts.join(" ++ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_each_language_its_own_date_and_a_todo_record_for_the_others() {
+        assert_eq!(
+            pattern_expression("{m} {d}, {y}"),
+            "month ++ \" \" ++ day ++ \", \" ++ year"
+        );
+        assert_eq!(
+            pattern_expression("{y}年{m}{d}日"),
+            "year ++ \"年\" ++ month ++ day ++ \"日\""
+        );
+        assert_eq!(date_names("en-GB").unwrap().pattern, "{d} {m} {y}");
+        assert_eq!(date_names("pt-BR").unwrap().months[2], "março");
+        assert_eq!(date_names("ru").unwrap().months_in_dates[2], "марта");
+        assert!(date_names("eo").is_none());
+        assert_eq!(names_record("pt-BR"), "dateNamesPtBR");
+
+        let languages: Vec<String> = ["en", "fr", "eo"].map(String::from).to_vec();
+        let block = helpers_block(&languages, false);
+        assert!(block.contains("        EO ->\n            dateNamesEo"));
+        assert!(block
+            .contains("    , date = \\day month year -> day ++ \" \" ++ month ++ \" \" ++ year\n"));
+        assert!(!block.contains("dateNamesEo :"));
+        assert!(!uses_time(&block));
+        assert!(uses_time(&helpers_block(&languages, true)));
+
+        let todo = todo_records("", &languages, "TODO");
+        assert_eq!(todo.len(), 1);
+        assert!(todo[0].contains("dateNamesEo =\n    { months = [ \"TODO January\", "));
+        assert!(todo_records(&todo[0], &languages, "TODO").is_empty());
+    }
+}
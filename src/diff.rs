@@ -0,0 +1,158 @@
+/// One line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// `@@ -old_start,old_len +new_start,new_len @@`
+    Hunk(String),
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Compute a unified diff between two texts with `context` lines around each change
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_ops(&old_lines, &new_lines);
+
+    // Indices of ops that are changes, so hunks can be cut around them
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut output = Vec::new();
+    let mut i = 0;
+    while i < changes.len() {
+        let start = changes[i].saturating_sub(context);
+        let mut end = changes[i];
+        // Merge changes whose context would overlap into one hunk
+        while i + 1 < changes.len() && changes[i + 1] <= end + 2 * context + 1 {
+            i += 1;
+            end = changes[i];
+        }
+        let end = (end + context + 1).min(ops.len());
+        i += 1;
+
+        // Lines of each file that come before the hunk
+        let old_start = ops[..start]
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_start = ops[..start]
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        let old_len = ops[start..end]
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_len = ops[start..end]
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        output.push(DiffLine::Hunk(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + 1,
+            old_len,
+            new_start + 1,
+            new_len
+        )));
+
+        for op in &ops[start..end] {
+            output.push(match *op {
+                Op::Equal(o) => DiffLine::Context(old_lines[o].to_string()),
+                Op::Delete(o) => DiffLine::Removed(old_lines[o].to_string()),
+                Op::Insert(n) => DiffLine::Added(new_lines[n].to_string()),
+            });
+        }
+    }
+
+    output
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    /// Index into the old lines
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Line-level edit script via longest common subsequence
+///
+/// The common prefix and suffix are skipped first, so small edits to large
+/// files stay cheap.
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] = LCS length of old_mid[i..] and new_mid[j..]
+    let mut lcs = vec![vec![0u32; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<Op> = (0..prefix).map(Op::Equal).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            ops.push(Op::Equal(prefix + i));
+            i += 1;
+            j += 1;
+        } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(Op::Delete(prefix + i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    for k in 0..suffix {
+        ops.push(Op::Equal(old.len() - suffix + k));
+    }
+
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn produces_hunks_with_context() {
+        let old = "a\nb\nc\nd\ne\nf\ng\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\n";
+
+        assert_eq!(
+            unified_diff(old, new, 1),
+            vec![
+                DiffLine::Hunk("@@ -3,3 +3,3 @@".to_string()),
+                DiffLine::Context("c".to_string()),
+                DiffLine::Removed("d".to_string()),
+                DiffLine::Added("D".to_string()),
+                DiffLine::Context("e".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_texts_have_no_hunks() {
+        assert!(unified_diff("a\nb\n", "a\nb\n", 3).is_empty());
+    }
+}
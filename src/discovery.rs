@@ -0,0 +1,289 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::errors::{Code, Coded};
+use crate::parser::read_elm_file;
+
+/// Find every file matching a glob pattern like `src/**/I18n.elm`
+///
+/// Supports `*` (within one path segment), `?` and `**` (any number of segments).
+/// Results are sorted so output is deterministic.
+pub fn find_glob_matches(pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = normalize_pattern(pattern);
+    let regex = Regex::new(&glob_to_regex(&pattern))
+        .with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+    let root = glob_root(&pattern);
+
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut matches = Vec::new();
+    for entry in WalkDir::new(&root)
+        .into_iter()
+        .filter_entry(|e| !is_hidden(e))
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        let normalized = normalize(path);
+        if regex.is_match(&normalized) {
+            matches.push(PathBuf::from(normalized));
+        }
+    }
+
+    matches.sort();
+    Ok(matches)
+}
+
+/// Every `I18n.elm` under `root`, sorted, for finding the module when it isn't
+/// where the config says
+pub fn find_i18n_files(root: &Path) -> Result<Vec<PathBuf>> {
+    if !root.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut found = Vec::new();
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !is_hidden(e))
+    {
+        let entry = entry?;
+        if entry.file_type().is_file() && entry.file_name() == "I18n.elm" {
+            found.push(PathBuf::from(normalize(entry.path())));
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+/// Whether a path matches a glob pattern like `src/Generated/**`
+pub fn path_matches_glob(pattern: &str, path: &Path) -> bool {
+    Regex::new(&glob_to_regex(&normalize_pattern(pattern)))
+        .is_ok_and(|regex| regex.is_match(&normalize(path)))
+}
+
+/// Pair each globbed file with the record name to parse it with
+///
+/// In multi-file mode a file that is also configured keeps its configured record name;
+/// otherwise the name is read from the file's `translationsXx : Record` annotation,
+/// falling back to the single-file record name (or "Translations").
+///
+/// A pattern no file matches is an error, so a typo doesn't pass as a clean run.
+pub fn files_for_glob(config: &Config, pattern: &str) -> Result<Vec<(PathBuf, String)>> {
+    let matches = find_glob_matches(pattern)?;
+    if matches.is_empty() {
+        bail!(Coded::new(
+            Code::FileNotFound,
+            format!("No files match the glob pattern '{}'", pattern)
+        ));
+    }
+
+    let mut files = Vec::new();
+    for path in matches {
+        let configured = match config {
+            Config::SingleFile { .. } => None,
+            Config::MultiFile { files, .. } => files
+                .values()
+                .find(|fc| normalize(&fc.path) == normalize(&path))
+                .map(|fc| fc.record_name.clone()),
+        };
+
+        let record_name = match configured {
+            Some(name) => name,
+            None => {
+                let content = read_elm_file(&path)?;
+                let fallback = match config {
+                    Config::SingleFile { record_name, .. } => record_name.clone(),
+                    Config::MultiFile { .. } => "Translations".to_string(),
+                };
+                detect_record_name(&content).unwrap_or(fallback)
+            }
+        };
+
+        files.push((path, record_name));
+    }
+
+    Ok(files)
+}
+
+/// Read the record type name from a `translationsEn : Translations` style annotation
+pub fn detect_record_name(content: &str) -> Option<String> {
+    let annotation = Regex::new(r"^translations[A-Z]\w*\s*:\s*([A-Z]\w*)\s*$").unwrap();
+    content
+        .lines()
+        .find_map(|line| annotation.captures(line).map(|c| c[1].to_string()))
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if i + 1 < chars.len() && chars[i + 1] == '*' => {
+                if i + 2 < chars.len() && chars[i + 2] == '/' {
+                    // `**/` matches zero or more directories
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    regex.push('$');
+    regex
+}
+
+/// The deepest directory prefix of the pattern that contains no wildcards, from
+/// `/` for an absolute pattern
+fn glob_root(pattern: &str) -> PathBuf {
+    let mut root = if pattern.starts_with('/') {
+        PathBuf::from("/")
+    } else {
+        PathBuf::new()
+    };
+    let segments: Vec<&str> = pattern.split('/').collect();
+
+    for segment in &segments[..segments.len().saturating_sub(1)] {
+        if segment.contains(['*', '?']) {
+            break;
+        }
+        root.push(segment);
+    }
+
+    if root.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        root
+    }
+}
+
+/// A glob pattern written the way `normalize` writes the paths it's matched
+/// against: forward slashes, without a leading `./`
+fn normalize_pattern(pattern: &str) -> String {
+    let mut pattern = pattern.replace('\\', "/");
+    while let Some(rest) = pattern.strip_prefix("./") {
+        pattern = rest.trim_start_matches('/').to_string();
+    }
+    pattern
+}
+
+fn normalize(path: &Path) -> String {
+    let normalized = path.to_string_lossy().replace('\\', "/");
+    normalized
+        .strip_prefix("./")
+        .map(str::to_string)
+        .unwrap_or(normalized)
+}
+
+fn is_hidden(entry: &walkdir::DirEntry) -> bool {
+    entry.depth() > 0
+        && entry
+            .file_name()
+            .to_str()
+            .is_some_and(|s| s.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_globs_to_regexes() {
+        let regex = Regex::new(&glob_to_regex("src/**/I18n.elm")).unwrap();
+        assert!(regex.is_match("src/I18n.elm"));
+        assert!(regex.is_match("src/Feature/I18n.elm"));
+        assert!(regex.is_match("src/Feature/Deep/I18n.elm"));
+        assert!(!regex.is_match("src/Feature/I18n.elm.bak"));
+        assert!(!regex.is_match("lib/I18n.elm"));
+
+        let regex = Regex::new(&glob_to_regex("src/*/I18n?.elm")).unwrap();
+        assert!(regex.is_match("src/App/I18nX.elm"));
+        assert!(!regex.is_match("src/App/Deep/I18nX.elm"));
+    }
+
+    #[test]
+    fn finds_the_wildcard_free_root() {
+        assert_eq!(glob_root("src/**/I18n.elm"), PathBuf::from("src"));
+        assert_eq!(glob_root("src/app/*.elm"), PathBuf::from("src/app"));
+        assert_eq!(glob_root("**/I18n.elm"), PathBuf::from("."));
+    }
+
+    #[test]
+    fn matches_dotted_and_absolute_globs() {
+        assert_eq!(normalize_pattern("./src/**/I18n.elm"), "src/**/I18n.elm");
+        assert_eq!(normalize_pattern(".\\src\\*.elm"), "src/*.elm");
+        assert!(path_matches_glob(
+            "./src/**/I18n.elm",
+            Path::new("./src/App/I18n.elm")
+        ));
+        assert_eq!(glob_root("/abs/src/**/I18n.elm"), PathBuf::from("/abs/src"));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("src/App/I18n.elm");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "").unwrap();
+        let pattern = format!("{}/src/**/I18n.elm", normalize(dir.path()));
+        assert_eq!(
+            find_glob_matches(&pattern).unwrap(),
+            vec![PathBuf::from(normalize(&file))]
+        );
+
+        let missing = format!("{}/lib/**/I18n.elm", normalize(dir.path()));
+        let config: Config = serde_json::from_str(
+            r#"{"mode": "single-file", "elm-i18n-version": "0.6.1", "languages": ["en"], "sourceDir": "src", "file": "src/I18n.elm", "recordName": "Translations"}"#,
+        )
+        .unwrap();
+        let error = files_for_glob(&config, &missing).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<Coded>().map(|coded| coded.code),
+            Some(Code::FileNotFound)
+        );
+    }
+
+    #[test]
+    fn finds_i18n_modules_under_a_root() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path().join("src");
+        for file in ["Translations/I18n.elm", "Main.elm", ".cache/I18n.elm"] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        assert_eq!(
+            find_i18n_files(&root).unwrap(),
+            vec![PathBuf::from(normalize(
+                &root.join("Translations/I18n.elm")
+            ))]
+        );
+        assert!(find_i18n_files(&dir.path().join("app")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn detects_record_name_from_annotation() {
+        let content = "module I18n exposing (..)\n\ntranslationsEn : FeatureTranslations\ntranslationsEn =\n    {}\n";
+        assert_eq!(
+            detect_record_name(content),
+            Some("FeatureTranslations".to_string())
+        );
+        assert_eq!(detect_record_name("module I18n exposing (..)\n"), None);
+    }
+}
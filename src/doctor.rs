@@ -0,0 +1,685 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::language_edit::{dispatcher_check, round_trip_check};
+use crate::languages::translations_record_name;
+use crate::parser::{line_list, parse_i18n_content_with_record_name};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosisLevel {
+    Ok,
+    /// Worth a look, but the file can still be read
+    Warning,
+    /// The tool can't read the file until this is fixed
+    Blocking,
+}
+
+/// The outcome of one check, with a suggested fix for problems
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub level: DiagnosisLevel,
+    /// 1-based line the diagnosis points at, when it has one
+    pub line: Option<usize>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnosis {
+    fn ok(line: Option<usize>, message: String) -> Self {
+        Diagnosis {
+            level: DiagnosisLevel::Ok,
+            line,
+            message,
+            suggestion: None,
+        }
+    }
+
+    fn problem(
+        level: DiagnosisLevel,
+        line: Option<usize>,
+        message: String,
+        suggestion: String,
+    ) -> Self {
+        Diagnosis {
+            level,
+            line,
+            message,
+            suggestion: Some(suggestion),
+        }
+    }
+}
+
+/// Braces counted on one line, outside strings, characters and comments
+#[derive(Debug, Clone, Copy)]
+struct LineScan {
+    opens: usize,
+    closes: usize,
+    /// The line starts inside a `"""` string or a `{- -}` comment
+    continues_literal: bool,
+}
+
+/// A top-level definition: the lines from an unindented line to the next one
+struct Definition {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// Explain what keeps an I18n module from being read, without touching it
+///
+/// Runs targeted checks (module header, type alias, language records, the
+/// `translations` dispatcher, the `languageToString`/`stringToLanguage` round trip, brace balance, duplicate and unreadable fields) and finishes with the parser itself,
+/// so anything the checks miss still shows up with the parser's own message.
+pub fn diagnose(source: &str, record_name: &str, languages: &[String]) -> Vec<Diagnosis> {
+    let lines: Vec<&str> = source.lines().collect();
+    let scans = scan_lines(source);
+    let definitions = top_level_definitions(&lines, &scans);
+    let mut diagnoses = Vec::new();
+
+    diagnoses.push(check_module_header(&lines));
+    diagnoses.extend(check_brace_balance(&definitions, &scans));
+
+    let type_alias = Regex::new(r"^type\s+alias\s+(\w+)").unwrap();
+    match lines
+        .iter()
+        .position(|l| type_alias.captures(l).is_some_and(|c| &c[1] == record_name))
+    {
+        Some(i) => {
+            diagnoses.push(Diagnosis::ok(
+                Some(i + 1),
+                format!("`type alias {}` found", record_name),
+            ));
+            diagnoses.extend(check_block(&lines, &scans, i, record_name, ':'));
+        }
+        None => {
+            let others: Vec<String> = lines
+                .iter()
+                .enumerate()
+                .filter_map(|(i, l)| {
+                    type_alias
+                        .captures(l)
+                        .map(|c| format!("`{}` (line {})", &c[1], i + 1))
+                })
+                .collect();
+            let suggestion = if others.is_empty() {
+                format!(
+                    "Declare the translation keys as `type alias {} = {{ ... }}`",
+                    record_name
+                )
+            } else {
+                format!(
+                    "The file declares {}; if one of them holds the translations, set \"recordName\" to it in elm-i18n/config.json",
+                    others.join(", ")
+                )
+            };
+            diagnoses.push(Diagnosis::problem(
+                DiagnosisLevel::Blocking,
+                None,
+                format!("No `type alias {}` in the file", record_name),
+                suggestion,
+            ));
+        }
+    }
+
+    for lang in languages {
+        diagnoses.extend(check_record(&lines, &scans, record_name, lang));
+    }
+
+    if let Some(check) = dispatcher_check(source, record_name, languages) {
+        if check.problems.is_empty() {
+            diagnoses.push(Diagnosis::ok(
+                Some(check.line),
+                format!(
+                    "`{}` has a branch for each of the {} languages",
+                    check.function, check.variants
+                ),
+            ));
+        }
+        // Elm rejects the module, but elm-i18n can still read it
+        diagnoses.extend(check.problems.into_iter().map(|problem| {
+            Diagnosis::problem(
+                DiagnosisLevel::Warning,
+                Some(problem.line),
+                problem.message,
+                problem.suggestion,
+            )
+        }));
+    }
+
+    if let Some(check) = round_trip_check(source, languages) {
+        if check.problems.is_empty() {
+            diagnoses.push(Diagnosis::ok(
+                Some(check.line),
+                format!(
+                    "`stringToLanguage` reads back what `languageToString` gives for each of the {} languages",
+                    check.variants
+                ),
+            ));
+        }
+        // The module compiles, but a stored language comes back as another
+        diagnoses.extend(check.problems.into_iter().map(|problem| {
+            Diagnosis::problem(
+                DiagnosisLevel::Warning,
+                Some(problem.line),
+                problem.message,
+                problem.suggestion,
+            )
+        }));
+    }
+
+    let blocked = diagnoses
+        .iter()
+        .any(|d| d.level == DiagnosisLevel::Blocking);
+    match parse_i18n_content_with_record_name(source, record_name, languages) {
+        Ok(parse_result) => diagnoses.push(Diagnosis::ok(
+            None,
+            format!(
+                "The file parses: {} key{}",
+                parse_result.translations.len(),
+                if parse_result.translations.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            ),
+        )),
+        // The targeted checks already explain what the parser trips over
+        Err(_) if blocked => {}
+        Err(e) => diagnoses.push(Diagnosis::problem(
+            DiagnosisLevel::Blocking,
+            None,
+            format!("The parser still can't read the file: {}", e),
+            "Please report this along with the file, since none of the checks above caught it"
+                .to_string(),
+        )),
+    }
+
+    diagnoses
+}
+
+fn check_module_header(lines: &[&str]) -> Diagnosis {
+    let first_code = lines.iter().position(|l| {
+        let trimmed = l.trim();
+        !trimmed.is_empty() && !trimmed.starts_with("--") && !trimmed.starts_with("{-")
+    });
+    match first_code {
+        Some(i) if lines[i].starts_with("module ") || lines[i].starts_with("port module ") => {
+            Diagnosis::ok(Some(i + 1), "Module header found".to_string())
+        }
+        _ => Diagnosis::problem(
+            DiagnosisLevel::Warning,
+            first_code.map(|i| i + 1),
+            "The file doesn't start with a `module ... exposing (..)` header".to_string(),
+            "Start the file with `module I18n exposing (..)` (Elm won't compile it otherwise)"
+                .to_string(),
+        ),
+    }
+}
+
+fn check_brace_balance(definitions: &[Definition], scans: &[LineScan]) -> Vec<Diagnosis> {
+    let mut diagnoses = Vec::new();
+    for definition in definitions {
+        let (opens, closes) = scans[definition.start..=definition.end]
+            .iter()
+            .fold((0, 0), |(o, c), scan| (o + scan.opens, c + scan.closes));
+        if opens != closes {
+            diagnoses.push(Diagnosis::problem(
+                DiagnosisLevel::Blocking,
+                Some(definition.start + 1),
+                format!(
+                    "Braces don't balance in `{}` (lines {}–{}): {} `{{` but {} `}}`",
+                    definition.name,
+                    definition.start + 1,
+                    definition.end + 1,
+                    opens,
+                    closes
+                ),
+                if opens > closes {
+                    "Look for a record that is missing its closing `}`".to_string()
+                } else {
+                    "Look for a stray `}` or a record that is missing its opening `{`".to_string()
+                },
+            ));
+        }
+    }
+    diagnoses
+}
+
+fn check_record(
+    lines: &[&str],
+    scans: &[LineScan],
+    record_name: &str,
+    lang: &str,
+) -> Vec<Diagnosis> {
+    let name = translations_record_name(lang);
+    let annotation = Regex::new(&format!(r"^{}\s*:\s*(.*)$", regex::escape(&name))).unwrap();
+    let definition = Regex::new(&format!(r"^{}\s*=", regex::escape(&name))).unwrap();
+
+    let annotation_line = lines.iter().position(|l| annotation.is_match(l));
+    let definition_line = lines.iter().position(|l| definition.is_match(l));
+
+    let definitions: Vec<usize> = (0..lines.len())
+        .filter(|&i| annotation.is_match(lines[i]))
+        .map(|i| i + 1)
+        .collect();
+    if definitions.len() > 1 {
+        return vec![Diagnosis::problem(
+            DiagnosisLevel::Blocking,
+            Some(definitions[1]),
+            format!(
+                "`{}` is defined {} times (lines {}); elm-i18n would only read the first",
+                name,
+                definitions.len(),
+                line_list(&definitions)
+            ),
+            "Run `elm-i18n doctor --merge-duplicates` to merge them into one record, the later definition winning for fields both have".to_string(),
+        )];
+    }
+
+    match (annotation_line, definition_line) {
+        (Some(a), Some(d)) => {
+            let annotated_type = annotation.captures(lines[a]).unwrap()[1].trim().to_string();
+            if annotated_type != record_name {
+                return vec![Diagnosis::problem(
+                    DiagnosisLevel::Blocking,
+                    Some(a + 1),
+                    format!(
+                        "`{}` is annotated as `{}`, not `{}`",
+                        name, annotated_type, record_name
+                    ),
+                    format!("Change the annotation to `{} : {}`", name, record_name),
+                )];
+            }
+            let mut diagnoses = vec![Diagnosis::ok(
+                Some(a + 1),
+                format!("`{}` found for '{}'", name, lang),
+            )];
+            diagnoses.extend(check_block(lines, scans, d, &name, '='));
+            diagnoses
+        }
+        (None, Some(d)) => vec![Diagnosis::problem(
+            DiagnosisLevel::Blocking,
+            Some(d + 1),
+            format!("`{}` has no type annotation", name),
+            format!(
+                "Add `{} : {}` on the line above line {}",
+                name,
+                record_name,
+                d + 1
+            ),
+        )],
+        (Some(a), None) => vec![Diagnosis::problem(
+            DiagnosisLevel::Blocking,
+            Some(a + 1),
+            format!("`{}` is annotated but never defined", name),
+            format!("Define it below the annotation as `{} = {{ ... }}`", name),
+        )],
+        (None, None) => {
+            // A near miss like `translationsFR` or `translationFr`
+            let lowered = name.to_lowercase();
+            let near_miss = lines.iter().enumerate().find_map(|(i, l)| {
+                let candidate = l.split(|c: char| !c.is_alphanumeric()).next()?;
+                let folded = candidate.to_lowercase();
+                let similar = folded == lowered
+                    || folded == lowered.replacen("translations", "translation", 1);
+                (similar && !candidate.is_empty()).then(|| (i, candidate.to_string()))
+            });
+            let suggestion = match &near_miss {
+                Some((i, candidate)) => format!(
+                    "Line {} defines `{}`; rename it to `{}`",
+                    i + 1,
+                    candidate,
+                    name
+                ),
+                None => format!(
+                    "Run `elm-i18n add-language {}`, or remove '{}' from the languages in elm-i18n/config.json",
+                    lang, lang
+                ),
+            };
+            vec![Diagnosis::problem(
+                DiagnosisLevel::Blocking,
+                near_miss.as_ref().map(|(i, _)| i + 1),
+                format!("No `{}` record for language '{}'", name, lang),
+                suggestion,
+            )]
+        }
+    }
+}
+
+/// Duplicate and unreadable fields in the record that follows `start`
+///
+/// `separator` is `:` for the type alias and `=` for a language record.
+fn check_block(
+    lines: &[&str],
+    scans: &[LineScan],
+    start: usize,
+    name: &str,
+    separator: char,
+) -> Vec<Diagnosis> {
+    let mut diagnoses = Vec::new();
+    let field = Regex::new(&format!(r"^\s*[{{,]?\s*([a-z]\w*)\s*{}", separator)).unwrap();
+    let other_separator = if separator == ':' { '=' } else { ':' };
+    let wrong_separator =
+        Regex::new(&format!(r"^\s*[{{,]\s*([a-z]\w*)\s*{}", other_separator)).unwrap();
+
+    let mut depth = 0;
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        if scans[i].continues_literal {
+            continue;
+        }
+        // An unclosed record ends where the next definition starts
+        if i > start && starts_definition(line) {
+            break;
+        }
+        // Nested records and function bodies belong to the field above them
+        if depth == 1 || (depth == 0 && scans[i].opens > 0) {
+            let trimmed = line.trim();
+            if let Some(captures) = field.captures(line) {
+                let key = captures[1].to_string();
+                match seen.get(&key) {
+                    Some(first) => diagnoses.push(Diagnosis::problem(
+                        DiagnosisLevel::Blocking,
+                        Some(i + 1),
+                        format!(
+                            "`{}` is defined twice in `{}` (lines {} and {})",
+                            key,
+                            name,
+                            first + 1,
+                            i + 1
+                        ),
+                        "Keep one of them; Elm rejects records with duplicate fields".to_string(),
+                    )),
+                    None => {
+                        seen.insert(key, i);
+                    }
+                }
+            } else if let Some(captures) = wrong_separator.captures(line) {
+                diagnoses.push(Diagnosis::problem(
+                    DiagnosisLevel::Blocking,
+                    Some(i + 1),
+                    format!(
+                        "`{}` in `{}` uses `{}` instead of `{}`",
+                        &captures[1], name, other_separator, separator
+                    ),
+                    if separator == ':' {
+                        "The type alias declares fields as `name : Type`".to_string()
+                    } else {
+                        "Records define fields as `name = value`".to_string()
+                    },
+                ));
+            } else if (trimmed.starts_with(',') || trimmed.starts_with('{'))
+                && trimmed.len() > 1
+                && !trimmed[1..].trim_start().starts_with("--")
+            {
+                diagnoses.push(Diagnosis::problem(
+                    DiagnosisLevel::Warning,
+                    Some(i + 1),
+                    format!(
+                        "Line {} of `{}` looks like a field but can't be read: `{}`",
+                        i + 1,
+                        name,
+                        trimmed
+                    ),
+                    format!(
+                        "Field names start with a lowercase letter and use only letters, digits and `_` (e.g., `, welcomeBack {} ...`)",
+                        separator
+                    ),
+                ));
+            }
+        }
+
+        depth += scans[i].opens;
+        opened |= scans[i].opens > 0;
+        depth = depth.saturating_sub(scans[i].closes);
+        if opened && depth == 0 {
+            break;
+        }
+    }
+
+    diagnoses
+}
+
+/// Unindented code starts a definition (a stray `}` at column 0 doesn't)
+fn starts_definition(line: &str) -> bool {
+    line.chars().next().is_some_and(|c| !c.is_whitespace())
+        && !line.starts_with("--")
+        && !line.starts_with("{-")
+        && !line.starts_with('}')
+}
+
+/// Top-level definitions, each running up to the line before the next one
+fn top_level_definitions(lines: &[&str], scans: &[LineScan]) -> Vec<Definition> {
+    let starts: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, l)| !scans[*i].continues_literal && starts_definition(l))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut definitions: Vec<Definition> = Vec::new();
+    for (n, &start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).map_or(lines.len(), |&next| next) - 1;
+        let name = lines[start]
+            .split([':', '='])
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        // `translationsEn : Translations` and `translationsEn =` are one definition
+        match definitions.last_mut() {
+            Some(previous) if previous.name == name => previous.end = end,
+            _ => definitions.push(Definition { name, start, end }),
+        }
+    }
+    definitions
+}
+
+fn scan_lines(source: &str) -> Vec<LineScan> {
+    let mut scans = Vec::new();
+    let mut in_string: Option<&str> = None;
+    let mut comment_depth = 0;
+
+    for line in source.lines() {
+        let continues_literal = in_string.is_some() || comment_depth > 0;
+        let chars: Vec<char> = line.chars().collect();
+        let (mut opens, mut closes) = (0, 0);
+        let mut i = 0;
+        while i < chars.len() {
+            let rest: String = chars[i..].iter().take(3).collect();
+            if let Some(delimiter) = in_string {
+                if chars[i] == '\\' {
+                    i += 2;
+                    continue;
+                }
+                if rest.starts_with(delimiter) {
+                    in_string = None;
+                    i += delimiter.len();
+                    continue;
+                }
+                i += 1;
+                continue;
+            }
+            if comment_depth > 0 {
+                if rest.starts_with("-}") {
+                    comment_depth -= 1;
+                    i += 2;
+                } else if rest.starts_with("{-") {
+                    comment_depth += 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+                continue;
+            }
+            match chars[i] {
+                '-' if rest.starts_with("--") => break,
+                '{' if rest.starts_with("{-") => {
+                    comment_depth += 1;
+                    i += 2;
+                    continue;
+                }
+                '"' if rest == "\"\"\"" => {
+                    in_string = Some("\"\"\"");
+                    i += 3;
+                    continue;
+                }
+                '"' => in_string = Some("\""),
+                '\'' => {
+                    // Skip a character literal such as '{' or '\''
+                    let close = chars[i + 1..]
+                        .iter()
+                        .enumerate()
+                        .position(|(j, &c)| c == '\'' && (j == 0 || chars[i + j] != '\\'));
+                    if let Some(offset) = close.filter(|&offset| offset > 0) {
+                        i += offset + 2;
+                        continue;
+                    }
+                }
+                '{' => opens += 1,
+                '}' => closes += 1,
+                _ => {}
+            }
+            i += 1;
+        }
+        // A single-quoted string can't span lines
+        if in_string == Some("\"") {
+            in_string = None;
+        }
+        scans.push(LineScan {
+            opens,
+            closes,
+            continues_literal,
+        });
+    }
+    scans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn languages() -> Vec<String> {
+        vec!["en".to_string(), "fr".to_string()]
+    }
+
+    fn problems(source: &str) -> Vec<(DiagnosisLevel, Option<usize>, String)> {
+        diagnose(source, "Translations", &languages())
+            .into_iter()
+            .filter(|d| d.level != DiagnosisLevel::Ok)
+            .map(|d| (d.level, d.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn finds_nothing_wrong_with_a_well_formed_file() {
+        let source = "module I18n exposing (..)
+
+
+type alias Translations =
+    { welcome : String
+    , greet : String -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = \"Welcome {name}\"
+    -- Braces in comments don't count: {- {} -}
+    , greet = \\name -> \"Hi \" ++ name
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { welcome = \"\"\"Bienvenue
+{ }\"\"\"
+    , greet = \\name -> \"Salut \" ++ name
+    }
+";
+
+        assert_eq!(problems(source), vec![]);
+        assert!(diagnose(source, "Translations", &languages())
+            .iter()
+            .any(|d| d.message == "The file parses: 2 keys"));
+    }
+
+    #[test]
+    fn flags_a_record_defined_twice() {
+        let record = "translationsFr : Translations\ntranslationsFr =\n    { welcome = \"Bienvenue\"\n    }\n";
+        let source = format!(
+            "module I18n exposing (..)\n\n\ntype alias Translations =\n    {{ welcome : String\n    }}\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    {{ welcome = \"Welcome\"\n    }}\n\n\n{}\n\n{}",
+            record, record
+        );
+
+        assert_eq!(
+            problems(&source),
+            vec![(
+                DiagnosisLevel::Blocking,
+                Some(21),
+                "`translationsFr` is defined 2 times (lines 15 and 21); elm-i18n would only read the first"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn explains_what_keeps_the_parser_from_reading_a_file() {
+        let source = "type alias Translations =
+    { welcome : String
+    , welcome : String
+    , sign-in : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome : \"Welcome\"
+
+
+translationFr : Translations
+translationFr =
+    { welcome = \"Bienvenue\"
+    }
+";
+
+        assert_eq!(
+            problems(source),
+            vec![
+                (
+                    DiagnosisLevel::Warning,
+                    Some(1),
+                    "The file doesn't start with a `module ... exposing (..)` header".to_string()
+                ),
+                (
+                    DiagnosisLevel::Blocking,
+                    Some(8),
+                    "Braces don't balance in `translationsEn` (lines 8–12): 1 `{` but 0 `}`"
+                        .to_string()
+                ),
+                (
+                    DiagnosisLevel::Blocking,
+                    Some(3),
+                    "`welcome` is defined twice in `Translations` (lines 2 and 3)".to_string()
+                ),
+                (
+                    DiagnosisLevel::Warning,
+                    Some(4),
+                    "Line 4 of `Translations` looks like a field but can't be read: `, sign-in : String`"
+                        .to_string()
+                ),
+                (
+                    DiagnosisLevel::Blocking,
+                    Some(10),
+                    "`welcome` in `translationsEn` uses `:` instead of `=`".to_string()
+                ),
+                (
+                    DiagnosisLevel::Blocking,
+                    Some(13),
+                    "No `translationsFr` record for language 'fr'".to_string()
+                ),
+            ]
+        );
+    }
+}
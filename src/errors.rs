@@ -0,0 +1,401 @@
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Display;
+
+/// What kind of error it is, as `--errors-as-json` reports it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Code {
+    /// Arguments the command line couldn't be parsed with
+    Usage,
+    Config,
+    FileNotFound,
+    KeyNotFound,
+    AlreadyExists,
+    UnknownLanguage,
+    Git,
+    ParseError,
+    InvalidArgument,
+    /// Any other error
+    #[default]
+    Error,
+}
+
+/// An error with its code, and the key and line it's about when there are such
+///
+/// Reported with [`Coded::report`], or returned through `?` for [`record_failure`]
+/// to find in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coded {
+    pub code: Code,
+    message: String,
+    key: Option<String>,
+    file: Option<String>,
+    line: Option<usize>,
+}
+
+impl Coded {
+    pub fn new(code: Code, message: impl Display) -> Self {
+        Coded {
+            code,
+            message: message.to_string(),
+            key: None,
+            file: None,
+            line: None,
+        }
+    }
+
+    /// That there's no translation `key`
+    pub fn key_not_found(key: &str) -> Self {
+        Coded::new(
+            Code::KeyNotFound,
+            format!("Translation '{}' not found", key),
+        )
+        .key(key)
+    }
+
+    /// That there's already a translation `key`
+    pub fn key_exists(key: &str) -> Self {
+        Coded::new(
+            Code::AlreadyExists,
+            format!("Translation '{}' already exists", key),
+        )
+        .key(key)
+    }
+
+    /// The key the error is about
+    pub fn key(mut self, key: impl Display) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// The 1-based line the error is on, in the file being read; the report only
+    /// has it once [`Coded::file`] says which file that is
+    pub fn line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// The file the error is in, unless one was already given
+    pub fn file(mut self, file: impl Display) -> Self {
+        self.file.get_or_insert_with(|| file.to_string());
+        self
+    }
+
+    /// Report it like [`error`], with its code and context
+    pub fn report(self) {
+        let message = self.message.clone();
+        remember(self);
+        error(message);
+    }
+}
+
+impl Display for Coded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Coded {}
+
+/// Report an error of the kind `code`
+pub fn error_with(code: Code, message: impl Display) {
+    Coded::new(code, message).report();
+}
+
+/// `error` as a [`Coded`] error of the kind `code`, its causes joined into the message
+pub fn coded(code: Code, error: anyhow::Error) -> anyhow::Error {
+    match error.downcast::<Coded>() {
+        Ok(coded) => coded.into(),
+        Err(error) => Coded::new(code, format!("{:#}", error)).into(),
+    }
+}
+
+/// Report `error` like [`error`], with its code when it's a [`Coded`] error
+pub fn report(error: anyhow::Error) {
+    match error.downcast::<Coded>() {
+        Ok(coded) => coded.report(),
+        Err(error) => self::error(error),
+    }
+}
+
+/// `error`, with the file it's in when it's a [`Coded`] error that has a line but
+/// doesn't say which file
+pub fn in_file(error: anyhow::Error, file: &std::path::Path) -> anyhow::Error {
+    match error.downcast::<Coded>() {
+        Ok(coded) => coded.file(file.display()).into(),
+        Err(error) => error,
+    }
+}
+
+/// An error as `--errors-as-json` prints it on stderr
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    pub code: Code,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The lines after the message, and further errors reported along with it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// The suggestions printed after the error, such as the command to run
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+}
+
+#[derive(Default)]
+struct State {
+    json: bool,
+    command: Option<String>,
+    file: Option<String>,
+    key: Option<String>,
+    /// The first error, when it was reported with its code
+    coded: Option<Coded>,
+    errors: Vec<String>,
+    hints: Vec<String>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Collect errors to print as one JSON object on exit, for the rest of the run
+pub fn enable_json() {
+    STATE.with(|state| state.borrow_mut().json = true);
+}
+
+pub fn json_enabled() -> bool {
+    STATE.with(|state| state.borrow().json)
+}
+
+/// The command being run, and the file and key it was given, to report errors with
+pub fn set_context(command: &str, file: Option<String>, key: Option<String>) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.command = Some(command.to_string());
+        state.file = file;
+        state.key = key;
+    });
+}
+
+/// Report an error: a ✗ line on stderr, or part of the JSON report
+pub fn error(message: impl Display) {
+    let collected = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.json {
+            state.errors.push(message.to_string());
+        }
+        state.json
+    });
+    if !collected {
+        eprintln!("{} {}", "✗".red(), message);
+    }
+}
+
+/// Report arguments the command line couldn't be parsed with
+pub fn usage_error(message: impl Display) {
+    error_with(Code::Usage, message);
+}
+
+/// Keep the code and context of `coded` if it's the first error of the run
+fn remember(coded: Coded) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.json && state.errors.is_empty() {
+            state.coded = Some(coded);
+        }
+    });
+}
+
+/// An ℹ note on stderr; after an error in JSON mode, one of its hints
+pub fn hint(message: impl Display) {
+    if !collect_hint(&message) {
+        eprintln!("{} {}", "ℹ".blue(), message);
+    }
+}
+
+/// A → suggestion on stderr; after an error in JSON mode, one of its hints
+pub fn suggestion(message: impl Display) {
+    if !collect_hint(&message) {
+        eprintln!("{} {}", "→".cyan(), message);
+    }
+}
+
+/// A line explaining the error before it, such as one of a list of candidates;
+/// in JSON mode, one of its details
+pub fn detail(message: impl Display) {
+    let collected = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let collect = state.json && !state.errors.is_empty();
+        if collect {
+            state.errors.push(message.to_string());
+        }
+        collect
+    });
+    if !collected {
+        eprintln!("{}", message);
+    }
+}
+
+fn collect_hint(message: &impl Display) -> bool {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let collect = state.json && !state.errors.is_empty();
+        if collect {
+            state.hints.push(message.to_string());
+        }
+        collect
+    })
+}
+
+/// In JSON mode, print the errors reported so far, or for a failure that reported
+/// none (such as `validate` finding problems), a report that the command failed
+pub fn flush(status: i32) {
+    let report = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if !state.json || status == 0 {
+            return None;
+        }
+        let errors = std::mem::take(&mut state.errors);
+        let hints = std::mem::take(&mut state.hints);
+        let errors = if errors.is_empty() {
+            let command = state.command.as_deref().unwrap_or("elm-i18n");
+            vec![format!("`{}` failed with status {}", command, status)]
+        } else {
+            errors
+        };
+        let mut report = error_report(&errors, hints, state.coded.take());
+        report.command = state.command.clone();
+        report.file = report.file.take().or_else(|| state.file.clone());
+        report.key = report.key.take().or_else(|| state.key.clone());
+        Some(report)
+    });
+    if let Some(report) = report {
+        eprintln!("{}", serde_json::to_string(&report).unwrap_or_default());
+    }
+}
+
+/// Record an error that ended the run, so [`flush`] reports it
+pub fn record_failure(error: &anyhow::Error) {
+    let message = error
+        .chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join(": ");
+    if let Some(coded) = coded_cause(error) {
+        remember(coded.clone());
+    }
+    STATE.with(|state| state.borrow_mut().errors.push(message));
+}
+
+/// The [`Coded`] error `error` is, has as its outermost context, or started as
+fn coded_cause(error: &anyhow::Error) -> Option<&Coded> {
+    error.downcast_ref::<Coded>().or_else(|| {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Coded>())
+    })
+}
+
+/// The report of `errors`, the first being the message and the rest details, with
+/// the code and context `coded` gives the first
+fn error_report(errors: &[String], hints: Vec<String>, coded: Option<Coded>) -> ErrorReport {
+    let ansi = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+    let plain = |text: &String| ansi.replace_all(text.trim(), "").to_string();
+    let coded = coded.unwrap_or_else(|| Coded::new(Code::Error, ""));
+    // A line is only worth reporting with the file it's in
+    let line = coded.line.filter(|_| coded.file.is_some());
+
+    ErrorReport {
+        code: coded.code,
+        message: errors.first().map(plain).unwrap_or_default(),
+        file: coded.file,
+        key: coded.key,
+        line,
+        details: errors.iter().skip(1).map(plain).collect(),
+        hints: hints.iter().map(plain).collect(),
+        ..ErrorReport::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_errors_with_the_code_and_context_they_were_given() {
+        let report = error_report(
+            &[
+                "Translation '\x1b[33mwelcome\x1b[0m' not found in src/I18n.elm".to_string(),
+                "Second problem".to_string(),
+            ],
+            vec!["Run 'elm-i18n list' to see the keys".to_string()],
+            Some(Coded::new(Code::KeyNotFound, "").key("welcome")),
+        );
+        assert_eq!(
+            report,
+            ErrorReport {
+                code: Code::KeyNotFound,
+                message: "Translation 'welcome' not found in src/I18n.elm".to_string(),
+                key: Some("welcome".to_string()),
+                details: vec!["Second problem".to_string()],
+                hints: vec!["Run 'elm-i18n list' to see the keys".to_string()],
+                ..ErrorReport::default()
+            }
+        );
+
+        // Nothing is read from the message itself
+        let uncoded = error_report(
+            &["Key 'x' not found on line 3".to_string()],
+            Vec::new(),
+            None,
+        );
+        assert_eq!(
+            (uncoded.code, uncoded.key, uncoded.line),
+            (Code::Error, None, None)
+        );
+
+        // A line goes with the file it's in, or isn't reported
+        let parse = Coded::new(Code::ParseError, "").line(39);
+        let report = |coded: Coded| error_report(&["x".to_string()], Vec::new(), Some(coded));
+        assert_eq!(report(parse.clone()).line, None);
+        let in_keys = report(parse.file("keys.txt"));
+        assert_eq!(
+            (in_keys.file.as_deref(), in_keys.line),
+            (Some("keys.txt"), Some(39))
+        );
+
+        let json = serde_json::to_string(&report(Coded::new(Code::FileNotFound, ""))).unwrap();
+        assert_eq!(json, r#"{"code":"file-not-found","message":"x"}"#);
+    }
+
+    #[test]
+    fn finds_the_code_of_an_error_returned_through_question_marks() {
+        let error = anyhow::Error::from(Coded::new(Code::ParseError, "bad").line(4));
+        let error = in_file(error, std::path::Path::new("src/I18n.elm")).context("Reading");
+        let cause = coded_cause(&error).unwrap();
+        assert_eq!(cause.code, Code::ParseError);
+        assert_eq!(cause.file.as_deref(), Some("src/I18n.elm"));
+
+        // Or added as context
+        let missing = anyhow::anyhow!("No such file")
+            .context(Coded::new(Code::FileNotFound, "Failed to read x.elm"));
+        assert_eq!(coded_cause(&missing).unwrap().code, Code::FileNotFound);
+        let flattened = coded(
+            Code::Config,
+            anyhow::anyhow!("empty").context("Invalid lint"),
+        );
+        assert_eq!(
+            coded_cause(&flattened),
+            Some(&Coded::new(Code::Config, "Invalid lint: empty"))
+        );
+    }
+}
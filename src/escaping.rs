@@ -0,0 +1,170 @@
+/// Text as the body of an Elm string literal, ready to go between `"` quotes
+///
+/// Backslashes, quotes, line breaks and tabs get their usual escapes. Other control
+/// characters, which Elm doesn't allow in a literal, and invisible ones such as a
+/// non-breaking space, which editors and reviewers can't tell from a space or
+/// nothing, are written as `\u{XXXX}`.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() || is_invisible(c) => {
+                escaped.push_str(&format!("\\u{{{:04X}}}", c as u32))
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Spaces other than U+0020 and characters that render as nothing
+///
+/// The zero-width joiner and variation selectors are left alone, since they're
+/// part of emoji like 👨‍👩‍👧 and ❤️.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00A0}'
+            | '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{180E}'
+            | '\u{2000}'..='\u{200C}'
+            | '\u{200E}'..='\u{200F}'
+            | '\u{2028}'..='\u{202F}'
+            | '\u{205F}'..='\u{2064}'
+            | '\u{2066}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+    )
+}
+
+/// The text an Elm string literal body stands for, resolving its escapes
+///
+/// The inverse of `escape`. Sequences Elm wouldn't accept, such as `\q` or a
+/// `\u{…}` that isn't a character, are kept as written.
+pub fn unescape(literal: &str) -> String {
+    let mut text = String::with_capacity(literal.len());
+    let mut chars = literal.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some(c @ ('\\' | '"' | '\'')) => text.push(c),
+            Some('u') if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut hex = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    hex.push(c);
+                }
+                let decoded = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .filter(|_| closed);
+                match decoded {
+                    Some(decoded) => text.push(decoded),
+                    None => {
+                        text.push_str("\\u{");
+                        text.push_str(&hex);
+                        if closed {
+                            text.push('}');
+                        }
+                    }
+                }
+            }
+            Some(other) => {
+                text.push('\\');
+                text.push(other);
+            }
+            None => text.push('\\'),
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::plain_string_value;
+
+    #[test]
+    fn escapes_and_resolves_each_sequence() {
+        assert_eq!(
+            escape("say \"hi\"\\\n\r\t\u{7}é👍"),
+            r#"say \"hi\"\\\n\r\t\u{0007}é👍"#
+        );
+        assert_eq!(
+            unescape(r#"say \"hi\" \'\u{00E9}\u{1F44D}\\n"#),
+            "say \"hi\" 'é👍\\n"
+        );
+        assert_eq!(
+            unescape(r"C:\path \u{D800} \u{zz} \u{41"),
+            r"C:\path \u{D800} \u{zz} \u{41"
+        );
+        assert_eq!(unescape("trailing \\"), "trailing \\");
+
+        // Invisible characters are spelled out; the ones emoji are built from aren't
+        assert_eq!(
+            escape("Prix\u{202F}: 5\u{A0}€\u{200B}"),
+            r"Prix\u{202F}: 5\u{00A0}€\u{200B}"
+        );
+        assert_eq!(escape("👨\u{200D}👩 ❤\u{FE0F}"), "👨\u{200D}👩 ❤\u{FE0F}");
+    }
+
+    #[test]
+    fn round_trips_arbitrary_text() {
+        // Characters that tend to break escaping, mixed with arbitrary code points
+        const TRICKY: [char; 20] = [
+            '\\', '"', '\'', '\n', '\r', '\t', '\0', '\u{7f}', '\u{85}', 'u', '{', '}', 'é', '👍',
+            '\u{200D}', '\u{FEFF}', '\u{A0}', '\u{202F}', '\u{2028}', '\u{AD}',
+        ];
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = || {
+            // xorshift64, so failures reproduce
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..2000 {
+            let length = next() % 24;
+            let text: String = (0..length)
+                .map(|_| match next() % 3 {
+                    0 => char::from_u32((next() % 0x11_0000) as u32).unwrap_or('x'),
+                    _ => TRICKY[(next() % TRICKY.len() as u64) as usize],
+                })
+                .collect();
+            let escaped = escape(&text);
+            assert_eq!(unescape(&escaped), text, "escaped as {:?}", escaped);
+            assert!(
+                !escaped.chars().any(|c| c.is_control() || is_invisible(c)),
+                "{:?}",
+                escaped
+            );
+            // And as the value of a field, read back the way lint and export read it
+            let value = format!("\"{}\"", escaped);
+            assert_eq!(plain_string_value(&value), Some(text));
+        }
+    }
+}
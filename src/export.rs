@@ -0,0 +1,559 @@
+use crate::context::{description, Contexts};
+use crate::lint::plain_string_value;
+use crate::types::ParseResult;
+use std::collections::HashMap;
+
+/// What `export` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// A Markdown table with a column per language, for copy review
+    Md,
+    /// A TOML table per language, for backends that read the same copy
+    Toml,
+    /// A Dart `const Map<String, String>` per language, for Flutter apps sharing the copy
+    Dart,
+}
+
+impl ExportFormat {
+    /// The extension of the files `export --split` writes
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Md => "md",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Dart => "dart",
+        }
+    }
+
+    /// What an export of `languages` contains
+    pub fn render(
+        self,
+        parse_result: &ParseResult,
+        languages: &[String],
+        contexts: &Contexts,
+    ) -> String {
+        match self {
+            ExportFormat::Md => markdown_table(parse_result, languages, contexts),
+            ExportFormat::Toml => toml_tables(parse_result, languages, contexts),
+            ExportFormat::Dart => dart_maps(parse_result, languages, contexts),
+        }
+    }
+}
+
+/// An export of each key on its own, for `export --split`: the key and its file's
+/// content, in type order
+pub fn split_by_key(
+    parse_result: &ParseResult,
+    format: ExportFormat,
+    languages: &[String],
+    contexts: &Contexts,
+) -> Vec<(String, String)> {
+    parse_result
+        .type_fields
+        .iter()
+        .map(|field| {
+            let single = ParseResult {
+                type_start_line: parse_result.type_start_line,
+                type_end_line: parse_result.type_end_line,
+                lang_bounds: parse_result.lang_bounds.clone(),
+                translations: parse_result
+                    .translations
+                    .get(&field.name)
+                    .map(|t| HashMap::from([(field.name.clone(), t.clone())]))
+                    .unwrap_or_default(),
+                type_fields: vec![field.clone()],
+                record_fields: HashMap::new(),
+            };
+            (
+                field.name.clone(),
+                format.render(&single, languages, contexts),
+            )
+        })
+        .collect()
+}
+
+/// A Markdown table with a Key column and a column per language, in type order
+///
+/// Plain strings are shown as their text; functions and other expressions are shown
+/// as inline code. When keys have notes in `contexts`, a Description column after
+/// the key carries them, with a link to the screenshot if there is one.
+pub fn markdown_table(
+    parse_result: &ParseResult,
+    languages: &[String],
+    contexts: &Contexts,
+) -> String {
+    let described = parse_result
+        .type_fields
+        .iter()
+        .any(|field| contexts.contains_key(&field.name));
+    let mut header = vec!["Key".to_string()];
+    if described {
+        header.push("Description".to_string());
+    }
+    header.extend(languages.iter().map(|lang| lang.to_uppercase()));
+
+    let mut output = table_row(&header);
+    output.push_str(&table_row(&vec!["---".to_string(); header.len()]));
+
+    for field in &parse_result.type_fields {
+        let translation = match parse_result.translations.get(&field.name) {
+            Some(translation) => translation,
+            None => continue,
+        };
+        let mut cells = vec![format!("`{}`", field.name)];
+        if described {
+            let mut cell = description(contexts, &field.name)
+                .map(escape_text)
+                .unwrap_or_default();
+            if let Some(screenshot) = contexts
+                .get(&field.name)
+                .and_then(|c| c.screenshot.as_ref())
+            {
+                if !cell.is_empty() {
+                    cell.push_str("<br>");
+                }
+                cell.push_str(&format!(
+                    "[screenshot](<{}>)",
+                    screenshot.replace('>', "%3E")
+                ));
+            }
+            cells.push(cell);
+        }
+        for lang in languages {
+            let cell = match translation.values.get(lang).map(|v| v.trim()) {
+                None | Some("") => String::new(),
+                Some(value) => match plain_string_value(value) {
+                    Some(text) => escape_text(&text),
+                    None => code_span(value),
+                },
+            };
+            cells.push(cell);
+        }
+        output.push_str(&table_row(&cells));
+    }
+
+    output
+}
+
+/// A `[lang]` table per language with the keys in type order
+///
+/// Values with line breaks are written as multi-line strings. Functions and other
+/// expressions have no TOML equivalent, so they're left out with a comment saying so.
+/// A key's description from `contexts` goes in a comment above it.
+pub fn toml_tables(
+    parse_result: &ParseResult,
+    languages: &[String],
+    contexts: &Contexts,
+) -> String {
+    let mut tables = Vec::new();
+    for lang in languages {
+        let mut table = format!("[{}]\n", toml_key(lang));
+        for field in &parse_result.type_fields {
+            let Some(value) = parse_result
+                .translations
+                .get(&field.name)
+                .and_then(|t| t.values.get(lang))
+            else {
+                continue;
+            };
+            for line in description(contexts, &field.name)
+                .into_iter()
+                .flat_map(str::lines)
+            {
+                table.push_str(&format!("# {}\n", line.trim_end()));
+            }
+            match plain_string_value(value) {
+                Some(text) => table.push_str(&format!(
+                    "{} = {}\n",
+                    toml_key(&field.name),
+                    toml_string(&text)
+                )),
+                None => table.push_str(&format!(
+                    "# {} isn't a plain string, so it isn't exported\n",
+                    field.name
+                )),
+            }
+        }
+        tables.push(table);
+    }
+    tables.join("\n")
+}
+
+/// A key bare when TOML allows it, and quoted otherwise
+fn toml_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+/// A basic string, or a multi-line basic string when the text has line breaks
+fn toml_string(text: &str) -> String {
+    let multiline = text.contains('\n');
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' if multiline => escaped.push('\n'),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    if multiline {
+        // The line break right after the opening quotes isn't part of the string
+        format!("\"\"\"\n{}\"\"\"", escaped)
+    } else {
+        format!("\"{}\"", escaped)
+    }
+}
+
+/// A `const Map<String, String>` per language named after it, with the keys in
+/// type order
+///
+/// As with TOML, functions and other expressions are left out with a comment, and
+/// a key's description goes in a comment above it.
+pub fn dart_maps(parse_result: &ParseResult, languages: &[String], contexts: &Contexts) -> String {
+    let mut maps = Vec::new();
+    for lang in languages {
+        let mut map = format!("const Map<String, String> {} = {{\n", dart_identifier(lang));
+        for field in &parse_result.type_fields {
+            let Some(value) = parse_result
+                .translations
+                .get(&field.name)
+                .and_then(|t| t.values.get(lang))
+            else {
+                continue;
+            };
+            for line in description(contexts, &field.name)
+                .into_iter()
+                .flat_map(str::lines)
+            {
+                map.push_str(&format!("  // {}\n", line.trim_end()));
+            }
+            match plain_string_value(value) {
+                Some(text) => map.push_str(&format!(
+                    "  {}: {},\n",
+                    dart_string(&field.name),
+                    dart_string(&text)
+                )),
+                None => map.push_str(&format!(
+                    "  // {} isn't a plain string, so it isn't exported\n",
+                    field.name
+                )),
+            }
+        }
+        map.push_str("};\n");
+        maps.push(map);
+    }
+    maps.join("\n")
+}
+
+/// A language code as a Dart variable name: `pt_BR` for `pt-BR`, and `is_` for
+/// `is`, which Dart reserves
+fn dart_identifier(lang: &str) -> String {
+    const RESERVED: [&str; 4] = ["do", "if", "in", "is"];
+    let name: String = lang
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if RESERVED.contains(&name.as_str()) || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// A single-quoted Dart string; `$` is escaped so nothing is interpolated
+fn dart_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('\'');
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\'' => escaped.push_str("\\'"),
+            '$' => escaped.push_str("\\$"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:X}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('\'');
+    escaped
+}
+
+fn table_row(cells: &[String]) -> String {
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Text safe inside a table cell: pipes escaped, line breaks as `<br>`, and
+/// markup shown rather than rendered
+pub fn escape_text(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+        .replace('*', "\\*")
+        .replace('_', "\\_")
+        .replace('`', "\\`");
+    escaped.lines().collect::<Vec<_>>().join("<br>")
+}
+
+/// An Elm expression as inline code on one line
+fn code_span(code: &str) -> String {
+    let code = code
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|");
+    // A backtick in the code needs a longer fence
+    let fence = if code.contains('`') { "``" } else { "`" };
+    format!("{} {} {}", fence, code, fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn renders_a_row_per_key_in_type_order() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            r#"type alias Translations =
+    { welcome : String
+    , terms : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome!"
+    , terms = "Read the <a href=\"/t\">terms</a> | privacy\n*policy*"
+    , itemCount = \n -> String.fromInt n ++ " items"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { welcome = ""
+    , terms = "Lisez les conditions"
+    , itemCount = \n -> String.fromInt n ++ " articles"
+    }
+"#,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+
+        assert_eq!(
+            markdown_table(&parse_result, &languages, &Contexts::new()),
+            r#"| Key | EN | FR |
+| --- | --- | --- |
+| `welcome` | Welcome! |  |
+| `terms` | Read the &lt;a href="/t"&gt;terms&lt;/a&gt; \| privacy<br>\*policy\* | Lisez les conditions |
+| `itemCount` | ` \n -> String.fromInt n ++ " items" ` | ` \n -> String.fromInt n ++ " articles" ` |
+"#
+        );
+    }
+
+    #[test]
+    fn splits_an_export_into_a_file_per_key() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            "type alias Translations =\n    { welcome : String\n    , save : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { welcome = \"Welcome!\"\n    , save = \"Save\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { welcome = \"Bienvenue !\"\n    , save = \"Enregistrer\"\n    }\n",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+
+        let files = split_by_key(
+            &parse_result,
+            ExportFormat::Toml,
+            &languages,
+            &Contexts::new(),
+        );
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "welcome".to_string(),
+                    "[en]\nwelcome = \"Welcome!\"\n\n[fr]\nwelcome = \"Bienvenue !\"\n".to_string()
+                ),
+                (
+                    "save".to_string(),
+                    "[en]\nsave = \"Save\"\n\n[fr]\nsave = \"Enregistrer\"\n".to_string()
+                ),
+            ]
+        );
+        let (_, table) = &split_by_key(
+            &parse_result,
+            ExportFormat::Md,
+            &languages,
+            &Contexts::new(),
+        )[1];
+        assert_eq!(
+            table,
+            "| Key | EN | FR |\n| --- | --- | --- |\n| `save` | Save | Enregistrer |\n"
+        );
+    }
+
+    #[test]
+    fn writes_a_toml_table_per_language() {
+        let languages = vec!["en".to_string(), "pt-BR".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            r#"type alias Translations =
+    { welcome : String
+    , terms : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Say \"hi\"\tnow"
+    , terms = "Read the terms\nand the C:\\ policy"
+    , itemCount = \n -> String.fromInt n ++ " items"
+    }
+
+
+translationsPtBR : Translations
+translationsPtBR =
+    { welcome = "Bem-vindo!"
+    , terms = ""
+    , itemCount = \n -> String.fromInt n ++ " itens"
+    }
+"#,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let contexts = Contexts::from([(
+            "welcome".to_string(),
+            crate::context::KeyContext {
+                description: Some("Heading of the home page\nKeep it short".to_string()),
+                screenshot: None,
+            },
+        )]);
+
+        assert_eq!(
+            toml_tables(&parse_result, &languages, &contexts),
+            r#"[en]
+# Heading of the home page
+# Keep it short
+welcome = "Say \"hi\"\tnow"
+terms = """
+Read the terms
+and the C:\\ policy"""
+# itemCount isn't a plain string, so it isn't exported
+
+[pt-BR]
+# Heading of the home page
+# Keep it short
+welcome = "Bem-vindo!"
+terms = ""
+# itemCount isn't a plain string, so it isn't exported
+"#
+        );
+        assert_eq!(toml_key("sign in"), "\"sign in\"");
+    }
+
+    #[test]
+    fn writes_a_dart_map_per_language() {
+        let languages = vec!["en".to_string(), "pt-BR".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            r#"type alias Translations =
+    { welcome : String
+    , price : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "It's \"here\"\nnow"
+    , price = "$5 at C:\\shop"
+    , itemCount = \n -> String.fromInt n ++ " items"
+    }
+
+
+translationsPtBR : Translations
+translationsPtBR =
+    { welcome = "Bem-vindo!"
+    , price = ""
+    , itemCount = \n -> String.fromInt n ++ " itens"
+    }
+"#,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let contexts = Contexts::from([(
+            "welcome".to_string(),
+            crate::context::KeyContext {
+                description: Some("Heading of the home page".to_string()),
+                screenshot: None,
+            },
+        )]);
+
+        assert_eq!(
+            dart_maps(&parse_result, &languages, &contexts),
+            r#"const Map<String, String> en = {
+  // Heading of the home page
+  'welcome': 'It\'s "here"\nnow',
+  'price': '\$5 at C:\\shop',
+  // itemCount isn't a plain string, so it isn't exported
+};
+
+const Map<String, String> pt_BR = {
+  // Heading of the home page
+  'welcome': 'Bem-vindo!',
+  'price': '',
+  // itemCount isn't a plain string, so it isn't exported
+};
+"#
+        );
+        assert_eq!(dart_identifier("is"), "is_");
+    }
+
+    #[test]
+    fn adds_a_description_column_when_keys_have_notes() {
+        let languages = vec!["en".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            "type alias Translations =\n    { save : String\n    , cancel : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    }\n",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let contexts = Contexts::from([(
+            "save".to_string(),
+            crate::context::KeyContext {
+                description: Some("Button under the *profile* form".to_string()),
+                screenshot: Some("docs/profile form.png".to_string()),
+            },
+        )]);
+
+        assert_eq!(
+            markdown_table(&parse_result, &languages, &contexts),
+            "| Key | Description | EN |\n| --- | --- | --- |\n| `save` | Button under the \\*profile\\* form<br>[screenshot](<docs/profile form.png>) | Save |\n| `cancel` |  | Cancel |\n"
+        );
+    }
+
+    #[test]
+    fn keeps_code_with_pipes_and_backticks_inside_one_cell() {
+        assert_eq!(code_span("a |> f"), r"` a \|> f `");
+        assert_eq!(code_span("\"`x`\""), "`` \"`x`\" ``");
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::context::{description, Contexts};
+use crate::lock::content_hash;
+use crate::timing;
+use crate::types::ParseResult;
+
+/// Where `export --only-changed` keeps what it last exported, next to the config
+pub const EXPORT_CACHE_FILE: &str = "elm-i18n/export-cache.json";
+
+/// What an export to one `--output` was made from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    /// Hash of the translation file, its notes and the export settings
+    pub source: String,
+    /// Each language's keys in type order, with a hash of what's exported for each
+    pub languages: BTreeMap<String, Vec<(String, String)>>,
+}
+
+/// The last export to each `--output`, as given on the command line
+pub type ExportCache = BTreeMap<String, ExportRecord>;
+
+/// The cache, or an empty one when there's none or it can't be read, which only
+/// means everything is exported again
+pub fn load(path: &Path) -> ExportCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, cache: &ExportCache) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut json = serde_json::to_string(cache)?;
+    json.push('\n');
+    timing::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The keys of `parse_result` in type order, each with a hash of its value in
+/// `lang` and its description, the parts of it an export shows
+pub fn key_hashes(
+    parse_result: &ParseResult,
+    lang: &str,
+    contexts: &Contexts,
+) -> Vec<(String, String)> {
+    parse_result
+        .type_fields
+        .iter()
+        .map(|field| {
+            let value = parse_result
+                .translations
+                .get(&field.name)
+                .and_then(|t| t.values.get(lang))
+                .map_or("", |v| v.trim());
+            let context = contexts.get(&field.name);
+            let exported = format!(
+                "{}\n{}\n{}",
+                value,
+                description(contexts, &field.name).unwrap_or_default(),
+                context
+                    .and_then(|c| c.screenshot.as_deref())
+                    .unwrap_or_default()
+            );
+            (field.name.clone(), content_hash(&exported))
+        })
+        .collect()
+}
+
+/// The keys added, removed or changed from `old` to `new`, in the order of `new`
+/// then of `old`; every key when there's nothing to compare with
+pub fn changed_keys(old: Option<&[(String, String)]>, new: &[(String, String)]) -> Vec<String> {
+    let Some(old) = old else {
+        return new.iter().map(|(key, _)| key.clone()).collect();
+    };
+    let before: BTreeMap<&String, &String> = old.iter().map(|(k, h)| (k, h)).collect();
+    let after: BTreeMap<&String, &String> = new.iter().map(|(k, h)| (k, h)).collect();
+    let changed = new
+        .iter()
+        .filter(|(key, hash)| before.get(key) != Some(&hash))
+        .map(|(key, _)| key.clone());
+    let removed = old
+        .iter()
+        .filter(|(key, _)| !after.contains_key(key))
+        .map(|(key, _)| key.clone());
+    changed.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::KeyContext;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn finds_the_keys_an_export_would_change() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse = |save_fr: &str, extra: bool| {
+            let (field, en, fr) = if extra {
+                (
+                    "\n    , title : String",
+                    "\n    , title = \"Profile\"",
+                    "\n    , title = \"Profil\"",
+                )
+            } else {
+                ("", "", "")
+            };
+            let source = format!(
+                "type alias Translations =\n    {{ save : String\n    , cancel : String{}\n    }}\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    {{ save = \"Save\"\n    , cancel = \"Cancel\"{}\n    }}\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    {{ save = \"{}\"\n    , cancel = \"Annuler\"{}\n    }}\n",
+                field, en, save_fr, fr
+            );
+            parse_i18n_content_with_record_name(&source, "Translations", &languages).unwrap()
+        };
+        let contexts = Contexts::new();
+        let before = parse("Sauver", false);
+        let after = parse("Enregistrer", true);
+
+        let en = (
+            key_hashes(&before, "en", &contexts),
+            key_hashes(&after, "en", &contexts),
+        );
+        assert_eq!(changed_keys(Some(&en.0), &en.1), vec!["title"]);
+        let fr = (
+            key_hashes(&before, "fr", &contexts),
+            key_hashes(&after, "fr", &contexts),
+        );
+        assert_eq!(changed_keys(Some(&fr.0), &fr.1), vec!["save", "title"]);
+        assert_eq!(changed_keys(Some(&fr.1), &fr.0), vec!["save", "title"]);
+        assert_eq!(changed_keys(None, &en.0), vec!["save", "cancel"]);
+
+        // A new description changes what an export shows in every language
+        let described = Contexts::from([(
+            "cancel".to_string(),
+            KeyContext {
+                description: Some("Closes the dialog".to_string()),
+                screenshot: None,
+            },
+        )]);
+        assert_eq!(
+            changed_keys(Some(&en.0), &key_hashes(&before, "en", &described)),
+            vec!["cancel"]
+        );
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("elm-i18n/export-cache.json");
+        assert!(load(&path).is_empty());
+        let cache = ExportCache::from([(
+            "locales/{lang}.toml".to_string(),
+            ExportRecord {
+                source: "abc".to_string(),
+                languages: BTreeMap::from([("en".to_string(), en.1.clone())]),
+            },
+        )]);
+        save(&path, &cache).unwrap();
+        assert_eq!(load(&path), cache);
+    }
+}
@@ -0,0 +1,376 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::escaping;
+use crate::language_edit::blank_comments;
+use crate::scan::{blank_strings, SourceSet, IGNORE_NEXT_LINE};
+
+/// The functions whose string argument `extract` reports when the config doesn't list any
+pub const DEFAULT_FUNCTIONS: [&str; 4] = ["text", "placeholder", "title", "alt"];
+
+/// How many words of the text go into a suggested key
+const KEY_WORDS: usize = 4;
+
+/// A string literal passed to one of the functions `extract` looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardcodedString {
+    /// 1-based line of the literal
+    pub line: usize,
+    /// The function as written, e.g. `Html.text`
+    pub function: String,
+    /// The literal's text, escapes resolved
+    pub text: String,
+    /// Byte range of the literal in the source, quotes included
+    pub literal: Range<usize>,
+    /// A key for the text: the module's last segment (or the enclosing function)
+    /// followed by the text's first words, e.g. `settingsSaveChanges`
+    pub suggested_key: String,
+}
+
+/// The string literals in `source` passed straight to one of `functions`
+/// (`text "Save"`, `Html.text <| "Save"`) that look like copy
+///
+/// Functions match by their last segment, so `text` also finds `Html.text` and
+/// `H.text`. Literals that aren't copy are left out: blank ones, single characters,
+/// URLs and paths, and single words like `btn-primary`. Lines below an
+/// `-- elm-i18n:ignore-next-line` comment are skipped.
+pub fn hardcoded_strings(source: &str, functions: &[String]) -> Vec<HardcodedString> {
+    let code = blank_comments(source);
+    let outside_strings = blank_strings(&code);
+    let source_lines: Vec<&str> = source.lines().collect();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+
+    let names: Vec<&str> = functions.iter().map(|f| last_segment(f)).collect();
+    let call =
+        Regex::new(r#"((?:[A-Z][\w]*\.)*([a-z][\w]*))[ \t]+(?:<\|\s*)?"((?:[^"\\\n]|\\.)*)""#)
+            .unwrap();
+    let module = Regex::new(r"(?m)^(?:port\s+|effect\s+)?module\s+([\w.]+)").unwrap();
+    let module_name = module
+        .captures(&code)
+        .map(|c| last_segment(&c[1]).to_string());
+    let definitions = definitions(&code);
+
+    let mut found = Vec::new();
+    for captures in call.captures_iter(&code) {
+        let function = captures.get(1).unwrap();
+        let start = function.start();
+        let literal_end = captures.get(0).unwrap().end();
+        let literal_start = captures.get(3).unwrap().start() - 1;
+        // A name preceded by part of another name, or inside a string, isn't a call
+        let preceded = code[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.');
+        if preceded || outside_strings[start..function.end()] != *function.as_str() {
+            continue;
+        }
+        // The empty literal of a `"""` string
+        if code[literal_end..].starts_with('"') {
+            continue;
+        }
+        if !names.contains(&&captures[2]) {
+            continue;
+        }
+        let line = line_of(start);
+        if line > 1 && source_lines[line - 2].trim() == IGNORE_NEXT_LINE {
+            continue;
+        }
+
+        let text = escaping::unescape(&captures[3]);
+        if !looks_like_copy(&text) {
+            continue;
+        }
+        let enclosing = enclosing(&definitions, start).map(|d| d.name);
+        found.push(HardcodedString {
+            line,
+            function: function.as_str().to_string(),
+            suggested_key: suggest_key(module_name.as_deref(), enclosing, &text),
+            text,
+            literal: literal_start..literal_end,
+        });
+    }
+    found
+}
+
+/// `hardcoded_strings` for every file of `sources`, with suggested keys made
+/// unique: a key suggested for different texts gets a number from the second on
+pub fn extract<'a>(
+    sources: &'a SourceSet,
+    functions: &[String],
+) -> Vec<(&'a Path, HardcodedString)> {
+    let mut texts_by_key: HashMap<String, Vec<String>> = HashMap::new();
+    let mut found = Vec::new();
+    for file in &sources.files {
+        for mut string in hardcoded_strings(&file.content, functions) {
+            let texts = texts_by_key
+                .entry(string.suggested_key.clone())
+                .or_default();
+            let index = match texts.iter().position(|t| *t == string.text) {
+                Some(index) => index,
+                None => {
+                    texts.push(string.text.clone());
+                    texts.len() - 1
+                }
+            };
+            if index > 0 {
+                string.suggested_key = format!("{}{}", string.suggested_key, index + 1);
+            }
+            found.push((file.path.as_path(), string));
+        }
+    }
+    found
+}
+
+/// The name of the top-level definition `offset` of `source` is in
+pub fn enclosing_definition(source: &str, offset: usize) -> Option<String> {
+    let code = blank_comments(source);
+    enclosing(&definitions(&code), offset).map(|d| d.name.to_string())
+}
+
+/// Whether `variable` has a value at `offset` of `source`, as far as can be told
+/// without type checking: an argument of the top-level definition around it, a
+/// `let` or lambda binding earlier in that definition, or a top-level value
+pub fn in_scope(source: &str, offset: usize, variable: &str) -> bool {
+    let code = blank_strings(&blank_comments(source));
+    let definitions = definitions(&code);
+    if definitions
+        .iter()
+        .any(|d| d.name == variable && d.arguments.trim().is_empty())
+    {
+        return true;
+    }
+    let Some(definition) = enclosing(&definitions, offset) else {
+        return false;
+    };
+
+    let variable = regex::escape(variable);
+    // The name on its own, not as a field (`model.t`) or part of a longer name
+    let binds = Regex::new(&format!(r"(?:^|[^\w.]){}(?:\W|$)", variable)).unwrap();
+    let let_binding = Regex::new(&format!(
+        r"(?m)^[ \t]+(?:{}\s*(?::|=[^=])|\{{[^}}\n]*\b{}\b[^}}\n]*\}}\s*=[^=])",
+        variable, variable
+    ))
+    .unwrap();
+    let lambda = Regex::new(r"\\([^\n\\]*?)->").unwrap();
+
+    let body = &code[definition.body_start..offset.max(definition.body_start)];
+    binds.is_match(definition.arguments)
+        || let_binding.is_match(body)
+        || lambda.captures_iter(body).any(|c| binds.is_match(&c[1]))
+}
+
+/// A top-level definition, found by its `name arguments =` line
+struct Definition<'a> {
+    start: usize,
+    /// Offset just past the `=`
+    body_start: usize,
+    name: &'a str,
+    arguments: &'a str,
+}
+
+fn definitions(code: &str) -> Vec<Definition<'_>> {
+    let definition = Regex::new(r"(?m)^([a-z][\w]*)\b([^\n:=]*)=").unwrap();
+    definition
+        .captures_iter(code)
+        .filter(|c| !matches!(&c[1], "type" | "port"))
+        .map(|c| Definition {
+            start: c.get(0).unwrap().start(),
+            body_start: c.get(0).unwrap().end(),
+            name: c.get(1).unwrap().as_str(),
+            arguments: c.get(2).unwrap().as_str(),
+        })
+        .collect()
+}
+
+fn enclosing<'d, 'a>(
+    definitions: &'d [Definition<'a>],
+    offset: usize,
+) -> Option<&'d Definition<'a>> {
+    definitions.iter().take_while(|d| d.start < offset).last()
+}
+
+fn last_segment(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Whether a literal is text for people rather than a URL, a class name or punctuation
+fn looks_like_copy(text: &str) -> bool {
+    let text = text.trim();
+    if text.chars().count() < 2 || !text.chars().any(char::is_alphabetic) {
+        return false;
+    }
+    let lowercase = text.to_lowercase();
+    let url_like = [
+        "http://", "https://", "mailto:", "tel:", "www.", "/", "./", "#",
+    ]
+    .iter()
+    .any(|prefix| lowercase.starts_with(prefix));
+    // `btn-primary`, `user_id`: one lowercase word joined by dashes or underscores
+    let identifier_like = text.contains(['-', '_'])
+        && text
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    !url_like && !identifier_like
+}
+
+fn suggest_key(module: Option<&str>, function: Option<&str>, text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(KEY_WORDS)
+        .map(capitalize)
+        .collect();
+    let prefix = match (module, function) {
+        (Some(module), _) if module != "Main" => lower_first(module),
+        (_, Some(function)) => function.to_string(),
+        (Some(module), None) => lower_first(module),
+        (None, None) => "copy".to_string(),
+    };
+    if words.is_empty() {
+        // Text with no ASCII words, such as "保存": name it after where it is
+        match function {
+            Some(function) if prefix != function => format!("{}{}", prefix, capitalize(function)),
+            _ => format!("{}Text", prefix),
+        }
+    } else {
+        format!("{}{}", prefix, words.concat())
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn lower_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_copy_passed_to_the_functions() {
+        let source = r#"module Page.Settings exposing (view)
+
+import Html exposing (..)
+import Html.Attributes as Attr
+
+
+view : Model -> Html Msg
+view model =
+    div [ Attr.class "settings-page", Attr.title "Your settings" ]
+        [ h1 [] [ Html.text "Save changes" ]
+        , input [ Attr.placeholder "Search…", Attr.alt "" ] []
+        , a [ href "https://example.com" ] [ text <| "Learn more about \"plans\"" ]
+        , text "x"
+        , text "btn-primary"
+        -- text "Commented out"
+        , p [] [ text "see text \"inside\" a string", text (String.fromInt model.count) ]
+        -- elm-i18n:ignore-next-line
+        , text "Debug only"
+        , text "保存"
+        ]
+"#;
+        let functions: Vec<String> = DEFAULT_FUNCTIONS.iter().map(|f| f.to_string()).collect();
+        let found: Vec<(usize, String, String, String)> = hardcoded_strings(source, &functions)
+            .into_iter()
+            .map(|s| (s.line, s.function, s.text, s.suggested_key))
+            .collect();
+        let row = |line, function: &str, text: &str, key: &str| {
+            (
+                line,
+                function.to_string(),
+                text.to_string(),
+                key.to_string(),
+            )
+        };
+        assert_eq!(
+            found,
+            vec![
+                row(9, "Attr.title", "Your settings", "settingsYourSettings"),
+                row(10, "Html.text", "Save changes", "settingsSaveChanges"),
+                row(11, "Attr.placeholder", "Search…", "settingsSearch"),
+                row(
+                    12,
+                    "text",
+                    "Learn more about \"plans\"",
+                    "settingsLearnMoreAboutPlans"
+                ),
+                row(
+                    16,
+                    "text",
+                    "see text \"inside\" a string",
+                    "settingsSeeTextInsideA"
+                ),
+                row(19, "text", "保存", "settingsView"),
+            ]
+        );
+
+        let custom = hardcoded_strings(source, &["Ui.label".to_string()]);
+        assert!(custom.is_empty());
+        let main = hardcoded_strings(
+            "module Main exposing (main)\n\nmain =\n    text \"Hello\"\n",
+            &functions,
+        );
+        assert_eq!(main[0].suggested_key, "mainHello");
+    }
+
+    #[test]
+    fn tells_whether_translations_are_in_scope() {
+        let source = r#"module Page.Settings exposing (view)
+
+t =
+    I18n.translations I18n.En
+
+
+view : Translations -> Model -> Html Msg
+view translations model =
+    let
+        { lang } =
+            model
+
+        label : Translations
+        label =
+            translations
+    in
+    div [] [ text "Save changes", List.map (\item -> text "Item") model.items ]
+
+
+footer model =
+    p [] [ text "All rights reserved -- model.translations" ]
+"#;
+        let functions = vec!["text".to_string()];
+        let strings = hardcoded_strings(source, &functions);
+        let save = &strings[0];
+        assert_eq!(&source[save.literal.clone()], "\"Save changes\"");
+        let item = strings[1].literal.start;
+        let footer = strings[2].literal.start;
+
+        assert!(in_scope(source, save.literal.start, "translations"));
+        assert!(in_scope(source, save.literal.start, "label"));
+        assert!(in_scope(source, save.literal.start, "lang"));
+        assert!(in_scope(source, item, "item"));
+        assert!(in_scope(source, footer, "t"));
+        assert!(in_scope(source, footer, "model"));
+        assert!(!in_scope(source, save.literal.start, "item"));
+        assert!(!in_scope(source, footer, "translations"));
+        assert_eq!(
+            enclosing_definition(source, footer).as_deref(),
+            Some("footer")
+        );
+    }
+}
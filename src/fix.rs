@@ -0,0 +1,615 @@
+use anyhow::Result;
+
+use crate::escaping;
+use crate::generator::{find_last_field_line, insert_record_field};
+use crate::lint::{
+    comma_style_problems, field_order_problems, missing_record_fields, trailing_whitespace_lines,
+};
+use crate::parser::{
+    duplicate_records, function_arity, line_list, parse_i18n_content_with_record_name,
+};
+use crate::timing::{self, Phase};
+use crate::types::RecordField;
+
+/// Result of applying the mechanical lint fixes to a file
+#[derive(Debug)]
+pub struct FixOutcome {
+    pub content: String,
+    /// Human-readable description of each fix, in the order applied
+    pub fixes: Vec<String>,
+}
+
+/// Apply every safe, mechanical lint fix to I18n source
+///
+/// Only fixes that can't change what the translations mean are applied: trailing
+/// whitespace, leading comma style, fields missing from a record (inserted with
+/// the TODO marker, unless they are non-String constants) and record field order.
+/// Everything else is left for a human.
+pub fn fix_source(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+    todo_marker: &str,
+) -> Result<FixOutcome> {
+    let _edit = timing::phase(Phase::Edit);
+    let has_trailing_newline = source.ends_with('\n');
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let mut fixes = Vec::new();
+
+    let whitespace_lines = trailing_whitespace_lines(source);
+    for &i in &whitespace_lines {
+        lines[i] = lines[i].trim_end().to_string();
+    }
+    if !whitespace_lines.is_empty() {
+        fixes.push(format!(
+            "trailing whitespace on {} line{}",
+            whitespace_lines.len(),
+            if whitespace_lines.len() == 1 { "" } else { "s" }
+        ));
+    }
+
+    let content = join_lines(&lines, has_trailing_newline);
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let line_refs: Vec<&str> = content.lines().collect();
+    let comma_problems = comma_style_problems(&parse_result, &line_refs);
+    for problem in &comma_problems {
+        if let Some(comma_line) = problem.trailing_comma_line {
+            let without_comma = lines[comma_line].trim_end().trim_end_matches(',');
+            lines[comma_line] = without_comma.trim_end().to_string();
+        }
+        lines[problem.line] = problem.expected.clone();
+        fixes.push(format!(
+            "comma style of `{}` in {} (line {})",
+            problem.key,
+            block_name(&problem.language),
+            problem.line + 1
+        ));
+    }
+
+    let content = join_lines(&lines, has_trailing_newline);
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let missing = missing_record_fields(&parse_result);
+
+    // Insert from the bottom of the file up so earlier line numbers stay valid
+    let mut bounds = parse_result.lang_bounds.clone();
+    bounds.sort_by_key(|b| std::cmp::Reverse(b.1));
+    for (lang, start, end) in &bounds {
+        let has_fields = parse_result
+            .record_fields
+            .get(lang)
+            .is_some_and(|fields| !fields.is_empty());
+        if !has_fields {
+            // Without an existing field there is no `{ first` line to append after
+            continue;
+        }
+
+        // Inserting each field right after the last one, in reverse, keeps type order
+        let insertion_line = find_last_field_line(&lines, *start, *end);
+        // A non-String constant (e.g. `Int`) has no placeholder, so it's left for a human
+        let record_missing: Vec<&String> = missing
+            .iter()
+            .filter(|(l, key)| {
+                l == lang
+                    && parse_result
+                        .translations
+                        .get(key)
+                        .is_none_or(|t| t.is_function || t.type_signature.is_none())
+            })
+            .map(|(_, key)| key)
+            .collect();
+        for key in record_missing.iter().rev().copied() {
+            let signature = parse_result
+                .translations
+                .get(key)
+                .and_then(|t| t.type_signature.clone());
+            match &signature {
+                Some(signature) => insert_record_field(
+                    &mut lines,
+                    insertion_line,
+                    key,
+                    &todo_function(signature, todo_marker),
+                    true,
+                ),
+                None => insert_record_field(&mut lines, insertion_line, key, todo_marker, false),
+            }
+        }
+        for key in record_missing {
+            fixes.push(format!(
+                "`{}` missing from the {} record (added with a TODO value)",
+                key,
+                lang.to_uppercase()
+            ));
+        }
+    }
+
+    let content = join_lines(&lines, has_trailing_newline);
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let type_order: Vec<&str> = parse_result
+        .type_fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    let mut misordered: Vec<_> = field_order_problems(&parse_result)
+        .into_iter()
+        .filter_map(|problem| {
+            parse_result
+                .lang_bounds
+                .iter()
+                .find(|(lang, _, _)| *lang == problem.language)
+                .cloned()
+        })
+        .collect();
+    misordered.sort_by_key(|b| std::cmp::Reverse(b.1));
+    let mut reordered = Vec::new();
+    for (lang, start, end) in &misordered {
+        let fields = &parse_result.record_fields[lang];
+        if reorder_record(&mut lines, *start, *end, fields, &type_order) {
+            reordered.push(lang.clone());
+        }
+    }
+    for lang in reordered.iter().rev() {
+        fixes.push(format!(
+            "field order of the {} record (now follows the type)",
+            lang.to_uppercase()
+        ));
+    }
+
+    Ok(FixOutcome {
+        content: join_lines(&lines, has_trailing_newline),
+        fixes,
+    })
+}
+
+/// Rewrite a record so its fields follow `type_order`
+///
+/// Each field moves together with its multi-line body and the comment lines
+/// directly above it. Fields the type doesn't know keep their relative order at
+/// the end. Returns false (and leaves the record alone) when its layout isn't
+/// the `{ first` / `, rest` / `}` shape this can safely rearrange.
+pub fn reorder_record(
+    lines: &mut Vec<String>,
+    start: usize,
+    end: usize,
+    fields: &[RecordField],
+    type_order: &[&str],
+) -> bool {
+    let first = match fields.first() {
+        Some(first) => first,
+        None => return false,
+    };
+    let brace_line = first.line;
+    if !lines[brace_line].trim_start().starts_with('{') || !lines[end].trim_start().starts_with('}')
+    {
+        return false;
+    }
+    let brace_column = lines[brace_line].find('{').unwrap_or(0);
+
+    let is_comment = |line: &str| line.trim_start().starts_with("--");
+    let comments_above = |line: usize, floor: usize| {
+        let mut top = line;
+        while top > floor && is_comment(&lines[top - 1]) {
+            top -= 1;
+        }
+        top
+    };
+
+    // Comment or blank lines right before `}` stay where they are
+    let mut tail_start = end;
+    while tail_start > brace_line + 1 && {
+        let line = lines[tail_start - 1].trim();
+        line.is_empty() || line.starts_with("--")
+    } {
+        tail_start -= 1;
+    }
+
+    // (field name, comment lines, field lines)
+    let mut chunks: Vec<(&str, Vec<String>, Vec<String>)> = Vec::new();
+    let region_start = comments_above(brace_line, start + 1);
+    for (i, field) in fields.iter().enumerate() {
+        let attach_start = if i == 0 {
+            region_start
+        } else {
+            comments_above(field.line, fields[i - 1].line + 1)
+        };
+        let code_end = match fields.get(i + 1) {
+            Some(next) => comments_above(next.line, field.line + 1),
+            None => tail_start,
+        };
+        if code_end <= field.line {
+            return false;
+        }
+
+        let mut code: Vec<String> = lines[field.line..code_end].to_vec();
+        while code.len() > 1 && code.last().is_some_and(|l| l.trim().is_empty()) {
+            code.pop();
+        }
+        let first_line = code[0].trim_start();
+        code[0] = first_line
+            .strip_prefix(['{', ','])
+            .unwrap_or(first_line)
+            .trim_start()
+            .to_string();
+
+        let comments = if i == 0 {
+            lines[region_start..brace_line].to_vec()
+        } else {
+            lines[attach_start..field.line].to_vec()
+        };
+        chunks.push((field.name.as_str(), comments, code));
+    }
+
+    let position = |name: &str| {
+        type_order
+            .iter()
+            .position(|t| *t == name)
+            .unwrap_or(usize::MAX)
+    };
+    chunks.sort_by_key(|(name, _, _)| position(name));
+
+    let indent = " ".repeat(brace_column);
+    let mut rewritten = Vec::new();
+    for (i, (_, comments, code)) in chunks.into_iter().enumerate() {
+        rewritten.extend(comments);
+        let leader = if i == 0 { "{" } else { "," };
+        rewritten.push(format!("{}{} {}", indent, leader, code[0]));
+        rewritten.extend(code.into_iter().skip(1));
+    }
+
+    lines.splice(region_start..tail_start, rewritten);
+    true
+}
+
+/// A placeholder function body that compiles for the given type signature
+fn todo_function(signature: &str, todo_marker: &str) -> String {
+    let arguments = function_arity(signature);
+    format!(
+        "\\{} -> \"{}\"",
+        vec!["_"; arguments].join(" "),
+        escaping::escape(todo_marker)
+    )
+}
+
+fn block_name(language: &Option<String>) -> String {
+    match language {
+        Some(lang) => format!("the {} record", lang.to_uppercase()),
+        None => "the type".to_string(),
+    }
+}
+
+fn join_lines(lines: &[String], trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+/// Merge each language record defined more than once into its first definition
+///
+/// The merged record keeps the first definition's field order. A field defined
+/// again takes its last definition's value, and fields only the later definitions
+/// have are appended. The later definitions are removed. None when no record is
+/// defined twice.
+pub fn merge_duplicate_records(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Option<FixOutcome> {
+    let duplicates = duplicate_records(source, record_name, languages);
+    if duplicates.is_empty() {
+        return None;
+    }
+    let _edit = timing::phase(Phase::Edit);
+    let lines: Vec<&str> = source.lines().collect();
+    // (first line, last line, what replaces them)
+    let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
+    let mut fixes = Vec::new();
+
+    for duplicate in &duplicates {
+        // Each field's lines, from `name = ...` to the line before the next field
+        let mut merged: Vec<(&str, Vec<String>)> = Vec::new();
+        for definition in &duplicate.definitions {
+            for (i, field) in definition.fields.iter().enumerate() {
+                let next = definition
+                    .fields
+                    .get(i + 1)
+                    .map_or(definition.end, |f| f.line);
+                let mut body: Vec<String> = lines[field.line..next.max(field.line + 1)]
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect();
+                body[0] = body[0]
+                    .trim_start()
+                    .trim_start_matches(['{', ','])
+                    .trim_start()
+                    .to_string();
+                while body.len() > 1 && body.last().is_some_and(|l| l.trim().is_empty()) {
+                    body.pop();
+                }
+                // A trailing comma separated it from the next field
+                if let Some(last) = body.last_mut() {
+                    *last = last.trim_end().trim_end_matches(',').trim_end().to_string();
+                }
+                match merged.iter_mut().find(|(name, _)| *name == field.name) {
+                    Some(existing) => existing.1 = body,
+                    None => merged.push((&field.name, body)),
+                }
+            }
+        }
+
+        let first = &duplicate.definitions[0];
+        let brace = (first.start..=first.end)
+            .find(|&i| lines[i].contains('{'))
+            .unwrap_or(first.end);
+        let mut record: Vec<String> = lines[first.start..brace]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let before_brace = &lines[brace][..lines[brace].find('{').unwrap_or(0)];
+        let indent = if before_brace.trim().is_empty() {
+            before_brace.to_string()
+        } else {
+            // `translationsFr = {`
+            record.push(before_brace.trim_end().to_string());
+            "    ".to_string()
+        };
+        for (i, (_, body)) in merged.iter().enumerate() {
+            let separator = if i == 0 { "{ " } else { ", " };
+            record.push(format!("{}{}{}", indent, separator, body[0]));
+            record.extend(body[1..].iter().cloned());
+        }
+        record.push(if merged.is_empty() {
+            format!("{}{{}}", indent)
+        } else {
+            format!("{}}}", indent)
+        });
+        replacements.push((first.start, first.end, record));
+
+        for later in &duplicate.definitions[1..] {
+            // Along with the blank lines after it (or before it, at the end of the
+            // file), so the gap it leaves is the usual one
+            let (mut start, mut end) = (later.start, later.end);
+            while end + 1 < lines.len() && lines[end + 1].trim().is_empty() {
+                end += 1;
+            }
+            if end + 1 == lines.len() {
+                while start > 0 && lines[start - 1].trim().is_empty() {
+                    start -= 1;
+                }
+            }
+            replacements.push((start, end, Vec::new()));
+        }
+        let starts: Vec<usize> = duplicate.definitions.iter().map(|d| d.start + 1).collect();
+        fixes.push(format!(
+            "the {} definitions of `{}` (lines {}) merged into one",
+            duplicate.definitions.len(),
+            duplicate.name,
+            line_list(&starts)
+        ));
+    }
+
+    // From the bottom of the file up so earlier line numbers stay valid
+    let mut lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    replacements.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    for (start, end, replacement) in replacements {
+        lines.splice(start..=end, replacement);
+    }
+    Some(FixOutcome {
+        content: join_lines(&lines, source.ends_with('\n')),
+        fixes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn languages() -> Vec<String> {
+        vec!["en".to_string(), "fr".to_string()]
+    }
+
+    #[test]
+    fn merges_a_record_defined_twice() {
+        let source = "module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    , cancel = \"Cancel\"
+    , itemCount = \\n -> String.fromInt n ++ \" items\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Sauver\",
+      cancel = \"Annuler\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" éléments\"
+    }
+";
+        assert!(parse_i18n_content_with_record_name(source, "Translations", &languages()).is_err());
+
+        let outcome = merge_duplicate_records(source, "Translations", &languages()).unwrap();
+        assert_eq!(
+            outcome.fixes,
+            vec!["the 2 definitions of `translationsFr` (lines 19 and 26) merged into one"]
+        );
+        assert!(outcome.content.ends_with(
+            "translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , cancel = \"Annuler\"
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" éléments\"
+    }
+"
+        ));
+        let parse_result =
+            parse_i18n_content_with_record_name(&outcome.content, "Translations", &languages())
+                .unwrap();
+        assert_eq!(
+            parse_result.translations["cancel"].values["fr"],
+            "\"Annuler\""
+        );
+
+        assert!(merge_duplicate_records(&outcome.content, "Translations", &languages()).is_none());
+    }
+
+    const MESSY: &str = "module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String   
+    ,itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\",
+      cancel = \"Cancel\"
+    , itemCount = \\n -> String.fromInt n ++ \" items\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    }
+";
+
+    #[test]
+    fn applies_mechanical_fixes() {
+        let outcome = fix_source(MESSY, "Translations", &languages(), "TODO").unwrap();
+
+        assert_eq!(
+            outcome.content,
+            "module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    , cancel = \"Cancel\"
+    , itemCount = \\n -> String.fromInt n ++ \" items\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , cancel = \"TODO\"
+    , itemCount = \\_ -> \"TODO\"
+    }
+"
+        );
+        assert_eq!(outcome.fixes.len(), 5);
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let once = fix_source(MESSY, "Translations", &languages(), "TODO").unwrap();
+        let twice = fix_source(&once.content, "Translations", &languages(), "TODO").unwrap();
+
+        assert_eq!(twice.content, once.content);
+        assert!(
+            twice.fixes.is_empty(),
+            "unexpected fixes: {:?}",
+            twice.fixes
+        );
+    }
+
+    #[test]
+    fn counts_function_arity_at_the_top_level() {
+        assert_eq!(todo_function("Int -> String", "TODO"), "\\_ -> \"TODO\"");
+        assert_eq!(
+            todo_function("(Int -> String) -> { a : Int } -> String", "TODO"),
+            "\\_ _ -> \"TODO\""
+        );
+    }
+
+    #[test]
+    fn reorders_records_to_follow_the_type() {
+        let source = "type alias Translations =
+    { save : String
+    , itemCount : Int -> String
+    , cancel : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    -- i18n-max-length: 8
+    { cancel = \"Cancel\"
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" items\"
+
+    -- Shown on the main button
+    , save = \"Save\"
+    }
+";
+
+        let outcome = fix_source(source, "Translations", &["en".to_string()], "TODO").unwrap();
+
+        assert_eq!(
+            outcome.content,
+            "type alias Translations =
+    { save : String
+    , itemCount : Int -> String
+    , cancel : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    -- Shown on the main button
+    { save = \"Save\"
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" items\"
+    -- i18n-max-length: 8
+    , cancel = \"Cancel\"
+    }
+"
+        );
+        assert_eq!(
+            outcome.fixes,
+            vec!["field order of the EN record (now follows the type)"]
+        );
+
+        let again = fix_source(
+            &outcome.content,
+            "Translations",
+            &["en".to_string()],
+            "TODO",
+        )
+        .unwrap();
+        assert_eq!(again.content, outcome.content);
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long elm-format may take before it is killed
+pub const FORMAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run source through `elm-format --stdin` and return the formatted module
+pub fn format_source(elm_format: &Path, source: &str, timeout: Duration) -> Result<String> {
+    let mut child = Command::new(elm_format)
+        .args(["--stdin", "--elm-version=0.19"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", elm_format.display()))?;
+
+    // Feed stdin and drain the pipes on threads so a large module can't deadlock
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = source.to_string();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let error_reader = thread::spawn(move || {
+        let mut output = String::new();
+        stderr.read_to_string(&mut output).map(|_| output)
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "{} didn't finish within {} seconds",
+                elm_format.display(),
+                timeout.as_secs()
+            );
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    // elm-format may exit before reading everything (e.g., on a syntax error)
+    let _ = writer.join();
+    let formatted = reader.join().expect("stdout reader panicked")?;
+    let errors = error_reader.join().expect("stderr reader panicked")?;
+
+    if !status.success() {
+        let message = errors.trim();
+        bail!(
+            "{} failed{}",
+            elm_format.display(),
+            if message.is_empty() {
+                String::new()
+            } else {
+                format!(":\n{}", message)
+            }
+        );
+    }
+    if formatted.trim().is_empty() {
+        bail!("{} produced no output", elm_format.display());
+    }
+
+    Ok(formatted)
+}
+
+/// Format a file in place; the file is left untouched if elm-format fails
+///
+/// Returns whether formatting changed anything.
+pub fn format_file(elm_format: &Path, file: &Path, timeout: Duration) -> Result<bool> {
+    let source =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let formatted = format_source(elm_format, &source, timeout)?;
+    if formatted == source {
+        return Ok(false);
+    }
+    fs::write(file, formatted).with_context(|| format!("Failed to write {}", file.display()))?;
+    Ok(true)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn script(dir: &Path, body: &str) -> PathBuf {
+        let path = dir.join("elm-format");
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn formats_files_and_keeps_them_on_failure() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("I18n.elm");
+        fs::write(&file, "module I18n exposing (..)\n\n\n\n").unwrap();
+
+        let squeeze = script(dir.path(), "cat -s");
+        assert!(format_file(&squeeze, &file, FORMAT_TIMEOUT).unwrap());
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "module I18n exposing (..)\n\n"
+        );
+
+        let failing = script(
+            dir.path(),
+            "cat > /dev/null; echo 'SYNTAX PROBLEM' >&2; exit 1",
+        );
+        let error = format_file(&failing, &file, FORMAT_TIMEOUT).unwrap_err();
+        assert!(
+            error.to_string().ends_with(":\nSYNTAX PROBLEM"),
+            "{}",
+            error
+        );
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "module I18n exposing (..)\n\n"
+        );
+    }
+
+    #[test]
+    fn gives_up_after_the_timeout() {
+        let dir = TempDir::new().unwrap();
+        let slow = script(dir.path(), "sleep 5");
+
+        let error = format_source(
+            &slow,
+            "module I18n exposing (..)\n",
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("didn't finish"), "{}", error);
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::Result;
+
+use crate::lock::{content_hash, parse_hashes, render_hashes, Lock, LockEntry};
+use crate::types::ParseResult;
+
+pub const FRESHNESS_FILE: &str = "elm-i18n.freshness";
+
+const FRESHNESS_HEADER: &str =
+    "# elm-i18n freshness: one `file key language hash` line per translated value,
+# the hash of the source-language value it was written against.
+# Kept up to date by edits; `elm-i18n lint --stale` lists the values to review.
+";
+
+/// The hash of a key's source-language value when each translation of it was last
+/// written, by file, key and translated language
+pub type Freshness = Lock;
+
+pub fn render_freshness(freshness: &Freshness) -> String {
+    render_hashes(FRESHNESS_HEADER, freshness)
+}
+
+pub fn parse_freshness(content: &str) -> Result<Freshness> {
+    parse_hashes(content, FRESHNESS_FILE)
+}
+
+fn entry(file: &str, key: &str, language: &str) -> LockEntry {
+    LockEntry {
+        file: file.to_string(),
+        key: key.to_string(),
+        language: language.to_string(),
+    }
+}
+
+/// The value of `key` in `lang`, if it has one
+fn value<'a>(parse_result: &'a ParseResult, key: &str, lang: &str) -> Option<&'a str> {
+    parse_result
+        .translations
+        .get(key)
+        .and_then(|t| t.values.get(lang))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+}
+
+/// Bring the records of `file` up to date after an edit from `before` to `after`
+///
+/// A translation written by the edit, or not recorded yet, is recorded against the
+/// current source value (the first of `languages`); one the edit left alone keeps
+/// its record, so changing only the source makes it stale. Records of keys and
+/// languages that are gone are dropped.
+pub fn refresh(
+    freshness: &mut Freshness,
+    file: &str,
+    before: Option<&ParseResult>,
+    after: &ParseResult,
+    languages: &[String],
+) {
+    let Some((source, targets)) = languages.split_first() else {
+        return;
+    };
+    freshness.retain(|recorded, _| {
+        recorded.file != file
+            || (targets.contains(&recorded.language)
+                && value(after, &recorded.key, &recorded.language).is_some())
+    });
+
+    for key in after.translations.keys() {
+        let Some(source_value) = value(after, key, source) else {
+            continue;
+        };
+        for lang in targets {
+            let Some(translated) = value(after, key, lang) else {
+                continue;
+            };
+            let written = before.is_none_or(|before| value(before, key, lang) != Some(translated));
+            let recorded = entry(file, key, lang);
+            if written || !freshness.contains_key(&recorded) {
+                freshness.insert(recorded, content_hash(source_value));
+            }
+        }
+    }
+}
+
+/// Record the translations of `keys` (every key when empty) in `langs` as written
+/// against the current source value, as after a review; returns how many changed
+pub fn mark_reviewed(
+    freshness: &mut Freshness,
+    file: &str,
+    parse_result: &ParseResult,
+    keys: &[String],
+    langs: &[String],
+    languages: &[String],
+) -> usize {
+    let Some((source, targets)) = languages.split_first() else {
+        return 0;
+    };
+    let mut marked = 0;
+    for key in parse_result.translations.keys() {
+        if !keys.is_empty() && !keys.contains(key) {
+            continue;
+        }
+        let Some(source_value) = value(parse_result, key, source) else {
+            continue;
+        };
+        for lang in targets
+            .iter()
+            .filter(|l| langs.is_empty() || langs.contains(l))
+        {
+            if value(parse_result, key, lang).is_none() {
+                continue;
+            }
+            let hash = content_hash(source_value);
+            if freshness.insert(entry(file, key, lang), hash.clone()) != Some(hash) {
+                marked += 1;
+            }
+        }
+    }
+    marked
+}
+
+/// The (key, language) pairs of `file` whose source value changed since the
+/// translation was written, sorted
+pub fn stale_values(
+    freshness: &Freshness,
+    file: &str,
+    parse_result: &ParseResult,
+    languages: &[String],
+) -> Vec<(String, String)> {
+    let Some(source) = languages.first() else {
+        return Vec::new();
+    };
+    freshness
+        .iter()
+        .filter(|(recorded, _)| recorded.file == file)
+        .filter(|(recorded, hash)| {
+            value(parse_result, &recorded.key, source)
+                .is_some_and(|current| content_hash(current) != **hash)
+        })
+        .map(|(recorded, _)| (recorded.key.clone(), recorded.language.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    fn module(save: (&str, &str), cancel: (&str, &str)) -> String {
+        format!(
+            "module I18n exposing (..)\n\ntype alias Translations =\n    {{ save : String\n    , cancel : String\n    }}\n\ntranslationsEn : Translations\ntranslationsEn =\n    {{ save = \"{}\"\n    , cancel = \"{}\"\n    }}\n\ntranslationsFr : Translations\ntranslationsFr =\n    {{ save = \"{}\"\n    , cancel = \"{}\"\n    }}\n",
+            save.0, cancel.0, save.1, cancel.1
+        )
+    }
+
+    #[test]
+    fn marks_translations_stale_when_only_the_source_changes() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse = |source: &str| {
+            parse_i18n_content_with_record_name(source, "Translations", &languages).unwrap()
+        };
+        let file = "src/I18n.elm";
+        let original = parse(&module(("Save", "Sauver"), ("Cancel", "Annuler")));
+        let mut freshness = Freshness::new();
+        refresh(&mut freshness, file, None, &original, &languages);
+        assert_eq!(freshness.len(), 2);
+
+        // English copy changes for both keys; only cancel's French is rewritten
+        let edited = parse(&module(("Save now", "Sauver"), ("Stop", "Arrêter")));
+        refresh(&mut freshness, file, Some(&original), &edited, &languages);
+        assert_eq!(
+            stale_values(&freshness, file, &edited, &languages),
+            vec![("save".to_string(), "fr".to_string())]
+        );
+        assert_eq!(
+            parse_freshness(&render_freshness(&freshness)).unwrap(),
+            freshness
+        );
+
+        assert_eq!(
+            mark_reviewed(&mut freshness, file, &edited, &[], &[], &languages),
+            1
+        );
+        assert!(stale_values(&freshness, file, &edited, &languages).is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use regex::Regex;
+
+/// The comment line a block `generate <name>` writes starts with; what's between it
+/// and the end marker belongs to elm-i18n and is rewritten on each run
+pub fn begin_marker(name: &str) -> String {
+    format!("-- elm-i18n:begin {}", name)
+}
+
+pub fn end_marker(name: &str) -> String {
+    format!("-- elm-i18n:end {}", name)
+}
+
+/// The `name` block of `source`, markers included, if it has one. The outer None
+/// is for no block, the inner one for a begin marker without an end marker.
+pub fn block<'a>(source: &'a str, name: &str) -> Option<Option<&'a str>> {
+    span(source, name).map(|span| span.map(|(begin, end)| &source[begin..end]))
+}
+
+fn span(source: &str, name: &str) -> Option<Option<(usize, usize)>> {
+    let begin = source.find(&begin_marker(name))?;
+    let end_marker = end_marker(name);
+    Some(
+        source[begin..]
+            .find(&end_marker)
+            .map(|end| (begin, begin + end + end_marker.len())),
+    )
+}
+
+/// `source` with its `name` block replaced by `block`, or with `block` appended
+/// when it has none; None when it has a begin marker without an end one
+pub fn upsert_block(source: &str, name: &str, block: &str) -> Option<String> {
+    match span(source, name) {
+        None => {
+            let mut content = source.trim_end().to_string();
+            content.push_str("\n\n\n");
+            content.push_str(block);
+            content.push('\n');
+            Some(content)
+        }
+        Some(None) => None,
+        Some(Some((begin, end))) => {
+            Some(format!("{}{}{}", &source[..begin], block, &source[end..]))
+        }
+    }
+}
+
+pub fn has_block(source: &str, name: &str) -> bool {
+    source.contains(&begin_marker(name))
+}
+
+/// `source` importing `module`: after the last import, or below the module
+/// declaration and its doc comment when there are none
+pub fn add_import(source: &str, module: &str) -> String {
+    let imported = Regex::new(&format!(r"(?m)^import\s+{}\b", regex::escape(module))).unwrap();
+    if imported.is_match(source) {
+        return source.to_string();
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    let import = format!("import {}", module);
+    let mut result: Vec<&str> = lines.clone();
+    if let Some(last) = lines.iter().rposition(|line| line.starts_with("import ")) {
+        // An import's exposing list can go on over indented lines
+        let end = last
+            + 1
+            + lines[last + 1..]
+                .iter()
+                .take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+                .count();
+        result.insert(end, &import);
+    } else {
+        let Some(module_line) = lines
+            .iter()
+            .position(|line| line.contains("module ") && line.contains("exposing"))
+        else {
+            return source.to_string();
+        };
+        let mut end = module_line
+            + 1
+            + lines[module_line + 1..]
+                .iter()
+                .take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty())
+                .count();
+        let doc_comment = lines[end..].iter().position(|line| !line.trim().is_empty());
+        if let Some(offset) = doc_comment.filter(|&o| lines[end + o].starts_with("{-|")) {
+            let start = end + offset;
+            let close = lines[start..]
+                .iter()
+                .position(|line| line.contains("-}"))
+                .map_or(lines.len() - 1, |o| start + o);
+            end = close + 1;
+        }
+        result.splice(end..end, ["", &import]);
+    }
+    let mut content = result.join("\n");
+    if source.ends_with('\n') {
+        content.push('\n');
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_a_module_once_below_the_others() {
+        let with_imports = "module I18n exposing (..)\n\nimport Dict\nimport Html exposing\n    ( text\n    )\n\n\ntype Language\n    = EN\n";
+        let added = add_import(with_imports, "Time");
+        assert_eq!(
+            added,
+            "module I18n exposing (..)\n\nimport Dict\nimport Html exposing\n    ( text\n    )\nimport Time\n\n\ntype Language\n    = EN\n"
+        );
+        assert_eq!(add_import(&added, "Time"), added);
+
+        let documented =
+            "module I18n exposing (..)\n\n{-| Translations\n-}\n\n\ntype Language\n    = EN\n";
+        assert_eq!(
+            add_import(documented, "Time"),
+            "module I18n exposing (..)\n\n{-| Translations\n-}\n\nimport Time\n\n\ntype Language\n    = EN\n"
+        );
+    }
+}
@@ -1,13 +1,51 @@
 use anyhow::{Context, Result};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-use crate::parser::parse_i18n_file_with_record_name;
+use crate::errors::Coded;
+use crate::escaping;
+use crate::parser::{
+    comment_free, is_comment_only, parse_i18n_content_with_record_name, read_elm_file,
+};
+use crate::timing::{self, Phase};
 use crate::types::Translation;
 
+/// Spaces before the `{`, `,` and `}` of fields this tool writes, unless `--indent`
+/// or "indent" in the config says otherwise
+pub const DEFAULT_INDENT: usize = 4;
+
+thread_local! {
+    static INDENT: Cell<usize> = const { Cell::new(DEFAULT_INDENT) };
+}
+
+/// Write new fields, and the blocks of `format` and `init`, at `width` spaces
+pub fn set_indent(width: usize) {
+    INDENT.with(|indent| indent.set(width));
+}
+
+/// The indentation width new content is written with
+pub fn indent_width() -> usize {
+    INDENT.with(Cell::get)
+}
+
+pub fn indent() -> String {
+    " ".repeat(indent_width())
+}
+
+/// Where `add --before` or `--after` puts a new field: next to another key, in
+/// the type and in every record, instead of last
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    Before(String),
+    After(String),
+}
+
 pub fn add_translation_with_record_name(
     path: &Path,
     translation: &Translation,
+    placement: Option<&Placement>,
     record_name: &str,
     languages: &[String],
 ) -> Result<()> {
@@ -16,17 +54,122 @@ pub fn add_translation_with_record_name(
     fs::copy(path, &backup_path)
         .with_context(|| format!("Failed to create backup at {}", backup_path.display()))?;
 
-    let content = fs::read_to_string(path)?;
+    let content = read_elm_file(path)?;
+    let new_content =
+        add_translation_placed(&content, translation, placement, record_name, languages)?;
+
+    timing::write(path, new_content)
+        .with_context(|| format!("Failed to write to {}", path.display()))?;
+
+    // Remove backup file after successful write
+    let _ = fs::remove_file(&backup_path);
+
+    Ok(())
+}
+
+/// Add a translation to I18n source held in memory, returning the new source
+pub fn add_translation_to_content(
+    content: &str,
+    translation: &Translation,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    add_translation_placed(content, translation, None, record_name, languages)
+}
+
+/// `add_translation_to_content` for many translations, parsing `content` once and
+/// adding each record's new fields after its last one in a single pass
+///
+/// Nested (dotted) keys are added one by one after the others.
+pub fn add_translations_to_content(
+    content: &str,
+    translations: &[Translation],
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let _edit = timing::phase(Phase::Edit);
+    let (nested, flat): (Vec<&Translation>, Vec<&Translation>) = translations
+        .iter()
+        .partition(|translation| translation.key.contains('.'));
+    let has_trailing_newline = content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
+
+    // Bottom to top, so the lines above keep their numbers
+    let mut sorted_bounds = parse_result.lang_bounds.clone();
+    sorted_bounds.sort_by_key(|b| std::cmp::Reverse(b.1));
+    for (lang, start, end) in &sorted_bounds {
+        let last = find_last_field_line(&lines, *start, *end);
+        for (i, translation) in flat.iter().enumerate() {
+            let value = translation
+                .values
+                .get(lang)
+                .map(|s| s.as_str())
+                .unwrap_or("");
+            insert_record_field(
+                &mut lines,
+                last + i,
+                &translation.key,
+                value,
+                translation.type_signature.is_some(),
+            );
+        }
+    }
+    let last = find_last_field_line(
+        &lines,
+        parse_result.type_start_line,
+        parse_result.type_end_line,
+    );
+    for (i, translation) in flat.iter().enumerate() {
+        insert_type_field(
+            &mut lines,
+            last + i,
+            &translation.key,
+            &translation.type_signature,
+        );
+    }
+
+    let mut new_content = lines.join("\n");
+    if has_trailing_newline {
+        new_content.push('\n');
+    }
+    for translation in nested {
+        new_content = crate::nested::add(&new_content, translation, record_name, languages)?;
+    }
+    Ok(new_content)
+}
+
+/// `add_translation_to_content`, with the new field where `placement` says rather
+/// than after the last one
+pub fn add_translation_placed(
+    content: &str,
+    translation: &Translation,
+    placement: Option<&Placement>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let _edit = timing::phase(Phase::Edit);
+    if translation.key.contains('.') {
+        if placement.is_some() {
+            anyhow::bail!("--before and --after don't place nested keys yet");
+        }
+        return crate::nested::add(content, translation, record_name, languages);
+    }
     let has_trailing_newline = content.ends_with('\n');
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
     // Parse the file to find insertion points
-    let parse_result = parse_i18n_file_with_record_name(path, record_name, languages)?;
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
+    if let Some(Placement::Before(reference) | Placement::After(reference)) = placement {
+        if !parse_result.translations.contains_key(reference) {
+            anyhow::bail!(Coded::key_not_found(reference));
+        }
+    }
 
     // Insert from bottom to top so line numbers stay valid
     // First: insert into language records (sorted by start_line descending)
     let mut sorted_bounds = parse_result.lang_bounds.clone();
-    sorted_bounds.sort_by(|a, b| b.1.cmp(&a.1));
+    sorted_bounds.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     for (lang, start, end) in &sorted_bounds {
         let value = translation
@@ -34,47 +177,109 @@ pub fn add_translation_with_record_name(
             .get(lang)
             .map(|s| s.as_str())
             .unwrap_or("");
-        let insertion_line = find_last_field_line(&lines, *start, *end);
+        let fields: Vec<(&str, usize)> = parse_result.record_fields[lang]
+            .iter()
+            .map(|f| (f.name.as_str(), f.line))
+            .collect();
+        let (insertion_line, first) = match placement {
+            Some(placement) => placed_line(&lines, &fields, *end, placement)
+                .with_context(|| format!("in the {} record", lang.to_uppercase()))?,
+            None => (find_last_field_line(&lines, *start, *end), false),
+        };
         insert_record_field(
             &mut lines,
             insertion_line,
             &translation.key,
             value,
-            translation.is_function,
+            translation.type_signature.is_some(),
         );
+        if first {
+            make_first_field(&mut lines, insertion_line + 1);
+        }
     }
 
     // Last: insert into type definition (comes before language records in the file)
-    let type_insertion_line = find_last_field_line(
-        &lines,
-        parse_result.type_start_line,
-        parse_result.type_end_line,
-    );
+    let type_fields: Vec<(&str, usize)> = parse_result
+        .type_fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.line))
+        .collect();
+    let (type_insertion_line, first) = match placement {
+        Some(placement) => {
+            placed_line(&lines, &type_fields, parse_result.type_end_line, placement)?
+        }
+        None => (
+            find_last_field_line(
+                &lines,
+                parse_result.type_start_line,
+                parse_result.type_end_line,
+            ),
+            false,
+        ),
+    };
     insert_type_field(
         &mut lines,
         type_insertion_line,
         &translation.key,
         &translation.type_signature,
     );
+    if first {
+        make_first_field(&mut lines, type_insertion_line + 1);
+    }
 
-    // Write the modified content
     let mut new_content = lines.join("\n");
     if has_trailing_newline {
         new_content.push('\n');
     }
-    fs::write(path, new_content)
-        .with_context(|| format!("Failed to write to {}", path.display()))?;
+    Ok(new_content)
+}
 
-    // Remove backup file after successful write
-    let _ = fs::remove_file(&backup_path);
+/// The line a field placed by `placement` goes after, among `fields` (name and
+/// line) of a record closing on line `end`, and whether it becomes the first field
+///
+/// The comments right above a field, `--` or `{- -}`, stay with it, so a field
+/// placed before it goes above them.
+fn placed_line(
+    lines: &[String],
+    fields: &[(&str, usize)],
+    end: usize,
+    placement: &Placement,
+) -> Result<(usize, bool)> {
+    let (Placement::Before(reference) | Placement::After(reference)) = placement;
+    let Some(index) = fields.iter().position(|(name, _)| name == reference) else {
+        anyhow::bail!(Coded::key_not_found(reference));
+    };
+    let code = comment_free(lines);
+    let block_start = |index: usize| {
+        let mut start = fields[index].1;
+        while start > fields[index - 1].1 + 1
+            && is_comment_only(&lines[start - 1], &code[start - 1])
+        {
+            start -= 1;
+        }
+        start
+    };
+    let at = match placement {
+        Placement::Before(_) if index == 0 => return Ok((fields[0].1 - 1, true)),
+        Placement::Before(_) => block_start(index),
+        Placement::After(_) if index + 1 == fields.len() => end,
+        Placement::After(_) => block_start(index + 1),
+    };
+    Ok((at - 1, false))
+}
 
-    Ok(())
+/// Make the field inserted on line `line` the record's first, ahead of the one
+/// that was: it takes the `{ ` and the old first field the `, `
+fn make_first_field(lines: &mut [String], line: usize) {
+    lines[line] = lines[line].replacen(", ", "{ ", 1);
+    lines[line + 1] = lines[line + 1].replacen("{ ", ", ", 1);
 }
 
-fn find_last_field_line(lines: &[String], start: usize, end: usize) -> usize {
+pub fn find_last_field_line(lines: &[String], start: usize, end: usize) -> usize {
     // Find the last line with a field definition before the closing brace.
     // Matches both value records (field = value) and type definitions (field : Type).
     let field_regex = regex::Regex::new(r"^\s*[,{]\s*\w+\s*[=:]\s*").unwrap();
+    let lines = comment_free(lines);
 
     for i in (start..end).rev() {
         let line = &lines[i];
@@ -83,9 +288,9 @@ fn find_last_field_line(lines: &[String], start: usize, end: usize) -> usize {
             // Now skip forward past any continuation lines (multiline values like case expressions).
             let mut last_line = i;
             for j in (i + 1)..end {
-                let next = lines[j].trim();
-                // Stop at closing brace or the next field definition
-                if next.starts_with('}') || field_regex.is_match(&lines[j]) {
+                // Stop at the next field definition; a closing brace before `end`
+                // belongs to a nested record, which is part of the value
+                if field_regex.is_match(&lines[j]) {
                     break;
                 }
                 // This is a continuation line of the current field's value
@@ -105,50 +310,43 @@ fn insert_type_field(
     type_sig: &Option<String>,
 ) {
     let type_annotation = type_sig.as_ref().map(|s| s.as_str()).unwrap_or("String");
-    let new_line = format!("    , {} : {}", key, type_annotation);
+    let new_line = format!("{}, {} : {}", indent(), key, type_annotation);
     lines.insert(after_line + 1, new_line);
 }
 
-fn insert_record_field(
+/// Insert `, key = value` after a line; `raw` values are Elm expressions and aren't quoted
+pub fn insert_record_field(
     lines: &mut Vec<String>,
     after_line: usize,
     key: &str,
     value: &str,
-    is_function: bool,
+    raw: bool,
 ) {
-    if is_function {
+    if raw {
         // Handle multiline function definitions
         let indented_value = value
             .lines()
             .enumerate()
             .map(|(i, line)| {
-                if i == 0 {
+                if i == 0 || line.is_empty() {
                     line.to_string()
                 } else {
-                    format!("        {}", line)
+                    format!("{}{}", indent().repeat(2), line)
                 }
             })
             .collect::<Vec<_>>()
             .join("\n");
 
-        let new_line = format!("    , {} = {}", key, indented_value);
+        let new_line = format!("{}, {} = {}", indent(), key, indented_value);
         lines.insert(after_line + 1, new_line);
     } else {
         // Simple string value
-        let escaped_value = escape_elm_string(value);
-        let new_line = format!("    , {} = \"{}\"", key, escaped_value);
+        let escaped_value = escaping::escape(value);
+        let new_line = format!("{}, {} = \"{}\"", indent(), key, escaped_value);
         lines.insert(after_line + 1, new_line);
     }
 }
 
-fn escape_elm_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
-}
-
 pub fn create_i18n_file(path: &Path, template: &str) -> Result<()> {
     // Create parent directories if they don't exist
     if let Some(parent) = path.parent() {
@@ -168,229 +366,328 @@ pub fn remove_translation_with_record_name(
     record_name: &str,
     languages: &[String],
 ) -> Result<()> {
+    let content = read_elm_file(path)?;
+    let new_content = remove_translation_from_content(&content, key, record_name, languages)?;
+
     // Create backup
     let backup_path = path.with_extension("elm.bak");
     fs::copy(path, &backup_path)
         .with_context(|| format!("Failed to create backup at {}", backup_path.display()))?;
 
-    let content = fs::read_to_string(path)?;
+    timing::write(path, new_content)
+        .with_context(|| format!("Failed to write to {}", path.display()))?;
+
+    // Remove backup file after successful write
+    let _ = fs::remove_file(&backup_path);
+
+    Ok(())
+}
+
+/// Remove a translation from I18n source held in memory, returning the new source
+pub fn remove_translation_from_content(
+    content: &str,
+    key: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let _edit = timing::phase(Phase::Edit);
+    if key.contains('.') {
+        return crate::nested::remove(content, key, record_name, languages);
+    }
     let has_trailing_newline = content.ends_with('\n');
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
     // Parse the file to find the translation
-    let parse_result = parse_i18n_file_with_record_name(path, record_name, languages)?;
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
 
     // Check if the key exists
     if !parse_result.translations.contains_key(key) {
-        // Remove backup before returning error
-        let _ = fs::remove_file(&backup_path);
-        anyhow::bail!("Translation '{}' not found", key);
+        anyhow::bail!(Coded::key_not_found(key));
+    }
+    if crate::nested::is_group(content, key, record_name, languages) {
+        return crate::nested::remove(content, key, record_name, languages);
     }
 
-    // Remove from Translations type
-    remove_type_field(&mut lines, key);
-
-    // Remove from each language's record (one call per language)
-    for _ in languages {
-        remove_record_field(&mut lines, key);
+    // The field's line in the type and in each record, removed from the bottom up
+    // so the lines above stay where the parser found them
+    let mut fields: Vec<(usize, usize, bool)> = parse_result
+        .type_fields
+        .iter()
+        .filter(|f| f.name == key)
+        .map(|f| (f.line, f.end_line, true))
+        .chain(
+            parse_result
+                .record_fields
+                .values()
+                .flatten()
+                .filter(|f| f.name == key)
+                .map(|f| (f.line, f.end_line, false)),
+        )
+        .collect();
+    fields.sort_by_key(|&(line, _, _)| std::cmp::Reverse(line));
+    for (line, end_line, in_type) in fields {
+        if in_type {
+            remove_type_field(&mut lines, line, end_line);
+        } else {
+            remove_record_field(&mut lines, line, end_line);
+        }
     }
 
-    // Write the modified content
     let mut new_content = lines.join("\n");
     if has_trailing_newline {
         new_content.push('\n');
     }
-    fs::write(path, new_content)
-        .with_context(|| format!("Failed to write to {}", path.display()))?;
+    Ok(new_content)
+}
 
-    // Remove backup file after successful write
-    let _ = fs::remove_file(&backup_path);
+/// Replace the values of an existing translation for the given languages
+///
+/// Languages not in `values` keep their current value.
+pub fn modify_translation_in_content(
+    content: &str,
+    key: &str,
+    values: &HashMap<String, String>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let _edit = timing::phase(Phase::Edit);
+    let has_trailing_newline = content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
+    let code = comment_free(&lines);
 
-    Ok(())
-}
+    let existing = parse_result
+        .translations
+        .get(key)
+        .ok_or_else(|| Coded::key_not_found(key))?;
 
-fn remove_type_field(lines: &mut Vec<String>, key: &str) {
-    // Find the line containing the type field
-    let mut field_idx = None;
-    let mut is_first_field = false;
+    // Modify records from the bottom up so earlier bounds stay valid when a
+    // multi-line value changes the number of lines
+    let mut sorted_bounds = parse_result.lang_bounds.clone();
+    sorted_bounds.sort_by_key(|b| std::cmp::Reverse(b.1));
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.contains(&format!(" {} :", key)) {
-            field_idx = Some(i);
-            // Check if this is the first field (no leading comma)
-            let trimmed = line.trim_start();
-            is_first_field = !trimmed.starts_with(',');
-            break;
-        }
-    }
+    for (lang, _, _) in &sorted_bounds {
+        let new_value = match values.get(lang) {
+            Some(value) => value,
+            None => continue,
+        };
 
-    if let Some(idx) = field_idx {
-        // Remove the field line
-        lines.remove(idx);
-
-        // If we removed the first field, we need to make the next field the first
-        if is_first_field && idx < lines.len() {
-            // Find the next field line (starts with comma)
-            let mut next_field_idx = idx;
-            while next_field_idx < lines.len() {
-                let line = lines[next_field_idx].trim();
-                if line.starts_with(',') {
-                    // This is the next field - convert it to first field format
-                    // Change ", fieldName : Type" to "  fieldName : Type"
-                    let field_line = &lines[next_field_idx];
-                    let new_line = field_line.replacen(", ", "  ", 1);
-                    lines[next_field_idx] = new_line;
-                    break;
-                } else if line.starts_with('}') {
-                    // No more fields
-                    break;
+        // The field within this language record, up to where its value ends
+        // Functions and other non-String values are written as-is
+        let is_function = existing.type_signature.is_some();
+        let field = parse_result
+            .record_fields
+            .get(lang)
+            .and_then(|fields| fields.iter().find(|field| field.name == key));
+        let field_start = field.map(|field| field.line);
+        let field_end = field.map(|field| field.end_line.max(field.line));
+
+        if let (Some(fs), Some(fe)) = (field_start, field_end) {
+            // Detect if it's the first field (uses { key = instead of , key =)
+            let is_first = code[fs].trim_start().starts_with('{');
+            let prefix = format!("{}{} ", indent(), if is_first { '{' } else { ',' });
+            // A comment after the value stays on its line
+            let trailing_comment = if is_function {
+                String::new()
+            } else {
+                lines[fe][code[fe].trim_end().len()..].to_string()
+            };
+
+            // Remove old field lines
+            for _ in fs..=fe {
+                lines.remove(fs);
+            }
+
+            // Insert new field
+            if is_function {
+                let new_lines: Vec<String> = format!("{}{} = {}", prefix, key, new_value)
+                    .lines()
+                    .map(|l| l.to_string())
+                    .collect();
+                for (idx, line) in new_lines.iter().enumerate() {
+                    lines.insert(fs + idx, line.clone());
                 }
-                // Skip comments and empty lines
-                next_field_idx += 1;
+            } else {
+                let escaped = escaping::escape(new_value);
+                lines.insert(
+                    fs,
+                    format!("{}{} = \"{}\"{}", prefix, key, escaped, trailing_comment),
+                );
             }
         }
     }
+
+    let mut new_content = lines.join("\n");
+    if has_trailing_newline {
+        new_content.push('\n');
+    }
+    Ok(new_content)
 }
 
-fn remove_record_field(lines: &mut Vec<String>, key: &str) {
-    let mut field_start_idx = None;
-    let mut comma_line_idx = None;
-    let mut is_first_field = false;
+/// Rename a translation in the type alias and every language record
+///
+/// Values and layout are left as they are; only the field names change.
+pub fn rename_translation_in_content(
+    content: &str,
+    old_key: &str,
+    new_key: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let _edit = timing::phase(Phase::Edit);
+    let has_trailing_newline = content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
 
-    // Find the field - it might be preceded by a comma on the previous line
-    for (i, line) in lines.iter().enumerate() {
-        // Check if this line has a comma followed by our field on the next line
-        if i + 1 < lines.len()
-            && line.trim().ends_with(',')
-            && lines[i + 1].contains(&format!("{} =", key))
-        {
-            comma_line_idx = Some(i);
-            field_start_idx = Some(i + 1);
-            break;
-        }
-        // Check if this line starts with comma and our field
-        if line.trim_start().starts_with(&format!(", {} =", key)) {
-            field_start_idx = Some(i);
-            break;
-        }
-        // Check if this line just has our field (first field in record)
-        if line.contains(&format!("{} =", key)) && !line.trim_start().starts_with(',') {
-            field_start_idx = Some(i);
-            is_first_field = true;
-            break;
-        }
+    if !parse_result.translations.contains_key(old_key) {
+        anyhow::bail!(Coded::key_not_found(old_key));
+    }
+    if parse_result.translations.contains_key(new_key) {
+        anyhow::bail!(Coded::key_exists(new_key));
     }
 
-    if let Some(start_idx) = field_start_idx {
-        let mut lines_to_remove = vec![start_idx];
-
-        // Check if it's a multi-line value (function or complex expression)
-        let field_line = &lines[start_idx];
-        let is_function =
-            field_line.contains("\\") || field_line.contains("case") || field_line.contains("if ");
-        let is_multiline = is_function || !field_line.trim().ends_with('"');
-
-        if is_multiline {
-            // Find the end of this field
-            let mut j = start_idx + 1;
-            let indent_level = count_leading_spaces(&lines[start_idx]);
-
-            while j < lines.len() {
-                let current_line = &lines[j];
-                let current_indent = count_leading_spaces(current_line);
-                let trimmed = current_line.trim();
-
-                // Check if we've reached the next field at the same or lower indent level
-                if !trimmed.is_empty() {
-                    // Next field at same level (starts with comma or closing brace)
-                    if current_indent <= indent_level
-                        && (trimmed.starts_with(',') || trimmed.starts_with('}'))
-                    {
-                        break;
-                    }
-                    // For fields inside the record, check for field assignment at similar indent
-                    if current_indent <= indent_level + 4
-                        && trimmed.contains(" = ")
-                        && !trimmed.starts_with("case ")
-                    {
-                        // This might be the next field if it's not inside a case expression
-                        let before_eq = trimmed.split(" = ").next().unwrap_or("");
-                        if before_eq.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                            break;
-                        }
-                    }
-                }
+    // The field name at the start of a line, after the record's `{` or `,`
+    let field = regex::Regex::new(&format!(
+        r"^(\s*(?:[{{,]\s*)?){}(\s*[:=])",
+        regex::escape(old_key)
+    ))?;
+    let field_lines = parse_result
+        .type_fields
+        .iter()
+        .filter(|f| f.name == old_key)
+        .map(|f| f.line)
+        .chain(
+            parse_result
+                .record_fields
+                .values()
+                .flatten()
+                .filter(|f| f.name == old_key)
+                .map(|f| f.line),
+        );
+    for line in field_lines {
+        lines[line] = field
+            .replace(&lines[line], format!("${{1}}{}${{2}}", new_key))
+            .into_owned();
+    }
 
-                lines_to_remove.push(j);
-                j += 1;
+    let mut new_content = lines.join("\n");
+    if has_trailing_newline {
+        new_content.push('\n');
+    }
+    Ok(new_content)
+}
+
+/// Remove the type field on lines `idx` to `end_idx`
+pub fn remove_type_field(lines: &mut Vec<String>, idx: usize, end_idx: usize) {
+    let mut code = comment_free(lines);
+    // Check if this is the first field (no leading comma)
+    let is_first_field = !code[idx].trim_start().starts_with(',');
+
+    // Remove the field's lines
+    let opens_record = code[idx].trim_start().starts_with('{');
+    lines.drain(idx..=end_idx.max(idx));
+    code.drain(idx..=end_idx.max(idx));
+
+    // If we removed the first field, we need to make the next field the first
+    if is_first_field && idx < lines.len() {
+        // Find the next field line (starts with comma)
+        let mut next_field_idx = idx;
+        while next_field_idx < lines.len() {
+            let line = code[next_field_idx].trim();
+            if line.starts_with(',') {
+                // This is the next field - convert it to first field format
+                // Change ", fieldName : Type" to "{ fieldName : Type" (or "  fieldName :
+                // Type" when the brace is on a line of its own)
+                let field_line = &lines[next_field_idx];
+                let new_line = field_line.replacen(", ", if opens_record { "{ " } else { "  " }, 1);
+                lines[next_field_idx] = new_line;
+                break;
+            } else if line.starts_with('}') {
+                // No more fields
+                break;
             }
+            // Skip comments and empty lines
+            next_field_idx += 1;
         }
+    }
+}
 
-        // Also remove the comma line if it exists and only contains a comma
-        if let Some(comma_idx) = comma_line_idx {
-            if lines[comma_idx].trim() == "," {
-                lines_to_remove.insert(0, comma_idx);
-            }
+/// Remove the record field on lines `start_idx` to `end_idx`, where its value ends
+pub fn remove_record_field(lines: &mut Vec<String>, start_idx: usize, end_idx: usize) {
+    // Comments are blanked out of `code`, so braces and commas in them don't count
+    let mut code = comment_free(lines);
+    // The field might be preceded by a comma on the previous line
+    let mut comma_line_idx = None;
+    let mut is_first_field = false;
+    if start_idx > 0 && code[start_idx - 1].trim().ends_with(',') {
+        comma_line_idx = Some(start_idx - 1);
+    } else if !code[start_idx].trim_start().starts_with(',') {
+        is_first_field = true;
+    }
+
+    let mut lines_to_remove: Vec<usize> = (start_idx..=end_idx.max(start_idx)).collect();
+    let opens_record = code[start_idx].trim_start().starts_with('{');
+
+    // Also remove the comma line if it exists and only contains a comma
+    if let Some(comma_idx) = comma_line_idx {
+        if code[comma_idx].trim() == "," {
+            lines_to_remove.insert(0, comma_idx);
         }
+    }
 
-        // Handle the case where we need to fix trailing commas
-        // If we're removing the last field before }, we need to remove the comma from the previous field
-        if start_idx > 0 && lines_to_remove.len() > 0 {
-            let last_removed_idx = *lines_to_remove.last().unwrap();
-            if last_removed_idx + 1 < lines.len()
-                && lines[last_removed_idx + 1].trim().starts_with('}')
-            {
-                // Check if previous field ends with comma
-                let prev_field_idx = start_idx - 1;
-                if lines[prev_field_idx].trim().ends_with(',') {
-                    // Remove the trailing comma
-                    lines[prev_field_idx] = lines[prev_field_idx]
-                        .trim_end()
-                        .trim_end_matches(',')
-                        .to_string();
-                }
+    // Handle the case where we need to fix trailing commas
+    // If we're removing the last field before }, we need to remove the comma from the previous field
+    if start_idx > 0 && !lines_to_remove.is_empty() {
+        let last_removed_idx = *lines_to_remove.last().unwrap();
+        if last_removed_idx + 1 < lines.len() && code[last_removed_idx + 1].trim().starts_with('}')
+        {
+            // Check if previous field ends with comma
+            let prev_field_idx = start_idx - 1;
+            if code[prev_field_idx].trim().ends_with(',') {
+                // Remove the trailing comma
+                lines[prev_field_idx] = lines[prev_field_idx]
+                    .trim_end()
+                    .trim_end_matches(',')
+                    .to_string();
             }
         }
+    }
 
-        // Remove lines in reverse order to maintain indices
-        lines_to_remove.sort_by(|a, b| b.cmp(a));
-        for &line_idx in lines_to_remove.iter() {
-            lines.remove(line_idx);
-        }
+    // Remove lines in reverse order to maintain indices
+    lines_to_remove.sort_by(|a, b| b.cmp(a));
+    for &line_idx in lines_to_remove.iter() {
+        lines.remove(line_idx);
+        code.remove(line_idx);
+    }
 
-        // If we removed the first field, promote the next field to be first
-        if is_first_field {
-            // After removal, find the next field line (starts with comma)
-            // The removed lines are gone, so we search from where the first field was
-            let search_start = if start_idx >= lines_to_remove.len() {
-                start_idx - lines_to_remove.len() + 1
-            } else {
-                0
-            };
+    // If we removed the first field, promote the next field to be first
+    if is_first_field {
+        // After removal, find the next field line (starts with comma)
+        // The removed lines are gone, so we search from where the first field was
+        let search_start = if start_idx >= lines_to_remove.len() {
+            start_idx - lines_to_remove.len() + 1
+        } else {
+            0
+        };
 
-            for i in search_start..lines.len() {
-                let line = lines[i].trim();
-                if line.starts_with(',') {
-                    // This is the next field - convert it to first field format
-                    // Change ", fieldName = value" to "  fieldName = value"
-                    let field_line = &lines[i];
-                    let new_line = field_line.replacen(", ", "  ", 1);
-                    lines[i] = new_line;
-                    break;
-                } else if line.starts_with('}') {
-                    // No more fields
-                    break;
-                }
-                // Skip comments and empty lines
+        for i in search_start..lines.len() {
+            let line = code[i].trim();
+            if line.starts_with(',') {
+                // This is the next field - convert it to first field format
+                // Change ", fieldName = value" to "{ fieldName = value"
+                let field_line = &lines[i];
+                let new_line = field_line.replacen(", ", if opens_record { "{ " } else { "  " }, 1);
+                lines[i] = new_line;
+                break;
+            } else if line.starts_with('}') {
+                // No more fields
+                break;
             }
+            // Skip comments and empty lines
         }
     }
 }
 
-fn count_leading_spaces(line: &str) -> usize {
-    line.len() - line.trim_start().len()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,6 +695,40 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn removing_the_first_field_keeps_the_brace() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let content = r#"type alias Translations =
+    { appTitle : String
+    , welcome : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { appTitle = "App"
+    , welcome = "Welcome"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { appTitle = "Appli"
+    , welcome = "Bienvenue"
+    }
+"#;
+        let new_content =
+            remove_translation_from_content(content, "appTitle", "Translations", &languages)
+                .unwrap();
+        assert!(new_content.contains("    { welcome : String\n    }"));
+        assert!(new_content.contains("    { welcome = \"Welcome\"\n    }"));
+        assert!(new_content.contains("    { welcome = \"Bienvenue\"\n    }"));
+        // And what's left still parses, so the next removal works
+        let new_content =
+            remove_translation_from_content(&new_content, "welcome", "Translations", &languages);
+        assert!(new_content.is_ok());
+    }
+
     #[test]
     fn test_remove_anonymous_function_field() {
         let temp_dir = TempDir::new().unwrap();
@@ -592,8 +923,14 @@ translationsFr =
             type_signature: None,
         };
 
-        add_translation_with_record_name(&i18n_file, &translation, "Translations", &languages)
-            .unwrap();
+        add_translation_with_record_name(
+            &i18n_file,
+            &translation,
+            None,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
 
         let result = fs::read_to_string(&i18n_file).unwrap();
 
@@ -634,8 +971,14 @@ translationsFr =
             type_signature: None,
         };
 
-        add_translation_with_record_name(&i18n_file, &translation, "Translations", &languages)
-            .unwrap();
+        add_translation_with_record_name(
+            &i18n_file,
+            &translation,
+            None,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
 
         let result = fs::read_to_string(&i18n_file).unwrap();
         assert!(
@@ -643,4 +986,359 @@ translationsFr =
             "File should preserve trailing newline"
         );
     }
+
+    #[test]
+    fn test_edits_content_in_memory() {
+        let content = r#"module I18n exposing (..)
+
+type alias Translations =
+    { welcome : String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome"
+    }
+
+translationsFr : Translations
+translationsFr =
+    { welcome = "Bienvenue"
+    }
+"#;
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let translation = Translation {
+            key: "goodbye".to_string(),
+            values: HashMap::from([
+                ("en".to_string(), "Goodbye".to_string()),
+                ("fr".to_string(), "Au revoir".to_string()),
+            ]),
+            is_function: false,
+            type_signature: None,
+        };
+
+        let added =
+            add_translation_to_content(content, &translation, "Translations", &languages).unwrap();
+        assert!(added.contains("    , goodbye : String\n"));
+        assert!(added.contains("    , goodbye = \"Au revoir\"\n"));
+
+        let values = HashMap::from([("fr".to_string(), "Salut \"toi\"".to_string())]);
+        let modified =
+            modify_translation_in_content(&added, "goodbye", &values, "Translations", &languages)
+                .unwrap();
+        assert!(modified.contains("    , goodbye = \"Goodbye\"\n"));
+        assert!(modified.contains("    , goodbye = \"Salut \\\"toi\\\"\"\n"));
+
+        let removed =
+            remove_translation_from_content(&modified, "goodbye", "Translations", &languages)
+                .unwrap();
+        assert_eq!(removed, content);
+
+        let constant = Translation {
+            key: "firstDayOfWeek".to_string(),
+            values: HashMap::from([
+                ("en".to_string(), "0".to_string()),
+                ("fr".to_string(), "1".to_string()),
+            ]),
+            is_function: false,
+            type_signature: Some("Int".to_string()),
+        };
+        let added =
+            add_translation_to_content(content, &constant, "Translations", &languages).unwrap();
+        assert!(added.contains("    , firstDayOfWeek : Int\n"));
+        assert!(added.contains("    , firstDayOfWeek = 1\n"));
+
+        let values = HashMap::from([("fr".to_string(), "2".to_string())]);
+        let modified = modify_translation_in_content(
+            &added,
+            "firstDayOfWeek",
+            &values,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert!(modified.contains("    , firstDayOfWeek = 2\n"));
+    }
+
+    #[test]
+    fn test_rename_keeps_values_and_layout() {
+        let content = r#"module I18n exposing (..)
+
+type alias Translations =
+    { welcome : String
+    , welcomeBack : String -> String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome"
+    , welcomeBack = \name -> "Welcome back, " ++ name
+    }
+
+translationsFr : Translations
+translationsFr =
+    { welcome = "Bienvenue"
+    , welcomeBack =
+        \name -> "Bon retour, " ++ name
+    }
+"#;
+        let languages = vec!["en".to_string(), "fr".to_string()];
+
+        let renamed = rename_translation_in_content(
+            content,
+            "welcome",
+            "greeting",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(
+            renamed,
+            content
+                .replace("{ welcome :", "{ greeting :")
+                .replace("{ welcome =", "{ greeting =")
+        );
+
+        let renamed = rename_translation_in_content(
+            content,
+            "welcomeBack",
+            "greetingBack",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(renamed, content.replace("welcomeBack", "greetingBack"));
+
+        let taken = rename_translation_in_content(
+            content,
+            "welcome",
+            "welcomeBack",
+            "Translations",
+            &languages,
+        );
+        assert!(taken.is_err());
+    }
+
+    #[test]
+    fn test_writes_fields_at_the_configured_indent() {
+        let content = "module I18n exposing (..)\n\ntype alias Translations =\n  { welcome : String\n  }\n\ntranslationsEn : Translations\ntranslationsEn =\n  { welcome = \"Welcome\"\n  }\n";
+        let languages = vec!["en".to_string()];
+        let plural = Translation {
+            key: "itemCount".to_string(),
+            values: HashMap::from([(
+                "en".to_string(),
+                "\\n ->\ncase n of\n    1 -> \"one item\"\n    _ -> \"items\"".to_string(),
+            )]),
+            is_function: true,
+            type_signature: Some("Int -> String".to_string()),
+        };
+
+        set_indent(2);
+        let added =
+            add_translation_to_content(content, &plural, "Translations", &languages).unwrap();
+        assert!(added.contains("  , itemCount : Int -> String\n"));
+        assert!(added.contains(
+            "  , itemCount = \\n ->\n    case n of\n        1 -> \"one item\"\n        _ -> \"items\"\n  }"
+        ));
+
+        let values = HashMap::from([("en".to_string(), "\\n -> \"items\"".to_string())]);
+        let modified =
+            modify_translation_in_content(&added, "itemCount", &values, "Translations", &languages)
+                .unwrap();
+        assert!(modified.contains("  , itemCount = \\n -> \"items\"\n  }"));
+
+        let template =
+            crate::templates::get_i18n_template_with_record_name(&languages, "Translations");
+        assert!(template.contains("\n  { appTitle : String\n  , appName"));
+        assert!(template.contains("\n  case lang of\n    EN ->\n      \"en\""));
+        set_indent(DEFAULT_INDENT);
+    }
+
+    #[test]
+    fn places_a_new_field_next_to_another_key() {
+        let content = r#"module I18n exposing (..)
+
+type alias Translations =
+    { appTitle : String
+    , loginButton : String
+    , appName : String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { appTitle = "Title"
+    , loginButton = "Log in"
+    -- the name in the header
+    , appName = "App"
+    }
+"#;
+        let languages = vec!["en".to_string()];
+        let translation = |key: &str| Translation {
+            key: key.to_string(),
+            values: HashMap::from([("en".to_string(), "New".to_string())]),
+            is_function: false,
+            type_signature: None,
+        };
+        let place = |key: &str, placement: Placement| {
+            add_translation_placed(
+                content,
+                &translation(key),
+                Some(&placement),
+                "Translations",
+                &languages,
+            )
+        };
+
+        let after = place("logoutButton", Placement::After("loginButton".to_string())).unwrap();
+        assert!(after.contains(
+            "    , loginButton = \"Log in\"\n    , logoutButton = \"New\"\n    -- the name in the header\n    , appName"
+        ));
+        assert!(after.contains("    , loginButton : String\n    , logoutButton : String\n"));
+
+        let first = place("brand", Placement::Before("appTitle".to_string())).unwrap();
+        assert!(first.contains("\n    { brand : String\n    , appTitle : String\n"));
+        assert!(first.contains("\n    { brand = \"New\"\n    , appTitle = \"Title\"\n"));
+
+        let last = place("footer", Placement::After("appName".to_string())).unwrap();
+        assert!(last.contains("    , appName = \"App\"\n    , footer = \"New\"\n    }"));
+
+        let error = place("footer", Placement::Before("missing".to_string())).unwrap_err();
+        assert_eq!(error.to_string(), "Translation 'missing' not found");
+    }
+
+    #[test]
+    fn edits_skip_block_comments_in_the_records() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/real-world/block-comments.elm");
+        let source = fs::read_to_string(fixture).unwrap();
+        let translation = Translation {
+            key: "logout".to_string(),
+            values: HashMap::from([
+                ("en".to_string(), "Log out".to_string()),
+                ("fr".to_string(), "Se déconnecter".to_string()),
+            ]),
+            is_function: false,
+            type_signature: None,
+        };
+
+        let added =
+            add_translation_to_content(&source, &translation, "Translations", &languages).unwrap();
+        assert!(added
+            .contains("    {- , removed = \"Gone\"\n    -}\n    , logout = \"Log out\"\n    }"));
+        let removed =
+            remove_translation_from_content(&added, "logout", "Translations", &languages).unwrap();
+        assert_eq!(removed, source);
+
+        // The next field becomes the first, not the one in the comment below the `{`
+        let removed =
+            remove_translation_from_content(&source, "greeting", "Translations", &languages)
+                .unwrap();
+        assert!(removed.contains("    -}\n    { farewell : String {- { -}\n"));
+        assert!(removed.contains("    -}\n    { farewell = \"Bye\"\n"));
+        assert!(removed.contains("\n    , alsoRemoved = {- } -} \"x\"\n"));
+
+        // The comment after a multi-line value belongs to what follows it
+        let removed =
+            remove_translation_from_content(&source, "itemCount", "Translations", &languages)
+                .unwrap();
+        assert!(removed
+            .contains("    , farewell = \"Bye\"\n    {- , removed = \"Gone\"\n    -}\n    }"));
+
+        let values = HashMap::from([("en".to_string(), "Hi".to_string())]);
+        let modified =
+            modify_translation_in_content(&source, "greeting", &values, "Translations", &languages)
+                .unwrap();
+        assert!(modified.contains("    { greeting = \"Hi\" {- = , } -}\n"));
+    }
+
+    #[test]
+    fn edits_leave_the_declarations_around_the_translations_alone() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/real-world/ports.elm");
+        let source = fs::read_to_string(fixture).unwrap();
+        let translation = Translation {
+            key: "newKey".to_string(),
+            values: HashMap::from([
+                ("en".to_string(), "New".to_string()),
+                ("fr".to_string(), "Nouveau".to_string()),
+            ]),
+            is_function: false,
+            type_signature: None,
+        };
+
+        let added =
+            add_translation_to_content(&source, &translation, "Translations", &languages).unwrap();
+        let removed =
+            remove_translation_from_content(&added, "newKey", "Translations", &languages).unwrap();
+        assert_eq!(removed, source);
+
+        // `port save` and `autosave =` look like the fields of `save` to a line scan
+        let removed =
+            remove_translation_from_content(&source, "save", "Translations", &languages).unwrap();
+        assert!(removed.contains("\nport save : Encode.Value -> Cmd msg\n"));
+        assert!(removed.contains("\nautosave =\n    True\n"));
+        assert_eq!(source.lines().count() - removed.lines().count(), 3);
+    }
+
+    #[test]
+    fn edits_take_the_whole_of_a_value_that_goes_on_over_lines() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/real-world/multiline-values.elm");
+        let source = fs::read_to_string(fixture).unwrap();
+
+        // The lines of the `"""` string go with it, and so does a type on two lines
+        let removed =
+            remove_translation_from_content(&source, "poem", "Translations", &languages).unwrap();
+        let removed =
+            remove_translation_from_content(&removed, "wrapped", "Translations", &languages)
+                .unwrap();
+        assert!(!removed.contains("red") && !removed.contains("Int -> String"));
+        assert!(removed.contains("            ++ \"world\"\n    }\n"));
+        assert!(removed.contains("        ++ \"monde\"\n    }\n"));
+
+        let values = HashMap::from([("fr".to_string(), "Salut".to_string())]);
+        let modified =
+            modify_translation_in_content(&source, "joined", &values, "Translations", &languages)
+                .unwrap();
+        assert!(modified.contains("    , joined = \"Salut\"\n    , poem = \"\"\"Roses\"\"\"\n"));
+        let values = HashMap::from([("fr".to_string(), "c = d".to_string())]);
+        let modified =
+            modify_translation_in_content(&source, "eq", &values, "Translations", &languages)
+                .unwrap();
+        assert!(modified.contains("    { eq = \"c = d\" -- note, with = and ,\n"));
+    }
+
+    #[test]
+    fn adds_many_translations_as_one_by_one() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/real-world/multiline-values.elm");
+        let source = fs::read_to_string(fixture).unwrap();
+        let translations: Vec<Translation> = ["first", "second", "third"]
+            .iter()
+            .map(|key| Translation {
+                key: key.to_string(),
+                values: HashMap::from([
+                    ("en".to_string(), format!("{} value", key)),
+                    ("fr".to_string(), String::new()),
+                ]),
+                is_function: false,
+                type_signature: None,
+            })
+            .collect();
+
+        let mut one_by_one = source.clone();
+        for translation in &translations {
+            one_by_one =
+                add_translation_to_content(&one_by_one, translation, "Translations", &languages)
+                    .unwrap();
+        }
+        assert_eq!(
+            add_translations_to_content(&source, &translations, "Translations", &languages)
+                .unwrap(),
+            one_by_one
+        );
+    }
 }
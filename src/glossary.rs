@@ -0,0 +1,245 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::lint::string_literal_contents;
+use crate::types::ParseResult;
+
+/// Terms that must always be translated the same way
+///
+/// Loaded from a CSV file whose header names the languages; the first column is
+/// the source language:
+///
+/// ```text
+/// en,fr,es
+/// Sign in,Se connecter,Iniciar sesión
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glossary {
+    pub source_language: String,
+    pub entries: Vec<GlossaryEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryEntry {
+    pub term: String,
+    /// Required translation by language; languages with an empty cell aren't checked
+    pub translations: HashMap<String, String>,
+}
+
+/// A value that uses a glossary term without its mandated translation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossaryViolation {
+    pub key: String,
+    pub language: String,
+    pub term: String,
+    pub expected: String,
+}
+
+pub fn load_glossary(path: &Path) -> Result<Glossary> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read glossary {}", path.display()))?;
+    parse_glossary_csv(&content).with_context(|| format!("Invalid glossary {}", path.display()))
+}
+
+pub fn parse_glossary_csv(content: &str) -> Result<Glossary> {
+    let mut rows = content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(split_csv_line);
+
+    let header: Vec<String> = match rows.next() {
+        Some(header) => header.iter().map(|l| l.trim().to_lowercase()).collect(),
+        None => bail!("the glossary is empty; expected a header like `en,fr`"),
+    };
+    if header.len() < 2 || header.iter().any(|l| l.is_empty()) {
+        bail!("the header must name the source language and at least one other language");
+    }
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let term = row.first().map(|t| t.trim()).unwrap_or("");
+        if term.is_empty() {
+            continue;
+        }
+        let translations = header
+            .iter()
+            .zip(row.iter())
+            .skip(1)
+            .filter(|(_, value)| !value.trim().is_empty())
+            .map(|(lang, value)| (lang.clone(), value.trim().to_string()))
+            .collect();
+        entries.push(GlossaryEntry {
+            term: term.to_string(),
+            translations,
+        });
+    }
+
+    Ok(Glossary {
+        source_language: header[0].clone(),
+        entries,
+    })
+}
+
+/// Split a CSV line, honoring double-quoted cells (`"a, b"` and `""` escapes)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+/// Every value whose source text uses a glossary term but whose translation lacks
+/// the mandated text
+///
+/// Matching is case-insensitive and only counts whole words, so "Sign in" doesn't
+/// match "Sign inside".
+pub fn glossary_violations(
+    parse_result: &ParseResult,
+    glossary: &Glossary,
+    languages: &[String],
+) -> Vec<GlossaryViolation> {
+    let mut violations = Vec::new();
+
+    let text = |value: &str| string_literal_contents(value).join(" ");
+    let patterns: Vec<(&GlossaryEntry, Regex)> = glossary
+        .entries
+        .iter()
+        .filter_map(|entry| word_pattern(&entry.term).map(|p| (entry, p)))
+        .collect();
+
+    for (key, translation) in &parse_result.translations {
+        let source = match translation.values.get(&glossary.source_language) {
+            Some(value) => text(value),
+            None => continue,
+        };
+
+        for (entry, pattern) in &patterns {
+            if !pattern.is_match(&source) {
+                continue;
+            }
+            for lang in languages {
+                let expected = match entry.translations.get(lang) {
+                    Some(expected) => expected,
+                    None => continue,
+                };
+                let value = match translation.values.get(lang) {
+                    Some(value) if !value.trim().is_empty() => text(value),
+                    _ => continue,
+                };
+                let uses_expected = word_pattern(expected).is_some_and(|p| p.is_match(&value));
+                if !uses_expected {
+                    violations.push(GlossaryViolation {
+                        key: key.clone(),
+                        language: lang.clone(),
+                        term: entry.term.clone(),
+                        expected: expected.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Case-insensitive whole-word pattern for a term
+///
+/// `\b` only applies next to word characters, so a term like "C++" still matches.
+fn word_pattern(term: &str) -> Option<Regex> {
+    let starts_with_word = term.chars().next().is_some_and(|c| c.is_alphanumeric());
+    let ends_with_word = term.chars().last().is_some_and(|c| c.is_alphanumeric());
+    Regex::new(&format!(
+        "(?i){}{}{}",
+        if starts_with_word { r"\b" } else { "" },
+        regex::escape(term),
+        if ends_with_word { r"\b" } else { "" }
+    ))
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn parses_quoted_cells_and_skips_comments() {
+        let glossary = parse_glossary_csv(
+            "# Terminology approved by the localization team
+EN,fr
+Sign in,Se connecter
+\"Save, then close\",\"Enregistrer, puis \"\"fermer\"\"\"
+Dashboard,
+",
+        )
+        .unwrap();
+
+        assert_eq!(glossary.source_language, "en");
+        assert_eq!(glossary.entries.len(), 3);
+        assert_eq!(
+            glossary.entries[1].translations["fr"],
+            "Enregistrer, puis \"fermer\""
+        );
+        assert!(glossary.entries[2].translations.is_empty());
+    }
+
+    #[test]
+    fn reports_translations_missing_the_mandated_text() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            r#"type alias Translations =
+    { signIn : String
+    , signInHint : String
+    , signInside : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { signIn = "Sign in"
+    , signInHint = "Please SIGN IN to continue"
+    , signInside = "Sign inside the box"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { signIn = "Connexion"
+    , signInHint = "Veuillez vous se connecter pour continuer"
+    , signInside = "Signez dans la case"
+    }
+"#,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let glossary = parse_glossary_csv("en,fr\nSign in,Se connecter\n").unwrap();
+
+        assert_eq!(
+            glossary_violations(&parse_result, &glossary, &languages),
+            vec![GlossaryViolation {
+                key: "signIn".to_string(),
+                language: "fr".to_string(),
+                term: "Sign in".to_string(),
+                expected: "Se connecter".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A character's Grapheme_Cluster_Break property, with Extended_Pictographic
+/// split out of `Other` for emoji sequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Break {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Prepend,
+    SpacingMark,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Pictographic,
+    Other,
+}
+
+/// The properties' tables, which the regex crate carries
+fn classes() -> &'static [(Regex, Break)] {
+    static CLASSES: OnceLock<Vec<(Regex, Break)>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        [
+            (r"\p{gcb=CR}", Break::Cr),
+            (r"\p{gcb=LF}", Break::Lf),
+            (r"\p{gcb=Control}", Break::Control),
+            (r"\p{gcb=Extend}", Break::Extend),
+            (r"\p{gcb=ZWJ}", Break::Zwj),
+            (r"\p{gcb=Regional_Indicator}", Break::RegionalIndicator),
+            (r"\p{gcb=Prepend}", Break::Prepend),
+            (r"\p{gcb=SpacingMark}", Break::SpacingMark),
+            (r"\p{gcb=L}", Break::L),
+            (r"\p{gcb=V}", Break::V),
+            (r"\p{gcb=T}", Break::T),
+            (r"\p{gcb=LV}", Break::Lv),
+            (r"\p{gcb=LVT}", Break::Lvt),
+            (r"\p{Extended_Pictographic}", Break::Pictographic),
+        ]
+        .into_iter()
+        .map(|(pattern, class)| (Regex::new(&format!("^{}$", pattern)).unwrap(), class))
+        .collect()
+    })
+}
+
+fn class(c: char) -> Break {
+    let mut buffer = [0; 4];
+    let c = c.encode_utf8(&mut buffer);
+    classes()
+        .iter()
+        .find(|(regex, _)| regex.is_match(c))
+        .map_or(Break::Other, |(_, class)| *class)
+}
+
+/// The viramas that join consonants into a conjunct (Indic_Conjunct_Break=Linker),
+/// in Devanagari, Bengali, Gujarati, Oriya, Telugu and Malayalam
+fn is_linker(c: char) -> bool {
+    matches!(
+        c,
+        '\u{094D}' | '\u{09CD}' | '\u{0ACD}' | '\u{0B4D}' | '\u{0C4D}' | '\u{0D4D}'
+    )
+}
+
+/// The consonants of those scripts (Indic_Conjunct_Break=Consonant)
+fn is_consonant(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0915..=0x0939
+            | 0x0958..=0x095F
+            | 0x0978..=0x097F
+            | 0x0995..=0x09A8
+            | 0x09AA..=0x09B0
+            | 0x09B2
+            | 0x09B6..=0x09B9
+            | 0x09DC..=0x09DD
+            | 0x09DF
+            | 0x09F0..=0x09F1
+            | 0x0A95..=0x0AA8
+            | 0x0AAA..=0x0AB0
+            | 0x0AB2..=0x0AB3
+            | 0x0AB5..=0x0AB9
+            | 0x0AF9
+            | 0x0B15..=0x0B28
+            | 0x0B2A..=0x0B30
+            | 0x0B32..=0x0B33
+            | 0x0B35..=0x0B39
+            | 0x0B5C..=0x0B5D
+            | 0x0B5F
+            | 0x0B71
+            | 0x0C15..=0x0C28
+            | 0x0C2A..=0x0C39
+            | 0x0C58..=0x0C5A
+            | 0x0D15..=0x0D3A
+    )
+}
+
+/// The number of user-perceived characters in `text`: its extended grapheme
+/// clusters, as Unicode's UAX #29 splits them
+///
+/// So a flag of two regional indicators, a Devanagari conjunct with its vowel
+/// signs, a Thai consonant with its marks and an emoji joined by ZWJs each count
+/// once.
+pub fn grapheme_len(text: &str) -> usize {
+    let mut count = 0;
+    let mut previous: Option<Break> = None;
+    // Regional indicators in a row so far, which pair up into flags
+    let mut indicators = 0;
+    // GB11: a pictograph followed by Extends, and then by a ZWJ
+    let mut pictograph = false;
+    let mut pictograph_zwj = false;
+    // GB9c: a consonant followed by Extends and linkers, and whether a linker was one
+    let mut consonant = false;
+    let mut linked = false;
+
+    for c in text.chars() {
+        let current = class(c);
+        let joined = match previous {
+            None => false,
+            Some(previous) => !breaks_between(
+                previous,
+                current,
+                indicators,
+                pictograph_zwj,
+                linked && is_consonant(c),
+            ),
+        };
+        if !joined {
+            count += 1;
+        }
+
+        indicators = if current == Break::RegionalIndicator {
+            indicators + 1
+        } else {
+            0
+        };
+        pictograph_zwj = pictograph && current == Break::Zwj;
+        pictograph = match current {
+            Break::Pictographic => true,
+            Break::Extend => pictograph,
+            _ => false,
+        };
+        if is_consonant(c) {
+            consonant = true;
+            linked = false;
+        } else if is_linker(c) {
+            linked = consonant;
+        } else if !matches!(current, Break::Extend | Break::Zwj) {
+            consonant = false;
+            linked = false;
+        }
+        previous = Some(current);
+    }
+
+    count
+}
+
+/// Whether UAX #29 puts a cluster boundary between `previous` and `current`
+fn breaks_between(
+    previous: Break,
+    current: Break,
+    indicators: usize,
+    pictograph_zwj: bool,
+    conjunct: bool,
+) -> bool {
+    use Break::*;
+    match (previous, current) {
+        // GB3 to GB5: line breaks and controls stand alone
+        (Cr, Lf) => false,
+        (Cr | Lf | Control, _) | (_, Cr | Lf | Control) => true,
+        // GB6 to GB8: Hangul syllables
+        (L, L | V | Lv | Lvt) | (Lv | V, V | T) | (Lvt | T, T) => false,
+        // GB9, GB9a, GB9b: marks go with what they follow, prefixes with what follows
+        (_, Extend | Zwj | SpacingMark) | (Prepend, _) => false,
+        // GB11: emoji joined by a ZWJ
+        (Zwj, Pictographic) if pictograph_zwj => false,
+        // GB12, GB13: flags are pairs of regional indicators
+        (RegionalIndicator, RegionalIndicator) => indicators.is_multiple_of(2),
+        // GB9c: consonants joined by a virama
+        _ if conjunct => false,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_graphemes_rather_than_bytes() {
+        assert_eq!(grapheme_len("Größe"), 5);
+        assert_eq!(grapheme_len("e\u{301}te\u{301}"), 3);
+        assert_eq!(grapheme_len("保存する"), 4);
+        assert_eq!(grapheme_len("👍🏽 ok"), 4);
+        assert_eq!(grapheme_len("👨‍👩‍👧"), 1);
+        assert_eq!(grapheme_len("\r\n"), 1);
+        assert_eq!(grapheme_len("한국어"), 3);
+    }
+
+    #[test]
+    fn counts_flags_and_indic_syllables_once() {
+        // Two flags of two regional indicators each
+        assert_eq!(grapheme_len("🇫🇷🇩🇪"), 2);
+        assert_eq!(grapheme_len("Paris 🇫🇷"), 7);
+        // न, म and the conjunct स्ते, its vowel sign U+0947 included
+        assert_eq!(grapheme_len("नमस्ते"), 3);
+        assert_eq!(grapheme_len("हिन्दी"), 2);
+        // Thai marks above (U+0E31, U+0E49) join their consonant
+        assert_eq!(grapheme_len("สวัสดี"), 4);
+        assert_eq!(grapheme_len("กั้น"), 2);
+    }
+}
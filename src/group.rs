@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use crate::config::KeyStyle;
+use crate::coverage::LanguageCoverage;
+use crate::types::Translation;
+use crate::validate::{value_status, ValueStatus};
+
+/// A group of keys, with how many of them each language has translated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupSummary {
+    /// Dotted, like `checkout.payment`, with either key style
+    pub name: String,
+    pub keys: usize,
+    pub coverage: Vec<LanguageCoverage>,
+}
+
+/// The groups of `translations`, by name: each dotted prefix of the nested keys,
+/// or with flat keys each first camelCase word that starts two keys or more
+pub fn groups(
+    translations: &[Translation],
+    style: KeyStyle,
+    languages: &[String],
+    todo_marker: &str,
+) -> Vec<GroupSummary> {
+    let mut members: BTreeMap<String, Vec<&Translation>> = BTreeMap::new();
+    for translation in translations {
+        let names: Vec<String> = match style {
+            KeyStyle::Nested => {
+                let segments: Vec<&str> = translation.key.split('.').collect();
+                (1..segments.len())
+                    .map(|n| segments[..n].join("."))
+                    .collect()
+            }
+            KeyStyle::Flat => first_word(&translation.key)
+                .map(str::to_string)
+                .into_iter()
+                .collect(),
+        };
+        for name in names {
+            members.entry(name).or_default().push(translation);
+        }
+    }
+    members
+        .into_iter()
+        .filter(|(_, keys)| style == KeyStyle::Nested || keys.len() > 1)
+        .map(|(name, keys)| GroupSummary {
+            name,
+            keys: keys.len(),
+            coverage: languages
+                .iter()
+                .map(|lang| LanguageCoverage {
+                    language: lang.clone(),
+                    translated: keys
+                        .iter()
+                        .filter(|t| {
+                            value_status(t.values.get(lang), todo_marker) == ValueStatus::Translated
+                        })
+                        .count(),
+                    total: keys.len(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The first word of a camelCase key with more than one: `checkout` for
+/// `checkoutPayNow`
+fn first_word(key: &str) -> Option<&str> {
+    let end = key
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !c.is_lowercase())
+        .map(|(i, _)| i)?;
+    Some(&key[..end])
+}
+
+/// The prefix of the flat keys of the group `group`: `checkoutLegacy` for
+/// `checkout.legacy`
+pub fn flat_prefix(group: &str) -> String {
+    group
+        .split('.')
+        .enumerate()
+        .map(|(i, word)| {
+            if i == 0 {
+                word.to_string()
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Whether the flat `key` is in the group with `prefix`: it goes on past it with
+/// a new word, so `checkoutTitle` is in `checkout` but `checkouts` and `checkout`
+/// aren't
+pub fn in_flat_group(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| !c.is_lowercase())
+}
+
+/// Each key of `keys` in the flat group `from`, with the key it has once the
+/// group is `to`; both are dotted group names
+pub fn flat_renames<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    from: &str,
+    to: &str,
+) -> Vec<(String, String)> {
+    let old_prefix = flat_prefix(from);
+    let new_prefix = flat_prefix(to);
+    keys.into_iter()
+        .filter(|key| in_flat_group(key, &old_prefix))
+        .map(|key| {
+            let new_key = format!("{}{}", new_prefix, &key[old_prefix.len()..]);
+            (key.to_string(), new_key)
+        })
+        .collect()
+}
+
+/// Each of `keys` that is `old` or starts with it as a word, with `new` in its
+/// place: `accountTitle` and `account` for `account`, but not `accounts`
+pub fn prefix_renames<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    old: &str,
+    new: &str,
+) -> Vec<(String, String)> {
+    keys.into_iter()
+        .filter(|key| *key == old || in_flat_group(key, old))
+        .map(|key| (key.to_string(), format!("{}{}", new, &key[old.len()..])))
+        .collect()
+}
+
+/// The renames of `renames` whose new key is one of `keys` already
+pub fn collisions<'a>(renames: &'a [(String, String)], keys: &[&str]) -> Vec<&'a (String, String)> {
+    renames
+        .iter()
+        .filter(|(_, new)| keys.contains(&new.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn translation(key: &str, en: &str, fr: &str) -> Translation {
+        Translation {
+            key: key.to_string(),
+            values: HashMap::from([
+                ("en".to_string(), format!("\"{}\"", en)),
+                ("fr".to_string(), format!("\"{}\"", fr)),
+            ]),
+            is_function: false,
+            type_signature: None,
+        }
+    }
+
+    #[test]
+    fn groups_keys_by_prefix_or_by_nested_record() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let flat = [
+            translation("checkoutTitle", "Checkout", "Paiement"),
+            translation("checkoutPayNow", "Pay now", "TODO"),
+            translation("checkouts", "Orders", "Commandes"),
+            translation("welcome", "Welcome", "Bienvenue"),
+            translation("welcomeBack", "Welcome back", "Bon retour"),
+        ];
+        let listed: Vec<(String, usize, usize)> = groups(&flat, KeyStyle::Flat, &languages, "TODO")
+            .into_iter()
+            .map(|g| (g.name, g.keys, g.coverage[1].translated))
+            .collect();
+        // `welcomeBack` alone makes no group
+        assert_eq!(listed, [("checkout".to_string(), 2, 1)]);
+
+        let nested = [
+            translation("checkout.title", "Checkout", "Paiement"),
+            translation("checkout.payment.cardNumber", "Card number", ""),
+            translation("welcome", "Welcome", "Bienvenue"),
+        ];
+        let listed: Vec<(String, usize, usize)> =
+            groups(&nested, KeyStyle::Nested, &languages, "TODO")
+                .into_iter()
+                .map(|g| (g.name, g.keys, g.coverage[1].translated))
+                .collect();
+        assert_eq!(
+            listed,
+            [
+                ("checkout".to_string(), 2, 1),
+                ("checkout.payment".to_string(), 1, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_the_keys_of_a_flat_group() {
+        let keys = [
+            "checkoutTitle",
+            "checkoutLegacyNote",
+            "checkouts",
+            "checkout",
+        ];
+        assert_eq!(
+            flat_renames(keys, "checkout", "payment"),
+            [
+                ("checkoutTitle".to_string(), "paymentTitle".to_string()),
+                (
+                    "checkoutLegacyNote".to_string(),
+                    "paymentLegacyNote".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            flat_renames(keys, "checkout.legacy", "archive.legacy"),
+            [(
+                "checkoutLegacyNote".to_string(),
+                "archiveLegacyNote".to_string()
+            )]
+        );
+        assert_eq!(
+            flat_renames(keys, "checkout.legacy", "legacy"),
+            [("checkoutLegacyNote".to_string(), "legacyNote".to_string())]
+        );
+    }
+
+    #[test]
+    fn renames_a_prefix_and_finds_the_keys_it_would_collide_with() {
+        let keys = ["account", "accountTitle", "accounts", "profileTitle"];
+        let renames = prefix_renames(keys, "account", "profile");
+        assert_eq!(
+            renames,
+            [
+                ("account".to_string(), "profile".to_string()),
+                ("accountTitle".to_string(), "profileTitle".to_string()),
+            ]
+        );
+        assert_eq!(collisions(&renames, &keys), [&renames[1]]);
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Remembers the content of translation files so edits can be detected afterwards
+pub struct EditWatcher {
+    snapshots: Vec<(PathBuf, Option<String>)>,
+}
+
+impl EditWatcher {
+    pub fn new(paths: &[PathBuf]) -> Self {
+        let mut snapshots: Vec<(PathBuf, Option<String>)> = Vec::new();
+        for path in paths {
+            if snapshots.iter().any(|(seen, _)| seen == path) {
+                continue;
+            }
+            snapshots.push((path.clone(), fs::read_to_string(path).ok()));
+        }
+        EditWatcher { snapshots }
+    }
+
+    /// Files whose content differs from when the watcher was created
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        self.snapshots
+            .iter()
+            .filter(|(path, before)| {
+                let after = fs::read_to_string(path).ok();
+                after.is_some() && after != *before
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// A file's content from when the watcher was created (`None` if it didn't exist)
+    pub fn original(&self, path: &Path) -> Option<&str> {
+        self.snapshots
+            .iter()
+            .find(|(seen, _)| seen == path)
+            .and_then(|(_, before)| before.as_deref())
+    }
+
+    /// Put every changed file back the way it was, removing files that didn't exist
+    pub fn restore_changed(&self) -> Result<Vec<PathBuf>> {
+        let changed = self.changed_files();
+        for (path, before) in &self.snapshots {
+            if !changed.contains(path) {
+                continue;
+            }
+            match before {
+                Some(content) => fs::write(path, content),
+                None => fs::remove_file(path),
+            }
+            .with_context(|| format!("Failed to restore {}", path.display()))?;
+        }
+        Ok(changed)
+    }
+}
+
+/// Build the shell command for a hook, substituting `{file}` with the edited file
+pub fn hook_command(template: &str, file: &Path) -> String {
+    template.replace("{file}", &shell_quote(&file.to_string_lossy()))
+}
+
+/// Run the post-edit hook for one file, failing if it exits non-zero
+pub fn run_post_edit_hook(template: &str, file: &Path) -> Result<()> {
+    let command = hook_command(template, file);
+
+    let status = if cfg!(windows) {
+        Command::new("cmd").args(["/C", &command]).status()
+    } else {
+        Command::new("sh").args(["-c", &command]).status()
+    }
+    .with_context(|| format!("Failed to run post-edit hook: {}", command))?;
+
+    if !status.success() {
+        let code = status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        anyhow::bail!("Post-edit hook `{}` failed (exit code {})", command, code);
+    }
+
+    Ok(())
+}
+
+fn shell_quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_alphanumeric() || "/._-+:@".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.to_string()
+    } else if cfg!(windows) {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn substitutes_and_quotes_the_file() {
+        assert_eq!(
+            hook_command("elm-format --yes {file}", Path::new("src/I18n.elm")),
+            "elm-format --yes src/I18n.elm"
+        );
+        if !cfg!(windows) {
+            assert_eq!(
+                hook_command("fmt {file}", Path::new("src/My App/I18n.elm")),
+                "fmt 'src/My App/I18n.elm'"
+            );
+        }
+    }
+
+    #[test]
+    fn reports_only_files_that_changed() {
+        let dir = TempDir::new().unwrap();
+        let edited = dir.path().join("Edited.elm");
+        let untouched = dir.path().join("Untouched.elm");
+        fs::write(&edited, "before").unwrap();
+        fs::write(&untouched, "same").unwrap();
+
+        let watcher = EditWatcher::new(&[edited.clone(), untouched.clone(), edited.clone()]);
+        fs::write(&edited, "after").unwrap();
+
+        assert_eq!(watcher.changed_files(), vec![edited.clone()]);
+
+        let created = dir.path().join("Created.elm");
+        let watcher = EditWatcher::new(&[edited.clone(), created.clone()]);
+        fs::write(&edited, "broken").unwrap();
+        fs::write(&created, "new").unwrap();
+        watcher.restore_changed().unwrap();
+        assert_eq!(fs::read_to_string(&edited).unwrap(), "after");
+        assert!(!created.exists());
+    }
+}
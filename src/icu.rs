@@ -0,0 +1,717 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::escaping::escape;
+use crate::plural::{self, CATEGORIES as PLURAL_CATEGORIES};
+
+/// A piece of an ICU message
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    /// `{name}`
+    Argument(String),
+    /// `#` in a plural branch: the number of the innermost plural around it
+    Count(String),
+    /// `{name, plural, ...}`, or a plural by the ordinal rules from `add-plural --ordinal`
+    Plural {
+        argument: String,
+        branches: Vec<(Selector, Vec<Part>)>,
+        ordinal: bool,
+    },
+    /// `{name, select, ...}`
+    Select {
+        argument: String,
+        branches: Vec<(String, Vec<Part>)>,
+    },
+}
+
+/// What picks a plural branch: `=0` or a category like `one`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    Exact(i64),
+    Category(String),
+}
+
+/// Argument formats ICU has that elm-i18n doesn't compile
+const FORMATS: [&str; 6] = ["number", "date", "time", "spellout", "ordinal", "duration"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgumentType {
+    Int,
+    String,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// The ICU features met that elm-i18n doesn't compile, to list them all at once
+    unsupported: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// The characters up to whitespace or one of `stops`
+    fn word(&mut self, stops: &[char]) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !stops.contains(&c))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn expect(&mut self, expected: char, context: &str) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => bail!("expected `{}` {} but found `{}`", expected, context, c),
+            None => bail!("expected `{}` {} but the message ended", expected, context),
+        }
+    }
+
+    /// Text and arguments up to the end, or the `}` closing a branch when `nested`;
+    /// `#` stands for `plural` when the text is one of its branches
+    fn message(&mut self, plural: Option<&str>, nested: bool) -> Result<Vec<Part>> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '}' if nested => break,
+                '}' => bail!("has a `}}` that doesn't close anything; quote it as '}}'"),
+                '{' => {
+                    self.pos += 1;
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.argument()?);
+                }
+                '#' if plural.is_some() => {
+                    self.pos += 1;
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Count(plural.unwrap_or_default().to_string()));
+                }
+                '\'' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\'') => {
+                            self.pos += 1;
+                            text.push('\'');
+                        }
+                        Some('{' | '}' | '|') => text.push_str(&self.quoted()?),
+                        Some('#') if plural.is_some() => text.push_str(&self.quoted()?),
+                        _ => text.push('\''),
+                    }
+                }
+                c => {
+                    self.pos += 1;
+                    text.push(c);
+                }
+            }
+        }
+        if nested && self.peek().is_none() {
+            bail!("has a branch that never closes; a `}}` is missing");
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(parts)
+    }
+
+    /// The text quoted after a `'`, up to the next lone `'`
+    fn quoted(&mut self) -> Result<String> {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c != '\'' {
+                text.push(c);
+            } else if self.peek() == Some('\'') {
+                self.pos += 1;
+                text.push('\'');
+            } else {
+                return Ok(text);
+            }
+        }
+        bail!("has a quote `'` that never closes; write a lone apostrophe as ''")
+    }
+
+    /// Move past the rest of an argument this can't compile, through its `}`
+    fn skip_argument(&mut self, name: &str, kind: &str) -> Result<()> {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => return Ok(()),
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        bail!("has a `{{{}, {}` that never closes", name, kind)
+    }
+
+    /// The argument after a `{`, through its closing `}`
+    fn argument(&mut self) -> Result<Part> {
+        self.skip_whitespace();
+        let name = self.word(&['{', '}', ',']);
+        if name.is_empty() {
+            bail!("has an argument without a name");
+        }
+        check_argument_name(&name)?;
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Part::Argument(name));
+        }
+        self.expect(',', &format!("after `{{{}`", name))?;
+        self.skip_whitespace();
+        let kind = self.word(&['{', '}', ',']);
+        match kind.as_str() {
+            "plural" | "select" => {}
+            "selectordinal" => self.unsupported.push(format!(
+                "`{{{}, selectordinal, ...}}` (ordinals like 1st and 2nd)",
+                name
+            )),
+            "" => bail!("has `{{{},` without a type after the comma", name),
+            _ => {
+                self.unsupported.push(if FORMATS.contains(&kind.as_str()) {
+                    format!(
+                        "`{{{}, {}}}` (formatting; pass the formatted text as an argument)",
+                        name, kind
+                    )
+                } else {
+                    format!("`{{{}, {}}}` (not an ICU argument type)", name, kind)
+                });
+                self.skip_argument(&name, &kind)?;
+                return Ok(Part::Argument(name));
+            }
+        }
+        self.expect(',', &format!("after `{{{}, {}`", name, kind))?;
+
+        let mut branches: Vec<(String, Vec<Part>)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                None => bail!("has a `{{{}, {}, ...}}` that never closes", name, kind),
+                _ => {}
+            }
+            let selector = self.word(&['{', '}']);
+            if selector.starts_with("offset:") {
+                self.unsupported
+                    .push(format!("`offset:` in `{{{}, plural, ...}}`", name));
+                if selector == "offset:" {
+                    self.skip_whitespace();
+                    self.word(&['{', '}']);
+                }
+                continue;
+            }
+            if branches.iter().any(|(s, _)| *s == selector) {
+                bail!(
+                    "has two `{}` branches in `{{{}, {}, ...}}`",
+                    selector,
+                    name,
+                    kind
+                );
+            }
+            self.expect(
+                '{',
+                &format!("after `{}` in `{{{}, {}, ...}}`", selector, name, kind),
+            )?;
+            let plural = (kind != "select").then_some(name.as_str());
+            let branch = self.message(plural, true)?;
+            self.pos += 1;
+            branches.push((selector, branch));
+        }
+        if !branches.iter().any(|(selector, _)| selector == "other") {
+            bail!(
+                "has no `other` branch in `{{{}, {}, ...}}`, which ICU requires",
+                name,
+                kind
+            );
+        }
+
+        if kind == "select" {
+            return Ok(Part::Select {
+                argument: name,
+                branches,
+            });
+        }
+        let branches = branches
+            .into_iter()
+            .map(|(selector, branch)| {
+                let selector = match selector.strip_prefix('=') {
+                    Some(number) => match number.parse() {
+                        Ok(number) => Selector::Exact(number),
+                        Err(_) => bail!("has `{}`, which isn't `=` and a whole number", selector),
+                    },
+                    None if PLURAL_CATEGORIES.contains(&selector.as_str()) => {
+                        Selector::Category(selector)
+                    }
+                    None => bail!(
+                        "has `{}` in `{{{}, plural, ...}}`, which isn't a plural category ({}) or `=N`",
+                        selector,
+                        name,
+                        PLURAL_CATEGORIES.join(", ")
+                    ),
+                };
+                Ok((selector, branch))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Part::Plural {
+            argument: name,
+            branches,
+            ordinal: false,
+        })
+    }
+}
+
+fn check_argument_name(name: &str) -> Result<()> {
+    let identifier = Regex::new(r"^[a-z][A-Za-z0-9_]*$").unwrap();
+    if !identifier.is_match(name) || crate::ELM_RESERVED_WORDS.contains(&name) {
+        bail!(
+            "has the argument `{}`, which can't be an Elm argument name; use a name like `count`",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn parse(message: &str) -> Result<Vec<Part>> {
+    parse_within(message, None)
+}
+
+/// `message`, as a branch of the plural of `plural` when there's one
+fn parse_within(message: &str, plural: Option<&str>) -> Result<Vec<Part>> {
+    let mut parser = Parser {
+        chars: message.chars().collect(),
+        pos: 0,
+        unsupported: Vec::new(),
+    };
+    let parts = parser.message(plural, false)?;
+    if !parser.unsupported.is_empty() {
+        bail!(
+            "uses ICU features elm-i18n can't compile: {}",
+            parser.unsupported.join(", ")
+        );
+    }
+    Ok(parts)
+}
+
+/// The arguments of `messages` in order of first use, with their types: a plural's
+/// is an Int, and any other a String
+fn arguments(messages: &[(&String, Vec<Part>)]) -> Result<Vec<(String, ArgumentType)>> {
+    /// Each argument `parts` use, with the type the use gives it, if any
+    fn uses(parts: &[Part], found: &mut Vec<(String, Option<ArgumentType>)>) {
+        for part in parts {
+            match part {
+                Part::Text(_) => {}
+                Part::Argument(name) => found.push((name.clone(), None)),
+                Part::Count(name) => found.push((name.clone(), Some(ArgumentType::Int))),
+                Part::Plural {
+                    argument, branches, ..
+                } => {
+                    found.push((argument.clone(), Some(ArgumentType::Int)));
+                    for (_, branch) in branches {
+                        uses(branch, found);
+                    }
+                }
+                Part::Select { argument, branches } => {
+                    found.push((argument.clone(), Some(ArgumentType::String)));
+                    for (_, branch) in branches {
+                        uses(branch, found);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut arguments: Vec<(String, Option<ArgumentType>)> = Vec::new();
+    for (lang, parts) in messages {
+        let mut found = Vec::new();
+        uses(parts, &mut found);
+        for (name, kind) in found {
+            match arguments.iter_mut().find(|(n, _)| *n == name) {
+                None => arguments.push((name, kind)),
+                Some((_, known)) => match (*known, kind) {
+                    (Some(a), Some(b)) if a != b => bail!(
+                        "{} message uses `{}` as both a plural and a select argument",
+                        lang.to_uppercase(),
+                        name
+                    ),
+                    (None, kind) => *known = kind,
+                    _ => {}
+                },
+            }
+        }
+    }
+    Ok(arguments
+        .into_iter()
+        .map(|(name, kind)| (name, kind.unwrap_or(ArgumentType::String)))
+        .collect())
+}
+
+/// `lines` indented by `width` spaces, blank lines left empty
+fn indented(lines: Vec<String>, width: usize) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| {
+            if line.is_empty() {
+                line
+            } else {
+                format!("{}{}", " ".repeat(width), line)
+            }
+        })
+        .collect()
+}
+
+/// The Elm expression for `parts` in `lang`, as lines indented from column 0
+///
+/// Text around a plural or select goes into each of its branches, so every
+/// branch reads as a whole sentence.
+fn expression(parts: &[Part], types: &[(String, ArgumentType)], lang: &str) -> Result<Vec<String>> {
+    let Some(at) = parts
+        .iter()
+        .position(|part| matches!(part, Part::Plural { .. } | Part::Select { .. }))
+    else {
+        return Ok(vec![concatenation(parts, types)]);
+    };
+    let branch = |branch: &[Part]| -> Result<Vec<String>> {
+        let whole = [&parts[..at], branch, &parts[at + 1..]].concat();
+        expression(&whole, types, lang)
+    };
+
+    let mut lines = Vec::new();
+    match &parts[at] {
+        Part::Select { argument, branches } => {
+            if branches.len() == 1 {
+                return branch(&branches[0].1);
+            }
+            lines.push(format!("case {} of", argument));
+            let cases = branches.iter().filter(|(key, _)| key != "other");
+            let other = branches.iter().filter(|(key, _)| key == "other");
+            for (i, (key, parts)) in cases.chain(other).enumerate() {
+                if i > 0 {
+                    lines.push(String::new());
+                }
+                let pattern = if key == "other" {
+                    "_".to_string()
+                } else {
+                    format!("\"{}\"", escape(key))
+                };
+                lines.push(format!("    {} ->", pattern));
+                lines.extend(indented(branch(parts)?, 8));
+            }
+        }
+        Part::Plural {
+            argument,
+            branches,
+            ordinal,
+        } => {
+            if *ordinal {
+                lines.push(plural::ORDINAL_MARKER.to_string());
+            }
+            let given = |selector: &Selector| {
+                branches
+                    .iter()
+                    .find(|(s, _)| s == selector)
+                    .map(|(_, parts)| parts)
+            };
+            let category = |name: &str| given(&Selector::Category(name.to_string()));
+            let mut conditions: Vec<(String, &Vec<Part>)> = branches
+                .iter()
+                .filter_map(|(selector, parts)| match selector {
+                    Selector::Exact(n) => Some((format!("{} == {}", argument, n), parts)),
+                    Selector::Category(_) => None,
+                })
+                .collect();
+            let uses_categories = branches
+                .iter()
+                .any(|(s, _)| matches!(s, Selector::Category(c) if c != "other"));
+            let mut rest = category("other");
+            if uses_categories {
+                let found = if *ordinal {
+                    plural::ordinal_rules(lang)
+                } else {
+                    plural::rules(lang)
+                };
+                let Some(rules) = found else {
+                    bail!(
+                        "{} message: elm-i18n doesn't know the {} rules of {}; use only `=N` and `other` branches, or write the function with add-fn",
+                        lang.to_uppercase(),
+                        if *ordinal { "ordinal" } else { "plural" },
+                        lang
+                    );
+                };
+                for (name, condition) in rules.conditions {
+                    if let Some(parts) = category(name) {
+                        conditions.push((condition.replace("{n}", argument), parts));
+                    }
+                }
+                rest = category(rules.fallback).or(rest);
+            }
+            let Some(rest) = rest else {
+                bail!(
+                    "{} plural has no branch for the counts the others don't take",
+                    lang.to_uppercase()
+                );
+            };
+
+            if conditions.is_empty() {
+                lines.extend(branch(rest)?);
+                return Ok(lines);
+            }
+            for (i, (condition, parts)) in conditions.iter().enumerate() {
+                if i == 0 {
+                    lines.push(format!("if {} then", condition));
+                } else {
+                    lines.push(String::new());
+                    lines.push(format!("else if {} then", condition));
+                }
+                lines.extend(indented(branch(parts)?, 4));
+            }
+            lines.push(String::new());
+            lines.push("else".to_string());
+            lines.extend(indented(branch(rest)?, 4));
+        }
+        _ => unreachable!(),
+    }
+    Ok(lines)
+}
+
+/// Text and simple arguments joined with `++`
+fn concatenation(parts: &[Part], types: &[(String, ArgumentType)]) -> String {
+    let shown = |name: &str| match types.iter().find(|(n, _)| n == name) {
+        Some((_, ArgumentType::Int)) => format!("String.fromInt {}", name),
+        _ => name.to_string(),
+    };
+    let mut pieces: Vec<String> = Vec::new();
+    let mut text = String::new();
+    for part in parts {
+        match part {
+            Part::Text(t) => {
+                text.push_str(t);
+                continue;
+            }
+            Part::Argument(name) | Part::Count(name) => {
+                if !text.is_empty() {
+                    pieces.push(format!("\"{}\"", escape(&std::mem::take(&mut text))));
+                }
+                pieces.push(shown(name));
+            }
+            Part::Plural { .. } | Part::Select { .. } => {}
+        }
+    }
+    if !text.is_empty() || pieces.is_empty() {
+        pieces.push(format!("\"{}\"", escape(&text)));
+    }
+    pieces.join(" ++ ")
+}
+
+/// The Elm function type and each language's implementation for a key whose value
+/// in each language is an ICU message, such as
+/// `{count, plural, =0 {No items} one {# item} other {# items}}`
+///
+/// Every implementation takes one record with the arguments of all the messages, in
+/// order of first use: a plural's as an Int and others as Strings, e.g.
+/// `{ count : Int } -> String`. A plural picks its branch with
+/// `if` and the language's rules for whole numbers; a select with `case`.
+pub fn compile(
+    messages: &HashMap<String, String>,
+    languages: &[String],
+) -> Result<(String, HashMap<String, String>)> {
+    let mut parsed = Vec::new();
+    for lang in languages {
+        let Some(message) = messages.get(lang) else {
+            continue;
+        };
+        match parse(message) {
+            Ok(parts) => parsed.push((lang, parts)),
+            Err(problem) => bail!("{} message {}", lang.to_uppercase(), problem),
+        }
+    }
+    let arguments = arguments(&parsed)?;
+    if arguments.is_empty() {
+        bail!("The messages have no arguments; add a plain string with `add` instead");
+    }
+    function(&parsed, &arguments)
+}
+
+/// The Elm function type and each language's implementation for a plural of
+/// `argument` given as the text of each of its branches in each language, like the
+/// branches of an ICU plural: `#` or `{argument}` is the count, and other
+/// `{name}`s are String arguments. With `ordinal` the categories are the ordinal
+/// ones, and each implementation starts with `plural::ORDINAL_MARKER`.
+pub fn compile_plural(
+    argument: &str,
+    branches: &[(String, Vec<(Selector, String)>)],
+    ordinal: bool,
+) -> Result<(String, HashMap<String, String>)> {
+    let mut parsed = Vec::new();
+    for (lang, texts) in branches {
+        let mut parts = Vec::new();
+        for (selector, text) in texts {
+            match parse_within(text, Some(argument)) {
+                Ok(branch) => parts.push((selector.clone(), branch)),
+                Err(problem) => bail!("{} value {}", lang.to_uppercase(), problem),
+            }
+        }
+        let plural = Part::Plural {
+            argument: argument.to_string(),
+            branches: parts,
+            ordinal,
+        };
+        parsed.push((lang, vec![plural]));
+    }
+    let arguments = arguments(&parsed)?;
+    function(&parsed, &arguments)
+}
+
+/// The type and implementations of a function of `arguments` for `parsed`
+fn function(
+    parsed: &[(&String, Vec<Part>)],
+    arguments: &[(String, ArgumentType)],
+) -> Result<(String, HashMap<String, String>)> {
+    let names: Vec<&str> = arguments.iter().map(|(name, _)| name.as_str()).collect();
+    let mut values = HashMap::new();
+    for (lang, parts) in parsed {
+        let body = expression(parts, arguments, lang)?;
+        let separator = if body.len() == 1 { " " } else { "\n" };
+        let value = format!(
+            "\\{{ {} }} ->{}{}",
+            names.join(", "),
+            separator,
+            body.join("\n")
+        );
+        values.insert(lang.to_string(), value);
+    }
+    let fields: Vec<String> = arguments
+        .iter()
+        .map(|(name, kind)| {
+            let kind = match kind {
+                ArgumentType::Int => "Int",
+                ArgumentType::String => "String",
+            };
+            format!("{} : {}", name, kind)
+        })
+        .collect();
+    Ok((format!("{{ {} }} -> String", fields.join(", ")), values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::add_translation_to_content;
+    use crate::parser::parse_i18n_content_with_record_name;
+    use crate::types::Translation;
+    use crate::validate::implementation_problems;
+    use std::path::Path;
+
+    fn compiled(messages: &[(&str, &str)]) -> Result<(String, HashMap<String, String>)> {
+        let languages: Vec<String> = messages.iter().map(|(l, _)| l.to_string()).collect();
+        let messages = messages
+            .iter()
+            .map(|(l, m)| (l.to_string(), m.to_string()))
+            .collect();
+        compile(&messages, &languages)
+    }
+
+    #[test]
+    fn compiles_representative_messages_into_a_module() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let messages = [
+            ("greeting", "Hello, {name}!", "Bonjour, {name} !"),
+            (
+                "itemCount",
+                "{count, plural, =0 {No items} one {# item} other {# items}}",
+                "{count, plural, =0 {Aucun article} one {# article} other {# articles}}",
+            ),
+            (
+                "lastSeen",
+                "{name} was here {days, plural, =1 {yesterday} other {# days ago}}",
+                "{name} était là {days, plural, =1 {hier} other {il y a # jours}}",
+            ),
+            (
+                "invitation",
+                "{host} invited {guests, plural, one {a guest} other {# guests}} to {gender, select, female {her} male {his} other {their}} party",
+                "{host} a invité {guests, plural, one {# personne} other {# personnes}} à sa fête",
+            ),
+            (
+                "quoted",
+                "Type '{'{shortcut}'}' to search, it''s quick",
+                "Tapez '{'{shortcut}'}' pour chercher",
+            ),
+        ];
+
+        let mut module = "module I18n exposing (..)\n\n\ntype alias Translations =\n    { appName : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { appName = \"Shop\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { appName = \"Boutique\"\n    }\n".to_string();
+        for (key, en, fr) in messages {
+            let (type_signature, values) = compiled(&[("en", en), ("fr", fr)]).unwrap();
+            for value in values.values() {
+                assert_eq!(
+                    implementation_problems(&type_signature, value),
+                    Vec::<String>::new()
+                );
+            }
+            let translation = Translation {
+                key: key.to_string(),
+                values,
+                is_function: true,
+                type_signature: Some(type_signature),
+            };
+            module = add_translation_to_content(&module, &translation, "Translations", &languages)
+                .unwrap();
+        }
+
+        let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/icu/I18n.elm");
+        assert_eq!(module, std::fs::read_to_string(expected).unwrap());
+        let parsed =
+            parse_i18n_content_with_record_name(&module, "Translations", &languages).unwrap();
+        assert_eq!(parsed.translations.len(), 6);
+        assert!(parsed.translations["invitation"].is_function);
+    }
+
+    #[test]
+    fn lists_what_it_cannot_compile() {
+        let problem = |message: &str| compiled(&[("en", message)]).unwrap_err().to_string();
+        assert_eq!(
+            problem("{rank, selectordinal, one {#st} other {#th}} for {price, number, ::currency/EUR} on {day, date, short}"),
+            "EN message uses ICU features elm-i18n can't compile: `{rank, selectordinal, ...}` (ordinals like 1st and 2nd), `{price, number}` (formatting; pass the formatted text as an argument), `{day, date}` (formatting; pass the formatted text as an argument)"
+        );
+        assert_eq!(
+            problem("{count, plural, offset:1 other {#}}"),
+            "EN message uses ICU features elm-i18n can't compile: `offset:` in `{count, plural, ...}`"
+        );
+        assert_eq!(
+            problem("{count, plural, one {# item}}"),
+            "EN message has no `other` branch in `{count, plural, ...}`, which ICU requires"
+        );
+        assert_eq!(
+            problem("{count, plural, other {# items}"),
+            "EN message has a `{count, plural, ...}` that never closes"
+        );
+        assert!(compiled(&[("mt", "{n, plural, one {#} other {#}}")])
+            .unwrap_err()
+            .to_string()
+            .contains("doesn't know the plural rules of mt"));
+        assert!(compiled(&[("mt", "{n, plural, =1 {one} other {#}}")]).is_ok());
+    }
+}
@@ -0,0 +1,879 @@
+use anyhow::Result;
+use regex::Regex;
+use std::ops::Range;
+
+use crate::languages::{language_variant, translations_record_name};
+use crate::timing::{self, Phase};
+
+/// The helper functions with a branch per language
+const DISPATCHERS: [&str; 3] = ["languageToString", "stringToLanguage", "translations"];
+
+/// Result of adding a language to a module
+#[derive(Debug)]
+pub struct LanguageEdit {
+    pub content: String,
+    /// Parts of the module that weren't found, so weren't updated (e.g., "stringToLanguage")
+    pub missed: Vec<String>,
+}
+
+/// A branch of a `case` expression
+#[derive(Debug)]
+struct CaseBranch {
+    /// The pattern before `->` (e.g., `FR` or `"fr"`)
+    pattern: String,
+    /// Byte offset of the branch's first line, including comments right above it
+    start: usize,
+    /// Byte offset of the end of the branch's last line, before its newline
+    end: usize,
+    /// Whether the body is on the pattern's line (`FR -> "fr"`)
+    inline: bool,
+    /// Index of the pattern's line
+    line: usize,
+    /// The code after `->`, on one line and without comments
+    body: String,
+}
+
+/// The branches of the `case` expression a function starts with
+#[derive(Debug)]
+struct CaseExpression {
+    branches: Vec<CaseBranch>,
+    indent: usize,
+    body_indent: usize,
+    /// What goes between two branches: "\n\n" when they're separated by blank lines
+    separator: &'static str,
+}
+
+/// Add `new_lang` to a module that already has `from_lang`
+///
+/// Adds a variant to the `Language` type after `from_lang`'s, a copy of
+/// `from_lang`'s record, and a branch to `languageToString`, `stringToLanguage` and
+/// `translations`. Comments are skipped when looking for variants and `case`
+/// branches, so documented variants and branches are edited like bare ones.
+pub fn add_language_to_content(
+    content: &str,
+    from_lang: &str,
+    new_lang: &str,
+    record_name: &str,
+) -> LanguageEdit {
+    let _edit = timing::phase(Phase::Edit);
+    let from_variant = language_variant(from_lang);
+    let new_variant = language_variant(new_lang);
+    let from_record = translations_record_name(from_lang);
+    let new_record = translations_record_name(new_lang);
+    let mut content = content.to_string();
+    let mut missed = Vec::new();
+
+    match insert_variant(&content, &from_variant, &new_variant) {
+        Some(updated) => content = updated,
+        None => missed.push("the Language type".to_string()),
+    }
+
+    match duplicate_record(&content, &from_record, &new_record, record_name) {
+        Some(updated) => content = updated,
+        None => missed.push(format!("the {} record", from_record)),
+    }
+
+    for function in DISPATCHERS {
+        let (anchor, pattern, body) = match function {
+            "languageToString" => (
+                from_variant.clone(),
+                new_variant.clone(),
+                format!("\"{}\"", new_lang),
+            ),
+            "stringToLanguage" => (
+                format!("\"{}\"", from_lang),
+                format!("\"{}\"", new_lang),
+                new_variant.clone(),
+            ),
+            _ => (
+                from_variant.clone(),
+                new_variant.clone(),
+                new_record.clone(),
+            ),
+        };
+        match insert_branch(&content, function, &anchor, &pattern, &body) {
+            Some(updated) => content = updated,
+            None => missed.push(function.to_string()),
+        }
+    }
+
+    LanguageEdit { content, missed }
+}
+
+/// What `dispatcher_check` found in the function that picks a language's record,
+/// or `round_trip_check` in `stringToLanguage`
+#[derive(Debug)]
+pub struct DispatcherCheck {
+    /// The function's name, usually `translations`
+    pub function: String,
+    /// 1-based line of its type annotation
+    pub line: usize,
+    /// How many `Language` variants it was checked against
+    pub variants: usize,
+    pub problems: Vec<DispatcherProblem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatcherProblem {
+    /// 1-based line the problem points at
+    pub line: usize,
+    pub message: String,
+    pub suggestion: String,
+}
+
+/// Check the `Language -> record_name` function (`translations` in a default
+/// module) against the `Language` type: every variant needs a branch of its own
+/// that returns the variant's `translationsXx` record, and every branch needs a
+/// variant. Languages without a variant in the file come from `languages`.
+///
+/// None when the module has no such function or it isn't a `case` on the language.
+pub fn dispatcher_check(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Option<DispatcherCheck> {
+    let code = blank_comments(source);
+    let lines = line_spans(&code);
+    let annotation = Regex::new(&format!(
+        r"^(\w+)\s*:\s*Language\s*->\s*{}\s*$",
+        regex::escape(record_name)
+    ))
+    .unwrap();
+    let (annotation_line, function) = lines.iter().enumerate().find_map(|(i, &(start, end))| {
+        annotation
+            .captures(&code[start..end])
+            .map(|captures| (i, captures[1].to_string()))
+    })?;
+    let case = case_expression(source, &function)?;
+
+    // The record each variant should map to
+    let mut expected: Vec<(String, String)> = languages
+        .iter()
+        .map(|lang| (language_variant(lang), translations_record_name(lang)))
+        .collect();
+    if let Some((_, variants)) = language_type_variants(&code, &lines) {
+        expected = variants
+            .into_iter()
+            .map(|variant| {
+                let record = expected
+                    .iter()
+                    .find(|(name, _)| *name == variant.name)
+                    .map(|(_, record)| record.clone())
+                    .unwrap_or_else(|| {
+                        translations_record_name(&variant.name.to_lowercase().replace('_', "-"))
+                    });
+                (variant.name, record)
+            })
+            .collect();
+    }
+
+    let mut problems = Vec::new();
+    let default = case.branches.iter().find(|b| b.pattern == "_");
+    for (variant, record) in &expected {
+        let branches: Vec<&CaseBranch> = case
+            .branches
+            .iter()
+            .filter(|b| b.pattern == *variant)
+            .collect();
+        match branches.as_slice() {
+            [] => problems.push(DispatcherProblem {
+                line: annotation_line + 1,
+                message: match default {
+                    Some(default) => format!(
+                        "`{}` has no branch for `{}`, so it gets `{}` from the `_` branch",
+                        function, variant, default.body
+                    ),
+                    None => format!(
+                        "`{}` has no branch for `{}`, so its `case` isn't exhaustive",
+                        function, variant
+                    ),
+                },
+                suggestion: format!("Add a `{} -> {}` branch", variant, record),
+            }),
+            [branch, duplicates @ ..] => {
+                if branch.body != *record {
+                    problems.push(DispatcherProblem {
+                        line: branch.line + 1,
+                        message: format!(
+                            "`{}` maps `{}` to `{}` instead of `{}`",
+                            function, variant, branch.body, record
+                        ),
+                        suggestion: format!("Change the branch to `{} -> {}`", variant, record),
+                    });
+                }
+                for duplicate in duplicates {
+                    problems.push(DispatcherProblem {
+                        line: duplicate.line + 1,
+                        message: format!(
+                            "`{}` has a second branch for `{}` (the first is on line {})",
+                            function,
+                            variant,
+                            branch.line + 1
+                        ),
+                        suggestion: "Remove it; Elm rejects redundant patterns".to_string(),
+                    });
+                }
+            }
+        }
+    }
+    for branch in &case.branches {
+        if branch.pattern != "_"
+            && !expected
+                .iter()
+                .any(|(variant, _)| *variant == branch.pattern)
+        {
+            problems.push(DispatcherProblem {
+                line: branch.line + 1,
+                message: format!(
+                    "`{}` has a branch for `{}`, which isn't a `Language` variant",
+                    function, branch.pattern
+                ),
+                suggestion: format!(
+                    "Remove the branch, or add `{}` to `type Language`",
+                    branch.pattern
+                ),
+            });
+        }
+    }
+    problems.sort_by_key(|p| p.line);
+
+    Some(DispatcherCheck {
+        function,
+        line: annotation_line + 1,
+        variants: expected.len(),
+        problems,
+    })
+}
+
+/// Check that `stringToLanguage` turns what `languageToString` gives for each
+/// `Language` variant back into that variant, so no language silently falls back
+/// to the default when it's stored and read again
+///
+/// Only string literal branches are compared. None when the module lacks either
+/// function, or `stringToLanguage` doesn't `case` on its argument as it is (e.g.,
+/// `case String.toLower str of`), since its branches can't be read off the source
+/// then.
+pub fn round_trip_check(source: &str, languages: &[String]) -> Option<DispatcherCheck> {
+    let code = blank_comments(source);
+    let lines = line_spans(&code);
+    let scrutinee = Regex::new(r"(?m)^stringToLanguage\s+(\w+)\s*=\s*case\s+(\w+)\s+of\s*$")
+        .unwrap()
+        .captures(&code)?;
+    if scrutinee[1] != scrutinee[2] {
+        return None;
+    }
+    let to_string = case_expression(source, "languageToString")?;
+    let from_string = case_expression(source, "stringToLanguage")?;
+    let annotation = Regex::new(r"^stringToLanguage\s*:").unwrap();
+    let line = lines
+        .iter()
+        .position(|&(start, end)| annotation.is_match(&code[start..end]))
+        .or_else(|| from_string.branches.first().map(|b| b.line))?;
+
+    let variants: Vec<String> = match language_type_variants(&code, &lines) {
+        Some((_, variants)) => variants.into_iter().map(|v| v.name).collect(),
+        None => languages
+            .iter()
+            .map(|lang| language_variant(lang))
+            .collect(),
+    };
+    let literal = Regex::new(r#"^"[^"\\]*"$"#).unwrap();
+    let stored = |variant: &str| {
+        to_string
+            .branches
+            .iter()
+            .find(|b| b.pattern == variant)
+            .or_else(|| to_string.branches.iter().find(|b| b.pattern == "_"))
+            .map(|b| b.body.trim())
+            .filter(|body| literal.is_match(body))
+    };
+    let fallback = from_string.branches.iter().find(|b| b.pattern == "_");
+
+    let mut problems = Vec::new();
+    for variant in &variants {
+        let Some(text) = stored(variant) else {
+            continue;
+        };
+        let branch = from_string.branches.iter().find(|b| b.pattern == text);
+        let read_back = branch.or(fallback).map(|b| b.body.trim());
+        if read_back == Some(variant.as_str()) {
+            continue;
+        }
+        let message = match (branch, fallback) {
+            (Some(branch), _) => format!(
+                "`languageToString` turns `{}` into {}, which `stringToLanguage` reads as `{}`",
+                variant,
+                text,
+                branch.body.trim()
+            ),
+            (None, Some(fallback)) => format!(
+                "`languageToString` turns `{}` into {}, which `stringToLanguage` has no branch for, so it reads back as `{}`",
+                variant,
+                text,
+                fallback.body.trim()
+            ),
+            (None, None) => format!(
+                "`languageToString` turns `{}` into {}, which `stringToLanguage` has no branch for",
+                variant, text
+            ),
+        };
+        problems.push(DispatcherProblem {
+            line: branch.map_or(line, |b| b.line) + 1,
+            message,
+            suggestion: match branch {
+                Some(_) => format!("Change the branch to `{} -> {}`", text, variant),
+                None => format!(
+                    "Add a `{} -> {}` branch to `stringToLanguage`",
+                    text, variant
+                ),
+            },
+        });
+    }
+    problems.sort_by_key(|p| p.line);
+
+    Some(DispatcherCheck {
+        function: "stringToLanguage".to_string(),
+        line: line + 1,
+        variants: variants.len(),
+        problems,
+    })
+}
+
+/// Add `new_variant` after `anchor` (or after the last variant) in `type Language`
+fn insert_variant(source: &str, anchor: &str, new_variant: &str) -> Option<String> {
+    let code = blank_comments(source);
+    let lines = line_spans(&code);
+    let (type_line, variants) = language_type_variants(&code, &lines)?;
+
+    let variant = variants
+        .iter()
+        .rev()
+        .find(|v| v.name == anchor)
+        .or_else(|| variants.last())?;
+    let mut updated = source.to_string();
+    if variant.leads && variant.line > type_line {
+        // `    | FR -- French` gets a line of its own below it
+        let (start, end) = lines[variant.line];
+        let text = &source[start..end];
+        let indent = &text[..text.len() - text.trim_start().len()];
+        updated.insert_str(end, &format!("\n{}| {}", indent, new_variant));
+    } else {
+        updated.insert_str(variant.name_end, &format!(" | {}", new_variant));
+    }
+    Some(updated)
+}
+
+/// A variant of `type Language`
+#[derive(Debug)]
+struct Variant {
+    name: String,
+    /// Index of its line
+    line: usize,
+    /// Byte offset of the end of its name
+    name_end: usize,
+    /// Whether its line starts with it (`    | FR`)
+    leads: bool,
+}
+
+/// The line of `type Language` in comment-blanked `code`, and its variants
+fn language_type_variants(code: &str, lines: &[(usize, usize)]) -> Option<(usize, Vec<Variant>)> {
+    let type_line = lines.iter().position(|&(start, end)| {
+        let line = &code[start..end];
+        line.strip_prefix("type Language")
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    })?;
+
+    let variant = Regex::new(r"[=|]\s*([A-Z][A-Za-z0-9_]*)").unwrap();
+    let mut variants = Vec::new();
+    for (i, &(start, end)) in lines.iter().enumerate().skip(type_line) {
+        let line = &code[start..end];
+        if i > type_line && !line.trim().is_empty() && !line.starts_with(char::is_whitespace) {
+            break;
+        }
+        for captures in variant.captures_iter(line) {
+            let name = captures.get(1).unwrap();
+            variants.push(Variant {
+                name: name.as_str().to_string(),
+                line: i,
+                name_end: start + name.end(),
+                leads: line.trim_start().len() == line.len() - captures.get(0).unwrap().start(),
+            });
+        }
+    }
+    Some((type_line, variants))
+}
+
+/// Add a copy of the `from_record` record named `new_record` right after it
+fn duplicate_record(
+    source: &str,
+    from_record: &str,
+    new_record: &str,
+    record_name: &str,
+) -> Option<String> {
+    let code = blank_comments(source);
+    let annotation = format!("{} : {}", from_record, record_name);
+    let start = line_spans(&code)
+        .into_iter()
+        .map(|(start, _)| start)
+        .find(|&start| code[start..].starts_with(&annotation))?;
+    let end = start + find_closing_brace(&code[start..])? + 1;
+
+    let copy = source[start..end].replace(from_record, new_record);
+    let mut updated = source.to_string();
+    updated.insert_str(end, &format!("\n\n\n{}", copy));
+    Some(updated)
+}
+
+/// Add a `pattern -> body` branch to `function`'s `case`: after the `anchor`
+/// branch if there is one, otherwise before the `_` branch, otherwise last
+fn insert_branch(
+    source: &str,
+    function: &str,
+    anchor: &str,
+    pattern: &str,
+    body: &str,
+) -> Option<String> {
+    let case = case_expression(source, function)?;
+    insert_case_branch(source, &case, anchor, pattern, body)
+}
+
+/// Add a `pattern -> body` branch to the first `case` within `lines` of `source`,
+/// such as the lines of a translation's field, before its `_` branch if it has one
+///
+/// The branch is laid out like the others: on one line when they all are, and
+/// separated by a blank line when they are.
+pub fn add_case_branch(
+    source: &str,
+    lines: Range<usize>,
+    pattern: &str,
+    body: &str,
+) -> Result<String> {
+    let code = blank_comments(source);
+    let spans = line_spans(&code);
+    let end = lines.end.min(spans.len());
+    let case_line = (lines.start..end)
+        .find(|&i| is_case_of(&code[spans[i].0..spans[i].1]))
+        .ok_or_else(|| anyhow::anyhow!("has no `case ... of` to add a branch to"))?;
+    let case = case_at(source, &code, &spans[..end], case_line)
+        .ok_or_else(|| anyhow::anyhow!("has a `case` whose branches couldn't be read"))?;
+
+    let squashed = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if case
+        .branches
+        .iter()
+        .any(|branch| squashed(&branch.pattern) == squashed(pattern))
+    {
+        anyhow::bail!("already has a `{}` branch", pattern.trim());
+    }
+    insert_case_branch(source, &case, "", pattern.trim(), body.trim())
+        .ok_or_else(|| anyhow::anyhow!("has a `case` whose branches couldn't be read"))
+}
+
+/// `source` with a branch added to `case`, after the `anchor` branch if there is
+/// one, otherwise before the `_` branch, otherwise last
+fn insert_case_branch(
+    source: &str,
+    case: &CaseExpression,
+    anchor: &str,
+    pattern: &str,
+    body: &str,
+) -> Option<String> {
+    let indent = " ".repeat(case.indent);
+    let branch = if case.branches.iter().all(|b| b.inline) {
+        format!("{}{} -> {}", indent, pattern, body)
+    } else {
+        format!(
+            "{}{} ->\n{}{}",
+            indent,
+            pattern,
+            " ".repeat(case.body_indent),
+            body
+        )
+    };
+
+    let mut updated = source.to_string();
+    if let Some(anchor) = case.branches.iter().find(|b| b.pattern == anchor) {
+        updated.insert_str(anchor.end, &format!("{}{}", case.separator, branch));
+    } else if let Some(default) = case.branches.iter().find(|b| b.pattern == "_") {
+        updated.insert_str(default.start, &format!("{}{}", branch, case.separator));
+    } else {
+        let last = case.branches.last()?;
+        updated.insert_str(last.end, &format!("{}{}", case.separator, branch));
+    }
+    Some(updated)
+}
+
+/// The branches of the `case` that the top-level `function` is defined with
+fn case_expression(source: &str, function: &str) -> Option<CaseExpression> {
+    let code = blank_comments(source);
+    let lines = line_spans(&code);
+    let definition = Regex::new(&format!(r"^{}(\s+\w+)*\s*=", regex::escape(function))).unwrap();
+    let code_line = |i: usize| &code[lines[i].0..lines[i].1];
+
+    let definition_line = (0..lines.len()).find(|&i| definition.is_match(code_line(i)))?;
+    let case_line = (definition_line..lines.len())
+        .take_while(|&i| {
+            i == definition_line
+                || code_line(i).trim().is_empty()
+                || code_line(i).starts_with(char::is_whitespace)
+        })
+        .find(|&i| is_case_of(code_line(i)))?;
+    case_at(source, &code, &lines, case_line)
+}
+
+/// Whether a line of code ends with the start of a `case ... of`
+fn is_case_of(code: &str) -> bool {
+    let line = code.trim();
+    line.ends_with(" of") && (line.starts_with("case ") || line.contains(" case "))
+}
+
+/// The branches of the `case` whose `case ... of` is on `case_line`, looking no
+/// further than `lines`
+fn case_at(
+    source: &str,
+    code: &str,
+    lines: &[(usize, usize)],
+    case_line: usize,
+) -> Option<CaseExpression> {
+    let code_line = |i: usize| &code[lines[i].0..lines[i].1];
+    let source_line = |i: usize| &source[lines[i].0..lines[i].1];
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+
+    // Lines of the case expression with code on them, and their indentation
+    let body: Vec<usize> = (case_line + 1..lines.len())
+        .filter(|&i| !code_line(i).trim().is_empty())
+        .collect();
+    let branch_indent = indent_of(code_line(*body.first()?));
+    let body: Vec<usize> = body
+        .into_iter()
+        .take_while(|&i| indent_of(code_line(i)) >= branch_indent)
+        .collect();
+    let last_line = *body.last()?;
+
+    let mut branches: Vec<CaseBranch> = Vec::new();
+    let mut body_indent = None;
+    let pattern_lines: Vec<usize> = body
+        .iter()
+        .copied()
+        .filter(|&i| indent_of(code_line(i)) == branch_indent)
+        .collect();
+    for (n, &line) in pattern_lines.iter().enumerate() {
+        let (pattern, rest) = code_line(line).trim().split_once("->")?;
+        let inline = !rest.trim().is_empty();
+
+        // Comments right above the pattern belong to the branch
+        let mut first = line;
+        while first > case_line + 1 && is_comment_line(code_line(first - 1), source_line(first - 1))
+        {
+            first -= 1;
+        }
+
+        // The branch's own lines are the more-indented ones before the next branch,
+        // comments included
+        let next = pattern_lines.get(n + 1).copied().unwrap_or(last_line + 1);
+        let mut end_line = line;
+        for i in line + 1..next {
+            let text = source_line(i);
+            if !text.trim().is_empty() && indent_of(text) > branch_indent {
+                end_line = i;
+                if body_indent.is_none() && !inline && !code_line(i).trim().is_empty() {
+                    body_indent = Some(indent_of(code_line(i)));
+                }
+            }
+        }
+
+        let body = code[lines[line].0..lines[end_line].1]
+            .split_once("->")
+            .map_or("", |(_, body)| body)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        branches.push(CaseBranch {
+            pattern: pattern.trim().to_string(),
+            start: lines[first].0,
+            end: lines[end_line].1,
+            inline,
+            line,
+            body,
+        });
+    }
+
+    let blank_between = branches.windows(2).any(|pair| {
+        source[pair[0].end..pair[1].start]
+            .lines()
+            .skip(1)
+            .any(|line| line.trim().is_empty())
+    });
+    let separator = if blank_between || branches.len() < 2 {
+        "\n\n"
+    } else {
+        "\n"
+    };
+
+    Some(CaseExpression {
+        branches,
+        indent: branch_indent,
+        body_indent: body_indent.unwrap_or(branch_indent + 4),
+        separator,
+    })
+}
+
+fn is_comment_line(code: &str, source: &str) -> bool {
+    code.trim().is_empty() && !source.trim().is_empty()
+}
+
+/// Start and end byte offsets of each line, without the newline
+fn line_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n').trim_end_matches('\r');
+        spans.push((start, start + content.len()));
+        start += line.len();
+    }
+    spans
+}
+
+/// The source with its `--` and `{- -}` comments replaced by spaces, byte for
+/// byte, so offsets into it are offsets into the source. Strings and character
+/// literals are kept, along with anything in them that looks like a comment.
+pub fn blank_comments(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.char_indices();
+    let mut depth = 0;
+    let mut line_comment = false;
+    let mut string: Option<&str> = None;
+    let blank = |output: &mut String, c: char| {
+        for _ in 0..c.len_utf8() {
+            output.push(' ');
+        }
+    };
+
+    while let Some((i, c)) = chars.next() {
+        let rest = &source[i..];
+        if c == '\n' || c == '\r' {
+            line_comment = false;
+            output.push(c);
+        } else if line_comment {
+            blank(&mut output, c);
+        } else if depth > 0 {
+            if rest.starts_with("-}") || rest.starts_with("{-") {
+                depth += if c == '-' { -1 } else { 1 };
+                output.push_str("  ");
+                chars.next();
+            } else {
+                blank(&mut output, c);
+            }
+        } else if let Some(delimiter) = string {
+            if c == '\\' {
+                output.push(c);
+                if let Some((_, escaped)) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if rest.starts_with(delimiter) {
+                output.push_str(delimiter);
+                for _ in 1..delimiter.len() {
+                    chars.next();
+                }
+                string = None;
+            } else {
+                output.push(c);
+            }
+        } else if rest.starts_with("--") {
+            line_comment = true;
+            blank(&mut output, c);
+        } else if rest.starts_with("{-") {
+            depth = 1;
+            output.push_str("  ");
+            chars.next();
+        } else if rest.starts_with("\"\"\"") {
+            string = Some("\"\"\"");
+            output.push_str("\"\"\"");
+            chars.next();
+            chars.next();
+        } else if c == '"' {
+            string = Some("\"");
+            output.push(c);
+        } else if c == '\'' {
+            // A character literal such as '"' or '\''
+            output.push(c);
+            while let Some((_, next)) = chars.next() {
+                output.push(next);
+                if next == '\\' {
+                    if let Some((_, escaped)) = chars.next() {
+                        output.push(escaped);
+                    }
+                } else if next == '\'' || next == '\n' {
+                    break;
+                }
+            }
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// Position of the closing brace that ends the first record in `text`
+fn find_closing_brace(text: &str) -> Option<usize> {
+    let mut brace_count = 0;
+    let mut found_open = false;
+    for (i, c) in text.char_indices() {
+        if c == '{' {
+            brace_count += 1;
+            found_open = true;
+        } else if c == '}' {
+            brace_count -= 1;
+            if found_open && brace_count == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTED: &str = include_str!("../tests/fixtures/commented-cases/I18n.elm");
+
+    #[test]
+    fn edits_commented_variants_and_case_branches() {
+        let edit = add_language_to_content(COMMENTED, "fr", "de", "Translations");
+        assert!(edit.missed.is_empty(), "{:?}", edit.missed);
+        assert_eq!(
+            edit.content,
+            include_str!("../tests/fixtures/commented-cases/I18n.added-de.elm")
+        );
+    }
+
+    #[test]
+    fn adds_a_branch_to_a_case_within_some_lines() {
+        let source = "    , plural =\n        \\count ->\n            case count of\n                -- Exactly one\n                One ->\n                    \"one\"\n\n                _ ->\n                    \"many\"\n    , next = \"x\"\n";
+        assert_eq!(
+            add_case_branch(source, 0..9, "Few", "\"a few\"").unwrap(),
+            "    , plural =\n        \\count ->\n            case count of\n                -- Exactly one\n                One ->\n                    \"one\"\n\n                Few ->\n                    \"a few\"\n\n                _ ->\n                    \"many\"\n    , next = \"x\"\n"
+        );
+
+        let inline = "    { plural = \\n -> case n of\n        One -> \"un\"\n        Many -> \"des\"\n    }\n";
+        assert_eq!(
+            add_case_branch(inline, 0..3, " Few ", " \"quelques\"").unwrap(),
+            "    { plural = \\n -> case n of\n        One -> \"un\"\n        Many -> \"des\"\n        Few -> \"quelques\"\n    }\n"
+        );
+
+        let error = |source: &str, lines| {
+            add_case_branch(source, lines, "One", "\"1\"")
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error(inline, 0..3), "already has a `One` branch");
+        assert_eq!(
+            error("    , greet = \\name -> name\n", 0..1),
+            "has no `case ... of` to add a branch to"
+        );
+        // The case is only looked for within the lines given
+        assert!(add_case_branch(inline, 3..4, "Few", "\"x\"").is_err());
+    }
+
+    #[test]
+    fn cross_checks_the_dispatcher_against_the_language_type() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let check = dispatcher_check(COMMENTED, "Translations", &languages).unwrap();
+        assert_eq!(check.function, "translations");
+        assert_eq!(check.variants, 2);
+        assert!(check.problems.is_empty(), "{:?}", check.problems);
+
+        let source = "type Language\n    = EN\n    | FR\n    | DE\n\n\ntranslations : Language -> Translations\ntranslations lang =\n    case lang of\n        EN ->\n            translationsEn\n\n        FR ->\n            translationsEn\n\n        ES ->\n            translationsEs\n";
+        let messages: Vec<(usize, String)> = dispatcher_check(source, "Translations", &languages)
+            .unwrap()
+            .problems
+            .into_iter()
+            .map(|p| (p.line, p.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    7,
+                    "`translations` has no branch for `DE`, so its `case` isn't exhaustive"
+                        .to_string()
+                ),
+                (
+                    13,
+                    "`translations` maps `FR` to `translationsEn` instead of `translationsFr`"
+                        .to_string()
+                ),
+                (
+                    16,
+                    "`translations` has a branch for `ES`, which isn't a `Language` variant"
+                        .to_string()
+                ),
+            ]
+        );
+
+        assert!(dispatcher_check(
+            "translations = translationsEn\n",
+            "Translations",
+            &languages
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn finds_languages_that_dont_round_trip() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let check = round_trip_check(COMMENTED, &languages).unwrap();
+        assert!(check.problems.is_empty(), "{:?}", check.problems);
+
+        let source = "type Language\n    = EN\n    | FR\n    | DE\n    | ES\n\n\nlanguageToString : Language -> String\nlanguageToString lang =\n    case lang of\n        EN ->\n            \"en\"\n\n        FR ->\n            \"fr\"\n\n        DE ->\n            \"de\"\n\n        ES ->\n            \"es\"\n\n\nstringToLanguage : String -> Language\nstringToLanguage str =\n    case str of\n        \"fr\" ->\n            FR\n\n        \"es\" ->\n            DE\n\n        _ ->\n            EN\n";
+        let messages: Vec<(usize, String)> = round_trip_check(source, &languages)
+            .unwrap()
+            .problems
+            .into_iter()
+            .map(|p| (p.line, p.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    24,
+                    "`languageToString` turns `DE` into \"de\", which `stringToLanguage` has no branch for, so it reads back as `EN`"
+                        .to_string()
+                ),
+                (
+                    30,
+                    "`languageToString` turns `ES` into \"es\", which `stringToLanguage` reads as `DE`"
+                        .to_string()
+                ),
+            ]
+        );
+
+        // A case on a transformed string can't be compared
+        let lowered = source.replace("case str of", "case String.toLower str of");
+        assert!(round_trip_check(&lowered, &languages).is_none());
+    }
+
+    #[test]
+    fn ignores_comments_that_look_like_code() {
+        assert_eq!(
+            blank_comments("x = \"-- kept\" -- dropped {- a -}\ny = '\"' {- b\n -} z"),
+            "x = \"-- kept\"                   \ny = '\"'     \n    z"
+        );
+
+        let source = "type Language = EN | FR -- | DE\n\n\nf lang =\n    case lang of\n        EN -> 1\n        -- FR -> 3\n        FR -> 2\n";
+        let edit = add_language_to_content(source, "fr", "es", "Translations");
+        assert!(edit
+            .content
+            .starts_with("type Language = EN | FR | ES -- | DE\n"));
+        assert_eq!(
+            edit.missed,
+            vec![
+                "the translationsFr record",
+                "languageToString",
+                "stringToLanguage",
+                "translations"
+            ]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+/// ISO 639-1 language codes
+const ISO_639_1: &str = "aa ab ae af ak am an ar as av ay az ba be bg bh bi bm bn bo br bs ca ce \
+ch co cr cs cu cv cy da de dv dz ee el en eo es et eu fa ff fi fj fo fr fy ga gd gl gn gu gv ha he \
+hi ho hr ht hu hy hz ia id ie ig ii ik io is it iu ja jv ka kg ki kj kk kl km kn ko kr ks ku kv kw \
+ky la lb lg li ln lo lt lu lv mg mh mi mk ml mn mr ms mt my na nb nd ne ng nl nn no nr nv ny oc oj \
+om or os pa pi pl ps pt qu rm rn ro ru rw sa sc sd se sg si sk sl sm sn so sq sr ss st su sv sw ta \
+te tg th ti tk tl tn to tr ts tt tw ty ug uk ur uz ve vi vo wa wo xh yi yo za zh zu";
+
+/// Language names (in English and in the language itself) and ISO 639-2 codes people
+/// type instead of the two-letter code
+const ALIASES: &[(&str, &str)] = &[
+    ("english", "en"),
+    ("anglais", "en"),
+    ("eng", "en"),
+    ("french", "fr"),
+    ("francais", "fr"),
+    ("fra", "fr"),
+    ("fre", "fr"),
+    ("spanish", "es"),
+    ("espanol", "es"),
+    ("castellano", "es"),
+    ("spa", "es"),
+    ("german", "de"),
+    ("deutsch", "de"),
+    ("allemand", "de"),
+    ("deu", "de"),
+    ("ger", "de"),
+    ("italian", "it"),
+    ("italiano", "it"),
+    ("ita", "it"),
+    ("portuguese", "pt"),
+    ("portugues", "pt"),
+    ("por", "pt"),
+    ("dutch", "nl"),
+    ("nederlands", "nl"),
+    ("nld", "nl"),
+    ("dut", "nl"),
+    ("russian", "ru"),
+    ("rus", "ru"),
+    ("chinese", "zh"),
+    ("mandarin", "zh"),
+    ("zho", "zh"),
+    ("chi", "zh"),
+    ("japanese", "ja"),
+    ("nihongo", "ja"),
+    ("jpn", "ja"),
+    ("korean", "ko"),
+    ("kor", "ko"),
+    ("arabic", "ar"),
+    ("ara", "ar"),
+    ("polish", "pl"),
+    ("polski", "pl"),
+    ("pol", "pl"),
+    ("swedish", "sv"),
+    ("svenska", "sv"),
+    ("swe", "sv"),
+    ("danish", "da"),
+    ("dansk", "da"),
+    ("dan", "da"),
+    ("norwegian", "no"),
+    ("norsk", "no"),
+    ("nor", "no"),
+    ("finnish", "fi"),
+    ("suomi", "fi"),
+    ("fin", "fi"),
+    ("turkish", "tr"),
+    ("turkce", "tr"),
+    ("tur", "tr"),
+    ("greek", "el"),
+    ("ell", "el"),
+    ("hebrew", "he"),
+    ("heb", "he"),
+    ("hindi", "hi"),
+    ("hin", "hi"),
+    ("czech", "cs"),
+    ("cestina", "cs"),
+    ("ces", "cs"),
+    ("ukrainian", "uk"),
+    ("ukr", "uk"),
+    ("romanian", "ro"),
+    ("romana", "ro"),
+    ("ron", "ro"),
+    ("hungarian", "hu"),
+    ("magyar", "hu"),
+    ("hun", "hu"),
+    ("indonesian", "id"),
+    ("ind", "id"),
+    ("vietnamese", "vi"),
+    ("vie", "vi"),
+    ("thai", "th"),
+    ("tha", "th"),
+    ("catalan", "ca"),
+    ("catala", "ca"),
+    ("cat", "ca"),
+];
+
+/// A language code cleaned up from user input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedLanguage {
+    pub code: String,
+    /// What was done to the input, or why the code looks suspicious
+    pub note: Option<LanguageNote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageNote {
+    /// A name or alias was mapped to its code (e.g., "Français" → "fr")
+    Converted(String),
+    /// The code looks plausible but isn't one this tool knows
+    Unknown(String),
+}
+
+/// Map a language code, name or alias to a lowercase ISO 639-1 code, keeping any
+/// region or script subtag in its conventional case ("pt_br" → "pt-BR")
+///
+/// Fails on input that can't be a language code (it would make a malformed
+/// `Language` variant); accepts unknown 2–3 letter codes with a note.
+pub fn normalize_language_code(input: &str) -> Result<NormalizedLanguage, String> {
+    let trimmed = input.trim();
+
+    if let Some((base, subtags)) = trimmed.split_once(['-', '_']) {
+        let mut normalized = normalize_language_code(base)?;
+        for subtag in subtags.split(['-', '_']) {
+            normalized.code.push('-');
+            normalized
+                .code
+                .push_str(&normalize_subtag(subtag).ok_or_else(|| {
+                    format!(
+                        "'{}' isn't a region or script subtag in '{}'; use a code like \"pt-BR\"",
+                        subtag, trimmed
+                    )
+                })?);
+        }
+        if let Some(LanguageNote::Converted(_)) = normalized.note {
+            normalized.note = Some(LanguageNote::Converted(format!(
+                "'{}' → {}",
+                trimmed, normalized.code
+            )));
+        }
+        return Ok(normalized);
+    }
+    let folded = fold_accents(&trimmed.to_lowercase());
+
+    if is_iso_639_1(&folded) {
+        return Ok(NormalizedLanguage {
+            code: folded,
+            note: None,
+        });
+    }
+
+    if let Some((_, code)) = ALIASES.iter().find(|(alias, _)| *alias == folded) {
+        return Ok(NormalizedLanguage {
+            code: code.to_string(),
+            note: Some(LanguageNote::Converted(format!("'{}' → {}", trimmed, code))),
+        });
+    }
+
+    let plausible =
+        (2..=3).contains(&folded.len()) && folded.chars().all(|c| c.is_ascii_lowercase());
+    if plausible {
+        Ok(NormalizedLanguage {
+            code: folded.clone(),
+            note: Some(LanguageNote::Unknown(format!(
+                "'{}' isn't an ISO 639-1 language code I know",
+                folded
+            ))),
+        })
+    } else {
+        Err(format!(
+            "'{}' isn't a language code or a language name I know; use an ISO 639-1 code like \"en\"",
+            trimmed
+        ))
+    }
+}
+
+/// "br" → "BR", "419" → "419", "hant" → "Hant"
+fn normalize_subtag(subtag: &str) -> Option<String> {
+    let is_region = (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()));
+    if is_region {
+        return Some(subtag.to_ascii_uppercase());
+    }
+    if subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+        let lower = subtag.to_ascii_lowercase();
+        return Some(lower[..1].to_ascii_uppercase() + &lower[1..]);
+    }
+    None
+}
+
+/// The `Language` constructor for a code: "en" → `EN`, "pt-BR" → `PT_BR`
+///
+/// Constructors can't contain hyphens; `languageToString` still maps the
+/// constructor back to the code itself.
+pub fn language_variant(code: &str) -> String {
+    code.replace('-', "_").to_uppercase()
+}
+
+/// The name of a language's translation record: "en" → `translationsEn`,
+/// "pt-BR" → `translationsPtBR`
+pub fn translations_record_name(code: &str) -> String {
+    let mut name = String::from("translations");
+    for part in code.split('-') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    name
+}
+
+fn is_iso_639_1(code: &str) -> bool {
+    code.len() == 2 && ISO_639_1.split_whitespace().any(|c| c == code)
+}
+
+fn fold_accents(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'ç' | 'č' => 'c',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'ì' | 'í' | 'î' | 'ï' | 'ı' => 'i',
+            'ñ' => 'n',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+            'š' | 'ş' => 's',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(input: &str) -> String {
+        normalize_language_code(input).unwrap().code
+    }
+
+    #[test]
+    fn maps_names_and_aliases_to_codes() {
+        assert_eq!(code(" EN "), "en");
+        assert_eq!(code("English"), "en");
+        assert_eq!(code("Français"), "fr");
+        assert_eq!(code("Español"), "es");
+        assert_eq!(code("deu"), "de");
+        assert_eq!(
+            normalize_language_code("Francais").unwrap().note,
+            Some(LanguageNote::Converted("'Francais' → fr".to_string()))
+        );
+        assert_eq!(normalize_language_code("fr").unwrap().note, None);
+    }
+
+    #[test]
+    fn warns_on_unknown_codes_and_refuses_malformed_ones() {
+        assert!(matches!(
+            normalize_language_code("xq").unwrap().note,
+            Some(LanguageNote::Unknown(_))
+        ));
+        assert!(normalize_language_code("klingonese").is_err());
+        assert!(normalize_language_code("e1").is_err());
+        assert!(normalize_language_code("").is_err());
+    }
+
+    #[test]
+    fn keeps_regional_variants_as_valid_elm_names() {
+        assert_eq!(code("pt_br"), "pt-BR");
+        assert_eq!(code("EN-us"), "en-US");
+        assert_eq!(code("es-419"), "es-419");
+        assert_eq!(code("zh-hant"), "zh-Hant");
+        assert_eq!(code("Português-BR"), "pt-BR");
+        assert!(normalize_language_code("pt-Brazil").is_err());
+
+        assert_eq!(language_variant("en"), "EN");
+        assert_eq!(language_variant("pt-BR"), "PT_BR");
+        assert_eq!(translations_record_name("fr"), "translationsFr");
+        assert_eq!(translations_record_name("pt-BR"), "translationsPtBR");
+    }
+}
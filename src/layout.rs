@@ -0,0 +1,306 @@
+use anyhow::Result;
+use regex::Regex;
+
+use crate::generator::indent_width;
+use crate::parser::parse_i18n_content_with_record_name;
+use crate::timing::{self, Phase};
+
+/// Result of re-emitting the translation blocks of a file
+#[derive(Debug)]
+pub struct LayoutOutcome {
+    pub content: String,
+    /// Blocks whose layout is too unusual to rewrite safely, left as they were
+    pub skipped: Vec<String>,
+}
+
+/// A block to rewrite: the type alias (separator `:`) or a record (separator `=`)
+struct Block {
+    name: String,
+    start: usize,
+    end: usize,
+    field_lines: Vec<usize>,
+    separator: char,
+}
+
+/// Re-emit the `Translations` type and every language record with one layout
+///
+/// Fields go one per line with leading commas at the indent width (four spaces
+/// unless configured otherwise), with single spaces around `:` and `=`, and
+/// comments between fields are indented with them. Multi-line values keep their
+/// shape and move with the block. Everything else in the module is left untouched.
+pub fn layout_translation_blocks(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<LayoutOutcome> {
+    let _edit = timing::phase(Phase::Edit);
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+
+    let mut blocks = vec![Block {
+        name: "the type".to_string(),
+        start: parse_result.type_start_line,
+        end: parse_result.type_end_line,
+        field_lines: parse_result.type_fields.iter().map(|f| f.line).collect(),
+        separator: ':',
+    }];
+    for (lang, start, end) in &parse_result.lang_bounds {
+        blocks.push(Block {
+            name: format!("the {} record", lang.to_uppercase()),
+            start: *start,
+            end: *end,
+            field_lines: parse_result.record_fields[lang]
+                .iter()
+                .map(|f| f.line)
+                .collect(),
+            separator: '=',
+        });
+    }
+
+    // Rewrite from the bottom of the file up so earlier line numbers stay valid
+    blocks.sort_by_key(|b| std::cmp::Reverse(b.start));
+    let mut skipped = Vec::new();
+    for block in &blocks {
+        match layout_block(&lines, block) {
+            Some((first, rewritten)) => {
+                lines.splice(first..=block.end, rewritten);
+            }
+            None => skipped.push(block.name.clone()),
+        }
+    }
+    skipped.reverse();
+
+    let mut content = lines.join("\n");
+    if source.ends_with('\n') {
+        content.push('\n');
+    }
+    Ok(LayoutOutcome { content, skipped })
+}
+
+/// The block's lines from `{` to `}` laid out again, or `None` when the block
+/// isn't in the `{ field` / `, field` / `}` shape (e.g., a record update)
+fn layout_block(lines: &[String], block: &Block) -> Option<(usize, Vec<String>)> {
+    let indent = " ".repeat(indent_width());
+    let brace_line = (block.start..=block.end).find(|&i| lines[i].contains('{'))?;
+    let first_field = *block.field_lines.first()?;
+    let mut distinct = block.field_lines.clone();
+    distinct.dedup();
+    if brace_line == block.end
+        || lines[block.end].trim() != "}"
+        || first_field < brace_line
+        || distinct.len() != block.field_lines.len()
+    {
+        return None;
+    }
+
+    let brace_column = lines[brace_line].find('{')?;
+    let brace_alone = lines[brace_line][brace_column + 1..].trim().is_empty();
+    if !lines[brace_line][..brace_column].trim().is_empty()
+        || (first_field > brace_line && !brace_alone)
+    {
+        return None;
+    }
+
+    // Continuation lines of multi-line values move with the brace
+    let shift = indent_width() as isize - brace_column as isize;
+    let shifted = |line: &str| {
+        if line.trim().is_empty() {
+            return String::new();
+        }
+        let current = line.len() - line.trim_start().len();
+        let column = (current as isize + shift).max(1) as usize;
+        format!("{}{}", " ".repeat(column), line.trim())
+    };
+    let is_comment = |line: &str| line.trim_start().starts_with("--");
+    let field_start = Regex::new(&format!(r"^(\w+)\s*{}\s*(.*)$", block.separator)).ok()?;
+
+    let mut rewritten = Vec::new();
+    // Comments between a lone `{` and the first field go above the brace, with
+    // the annotations for the first field
+    for line in lines.iter().take(first_field).skip(brace_line + 1) {
+        if !line.trim().is_empty() {
+            rewritten.push(format!("{}{}", indent, line.trim()));
+        }
+    }
+
+    for (i, &line) in block.field_lines.iter().enumerate() {
+        let is_last = i + 1 == block.field_lines.len();
+        let next = block.field_lines.get(i + 1).copied().unwrap_or(block.end);
+        // Comments and blank lines right above the next field (or `}`) aren't
+        // part of this field's value
+        let mut code_end = next;
+        while code_end > line + 1
+            && (is_comment(&lines[code_end - 1]) || lines[code_end - 1].trim().is_empty())
+        {
+            code_end -= 1;
+        }
+
+        let mut code: Vec<String> = lines[line..code_end].to_vec();
+        // Lines of a `"""` string are part of the value, so they're kept verbatim
+        let verbatim = code.iter().any(|l| l.contains("\"\"\""));
+        if !verbatim {
+            code = code.iter().map(|l| l.trim_end().to_string()).collect();
+        }
+        // Trailing-comma style: the comma moves to the front of the next field
+        if let Some(last) = code.last_mut().filter(|l| !is_last && l.ends_with(',')) {
+            last.pop();
+            *last = last.trim_end().to_string();
+        }
+
+        let first_line = code[0].trim_start();
+        let first_line = first_line
+            .strip_prefix(['{', ','])
+            .unwrap_or(first_line)
+            .trim_start();
+        let captures = field_start.captures(first_line)?;
+        let leader = if i == 0 { "{" } else { "," };
+        let mut field = format!("{}{} {} {}", indent, leader, &captures[1], block.separator);
+        if !captures[2].trim().is_empty() {
+            field.push(' ');
+            field.push_str(captures[2].trim());
+        }
+        rewritten.push(field);
+        if verbatim {
+            rewritten.extend(code[1..].iter().cloned());
+        } else {
+            rewritten.extend(code[1..].iter().map(|l| shifted(l)));
+        }
+
+        for comment in lines[code_end..next].iter().filter(|l| is_comment(l)) {
+            rewritten.push(format!("{}{}", indent, comment.trim()));
+        }
+    }
+    rewritten.push(format!("{}}}", indent));
+
+    Some((brace_line, rewritten))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(source: &str) -> LayoutOutcome {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        layout_translation_blocks(source, "Translations", &languages).unwrap()
+    }
+
+    #[test]
+    fn reemits_the_type_and_records_with_one_layout() {
+        let source = "module I18n exposing (..)
+
+-- The rest of the module is left alone
+type Language   = EN | FR
+
+
+type alias Translations =
+  {   save:String,
+      itemCount  :  Int -> String
+  }
+
+
+translationsEn : Translations
+translationsEn =
+      { save =   \"Save\"   ,
+      -- Plural form
+
+      itemCount =
+          \\n ->
+              String.fromInt n ++ \" items\"
+      }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , itemCount = \\n -> String.fromInt n ++ \" articles\"
+    }
+";
+
+        let outcome = layout(source);
+
+        assert_eq!(
+            outcome.content,
+            "module I18n exposing (..)
+
+-- The rest of the module is left alone
+type Language   = EN | FR
+
+
+type alias Translations =
+    { save : String
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    -- Plural form
+    , itemCount =
+        \\n ->
+            String.fromInt n ++ \" items\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , itemCount = \\n -> String.fromInt n ++ \" articles\"
+    }
+"
+        );
+        assert!(outcome.skipped.is_empty());
+        assert_eq!(layout(&outcome.content).content, outcome.content);
+    }
+
+    #[test]
+    fn keeps_multiline_strings_and_skips_record_updates() {
+        let source = "type alias Translations =
+    { terms : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+      { terms =
+            \"\"\"
+  Indented on purpose   
+\"\"\"
+      }
+
+
+translationsFr : Translations
+translationsFr =
+    { translationsEn
+        | terms = \"Conditions\"
+    }
+";
+
+        let outcome = layout(source);
+
+        assert_eq!(outcome.skipped, vec!["the FR record"]);
+        assert_eq!(
+            outcome.content,
+            "type alias Translations =
+    { terms : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { terms =
+            \"\"\"
+  Indented on purpose   
+\"\"\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { translationsEn
+        | terms = \"Conditions\"
+    }
+"
+        );
+    }
+}
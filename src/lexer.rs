@@ -0,0 +1,205 @@
+/// What a token of Elm source is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A string or char literal, quotes included; `"""` strings span lines
+    Literal,
+    /// A name, qualified or with field access: `count`, `String.fromInt`
+    Name,
+    Number,
+    /// A run of symbols: `=`, `:`, `->`, `++`, `|`, and `\` starting a lambda
+    Operator,
+    /// `--` to the end of the line, or a `{- -}` with the ones nested in it
+    Comment,
+    /// `(`, `[` or `{`
+    Open,
+    /// `)`, `]` or `}`
+    Close,
+    Comma,
+}
+
+/// A token, with its byte span in the source and the 0-based line it starts on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
+
+const SYMBOLS: &str = "+-*/=<>|&:.!^%\\~?@#$";
+
+/// The tokens of `source`, whitespace left out. A string or comment that never
+/// closes runs to the end, so any source gets tokens.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(source.len(), |(offset, _)| *offset);
+    let starts_with = |i: usize, text: &str| source[offset(i)..].starts_with(text);
+    let mut tokens = Vec::new();
+    let mut line = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i].1;
+        let start = i;
+        let kind = if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        } else if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if starts_with(i, "--") {
+            while i < chars.len() && chars[i].1 != '\n' {
+                i += 1;
+            }
+            TokenKind::Comment
+        } else if starts_with(i, "{-") {
+            let mut depth = 0;
+            while i < chars.len() {
+                if starts_with(i, "{-") {
+                    depth += 1;
+                    i += 2;
+                } else if starts_with(i, "-}") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+            TokenKind::Comment
+        } else if c == '"' || c == '\'' {
+            let delimiter = if starts_with(i, "\"\"\"") {
+                "\"\"\""
+            } else {
+                &source[offset(i)..offset(i + 1)]
+            };
+            i += delimiter.chars().count();
+            while i < chars.len() {
+                if chars[i].1 == '\\' {
+                    i += 2;
+                } else if starts_with(i, delimiter) {
+                    i += delimiter.chars().count();
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            TokenKind::Literal
+        } else if c.is_ascii_digit() {
+            while i < chars.len()
+                && (chars[i].1.is_ascii_alphanumeric()
+                    || chars[i].1 == '.'
+                        && chars.get(i + 1).is_some_and(|(_, c)| c.is_ascii_digit()))
+            {
+                i += 1;
+            }
+            TokenKind::Number
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len()
+                && (chars[i].1.is_alphanumeric()
+                    || chars[i].1 == '_'
+                    || chars[i].1 == '.'
+                        && chars.get(i + 1).is_some_and(|(_, c)| c.is_alphabetic()))
+            {
+                i += 1;
+            }
+            TokenKind::Name
+        } else if "([{".contains(c) {
+            i += 1;
+            TokenKind::Open
+        } else if ")]}".contains(c) {
+            i += 1;
+            TokenKind::Close
+        } else if c == ',' {
+            i += 1;
+            TokenKind::Comma
+        } else if SYMBOLS.contains(c) {
+            // `\` starts a lambda right before its arguments: `\{ count } ->`
+            i += 1;
+            while c != '\\'
+                && i < chars.len()
+                && SYMBOLS.contains(chars[i].1)
+                && !starts_with(i, "--")
+            {
+                i += 1;
+            }
+            TokenKind::Operator
+        } else {
+            i += 1;
+            TokenKind::Operator
+        };
+        let i = i.min(chars.len());
+        tokens.push(Token {
+            kind,
+            start: offset(start),
+            end: offset(i),
+            line,
+        });
+        line += source[offset(start)..offset(i)].matches('\n').count();
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds_and_texts(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(source)
+            .into_iter()
+            .map(|token| (token.kind, token.text(source)))
+            .collect()
+    }
+
+    #[test]
+    fn tokenizes_fields_with_strings_comments_and_lambdas() {
+        use TokenKind::*;
+        let source = "{ a = \"x = {y}, -- z\" -- done\n, f = \\{ n } -> String.fromInt n ++ \"\"\"\n}\"\"\" {- {- -} -} , c = '\\'' }";
+        assert_eq!(
+            kinds_and_texts(source),
+            [
+                (Open, "{"),
+                (Name, "a"),
+                (Operator, "="),
+                (Literal, "\"x = {y}, -- z\""),
+                (Comment, "-- done"),
+                (Comma, ","),
+                (Name, "f"),
+                (Operator, "="),
+                (Operator, "\\"),
+                (Open, "{"),
+                (Name, "n"),
+                (Close, "}"),
+                (Operator, "->"),
+                (Name, "String.fromInt"),
+                (Name, "n"),
+                (Operator, "++"),
+                (Literal, "\"\"\"\n}\"\"\""),
+                (Comment, "{- {- -} -}"),
+                (Comma, ","),
+                (Name, "c"),
+                (Operator, "="),
+                (Literal, "'\\''"),
+                (Close, "}"),
+            ]
+        );
+        let lines: Vec<usize> = tokenize(source).iter().map(|token| token.line).collect();
+        assert_eq!(lines[5], 1);
+        assert_eq!(lines[17], 2, "the line after the one the string closes on");
+        // What never closes runs to the end
+        assert_eq!(
+            kinds_and_texts("x = \"open"),
+            [(Name, "x"), (Operator, "="), (Literal, "\"open")]
+        );
+        assert_eq!(kinds_and_texts("{- open"), [(Comment, "{- open")]);
+    }
+}
@@ -0,0 +1,1347 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::config::{LintConfig, Severity};
+use crate::escaping;
+use crate::glossary::{glossary_violations, Glossary};
+use crate::grapheme::grapheme_len;
+use crate::namespace::keys_by_prefix;
+use crate::naming::NamingRules;
+use crate::parser::{comment_free, parse_lint_suppressions, parse_max_length_annotations};
+use crate::plural::coverage_problems;
+use crate::types::ParseResult;
+use crate::validate::{arity_mismatch, value_status, ValueStatus};
+
+pub const UNBALANCED_MARKUP: &str = "unbalanced-markup";
+pub const MAX_LENGTH: &str = "max-length";
+pub const MISSING_FIELD: &str = "missing-field";
+pub const TRAILING_WHITESPACE: &str = "trailing-whitespace";
+pub const COMMA_STYLE: &str = "comma-style";
+pub const FIELD_ORDER: &str = "field-order";
+pub const UNKNOWN_FIELD: &str = "unknown-field";
+pub const GLOSSARY: &str = "glossary";
+pub const TODO_MARKER: &str = "todo-marker";
+pub const MAX_KEYS: &str = "max-keys";
+pub const FUNCTION_ARITY: &str = "function-arity";
+pub const PLURAL_CATEGORIES: &str = "plural-categories";
+pub const STALE_TRANSLATION: &str = "stale-translation";
+
+/// How many of the largest prefixes the `max-keys` message names
+const LARGEST_PREFIXES: usize = 3;
+
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// A single problem reported by `lint`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub key: String,
+    pub language: Option<String>,
+    /// 1-based line the finding points at, when it has one
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// A field line whose leading comma is missing, misplaced or misaligned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommaProblem {
+    /// 0-based line of the field
+    pub line: usize,
+    pub key: String,
+    pub language: Option<String>,
+    pub message: String,
+    /// What the field line should look like
+    pub expected: String,
+    /// 0-based line ending with a comma that belongs to this field
+    pub trailing_comma_line: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MarkupTag {
+    Open(String),
+    Close(String),
+    SelfClosing(String),
+}
+
+/// Run every enabled lint rule over a parsed I18n file
+///
+/// `source` is the file's content, used by rules that read annotation comments.
+/// Findings for a key annotated with `-- i18n-ignore: rule` are dropped.
+/// Findings are grouped by language, then sorted by key.
+pub fn lint_translations(
+    parse_result: &ParseResult,
+    source: &str,
+    languages: &[String],
+    config: &LintConfig,
+    glossary: Option<&Glossary>,
+) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    let severity = config.severity(UNBALANCED_MARKUP, Severity::Error);
+    if severity != Severity::Off {
+        findings.extend(check_markup(parse_result, languages, severity));
+    }
+
+    let severity = config.severity(MISSING_FIELD, Severity::Error);
+    if severity != Severity::Off {
+        findings.extend(check_missing_fields(parse_result, source, severity));
+    }
+
+    let severity = config.severity(UNKNOWN_FIELD, Severity::Error);
+    if severity != Severity::Off {
+        findings.extend(check_unknown_fields(parse_result, source, severity));
+    }
+
+    let severity = config.severity(TRAILING_WHITESPACE, Severity::Warning);
+    if severity != Severity::Off {
+        let lines = trailing_whitespace_lines(source);
+        if !lines.is_empty() {
+            findings.push(LintFinding {
+                rule: TRAILING_WHITESPACE,
+                severity,
+                key: String::new(),
+                language: None,
+                line: Some(lines[0] + 1),
+                message: format!(
+                    "trailing whitespace on line{} {}",
+                    if lines.len() == 1 { "" } else { "s" },
+                    lines
+                        .iter()
+                        .map(|l| (l + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            });
+        }
+    }
+
+    let severity = config.severity(COMMA_STYLE, Severity::Warning);
+    if severity != Severity::Off {
+        let lines: Vec<&str> = source.lines().collect();
+        findings.extend(
+            comma_style_problems(parse_result, &lines)
+                .into_iter()
+                .map(|problem| LintFinding {
+                    rule: COMMA_STYLE,
+                    severity,
+                    key: problem.key,
+                    language: problem.language,
+                    line: Some(problem.line + 1),
+                    message: problem.message,
+                }),
+        );
+    }
+
+    let severity = config.severity(FIELD_ORDER, Severity::Warning);
+    if severity != Severity::Off {
+        findings.extend(
+            field_order_problems(parse_result)
+                .into_iter()
+                .map(|problem| LintFinding {
+                    rule: FIELD_ORDER,
+                    severity,
+                    message: format!(
+                        "fields are out of order from here: the type has `{}` in this position",
+                        problem.expected
+                    ),
+                    key: problem.key,
+                    language: Some(problem.language),
+                    line: Some(problem.line + 1),
+                }),
+        );
+    }
+
+    let severity = config.severity(MAX_LENGTH, Severity::Error);
+    if severity != Severity::Off {
+        let annotations = parse_max_length_annotations(source);
+        findings.extend(check_max_length(
+            parse_result,
+            &annotations,
+            languages,
+            config,
+            severity,
+        ));
+    }
+
+    let severity = config.severity(GLOSSARY, Severity::Warning);
+    if let (Some(glossary), true) = (glossary, severity != Severity::Off) {
+        findings.extend(
+            glossary_violations(parse_result, glossary, languages)
+                .into_iter()
+                .map(|violation| LintFinding {
+                    rule: GLOSSARY,
+                    severity,
+                    message: format!(
+                        "{} uses \"{}\", which the glossary translates as \"{}\"",
+                        glossary.source_language.to_uppercase(),
+                        violation.term,
+                        violation.expected
+                    ),
+                    line: field_line(parse_result, &violation.language, &violation.key),
+                    key: violation.key,
+                    language: Some(violation.language),
+                }),
+        );
+    }
+
+    let severity = config.severity(TODO_MARKER, Severity::Warning);
+    if severity != Severity::Off {
+        let marker = config.todo_marker();
+        for (key, translation) in &parse_result.translations {
+            for lang in languages {
+                if value_status(translation.values.get(lang), marker) == ValueStatus::Todo {
+                    findings.push(LintFinding {
+                        rule: TODO_MARKER,
+                        severity,
+                        key: key.clone(),
+                        language: Some(lang.clone()),
+                        line: field_line(parse_result, lang, key),
+                        message: format!("still has the `{}` marker", marker),
+                    });
+                }
+            }
+        }
+    }
+
+    let severity = config.severity(FUNCTION_ARITY, Severity::Error);
+    if severity != Severity::Off {
+        for (key, translation) in &parse_result.translations {
+            let signature = match &translation.type_signature {
+                Some(signature) if translation.is_function => signature,
+                _ => continue,
+            };
+            for lang in languages {
+                let message = translation
+                    .values
+                    .get(lang)
+                    .and_then(|value| arity_mismatch(signature, value));
+                if let Some(message) = message {
+                    findings.push(LintFinding {
+                        rule: FUNCTION_ARITY,
+                        severity,
+                        key: key.clone(),
+                        language: Some(lang.clone()),
+                        line: field_line(parse_result, lang, key),
+                        message,
+                    });
+                }
+            }
+        }
+    }
+
+    let severity = config.severity(PLURAL_CATEGORIES, Severity::Warning);
+    if severity != Severity::Off {
+        for (key, translation) in &parse_result.translations {
+            let signature = match &translation.type_signature {
+                Some(signature) if translation.is_function => signature,
+                _ => continue,
+            };
+            for lang in languages {
+                let Some(value) = translation.values.get(lang) else {
+                    continue;
+                };
+                for message in coverage_problems(signature, value, lang) {
+                    findings.push(LintFinding {
+                        rule: PLURAL_CATEGORIES,
+                        severity,
+                        key: key.clone(),
+                        language: Some(lang.clone()),
+                        line: field_line(parse_result, lang, key),
+                        message,
+                    });
+                }
+            }
+        }
+    }
+
+    let severity = config.severity(MAX_KEYS, Severity::Warning);
+    if let (Some(max_keys), true) = (config.max_keys, severity != Severity::Off) {
+        let keys = parse_result.translations.keys().map(String::as_str);
+        if let Some(message) = key_count_message(keys, max_keys) {
+            findings.push(LintFinding {
+                rule: MAX_KEYS,
+                severity,
+                key: String::new(),
+                language: None,
+                line: Some(parse_result.type_start_line + 1),
+                message,
+            });
+        }
+    }
+
+    // The rules were checked when the config was loaded
+    if let Ok(naming) = NamingRules::new(&config.naming) {
+        for field in &parse_result.type_fields {
+            for violation in naming.violations(&field.name) {
+                let severity = config.severity(violation.rule, Severity::Error);
+                if severity != Severity::Off {
+                    findings.push(LintFinding {
+                        rule: violation.rule,
+                        severity,
+                        key: field.name.clone(),
+                        language: None,
+                        line: Some(field.line + 1),
+                        message: violation.message,
+                    });
+                }
+            }
+        }
+    }
+
+    finish_findings(&mut findings, source, languages);
+    findings
+}
+
+/// Drop the findings suppressed with `-- i18n-ignore: rule`, and order the rest by
+/// language, then key
+pub fn finish_findings(findings: &mut Vec<LintFinding>, source: &str, languages: &[String]) {
+    let suppressions = parse_lint_suppressions(source);
+    findings.retain(|finding| {
+        suppressions
+            .get(&finding.key)
+            .is_none_or(|rules| !rules.iter().any(|rule| rule == finding.rule))
+    });
+
+    findings.sort_by(|a, b| {
+        language_index(&a.language, languages)
+            .cmp(&language_index(&b.language, languages))
+            .then_with(|| a.key.cmp(&b.key))
+            .then_with(|| a.line.cmp(&b.line))
+            .then_with(|| a.rule.cmp(b.rule))
+            .then_with(|| a.message.cmp(&b.message))
+    });
+}
+
+/// What `lint --stale` reports for translations whose source value changed since
+/// they were written, given as (key, language)
+pub fn stale_findings(
+    parse_result: &ParseResult,
+    stale: &[(String, String)],
+    severity: Severity,
+) -> Vec<LintFinding> {
+    stale
+        .iter()
+        .map(|(key, lang)| LintFinding {
+            rule: STALE_TRANSLATION,
+            severity,
+            key: key.clone(),
+            language: Some(lang.clone()),
+            line: field_line(parse_result, lang, key),
+            message: "the source text changed since this was translated; review it".to_string(),
+        })
+        .collect()
+}
+
+/// What `max-keys` reports when there are more than `max_keys` keys, naming the
+/// biggest prefixes as candidates to split into sub-records
+pub fn key_count_message<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    max_keys: usize,
+) -> Option<String> {
+    let groups = keys_by_prefix(keys);
+    let count: usize = groups.iter().map(|(_, count)| count).sum();
+    if count <= max_keys {
+        return None;
+    }
+
+    let mut message = format!(
+        "the file has {} keys; the limit is {}. Consider splitting it into sub-records by prefix",
+        count, max_keys
+    );
+    let largest: Vec<String> = groups
+        .iter()
+        .filter_map(|(prefix, count)| prefix.map(|p| format!("{} {}", p, count)))
+        .take(LARGEST_PREFIXES)
+        .collect();
+    if !largest.is_empty() {
+        message.push_str(&format!(" (largest: {})", largest.join(", ")));
+    }
+    Some(message)
+}
+
+fn language_index(lang: &Option<String>, languages: &[String]) -> usize {
+    lang.as_ref()
+        .and_then(|l| languages.iter().position(|configured| configured == l))
+        .unwrap_or(usize::MAX)
+}
+
+fn check_markup(
+    parse_result: &ParseResult,
+    languages: &[String],
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    for (key, translation) in &parse_result.translations {
+        // (language, sorted tag names) for each language that has markup or a value
+        let mut tag_sets: Vec<(String, Vec<String>)> = Vec::new();
+
+        for lang in languages {
+            let value = match translation.values.get(lang) {
+                Some(value) if !value.trim().is_empty() => value,
+                _ => continue,
+            };
+
+            // Only look inside string literals so `->`, `<|` or comparisons in
+            // function bodies are never mistaken for tags
+            let text = string_literal_contents(value).concat();
+            let tags = markup_tags(&text);
+
+            for problem in markup_balance_problems(&tags) {
+                findings.push(LintFinding {
+                    rule: UNBALANCED_MARKUP,
+                    severity,
+                    key: key.clone(),
+                    language: Some(lang.clone()),
+                    line: field_line(parse_result, lang, key),
+                    message: problem,
+                });
+            }
+
+            let mut names: Vec<String> = tags
+                .iter()
+                .filter_map(|tag| match tag {
+                    MarkupTag::Open(name) | MarkupTag::SelfClosing(name) => Some(name.clone()),
+                    MarkupTag::Close(_) => None,
+                })
+                .collect();
+            names.sort();
+            tag_sets.push((lang.clone(), names));
+        }
+
+        // Compare every language against the first one that has a value
+        if let Some((base_lang, base_tags)) = tag_sets.first() {
+            for (lang, tags) in tag_sets.iter().skip(1) {
+                if tags != base_tags {
+                    findings.push(LintFinding {
+                        rule: UNBALANCED_MARKUP,
+                        severity,
+                        key: key.clone(),
+                        language: Some(lang.clone()),
+                        line: field_line(parse_result, lang, key),
+                        message: format!(
+                            "tags differ from {}: {} has {}, {} has {}",
+                            base_lang.to_uppercase(),
+                            base_lang.to_uppercase(),
+                            format_tag_list(base_tags),
+                            lang.to_uppercase(),
+                            format_tag_list(tags)
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    findings
+}
+
+fn format_tag_list(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "no tags".to_string()
+    } else {
+        tags.iter()
+            .map(|t| format!("<{}>", t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The first field of a record that is not where the type alias puts it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldOrderProblem {
+    pub language: String,
+    pub key: String,
+    /// 0-based line of the misplaced field
+    pub line: usize,
+    /// The field the type has in that position
+    pub expected: String,
+}
+
+/// Records whose fields (among those in the type) don't follow the type's order
+pub fn field_order_problems(parse_result: &ParseResult) -> Vec<FieldOrderProblem> {
+    let mut problems = Vec::new();
+
+    for (lang, _, _) in &parse_result.lang_bounds {
+        let fields = match parse_result.record_fields.get(lang) {
+            Some(fields) => fields,
+            None => continue,
+        };
+
+        let in_type: Vec<_> = fields
+            .iter()
+            .filter(|f| parse_result.type_fields.iter().any(|t| t.name == f.name))
+            .collect();
+        let expected = parse_result
+            .type_fields
+            .iter()
+            .filter(|t| in_type.iter().any(|f| f.name == t.name));
+
+        if let Some((field, expected)) = in_type
+            .iter()
+            .zip(expected)
+            .find(|(field, expected)| field.name != expected.name)
+        {
+            problems.push(FieldOrderProblem {
+                language: lang.clone(),
+                key: field.name.clone(),
+                line: field.line,
+                expected: expected.name.clone(),
+            });
+        }
+    }
+
+    problems
+}
+
+/// Type fields that a language record does not define, in type order
+pub fn missing_record_fields(parse_result: &ParseResult) -> Vec<(String, String)> {
+    let mut missing = Vec::new();
+
+    for (lang, _, _) in &parse_result.lang_bounds {
+        let record = parse_result.record_fields.get(lang);
+        for field in &parse_result.type_fields {
+            let defined = record.is_some_and(|fields| fields.iter().any(|f| f.name == field.name));
+            if !defined {
+                missing.push((lang.clone(), field.name.clone()));
+            }
+        }
+    }
+
+    missing
+}
+
+fn check_missing_fields(
+    parse_result: &ParseResult,
+    source: &str,
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    missing_record_fields(parse_result)
+        .into_iter()
+        .map(|(lang, key)| {
+            let type_line = parse_result
+                .type_fields
+                .iter()
+                .find(|f| f.name == key)
+                .map(|f| f.line)
+                .unwrap_or(parse_result.type_start_line);
+
+            LintFinding {
+                rule: MISSING_FIELD,
+                severity,
+                message: format!(
+                    "is in the type but missing from {}",
+                    record_name(parse_result, &lines, &lang)
+                ),
+                key,
+                language: Some(lang),
+                line: Some(type_line + 1),
+            }
+        })
+        .collect()
+}
+
+/// Record fields that the type alias doesn't declare
+fn check_unknown_fields(
+    parse_result: &ParseResult,
+    source: &str,
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut findings = Vec::new();
+
+    for (lang, _, _) in &parse_result.lang_bounds {
+        let fields = match parse_result.record_fields.get(lang) {
+            Some(fields) => fields,
+            None => continue,
+        };
+        for field in fields {
+            if parse_result
+                .type_fields
+                .iter()
+                .any(|t| t.name == field.name)
+            {
+                continue;
+            }
+            findings.push(LintFinding {
+                rule: UNKNOWN_FIELD,
+                severity,
+                key: field.name.clone(),
+                language: Some(lang.clone()),
+                line: Some(field.line + 1),
+                message: format!(
+                    "is in {} but not in the type",
+                    record_name(parse_result, &lines, lang)
+                ),
+            });
+        }
+    }
+
+    findings
+}
+
+/// Keys the type and the records disagree on, in either direction
+///
+/// Edits to a file in this state tend to make it worse, so mutating commands
+/// refuse to run until these are fixed.
+pub fn key_set_mismatches(parse_result: &ParseResult, source: &str) -> Vec<LintFinding> {
+    let mut findings = check_missing_fields(parse_result, source, Severity::Error);
+    findings.extend(check_unknown_fields(parse_result, source, Severity::Error));
+    findings
+}
+
+/// 1-based line where a language record defines a key
+pub fn field_line(parse_result: &ParseResult, lang: &str, key: &str) -> Option<usize> {
+    parse_result
+        .record_fields
+        .get(lang)?
+        .iter()
+        .find(|field| field.name == key)
+        .map(|field| field.line + 1)
+}
+
+/// The 0-based lines a language record's field for a key spans, from its name to
+/// the last line of its value
+pub fn field_lines(parse_result: &ParseResult, lang: &str, key: &str) -> Option<Range<usize>> {
+    let field = parse_result
+        .record_fields
+        .get(lang)?
+        .iter()
+        .find(|field| field.name == key)?;
+    Some(field.line..field.end_line + 1)
+}
+
+/// The name a language record is defined under (e.g., "translationsFr")
+fn record_name(parse_result: &ParseResult, lines: &[&str], lang: &str) -> String {
+    parse_result
+        .lang_bounds
+        .iter()
+        .find(|(l, _, _)| l == lang)
+        .and_then(|(_, start, _)| lines.get(*start))
+        .and_then(|line| line.split_whitespace().next())
+        .unwrap_or(lang)
+        .to_string()
+}
+
+/// 0-based lines ending in spaces or tabs, ignoring the inside of `"""` strings
+pub fn trailing_whitespace_lines(source: &str) -> Vec<usize> {
+    let mut in_multiline_string = false;
+    let mut lines = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        if line.matches("\"\"\"").count() % 2 == 1 {
+            in_multiline_string = !in_multiline_string;
+        }
+        // Whitespace at the end of a line inside a multi-line string is content
+        if !in_multiline_string && line.trim_end() != line {
+            lines.push(i);
+        }
+    }
+
+    lines
+}
+
+/// Field lines that don't follow the `{ first`, `, rest` leading comma style
+///
+/// Every field after the first should start with `, ` lined up under the `{`.
+pub fn comma_style_problems(parse_result: &ParseResult, lines: &[&str]) -> Vec<CommaProblem> {
+    let mut blocks: Vec<(Option<String>, usize, usize, Vec<(String, usize)>)> = vec![(
+        None,
+        parse_result.type_start_line,
+        parse_result.type_end_line,
+        parse_result
+            .type_fields
+            .iter()
+            .map(|f| (f.name.clone(), f.line))
+            .collect(),
+    )];
+    for (lang, start, end) in &parse_result.lang_bounds {
+        let fields = parse_result
+            .record_fields
+            .get(lang)
+            .map(|fields| fields.iter().map(|f| (f.name.clone(), f.line)).collect())
+            .unwrap_or_default();
+        blocks.push((Some(lang.clone()), *start, *end, fields));
+    }
+
+    let mut problems = Vec::new();
+    // Braces and commas in comments don't count
+    let code = comment_free(lines);
+
+    for (language, start, end, fields) in blocks {
+        let brace_column = match (start..=end)
+            .filter_map(|i| code.get(i))
+            .find_map(|line| line.find('{'))
+        {
+            Some(column) => column,
+            None => continue,
+        };
+
+        for (key, line) in fields {
+            let text = match lines.get(line) {
+                Some(text) => *text,
+                None => continue,
+            };
+            let trimmed = text.trim_start();
+            if code[line].trim_start().starts_with('{') {
+                continue;
+            }
+
+            let rest = trimmed.trim_start_matches(',').trim_start();
+            let expected = format!("{}, {}", " ".repeat(brace_column), rest);
+
+            let message = if !code[line].trim_start().starts_with(',') {
+                let previous = (start..line).rev().find(|&i| !code[i].trim().is_empty());
+                match previous.filter(|&i| ends_with_code_comma(&code[i])) {
+                    Some(comma_line) => {
+                        problems.push(CommaProblem {
+                            line,
+                            key,
+                            language: language.clone(),
+                            message: "comma should start this field, not end the previous one"
+                                .to_string(),
+                            expected,
+                            trailing_comma_line: Some(comma_line),
+                        });
+                        continue;
+                    }
+                    None => continue,
+                }
+            } else if !trimmed[1..].starts_with(' ') || trimmed[1..].starts_with("  ") {
+                "expected one space after the leading comma".to_string()
+            } else if text.len() - trimmed.len() != brace_column {
+                format!(
+                    "leading comma should line up with the {{ in column {}",
+                    brace_column + 1
+                )
+            } else {
+                continue;
+            };
+
+            problems.push(CommaProblem {
+                line,
+                key,
+                language: language.clone(),
+                message,
+                expected,
+                trailing_comma_line: None,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Whether a line ends with a comma that is outside any string literal
+fn ends_with_code_comma(line: &str) -> bool {
+    let trimmed = line.trim_end();
+    if !trimmed.ends_with(',') {
+        return false;
+    }
+
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in trimmed.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_string = !in_string,
+            _ => {}
+        }
+    }
+    !in_string
+}
+
+fn check_max_length(
+    parse_result: &ParseResult,
+    annotations: &HashMap<String, usize>,
+    languages: &[String],
+    config: &LintConfig,
+    severity: Severity,
+) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+
+    for (key, translation) in &parse_result.translations {
+        // Only plain strings have a fixed length; functions are assembled at runtime
+        if translation.type_signature.is_some() {
+            continue;
+        }
+
+        let budget = match length_budget(key, annotations, config) {
+            Some(budget) => budget,
+            None => continue,
+        };
+
+        for lang in languages {
+            let value = match translation.values.get(lang) {
+                Some(value) if !value.trim().is_empty() => value,
+                _ => continue,
+            };
+
+            let text: String = string_literal_contents(value)
+                .iter()
+                .map(|literal| escaping::unescape(literal))
+                .collect();
+            let length = grapheme_len(&text);
+
+            if length > budget {
+                findings.push(LintFinding {
+                    rule: MAX_LENGTH,
+                    severity,
+                    key: key.clone(),
+                    language: Some(lang.clone()),
+                    line: field_line(parse_result, lang, key),
+                    message: format!(
+                        "is {} characters, budget is {} ({} over)",
+                        length,
+                        budget,
+                        length - budget
+                    ),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// The budget for a key: its annotation, else the most specific matching config pattern
+fn length_budget(
+    key: &str,
+    annotations: &HashMap<String, usize>,
+    config: &LintConfig,
+) -> Option<usize> {
+    if let Some(budget) = annotations.get(key) {
+        return Some(*budget);
+    }
+
+    config
+        .max_length
+        .iter()
+        .filter(|(pattern, _)| key_matches_pattern(pattern, key))
+        .max_by(|(a, a_budget), (b, b_budget)| {
+            // Fewer wildcards and a longer literal part win; ties go to the tighter budget
+            let specificity = |p: &str| (usize::MAX - p.matches('*').count(), p.len());
+            specificity(a)
+                .cmp(&specificity(b))
+                .then_with(|| b_budget.cmp(a_budget))
+        })
+        .map(|(_, budget)| *budget)
+}
+
+/// Match a key against a pattern where `*` stands for any run of characters
+pub fn key_matches_pattern(pattern: &str, key: &str) -> bool {
+    let regex = format!(
+        "^{}$",
+        pattern
+            .split('*')
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(".*")
+    );
+    Regex::new(&regex).is_ok_and(|r| r.is_match(key))
+}
+
+/// Extract the contents of every string literal in an Elm expression
+pub fn string_literal_contents(value: &str) -> Vec<String> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut literals = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            // Character literals like '"' must not open a string
+            '\'' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+            }
+            '"' => {
+                let triple = i + 2 < chars.len() && chars[i + 1] == '"' && chars[i + 2] == '"';
+                let delimiter_len = if triple { 3 } else { 1 };
+                i += delimiter_len;
+
+                let mut literal = String::new();
+                while i < chars.len() {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        literal.push(chars[i]);
+                        literal.push(chars[i + 1]);
+                        i += 2;
+                        continue;
+                    }
+                    let closes = if triple {
+                        i + 2 < chars.len()
+                            && chars[i] == '"'
+                            && chars[i + 1] == '"'
+                            && chars[i + 2] == '"'
+                    } else {
+                        chars[i] == '"'
+                    };
+                    if closes {
+                        i += delimiter_len;
+                        break;
+                    }
+                    literal.push(chars[i]);
+                    i += 1;
+                }
+                literals.push(literal);
+            }
+            _ => i += 1,
+        }
+    }
+
+    literals
+}
+
+/// The text of a value that is a single string literal, e.g. `Bienvenue!` for
+/// `"Bienvenue!"`; `None` for any other expression
+pub fn plain_string_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    match string_literal_contents(value).as_slice() {
+        [literal]
+            if value == format!("\"{}\"", literal)
+                || value == format!("\"\"\"{}\"\"\"", literal) =>
+        {
+            Some(escaping::unescape(literal))
+        }
+        _ => None,
+    }
+}
+
+fn markup_tags(text: &str) -> Vec<MarkupTag> {
+    let tag_regex = Regex::new(r#"<(/?)([A-Za-z][A-Za-z0-9-]*)(?:\s+[^<>]*?)?\s*(/?)>"#).unwrap();
+
+    tag_regex
+        .captures_iter(text)
+        .map(|captures| {
+            let name = captures[2].to_lowercase();
+            if !captures[1].is_empty() {
+                MarkupTag::Close(name)
+            } else if !captures[3].is_empty() || VOID_ELEMENTS.contains(&name.as_str()) {
+                MarkupTag::SelfClosing(name)
+            } else {
+                MarkupTag::Open(name)
+            }
+        })
+        .collect()
+}
+
+fn markup_balance_problems(tags: &[MarkupTag]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut open_tags: Vec<&str> = Vec::new();
+
+    for tag in tags {
+        match tag {
+            MarkupTag::Open(name) => open_tags.push(name),
+            MarkupTag::SelfClosing(_) => {}
+            MarkupTag::Close(name) => match open_tags.last() {
+                Some(open) if open == name => {
+                    open_tags.pop();
+                }
+                Some(open) => {
+                    problems.push(format!("</{}> closes <{}>", name, open));
+                    // Recover if the tag was opened further up the stack
+                    if let Some(pos) = open_tags.iter().rposition(|t| t == name) {
+                        open_tags.truncate(pos);
+                    }
+                }
+                None => problems.push(format!("</{}> has no matching opening tag", name)),
+            },
+        }
+    }
+
+    for name in open_tags {
+        problems.push(format!("<{}> is never closed", name));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Translation;
+    use std::collections::HashMap;
+
+    fn parse_result_with(key: &str, values: &[(&str, &str)], is_function: bool) -> ParseResult {
+        let translation = Translation {
+            key: key.to_string(),
+            values: values
+                .iter()
+                .map(|(lang, value)| (lang.to_string(), value.to_string()))
+                .collect(),
+            is_function,
+            type_signature: None,
+        };
+
+        ParseResult {
+            type_start_line: 0,
+            type_end_line: 0,
+            lang_bounds: vec![],
+            translations: HashMap::from([(key.to_string(), translation)]),
+            type_fields: vec![],
+            record_fields: HashMap::new(),
+        }
+    }
+
+    fn languages() -> Vec<String> {
+        vec!["en".to_string(), "fr".to_string()]
+    }
+
+    #[test]
+    fn reports_unclosed_and_mismatched_tags() {
+        let parse_result = parse_result_with(
+            "terms",
+            &[
+                ("en", r#""Read the <a href=\"/terms\">terms</a>""#),
+                ("fr", r#""Lisez les <a href=\"/terms\">conditions""#),
+            ],
+            false,
+        );
+
+        let findings = lint_translations(
+            &parse_result,
+            "",
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].language.as_deref(), Some("fr"));
+        assert_eq!(findings[0].message, "<a> is never closed");
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn reports_tags_missing_from_one_language() {
+        let parse_result = parse_result_with(
+            "welcome",
+            &[
+                ("en", r#""<strong>Welcome</strong> back""#),
+                ("fr", r#""Bon retour""#),
+            ],
+            false,
+        );
+
+        let findings = lint_translations(
+            &parse_result,
+            "",
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "tags differ from EN: EN has <strong>, FR has no tags"
+        );
+    }
+
+    #[test]
+    fn accepts_self_closing_tags_and_ignores_function_arrows() {
+        let parse_result = parse_result_with(
+            "itemCount",
+            &[
+                (
+                    "en",
+                    r#"\n -> if n < 2 then "one<br/>item" else String.fromInt n ++ "<br>items""#,
+                ),
+                (
+                    "fr",
+                    r#"\n -> if n < 2 then "un<br/>élément" else String.fromInt n ++ "<br>éléments""#,
+                ),
+            ],
+            true,
+        );
+
+        let findings = lint_translations(
+            &parse_result,
+            "",
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+        assert!(findings.is_empty(), "unexpected findings: {:?}", findings);
+    }
+
+    #[test]
+    fn respects_configured_severity() {
+        let parse_result = parse_result_with(
+            "bold",
+            &[("en", r#""<b>Bold</i>""#), ("fr", r#""<b>Gras</b>""#)],
+            false,
+        );
+
+        let mut config = LintConfig::default();
+        config
+            .rules
+            .insert(UNBALANCED_MARKUP.to_string(), Severity::Warning);
+        let findings = lint_translations(&parse_result, "", &languages(), &config, None);
+        assert!(findings.iter().all(|f| f.severity == Severity::Warning));
+        assert!(findings.iter().any(|f| f.message == "</i> closes <b>"));
+
+        config
+            .rules
+            .insert(UNBALANCED_MARKUP.to_string(), Severity::Off);
+        assert!(lint_translations(&parse_result, "", &languages(), &config, None).is_empty());
+    }
+
+    #[test]
+    fn reports_the_configured_todo_marker() {
+        let parse_result = parse_result_with(
+            "save",
+            &[("en", r#""Save""#), ("fr", r#""__TODO__""#)],
+            false,
+        );
+
+        let mut config = LintConfig {
+            todo_marker: Some("__TODO__".to_string()),
+            ..LintConfig::default()
+        };
+        let findings = lint_translations(&parse_result, "", &languages(), &config, None);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, TODO_MARKER);
+        assert_eq!(findings[0].language.as_deref(), Some("fr"));
+        assert_eq!(findings[0].severity, Severity::Warning);
+        assert_eq!(findings[0].message, "still has the `__TODO__` marker");
+
+        config
+            .rules
+            .insert(TODO_MARKER.to_string(), Severity::Error);
+        let findings = lint_translations(&parse_result, "", &languages(), &config, None);
+        assert_eq!(findings[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn reports_lambdas_that_dont_match_the_signature() {
+        let mut parse_result = parse_result_with(
+            "itemCount",
+            &[
+                (
+                    "en",
+                    r#"\count total -> String.fromInt count ++ " of " ++ String.fromInt total"#,
+                ),
+                ("fr", r#"\count -> String.fromInt count ++ " articles""#),
+            ],
+            true,
+        );
+        parse_result
+            .translations
+            .get_mut("itemCount")
+            .unwrap()
+            .type_signature = Some("Int -> Int -> String".to_string());
+
+        let findings = lint_translations(
+            &parse_result,
+            "",
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, FUNCTION_ARITY);
+        assert_eq!(findings[0].language.as_deref(), Some("fr"));
+        assert_eq!(
+            findings[0].message,
+            "takes 1 argument but `Int -> Int -> String` has 2"
+        );
+    }
+
+    #[test]
+    fn warns_when_the_file_has_too_many_keys() {
+        let keys = ["checkoutTitle", "checkoutSubmit", "homeTitle", "save"];
+        assert_eq!(key_count_message(keys, 4), None);
+        assert_eq!(
+            key_count_message(keys, 3).unwrap(),
+            "the file has 4 keys; the limit is 3. Consider splitting it into sub-records by prefix (largest: checkout 2, home 1)"
+        );
+
+        let parse_result = parse_result_with("save", &[("en", r#""Save""#)], false);
+        let config = LintConfig {
+            max_keys: Some(1),
+            ..LintConfig::default()
+        };
+        assert!(lint_translations(&parse_result, "", &languages(), &config, None).is_empty());
+    }
+
+    #[test]
+    fn skips_rules_suppressed_by_an_ignore_comment() {
+        let parse_result = parse_result_with(
+            "legal",
+            &[("en", r#""<b>Terms""#), ("fr", r#""<b>Conditions""#)],
+            false,
+        );
+        let glossary =
+            crate::glossary::parse_glossary_csv("en,fr\nTerms,Conditions générales\n").unwrap();
+        let source = "translationsEn =\n    -- i18n-ignore: glossary, unbalanced-markup\n    { legal = \"<b>Terms\"\n    }\n";
+
+        let rules: Vec<&str> = lint_translations(
+            &parse_result,
+            "",
+            &languages(),
+            &LintConfig::default(),
+            Some(&glossary),
+        )
+        .iter()
+        .map(|f| f.rule)
+        .collect();
+        assert_eq!(rules, vec![UNBALANCED_MARKUP, GLOSSARY, UNBALANCED_MARKUP]);
+
+        assert!(lint_translations(
+            &parse_result,
+            source,
+            &languages(),
+            &LintConfig::default(),
+            Some(&glossary),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn extracts_string_literals_from_expressions() {
+        assert_eq!(
+            string_literal_contents(r##"\c -> if c == '"' then "a\"b" else """multi""""##),
+            vec![r#"a\"b"#.to_string(), "multi".to_string()]
+        );
+        assert_eq!(
+            plain_string_value(r#" "Say \"hi\"" "#),
+            Some("Say \"hi\"".to_string())
+        );
+        assert_eq!(plain_string_value(r#""a" ++ "b""#), None);
+        assert_eq!(plain_string_value(r#"\n -> "x""#), None);
+    }
+
+    #[test]
+    fn enforces_annotated_length_budgets_per_language() {
+        let mut parse_result = parse_result_with(
+            "save",
+            &[("en", r#""Save""#), ("fr", r#""Enregistrer""#)],
+            false,
+        );
+        parse_result.translations.extend(
+            parse_result_with(
+                "cancel",
+                &[("en", r#""Cancel""#), ("fr", r#""Annuler l'opération""#)],
+                false,
+            )
+            .translations,
+        );
+        let source = "type alias Translations =\n    -- i18n-max-length: 8\n    { save : String\n\n    -- i18n-max-length: 10\n    , cancel : String\n    }\n";
+
+        let findings = lint_translations(
+            &parse_result,
+            source,
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+
+        let summary: Vec<(&str, &str, &str)> = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.language.as_deref().unwrap(),
+                    f.key.as_str(),
+                    f.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("fr", "cancel", "is 19 characters, budget is 10 (9 over)"),
+                ("fr", "save", "is 11 characters, budget is 8 (3 over)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_the_most_specific_configured_pattern() {
+        let mut config = LintConfig::default();
+        config.max_length.insert("nav*".to_string(), 20);
+        config.max_length.insert("navHome".to_string(), 4);
+
+        assert_eq!(length_budget("navHome", &HashMap::new(), &config), Some(4));
+        assert_eq!(
+            length_budget("navAbout", &HashMap::new(), &config),
+            Some(20)
+        );
+        assert_eq!(length_budget("title", &HashMap::new(), &config), None);
+        assert_eq!(
+            length_budget(
+                "navHome",
+                &HashMap::from([("navHome".to_string(), 9)]),
+                &config
+            ),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn reports_missing_fields_and_comma_style_with_lines() {
+        let source = "type alias Translations =\n    { save : String\n    ,cancel : String \n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { save = \"Enregistrer\"\n    }\n";
+        let parse_result = crate::parser::parse_i18n_content_with_record_name(
+            source,
+            "Translations",
+            &languages(),
+        )
+        .unwrap();
+
+        let findings = lint_translations(
+            &parse_result,
+            source,
+            &languages(),
+            &LintConfig::default(),
+            None,
+        );
+        let summary: Vec<(&str, &str, Option<usize>)> = findings
+            .iter()
+            .map(|f| (f.rule, f.key.as_str(), f.line))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (MISSING_FIELD, "cancel", Some(3)),
+                (TRAILING_WHITESPACE, "", Some(3)),
+                (COMMA_STYLE, "cancel", Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_record_fields_missing_from_the_type() {
+        let source = "type alias Translations =\n    { save : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , extra = \"Extra\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { save = \"Enregistrer\"\n    }\n";
+        let parse_result = crate::parser::parse_i18n_content_with_record_name(
+            source,
+            "Translations",
+            &languages(),
+        )
+        .unwrap();
+
+        let mismatches = key_set_mismatches(&parse_result, source);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].rule, UNKNOWN_FIELD);
+        assert_eq!(mismatches[0].key, "extra");
+        assert_eq!(mismatches[0].line, Some(9));
+        assert_eq!(
+            mismatches[0].message,
+            "is in translationsEn but not in the type"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+use crate::config::Severity;
+use crate::lint::LintFinding;
+
+/// How `lint` prints its findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LintOutput {
+    /// Grouped by language, for people
+    Text,
+    /// GitHub Actions workflow commands, shown inline on the pull request diff
+    Github,
+    /// A SARIF 2.1.0 log, for code-scanning upload
+    Sarif,
+}
+
+/// A lint finding together with the file it was found in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileFinding {
+    pub file: PathBuf,
+    pub finding: LintFinding,
+}
+
+/// One `::error` or `::warning` workflow command per finding
+pub fn github_annotations(findings: &[FileFinding]) -> String {
+    let mut output = String::new();
+    for FileFinding { file, finding } in findings {
+        let command = match finding.severity {
+            Severity::Error => "error",
+            _ => "warning",
+        };
+        let mut properties = vec![format!("file={}", escape_property(&file_uri(file)))];
+        if let Some(line) = finding.line {
+            properties.push(format!("line={}", line));
+        }
+        properties.push(format!("title={}", escape_property(finding.rule)));
+        output.push_str(&format!(
+            "::{} {}::{}\n",
+            command,
+            properties.join(","),
+            escape_data(&annotation_text(finding))
+        ));
+    }
+    output
+}
+
+/// A minimal SARIF 2.1.0 log with one result per finding
+pub fn sarif_log(findings: &[FileFinding]) -> String {
+    let mut rules: Vec<&str> = findings.iter().map(|f| f.finding.rule).collect();
+    rules.sort();
+    rules.dedup();
+
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|FileFinding { file, finding }| {
+            let mut location = json!({ "artifactLocation": { "uri": file_uri(file) } });
+            if let Some(line) = finding.line {
+                location["region"] = json!({ "startLine": line });
+            }
+            json!({
+                "ruleId": finding.rule,
+                "level": match finding.severity {
+                    Severity::Error => "error",
+                    _ => "warning",
+                },
+                "message": { "text": annotation_text(finding) },
+                "locations": [{ "physicalLocation": location }],
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "elm-i18n",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    });
+    let mut output = serde_json::to_string_pretty(&log).expect("a JSON value serializes");
+    output.push('\n');
+    output
+}
+
+/// The finding as one line: "welcome [FR]: is in the type but missing from translationsFr"
+fn annotation_text(finding: &LintFinding) -> String {
+    let mut subject = finding.key.clone();
+    if let Some(lang) = &finding.language {
+        if !subject.is_empty() {
+            subject.push(' ');
+        }
+        subject.push_str(&format!("[{}]", lang.to_uppercase()));
+    }
+    if subject.is_empty() {
+        finding.message.clone()
+    } else {
+        format!("{}: {}", subject, finding.message)
+    }
+}
+
+/// Paths as forward-slash URIs relative to the project, as both formats expect
+fn file_uri(file: &Path) -> String {
+    let path = file.strip_prefix("./").unwrap_or(file);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LintConfig;
+    use crate::lint::lint_translations;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/lint/I18n.elm");
+
+    fn fixture_findings() -> Vec<FileFinding> {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result =
+            parse_i18n_content_with_record_name(FIXTURE, "Translations", &languages).unwrap();
+        lint_translations(
+            &parse_result,
+            FIXTURE,
+            &languages,
+            &LintConfig::default(),
+            None,
+        )
+        .into_iter()
+        .map(|finding| FileFinding {
+            file: PathBuf::from("src/I18n.elm"),
+            finding,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn prints_github_workflow_commands() {
+        assert_eq!(
+            github_annotations(&fixture_findings()),
+            "::error file=src/I18n.elm,line=11,title=missing-field::terms [FR]: is in the type but missing from translationsFr
+::error file=src/I18n.elm,line=24,title=unbalanced-markup::welcome [FR]: <b> is never closed
+::warning file=src/I18n.elm,line=18,title=trailing-whitespace::trailing whitespace on line 18
+"
+        );
+    }
+
+    #[test]
+    fn writes_a_sarif_log() {
+        let sarif = sarif_log(&fixture_findings());
+        let expected = r#"{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "runs": [
+    {
+      "results": [
+        {
+          "level": "error",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/I18n.elm"
+                },
+                "region": {
+                  "startLine": 11
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "terms [FR]: is in the type but missing from translationsFr"
+          },
+          "ruleId": "missing-field"
+        },
+        {
+          "level": "error",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/I18n.elm"
+                },
+                "region": {
+                  "startLine": 24
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "welcome [FR]: <b> is never closed"
+          },
+          "ruleId": "unbalanced-markup"
+        },
+        {
+          "level": "warning",
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/I18n.elm"
+                },
+                "region": {
+                  "startLine": 18
+                }
+              }
+            }
+          ],
+          "message": {
+            "text": "trailing whitespace on line 18"
+          },
+          "ruleId": "trailing-whitespace"
+        }
+      ],
+      "tool": {
+        "driver": {
+          "informationUri": "https://github.com/charles-andre-assus/elm-i18n",
+          "name": "elm-i18n",
+          "rules": [
+            {
+              "id": "missing-field"
+            },
+            {
+              "id": "trailing-whitespace"
+            },
+            {
+              "id": "unbalanced-markup"
+            }
+          ],
+          "version": "VERSION"
+        }
+      }
+    }
+  ],
+  "version": "2.1.0"
+}
+"#;
+        assert_eq!(
+            sarif,
+            expected.replace("VERSION", env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
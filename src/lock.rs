@@ -0,0 +1,214 @@
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+use crate::types::ParseResult;
+
+pub const LOCK_FILE: &str = "elm-i18n.lock";
+
+const LOCK_HEADER: &str = "# elm-i18n lock file: one `file key language hash` line per value.
+# Regenerate it with `elm-i18n lock`; check it with `elm-i18n verify-lock`.
+";
+
+/// A locked value, identified by file, key and language
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LockEntry {
+    pub file: String,
+    pub key: String,
+    pub language: String,
+}
+
+/// Content hash of every value, sorted so the rendered lock is stable
+pub type Lock = BTreeMap<LockEntry, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A value that differs from the lock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub entry: LockEntry,
+    pub kind: DriftKind,
+}
+
+/// Lock entries for one parsed file
+pub fn lock_entries(file: &str, parse_result: &ParseResult, languages: &[String]) -> Lock {
+    let mut lock = Lock::new();
+    for (key, translation) in &parse_result.translations {
+        for lang in languages {
+            if let Some(value) = translation.values.get(lang).filter(|v| !v.is_empty()) {
+                lock.insert(
+                    LockEntry {
+                        file: file.to_string(),
+                        key: key.clone(),
+                        language: lang.clone(),
+                    },
+                    content_hash(value),
+                );
+            }
+        }
+    }
+    lock
+}
+
+pub fn render_lock(lock: &Lock) -> String {
+    render_hashes(LOCK_HEADER, lock)
+}
+
+pub fn parse_lock(content: &str) -> Result<Lock> {
+    parse_hashes(content, LOCK_FILE)
+}
+
+/// `hashes` as `file key language hash` lines under `header`, the format of the
+/// lock and the freshness file
+pub fn render_hashes(header: &str, hashes: &Lock) -> String {
+    let mut output = header.to_string();
+    for (entry, hash) in hashes {
+        output.push_str(&format!(
+            "{} {} {} {}\n",
+            entry.file, entry.key, entry.language, hash
+        ));
+    }
+    output
+}
+
+/// Read what [`render_hashes`] wrote to the file named `name`
+pub fn parse_hashes(content: &str, name: &str) -> Result<Lock> {
+    let mut lock = Lock::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // The file path comes first and may contain spaces
+        let parts: Vec<&str> = line.rsplitn(4, ' ').collect();
+        if parts.len() != 4 {
+            bail!(
+                "Malformed line {} in {}: expected `file key language hash`",
+                i + 1,
+                name
+            );
+        }
+        lock.insert(
+            LockEntry {
+                file: parts[3].to_string(),
+                key: parts[2].to_string(),
+                language: parts[1].to_string(),
+            },
+            parts[0].to_string(),
+        );
+    }
+    Ok(lock)
+}
+
+/// Values added, removed or changed in `current` relative to `locked`, in lock order
+pub fn lock_drift(locked: &Lock, current: &Lock) -> Vec<Drift> {
+    let mut drift: Vec<Drift> = Vec::new();
+    for (entry, hash) in current {
+        match locked.get(entry) {
+            None => drift.push(Drift {
+                entry: entry.clone(),
+                kind: DriftKind::Added,
+            }),
+            Some(locked_hash) if locked_hash != hash => drift.push(Drift {
+                entry: entry.clone(),
+                kind: DriftKind::Changed,
+            }),
+            Some(_) => {}
+        }
+    }
+    for entry in locked.keys() {
+        if !current.contains_key(entry) {
+            drift.push(Drift {
+                entry: entry.clone(),
+                kind: DriftKind::Removed,
+            });
+        }
+    }
+    drift.sort_by(|a, b| a.entry.cmp(&b.entry));
+    drift
+}
+
+/// 64-bit FNV-1a of the value as written in the file, as 16 hex digits
+///
+/// Hand-rolled so the lock stays identical across Rust versions and platforms.
+pub fn content_hash(value: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in value.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, key: &str, language: &str) -> LockEntry {
+        LockEntry {
+            file: file.to_string(),
+            key: key.to_string(),
+            language: language.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_rendered_format() {
+        let mut lock = Lock::new();
+        lock.insert(
+            entry("src/My App/I18n.elm", "save", "fr"),
+            content_hash("\"Sauver\""),
+        );
+        lock.insert(
+            entry("src/I18n.elm", "cancel", "en"),
+            content_hash("\"Cancel\""),
+        );
+
+        let rendered = render_lock(&lock);
+        assert!(rendered.ends_with(&format!(
+            "src/I18n.elm cancel en {}\nsrc/My App/I18n.elm save fr {}\n",
+            content_hash("\"Cancel\""),
+            content_hash("\"Sauver\"")
+        )));
+        assert_eq!(parse_lock(&rendered).unwrap(), lock);
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_values() {
+        let mut locked = Lock::new();
+        locked.insert(
+            entry("src/I18n.elm", "save", "en"),
+            content_hash("\"Save\""),
+        );
+        locked.insert(
+            entry("src/I18n.elm", "close", "en"),
+            content_hash("\"Close\""),
+        );
+        let mut current = Lock::new();
+        current.insert(
+            entry("src/I18n.elm", "save", "en"),
+            content_hash("\"Save!\""),
+        );
+        current.insert(
+            entry("src/I18n.elm", "open", "en"),
+            content_hash("\"Open\""),
+        );
+
+        let drift: Vec<(String, DriftKind)> = lock_drift(&locked, &current)
+            .into_iter()
+            .map(|d| (d.entry.key, d.kind))
+            .collect();
+        assert_eq!(
+            drift,
+            vec![
+                ("close".to_string(), DriftKind::Removed),
+                ("open".to_string(), DriftKind::Added),
+                ("save".to_string(), DriftKind::Changed),
+            ]
+        );
+    }
+}
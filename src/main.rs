@@ -1,25 +1,141 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::needless_range_loop,
+    clippy::type_complexity
+)]
+
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use colored::*;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 
+mod backup;
+mod canonical;
+mod changelog;
+mod ci;
 mod config;
+mod context;
+mod coverage;
+mod currency_format;
+mod date_format;
+mod diff;
+mod discovery;
+mod doctor;
+mod errors;
+mod escaping;
+mod export;
+mod export_cache;
+mod extract;
+mod fix;
+mod format;
+mod freshness;
+mod generated;
 mod generator;
+mod glossary;
+mod grapheme;
+mod group;
+mod hooks;
+mod icu;
+mod language_edit;
+mod languages;
+mod layout;
+mod lexer;
+mod lint;
+mod lint_output;
+mod lock;
+mod namespace;
+mod naming;
+mod nested;
+mod number_format;
 mod parser;
+mod placeholder;
+mod plural;
+mod relative_time;
+mod release_notes;
+mod render;
 mod replacer;
+mod revision;
+mod scan;
+mod select;
+mod status;
+mod summary;
 mod templates;
+mod testgen;
+mod timing;
 mod types;
+mod validate;
+mod verify;
+mod watch;
 
-use crate::config::{config_exists, config_file_path, prompt_setup_message, Config, FileConfig};
+use crate::canonical::canonicalize;
+use crate::changelog::{
+    append_records, format_timestamp, timestamp_now, translation_changes, ChangeRecord,
+};
+use crate::ci::{Check, CHECKS};
+use crate::config::{
+    config_exists, config_file_path, prompt_setup_message, ChangelogConfig, CiConfig, Config,
+    FileConfig, KeyStyle, LintConfig, PlaceholderStyle, ScanConfig, Severity,
+};
+use crate::diff::{unified_diff, DiffLine};
+use crate::discovery::{files_for_glob, find_i18n_files};
+use crate::doctor::{diagnose, DiagnosisLevel};
+use crate::errors::{Code, Coded};
+use crate::export::{split_by_key, ExportFormat};
+use crate::export_cache::{ExportCache, ExportRecord, EXPORT_CACHE_FILE};
+use crate::extract::{enclosing_definition, extract, hardcoded_strings, in_scope};
+use crate::fix::{fix_source, merge_duplicate_records};
+use crate::format::{format_file, FORMAT_TIMEOUT};
+use crate::freshness::{
+    mark_reviewed, parse_freshness, refresh, render_freshness, stale_values, FRESHNESS_FILE,
+};
 use crate::generator::{
-    add_translation_with_record_name, create_i18n_file, remove_translation_with_record_name,
+    add_translation_placed, add_translation_to_content, add_translation_with_record_name,
+    add_translations_to_content, create_i18n_file, modify_translation_in_content,
+    remove_translation_from_content, remove_translation_with_record_name,
+    rename_translation_in_content, set_indent, Placement,
+};
+use crate::glossary::load_glossary;
+use crate::hooks::{run_post_edit_hook, EditWatcher};
+use crate::language_edit::{
+    add_case_branch, add_language_to_content, dispatcher_check, round_trip_check,
+};
+use crate::languages::{normalize_language_code, LanguageNote};
+use crate::layout::layout_translation_blocks;
+use crate::lint::{
+    field_line, field_lines, finish_findings, key_count_message, key_matches_pattern,
+    key_set_mismatches, lint_translations, plain_string_value, stale_findings, LintFinding,
+    MAX_KEYS, STALE_TRANSLATION, TODO_MARKER,
+};
+use crate::lint_output::{github_annotations, sarif_log, FileFinding, LintOutput};
+use crate::lock::{
+    content_hash, lock_drift, lock_entries, parse_lock, render_lock, DriftKind, Lock, LOCK_FILE,
+};
+use crate::namespace::keys_by_prefix;
+use crate::naming::NamingRules;
+use crate::parser::{
+    check_key_exists_with_record_name, duplicate_records, has_translation_record, is_function_type,
+    line_list, parse_i18n_content_with_record_name, parse_i18n_file_with_record_name,
+    read_elm_file, starts_with_bom,
 };
-use crate::parser::{check_key_exists_with_record_name, parse_i18n_file_with_record_name};
 use crate::replacer::{find_string_occurrences, find_unused_keys, replace_strings};
-use crate::templates::get_i18n_template_with_record_name;
-use crate::types::Translation;
+use crate::revision::{
+    blame_file, file_at_revision, key_changes, BlameLine, KeyChanges, RevisionError,
+};
+use crate::scan::{
+    closest_key, elm_source_directories, holds_translations, kept_keys, RenamedFile, SourceSet,
+    KEEP_NEXT_LINE,
+};
+use crate::status::{file_status, render_html, CoverageEntry, StatusReport};
+use crate::summary::Summary;
+use crate::templates::{get_i18n_template_with_record_name, render_template};
+use crate::types::{ParseResult, Translation};
+use crate::validate::{
+    empty_source_keys, implementation_problems, literal_type_mismatch, placeholder_style_issues,
+    validate_translations, value_status, ValueStatus,
+};
+use crate::verify::{find_elm, find_elm_project, find_executable, verify_module, VerifyOutcome};
 
 // Elm reserved words
 const ELM_RESERVED_WORDS: &[&str] = &[
@@ -49,6 +165,8 @@ const ELM_RESERVED_WORDS: &[&str] = &[
 const LOCAL_CONFIG_FILE: &str = "elm-i18n/config.json";
 const LOCAL_SUPPRESSED_FILE: &str = "elm-i18n/suppressed.json";
 const SHARED_VALUES_CHECK_NAME: &str = "shared-values";
+/// Widest `--indent`; Elm needs fields indented, so the narrowest is one space
+const MAX_INDENT: usize = 8;
 
 #[derive(Parser)]
 #[command(name = "elm-i18n")]
@@ -59,6 +177,73 @@ struct Cli {
     #[arg(long, global = true)]
     target: Option<String>,
 
+    /// Read the I18n module from stdin and print the modified module to stdout
+    #[arg(long, global = true)]
+    stdin: bool,
+
+    /// Print the modified module to stdout instead of writing the file
+    #[arg(long, global = true)]
+    stdout: bool,
+
+    /// Keep the edit even if the configured post-edit hook fails
+    #[arg(long, global = true)]
+    ignore_hook_failure: bool,
+
+    /// Type-check edited files with `elm make` and undo the edit if they don't compile
+    ///
+    /// Without an elm binary or an elm.json above the file, there's nothing to
+    /// check with: it warns and keeps the edit.
+    #[arg(long, global = true)]
+    verify: bool,
+
+    /// Path to the elm binary (defaults to the `elm` on PATH)
+    #[arg(long, global = true)]
+    elm_path: Option<PathBuf>,
+
+    /// Run edited files through elm-format (also enabled by "elmFormat" in the config)
+    #[arg(long, global = true)]
+    format: bool,
+
+    /// Path to the elm-format binary (defaults to the `elm-format` on PATH)
+    #[arg(long, global = true)]
+    elm_format_path: Option<PathBuf>,
+
+    /// Spaces to indent new fields with (defaults to "indent" in the config, or 4)
+    #[arg(long, global = true, value_name = "N")]
+    indent: Option<usize>,
+
+    /// Don't update elm-i18n.lock after an edit
+    #[arg(long, global = true)]
+    no_lock: bool,
+
+    /// Append a JSON line per changed value (key, language, old and new) to this file
+    #[arg(long, global = true)]
+    changelog: Option<PathBuf>,
+
+    /// Print how long reading, parsing, editing and writing took, to stderr
+    #[arg(long, global = true)]
+    timing: bool,
+
+    /// Record which source text each translation was written against, in
+    /// elm-i18n.freshness, so `lint --stale` can list the ones to review
+    #[arg(long, global = true)]
+    track_freshness: bool,
+
+    /// Keep a backup of each edited file, named with this suffix; `{timestamp}` in it
+    /// keeps every one (e.g., ".{timestamp}.bak"; also "backupSuffix" in the config)
+    #[arg(long, global = true, value_name = "SUFFIX")]
+    backup_suffix: Option<String>,
+
+    /// Print errors to stderr as a JSON object with a code, message and the file,
+    /// key and line they concern, leaving other output as it is
+    #[arg(long, global = true)]
+    errors_as_json: bool,
+
+    /// Keep translator notes in this JSON file rather than in the I18n.context.json
+    /// next to each translation file
+    #[arg(long, global = true, value_name = "PATH")]
+    context_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -80,9 +265,13 @@ enum Commands {
         key: String,
 
         /// Translation value as LANG=VALUE (e.g., -t en="Hello" -t fr="Bonjour")
-        #[arg(short = 't', long = "translation", required = true)]
+        #[arg(short = 't', long = "translation", required_unless_present = "todo")]
         translations: Vec<String>,
 
+        /// Languages to fill with the TODO marker instead of a value (e.g., --todo fr,de)
+        #[arg(long, value_delimiter = ',', conflicts_with = "raw")]
+        todo: Vec<String>,
+
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
@@ -94,6 +283,44 @@ enum Commands {
         /// Root directory to search for replacements (defaults to src/)
         #[arg(long, default_value = "src")]
         src_dir: PathBuf,
+
+        /// Insert the values as Elm expressions instead of strings (requires --type)
+        #[arg(long, requires = "type_annotation", conflicts_with = "replace")]
+        raw: bool,
+
+        /// Type of a --raw value (e.g., "Int", "Bool")
+        #[arg(long = "type", requires = "raw")]
+        type_annotation: Option<String>,
+
+        /// Add the key even if it breaks the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
+
+        /// What the key is for, kept for translators in the context file
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Read the values as ICU plural/select messages and add a function (as add-icu does)
+        #[arg(long, conflicts_with_all = ["todo", "raw", "replace"])]
+        icu: bool,
+
+        /// Type of a {placeholder} in the values, which are Strings by default (e.g., --param count:Int)
+        #[arg(long = "param", value_name = "NAME:TYPE", conflicts_with_all = ["raw", "icu"])]
+        params: Vec<String>,
+
+        /// Take a dotted key like checkout.title as a key of nested records
+        /// (t.checkout.title), making the groups it needs
+        #[arg(long)]
+        nested: bool,
+
+        /// Put the new field right before this key, in the type and every record,
+        /// instead of last
+        #[arg(long, value_name = "KEY", conflicts_with = "after")]
+        before: Option<String>,
+
+        /// Put the new field right after this key, in the type and every record
+        #[arg(long, value_name = "KEY")]
+        after: Option<String>,
     },
 
     /// Add a function translation
@@ -113,12 +340,144 @@ enum Commands {
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
+
+        /// Add the key even if it breaks the naming rules in the config, or an
+        /// implementation looks malformed to the structural check
+        #[arg(long)]
+        no_validate: bool,
+
+        /// What the key is for, kept for translators in the context file
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Add a function translation written as ICU plural/select messages
+    #[command(name = "add-icu")]
+    AddIcu {
+        /// The function key
+        key: String,
+
+        /// ICU message as LANG=MESSAGE (e.g., -t en="{count, plural, one {# item} other {# items}}")
+        #[arg(short = 't', long = "translation", alias = "lang", required = true)]
+        translations: Vec<String>,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Add the key even if it breaks the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
+
+        /// What the key is for, kept for translators in the context file
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Add a plural function with a value for each CLDR plural category of each language
+    #[command(name = "add-plural")]
+    AddPlural {
+        /// The function key
+        key: String,
+
+        /// Value of a plural category as LANG.CATEGORY=VALUE, with # or {count} for the
+        /// count (e.g., -t en.one="# item" -t en.other="# items"); LANG.0=VALUE is for
+        /// an exact count, and --en-one "# item" is the same as -t en.one="# item"
+        #[arg(short = 't', long = "translation", required = true)]
+        translations: Vec<String>,
+
+        /// Name of the count in the function's record
+        #[arg(long, default_value = "count")]
+        count: String,
+
+        /// Use the CLDR ordinal categories (1st, 2nd, 3rd, 4th) instead of the
+        /// cardinal ones
+        #[arg(long)]
+        ordinal: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Add the key even if it breaks the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
+
+        /// What the key is for, kept for translators in the context file
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Add a function translation that picks its text by a variant, such as a gender
+    #[command(name = "add-select")]
+    AddSelect {
+        /// The function key
+        key: String,
+
+        /// Name of the argument the text is picked by
+        #[arg(long)]
+        arg: String,
+
+        /// The variants, comma-separated; `other` matches what the others don't
+        /// (defaults to the constructors of --arg-type)
+        #[arg(long, value_delimiter = ',')]
+        variants: Vec<String>,
+
+        /// Type of the argument: a custom type declared in the module, or exposed
+        /// to it by an import, is matched by its constructors; a String by the
+        /// variants as literals
+        #[arg(long, default_value = "String")]
+        arg_type: String,
+
+        /// Value of a variant as LANG.VARIANT=VALUE (e.g., -t fr.female="Bienvenue")
+        #[arg(short = 't', long = "translation", required = true)]
+        translations: Vec<String>,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Add the key even if it breaks the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
+
+        /// What the key is for, kept for translators in the context file
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// Add every key listed in a file (one per line) with empty values
+    Scaffold {
+        /// File listing the keys; blank lines and `#` comments are ignored
+        manifest: PathBuf,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Add the keys even if they break the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
     },
 
     /// Check if a translation key exists
     Check {
-        /// The translation key to check
-        key: String,
+        /// The translation key to check (several with --complete)
+        #[arg(required = true, value_name = "KEY")]
+        keys: Vec<String>,
+
+        /// Only print this language's value (with --complete, only count these
+        /// comma-separated languages)
+        #[arg(long, alias = "lang", value_delimiter = ',')]
+        language: Vec<String>,
+
+        /// Check that every key has a real value (not empty or TODO) in every language
+        #[arg(long, conflicts_with = "json")]
+        complete: bool,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
 
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
@@ -134,18 +493,88 @@ enum Commands {
         /// Path where to create I18n.elm (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
+
+        /// Elm module to start from instead of the built-in one, with placeholders
+        /// such as {{recordName}} and {{#each language}}…{{/each}}
+        #[arg(long, value_name = "PATH")]
+        template: Option<PathBuf>,
+
+        /// Add the backup file patterns to .gitignore without asking
+        #[arg(long)]
+        gitignore: bool,
     },
 
-    /// Remove a translation
+    /// Remove translations
     Remove {
-        /// The translation key to remove
-        key: String,
+        /// The translation keys to remove
+        #[arg(required = true)]
+        keys: Vec<String>,
+
+        /// Search the Elm code first and keep keys it still reads
+        #[arg(long)]
+        check_usages: bool,
+
+        /// Directory of Elm code to search; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR", requires = "check_usages")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Remove keys the Elm code still reads anyway, after listing the reads
+        #[arg(long, requires = "check_usages")]
+        force: bool,
 
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
     },
 
+    /// Rename a translation key
+    Rename {
+        /// The key to rename
+        old_key: String,
+
+        /// Its new name
+        new_key: String,
+
+        /// Rename every key that starts with OLD_KEY as a word (accountTitle, and
+        /// account itself) to start with NEW_KEY instead
+        #[arg(long)]
+        prefix: bool,
+
+        /// Also rename reads of the key off translations in the Elm code, listing
+        /// the reads it can't tell are translations instead of touching them
+        #[arg(long)]
+        update_usages: bool,
+
+        /// Directory of Elm code to update; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR", requires = "update_usages")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Elm files not to update, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB", requires = "update_usages")]
+        ignore_path: Vec<String>,
+
+        /// Show the changes as diffs without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// List the groups of keys, or rename or move one with all of its keys
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm", global = true)]
+        file: PathBuf,
+    },
+
     /// Remove all unused translations
     RemoveUnused {
         /// Path to I18n.elm file (defaults to src/I18n.elm)
@@ -161,6 +590,36 @@ enum Commands {
         confirm: bool,
     },
 
+    /// Remove every key the Elm code never reads, in one edit per file
+    Prune {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Directory of Elm code to search; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Key to keep even though nothing reads it; `*` matches any characters.
+        /// Repeatable, and added to scan.ignore in the config
+        #[arg(long, value_name = "KEY")]
+        keep: Vec<String>,
+
+        /// Elm files not to search, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB")]
+        ignore_path: Vec<String>,
+
+        /// Remove the keys without asking
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Show the diff without writing it
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
+
     /// List all translations
     List {
         /// Path to I18n.elm file (defaults to src/I18n.elm)
@@ -174,6 +633,170 @@ enum Commands {
         /// Filter keys by pattern
         #[arg(long)]
         filter: Option<String>,
+
+        /// List translations from every file matching a glob (e.g., "src/**/I18n.elm")
+        #[arg(long)]
+        glob: Option<String>,
+
+        /// Only list keys that still have the TODO marker in some language
+        #[arg(long)]
+        todo: bool,
+
+        /// Another version of the file to mark each key against, as new, changed
+        /// or removed
+        #[arg(long, value_name = "FILE", conflicts_with = "glob")]
+        diff_base: Option<PathBuf>,
+    },
+
+    /// Print every key, one per line and sorted, for other tools to read
+    Keys {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Only print keys of function translations
+        #[arg(long)]
+        functions_only: bool,
+    },
+
+    /// Report keys that no Elm code outside the translation files reads
+    Scan {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Directory of Elm code to search; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Key to count as used, e.g. one read through a dynamic lookup; `*` matches
+        /// any characters. Repeatable, and added to scan.ignore in the config
+        #[arg(long, value_name = "KEY")]
+        ignore: Vec<String>,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Exit with status 1 when a key is unused
+        #[arg(long, conflicts_with = "missing")]
+        fail_on_unused: bool,
+
+        /// Report reads of keys that don't exist instead, exiting with status 1 if
+        /// there are any
+        #[arg(long, conflicts_with = "ignore")]
+        missing: bool,
+
+        /// Elm files not to search, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB")]
+        ignore_path: Vec<String>,
+    },
+
+    /// List every place in the Elm code that reads a key
+    Usages {
+        /// The translation key to look for
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        key: Option<String>,
+
+        /// List the usages of every key
+        #[arg(long)]
+        all: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Directory of Elm code to search; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Elm files not to search, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB")]
+        ignore_path: Vec<String>,
+
+        /// Print the usages as JSON, keyed by translation key
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List string literals in the Elm code that look like copy to translate
+    Extract {
+        /// Directory of Elm code to search; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Function whose string argument counts as copy, e.g. "Ui.label". Repeatable,
+        /// and added to scan.extractFunctions in the config (or to the default text,
+        /// placeholder, title and alt)
+        #[arg(long = "function", value_name = "NAME")]
+        functions: Vec<String>,
+
+        /// Elm files not to search, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB")]
+        ignore_path: Vec<String>,
+
+        /// Print the strings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Move a hardcoded string `extract` found into a new translation key
+    Rewrite {
+        /// The Elm file the string is in
+        source: PathBuf,
+
+        /// Line of the string, as `extract` reports it
+        #[arg(long)]
+        line: usize,
+
+        /// The new translation key
+        #[arg(long)]
+        key: String,
+
+        /// Value for another language as LANG=VALUE (e.g., -t fr="Enregistrer"); the
+        /// string itself is the --lang value
+        #[arg(short = 't', long = "translation")]
+        translations: Vec<String>,
+
+        /// Languages to fill with the TODO marker instead of a value (e.g., --todo fr,de)
+        #[arg(long, value_delimiter = ',')]
+        todo: Vec<String>,
+
+        /// The text `extract` reported, checked against the line so a stale line
+        /// number doesn't move the wrong string
+        #[arg(long)]
+        text: Option<String>,
+
+        /// Language the string is written in (defaults to en, or the first configured
+        /// language)
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// The translations value the code around the string has (e.g., "model.t")
+        #[arg(long, default_value = "t")]
+        accessor: String,
+
+        /// Function whose string argument counts as copy, as for `extract`
+        #[arg(long = "function", value_name = "NAME")]
+        functions: Vec<String>,
+
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Add the key even if it breaks the naming rules in the config
+        #[arg(long)]
+        no_validate: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
     },
 
     /// Find keys that have exactly the same translations
@@ -196,2302 +819,11023 @@ enum Commands {
         suppress: bool,
     },
 
-    /// Modify an existing translation (update specific language values only)
-    Modify {
-        /// The translation key to modify
-        key: String,
-
-        /// Translation value as LANG=VALUE (e.g., -t es="Hola")
-        #[arg(short = 't', long = "translation", required = true)]
-        translations: Vec<String>,
-
+    /// Check translations for problems (e.g., unbalanced markup tags)
+    Lint {
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
-    },
 
-    /// Bulk-modify translations for one language from a JSON file
-    #[command(name = "modify-bulk")]
-    ModifyBulk {
-        /// Language code to modify (e.g., "es", "de")
+        /// Lint every file matching a glob (e.g., "src/**/I18n.elm")
         #[arg(long)]
-        lang: String,
+        glob: Option<String>,
 
-        /// Path to JSON file with key-value translations (e.g., {"loading": "Cargando...", ...})
-        #[arg(long = "from")]
-        json_file: PathBuf,
+        /// Apply the mechanical fixes (whitespace, comma style, missing fields)
+        #[arg(long)]
+        fix: bool,
 
+        /// With --fix, show the changes as a diff without writing them
+        #[arg(long, requires = "fix")]
+        dry_run: bool,
+
+        /// How to print findings: "github" annotates the pull request in GitHub
+        /// Actions, "sarif" prints a SARIF 2.1.0 log for code scanning
+        #[arg(long, value_enum, default_value_t = LintOutput::Text, conflicts_with = "fix")]
+        output: LintOutput,
+
+        /// Treat a rule's findings as errors, whatever the config says (e.g.,
+        /// --deny todo-marker on release branches)
+        #[arg(long, value_name = "RULE")]
+        deny: Vec<String>,
+
+        /// Exit non-zero on warnings too, not just errors
+        #[arg(long)]
+        fail_on_warning: bool,
+
+        /// Also report translations whose source text changed since they were
+        /// written (needs --track-freshness)
+        #[arg(long, conflicts_with = "fix")]
+        stale: bool,
+    },
+
+    /// Run the checks a merge should pass, reading each file once
+    Ci {
         /// Path to I18n.elm file (defaults to src/I18n.elm)
         #[arg(long, default_value = "src/I18n.elm")]
         file: PathBuf,
-    },
 
-    /// Add a new language by duplicating an existing one
-    #[command(name = "add-language")]
-    AddLanguage {
-        /// New language code (e.g., "de", "es", "ja")
-        new_lang: String,
+        /// Also report keys the Elm code under this directory never reads; repeat
+        /// for more
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
 
-        /// Existing language to copy values from (e.g., "en")
-        #[arg(long, default_value = "en")]
-        from: String,
+        /// How to print findings: "github" annotates the pull request in GitHub
+        /// Actions, "sarif" prints a SARIF 2.1.0 log for code scanning
+        #[arg(long, value_enum, default_value_t = LintOutput::Text)]
+        output: LintOutput,
     },
 
-    /// Show version information
-    Version,
-}
-
-/// Validates and cleans a translation key
-fn validate_and_clean_key(key: &str) -> Result<String> {
-    // Check for forbidden characters
-    if key.contains('.') {
-        eprintln!(
-            "{} Error: Translation keys cannot contain dots (.)",
-            "✗".red()
-        );
-        eprintln!(
-            "{} The dot character is reserved for accessing nested translations (e.g., t.welcome)",
-            "ℹ".blue()
-        );
-        eprintln!("{} Please use camelCase or underscores instead", "ℹ".blue());
-        std::process::exit(1);
-    }
+    /// Lay out the type and the language records consistently, without elm-format
+    Format {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
 
-    // Handle reserved words
-    let mut cleaned_key = key.to_string();
-    if ELM_RESERVED_WORDS.contains(&key) {
-        cleaned_key = format!("{}_", key);
-        println!(
-            "{} Warning: '{}' is a reserved word in Elm, using '{}' instead",
-            "⚠".yellow(),
-            key.yellow(),
-            cleaned_key.green()
-        );
-    }
+        /// Don't write anything; fail if a file isn't formatted (for pre-commit hooks)
+        #[arg(long)]
+        check: bool,
+    },
 
-    // Validate key format (alphanumeric + underscores, starting with letter)
-    if !cleaned_key.chars().next().unwrap_or('0').is_alphabetic() {
-        eprintln!(
-            "{} Error: Translation keys must start with a letter",
-            "✗".red()
-        );
-        std::process::exit(1);
-    }
+    /// Rewrite the file in one canonical form: format's layout, keys sorted, String
+    /// literals and blank lines normalized
+    Canonicalize {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
 
-    if !cleaned_key.chars().all(|c| c.is_alphanumeric() || c == '_') {
-        eprintln!(
-            "{} Error: Translation keys can only contain letters, numbers, and underscores",
-            "✗".red()
-        );
-        std::process::exit(1);
-    }
+        /// Don't write anything; fail if a file isn't canonical (for pre-commit hooks)
+        #[arg(long)]
+        check: bool,
+    },
 
-    Ok(cleaned_key)
-}
+    /// Show how much of each language is translated, empty or still marked TODO, and
+    /// how many keys each prefix has
+    Stats {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
 
-/// Parse translation CLI arguments in LANG=VALUE format
-fn parse_translation_args(
-    args: &[String],
-    languages: &[String],
-) -> Result<std::collections::HashMap<String, String>> {
-    let mut values = std::collections::HashMap::new();
+        /// Show the stats of every file matching a glob (e.g., "src/**/I18n.elm")
+        #[arg(long)]
+        glob: Option<String>,
 
-    for arg in args {
-        let (lang, value) = arg.split_once('=').ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid translation format: '{}'. Expected LANG=VALUE (e.g., en=\"Hello\")",
-                arg
-            )
-        })?;
-        let lang = lang.trim().to_lowercase();
-        if !languages.contains(&lang) {
-            eprintln!(
-                "{} Warning: language '{}' is not in configured languages: {}",
-                "⚠".yellow(),
-                lang.yellow(),
-                languages.join(", ")
-            );
-        }
-        values.insert(lang, value.to_string());
-    }
+        /// Show only how complete each language is, as a bar chart on a terminal
+        #[arg(long)]
+        by_language: bool,
 
-    // Check that all configured languages have values
-    for lang in languages {
-        if !values.contains_key(lang) {
-            eprintln!(
-                "{} Missing translation for language '{}'. Use -t {}=\"...\"",
-                "✗".red(),
-                lang.yellow(),
-                lang
-            );
-            std::process::exit(1);
-        }
-    }
+        /// Print the counts as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
-    Ok(values)
-}
+    /// Show how much of each language is translated, and fail under a threshold
+    Coverage {
+        /// Only report this language; repeat for more
+        #[arg(long = "lang", value_name = "LANG")]
+        langs: Vec<String>,
 
-/// Parse translation args without requiring all languages (for modify command)
-fn parse_partial_translation_args(
-    args: &[String],
-    languages: &[String],
-) -> Result<std::collections::HashMap<String, String>> {
-    let mut values = std::collections::HashMap::new();
+        /// Exit non-zero when a reported language is under this percentage (e.g., 95)
+        #[arg(long, value_name = "PERCENT")]
+        fail_under: Option<f64>,
 
-    for arg in args {
-        let (lang, value) = arg.split_once('=').ok_or_else(|| {
-            anyhow::anyhow!(
-                "Invalid translation format: '{}'. Expected LANG=VALUE (e.g., es=\"Hola\")",
-                arg
-            )
-        })?;
-        let lang = lang.trim().to_lowercase();
-        if !languages.contains(&lang) {
-            eprintln!(
-                "{} Warning: language '{}' is not in configured languages: {}",
-                "⚠".yellow(),
-                lang.yellow(),
-                languages.join(", ")
-            );
-        }
-        values.insert(lang, value.to_string());
-    }
+        /// Print the coverage as JSON
+        #[arg(long)]
+        json: bool,
 
-    if values.is_empty() {
-        anyhow::bail!("At least one translation must be provided");
-    }
+        /// Write a shields.io endpoint badge for this language; repeat for more
+        #[arg(long = "badge", value_name = "LANG", conflicts_with = "json")]
+        badges: Vec<String>,
 
-    Ok(values)
-}
+        /// Write a badge for every reported language
+        #[arg(long, conflicts_with_all = ["badges", "json"])]
+        all: bool,
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+        /// Where to write badges: the file for one, or a directory of LANG.json files
+        /// for several (prints a single badge to stdout without it)
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
 
-    // Handle commands that don't need config
-    match &cli.command {
-        Commands::Setup => return handle_setup(),
-        Commands::Version => return handle_version(),
-        Commands::Status => return handle_status(),
-        Commands::SetupClaude => return handle_setup_claude(),
-        _ => {}
-    }
+        /// Make badges red under this percentage
+        #[arg(long, value_name = "PERCENT", default_value_t = coverage::BADGE_RED_UNDER)]
+        red_under: f64,
 
-    // Load config for all other commands
-    let config = match Config::load()? {
-        Some(config) => config,
-        None => {
-            prompt_setup_message();
-            std::process::exit(1);
-        }
-    };
+        /// Make badges yellow under this percentage, and green from it
+        #[arg(long, value_name = "PERCENT", default_value_t = coverage::BADGE_YELLOW_UNDER)]
+        yellow_under: f64,
 
-    // Determine target file based on config and shortcut
-    let (file_path, record_name) = determine_target_file(&config, &cli.target, &cli.command)?;
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
 
-    let languages = config.languages();
+    /// Write a status page of every key and language for people who don't use the CLI
+    #[command(group(clap::ArgGroup::new("output").required(true).args(["html", "json"])))]
+    Report {
+        /// Write a self-contained HTML page to this path (e.g., report.html)
+        #[arg(long, value_name = "PATH")]
+        html: Option<PathBuf>,
 
-    match cli.command {
-        Commands::Setup => unreachable!(),
+        /// Print the same data as JSON instead
+        #[arg(long)]
+        json: bool,
 
-        Commands::Add {
-            key,
-            translations,
-            file,
-            replace,
-            src_dir,
-        } => {
-            let cleaned_key = validate_and_clean_key(&key)?;
-            let values = parse_translation_args(&translations, languages)?;
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            let actual_src_dir = if src_dir.to_str() == Some("src") {
-                config.source_dir().clone()
-            } else {
-                src_dir
-            };
-            handle_add(
-                &actual_file,
-                &cleaned_key,
-                &values,
-                false,
-                None,
-                replace,
-                &actual_src_dir,
-                &record_name,
-                languages,
-            )?;
-        }
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
 
-        Commands::AddFunction {
-            key,
-            type_sig,
-            translations,
-            file,
-        } => {
-            let cleaned_key = validate_and_clean_key(&key)?;
-            let values = parse_translation_args(&translations, languages)?;
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_add(
-                &actual_file,
-                &cleaned_key,
-                &values,
-                true,
-                Some(type_sig),
-                false,
-                config.source_dir(),
-                &record_name,
-                languages,
-            )?;
-        }
+    /// Check that every translation has a value in every language
+    Validate {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
 
-        Commands::Check { key, file } => {
-            let cleaned_key = validate_and_clean_key(&key)?;
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_check(&actual_file, &cleaned_key, &record_name, languages)?;
-        }
+        /// Validate every file matching a glob (e.g., "src/**/I18n.elm")
+        #[arg(long)]
+        glob: Option<String>,
 
-        Commands::Init {
-            languages: init_langs,
-            file,
-        } => {
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_init(&actual_file, &init_langs, &record_name)?;
-        }
+        /// Also check function arguments and {placeholder} names across languages
+        #[arg(long)]
+        strict: bool,
 
-        Commands::Modify {
-            key,
-            translations,
-            file,
-        } => {
-            let cleaned_key = validate_and_clean_key(&key)?;
-            let values = parse_partial_translation_args(&translations, languages)?;
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_modify(&actual_file, &cleaned_key, &values, &record_name, languages)?;
-        }
+        /// Warn about keys without a description in the context file
+        #[arg(long)]
+        require_descriptions: bool,
 
-        Commands::ModifyBulk {
-            lang,
-            json_file,
-            file,
-        } => {
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_modify_bulk(&actual_file, &lang, &json_file, &record_name, languages)?;
-        }
+        /// Flag values whose placeholders aren't written this way, such as a `%s`
+        /// (defaults to "placeholderStyle" in the config)
+        #[arg(long, value_enum)]
+        placeholder_style: Option<PlaceholderStyle>,
 
-        Commands::Remove { key, file } => {
-            let cleaned_key = validate_and_clean_key(&key)?;
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_remove(&actual_file, &cleaned_key, &record_name, languages)?;
-        }
+        /// Exit non-zero on warnings too (empty source values, missing descriptions,
+        /// placeholders in another style), not just problems
+        #[arg(long)]
+        fail_on_warning: bool,
+    },
+
+    /// Explain what keeps elm-i18n from reading a file, without changing it
+    Doctor {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Merge language records defined more than once (e.g., two `translationsFr`
+        /// after a bad merge) into one, the later definition winning per field
+        #[arg(long)]
+        merge_duplicates: bool,
+    },
+
+    /// Type-check the I18n module with `elm make`
+    Verify {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Record every translation's content hash in elm-i18n.lock
+    Lock,
+
+    /// Fail if translations were added, removed or changed since the last `lock`
+    #[command(name = "verify-lock")]
+    VerifyLock,
+
+    /// Generate an elm-test module that references every translation in every language
+    #[command(name = "gen-test")]
+    GenTest {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Where to write the test module (defaults to tests/<Module>Test.elm)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a copy of the module with each `${NAME}` in the values filled in from
+    /// the environment, for a build; the source keeps the placeholders
+    Render {
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Where to write the rendered module (e.g., build/src/I18n.elm)
+        #[arg(long)]
+        output: PathBuf,
+
+        /// The module name to give it (defaults to the name it has)
+        #[arg(long)]
+        module: Option<String>,
+
+        /// A NAME=value file to read variables from, such as a .env or a mounted
+        /// secret. Repeatable; later files win, and the environment wins over all
+        #[arg(long, value_name = "PATH")]
+        env_file: Vec<PathBuf>,
+    },
+
+    /// Export the translations for review (e.g., a Markdown table)
+    Export {
+        /// What to export; `--format FORMAT` is the same
+        #[arg(value_enum, value_name = "FORMAT")]
+        export_format: ExportFormat,
+
+        /// Write to this file instead of stdout; with `{lang}` in it, writes a file
+        /// per language (e.g., locales/{lang}.toml)
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Only write the files whose translations changed since the last export to
+        /// them, for a `watch` loop
+        #[arg(long, requires = "output")]
+        only_changed: bool,
+
+        /// Write a file per key, with every language, to the --output directory
+        /// (e.g., out/welcome.toml)
+        #[arg(long, requires = "output")]
+        split: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// List the keys added, removed and changed since a git revision
+    Diff {
+        /// Revision to compare with (e.g., a tag like v2.3.0, or main)
+        #[arg(long, value_name = "REV")]
+        base: String,
+
+        /// Where the file was at that revision, if it has moved since
+        #[arg(long, value_name = "PATH")]
+        base_path: Option<PathBuf>,
+
+        /// Only compare this language; repeat for more
+        #[arg(long = "lang", value_name = "LANG")]
+        langs: Vec<String>,
+
+        /// Only list keys whose values changed, not added or removed ones
+        #[arg(long)]
+        changed_only: bool,
+
+        /// Print the changes as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Write release notes of the keys added, changed and removed between two git revisions
+    Changelog {
+        /// Revision of the previous release (e.g., a tag like v2.3.0)
+        #[arg(long, value_name = "REV")]
+        from: String,
+
+        /// Revision of this release (defaults to the working tree)
+        #[arg(long, value_name = "REV")]
+        to: Option<String>,
+
+        /// List the values in every language, not just the source language
+        #[arg(long)]
+        all_languages: bool,
+
+        /// Print the changes as JSON instead of Markdown (`--format json` is the
+        /// same, and `--format markdown` the default)
+        #[arg(long)]
+        json: bool,
+
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Show who last changed a key's type and values, and when, from git blame
+    Blame {
+        /// The translation key to look up
+        #[arg(required_unless_present = "all")]
+        key: Option<String>,
+
+        /// Show every key
+        #[arg(long, conflicts_with = "key")]
+        all: bool,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Mark translations as reviewed against the current source text, or stop
+    /// tracking freshness
+    #[command(group(clap::ArgGroup::new("action").required(true).args(["review", "all", "remove"])))]
+    Freshness {
+        /// Mark this key's translations as reviewed; repeat for more
+        #[arg(long, value_name = "KEY")]
+        review: Vec<String>,
+
+        /// Mark every translation as reviewed
+        #[arg(long)]
+        all: bool,
+
+        /// Only mark this language; repeat for more
+        #[arg(long = "lang", value_name = "LANG")]
+        langs: Vec<String>,
+
+        /// Stop tracking: delete elm-i18n.freshness
+        #[arg(long, conflicts_with_all = ["review", "all", "langs"])]
+        remove: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Add helpers to the I18n module, kept between marker comments so running it
+    /// again refreshes them
+    Generate {
+        #[command(subcommand)]
+        helpers: GenerateHelpers,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm", global = true)]
+        file: PathBuf,
+    },
+
+    /// List the backups next to the I18n file, or delete the older ones
+    Backups {
+        #[command(subcommand)]
+        action: BackupsAction,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm", global = true)]
+        file: PathBuf,
+    },
+
+    /// Put back a backup of the I18n file, or list the backups there are
+    Restore {
+        /// The backup to restore: its number in --list, or its path (defaults to the newest)
+        backup: Option<String>,
+
+        /// List the backups, newest first
+        #[arg(long, conflicts_with = "backup")]
+        list: bool,
+
+        /// Show what restoring would change without writing the file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Re-run checks and exports each time the I18n file changes, until Ctrl-C
+    Watch {
+        /// Commands to run, separated by commas (e.g., "lint,export md --output docs/copy.md")
+        #[arg(long, required = true, value_name = "COMMANDS")]
+        run: String,
+
+        /// Also watch the Elm files under this directory, for commands that scan
+        /// them such as `scan`; repeat for more
+        #[arg(long = "src", value_name = "DIR")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Clear the terminal before each run
+        #[arg(long)]
+        clear: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Modify an existing translation (update specific language values only)
+    Modify {
+        /// The translation key to modify
+        key: String,
+
+        /// Translation value as LANG=VALUE (e.g., -t es="Hola")
+        #[arg(short = 't', long = "translation", required = true)]
+        translations: Vec<String>,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Add a branch to the `case` of a function translation, in each language given
+    #[command(name = "add-case")]
+    AddCase {
+        /// The function translation whose `case` gets the branch
+        key: String,
+
+        /// The branch as LANG=BRANCH (e.g., -t fr='Few -> "quelques"')
+        #[arg(short = 't', long = "translation", required = true)]
+        branches: Vec<String>,
+
+        /// Show the change as a diff without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Rename a `{placeholder}` of one key in every language, along with the
+    /// function argument of the same name
+    #[command(name = "rename-placeholder")]
+    RenamePlaceholder {
+        /// The translation key whose values use the placeholder
+        key: String,
+
+        /// The placeholder's current name (e.g., "name" or "{name}")
+        old: String,
+
+        /// Its new name
+        new: String,
+
+        /// Show the change as a diff without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+    },
+
+    /// Bulk-modify translations for one language from a JSON file
+    #[command(name = "modify-bulk")]
+    ModifyBulk {
+        /// Language code to modify (e.g., "es", "de")
+        #[arg(long)]
+        lang: String,
+
+        /// Path to JSON file with key-value translations (e.g., {"loading": "Cargando...", ...})
+        #[arg(long = "from")]
+        json_file: PathBuf,
+
+        /// Path to I18n.elm file (defaults to src/I18n.elm)
+        #[arg(long, default_value = "src/I18n.elm")]
+        file: PathBuf,
+
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// With --dry-run, print the planned operations and their summary as JSON
+        #[arg(long, requires = "dry_run")]
+        json: bool,
+
+        /// Leave a key as it is when its value in the JSON file is empty, for a file
+        /// the translator has only partly done
+        #[arg(long)]
+        allow_partial: bool,
+    },
+
+    /// Add a new language by duplicating an existing one
+    #[command(name = "add-language")]
+    AddLanguage {
+        /// New language code (e.g., "de", "es", "ja")
+        new_lang: String,
+
+        /// Existing language to copy values from (e.g., "en")
+        #[arg(long, default_value = "en")]
+        from: String,
+    },
+
+    /// Show version information
+    Version,
+}
+
+/// What `backups` does
+#[derive(Subcommand)]
+enum BackupsAction {
+    /// List the backups, newest first, with how many keys each has against the file
+    List,
+
+    /// Delete all but the newest backups
+    Clean {
+        /// How many of the newest backups to keep
+        #[arg(long, value_name = "N")]
+        keep: usize,
+
+        /// Show what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// What `group` does
+#[derive(Subcommand)]
+enum GroupAction {
+    /// List the groups with how many keys each has and how much of them each
+    /// language has translated
+    List {
+        /// Print the groups as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rename a group, like checkout to payment: checkoutTitle becomes
+    /// paymentTitle, or checkout.title payment.title with nested keys
+    Rename {
+        /// The group to rename, dotted for one in another (checkout.legacy)
+        group: String,
+
+        /// Its new name, in the same group as before
+        new_name: String,
+
+        /// Also rename reads of the keys off translations in the Elm code, listing
+        /// the reads it can't tell are translations instead of touching them
+        #[arg(long)]
+        update_usages: bool,
+
+        /// Directory of Elm code to update; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR", requires = "update_usages")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Elm files not to update, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB", requires = "update_usages")]
+        ignore_path: Vec<String>,
+
+        /// Show the changes as diffs without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move a group with its keys into another group, made if it doesn't exist
+    Move {
+        /// The group to move, dotted for one in another (checkout.legacy)
+        group: String,
+
+        /// The group to move it into, or . for the top level
+        into: String,
+
+        /// Also rename reads of the keys off translations in the Elm code, listing
+        /// the reads it can't tell are translations instead of touching them
+        #[arg(long)]
+        update_usages: bool,
+
+        /// Directory of Elm code to update; repeat for more (defaults to the
+        /// source-directories of elm.json)
+        #[arg(long = "src", value_name = "DIR", requires = "update_usages")]
+        src_dirs: Vec<PathBuf>,
+
+        /// Elm files not to update, as a glob (e.g., "src/Generated/**"). Repeatable,
+        /// and added to scan.ignorePaths in the config
+        #[arg(long, value_name = "GLOB", requires = "update_usages")]
+        ignore_path: Vec<String>,
+
+        /// Show the changes as diffs without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// What `generate` adds
+#[derive(Subcommand, Clone, PartialEq, Eq)]
+enum GenerateHelpers {
+    /// `formatInt` and `formatNumber`, with each language's thousands separator and
+    /// decimal mark (1,234.56 in English, 1 234,56 in French)
+    NumberFormat,
+
+    /// `formatDate`, `monthName` and `weekdayName`, with each language's month and
+    /// weekday names (March 5, 2024 in English, 5 mars 2024 in French)
+    DateFormat {
+        /// Also add `formatPosix` and `posixWeekdayName`, taking a `Time.Zone` and a
+        /// `Time.Posix` (needs elm/time)
+        #[arg(long)]
+        use_time: bool,
+    },
+
+    /// `formatCurrency` and a `Currency` type (USD, EUR and GBP), with each
+    /// language's symbol placement and separators ($1,234.00 in English, 1 234,00 €
+    /// in French)
+    CurrencyFormat {
+        /// Another currency for the type, as its ISO 4217 code (e.g., SEK). Repeatable;
+        /// the ones added before stay
+        #[arg(long = "currency", value_name = "CODE")]
+        currencies: Vec<String>,
+    },
+
+    /// `relativeTime` ("3 minutes ago", "in 3 minutes"), with its texts added as
+    /// plural translations in each language
+    RelativeTime,
+}
+
+impl GenerateHelpers {
+    /// The name in the block's marker comments
+    fn block(&self) -> &'static str {
+        match self {
+            GenerateHelpers::NumberFormat => number_format::BLOCK,
+            GenerateHelpers::DateFormat { .. } => date_format::BLOCK,
+            GenerateHelpers::CurrencyFormat { .. } => currency_format::BLOCK,
+            GenerateHelpers::RelativeTime => relative_time::BLOCK,
+        }
+    }
+
+    fn functions(&self) -> &'static str {
+        match self {
+            GenerateHelpers::NumberFormat => "formatInt, formatNumber and formatOrdinal",
+            GenerateHelpers::CurrencyFormat { .. } => "formatCurrency and Currency",
+            GenerateHelpers::RelativeTime => "relativeTime",
+            GenerateHelpers::DateFormat { use_time: false } => {
+                "formatDate, monthName and weekdayName"
+            }
+            GenerateHelpers::DateFormat { use_time: true } => {
+                "formatDate, formatPosix, monthName and weekdayName"
+            }
+        }
+    }
+
+    /// The helpers `source` already has a block of, as they were generated
+    fn in_source(source: &str) -> Vec<GenerateHelpers> {
+        let mut helpers = Vec::new();
+        if generated::has_block(source, number_format::BLOCK) {
+            helpers.push(GenerateHelpers::NumberFormat);
+        }
+        if let Some(Some(block)) = generated::block(source, date_format::BLOCK) {
+            helpers.push(GenerateHelpers::DateFormat {
+                use_time: date_format::uses_time(block),
+            });
+        }
+        if let Some(Some(block)) = generated::block(source, currency_format::BLOCK) {
+            helpers.push(GenerateHelpers::CurrencyFormat {
+                currencies: currency_format::currencies_in(block),
+            });
+        }
+        if generated::has_block(source, relative_time::BLOCK) {
+            helpers.push(GenerateHelpers::RelativeTime);
+        }
+        helpers
+    }
+}
+
+/// The currencies of a `Currency` type: the default ones, those `source` has
+/// already, then `added`, each once and upper-cased
+fn currency_codes(source: Option<&str>, added: &[String]) -> Result<Vec<String>> {
+    let code = regex::Regex::new(r"^[A-Z]{3}$")?;
+    let existing = source
+        .and_then(|source| generated::block(source, currency_format::BLOCK).flatten())
+        .map(currency_format::currencies_in)
+        .unwrap_or_default();
+    let mut currencies: Vec<String> = Vec::new();
+    for currency in currency_format::DEFAULT_CURRENCIES
+        .iter()
+        .map(|code| code.to_string())
+        .chain(existing)
+        .chain(added.iter().map(|code| code.trim().to_uppercase()))
+    {
+        if !code.is_match(&currency) {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "'{}' isn't a currency code; use its three letters, like SEK",
+                    currency.yellow()
+                ),
+            );
+            exit(1);
+        }
+        if !currencies.contains(&currency) {
+            currencies.push(currency);
+        }
+    }
+    Ok(currencies)
+}
+
+/// `source` with the `helpers` block written, or refreshed, for `languages`; None
+/// when the block has lost its end marker
+fn with_generated_helpers(
+    source: &str,
+    helpers: &GenerateHelpers,
+    languages: &[String],
+    todo_marker: &str,
+    style: KeyStyle,
+) -> Option<String> {
+    match helpers {
+        GenerateHelpers::NumberFormat => generated::upsert_block(
+            source,
+            number_format::BLOCK,
+            &number_format::helpers_block(languages, todo_marker),
+        ),
+        GenerateHelpers::DateFormat { use_time } => {
+            let block = date_format::helpers_block(languages, *use_time);
+            let mut content = generated::upsert_block(source, date_format::BLOCK, &block)?;
+            if *use_time {
+                content = generated::add_import(&content, "Time");
+            }
+            // Records for languages without built-in names go below the block, where
+            // refreshing it leaves what the developer filled in alone
+            for record in date_format::todo_records(&content, languages, todo_marker) {
+                content = format!("{}\n\n\n{}\n", content.trim_end(), record);
+            }
+            Some(content)
+        }
+        GenerateHelpers::CurrencyFormat { currencies } => generated::upsert_block(
+            source,
+            currency_format::BLOCK,
+            &currency_format::helpers_block(languages, currencies),
+        ),
+        GenerateHelpers::RelativeTime => {
+            let translations_fn = source
+                .lines()
+                .any(|line| line.starts_with("translations : Language ->"));
+            generated::upsert_block(
+                source,
+                relative_time::BLOCK,
+                &relative_time::helpers_block(languages, style, translations_fn),
+            )
+        }
+    }
+}
+
+/// Refuse a new key that breaks a naming rule set to "error" in the lint config
+///
+/// Rules set to "warning" are reported without stopping the edit.
+fn check_key_naming(key: &str, lint_config: &LintConfig) -> Result<()> {
+    let mut refused = false;
+    for violation in NamingRules::new(&lint_config.naming)?.violations(key) {
+        match lint_config.severity(violation.rule, Severity::Error) {
+            Severity::Off => {}
+            Severity::Warning => eprintln!(
+                "{} Key '{}' {} ({})",
+                "⚠".yellow(),
+                key.yellow(),
+                violation.message,
+                violation.rule
+            ),
+            Severity::Error => {
+                refused = true;
+                Coded::new(
+                    Code::InvalidArgument,
+                    format!(
+                        "Key '{}' {} ({})",
+                        key.yellow(),
+                        violation.message,
+                        violation.rule
+                    ),
+                )
+                .key(key)
+                .report();
+            }
+        }
+    }
+
+    if refused {
+        errors::hint("Rename the key, or pass --no-validate to add it anyway");
+        exit(1);
+    }
+    Ok(())
+}
+
+/// Warn when adding `new_keys` takes the file past `lint.maxKeys`, or refuse when
+/// `max-keys` is configured as an error
+fn check_key_count(parse_result: &ParseResult, new_keys: &[String], lint_config: &LintConfig) {
+    let max_keys = match lint_config.max_keys {
+        Some(max_keys) => max_keys,
+        None => return,
+    };
+    let keys = parse_result.translations.keys().map(String::as_str).chain(
+        new_keys
+            .iter()
+            .filter(|key| !parse_result.translations.contains_key(*key))
+            .map(String::as_str),
+    );
+    let message = match key_count_message(keys, max_keys) {
+        Some(message) => message,
+        None => return,
+    };
+
+    match lint_config.severity(MAX_KEYS, Severity::Warning) {
+        Severity::Off => {}
+        Severity::Warning => eprintln!(
+            "{} After this add, {} ({})",
+            "⚠".yellow(),
+            message,
+            MAX_KEYS
+        ),
+        Severity::Error => {
+            errors::error(format!("After this add, {} ({})", message, MAX_KEYS));
+            errors::hint("Split the file, raise lint.maxKeys, or pass --no-validate to add anyway");
+            exit(1);
+        }
+    }
+}
+
+/// `check_key_count` for a key about to be added to `file`
+///
+/// Only parses the file when a limit is configured. A file that can't be read is
+/// left for the add itself to report.
+fn check_key_count_of_file(
+    file: &Path,
+    key: &str,
+    record_name: &str,
+    languages: &[String],
+    lint_config: &LintConfig,
+) {
+    if lint_config.max_keys.is_none() || !file.exists() {
+        return;
+    }
+    if let Ok(parse_result) = parse_i18n_file_with_record_name(file, record_name, languages) {
+        check_key_count(&parse_result, &[key.to_string()], lint_config);
+    }
+}
+
+/// A key that may be dotted, like `checkout.payment.cardNumber`, for a key of
+/// nested records; each part is cleaned as a key of its own. Without `nested`,
+/// a dotted key is refused as `validate_and_clean_key` does.
+fn validate_and_clean_nested_key(key: &str, nested: bool) -> Result<String> {
+    if !nested || !key.contains('.') {
+        return validate_and_clean_key(key);
+    }
+    let segments = key
+        .split('.')
+        .map(validate_and_clean_key)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(segments.join("."))
+}
+
+/// The last part of a dotted key, which the naming rules apply to
+fn leaf_segment(key: &str) -> &str {
+    key.rsplit('.').next().unwrap_or(key)
+}
+
+/// Validates and cleans a translation key
+fn validate_and_clean_key(key: &str) -> Result<String> {
+    // Check for forbidden characters
+    if key.contains('.') {
+        errors::error_with(
+            Code::InvalidArgument,
+            "Translation keys cannot contain dots (.)",
+        );
+        errors::hint(
+            "The dot character is reserved for accessing nested translations (e.g., t.welcome)",
+        );
+        errors::hint("Please use camelCase or underscores instead, or pass --nested to `add` to make it a key of nested records (t.checkout.title)");
+        exit(1);
+    }
+
+    // Handle reserved words
+    let mut cleaned_key = key.to_string();
+    if ELM_RESERVED_WORDS.contains(&key) {
+        cleaned_key = format!("{}_", key);
+        eprintln!(
+            "{} Warning: '{}' is a reserved word in Elm, using '{}' instead",
+            "⚠".yellow(),
+            key.yellow(),
+            cleaned_key.green()
+        );
+    }
+
+    // Validate key format (alphanumeric + underscores, starting with letter)
+    if !cleaned_key.chars().next().unwrap_or('0').is_alphabetic() {
+        errors::error_with(
+            Code::InvalidArgument,
+            "Translation keys must start with a letter",
+        );
+        exit(1);
+    }
+
+    if !cleaned_key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        errors::error_with(
+            Code::InvalidArgument,
+            "Translation keys can only contain letters, numbers, and underscores",
+        );
+        exit(1);
+    }
+
+    Ok(cleaned_key)
+}
+
+/// The configured language a command-line code refers to, ignoring case and
+/// `-`/`_` (so `pt_br` finds "pt-BR"); unknown codes come back lowercased
+fn configured_language(input: &str, languages: &[String]) -> String {
+    let key = |code: &str| code.trim().to_lowercase().replace('_', "-");
+    languages
+        .iter()
+        .find(|lang| key(lang) == key(input))
+        .cloned()
+        .unwrap_or_else(|| input.trim().to_lowercase())
+}
+
+/// Parse translation CLI arguments in LANG=VALUE format
+fn parse_translation_args(
+    args: &[String],
+    languages: &[String],
+) -> Result<std::collections::HashMap<String, String>> {
+    let mut values = std::collections::HashMap::new();
+
+    for arg in args {
+        let (lang, value) = arg.split_once('=').ok_or_else(|| {
+            Coded::new(
+                Code::InvalidArgument,
+                format!(
+                    "Invalid translation format: '{}'. Expected LANG=VALUE (e.g., en=\"Hello\")",
+                    arg
+                ),
+            )
+        })?;
+        let lang = configured_language(lang, languages);
+        if !languages.contains(&lang) {
+            eprintln!(
+                "{} Warning: language '{}' is not in configured languages: {}",
+                "⚠".yellow(),
+                lang.yellow(),
+                languages.join(", ")
+            );
+        }
+        values.insert(lang, value.to_string());
+    }
+
+    // Check that all configured languages have values
+    for lang in languages {
+        if !values.contains_key(lang) {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "Missing translation for language '{}'. Use -t {}=\"...\"",
+                    lang.yellow(),
+                    lang
+                ),
+            );
+            exit(1);
+        }
+    }
+
+    Ok(values)
+}
+
+/// The `-t` arguments plus `LANG=marker` for each `--todo` language
+///
+/// Exits if a language is given both a value and `--todo`.
+fn with_todo_markers(
+    translations: &[String],
+    todo: &[String],
+    todo_marker: &str,
+    languages: &[String],
+) -> Vec<String> {
+    let mut args = translations.to_vec();
+    for input in todo.iter().filter(|input| !input.trim().is_empty()) {
+        let lang = configured_language(input, languages);
+        let has_value = translations.iter().any(|arg| {
+            arg.split_once('=')
+                .is_some_and(|(l, _)| configured_language(l, languages) == lang)
+        });
+        if has_value {
+            errors::error(format!(
+                "Language '{}' has both a value and --todo",
+                lang.yellow()
+            ));
+            exit(1);
+        }
+        args.push(format!("{}={}", lang, todo_marker));
+    }
+    args
+}
+
+/// Normalize language codes typed by the user (e.g., "English" → "en")
+///
+/// Prints what was converted and warns about unknown codes; exits on input that
+/// can't be a language code.
+fn normalize_languages<'a>(inputs: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for input in inputs {
+        match normalize_language_code(input) {
+            Ok(normalized) => {
+                match normalized.note {
+                    Some(LanguageNote::Converted(message)) => {
+                        println!("{} Using language code {}", "ℹ".blue(), message)
+                    }
+                    Some(LanguageNote::Unknown(message)) => {
+                        eprintln!("{} Warning: {}", "⚠".yellow(), message)
+                    }
+                    None => {}
+                }
+                if !codes.contains(&normalized.code) {
+                    codes.push(normalized.code);
+                }
+            }
+            Err(message) => {
+                errors::error(message);
+                exit(1);
+            }
+        }
+    }
+    codes
+}
+
+/// Parse translation args without requiring all languages (for modify command)
+fn parse_partial_translation_args(
+    args: &[String],
+    languages: &[String],
+) -> Result<std::collections::HashMap<String, String>> {
+    let mut values = std::collections::HashMap::new();
+
+    for arg in args {
+        let (lang, value) = arg.split_once('=').ok_or_else(|| {
+            Coded::new(
+                Code::InvalidArgument,
+                format!(
+                    "Invalid translation format: '{}'. Expected LANG=VALUE (e.g., es=\"Hola\")",
+                    arg
+                ),
+            )
+        })?;
+        let lang = configured_language(lang, languages);
+        if !languages.contains(&lang) {
+            eprintln!(
+                "{} Warning: language '{}' is not in configured languages: {}",
+                "⚠".yellow(),
+                lang.yellow(),
+                languages.join(", ")
+            );
+        }
+        values.insert(lang, value.to_string());
+    }
+
+    if values.is_empty() {
+        anyhow::bail!("At least one translation must be provided");
+    }
+
+    Ok(values)
+}
+
+fn main() -> Result<()> {
+    let cli = match Cli::try_parse_from(expand_flags(std::env::args())) {
+        Ok(cli) => cli,
+        // The flags weren't parsed, so look for this one among the arguments
+        Err(e) if e.use_stderr() && std::env::args().any(|arg| arg == "--errors-as-json") => {
+            errors::enable_json();
+            let message = e.to_string();
+            let message = message.lines().next().unwrap_or_default();
+            errors::usage_error(message.trim_start_matches("error: "));
+            exit(e.exit_code());
+        }
+        Err(e) => e.exit(),
+    };
+    if cli.timing {
+        timing::enable();
+    }
+    if cli.errors_as_json {
+        errors::enable_json();
+    }
+    let result = run(cli);
+    if let Err(e) = &result {
+        if errors::json_enabled() {
+            errors::record_failure(e);
+            exit(1);
+        }
+    }
+    print_timing();
+    result
+}
+
+/// Print the `--timing` report, if it was asked for
+fn print_timing() {
+    if let Some(report) = timing::report() {
+        eprintln!("{} {}", "⏱".blue(), report);
+    }
+}
+
+/// `std::process::exit`, printing the `--errors-as-json` report and the `--timing`
+/// report first
+fn exit(code: i32) -> ! {
+    errors::flush(code);
+    print_timing();
+    std::process::exit(code)
+}
+
+fn run(cli: Cli) -> Result<()> {
+    // Handle commands that don't need config
+    match &cli.command {
+        Commands::Setup => return handle_setup(),
+        Commands::Version => return handle_version(),
+        Commands::Status => return handle_status(),
+        Commands::SetupClaude => return handle_setup_claude(),
+        _ => {}
+    }
+
+    // Load config for all other commands
+    let config = match Config::load()? {
+        Some(config) => config,
+        None if errors::json_enabled() => {
+            errors::error_with(
+                Code::Config,
+                format!("No elm-i18n configuration found at {}", config_file_path()),
+            );
+            errors::suggestion("Run `elm-i18n setup` to create one");
+            exit(1);
+        }
+        None => {
+            prompt_setup_message();
+            exit(1);
+        }
+    };
+
+    if let Some(path) = &cli.context_file {
+        context::set_file(path);
+    }
+
+    if let Some(suffix) = cli.backup_suffix.as_deref().or(config.backup_suffix()) {
+        if suffix.is_empty() || suffix.contains(['/', '\\']) {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "The backup suffix {:?} must be non-empty and can't contain a path separator",
+                    suffix
+                ),
+            );
+            exit(1);
+        }
+        backup::set_suffix(suffix);
+    }
+
+    match cli.indent.or(config.indent()) {
+        Some(width @ 1..=MAX_INDENT) => set_indent(width),
+        Some(width) => {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "An indent of {} spaces isn't supported; use 1 to {}",
+                    width, MAX_INDENT
+                ),
+            );
+            exit(1);
+        }
+        None => {}
+    }
+
+    // init creates the file, and an explicit --file says where it is
+    let explicit_file =
+        command_file_arg(&cli.command).is_some_and(|f| f.to_str() != Some("src/I18n.elm"));
+    let config = if matches!(cli.command, Commands::Init { .. }) || explicit_file {
+        config
+    } else {
+        locate_missing_i18n_file(config)?
+    };
+
+    // Determine target file based on config and shortcut
+    let (file_path, record_name) = determine_target_file(&config, &cli.target, &cli.command)?;
+    errors::set_context(
+        command_name(&cli.command),
+        Some(
+            command_file_arg(&cli.command)
+                .filter(|_| explicit_file)
+                .unwrap_or(&file_path)
+                .display()
+                .to_string(),
+        ),
+        command_key_arg(&cli.command),
+    );
+
+    let languages = config.languages();
+
+    if cli.stdin || cli.stdout {
+        return handle_stream(
+            &cli.command,
+            cli.stdin,
+            &file_path,
+            &record_name,
+            languages,
+            config.lint(),
+        );
+    }
+
+    let mut watched_files: Vec<PathBuf> = config
+        .translation_files()
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    watched_files.push(file_path.clone());
+    if let Some(file) = command_file_arg(&cli.command) {
+        watched_files.push(file.clone());
+    }
+    let watcher = EditWatcher::new(&watched_files);
+
+    let mut bom_files: Vec<&PathBuf> = watched_files
+        .iter()
+        .filter(|f| starts_with_bom(f))
+        .collect();
+    bom_files.sort();
+    bom_files.dedup();
+    for file in bom_files {
+        eprintln!(
+            "{} {} starts with a byte-order mark; it is ignored, and edits write the file without it",
+            "⚠".yellow(),
+            file.display()
+        );
+    }
+    // add-language also records the new language in the config
+    let config_watcher = EditWatcher::new(&[PathBuf::from(config_file_path())]);
+    let ignore_hook_failure = cli.ignore_hook_failure;
+    let verify_edits = cli.verify;
+    let elm_path = cli.elm_path.clone();
+    let format_edits = cli.format || config.elm_format();
+    let elm_format_path = cli.elm_format_path.clone();
+    let changelog = cli.changelog.clone();
+    let update_lock_file = !cli.no_lock && !matches!(cli.command, Commands::Lock);
+    let name = command_name(&cli.command);
+    // freshness --remove stops tracking, so it mustn't write the file again
+    let track_freshness = cli.track_freshness && !matches!(cli.command, Commands::Freshness { .. });
+    // Record names of the files a command may edit, for reading them back for the changelog
+    let mut watched_records = config.translation_files();
+    watched_records.push((file_path.clone(), record_name.clone()));
+    if let Some(file) = command_file_arg(&cli.command) {
+        watched_records.push((file.clone(), record_name.clone()));
+    }
+
+    if verify_edits && matches!(cli.command, Commands::Add { replace: true, .. }) {
+        errors::error("--verify can't undo the replacements --replace makes in other files");
+        errors::hint("Run the add first, then `elm-i18n verify`");
+        exit(1);
+    }
+    if verify_edits
+        && matches!(
+            cli.command,
+            Commands::Rename {
+                update_usages: true,
+                dry_run: false,
+                ..
+            }
+        )
+    {
+        errors::error("--verify can't undo the edits --update-usages makes in other files");
+        errors::hint("Run the rename first, then `elm-i18n verify`");
+        exit(1);
+    }
+    if verify_edits
+        && matches!(
+            cli.command,
+            Commands::Group {
+                action: GroupAction::Rename {
+                    update_usages: true,
+                    dry_run: false,
+                    ..
+                } | GroupAction::Move {
+                    update_usages: true,
+                    dry_run: false,
+                    ..
+                },
+                ..
+            }
+        )
+    {
+        errors::error("--verify can't undo the edits --update-usages makes in other files");
+        errors::hint("Move or rename the group first, then `elm-i18n verify`");
+        exit(1);
+    }
+    if verify_edits && matches!(cli.command, Commands::Rewrite { dry_run: false, .. }) {
+        errors::error("--verify can't undo the edit rewrite makes to the Elm file");
+        errors::hint("Run the rewrite first, then `elm-i18n verify`");
+        exit(1);
+    }
+
+    match cli.command {
+        Commands::Setup => unreachable!(),
+
+        Commands::Add {
+            key,
+            translations,
+            todo,
+            file,
+            replace,
+            src_dir,
+            type_annotation,
+            no_validate,
+            description,
+            icu,
+            params,
+            nested,
+            before,
+            after,
+            ..
+        } => {
+            let placement = placement(before, after)?;
+            let nested = nested || config.key_style() == KeyStyle::Nested;
+            let cleaned_key = validate_and_clean_nested_key(&key, nested)?;
+            if !no_validate {
+                check_key_naming(leaf_segment(&cleaned_key), config.lint())?;
+            }
+            let translations =
+                with_todo_markers(&translations, &todo, config.lint().todo_marker(), languages);
+            let values = parse_translation_args(&translations, languages)?;
+            let placeholders = if icu || type_annotation.is_some() {
+                None
+            } else {
+                compile_placeholders(&values, languages, &params, config.lint().todo_marker())
+            };
+            let (values, is_function, type_annotation) = if icu {
+                let (type_sig, values) = compile_icu(&values, languages);
+                (values, true, Some(type_sig))
+            } else if let Some((type_sig, values)) = placeholders {
+                println!(
+                    "{} The values have placeholders, so '{}' is a function of {}",
+                    "ℹ".blue(),
+                    cleaned_key.yellow(),
+                    type_sig
+                );
+                (values, true, Some(type_sig))
+            } else {
+                check_raw_values(type_annotation.as_deref(), &values, languages)?;
+                (values, false, type_annotation)
+            };
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            let actual_src_dir = if src_dir.to_str() == Some("src") {
+                config.source_dir().clone()
+            } else {
+                src_dir
+            };
+            handle_add(
+                &actual_file,
+                &cleaned_key,
+                &values,
+                is_function,
+                type_annotation,
+                replace,
+                &actual_src_dir,
+                placement.as_ref(),
+                &record_name,
+                languages,
+            )?;
+            if let Some(description) = description {
+                describe_key(&actual_file, &cleaned_key, &description)?;
+            }
+        }
+
+        Commands::AddFunction {
+            key,
+            type_sig,
+            translations,
+            file,
+            no_validate,
+            description,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            if !no_validate {
+                check_key_naming(&cleaned_key, config.lint())?;
+            }
+            let values = parse_translation_args(&translations, languages)?;
+            if !no_validate {
+                check_implementations(&type_sig, &values, languages);
+            }
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            handle_add(
+                &actual_file,
+                &cleaned_key,
+                &values,
+                true,
+                Some(type_sig),
+                false,
+                config.source_dir(),
+                None,
+                &record_name,
+                languages,
+            )?;
+            if let Some(description) = description {
+                describe_key(&actual_file, &cleaned_key, &description)?;
+            }
+        }
+
+        Commands::AddIcu {
+            key,
+            translations,
+            file,
+            no_validate,
+            description,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            if !no_validate {
+                check_key_naming(&cleaned_key, config.lint())?;
+            }
+            let messages = parse_translation_args(&translations, languages)?;
+            let (type_sig, values) = compile_icu(&messages, languages);
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            handle_add(
+                &actual_file,
+                &cleaned_key,
+                &values,
+                true,
+                Some(type_sig),
+                false,
+                config.source_dir(),
+                None,
+                &record_name,
+                languages,
+            )?;
+            if let Some(description) = description {
+                describe_key(&actual_file, &cleaned_key, &description)?;
+            }
+        }
+
+        Commands::AddPlural {
+            key,
+            translations,
+            count,
+            ordinal,
+            file,
+            no_validate,
+            description,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            if !no_validate {
+                check_key_naming(&cleaned_key, config.lint())?;
+            }
+            let (type_sig, values) = compile_plural(&translations, &count, languages, ordinal);
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            handle_add(
+                &actual_file,
+                &cleaned_key,
+                &values,
+                true,
+                Some(type_sig),
+                false,
+                config.source_dir(),
+                None,
+                &record_name,
+                languages,
+            )?;
+            if let Some(description) = description {
+                describe_key(&actual_file, &cleaned_key, &description)?;
+            }
+        }
+
+        Commands::AddSelect {
+            key,
+            arg,
+            variants,
+            arg_type,
+            translations,
+            file,
+            no_validate,
+            description,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            if !no_validate {
+                check_key_naming(&cleaned_key, config.lint())?;
+            }
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            let (type_sig, values) = compile_select(
+                &actual_file,
+                &translations,
+                &arg,
+                &arg_type,
+                &variants,
+                config.source_dir(),
+                languages,
+            );
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            handle_add(
+                &actual_file,
+                &cleaned_key,
+                &values,
+                true,
+                Some(type_sig),
+                false,
+                config.source_dir(),
+                None,
+                &record_name,
+                languages,
+            )?;
+            if let Some(description) = description {
+                describe_key(&actual_file, &cleaned_key, &description)?;
+            }
+        }
+
+        Commands::Scaffold {
+            manifest,
+            file,
+            no_validate,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            let lint_config = (!no_validate).then(|| config.lint());
+            handle_scaffold(
+                &actual_file,
+                &manifest,
+                lint_config,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::Check {
+            keys,
+            language,
+            complete,
+            json,
+            file,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            let requested = requested_languages(&language, &actual_file, languages);
+            if complete {
+                let cleaned_keys = keys
+                    .iter()
+                    .map(|key| validate_and_clean_nested_key(key, true))
+                    .collect::<Result<Vec<_>>>()?;
+                let counted = if requested.is_empty() {
+                    languages.to_vec()
+                } else {
+                    requested
+                };
+                handle_check_complete(
+                    &actual_file,
+                    &cleaned_keys,
+                    &counted,
+                    config.lint().todo_marker(),
+                    &record_name,
+                    languages,
+                )?;
+            } else {
+                if keys.len() > 1 {
+                    errors::error("check takes one key; add --complete to check several");
+                    exit(1);
+                }
+                if requested.len() > 1 {
+                    errors::error("--language takes one language unless --complete is given");
+                    exit(1);
+                }
+                let cleaned_key = validate_and_clean_nested_key(&keys[0], true)?;
+                handle_check(
+                    &actual_file,
+                    &cleaned_key,
+                    requested.first().map(String::as_str),
+                    json,
+                    &record_name,
+                    languages,
+                )?;
+            }
+        }
+
+        Commands::Init {
+            languages: init_langs,
+            file,
+            template,
+            gitignore,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_init(&actual_file, &init_langs, &record_name, template.as_deref())?;
+            offer_gitignore(gitignore)?;
+        }
+
+        Commands::Modify {
+            key,
+            translations,
+            file,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            let values = parse_partial_translation_args(&translations, languages)?;
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_modify(&actual_file, &cleaned_key, &values, &record_name, languages)?;
+        }
+
+        Commands::AddCase {
+            key,
+            branches,
+            dry_run,
+            file,
+        } => {
+            let key = validate_and_clean_key(&key)?;
+            let branches = parse_partial_translation_args(&branches, languages)?;
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_add_case(
+                &actual_file,
+                &key,
+                &branches,
+                dry_run,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::RenamePlaceholder {
+            key,
+            old,
+            new,
+            dry_run,
+            file,
+        } => {
+            let key = validate_and_clean_key(&key)?;
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_rename_placeholder(
+                &actual_file,
+                &key,
+                &old,
+                &new,
+                dry_run,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::ModifyBulk {
+            lang,
+            json_file,
+            file,
+            dry_run,
+            json,
+            allow_partial,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_modify_bulk(
+                &actual_file,
+                &lang,
+                &json_file,
+                config.lint().todo_marker(),
+                BulkPreview::from_flags(dry_run, json),
+                allow_partial,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::Remove {
+            keys,
+            check_usages,
+            src_dirs,
+            force,
+            file,
+        } => {
+            let cleaned_keys = keys
+                .iter()
+                .map(|key| validate_and_clean_nested_key(key, true))
+                .collect::<Result<Vec<_>>>()?;
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            let sources = if check_usages {
+                let files = vec![(actual_file.clone(), record_name.clone())];
+                usage_check_sources(&config, &files, src_dirs)?
+            } else {
+                None
+            };
+            let status = handle_remove(
+                &actual_file,
+                &cleaned_keys,
+                sources.as_ref(),
+                force,
+                &record_name,
+                languages,
+            )?;
+            if status != 0 {
+                exit(status);
+            }
+        }
+
+        Commands::Rename {
+            old_key,
+            new_key,
+            prefix,
+            update_usages,
+            src_dirs,
+            ignore_path,
+            dry_run,
+            file,
+        } => {
+            let old_key = validate_and_clean_key(&old_key)?;
+            let new_key = validate_and_clean_key(&new_key)?;
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if prefix {
+                handle_rename_prefix(
+                    &config,
+                    &actual_file,
+                    &old_key,
+                    &new_key,
+                    update_usages.then_some((src_dirs, ignore_path)),
+                    dry_run,
+                    &record_name,
+                    languages,
+                )?;
+            } else {
+                let files = vec![(actual_file.clone(), record_name.clone())];
+                let sources = if update_usages {
+                    refuse_if_defined_elsewhere(&config, &actual_file, &old_key, languages)?;
+                    let src_dirs = scan_source_dirs(&config, src_dirs)?;
+                    Some(scan_sources(&config, &files, &src_dirs, ignore_path)?)
+                } else {
+                    None
+                };
+                handle_rename(
+                    &actual_file,
+                    &old_key,
+                    &new_key,
+                    sources.as_ref(),
+                    &config.scan().variables(),
+                    dry_run,
+                    &record_name,
+                    languages,
+                )?;
+            }
+        }
+
+        Commands::Group { action, file } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            match action {
+                GroupAction::List { json } => handle_group_list(
+                    &actual_file,
+                    json,
+                    config.key_style(),
+                    config.lint().todo_marker(),
+                    &record_name,
+                    languages,
+                )?,
+                GroupAction::Rename {
+                    group,
+                    new_name,
+                    update_usages,
+                    src_dirs,
+                    ignore_path,
+                    dry_run,
+                } => {
+                    let group = validate_and_clean_nested_key(&group, true)?;
+                    if new_name.contains('.') {
+                        errors::error(format!(
+                            "'{}' is a path; a group is renamed in the group it's in",
+                            new_name.yellow()
+                        ));
+                        errors::hint(format!(
+                            "Use `elm-i18n group move {} GROUP` to move it elsewhere",
+                            group
+                        ));
+                        exit(1);
+                    }
+                    let new_name = validate_and_clean_key(&new_name)?;
+                    let usages = update_usages.then_some((src_dirs, ignore_path));
+                    handle_group_edit(
+                        &config,
+                        &actual_file,
+                        &group,
+                        GroupTarget::Renamed(new_name),
+                        usages,
+                        dry_run,
+                        &record_name,
+                        languages,
+                    )?;
+                }
+                GroupAction::Move {
+                    group,
+                    into,
+                    update_usages,
+                    src_dirs,
+                    ignore_path,
+                    dry_run,
+                } => {
+                    let group = validate_and_clean_nested_key(&group, true)?;
+                    let into = if into == "." {
+                        None
+                    } else {
+                        Some(validate_and_clean_nested_key(&into, true)?)
+                    };
+                    let usages = update_usages.then_some((src_dirs, ignore_path));
+                    handle_group_edit(
+                        &config,
+                        &actual_file,
+                        &group,
+                        GroupTarget::Moved(into),
+                        usages,
+                        dry_run,
+                        &record_name,
+                        languages,
+                    )?;
+                }
+            }
+        }
 
         Commands::RemoveUnused {
             file,
-            src_dir,
-            confirm,
+            src_dir,
+            confirm,
+        } => {
+            let actual_src_dir = if src_dir.to_str() == Some("src") {
+                config.source_dir().clone()
+            } else {
+                src_dir
+            };
+
+            // In multi-file mode without a target, process all files
+            if cli.target.is_none() {
+                if let Config::MultiFile { files, .. } = &config {
+                    println!(
+                        "{} Running remove-unused on all translation files...\n",
+                        "🔍".blue()
+                    );
+                    for (shortcut, file_config) in files {
+                        if !file_config.path.exists() {
+                            println!(
+                                "  {} Skipping {} (file not found)\n",
+                                "⚠".yellow(),
+                                shortcut
+                            );
+                            continue;
+                        }
+                        println!(
+                            "{} Processing {} ({})...",
+                            "→".cyan(),
+                            shortcut.yellow(),
+                            file_config.path.display()
+                        );
+                        handle_remove_unused(
+                            &file_config.path,
+                            &actual_src_dir,
+                            confirm,
+                            &file_config.record_name,
+                            languages,
+                        )?;
+                        println!();
+                    }
+                } else {
+                    // Single file mode
+                    handle_remove_unused(
+                        &file_path,
+                        &actual_src_dir,
+                        confirm,
+                        &record_name,
+                        languages,
+                    )?;
+                }
+            } else {
+                // Target was specified, use the determined file
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                handle_remove_unused(
+                    &actual_file,
+                    &actual_src_dir,
+                    confirm,
+                    &record_name,
+                    languages,
+                )?;
+            }
+        }
+
+        Commands::List {
+            glob: Some(pattern),
+            verbose,
+            filter,
+            todo,
+            ..
+        } => {
+            let files = files_for_glob(&config, &pattern)?;
+            let todo_marker = todo.then(|| config.lint().todo_marker());
+            handle_list_across_files(&files, verbose, &filter, todo_marker, languages)?
+        }
+
+        Commands::List {
+            file,
+            verbose,
+            filter,
+            glob: None,
+            todo,
+            diff_base,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            let todo_marker = todo.then(|| config.lint().todo_marker());
+            handle_list(
+                &actual_file,
+                verbose,
+                &filter,
+                todo_marker,
+                diff_base.as_deref(),
+                &record_name,
+                languages,
+            )?
+        }
+
+        Commands::Keys {
+            file,
+            functions_only,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_keys(&files, functions_only, languages)?;
+        }
+
+        Commands::Scan {
+            file,
+            src_dirs,
+            ignore,
+            json,
+            fail_on_unused,
+            missing,
+            ignore_path,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let src_dirs = scan_source_dirs(&config, src_dirs)?;
+            let sources = scan_sources(&config, &files, &src_dirs, ignore_path)?;
+
+            if missing {
+                let variables = config.scan().variables();
+                let found =
+                    handle_scan_missing(&files, &sources, &src_dirs, &variables, json, languages)?;
+                if found > 0 {
+                    exit(1);
+                }
+            } else {
+                let mut ignore_patterns = config.scan().ignore.clone();
+                ignore_patterns.extend(ignore);
+
+                let unused = handle_scan(
+                    &files,
+                    &sources,
+                    &src_dirs,
+                    &ignore_patterns,
+                    json,
+                    languages,
+                )?;
+                if fail_on_unused && unused > 0 {
+                    exit(1);
+                }
+            }
+        }
+
+        Commands::Prune {
+            file,
+            src_dirs,
+            keep,
+            ignore_path,
+            yes,
+            dry_run,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let src_dirs = scan_source_dirs(&config, src_dirs)?;
+            let sources = scan_sources(&config, &files, &src_dirs, ignore_path)?;
+            let mut keep_patterns = config.scan().ignore.clone();
+            keep_patterns.extend(keep);
+            handle_prune(
+                &files,
+                &sources,
+                &src_dirs,
+                &keep_patterns,
+                yes,
+                dry_run,
+                languages,
+            )?;
+        }
+
+        Commands::Usages {
+            key,
+            all: _,
+            file,
+            src_dirs,
+            ignore_path,
+            json,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let src_dirs = scan_source_dirs(&config, src_dirs)?;
+            let sources = scan_sources(&config, &files, &src_dirs, ignore_path)?;
+            handle_usages(&files, &sources, &src_dirs, key.as_deref(), json, languages)?;
+        }
+
+        Commands::Extract {
+            src_dirs,
+            functions,
+            ignore_path,
+            json,
+        } => {
+            let src_dirs = scan_source_dirs(&config, src_dirs)?;
+            let sources = scan_sources(&config, &[], &src_dirs, ignore_path)?;
+            let mut all_functions = config.scan().extract_functions();
+            all_functions.extend(functions);
+            handle_extract(&sources, &src_dirs, &all_functions, json)?;
+        }
+
+        Commands::Rewrite {
+            source,
+            line,
+            key,
+            translations,
+            todo,
+            text,
+            lang,
+            accessor,
+            functions,
+            dry_run,
+            no_validate,
+            file,
+        } => {
+            let cleaned_key = validate_and_clean_key(&key)?;
+            if !no_validate {
+                check_key_naming(&cleaned_key, config.lint())?;
+            }
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            if !no_validate {
+                check_key_count_of_file(
+                    &actual_file,
+                    &cleaned_key,
+                    &record_name,
+                    languages,
+                    config.lint(),
+                );
+            }
+            let source_lang = match lang {
+                Some(lang) => requested_languages(&[lang], &actual_file, languages)
+                    .pop()
+                    .unwrap_or_default(),
+                None => languages
+                    .iter()
+                    .find(|lang| *lang == "en")
+                    .or(languages.first())
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            let translations =
+                with_todo_markers(&translations, &todo, config.lint().todo_marker(), languages);
+            let mut all_functions = config.scan().extract_functions();
+            all_functions.extend(functions);
+            handle_rewrite(
+                &actual_file,
+                &source,
+                line,
+                text.as_deref(),
+                &cleaned_key,
+                &translations,
+                &source_lang,
+                &accessor,
+                &all_functions,
+                &config.scan().variables(),
+                dry_run,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::DuplicateKeys { file } => {
+            // In multi-file mode without a target, find duplicates across all files
+            if cli.target.is_none() {
+                if let Config::MultiFile { files, .. } = &config {
+                    handle_duplicates_cross_file(files, languages)?;
+                } else {
+                    // Single file mode
+                    handle_duplicates(&file_path, &record_name, languages)?;
+                }
+            } else {
+                // Target was specified, use the determined file
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                handle_duplicates(&actual_file, &record_name, languages)?;
+            }
+        }
+
+        Commands::SharedValues { file, suppress } => {
+            if cli.target.is_none() {
+                if let Config::MultiFile { files, .. } = &config {
+                    handle_shared_values_cross_file(files, languages, suppress)?;
+                } else {
+                    handle_shared_values(&file_path, &record_name, languages, suppress)?;
+                }
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                handle_shared_values(&actual_file, &record_name, languages, suppress)?;
+            }
+        }
+
+        Commands::Lint {
+            file,
+            glob,
+            fix,
+            dry_run,
+            output,
+            deny,
+            fail_on_warning,
+            stale,
+        } => {
+            let files = if let Some(pattern) = glob {
+                files_for_glob(&config, &pattern)?
+            } else if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let mut lint_config = config.lint().clone();
+            for rule in deny {
+                lint_config.rules.insert(rule, Severity::Error);
+            }
+            handle_lint(
+                &files,
+                languages,
+                &lint_config,
+                fix,
+                dry_run,
+                output,
+                fail_on_warning,
+                stale,
+            )?;
+        }
+
+        Commands::Format { file, check } => {
+            let is_default_file = file.to_str() == Some("src/I18n.elm");
+            let files = if is_default_file && cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if is_default_file {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_format(&files, languages, check, false)?;
+        }
+
+        Commands::Canonicalize { file, check } => {
+            let is_default_file = file.to_str() == Some("src/I18n.elm");
+            let files = if is_default_file && cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if is_default_file {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_format(&files, languages, check, true)?;
+        }
+
+        Commands::Stats {
+            file,
+            glob,
+            by_language,
+            json,
+        } => {
+            let files = if let Some(pattern) = glob {
+                files_for_glob(&config, &pattern)?
+            } else if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_stats(&files, config.lint(), languages, by_language, json)?;
+        }
+
+        Commands::Coverage {
+            langs,
+            fail_under,
+            json,
+            badges,
+            all,
+            out,
+            red_under,
+            yellow_under,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let reported = if !badges.is_empty() {
+                requested_languages(&badges, Path::new(LOCAL_CONFIG_FILE), languages)
+            } else if langs.is_empty() {
+                languages.to_vec()
+            } else {
+                requested_languages(&langs, Path::new(LOCAL_CONFIG_FILE), languages)
+            };
+            if !all && badges.is_empty() && out.is_some() {
+                errors::error("--out is where badges go; pass --badge LANG or --all");
+                exit(1);
+            }
+            let badges = (all || !badges.is_empty()).then_some(BadgeOptions {
+                out,
+                red_under,
+                yellow_under,
+            });
+            handle_coverage(
+                &files,
+                &reported,
+                fail_under,
+                json,
+                badges,
+                config.lint().todo_marker(),
+                languages,
+            )?;
+        }
+
+        Commands::Report {
+            html,
+            json: _,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_report(
+                &files,
+                html.as_deref(),
+                config.lint().todo_marker(),
+                languages,
+            )?;
+        }
+
+        Commands::Validate {
+            file,
+            glob,
+            strict,
+            require_descriptions,
+            placeholder_style,
+            fail_on_warning,
+        } => {
+            let files = if let Some(pattern) = glob {
+                files_for_glob(&config, &pattern)?
+            } else if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let status = handle_validate(
+                &files,
+                languages,
+                strict,
+                require_descriptions,
+                placeholder_style.or(config.placeholder_style()),
+                fail_on_warning,
+            )?;
+            if status != 0 {
+                exit(status);
+            }
+        }
+
+        Commands::Ci {
+            file,
+            src_dirs,
+            output,
+        } => {
+            let files = if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_ci(&config, &files, &src_dirs, output, languages)?;
+        }
+
+        Commands::Doctor {
+            file,
+            merge_duplicates,
+        } => {
+            let files = if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_doctor(&files, languages, merge_duplicates)?;
+        }
+
+        Commands::Verify { file } => {
+            let files = if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let files: Vec<PathBuf> = files.into_iter().map(|(path, _)| path).collect();
+            handle_verify(&files, elm_path.as_deref())?;
+        }
+
+        Commands::GenTest { file, output } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_gen_test(&actual_file, &record_name, languages, output)?;
+        }
+
+        Commands::Render {
+            file,
+            output,
+            module,
+            env_file,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_render(
+                &actual_file,
+                &record_name,
+                languages,
+                &output,
+                module.as_deref(),
+                &env_file,
+            )?;
+        }
+
+        Commands::Export {
+            export_format,
+            output,
+            only_changed,
+            split,
+            file,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_export(
+                &actual_file,
+                export_format,
+                output.as_deref(),
+                only_changed,
+                split,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::Diff {
+            base,
+            base_path,
+            langs,
+            changed_only,
+            json,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_diff(
+                &files,
+                &base,
+                base_path.as_deref(),
+                &langs,
+                changed_only,
+                json,
+                languages,
+            )?;
+        }
+
+        Commands::Changelog {
+            from,
+            to,
+            all_languages,
+            json,
+            output,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_changelog(
+                &files,
+                &from,
+                to.as_deref(),
+                all_languages,
+                json,
+                output.as_deref(),
+                &config.changelog().ignore,
+                languages,
+            )?;
+        }
+
+        Commands::Blame {
+            key,
+            all: _,
+            json,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let key = key.map(|key| validate_and_clean_key(&key)).transpose()?;
+            handle_blame(&files, key.as_deref(), json, languages)?;
+        }
+
+        Commands::Freshness {
+            review,
+            all: _,
+            langs,
+            remove,
+            file,
+        } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            let review = review
+                .iter()
+                .map(|key| validate_and_clean_key(key))
+                .collect::<Result<Vec<_>>>()?;
+            let langs = requested_languages(&langs, Path::new(LOCAL_CONFIG_FILE), languages);
+            handle_freshness(&files, &review, &langs, remove, languages)?;
+        }
+
+        Commands::Generate { helpers, file } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            // The elm/time helpers stay once added, so code using them keeps
+            // compiling when the block is refreshed
+            let helpers = match helpers {
+                GenerateHelpers::DateFormat { use_time: false }
+                    if actual_file.exists()
+                        && GenerateHelpers::in_source(&read_elm_file(&actual_file)?)
+                            .contains(&GenerateHelpers::DateFormat { use_time: true }) =>
+                {
+                    GenerateHelpers::DateFormat { use_time: true }
+                }
+                GenerateHelpers::CurrencyFormat { currencies } => {
+                    let source = if actual_file.exists() {
+                        Some(read_elm_file(&actual_file)?)
+                    } else {
+                        None
+                    };
+                    GenerateHelpers::CurrencyFormat {
+                        currencies: currency_codes(source.as_deref(), &currencies)?,
+                    }
+                }
+                helpers => helpers,
+            };
+            handle_generate(
+                &actual_file,
+                &helpers,
+                languages,
+                config.lint().todo_marker(),
+                config.key_style(),
+                &record_name,
+            )?;
+        }
+
+        Commands::Backups { action, file } => {
+            let files = if cli.target.is_none() && file.to_str() == Some("src/I18n.elm") {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            for (i, (file, record_name)) in files.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                match action {
+                    BackupsAction::List => {
+                        print_backups(file, record_name, languages);
+                    }
+                    BackupsAction::Clean { keep, dry_run } => {
+                        handle_clean_backups(file, keep, dry_run)?
+                    }
+                }
+            }
+        }
+
+        Commands::Restore {
+            backup,
+            list,
+            dry_run,
+            file,
+        } => {
+            let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                file_path.clone()
+            } else {
+                file
+            };
+            handle_restore(
+                &actual_file,
+                backup.as_deref(),
+                list,
+                dry_run,
+                &record_name,
+                languages,
+            )?;
+        }
+
+        Commands::Watch {
+            run,
+            src_dirs,
+            clear,
+            file,
+        } => {
+            let files = if cli.target.is_none() {
+                config.translation_files()
+            } else {
+                let actual_file = if file.to_str() == Some("src/I18n.elm") {
+                    file_path.clone()
+                } else {
+                    file
+                };
+                vec![(actual_file, record_name.clone())]
+            };
+            handle_watch(
+                &run,
+                &files,
+                &src_dirs,
+                clear,
+                cli.target.as_deref(),
+                languages,
+            )?;
+        }
+
+        Commands::AddLanguage { new_lang, from } => {
+            handle_add_language(&config, &new_lang, &from)?;
+        }
+
+        Commands::Lock => handle_lock(&config, languages)?,
+
+        Commands::VerifyLock => handle_verify_lock(&config, languages)?,
+
+        Commands::Version => unreachable!(),
+        Commands::Status => unreachable!(),
+        Commands::SetupClaude => unreachable!(),
+    }
+
+    if backup::keeps_history() {
+        for file in watcher.changed_files() {
+            if let Some(original) = watcher.original(&file) {
+                if !backup::was_backed_up(&file) {
+                    backup::write_backup(&file, original)?;
+                }
+            }
+        }
+    }
+
+    if format_edits {
+        format_edited_files(&watcher.changed_files(), elm_format_path.as_deref());
+    }
+
+    if verify_edits {
+        verify_edited_files(&watcher, &config_watcher, elm_path.as_deref())?;
+    }
+
+    // add-language adds to the configured languages
+    let reloaded = Config::load()?;
+    let languages_after = reloaded.as_ref().map_or(languages, |c| c.languages());
+
+    if let Some(changelog) = &changelog {
+        record_changes(
+            changelog,
+            name,
+            &watcher,
+            &watched_records,
+            languages,
+            languages_after,
+        )?;
+    }
+
+    if track_freshness || Path::new(FRESHNESS_FILE).exists() {
+        update_freshness(&watcher, &watched_records, languages_after)?;
+    }
+
+    if update_lock_file && Path::new(LOCK_FILE).exists() {
+        update_lock(&watcher.changed_files(), &watched_records, languages_after)?;
+    }
+
+    if let Some(hook) = config.post_edit_hook() {
+        run_post_edit_hooks(hook, &watcher.changed_files(), ignore_hook_failure);
+    }
+
+    Ok(())
+}
+
+/// Apply an edit to a module read from stdin (or the file) and print the result
+///
+/// Nothing is written to disk, so no backup is made and no hook runs.
+fn handle_stream(
+    command: &Commands,
+    from_stdin: bool,
+    file_path: &Path,
+    record_name: &str,
+    languages: &[String],
+    lint_config: &LintConfig,
+) -> Result<()> {
+    let source = if from_stdin {
+        let mut source = String::new();
+        io::stdin()
+            .read_to_string(&mut source)
+            .context("Failed to read the I18n module from stdin")?;
+        source.trim_start_matches('\u{feff}').to_string()
+    } else {
+        let file = match command_file_arg(command) {
+            Some(file) if file.to_str() != Some("src/I18n.elm") => file.as_path(),
+            _ => file_path,
+        };
+        read_elm_file(file)?
+    };
+
+    if let Some(message) = inconsistency_report(&source, record_name, languages)? {
+        anyhow::bail!(message);
+    }
+
+    let output = match command {
+        Commands::Add {
+            key,
+            translations,
+            todo,
+            replace,
+            type_annotation,
+            no_validate,
+            description,
+            icu,
+            params,
+            nested,
+            before,
+            after,
+            ..
+        } => {
+            let placement = placement(before.clone(), after.clone())?;
+            if *replace {
+                anyhow::bail!(
+                    "--replace edits source files and can't be used with --stdin/--stdout"
+                );
+            }
+            if description.is_some() {
+                anyhow::bail!(
+                    "--description writes the context file and can't be used with --stdin/--stdout"
+                );
+            }
+            let key = validate_and_clean_nested_key(key, *nested)?;
+            if !no_validate {
+                check_key_naming(leaf_segment(&key), lint_config)?;
+                if lint_config.max_keys.is_some() {
+                    let parse_result =
+                        parse_i18n_content_with_record_name(&source, record_name, languages)?;
+                    check_key_count(&parse_result, std::slice::from_ref(&key), lint_config);
+                }
+            }
+            let translations =
+                with_todo_markers(translations, todo, lint_config.todo_marker(), languages);
+            let values = parse_translation_args(&translations, languages)?;
+            let placeholders = if *icu || type_annotation.is_some() {
+                None
+            } else {
+                compile_placeholders(&values, languages, params, lint_config.todo_marker())
+            };
+            let translation = if *icu {
+                let (type_sig, values) = compile_icu(&values, languages);
+                Translation {
+                    key,
+                    values,
+                    is_function: true,
+                    type_signature: Some(type_sig),
+                }
+            } else if let Some((type_sig, values)) = placeholders {
+                Translation {
+                    key,
+                    values,
+                    is_function: true,
+                    type_signature: Some(type_sig),
+                }
+            } else {
+                check_raw_values(type_annotation.as_deref(), &values, languages)?;
+                Translation {
+                    key,
+                    values,
+                    is_function: false,
+                    type_signature: type_annotation.clone(),
+                }
+            };
+            add_new_translation_to_content(
+                &source,
+                &translation,
+                placement.as_ref(),
+                record_name,
+                languages,
+            )?
+        }
+        Commands::AddFunction {
+            key,
+            type_sig,
+            translations,
+            no_validate,
+            description,
+            ..
+        } => {
+            if description.is_some() {
+                anyhow::bail!(
+                    "--description writes the context file and can't be used with --stdin/--stdout"
+                );
+            }
+            let key = validate_and_clean_key(key)?;
+            if !no_validate {
+                check_key_naming(&key, lint_config)?;
+                if lint_config.max_keys.is_some() {
+                    let parse_result =
+                        parse_i18n_content_with_record_name(&source, record_name, languages)?;
+                    check_key_count(&parse_result, std::slice::from_ref(&key), lint_config);
+                }
+            }
+            let values = parse_translation_args(translations, languages)?;
+            if !no_validate {
+                check_implementations(type_sig, &values, languages);
+            }
+            let translation = Translation {
+                key,
+                values,
+                is_function: true,
+                type_signature: Some(type_sig.clone()),
+            };
+            add_new_translation_to_content(&source, &translation, None, record_name, languages)?
+        }
+        Commands::Modify {
+            key, translations, ..
+        } => {
+            let key = validate_and_clean_key(key)?;
+            let values = parse_partial_translation_args(translations, languages)?;
+            modify_translation_in_content(&source, &key, &values, record_name, languages)?
+        }
+        Commands::Remove { keys, .. } => {
+            let mut output = source;
+            for key in keys {
+                let key = validate_and_clean_nested_key(key, true)?;
+                output = remove_translation_from_content(&output, &key, record_name, languages)?;
+            }
+            output
+        }
+        _ => anyhow::bail!("--stdin/--stdout is only supported by add, add-fn, modify and remove"),
+    };
+
+    print!("{}", output);
+    io::stdout().flush()?;
+    Ok(())
+}
+
+fn add_new_translation_to_content(
+    source: &str,
+    translation: &Translation,
+    placement: Option<&Placement>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    if parse_result.translations.contains_key(&translation.key) {
+        anyhow::bail!(Coded::key_exists(&translation.key));
+    }
+    add_translation_placed(source, translation, placement, record_name, languages)
+}
+
+/// The placement `add --before KEY` or `--after KEY` asks for, if either
+fn placement(before: Option<String>, after: Option<String>) -> Result<Option<Placement>> {
+    Ok(match (before, after) {
+        (Some(key), _) => Some(Placement::Before(validate_and_clean_key(&key)?)),
+        (None, Some(key)) => Some(Placement::After(validate_and_clean_key(&key)?)),
+        (None, None) => None,
+    })
+}
+
+/// Exit before editing a file whose type and records disagree on the keys
+fn refuse_if_inconsistent(file: &Path, record_name: &str, languages: &[String]) -> Result<()> {
+    let source = read_elm_file(file)?;
+
+    if let Some(report) = inconsistency_report(&source, record_name, languages)? {
+        errors::error(format!("{}: {}", file.display(), report));
+        errors::hint("Run 'elm-i18n lint --fix' to add missing fields; fields missing from the type must be declared or removed by hand");
+        exit(1);
+    }
+
+    Ok(())
+}
+
+/// Describe every key the type and records disagree on, if any
+fn inconsistency_report(
+    source: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<Option<String>> {
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    let mismatches = key_set_mismatches(&parse_result, source);
+    if mismatches.is_empty() {
+        return Ok(None);
+    }
+
+    let mut report = String::from("the type and the records disagree, so I won't edit it");
+    for finding in &mismatches {
+        report.push_str(&format!(
+            "\n  {} [{}] line {}: {}",
+            finding.key,
+            finding.language.as_deref().unwrap_or("").to_uppercase(),
+            finding.line.unwrap_or(0),
+            finding.message
+        ));
+    }
+    Ok(Some(report))
+}
+
+/// Check `add --raw` values against their `--type` before anything is written
+fn check_raw_values(
+    type_annotation: Option<&str>,
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) -> Result<()> {
+    let type_annotation = match type_annotation {
+        Some(type_annotation) => type_annotation,
+        None => return Ok(()),
+    };
+    if is_function_type(type_annotation) {
+        anyhow::bail!(
+            "`{}` is a function type; use `elm-i18n add-fn` for functions",
+            type_annotation
+        );
+    }
+    for lang in languages {
+        if let Some(message) = values
+            .get(lang)
+            .and_then(|value| literal_type_mismatch(type_annotation, value))
+        {
+            anyhow::bail!("{} value {}", lang.to_uppercase(), message);
+        }
+    }
+    Ok(())
+}
+
+/// Refuse `add-fn` implementations with structural problems, naming the language
+/// and the problem
+/// The type and each language's implementation for ICU `messages`, exiting with
+/// what isn't understood when they can't be compiled
+fn compile_icu(
+    messages: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) -> (String, std::collections::HashMap<String, String>) {
+    let (type_sig, values) = match icu::compile(messages, languages) {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            errors::error(e);
+            errors::hint("ICU input compiles plural, select and {name} arguments; write anything else with add-fn");
+            exit(1);
+        }
+    };
+    check_implementations(&type_sig, &values, languages);
+    (type_sig, values)
+}
+
+/// Each `-t LANG.VARIANT=VALUE` argument with its language, variant and value;
+/// exits on one that isn't written like `format`
+fn parse_variant_args<'a>(
+    args: &'a [String],
+    languages: &[String],
+    format: &str,
+) -> Vec<(&'a String, String, String, String)> {
+    let mut parsed = Vec::new();
+    for arg in args {
+        let split = arg.split_once('=').and_then(|(target, value)| {
+            let (lang, variant) = target.rsplit_once('.')?;
+            Some((lang, variant.trim(), value))
+        });
+        let Some((lang, variant, value)) = split.filter(|(_, variant, _)| !variant.is_empty())
+        else {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!("Invalid value: '{}'. Expected {}", arg, format),
+            );
+            exit(1);
+        };
+        let lang = configured_language(lang, languages);
+        if !languages.contains(&lang) {
+            eprintln!(
+                "{} Warning: language '{}' is not in configured languages: {}",
+                "⚠".yellow(),
+                lang.yellow(),
+                languages.join(", ")
+            );
+        }
+        parsed.push((arg, lang, variant.to_string(), value.to_string()));
+    }
+    parsed
+}
+
+/// The function `add-select` makes of `-t LANG.VARIANT=VALUE` arguments; exits
+/// when a language misses a variant or `arg_type` can't be matched
+fn compile_select(
+    file: &Path,
+    args: &[String],
+    argument: &str,
+    arg_type: &str,
+    variants: &[String],
+    source_dir: &Path,
+    languages: &[String],
+) -> (String, std::collections::HashMap<String, String>) {
+    use std::collections::HashMap;
+    let fail = |message: String| -> ! {
+        errors::error(message);
+        exit(1);
+    };
+    if !regex::Regex::new(r"^[a-z][A-Za-z0-9_]*$")
+        .unwrap()
+        .is_match(argument)
+        || ELM_RESERVED_WORDS.contains(&argument)
+    {
+        fail(format!(
+            "--arg {}: the argument's name must be a lowercase Elm name",
+            argument
+        ));
+    }
+
+    // A custom type is found in the module or in one it imports exposing it
+    let patterns = if arg_type == "String" {
+        select::Patterns::Strings
+    } else {
+        let source = read_elm_file(file).unwrap_or_default();
+        let constructors = select::custom_type_constructors(&source, arg_type).or_else(|| {
+            select::modules_exposing(&source, arg_type)
+                .iter()
+                .find_map(|module| {
+                    let path = source_dir.join(format!("{}.elm", module.replace('.', "/")));
+                    let source = std::fs::read_to_string(path).ok()?;
+                    select::custom_type_constructors(&source, arg_type)
+                })
+        });
+        match constructors {
+            Some(constructors) => select::Patterns::Constructors(constructors),
+            None => {
+                errors::error(format!(
+                    "--arg-type {}: {} doesn't declare a `type {}` with constructors taking no arguments, and imports none exposing one",
+                    arg_type,
+                    file.display(),
+                    arg_type
+                ));
+                errors::hint(format!(
+                    "Declare the type, import it with `exposing ({}(..))`, or leave out --arg-type to match Strings",
+                    arg_type
+                ));
+                exit(1);
+            }
+        }
+    };
+    let variants: Vec<String> = match (&patterns, variants.is_empty()) {
+        (select::Patterns::Constructors(constructors), true) => constructors.clone(),
+        (select::Patterns::Strings, true) => fail(
+            "--variants is needed to select over Strings (e.g., --variants male,female,other)"
+                .to_string(),
+        ),
+        _ => variants.iter().map(|v| v.trim().to_string()).collect(),
+    };
+    for (i, variant) in variants.iter().enumerate() {
+        if variant.is_empty() || variants[..i].contains(variant) {
+            fail(format!(
+                "--variants: `{}` is listed twice or empty",
+                variant
+            ));
+        }
+    }
+
+    let mut values: Vec<(String, HashMap<String, String>)> = languages
+        .iter()
+        .map(|lang| (lang.clone(), HashMap::new()))
+        .collect();
+    for (arg, lang, variant, value) in parse_variant_args(
+        args,
+        languages,
+        "LANG.VARIANT=VALUE (e.g., fr.female=\"Bienvenue\")",
+    ) {
+        // Constructors are matched ignoring case, as `-t fr.female` for `Female`
+        let known = match &patterns {
+            select::Patterns::Strings => variants.iter().find(|v| **v == variant),
+            select::Patterns::Constructors(_) => {
+                variants.iter().find(|v| v.eq_ignore_ascii_case(&variant))
+            }
+        };
+        let Some(variant) = known.cloned() else {
+            fail(format!(
+                "{}: `{}` isn't one of the variants, {}",
+                arg,
+                variant,
+                variants.join(", ")
+            ));
+        };
+        let Some((_, texts)) = values.iter_mut().find(|(l, _)| *l == lang) else {
+            continue;
+        };
+        if texts.insert(variant, value).is_some() {
+            fail(format!(
+                "{} is given twice",
+                arg.split('=').next().unwrap_or(arg)
+            ));
+        }
+    }
+    // Every language's missing variants, so one run shows them all
+    let missing: Vec<String> = values
+        .iter()
+        .filter_map(|(lang, texts)| {
+            let missing: Vec<&str> = variants
+                .iter()
+                .filter(|v| !texts.contains_key(*v))
+                .map(String::as_str)
+                .collect();
+            (!missing.is_empty())
+                .then(|| format!("{} has no {}", lang.to_uppercase(), missing.join(", ")))
+        })
+        .collect();
+    if !missing.is_empty() {
+        errors::error_with(
+            Code::InvalidArgument,
+            format!("Missing variants: {}", missing.join("; ")),
+        );
+        errors::hint(format!(
+            "Give each language every variant, e.g. -t {}.{}=\"...\"",
+            languages[0], variants[0]
+        ));
+        exit(1);
+    }
+
+    let (type_sig, implementations) =
+        match select::select_function(argument, arg_type, &patterns, &variants, &values) {
+            Ok(compiled) => compiled,
+            Err(e) => fail(e.to_string()),
+        };
+    check_implementations(&type_sig, &implementations, languages);
+    (type_sig, implementations)
+}
+
+/// The function `add-plural` makes of `-t LANG.CATEGORY=VALUE` arguments; exits when
+/// they aren't exactly the CLDR plural categories of each language, or its ordinal
+/// ones with `ordinal`
+fn compile_plural(
+    args: &[String],
+    count: &str,
+    languages: &[String],
+    ordinal: bool,
+) -> (String, std::collections::HashMap<String, String>) {
+    let fail = |message: String| -> ! {
+        errors::error(message);
+        exit(1);
+    };
+    if !regex::Regex::new(r"^[a-z][A-Za-z0-9_]*$")
+        .unwrap()
+        .is_match(count)
+    {
+        fail(format!(
+            "--count {}: the count's name must be a lowercase Elm name",
+            count
+        ));
+    }
+
+    let mut branches: Vec<(String, Vec<(icu::Selector, String)>)> = Vec::new();
+    for (arg, lang, selector, value) in parse_variant_args(
+        args,
+        languages,
+        "LANG.CATEGORY=VALUE (e.g., en.one=\"# item\")",
+    ) {
+        let selector = selector.as_str();
+        let selector = match selector.parse::<i64>() {
+            Ok(n) => icu::Selector::Exact(n),
+            Err(_) if plural::CATEGORIES.contains(&selector) => {
+                icu::Selector::Category(selector.to_string())
+            }
+            Err(_) => fail(format!(
+                "{}: `{}` isn't a plural category; use {}, or a number for an exact count",
+                arg,
+                selector,
+                plural::CATEGORIES.join(", ")
+            )),
+        };
+        let index = match branches.iter().position(|(l, _)| *l == lang) {
+            Some(index) => index,
+            None => {
+                branches.push((lang.clone(), Vec::new()));
+                branches.len() - 1
+            }
+        };
+        if branches[index].1.iter().any(|(s, _)| *s == selector) {
+            fail(format!(
+                "{} is given twice",
+                arg.split('=').next().unwrap_or(arg)
+            ));
+        }
+        branches[index].1.push((selector, value));
+    }
+
+    for lang in languages {
+        let Some((_, given)) = branches.iter().find(|(l, _)| l == lang) else {
+            let rules = if ordinal {
+                plural::ordinal_rules(lang)
+            } else {
+                plural::rules(lang)
+            };
+            let categories = rules.map_or(vec!["other"], |r| r.categories());
+            let example: Vec<String> = categories
+                .iter()
+                .map(|c| format!("-t {}.{}=\"...\"", lang, c))
+                .collect();
+            fail(format!(
+                "Missing plural values for language '{}'. Use {}",
+                lang.yellow(),
+                example.join(" ")
+            ));
+        };
+        let categories: Vec<&str> = given
+            .iter()
+            .filter_map(|(selector, _)| match selector {
+                icu::Selector::Category(c) => Some(c.as_str()),
+                icu::Selector::Exact(_) => None,
+            })
+            .collect();
+        if let Some(problem) = plural::category_problem(lang, &categories, ordinal) {
+            errors::error(problem);
+            errors::hint(if ordinal {
+                "With --ordinal the categories come from the CLDR ordinal rules; exact counts like en.0=\"No items\" can be added to any language"
+            } else {
+                "The categories come from the CLDR plural rules; exact counts like en.0=\"No items\" can be added to any language"
+            });
+            exit(1);
+        }
+    }
+    // In the order of the config, with each language's categories in CLDR order
+    // after its exact counts
+    branches.sort_by_key(|(lang, _)| languages.iter().position(|l| l == lang));
+    for (_, given) in &mut branches {
+        given.sort_by_key(|(selector, _)| match selector {
+            icu::Selector::Exact(n) => (0, *n),
+            icu::Selector::Category(c) => (
+                1,
+                plural::CATEGORIES.iter().position(|k| k == c).unwrap_or(0) as i64,
+            ),
+        });
+    }
+
+    let (type_sig, values) = match icu::compile_plural(count, &branches, ordinal) {
+        Ok(compiled) => compiled,
+        Err(e) => fail(e.to_string()),
+    };
+    check_implementations(&type_sig, &values, languages);
+    (type_sig, values)
+}
+
+/// The function `add` makes of values with `{placeholder}`s, or None when they have
+/// none; exits when the languages' placeholders differ
+fn compile_placeholders(
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+    params: &[String],
+    todo_marker: &str,
+) -> Option<(String, std::collections::HashMap<String, String>)> {
+    let compiled = params
+        .iter()
+        .map(|param| placeholder::parse_param(param))
+        .collect::<Result<Vec<_>>>()
+        .and_then(|params| {
+            placeholder::placeholder_function(values, languages, &params, todo_marker)
+        });
+    match compiled {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            let mismatch = e
+                .downcast_ref::<placeholder::PlaceholderMismatch>()
+                .is_some();
+            errors::error(e);
+            if mismatch {
+                errors::hint(format!(
+                    "Use the same {{placeholder}}s in every language; they're what the '{}' check of `elm-i18n ci` and `validate --strict` compare",
+                    ci::PLACEHOLDERS
+                ));
+            }
+            exit(1);
+        }
+    }
+}
+
+fn check_implementations(
+    type_sig: &str,
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) {
+    let mut refused = false;
+    for lang in languages {
+        let value = match values.get(lang) {
+            Some(value) => value,
+            None => continue,
+        };
+        for problem in implementation_problems(type_sig, value) {
+            refused = true;
+            errors::error(format!(
+                "{} implementation {}",
+                lang.to_uppercase(),
+                problem
+            ));
+        }
+    }
+
+    if refused {
+        errors::hint("Fix the implementation, or pass --no-validate if it's valid Elm the check doesn't understand");
+        exit(1);
+    }
+}
+
+/// The command-line arguments with the flags that stand for others rewritten as
+/// those, for clap to parse
+fn expand_flags(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    format_flags(plural_form_flags(args))
+}
+
+/// The command-line arguments with `--format FORMAT` rewritten for the commands it
+/// gives the output format of: `export --format md` as the `export md` it stands
+/// for, and `changelog --format json` as `changelog --json` (Markdown, the default,
+/// needs no flag). The global `--format` that runs elm-format takes no value, so a
+/// `--format` not followed by one of their formats is left for it
+fn format_flags(args: Vec<String>) -> Vec<String> {
+    let replacement = |command: &str, value: &str| -> Option<Vec<String>> {
+        match command {
+            "export" => <ExportFormat as clap::ValueEnum>::from_str(value, true)
+                .is_ok()
+                .then(|| vec![value.to_string()]),
+            "changelog" => match value.to_lowercase().as_str() {
+                "json" => Some(vec!["--json".to_string()]),
+                "markdown" | "md" => Some(vec![]),
+                _ => None,
+            },
+            _ => None,
+        }
+    };
+    let mut args = args.into_iter().peekable();
+    let mut rewritten = Vec::new();
+    let mut command: Option<String> = None;
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rewritten.push(arg);
+            rewritten.extend(args.by_ref());
+            break;
+        }
+        if command.is_none() && (arg == "export" || arg == "changelog") {
+            command = Some(arg.clone());
+        }
+        let Some(command) = &command else {
+            rewritten.push(arg);
+            continue;
+        };
+        if let Some(value) = arg.strip_prefix("--format=") {
+            match replacement(command, value) {
+                Some(flags) => rewritten.extend(flags),
+                None => rewritten.push(arg),
+            }
+        } else if let Some(flags) = args
+            .peek()
+            .filter(|_| arg == "--format")
+            .and_then(|value| replacement(command, value))
+        {
+            args.next();
+            rewritten.extend(flags);
+        } else {
+            rewritten.push(arg);
+        }
+    }
+    rewritten
+}
+
+/// The command-line arguments with `add-plural`'s `--LANG-FORM VALUE` flags (like
+/// `--en-one "1 item"` or `--fr-0="Aucun article"`) rewritten as the `-t LANG.FORM=VALUE`
+/// they stand for; any other argument is left as it is
+fn plural_form_flags(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let flag = regex::Regex::new(
+        r"^--([A-Za-z]{2,3}(?:[-_][A-Za-z0-9]{2,4})?)-(zero|one|two|few|many|other|\d+)(?:=(.*))?$",
+    )
+    .unwrap();
+    let mut args = args.into_iter();
+    let mut rewritten = Vec::new();
+    let mut in_add_plural = false;
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            rewritten.push(arg);
+            rewritten.extend(args.by_ref());
+            break;
+        }
+        in_add_plural = in_add_plural || arg == "add-plural";
+        let Some(captures) = flag.captures(&arg).filter(|_| in_add_plural) else {
+            rewritten.push(arg);
+            continue;
+        };
+        let value = match captures.get(3) {
+            Some(value) => value.as_str().to_string(),
+            None => match args.next() {
+                Some(value) => value,
+                None => {
+                    rewritten.push(arg);
+                    break;
+                }
+            },
+        };
+        rewritten.push("-t".to_string());
+        rewritten.push(format!("{}.{}={}", &captures[1], &captures[2], value));
+    }
+    rewritten
+}
+
+/// Append what the command changed in each edited file to the changelog
+fn record_changes(
+    changelog: &Path,
+    command: &str,
+    watcher: &EditWatcher,
+    files: &[(PathBuf, String)],
+    languages_before: &[String],
+    languages: &[String],
+) -> Result<()> {
+    let timestamp = timestamp_now();
+    let mut records = Vec::new();
+
+    for file in watcher.changed_files() {
+        let record_name = match files.iter().find(|(path, _)| *path == file) {
+            Some((_, record_name)) => record_name,
+            None => continue,
+        };
+        let after = read_elm_file(&file)?;
+        let after = match parse_i18n_content_with_record_name(&after, record_name, languages) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!(
+                    "{} Not logging changes to {}: {}",
+                    "⚠".yellow(),
+                    file.display(),
+                    e
+                );
+                continue;
+            }
+        };
+        let before = watcher.original(&file).and_then(|source| {
+            parse_i18n_content_with_record_name(source, record_name, languages_before).ok()
+        });
+
+        for change in translation_changes(before.as_ref(), &after, languages) {
+            records.push(ChangeRecord {
+                timestamp: timestamp.clone(),
+                command: command.to_string(),
+                file: file.display().to_string(),
+                key: change.key,
+                language: change.language,
+                change: change.change,
+                old: change.old,
+                new: change.new,
+            });
+        }
+    }
+
+    append_records(changelog, &records)?;
+    if !records.is_empty() {
+        println!(
+            "{} Logged {} change{} to {}",
+            "📝".blue(),
+            records.len(),
+            if records.len() == 1 { "" } else { "s" },
+            changelog.display()
+        );
+    }
+    Ok(())
+}
+
+/// Lock entries for every translation file that exists
+fn current_lock(files: &[(PathBuf, String)], languages: &[String]) -> Result<Lock> {
+    let mut lock = Lock::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            continue;
+        }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        lock.extend(lock_entries(
+            &file.display().to_string(),
+            &parse_result,
+            languages,
+        ));
+    }
+    Ok(lock)
+}
+
+fn handle_lock(config: &Config, languages: &[String]) -> Result<()> {
+    let lock = current_lock(&config.translation_files(), languages)?;
+    std::fs::write(LOCK_FILE, render_lock(&lock))
+        .with_context(|| format!("Failed to write {}", LOCK_FILE))?;
+
+    println!(
+        "{} Locked {} values in {}",
+        "🔒".blue(),
+        lock.len(),
+        LOCK_FILE
+    );
+    Ok(())
+}
+
+fn handle_verify_lock(config: &Config, languages: &[String]) -> Result<()> {
+    if !Path::new(LOCK_FILE).exists() {
+        errors::error(format!("No {} found", LOCK_FILE));
+        errors::hint("Run 'elm-i18n lock' to create it");
+        exit(1);
+    }
+
+    let locked = parse_lock(&std::fs::read_to_string(LOCK_FILE)?)?;
+    let current = current_lock(&config.translation_files(), languages)?;
+    let drift = lock_drift(&locked, &current);
+
+    if drift.is_empty() {
+        println!(
+            "{} All {} values match {}",
+            "✓".green(),
+            current.len(),
+            LOCK_FILE
+        );
+        return Ok(());
+    }
+
+    println!("{} Translations differ from {}:", "✗".red(), LOCK_FILE);
+    for item in &drift {
+        let (marker, description) = match item.kind {
+            DriftKind::Added => ("+".green(), "added"),
+            DriftKind::Removed => ("-".red(), "removed"),
+            DriftKind::Changed => ("~".yellow(), "changed"),
+        };
+        println!(
+            "  {} {} [{}] {} ({})",
+            marker,
+            item.entry.key.yellow(),
+            item.entry.language.to_uppercase().cyan(),
+            description,
+            item.entry.file.dimmed()
+        );
+    }
+    println!();
+    println!(
+        "{} Review the changes, then run 'elm-i18n lock' to accept them",
+        "ℹ".blue()
+    );
+    exit(1);
+}
+
+/// Refresh the lock entries of the files an edit changed, keeping the others as they were
+fn update_lock(
+    changed_files: &[PathBuf],
+    files: &[(PathBuf, String)],
+    languages: &[String],
+) -> Result<()> {
+    let edited: Vec<(PathBuf, String)> = files
+        .iter()
+        .filter(|(path, _)| changed_files.contains(path))
+        .cloned()
+        .collect();
+    if edited.is_empty() {
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(LOCK_FILE)?;
+    let mut lock = parse_lock(&content)?;
+    let edited_names: Vec<String> = edited
+        .iter()
+        .map(|(path, _)| path.display().to_string())
+        .collect();
+    lock.retain(|entry, _| !edited_names.contains(&entry.file));
+    lock.extend(current_lock(&edited, languages)?);
+
+    let rendered = render_lock(&lock);
+    if rendered != content {
+        std::fs::write(LOCK_FILE, rendered)
+            .with_context(|| format!("Failed to write {}", LOCK_FILE))?;
+        println!("{} Updated {}", "🔒".blue(), LOCK_FILE);
+    }
+    Ok(())
+}
+
+/// Record the translations the command wrote against the current source text in
+/// the freshness file, seeding it from every translation file the first time
+fn update_freshness(
+    watcher: &EditWatcher,
+    files: &[(PathBuf, String)],
+    languages: &[String],
+) -> Result<()> {
+    let exists = Path::new(FRESHNESS_FILE).exists();
+    let content = if exists {
+        std::fs::read_to_string(FRESHNESS_FILE)?
+    } else {
+        String::new()
+    };
+    let mut freshness = parse_freshness(&content)?;
+    let changed = watcher.changed_files();
+    let parse = |source: &str, record_name: &str| {
+        parse_i18n_content_with_record_name(source, record_name, languages).ok()
+    };
+
+    let mut seen = Vec::new();
+    for (file, record_name) in files {
+        if seen.contains(&file) || !file.exists() || (exists && !changed.contains(file)) {
+            continue;
+        }
+        seen.push(file);
+        let Some(after) = parse(&read_elm_file(file)?, record_name) else {
+            continue;
+        };
+        let before = watcher
+            .original(file)
+            .and_then(|source| parse(source, record_name));
+        let name = file.display().to_string();
+        if !exists {
+            // What was there before this command counts as reviewed
+            if let Some(before) = &before {
+                refresh(&mut freshness, &name, None, before, languages);
+            }
+        }
+        refresh(&mut freshness, &name, before.as_ref(), &after, languages);
+    }
+
+    let rendered = render_freshness(&freshness);
+    if rendered != content {
+        timing::write(FRESHNESS_FILE, &rendered)
+            .with_context(|| format!("Failed to write {}", FRESHNESS_FILE))?;
+        if exists {
+            println!("{} Updated {}", "🕒".blue(), FRESHNESS_FILE);
+        } else {
+            println!(
+                "{} Tracking translation freshness in {}",
+                "🕒".blue(),
+                FRESHNESS_FILE
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handle `freshness`: mark translations reviewed, or stop tracking
+fn handle_freshness(
+    files: &[(PathBuf, String)],
+    review: &[String],
+    langs: &[String],
+    remove: bool,
+    languages: &[String],
+) -> Result<()> {
+    let path = Path::new(FRESHNESS_FILE);
+    if remove {
+        if !path.exists() {
+            println!("{} Freshness isn't tracked; nothing to remove", "ℹ".blue());
+            return Ok(());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", FRESHNESS_FILE))?;
+        println!(
+            "{} Removed {}; edits no longer track freshness",
+            "✓".green(),
+            FRESHNESS_FILE
+        );
+        return Ok(());
+    }
+    if !path.exists() {
+        errors::error(format!(
+            "{} doesn't exist; start tracking with --track-freshness",
+            FRESHNESS_FILE
+        ));
+        exit(1);
+    }
+
+    let mut freshness = parse_freshness(&std::fs::read_to_string(path)?)?;
+    let mut marked = 0;
+    let mut found: Vec<&String> = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        found.extend(
+            review
+                .iter()
+                .filter(|key| parse_result.translations.contains_key(*key)),
+        );
+        marked += mark_reviewed(
+            &mut freshness,
+            &file.display().to_string(),
+            &parse_result,
+            review,
+            langs,
+            languages,
+        );
+    }
+    if let Some(missing) = review.iter().find(|key| !found.contains(key)) {
+        Coded::key_not_found(missing).report();
+        exit(1);
+    }
+
+    timing::write(path, render_freshness(&freshness))
+        .with_context(|| format!("Failed to write {}", FRESHNESS_FILE))?;
+    println!(
+        "{} Marked {} translation{} as reviewed",
+        "✓".green(),
+        marked,
+        if marked == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+/// The command's name as typed on the command line
+fn command_name(command: &Commands) -> &'static str {
+    match command {
+        Commands::Setup => "setup",
+        Commands::Status => "status",
+        Commands::Add { .. } => "add",
+        Commands::AddFunction { .. } => "add-fn",
+        Commands::AddIcu { .. } => "add-icu",
+        Commands::AddPlural { .. } => "add-plural",
+        Commands::AddSelect { .. } => "add-select",
+        Commands::Scaffold { .. } => "scaffold",
+        Commands::Check { .. } => "check",
+        Commands::Init { .. } => "init",
+        Commands::Remove { .. } => "remove",
+        Commands::Rename { .. } => "rename",
+        Commands::Group { .. } => "group",
+        Commands::RemoveUnused { .. } => "remove-unused",
+        Commands::Prune { .. } => "prune",
+        Commands::List { .. } => "list",
+        Commands::DuplicateKeys { .. } => "duplicate-keys",
+        Commands::SharedValues { .. } => "shared-values",
+        Commands::Lint { .. } => "lint",
+        Commands::Ci { .. } => "ci",
+        Commands::Stats { .. } => "stats",
+        Commands::Coverage { .. } => "coverage",
+        Commands::Report { .. } => "report",
+        Commands::Keys { .. } => "keys",
+        Commands::Scan { .. } => "scan",
+        Commands::Usages { .. } => "usages",
+        Commands::Extract { .. } => "extract",
+        Commands::Rewrite { .. } => "rewrite",
+        Commands::Validate { .. } => "validate",
+        Commands::Format { .. } => "format",
+        Commands::Canonicalize { .. } => "canonicalize",
+        Commands::Doctor { .. } => "doctor",
+        Commands::Verify { .. } => "verify",
+        Commands::GenTest { .. } => "gen-test",
+        Commands::Render { .. } => "render",
+        Commands::Export { .. } => "export",
+        Commands::Diff { .. } => "diff",
+        Commands::Changelog { .. } => "changelog",
+        Commands::Blame { .. } => "blame",
+        Commands::Freshness { .. } => "freshness",
+        Commands::Generate { .. } => "generate",
+        Commands::Backups { .. } => "backups",
+        Commands::Restore { .. } => "restore",
+        Commands::Watch { .. } => "watch",
+        Commands::Lock => "lock",
+        Commands::VerifyLock => "verify-lock",
+        Commands::Modify { .. } => "modify",
+        Commands::AddCase { .. } => "add-case",
+        Commands::RenamePlaceholder { .. } => "rename-placeholder",
+        Commands::ModifyBulk { .. } => "modify-bulk",
+        Commands::AddLanguage { .. } => "add-language",
+        Commands::SetupClaude => "setup-claude",
+        Commands::Version => "version",
+    }
+}
+
+/// The `--file` argument of commands that take one
+fn command_file_arg(command: &Commands) -> Option<&PathBuf> {
+    match command {
+        Commands::Add { file, .. }
+        | Commands::AddFunction { file, .. }
+        | Commands::AddIcu { file, .. }
+        | Commands::AddPlural { file, .. }
+        | Commands::AddSelect { file, .. }
+        | Commands::Scaffold { file, .. }
+        | Commands::Check { file, .. }
+        | Commands::Init { file, .. }
+        | Commands::Remove { file, .. }
+        | Commands::Rename { file, .. }
+        | Commands::RemoveUnused { file, .. }
+        | Commands::Prune { file, .. }
+        | Commands::List { file, .. }
+        | Commands::DuplicateKeys { file }
+        | Commands::SharedValues { file, .. }
+        | Commands::Lint { file, .. }
+        | Commands::Ci { file, .. }
+        | Commands::Format { file, .. }
+        | Commands::Canonicalize { file, .. }
+        | Commands::Stats { file, .. }
+        | Commands::Coverage { file, .. }
+        | Commands::Report { file, .. }
+        | Commands::Keys { file, .. }
+        | Commands::Scan { file, .. }
+        | Commands::Usages { file, .. }
+        | Commands::Rewrite { file, .. }
+        | Commands::Validate { file, .. }
+        | Commands::GenTest { file, .. }
+        | Commands::Render { file, .. }
+        | Commands::Export { file, .. }
+        | Commands::Diff { file, .. }
+        | Commands::Changelog { file, .. }
+        | Commands::Blame { file, .. }
+        | Commands::Freshness { file, .. }
+        | Commands::Generate { file, .. }
+        | Commands::Backups { file, .. }
+        | Commands::Group { file, .. }
+        | Commands::Restore { file, .. }
+        | Commands::Watch { file, .. }
+        | Commands::Doctor { file, .. }
+        | Commands::Verify { file }
+        | Commands::Modify { file, .. }
+        | Commands::AddCase { file, .. }
+        | Commands::RenamePlaceholder { file, .. }
+        | Commands::ModifyBulk { file, .. } => Some(file),
+        Commands::Setup
+        | Commands::Status
+        | Commands::SetupClaude
+        | Commands::AddLanguage { .. }
+        | Commands::Extract { .. }
+        | Commands::Lock
+        | Commands::VerifyLock
+        | Commands::Version => None,
+    }
+}
+
+/// The key a command is about, to report its errors with; the first of several
+fn command_key_arg(command: &Commands) -> Option<String> {
+    match command {
+        Commands::Add { key, .. }
+        | Commands::AddFunction { key, .. }
+        | Commands::AddIcu { key, .. }
+        | Commands::AddPlural { key, .. }
+        | Commands::AddSelect { key, .. }
+        | Commands::Rewrite { key, .. }
+        | Commands::Modify { key, .. }
+        | Commands::AddCase { key, .. }
+        | Commands::RenamePlaceholder { key, .. } => Some(key.clone()),
+        Commands::Rename { old_key, .. } => Some(old_key.clone()),
+        Commands::Check { keys, .. } | Commands::Remove { keys, .. } => keys.first().cloned(),
+        Commands::Usages { key, .. } | Commands::Blame { key, .. } => key.clone(),
+        _ => None,
+    }
+}
+
+fn handle_doctor(
+    files: &[(PathBuf, String)],
+    languages: &[String],
+    merge_duplicates: bool,
+) -> Result<()> {
+    let mut blocking = 0;
+
+    for (file, record_name) in files {
+        println!("{} Examining {}...", "🩺".blue(), file.display());
+
+        if !file.exists() {
+            println!("  {} File not found", "✗".red());
+            println!();
+            blocking += 1;
+            continue;
+        }
+        let mut source = match read_elm_file(file) {
+            Ok(source) => source,
+            Err(e) => {
+                println!("  {} {}", "✗".red(), e);
+                println!();
+                blocking += 1;
+                continue;
+            }
+        };
+        if merge_duplicates {
+            if let Some(outcome) = merge_duplicate_records(&source, record_name, languages) {
+                timing::write(file, &outcome.content)
+                    .with_context(|| format!("Failed to write to {}", file.display()))?;
+                for fix in &outcome.fixes {
+                    println!("  {} Fixed: {}", "✓".green(), fix);
+                }
+                source = outcome.content;
+            }
+        }
+        if starts_with_bom(file) {
+            println!(
+                "  {} Starts with a byte-order mark (harmless; elm-i18n ignores it)",
+                "ℹ".blue()
+            );
+        }
+
+        for diagnosis in diagnose(&source, record_name, languages) {
+            let marker = match diagnosis.level {
+                DiagnosisLevel::Ok => "✓".green(),
+                DiagnosisLevel::Warning => "⚠".yellow(),
+                DiagnosisLevel::Blocking => {
+                    blocking += 1;
+                    "✗".red()
+                }
+            };
+            let line = diagnosis
+                .line
+                .map(|line| format!("line {}: ", line))
+                .unwrap_or_default();
+            println!("  {} {}{}", marker, line.dimmed(), diagnosis.message);
+            if let Some(suggestion) = &diagnosis.suggestion {
+                println!("      {} {}", "→".cyan(), suggestion);
+            }
+        }
+        println!();
+    }
+
+    if blocking > 0 {
+        println!(
+            "{} {} blocking problem{} found; elm-i18n can't edit the file until {} fixed.",
+            "✗".red(),
+            blocking,
+            if blocking == 1 { "" } else { "s" },
+            if blocking == 1 { "it is" } else { "they are" }
+        );
+        exit(1);
+    }
+
+    println!("{} No blocking problems found", "✓".green());
+    Ok(())
+}
+
+/// What `{lang}` in an export's `--output` is replaced with
+const LANG_PLACEHOLDER: &str = "{lang}";
+
+fn handle_export(
+    file: &Path,
+    format: ExportFormat,
+    output: Option<&Path>,
+    only_changed: bool,
+    split: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+    if let Some(output) = output.filter(|output| split && output.is_file()) {
+        errors::error(format!(
+            "{} is a file; --split writes a file per key into a directory",
+            output.display()
+        ));
+        exit(1);
+    }
+
+    // Each file to write with the languages it has: one per language with `{lang}`
+    let template = output.map(|output| output.display().to_string());
+    let outputs: Vec<(Vec<String>, Option<PathBuf>)> = match &template {
+        Some(template) if template.contains(LANG_PLACEHOLDER) => languages
+            .iter()
+            .map(|lang| {
+                let path = PathBuf::from(template.replace(LANG_PLACEHOLDER, lang));
+                (vec![lang.clone()], Some(path))
+            })
+            .collect(),
+        _ => vec![(languages.to_vec(), output.map(Path::to_path_buf))],
+    };
+
+    let source = read_elm_file(file)?;
+    let context_source = std::fs::read_to_string(context::context_path(file)).unwrap_or_default();
+    let source_hash = content_hash(&format!(
+        "{:?}\n{}\n{}\n{}",
+        format,
+        languages.join(","),
+        source,
+        context_source
+    ));
+    let cache_path = Path::new(EXPORT_CACHE_FILE);
+    let mut cache = if only_changed {
+        export_cache::load(cache_path)
+    } else {
+        ExportCache::new()
+    };
+    let cached = template.as_ref().and_then(|template| cache.get(template));
+    let all_written = outputs
+        .iter()
+        .all(|(_, path)| path.as_ref().is_some_and(|path| path.exists()));
+    if only_changed && all_written && cached.is_some_and(|cached| cached.source == source_hash) {
+        println!(
+            "{} {} hasn't changed since the last export",
+            "✓".green(),
+            file.display()
+        );
+        return Ok(());
+    }
+
+    let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+    let contexts = context::load(file)?;
+    let hashes: std::collections::BTreeMap<String, Vec<(String, String)>> = languages
+        .iter()
+        .map(|lang| {
+            let hashes = export_cache::key_hashes(&parse_result, lang, &contexts);
+            (lang.clone(), hashes)
+        })
+        .collect();
+
+    let mut unchanged = Vec::new();
+    for (langs, path) in &outputs {
+        let changed: std::collections::BTreeSet<String> = langs
+            .iter()
+            .flat_map(|lang| {
+                let before = cached.and_then(|cached| cached.languages.get(lang));
+                export_cache::changed_keys(before.map(Vec::as_slice), &hashes[lang])
+            })
+            .collect();
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            if only_changed && changed.is_empty() {
+                unchanged.push(path.display().to_string());
+                continue;
+            }
+        }
+
+        if let Some(dir) = path.as_ref().filter(|_| split) {
+            // Without a previous export to compare with, every key's file is written
+            let rewrite_all = !only_changed || cached.is_none();
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            let files = split_by_key(&parse_result, format, langs, &contexts);
+            let mut written = 0;
+            for (key, exported) in &files {
+                let key_path = dir.join(format!("{}.{}", key, format.extension()));
+                if rewrite_all || changed.contains(key) || !key_path.exists() {
+                    timing::write(&key_path, exported)
+                        .with_context(|| format!("Failed to write to {}", key_path.display()))?;
+                    written += 1;
+                }
+            }
+            // Keys removed since the last export take their files with them
+            let mut removed = 0;
+            for key in changed
+                .iter()
+                .filter(|key| !files.iter().any(|(k, _)| k == *key))
+            {
+                let key_path = dir.join(format!("{}.{}", key, format.extension()));
+                if key_path.exists() {
+                    std::fs::remove_file(&key_path)
+                        .with_context(|| format!("Failed to remove {}", key_path.display()))?;
+                    removed += 1;
+                }
+            }
+            let removed = match removed {
+                0 => String::new(),
+                1 => " and removed a removed key's file".to_string(),
+                n => format!(" and removed the files of {} removed keys", n),
+            };
+            if written == files.len() {
+                println!(
+                    "{} Exported {} keys to a file each in {}{}",
+                    "✓".green(),
+                    written,
+                    dir.display(),
+                    removed
+                );
+            } else {
+                println!(
+                    "{} Rewrote {} of the {} key files in {}{}",
+                    "✓".green(),
+                    written,
+                    files.len(),
+                    dir.display(),
+                    removed
+                );
+            }
+            continue;
+        }
+
+        let exported = format.render(&parse_result, langs, &contexts);
+        let Some(path) = path else {
+            print!("{}", exported);
+            continue;
+        };
+        std::fs::write(path, &exported)
+            .with_context(|| format!("Failed to write to {}", path.display()))?;
+        let changes = if only_changed && cached.is_some() {
+            format!(
+                " ({} changed key{})",
+                changed.len(),
+                if changed.len() == 1 { "" } else { "s" }
+            )
+        } else {
+            String::new()
+        };
+        println!(
+            "{} Exported {} translations to {}{}",
+            "✓".green(),
+            parse_result.translations.len(),
+            path.display(),
+            changes
+        );
+    }
+    if !unchanged.is_empty() {
+        println!(
+            "{} No translation changes for {}; left as {}",
+            "ℹ".blue(),
+            unchanged.join(", "),
+            if unchanged.len() == 1 {
+                "it was"
+            } else {
+                "they were"
+            }
+        );
+    }
+
+    if only_changed {
+        if let Some(template) = template {
+            cache.insert(
+                template,
+                ExportRecord {
+                    source: source_hash,
+                    languages: hashes,
+                },
+            );
+            export_cache::save(cache_path, &cache)?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_watch(
+    run: &str,
+    files: &[(PathBuf, String)],
+    src_dirs: &[PathBuf],
+    clear: bool,
+    target: Option<&str>,
+    languages: &[String],
+) -> Result<()> {
+    let cli = Cli::command();
+    let command_names: Vec<String> = cli
+        .get_subcommands()
+        .flat_map(|c| std::iter::once(c.get_name()).chain(c.get_all_aliases()))
+        .map(str::to_string)
+        .collect();
+    let commands = match watch::split_commands(run, &command_names) {
+        Ok(commands) => commands,
+        Err(e) => {
+            errors::error(e);
+            exit(1);
+        }
+    };
+    // Catch a typo in the arguments now rather than on every change
+    for command in &commands {
+        if command[0] == "watch" {
+            errors::error("watch can't run itself");
+            exit(1);
+        }
+        if let Err(e) = Cli::try_parse_from(expand_flags(
+            std::iter::once("elm-i18n".to_string()).chain(command.iter().cloned()),
+        )) {
+            errors::error(format!("Can't run `{}` from --run:", command.join(" ")));
+            errors::detail(e.render().to_string().trim_end());
+            exit(1);
+        }
+    }
+    for dir in src_dirs {
+        if !dir.is_dir() {
+            errors::error_with(
+                Code::FileNotFound,
+                format!("Directory not found: {}", dir.display()),
+            );
+            exit(1);
+        }
+    }
+
+    let exe = std::env::current_exe().context("Failed to find the elm-i18n binary")?;
+    let mut watched: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+    watched.push(PathBuf::from(config_file_path()));
+
+    let file_list = files
+        .iter()
+        .map(|(path, _)| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let dir_list = src_dirs
+        .iter()
+        .map(|dir| format!(" and {}", dir.display()))
+        .collect::<String>();
+    println!(
+        "{} Watching {}{}; press Ctrl-C to stop",
+        "🔍".blue(),
+        file_list,
+        dir_list
+    );
+
+    loop {
+        run_watched_commands(&exe, &commands, files, clear, target, languages);
+        // Commands such as `format` may write the files themselves; only later changes count
+        let last = watch::snapshot(&watched, src_dirs);
+        let mut current = last.clone();
+        while current == last {
+            std::thread::sleep(watch::POLL_INTERVAL);
+            current = watch::snapshot(&watched, src_dirs);
+        }
+        // Wait for the files to settle, so one save runs the commands once
+        loop {
+            std::thread::sleep(watch::DEBOUNCE);
+            let settled = watch::snapshot(&watched, src_dirs);
+            if settled == current {
+                break;
+            }
+            current = settled;
+        }
+    }
+}
+
+/// Run each `watch` command once, printing a line for each with the time it ran
+fn run_watched_commands(
+    exe: &Path,
+    commands: &[Vec<String>],
+    files: &[(PathBuf, String)],
+    clear: bool,
+    target: Option<&str>,
+    languages: &[String],
+) {
+    if clear {
+        // Clear the screen and the scrollback, then move to the top left
+        print!("\x1b[2J\x1b[3J\x1b[H");
+    }
+    let clock = timestamp_now()[11..19].to_string();
+
+    // A file saved halfway through an edit may not parse; say so and wait for the next save
+    for (file, record_name) in files {
+        let problem = if !file.exists() {
+            Some("file not found".to_string())
+        } else {
+            parse_i18n_file_with_record_name(file, record_name, languages)
+                .err()
+                .map(|e| e.to_string())
+        };
+        if let Some(problem) = problem {
+            println!(
+                "{} {} {}: {}",
+                clock.dimmed(),
+                "✗".red(),
+                file.display(),
+                problem
+            );
+            let _ = io::stdout().flush();
+            return;
+        }
+    }
+
+    for command in commands {
+        let started = std::time::Instant::now();
+        let mut child = std::process::Command::new(exe);
+        if let Some(target) = target {
+            child.arg("--target").arg(target);
+        }
+        let output = child
+            .args(command)
+            .stdin(std::process::Stdio::null())
+            .output();
+        let elapsed = format!("({:.2}s)", started.elapsed().as_secs_f64());
+        match output {
+            Ok(output) if output.status.success() => println!(
+                "{} {} {} {}",
+                clock.dimmed(),
+                "✓".green(),
+                command.join(" "),
+                elapsed.dimmed()
+            ),
+            Ok(output) => {
+                println!(
+                    "{} {} {} {}",
+                    clock.dimmed(),
+                    "✗".red(),
+                    command.join(" "),
+                    elapsed.dimmed()
+                );
+                let captured = format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                for line in captured.lines().filter(|line| !line.trim().is_empty()) {
+                    println!("    {}", line);
+                }
+            }
+            Err(e) => println!(
+                "{} {} {}: {}",
+                clock.dimmed(),
+                "✗".red(),
+                command.join(" "),
+                e
+            ),
+        }
+    }
+    let _ = io::stdout().flush();
+}
+
+/// Who last changed each part of a key: its type field, then each language's value
+struct KeyBlame<'a> {
+    file: &'a Path,
+    key: String,
+    /// ("type" or a language, 1-based line, the line's blame)
+    parts: Vec<(String, usize, Option<BlameLine>)>,
+}
+
+fn handle_blame(
+    files: &[(PathBuf, String)],
+    key: Option<&str>,
+    json: bool,
+    languages: &[String],
+) -> Result<()> {
+    let mut blamed = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        if key.is_some_and(|key| !parse_result.translations.contains_key(key)) {
+            continue;
+        }
+        let lines = match blame_file(Path::new("."), file) {
+            Ok(lines) => lines,
+            Err(RevisionError::NotARepository) => {
+                errors::error_with(Code::Git, "Not in a git repository (or git isn't installed); blame reads the history with git");
+                exit(1);
+            }
+            Err(_) => {
+                eprintln!(
+                    "{} Skipping {} (not committed to git yet)",
+                    "⚠".yellow(),
+                    file.display()
+                );
+                continue;
+            }
+        };
+
+        // A multi-line value counts as changed when any of its lines did; blank
+        // and comment lines between fields don't count
+        let latest = |range: std::ops::Range<usize>| {
+            let blame = lines
+                .get(range.clone())
+                .unwrap_or_default()
+                .iter()
+                .filter(|line| {
+                    let text = line.text.trim();
+                    !text.is_empty() && !text.starts_with("--")
+                })
+                .max_by_key(|line| (!line.is_committed(), line.author_time))
+                .cloned();
+            (range.start + 1, blame)
+        };
+        for field in &parse_result.type_fields {
+            if key.is_some_and(|key| key != field.name) {
+                continue;
+            }
+            let (line, blame) = latest(field.line..field.line + 1);
+            let mut parts = vec![("type".to_string(), line, blame)];
+            for lang in languages {
+                if let Some(range) = field_lines(&parse_result, lang, &field.name) {
+                    let (line, blame) = latest(range);
+                    parts.push((lang.clone(), line, blame));
+                }
+            }
+            blamed.push(KeyBlame {
+                file,
+                key: field.name.clone(),
+                parts,
+            });
+        }
+    }
+
+    if let (Some(key), true) = (key, blamed.is_empty()) {
+        errors::error_with(
+            Code::KeyNotFound,
+            format!("Translation '{}' not found", key.yellow()),
+        );
+        exit(1);
+    }
+
+    if json {
+        let keys: Vec<serde_json::Value> = blamed
+            .iter()
+            .map(|blamed| {
+                let part = |(_, line, blame): &(String, usize, Option<BlameLine>)| match blame
+                    .as_ref()
+                    .filter(|blame| blame.is_committed())
+                {
+                    Some(blame) => serde_json::json!({
+                        "line": line,
+                        "commit": blame.commit,
+                        "author": blame.author,
+                        "date": format_timestamp(blame.author_time),
+                        "summary": blame.summary,
+                    }),
+                    None => serde_json::json!({
+                        "line": line,
+                        "commit": null,
+                        "author": null,
+                        "date": null,
+                        "summary": null,
+                    }),
+                };
+                let values: serde_json::Map<String, serde_json::Value> = blamed.parts[1..]
+                    .iter()
+                    .map(|p| (p.0.clone(), part(p)))
+                    .collect();
+                serde_json::json!({
+                    "key": blamed.key,
+                    "file": blamed.file.display().to_string(),
+                    "type": part(&blamed.parts[0]),
+                    "languages": values,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&keys)?);
+        return Ok(());
+    }
+
+    for (i, blamed) in blamed.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} {} ({})",
+            "🔍".blue(),
+            blamed.key.yellow(),
+            blamed.file.display()
+        );
+        for (part, line, blame) in &blamed.parts {
+            let label = if part == "type" {
+                part.clone()
+            } else {
+                part.to_uppercase()
+            };
+            let line = format!("line {}", line);
+            match blame.as_ref().filter(|blame| blame.is_committed()) {
+                Some(blame) => println!(
+                    "  {:<5} {:<9} {}  {}  {}  {}",
+                    label.cyan(),
+                    line.dimmed(),
+                    blame.commit[..8].yellow(),
+                    &format_timestamp(blame.author_time)[..10],
+                    blame.author,
+                    blame.summary
+                ),
+                None => println!(
+                    "  {:<5} {:<9} {}",
+                    label.cyan(),
+                    line.dimmed(),
+                    "not committed yet".dimmed()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the backups of `file`, newest first and numbered as `restore` takes them,
+/// with how many keys each has against the file now; false if there are none
+fn print_backups(file: &Path, record_name: &str, languages: &[String]) -> bool {
+    let backups = backup::list_backups(file);
+    if backups.is_empty() {
+        println!("{} No backups of {}", "ℹ".blue(), file.display());
+        return false;
+    }
+
+    let key_count = |path: &Path| {
+        let source = read_elm_file(path).ok()?;
+        let parse_result =
+            parse_i18n_content_with_record_name(&source, record_name, languages).ok()?;
+        Some(parse_result.translations.len())
+    };
+    let keys = |n: usize| format!("{} key{}", n, if n == 1 { "" } else { "s" });
+    let current = key_count(file);
+
+    println!(
+        "{} Backups of {}{}, newest first:",
+        "🗂".blue(),
+        file.display(),
+        current
+            .map(|n| format!(" ({})", keys(n)))
+            .unwrap_or_default()
+    );
+    for (n, (path, time)) in backups.iter().enumerate() {
+        let seconds = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let count = match (key_count(path), current) {
+            (None, _) => "can't be parsed".to_string(),
+            (Some(count), Some(now)) if count == now => format!("{}, as now", keys(count)),
+            (Some(count), Some(now)) => format!(
+                "{}, {} {} than now",
+                keys(count),
+                count.abs_diff(now),
+                if count > now { "more" } else { "fewer" }
+            ),
+            (Some(count), None) => keys(count),
+        };
+        println!(
+            "  {:>3}  {}  {}  {}",
+            n + 1,
+            format_timestamp(seconds).dimmed(),
+            path.display(),
+            count.dimmed()
+        );
+    }
+    true
+}
+
+/// Delete the backups of `file` beyond the newest `keep`
+fn handle_clean_backups(file: &Path, keep: usize, dry_run: bool) -> Result<()> {
+    let backups = backup::list_backups(file);
+    if backups.len() <= keep {
+        println!(
+            "{} {} has {} backup{}; nothing to delete",
+            "ℹ".blue(),
+            file.display(),
+            backups.len(),
+            if backups.len() == 1 { "" } else { "s" }
+        );
+        return Ok(());
+    }
+
+    let old = &backups[keep..];
+    for (path, _) in old {
+        if dry_run {
+            println!("  Would delete {}", path.display());
+        } else {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            println!("  {} Deleted {}", "🗑".blue(), path.display());
+        }
+    }
+    println!(
+        "{} {} {} backup{} of {}, keeping the newest {}",
+        if dry_run { "ℹ".blue() } else { "✓".green() },
+        if dry_run { "Would delete" } else { "Deleted" },
+        old.len(),
+        if old.len() == 1 { "" } else { "s" },
+        file.display(),
+        keep
+    );
+    Ok(())
+}
+
+/// List the backups of `file`, or put one back after backing up what it replaces
+fn handle_restore(
+    file: &Path,
+    backup: Option<&str>,
+    list: bool,
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if list {
+        if print_backups(file, record_name, languages) {
+            println!();
+            println!(
+                "{} Restore one with `elm-i18n restore <NUMBER>`",
+                "→".cyan()
+            );
+        }
+        return Ok(());
+    }
+
+    let backups = backup::list_backups(file);
+    let chosen = match backup {
+        None => match backups.first() {
+            Some((path, _)) => path.clone(),
+            None => {
+                errors::error(format!("No backups of {} to restore", file.display()));
+                exit(1);
+            }
+        },
+        Some(number) if number.parse::<usize>().is_ok() => {
+            let n: usize = number.parse().unwrap();
+            match n.checked_sub(1).and_then(|i| backups.get(i)) {
+                Some((path, _)) => path.clone(),
+                None => {
+                    errors::error(format!(
+                        "There's no backup {} of {}; it has {}",
+                        n,
+                        file.display(),
+                        backups.len()
+                    ));
+                    errors::suggestion("Run `elm-i18n restore --list` to see them");
+                    exit(1);
+                }
+            }
+        }
+        Some(path) => PathBuf::from(path),
+    };
+    if !chosen.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", chosen.display()),
+        );
+        exit(1);
+    }
+
+    let restored = read_elm_file(&chosen)?;
+    let current = if file.exists() {
+        Some(read_elm_file(file)?)
+    } else {
+        None
+    };
+    if current.as_deref() == Some(restored.as_str()) {
+        println!(
+            "{} {} already matches {}",
+            "ℹ".blue(),
+            file.display(),
+            chosen.display()
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        print_diff(file, current.as_deref().unwrap_or_default(), &restored);
+        return Ok(());
+    }
+
+    let replaced = current
+        .map(|current| backup::write_backup(file, &current))
+        .transpose()?;
+    timing::write(file, &restored)
+        .with_context(|| format!("Failed to write to {}", file.display()))?;
+    println!(
+        "{} Restored {} from {}",
+        "✓".green(),
+        file.display(),
+        chosen.display()
+    );
+    if let Some(replaced) = replaced {
+        println!(
+            "  {} What it replaced is in {}",
+            "ℹ".blue(),
+            replaced.display()
+        );
+    }
+    Ok(())
+}
+
+/// Handle `diff`: list the keys of `files` added, removed and changed since `base`
+fn handle_diff(
+    files: &[(PathBuf, String)],
+    base: &str,
+    base_path: Option<&Path>,
+    langs: &[String],
+    changed_only: bool,
+    json: bool,
+    languages: &[String],
+) -> Result<()> {
+    if base_path.is_some() && files.len() > 1 {
+        errors::error("--base-path is for a single file; pass --target to pick which one moved");
+        exit(1);
+    }
+
+    let mut reports = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        let compared = if langs.is_empty() {
+            languages.to_vec()
+        } else {
+            requested_languages(langs, file, languages)
+        };
+
+        let old_path = base_path.unwrap_or(file);
+        let old_source = source_at_revision("diff", base, old_path, base_path.is_none());
+
+        // A language added since the base has no record there to compare with
+        let (old_languages, new_languages): (Vec<String>, Vec<String>) = compared
+            .into_iter()
+            .partition(|lang| has_translation_record(&old_source, record_name, lang));
+        for lang in &new_languages {
+            errors::hint(format!(
+                "{} has no {} record at {}; its values aren't compared",
+                old_path.display(),
+                lang,
+                base
+            ));
+        }
+
+        let old = parse_i18n_content_with_record_name(&old_source, record_name, &old_languages)
+            .with_context(|| format!("Failed to read {} at {}", old_path.display(), base))?;
+        let current = parse_i18n_file_with_record_name(file, record_name, &old_languages)?;
+        let mut changes = key_changes(&old, &current, &old_languages);
+        if changed_only {
+            changes.added.clear();
+            changes.removed.clear();
+        }
+        reports.push((file, changes));
+    }
+
+    if json {
+        let files: Vec<serde_json::Value> = reports
+            .iter()
+            .map(|(file, changes)| key_changes_json(file, changes))
+            .collect();
+        let output = serde_json::json!({ "base": base, "files": files });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    for (file, changes) in &reports {
+        if changes.is_empty() {
+            println!(
+                "{} {}: no translation changes since {}",
+                "✓".green(),
+                file.display(),
+                base
+            );
+            continue;
+        }
+
+        let mut counts = Vec::new();
+        if !changed_only {
+            counts.push(format!("{} added", changes.added.len()));
+            counts.push(format!("{} removed", changes.removed.len()));
+        }
+        counts.push(format!("{} changed", changes.changed.len()));
+        println!(
+            "{} {} since {}: {}",
+            "📋".blue(),
+            file.display(),
+            base,
+            counts.join(", ")
+        );
+
+        let print_values = |values: &std::collections::BTreeMap<String, String>| {
+            for (lang, value) in values {
+                println!("      {}: {}", lang.to_uppercase().cyan(), value);
+            }
+        };
+        if !changes.added.is_empty() {
+            println!();
+            println!("  {}", "Added".bold());
+            for (key, values) in &changes.added {
+                println!("  {} {}", "+".green(), key.yellow());
+                print_values(values);
+            }
+        }
+        if !changes.removed.is_empty() {
+            println!();
+            println!("  {}", "Removed".bold());
+            for (key, values) in &changes.removed {
+                println!("  {} {}", "-".red(), key.yellow());
+                print_values(values);
+            }
+        }
+        if !changes.changed.is_empty() {
+            println!();
+            println!("  {}", "Changed".bold());
+            for (key, languages) in &changes.changed {
+                println!("  {} {}", "~".cyan(), key.yellow());
+                for (lang, old, new) in languages {
+                    println!(
+                        "      {}: {} {} {}",
+                        lang.to_uppercase().cyan(),
+                        old.dimmed(),
+                        "→".cyan(),
+                        new
+                    );
+                }
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// The source of `path` at `revision`, exiting with an error `command` can't go on
+/// without when git can't read it; `moved_hint` suggests --base-path for a missing file
+fn source_at_revision(command: &str, revision: &str, path: &Path, moved_hint: bool) -> String {
+    match file_at_revision(Path::new("."), revision, path) {
+        Ok(source) => source,
+        Err(RevisionError::NotARepository) => {
+            errors::error_with(Code::Git, format!(
+                "Not in a git repository (or git isn't installed); {} reads the old version with git",
+                command
+            ));
+            exit(1);
+        }
+        Err(RevisionError::UnknownRevision) => {
+            errors::error_with(Code::Git, format!("Unknown git revision '{}'", revision));
+            exit(1);
+        }
+        Err(RevisionError::MissingPath) => {
+            errors::error(format!("{} didn't exist at {}", path.display(), revision));
+            if moved_hint {
+                errors::suggestion("If it has moved since, pass its old path with --base-path");
+            }
+            exit(1);
+        }
+    }
+}
+
+/// The changes of `file` as `diff --json` and `changelog --json` print them
+fn key_changes_json(file: &Path, changes: &KeyChanges) -> serde_json::Value {
+    let keys = |keys: &[(String, std::collections::BTreeMap<String, String>)]| {
+        keys.iter()
+            .map(|(key, values)| serde_json::json!({ "key": key, "values": values }))
+            .collect::<Vec<_>>()
+    };
+    let changed: Vec<serde_json::Value> = changes
+        .changed
+        .iter()
+        .map(|(key, languages)| {
+            let languages: Vec<serde_json::Value> = languages
+                .iter()
+                .map(|(lang, old, new)| serde_json::json!({ "language": lang, "old": old, "new": new }))
+                .collect();
+            serde_json::json!({ "key": key, "changes": languages })
+        })
+        .collect();
+    serde_json::json!({
+        "file": file.display().to_string(),
+        "added": keys(&changes.added),
+        "removed": keys(&changes.removed),
+        "changed": changed,
+    })
+}
+
+/// Release notes of the translation changes in `files` from `from` to `to` (the
+/// working tree when none), comparing the source language unless `all_languages`
+#[allow(clippy::too_many_arguments)]
+fn handle_changelog(
+    files: &[(PathBuf, String)],
+    from: &str,
+    to: Option<&str>,
+    all_languages: bool,
+    json: bool,
+    output: Option<&Path>,
+    ignore: &[String],
+    languages: &[String],
+) -> Result<()> {
+    let compared = if all_languages {
+        languages.to_vec()
+    } else {
+        languages.iter().take(1).cloned().collect()
+    };
+    let to_label = to.unwrap_or("the working tree");
+
+    let mut sources = Vec::new();
+    for (file, record_name) in files {
+        let old_source = source_at_revision("changelog", from, file, false);
+        let new_source = match to {
+            Some(to) => source_at_revision("changelog", to, file, false),
+            None => std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?,
+        };
+        sources.push((file, record_name, old_source, new_source));
+    }
+
+    // A language added in between has no record in the old version to compare with
+    let (shown, skipped): (Vec<String>, Vec<String>) = compared.into_iter().partition(|lang| {
+        sources
+            .iter()
+            .all(|(_, record_name, old_source, new_source)| {
+                has_translation_record(old_source, record_name, lang)
+                    && has_translation_record(new_source, record_name, lang)
+            })
+    });
+    for lang in &skipped {
+        errors::hint(format!(
+            "{} isn't in both {} and {}; its values aren't compared",
+            lang, from, to_label
+        ));
+    }
+
+    let mut reports = Vec::new();
+    for (file, record_name, old_source, new_source) in &sources {
+        let old = parse_i18n_content_with_record_name(old_source, record_name, &shown)
+            .with_context(|| format!("Failed to read {} at {}", file.display(), from))?;
+        let new = parse_i18n_content_with_record_name(new_source, record_name, &shown)
+            .with_context(|| format!("Failed to read {} at {}", file.display(), to_label))?;
+        let changes = release_notes::without_ignored(key_changes(&old, &new, &shown), ignore);
+        reports.push((file.display().to_string(), changes));
+    }
+
+    let rendered = if json {
+        let files: Vec<serde_json::Value> = reports
+            .iter()
+            .map(|(file, changes)| key_changes_json(Path::new(file), changes))
+            .collect();
+        let output = serde_json::json!({ "from": from, "to": to, "files": files });
+        format!("{}\n", serde_json::to_string_pretty(&output)?)
+    } else {
+        release_notes::render_markdown(from, to_label, &reports, &shown)
+    };
+
+    match output {
+        Some(path) => {
+            timing::write(path, &rendered)
+                .with_context(|| format!("Failed to write to {}", path.display()))?;
+            println!(
+                "{} Wrote the release notes from {} to {} in {}",
+                "✓".green(),
+                from,
+                to_label,
+                path.display()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn handle_verify(files: &[PathBuf], elm_path: Option<&Path>) -> Result<()> {
+    let elm = match find_elm(elm_path) {
+        Some(elm) => elm,
+        None => {
+            print_elm_not_found(elm_path);
+            exit(1);
+        }
+    };
+
+    let mut failed = false;
+    for file in files {
+        if !file.exists() {
+            println!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+
+        println!("{} Compiling {}...", "🔍".blue(), file.display());
+        match verify_module(&elm, file)? {
+            VerifyOutcome::Compiles => println!("  {} {} compiles", "✓".green(), file.display()),
+            VerifyOutcome::Fails(report) => {
+                eprintln!("{}", report.trim_end());
+                println!("  {} {} doesn't compile", "✗".red(), file.display());
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+
+    Ok(())
+}
+
+/// After an edit with --verify, type-check what changed and undo the edit if it doesn't compile
+fn verify_edited_files(
+    watcher: &EditWatcher,
+    config_watcher: &EditWatcher,
+    elm_path: Option<&Path>,
+) -> Result<()> {
+    let changed: Vec<PathBuf> = watcher
+        .changed_files()
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "elm"))
+        .collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let elm = match find_elm(elm_path) {
+        Some(elm) => elm,
+        None => {
+            print_elm_not_found(elm_path);
+            println!("  {} The edit was kept without being verified", "ℹ".blue());
+            return Ok(());
+        }
+    };
+
+    for file in &changed {
+        if find_elm_project(file).is_none() {
+            eprintln!(
+                "{} No elm.json found above {}, so it can't be type-checked",
+                "⚠".yellow(),
+                file.display()
+            );
+            println!("  {} The edit was kept without being verified", "ℹ".blue());
+            continue;
+        }
+        println!("{} Compiling {}...", "🔍".blue(), file.display());
+        if let VerifyOutcome::Fails(report) = verify_module(&elm, file)? {
+            eprintln!("{}", report.trim_end());
+            for restored in watcher
+                .restore_changed()?
+                .into_iter()
+                .chain(config_watcher.restore_changed()?)
+            {
+                eprintln!("{} Restored {}", "↩".yellow(), restored.display());
+            }
+            errors::error(format!(
+                "{} doesn't compile after the edit, so it was undone",
+                file.display()
+            ));
+            exit(1);
+        }
+        println!("  {} {} compiles", "✓".green(), file.display());
+    }
+
+    Ok(())
+}
+
+/// Run each edited Elm file through elm-format, keeping the unformatted edit on failure
+fn format_edited_files(changed_files: &[PathBuf], elm_format_path: Option<&Path>) {
+    let files: Vec<&PathBuf> = changed_files
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "elm"))
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+
+    let elm_format = match find_executable("elm-format", elm_format_path) {
+        Some(elm_format) => elm_format,
+        None => {
+            match elm_format_path {
+                Some(path) => eprintln!(
+                    "{} No elm-format binary at {}",
+                    "⚠".yellow(),
+                    path.display()
+                ),
+                None => eprintln!(
+                    "{} elm-format isn't installed (or isn't on PATH), so the edit wasn't formatted",
+                    "⚠".yellow()
+                ),
+            }
+            eprintln!(
+                "  {} Install it with `npm install -g elm-format` or pass --elm-format-path",
+                "ℹ".blue()
+            );
+            return;
+        }
+    };
+
+    for file in files {
+        match format_file(&elm_format, file, FORMAT_TIMEOUT) {
+            Ok(true) => println!("{} Formatted {}", "🎨".blue(), file.display()),
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("{} {}", "⚠".yellow(), e);
+                eprintln!(
+                    "  {} {} was kept as written, without formatting",
+                    "ℹ".blue(),
+                    file.display()
+                );
+            }
+        }
+    }
+}
+
+fn print_elm_not_found(elm_path: Option<&Path>) {
+    match elm_path {
+        Some(path) => eprintln!("{} No elm binary at {}", "⚠".yellow(), path.display()),
+        None => eprintln!(
+            "{} elm isn't installed (or isn't on PATH), so the module can't be type-checked",
+            "⚠".yellow()
+        ),
+    }
+    eprintln!(
+        "  {} Install it from https://guide.elm-lang.org/install/elm.html or pass --elm-path",
+        "ℹ".blue()
+    );
+}
+
+/// Run the configured post-edit hook once for every file the command changed
+fn run_post_edit_hooks(hook: &str, changed_files: &[PathBuf], ignore_failure: bool) {
+    for file in changed_files {
+        println!(
+            "{} Running post-edit hook for {}...",
+            "🔧".blue(),
+            file.display()
+        );
+
+        if let Err(e) = run_post_edit_hook(hook, file) {
+            if ignore_failure {
+                println!("  {} {} (ignored)", "⚠".yellow(), e);
+                continue;
+            }
+            errors::error(e);
+            errors::hint(
+                "The edit was written; pass --ignore-hook-failure to treat this as a warning",
+            );
+            exit(1);
+        }
+    }
+}
+
+/// In single-file mode, when the configured file doesn't exist, use the only
+/// `I18n.elm` under the source directory instead
+///
+/// With none, commands report the configured file as missing; with several,
+/// there's no telling which is meant, so this lists them and exits.
+fn locate_missing_i18n_file(mut config: Config) -> Result<Config> {
+    if let Config::SingleFile {
+        file, source_dir, ..
+    } = &mut config
+    {
+        if file.exists() {
+            return Ok(config);
+        }
+        match find_i18n_files(source_dir)?.as_slice() {
+            [] => {}
+            [found] => {
+                errors::hint(format!(
+                    "{} doesn't exist; using {}, the only I18n.elm in {}",
+                    file.display(),
+                    found.display(),
+                    source_dir.display()
+                ));
+                errors::suggestion(format!(
+                    "Set \"file\" in {} to its path to skip the search",
+                    LOCAL_CONFIG_FILE
+                ));
+                *file = found.clone();
+            }
+            candidates => {
+                errors::error(format!(
+                    "{} doesn't exist, and {} has {} I18n.elm files:",
+                    file.display(),
+                    source_dir.display(),
+                    candidates.len()
+                ));
+                for candidate in candidates {
+                    errors::detail(format!("  {}", candidate.display()));
+                }
+                errors::suggestion(format!(
+                    "Set \"file\" in {} to the one to use, or pass --file",
+                    LOCAL_CONFIG_FILE
+                ));
+                exit(1);
+            }
+        }
+    }
+    Ok(config)
+}
+
+/// Determine which file to target based on config and shortcut
+fn determine_target_file(
+    config: &Config,
+    shortcut: &Option<String>,
+    command: &Commands,
+) -> Result<(PathBuf, String)> {
+    // For Init command, we might allow creation of new files
+    let is_init = matches!(command, Commands::Init { .. });
+    // These commands can work without a target (they process all files)
+    let is_remove_unused = matches!(command, Commands::RemoveUnused { .. });
+    let is_duplicates = matches!(command, Commands::DuplicateKeys { .. });
+    let is_shared_values = matches!(command, Commands::SharedValues { .. });
+    let is_add_language = matches!(command, Commands::AddLanguage { .. });
+    let is_lint = matches!(command, Commands::Lint { .. });
+    let is_format = matches!(
+        command,
+        Commands::Format { .. } | Commands::Canonicalize { .. }
+    );
+    let is_validate = matches!(command, Commands::Validate { .. });
+    let is_verify = matches!(command, Commands::Verify { .. });
+    let is_doctor = matches!(command, Commands::Doctor { .. });
+    let is_stats = matches!(command, Commands::Stats { .. });
+    let is_keys = matches!(command, Commands::Keys { .. });
+    let is_scan = matches!(
+        command,
+        Commands::Scan { .. } | Commands::Usages { .. } | Commands::Extract { .. }
+    );
+    let is_lock = matches!(command, Commands::Lock | Commands::VerifyLock);
+    let is_glob = matches!(command, Commands::List { glob: Some(_), .. });
+
+    match config {
+        Config::SingleFile {
+            file, record_name, ..
+        } => {
+            if shortcut.is_some() {
+                eprintln!(
+                    "{} Warning: File shortcuts are ignored in single-file mode",
+                    "⚠".yellow()
+                );
+            }
+            Ok((file.clone(), record_name.clone()))
+        }
+        Config::MultiFile { files, .. } => {
+            match shortcut {
+                Some(s) => match files.get(s) {
+                    Some(file_config) => {
+                        Ok((file_config.path.clone(), file_config.record_name.clone()))
+                    }
+                    None => {
+                        errors::error_with(
+                            Code::InvalidArgument,
+                            format!("Unknown file shortcut: {}", s.yellow()),
+                        );
+                        config.print_shortcuts();
+                        exit(1);
+                    }
+                },
+                None => {
+                    // Some commands can run without a target - they process all files
+                    if is_remove_unused
+                        || is_duplicates
+                        || is_shared_values
+                        || is_add_language
+                        || is_lint
+                        || is_format
+                        || is_validate
+                        || is_verify
+                        || is_doctor
+                        || is_stats
+                        || is_keys
+                        || is_scan
+                        || is_lock
+                        || is_glob
+                    {
+                        // Return dummy values - the command handler will iterate all files
+                        Ok((PathBuf::from(""), String::new()))
+                    } else if !is_init {
+                        config.print_shortcuts();
+                        exit(1);
+                    } else {
+                        // For init, we might allow specifying a new file
+                        errors::error_with(
+                            Code::Config,
+                            "Multi-file mode requires a file shortcut",
+                        );
+                        config.print_shortcuts();
+                        exit(1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Handle the setup-claude command
+fn handle_setup_claude() -> Result<()> {
+    use std::fs;
+
+    println!(
+        "{} Setting up CLAUDE.md with elm-i18n instructions...",
+        "🤖".blue()
+    );
+    println!();
+
+    // Load configuration to understand project setup
+    let config = match Config::load()? {
+        Some(config) => config,
+        None => {
+            errors::error_with(
+                Code::Config,
+                format!("No elm-i18n configuration found at {}!", config_file_path()),
+            );
+            errors::detail(format!(
+                "Run {} first to create a configuration.",
+                "elm-i18n setup".green()
+            ));
+            exit(1);
+        }
+    };
+
+    // Check if CLAUDE.md already exists
+    let claude_path = PathBuf::from("CLAUDE.md");
+    let existing_content = if claude_path.exists() {
+        fs::read_to_string(&claude_path).ok()
+    } else {
+        None
+    };
+
+    // Generate elm-i18n specific instructions
+    let elm_i18n_section = generate_claude_instructions(&config);
+
+    // Track if we're updating or creating
+    let is_update = existing_content.is_some();
+
+    // Merge or create CLAUDE.md
+    let final_content = if let Some(existing) = existing_content {
+        // Check if elm-i18n section already exists
+        if existing.contains("## elm-i18n Configuration") {
+            // Replace existing elm-i18n section
+            let before_section = existing
+                .split("## elm-i18n Configuration")
+                .next()
+                .unwrap_or("");
+            let after_section = existing
+                .split("## elm-i18n Configuration")
+                .nth(1)
+                .and_then(|s| s.split("\n## ").nth(1))
+                .map(|s| format!("\n## {}", s))
+                .unwrap_or_default();
+
+            format!("{}{}{}", before_section, elm_i18n_section, after_section)
+        } else {
+            // Append elm-i18n section
+            format!("{}\n\n{}", existing.trim(), elm_i18n_section)
+        }
+    } else {
+        // Create new CLAUDE.md with elm-i18n instructions
+        format!(
+            "# Project-Specific Instructions for Claude\n\n{}",
+            elm_i18n_section
+        )
+    };
+
+    // Write the file
+    fs::write(&claude_path, final_content)?;
+
+    println!(
+        "{} CLAUDE.md has been {}",
+        "✓".green(),
+        if is_update { "updated" } else { "created" }
+    );
+
+    println!();
+    println!("The file contains:");
+    println!("  • elm-i18n configuration details");
+    println!("  • Available translation files and shortcuts");
+    println!("  • Example commands for your specific setup");
+    println!();
+    println!("Claude will use these instructions to help with translations.");
+
+    Ok(())
+}
+
+fn generate_claude_instructions(config: &Config) -> String {
+    let mut instructions = String::from("## elm-i18n Configuration\n\n");
+    instructions.push_str("This project uses elm-i18n for managing translations. ");
+
+    match config {
+        Config::SingleFile {
+            file,
+            record_name,
+            languages,
+            ..
         } => {
-            let actual_src_dir = if src_dir.to_str() == Some("src") {
-                config.source_dir().clone()
+            instructions.push_str("It's configured in **single-file mode**.\n\n");
+            instructions.push_str("### Configuration Details\n\n");
+            instructions.push_str(&format!("- **Translation file**: `{}`\n", file.display()));
+            instructions.push_str(&format!("- **Record type**: `{}`\n", record_name));
+            instructions.push_str(&format!("- **Languages**: {}\n", languages.join(", ")));
+            instructions.push_str("\n### Usage Examples\n\n");
+            instructions.push_str("```bash\n");
+            instructions.push_str("# Add a simple translation\n");
+            instructions.push_str("elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"\n\n");
+            instructions.push_str("# Add a function translation\n");
+            instructions.push_str("elm-i18n add-fn itemCount \\\n");
+            instructions.push_str("  --type-sig \"Int -> String\" \\\n");
+            instructions.push_str("  -t en=\"\\n -> if n == 1 then \\\"1 item\\\" else String.fromInt n ++ \\\" items\\\"\" \\\n");
+            instructions.push_str("  -t fr=\"\\n -> if n == 1 then \\\"1 élément\\\" else String.fromInt n ++ \\\" éléments\\\"\"\n\n");
+            instructions.push_str("# Check if a key exists\n");
+            instructions.push_str("elm-i18n check myKey\n\n");
+            instructions.push_str("# List all translations\n");
+            instructions.push_str("elm-i18n list\n\n");
+            instructions.push_str("# Remove a translation\n");
+            instructions.push_str("elm-i18n remove myKey\n");
+            instructions.push_str("```\n");
+        }
+        Config::MultiFile {
+            files, languages, ..
+        } => {
+            instructions.push_str(&format!(
+                "It's configured in **multi-file mode** with {} translation files.\n\n",
+                files.len()
+            ));
+            instructions.push_str("### Configuration Details\n\n");
+            instructions.push_str(&format!("- **Languages**: {}\n", languages.join(", ")));
+            instructions.push_str("- **Translation files**:\n");
+
+            for (shortcut, file_config) in files {
+                instructions.push_str(&format!(
+                    "  - `--target {}` → `{}` (Record: `{}`)\n",
+                    shortcut,
+                    file_config.path.display(),
+                    file_config.record_name
+                ));
+            }
+
+            instructions.push_str("\n### Usage Examples\n\n");
+            instructions.push_str("```bash\n");
+
+            if let Some((first_shortcut, _)) = files.iter().next() {
+                instructions.push_str(&format!(
+                    "# Add a translation to the {} file\n",
+                    first_shortcut
+                ));
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"\n\n",
+                    first_shortcut
+                ));
+
+                instructions.push_str(&format!(
+                    "# Add a function translation to the {} file\n",
+                    first_shortcut
+                ));
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} add-fn itemCount \\\n",
+                    first_shortcut
+                ));
+                instructions.push_str("  --type-sig \"Int -> String\" \\\n");
+                instructions.push_str("  -t en=\"\\n -> if n == 1 then \\\"1 item\\\" else String.fromInt n ++ \\\" items\\\"\" \\\n");
+                instructions.push_str("  -t fr=\"\\n -> if n == 1 then \\\"1 élément\\\" else String.fromInt n ++ \\\" éléments\\\"\"\n\n");
+
+                instructions.push_str(&format!(
+                    "# Check if a key exists in the {} file\n",
+                    first_shortcut
+                ));
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} check myKey\n\n",
+                    first_shortcut
+                ));
+
+                instructions.push_str(&format!(
+                    "# List all translations in the {} file\n",
+                    first_shortcut
+                ));
+                instructions.push_str(&format!("elm-i18n --target {} list\n\n", first_shortcut));
+
+                instructions.push_str(&format!(
+                    "# Remove a translation from the {} file\n",
+                    first_shortcut
+                ));
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} remove myKey\n",
+                    first_shortcut
+                ));
+            }
+
+            instructions.push_str("```\n");
+
+            instructions.push_str("\n### Important Notes\n\n");
+            instructions.push_str(
+                "- **Always specify `--target <shortcut>`** when working with translations\n",
+            );
+            instructions.push_str("- Each file has its own record type and translation set\n");
+            instructions.push_str("- Use `elm-i18n status` to see all available shortcuts\n");
+        }
+    }
+
+    instructions.push_str("\n### Additional Commands\n\n");
+    instructions.push_str("```bash\n");
+    instructions.push_str("# Show current configuration\n");
+    instructions.push_str("elm-i18n status\n\n");
+    instructions.push_str("# Find and remove unused translations\n");
+    if config.is_multi_file() {
+        if let Config::MultiFile { files, .. } = config {
+            if let Some((shortcut, _)) = files.iter().next() {
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} remove-unused --confirm\n\n",
+                    shortcut
+                ));
+            }
+        }
+    } else {
+        instructions.push_str("elm-i18n remove-unused --confirm\n\n");
+    }
+    instructions.push_str("# Add translation and replace hardcoded strings\n");
+    if config.is_multi_file() {
+        if let Config::MultiFile { files, .. } = config {
+            if let Some((shortcut, _)) = files.iter().next() {
+                instructions.push_str(&format!(
+                    "elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\" --replace\n",
+                    shortcut
+                ));
+            }
+        }
+    } else {
+        instructions.push_str("elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\" --replace\n");
+    }
+    instructions.push_str("```\n");
+
+    instructions.push_str("\n### Key Naming Conventions\n\n");
+    instructions.push_str("- Use camelCase for keys (e.g., `welcomeMessage`, `userProfile`)\n");
+    instructions.push_str("- Keys cannot contain dots (.) as they're reserved for access syntax\n");
+    instructions.push_str("- Elm reserved words will automatically get an underscore suffix\n");
+
+    instructions
+}
+
+/// Handle the status command
+fn handle_status() -> Result<()> {
+    println!("{} Configuration Status", "🔧".blue());
+    println!();
+
+    match Config::load()? {
+        Some(config) => match &config {
+            Config::SingleFile {
+                file,
+                record_name,
+                languages,
+                source_dir,
+                ..
+            } => {
+                println!("Mode: {}", "Single-file".green());
+                println!("File: {}", file.display());
+                println!("Record Type: {}", record_name.yellow());
+                println!("Languages: {}", languages.join(", "));
+                println!("Source Directory: {}", source_dir.display());
+                println!();
+                println!("Usage example:");
+                println!("  elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"");
+            }
+            Config::MultiFile {
+                files,
+                languages,
+                source_dir,
+                ..
+            } => {
+                println!("Mode: {}", "Multi-file".green());
+                println!("Languages: {}", languages.join(", "));
+                println!("Source Directory: {}", source_dir.display());
+                println!();
+                println!("Available shortcuts:");
+
+                let shortcuts = config.get_shortcuts();
+                for (shortcut, path) in &shortcuts {
+                    if let Some(file_config) = files.get(shortcut) {
+                        println!(
+                            "  {} → {}",
+                            format!("--target {}", shortcut).yellow(),
+                            path.display()
+                        );
+                        println!("       Record Type: {}", file_config.record_name.cyan());
+                    }
+                }
+
+                println!();
+                println!("Usage example:");
+                if let Some((shortcut, _)) = shortcuts.first() {
+                    println!(
+                        "  elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"",
+                        shortcut
+                    );
+                }
+            }
+        },
+        None => {
+            println!("{} No configuration found!", "⚠".yellow());
+            println!();
+            println!(
+                "Run {} to create a configuration file.",
+                "elm-i18n setup".green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the version command
+fn handle_version() -> Result<()> {
+    println!("elm-i18n v{}", env!("CARGO_PKG_VERSION"));
+    println!("CLI tool for managing Elm I18n translations");
+    println!();
+    println!("New in v0.5.0:");
+    println!("  • Configuration file support ({})", config_file_path());
+    println!("  • Multi-file translation management");
+    println!("  • Custom shortcuts for quick file access");
+    println!("  • Run 'elm-i18n setup' to create configuration");
+    println!();
+    println!("New in v0.4.0:");
+    println!("  • Added 'list' command to view all translations");
+    println!("  • Support for --verbose to see full translation values");
+    println!("  • Filter translations with --filter option");
+    Ok(())
+}
+
+/// Handle the setup command
+fn handle_setup() -> Result<()> {
+    if config_exists() {
+        errors::error_with(
+            Code::Config,
+            format!("Configuration file already exists: {}", config_file_path()),
+        );
+        errors::detail("Delete it first if you want to reconfigure.");
+        exit(1);
+    }
+
+    println!("{} Welcome to elm-i18n setup!", "🎉".blue());
+    println!();
+    println!(
+        "This will create a {} configuration file.",
+        config_file_path()
+    );
+    println!();
+
+    // Ask for mode
+    println!("Choose translation mode:");
+    println!("  1) Single-file mode (one I18n.elm file)");
+    println!("  2) Multi-file mode (separate files for different parts)");
+    print!("\nSelect mode [1-2]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let mode_choice = input.trim();
+
+    let config = if mode_choice == "2" {
+        setup_multi_file_config()?
+    } else {
+        setup_single_file_config()?
+    };
+
+    config.save()?;
+
+    println!();
+    println!(
+        "{} Created {} configuration file",
+        "✓".green(),
+        config_file_path()
+    );
+
+    if config.is_multi_file() {
+        println!();
+        println!("Available shortcuts:");
+        for (shortcut, path) in config.get_shortcuts() {
+            println!(
+                "  {} → {}",
+                format!("--{}", shortcut).yellow(),
+                path.display()
+            );
+        }
+        println!();
+        println!("Example usage:");
+        if let Some((shortcut, _)) = config.get_shortcuts().first() {
+            println!(
+                "  elm-i18n --{} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"",
+                shortcut
+            );
+        }
+    } else {
+        println!();
+        println!("Example usage:");
+        println!("  elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"");
+    }
+
+    Ok(())
+}
+
+/// Setup single-file configuration
+fn setup_single_file_config() -> Result<Config> {
+    // Offer the project's only I18n.elm when it isn't at the usual place
+    let default_file = match find_i18n_files(Path::new("src"))?.as_slice() {
+        [found] if !Path::new("src/I18n.elm").exists() => found.clone(),
+        _ => PathBuf::from("src/I18n.elm"),
+    };
+
+    println!();
+    print!("Path to I18n.elm file [{}]: ", default_file.display());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let file_path = if input.trim().is_empty() {
+        default_file
+    } else {
+        PathBuf::from(input.trim())
+    };
+
+    print!("Record name [Translations]: ");
+    io::stdout().flush()?;
+
+    input.clear();
+    io::stdin().read_line(&mut input)?;
+    let record_name = if input.trim().is_empty() {
+        "Translations".to_string()
+    } else {
+        input.trim().to_string()
+    };
+
+    print!("Source directory [src]: ");
+    io::stdout().flush()?;
+
+    input.clear();
+    io::stdin().read_line(&mut input)?;
+    let source_dir = if input.trim().is_empty() {
+        PathBuf::from("src")
+    } else {
+        PathBuf::from(input.trim())
+    };
+
+    print!("Languages (comma-separated) [en,fr]: ");
+    io::stdout().flush()?;
+
+    input.clear();
+    io::stdin().read_line(&mut input)?;
+    let languages = if input.trim().is_empty() {
+        vec!["en".to_string(), "fr".to_string()]
+    } else {
+        normalize_languages(input.trim().split(','))
+    };
+
+    Ok(Config::SingleFile {
+        elm_i18n_version: env!("CARGO_PKG_VERSION").to_string(),
+        languages,
+        source_dir,
+        file: file_path,
+        record_name,
+        lint: LintConfig::default(),
+        scan: ScanConfig::default(),
+        ci: CiConfig::default(),
+        changelog: ChangelogConfig::default(),
+        post_edit_hook: None,
+        elm_format: false,
+        indent: None,
+        backup_suffix: None,
+        key_style: KeyStyle::Flat,
+        placeholder_style: None,
+    })
+}
+
+/// Setup multi-file configuration
+fn setup_multi_file_config() -> Result<Config> {
+    use std::collections::HashMap;
+
+    println!();
+    print!("Source directory [src]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let source_dir = if input.trim().is_empty() {
+        PathBuf::from("src")
+    } else {
+        PathBuf::from(input.trim())
+    };
+
+    print!("Languages (comma-separated) [en,fr]: ");
+    io::stdout().flush()?;
+
+    input.clear();
+    io::stdin().read_line(&mut input)?;
+    let languages = if input.trim().is_empty() {
+        vec!["en".to_string(), "fr".to_string()]
+    } else {
+        normalize_languages(input.trim().split(','))
+    };
+
+    let mut files = HashMap::new();
+
+    println!();
+    println!("Now let's configure your translation files.");
+    println!("Enter shortcuts and file paths (empty shortcut to finish):");
+
+    loop {
+        println!();
+        print!("Shortcut (e.g., 'app', 'landing', 'admin'): ");
+        io::stdout().flush()?;
+
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        let shortcut = input.trim().to_string();
+
+        if shortcut.is_empty() {
+            if files.is_empty() {
+                println!("{} At least one file must be configured", "⚠".yellow());
+                continue;
+            }
+            break;
+        }
+
+        print!("File path (e.g., 'src/I18n/App.elm'): ");
+        io::stdout().flush()?;
+
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        let path = PathBuf::from(input.trim());
+
+        print!("Record name (e.g., 'AppTranslations'): ");
+        io::stdout().flush()?;
+
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        let record_name = input.trim().to_string();
+
+        files.insert(shortcut.clone(), FileConfig { path, record_name });
+
+        println!("{} Added: --{}", "✓".green(), shortcut);
+    }
+
+    Ok(Config::MultiFile {
+        elm_i18n_version: env!("CARGO_PKG_VERSION").to_string(),
+        languages,
+        source_dir,
+        files,
+        lint: LintConfig::default(),
+        scan: ScanConfig::default(),
+        ci: CiConfig::default(),
+        changelog: ChangelogConfig::default(),
+        post_edit_hook: None,
+        elm_format: false,
+        indent: None,
+        backup_suffix: None,
+        key_style: KeyStyle::Flat,
+        placeholder_style: None,
+    })
+}
+
+fn handle_add(
+    file: &Path,
+    key: &str,
+    values: &std::collections::HashMap<String, String>,
+    is_function: bool,
+    type_sig: Option<String>,
+    replace: bool,
+    src_dir: &Path,
+    placement: Option<&Placement>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    // Check if file exists
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        errors::hint("Run 'elm-i18n init' to create a new I18n.elm file");
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let source = read_elm_file(file)?;
+    if let Some(nested::Entry::Group(keys)) = nested::lookup(&source, key, record_name, languages)?
+    {
+        errors::error_with(
+            Code::AlreadyExists,
+            format!(
+                "'{}' is a group of nested keys ({}), so it can't also be a translation",
+                key,
+                keys.join(", ")
+            ),
+        );
+        errors::hint(format!(
+            "Add a key inside it instead, like '{}.newKey', with --nested",
+            key
+        ));
+        exit(1);
+    }
+    for (dot, _) in key.match_indices('.') {
+        let path = &key[..dot];
+        if let Some(nested::Entry::Translation(_)) =
+            nested::lookup(&source, path, record_name, languages)?
+        {
+            errors::error_with(
+                Code::AlreadyExists,
+                format!(
+                    "'{}' is a translation, so it can't hold nested keys like '{}'",
+                    path, key
+                ),
+            );
+            errors::hint(format!(
+                "Use another key, or rename '{}' first to make room for the group",
+                path
+            ));
+            exit(1);
+        }
+    }
+
+    // Check if key already exists
+    match check_key_exists_with_record_name(file, key, record_name, languages)? {
+        Some(existing) => {
+            println!(
+                "{} Translation '{}' already exists:",
+                "ℹ".blue(),
+                key.yellow()
+            );
+            for lang in languages {
+                if let Some(val) = existing.values.get(lang) {
+                    println!("  {}: {}", lang.to_uppercase().green(), val);
+                }
+            }
+            println!();
+            println!(
+                "The existing translations might be sufficient. Consider using a different key."
+            );
+        }
+        None => {
+            // Hint at keys people may have missed that already say the same thing
+            if let (false, None, Some(source_lang)) = (is_function, &type_sig, languages.first()) {
+                if let Some(value) = values.get(source_lang) {
+                    let parse_result =
+                        parse_i18n_file_with_record_name(file, record_name, languages)?;
+                    for existing in keys_with_identical_text(&parse_result, source_lang, value) {
+                        println!(
+                            "{} Identical text exists under key '{}'",
+                            "ℹ".blue(),
+                            existing.yellow()
+                        );
+                    }
+                }
+            }
+
+            // Add the translation
+            let translation = Translation {
+                key: key.to_string(),
+                values: values.clone(),
+                is_function,
+                type_signature: type_sig,
+            };
+
+            add_translation_with_record_name(
+                file,
+                &translation,
+                placement,
+                record_name,
+                languages,
+            )?;
+
+            println!(
+                "{} Added translation '{}' to {}",
+                "✓".green(),
+                key.yellow(),
+                file.display()
+            );
+
+            if !is_function {
+                for lang in languages {
+                    if let Some(val) = values.get(lang) {
+                        println!("  {}: {}", lang.to_uppercase().green(), val);
+                    }
+                }
+                if let Some(default_language) = languages
+                    .first()
+                    .filter(|lang| values.get(*lang).is_some_and(|v| v.trim().is_empty()))
+                {
+                    println!(
+                        "{} {}",
+                        "⚠".yellow(),
+                        format!(
+                            "The {} value is empty; it's the source the other languages are translated from",
+                            default_language.to_uppercase()
+                        )
+                        .yellow()
+                        .bold()
+                    );
+                    println!(
+                        "  {} Fill it in with: elm-i18n modify {} -t {}=\"...\"",
+                        "→".cyan(),
+                        key,
+                        default_language
+                    );
+                }
+            }
+
+            // Handle string replacement if requested
+            if replace && !is_function {
+                println!();
+                println!(
+                    "{} Searching for hardcoded strings to replace...",
+                    "🔍".blue()
+                );
+
+                let search_strings: Vec<&str> = values.values().map(|s| s.as_str()).collect();
+                let matches = find_string_occurrences(src_dir, &search_strings)?;
+
+                if matches.is_empty() {
+                    println!("{} No hardcoded strings found to replace", "ℹ".blue());
+                } else {
+                    // Show what will be replaced for each language
+                    for (lang, value) in values {
+                        let lang_matches: Vec<_> = matches
+                            .iter()
+                            .filter(|m| m.line_content.contains(&format!(r#""{}""#, value)))
+                            .collect();
+
+                        if !lang_matches.is_empty() {
+                            println!();
+                            println!(
+                                "{} Found {} occurrences of \"{}\" ({}):",
+                                "✓".green(),
+                                lang_matches.len(),
+                                value,
+                                lang.to_uppercase()
+                            );
+                            for mat in lang_matches.iter().take(3) {
+                                println!("  {}:{}:", mat.file_path.display(), mat.line_number);
+                                println!("    {}", mat.line_content.trim());
+                            }
+                            if lang_matches.len() > 3 {
+                                println!("  ... and {} more", lang_matches.len() - 3);
+                            }
+                        }
+                    }
+
+                    // Perform replacements
+                    println!();
+                    println!("{} Replacing strings with t.{}...", "🔄".blue(), key);
+                    replace_strings(&matches, key, "I18n")?;
+
+                    println!(
+                        "{} Replaced {} occurrences across {} file(s)",
+                        "✓".green(),
+                        matches.len(),
+                        {
+                            let unique_files: std::collections::HashSet<_> =
+                                matches.iter().map(|m| &m.file_path).collect();
+                            unique_files.len()
+                        }
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The configured languages named in `inputs`, exiting on one that isn't configured
+fn requested_languages(inputs: &[String], file: &Path, languages: &[String]) -> Vec<String> {
+    let mut requested = Vec::new();
+    for input in inputs.iter().filter(|input| !input.trim().is_empty()) {
+        let lang = configured_language(input, languages);
+        if !languages.contains(&lang) {
+            errors::error_with(
+                Code::UnknownLanguage,
+                format!(
+                    "Language '{}' isn't in {} (languages: {})",
+                    lang,
+                    file.display(),
+                    languages.join(", ")
+                ),
+            );
+            exit(1);
+        }
+        if !requested.contains(&lang) {
+            requested.push(lang);
+        }
+    }
+    requested
+}
+
+/// Handle `check --complete`: a table of each key's status in each counted language
+fn handle_check_complete(
+    file: &Path,
+    keys: &[String],
+    counted: &[String],
+    todo_marker: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+    let key_width = keys.iter().map(|k| k.len()).max().unwrap_or(0).max(3);
+    let column_width = 10;
+
+    let header: String = counted
+        .iter()
+        .map(|lang| format!("{:column_width$}", lang.to_uppercase()))
+        .collect();
+    println!(
+        "  {:key_width$}  {}",
+        "Key".bold(),
+        header.trim_end().bold()
+    );
+
+    let mut incomplete = 0;
+    let mut unknown = Vec::new();
+    for key in keys {
+        let nested_translation = if key.contains('.') {
+            check_key_exists_with_record_name(file, key, record_name, languages)?
+        } else {
+            None
+        };
+        let translation = nested_translation
+            .as_ref()
+            .or_else(|| parse_result.translations.get(key));
+        if translation.is_none() {
+            unknown.push(key);
+        }
+        let mut row = format!("  {:key_width$}  ", key.yellow());
+        for lang in counted {
+            let status = value_status(translation.and_then(|t| t.values.get(lang)), todo_marker);
+            let cell = format!("{:column_width$}", status.label());
+            let cell = match status {
+                ValueStatus::Translated => cell.green(),
+                _ => {
+                    incomplete += 1;
+                    cell.red()
+                }
+            };
+            row.push_str(&cell.to_string());
+        }
+        println!("{}", row.trim_end());
+    }
+    println!();
+
+    for key in &unknown {
+        println!(
+            "{} '{}' isn't in the {} type",
+            "ℹ".blue(),
+            key.yellow(),
+            record_name
+        );
+    }
+
+    let languages_label = counted
+        .iter()
+        .map(|lang| lang.to_uppercase())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if incomplete > 0 {
+        println!(
+            "{} {} of {} values aren't translated in {}",
+            "✗".red(),
+            incomplete,
+            keys.len() * counted.len(),
+            languages_label
+        );
+        exit(1);
+    }
+
+    println!(
+        "{} {} translated in {}",
+        "✓".green(),
+        if keys.len() == 1 {
+            format!("'{}' is", keys[0])
+        } else {
+            format!("All {} keys are", keys.len())
+        },
+        languages_label
+    );
+    Ok(())
+}
+
+/// A value as `check` prints it: a plain string as its text, anything else as Elm code
+fn value_text(value: &str) -> String {
+    plain_string_value(value).unwrap_or_else(|| value.to_string())
+}
+
+/// What `check --json` prints for `key`: its value in `language`, or in every language
+fn check_json(
+    key: &str,
+    translation: Option<&Translation>,
+    language: Option<&str>,
+    languages: &[String],
+) -> serde_json::Value {
+    let json_value = |value: &String| serde_json::Value::String(value_text(value));
+    match (translation, language) {
+        (None, _) => serde_json::json!({ "key": key, "found": false }),
+        (Some(translation), Some(lang)) => serde_json::json!({
+            "key": key,
+            "language": lang,
+            "value": translation.values.get(lang).map(json_value),
+        }),
+        (Some(translation), None) => {
+            let values: serde_json::Map<String, serde_json::Value> = languages
+                .iter()
+                .filter_map(|lang| {
+                    let value = translation.values.get(lang)?;
+                    Some((lang.clone(), json_value(value)))
+                })
+                .collect();
+            serde_json::json!({ "key": key, "values": values })
+        }
+    }
+}
+
+fn handle_check(
+    file: &Path,
+    key: &str,
+    language: Option<&str>,
+    json: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+    let language = language.map(str::to_string);
+
+    let source = read_elm_file(file)?;
+    if let Some(nested::Entry::Group(keys)) = nested::lookup(&source, key, record_name, languages)?
+    {
+        if json {
+            println!(
+                "{}",
+                serde_json::json!({ "key": key, "found": true, "group": keys })
+            );
+        } else {
+            println!(
+                "{} '{}' is a group of {} nested key{}:",
+                "✓".green(),
+                key.yellow(),
+                keys.len(),
+                if keys.len() == 1 { "" } else { "s" }
+            );
+            for nested_key in &keys {
+                println!("  {}", nested_key);
+            }
+        }
+        return Ok(());
+    }
+
+    let translation = check_key_exists_with_record_name(file, key, record_name, languages)?;
+
+    if json {
+        println!(
+            "{}",
+            check_json(key, translation.as_ref(), language.as_deref(), languages)
+        );
+        if translation.is_none() {
+            exit(1);
+        }
+        return Ok(());
+    }
+
+    if translation.is_none() && language.is_some() {
+        errors::error_with(
+            Code::KeyNotFound,
+            format!("Translation '{}' not found", key.yellow()),
+        );
+        exit(1);
+    }
+    if let (Some(translation), Some(lang)) = (&translation, &language) {
+        match translation.values.get(lang) {
+            Some(value) => println!("{}", value_text(value)),
+            None => {
+                errors::error(format!(
+                    "Translation '{}' has no {} value",
+                    key.yellow(),
+                    lang.to_uppercase()
+                ));
+                exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    match translation {
+        Some(translation) => {
+            println!("{} Translation '{}' exists:", "✓".green(), key.yellow());
+            for lang in languages {
+                let Some(val) = translation.values.get(lang) else {
+                    continue;
+                };
+                // A plural reads better as what each of its forms says
+                let forms = translation
+                    .type_signature
+                    .as_deref()
+                    .and_then(|signature| plural::plural_forms(signature, val, lang));
+                match forms {
+                    Some(forms) => {
+                        println!("  {}:", lang.to_uppercase().green());
+                        let width = forms.iter().map(|(form, _)| form.len()).max().unwrap_or(0);
+                        for (form, text) in forms {
+                            println!("    {:width$}  {}", form.dimmed(), text);
+                        }
+                    }
+                    None => println!("  {}: {}", lang.to_uppercase().green(), val),
+                }
+            }
+
+            if let Some(type_sig) = translation.type_signature {
+                println!("  {}: {}", "Type".cyan(), type_sig);
+            }
+        }
+        None => {
+            println!("{} Translation '{}' not found", "✗".red(), key.yellow());
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the scaffold command: add the keys listed in a manifest with empty values
+///
+/// `lint_config` is `None` with `--no-validate`, which skips the naming rules.
+fn handle_scaffold(
+    file: &Path,
+    manifest: &Path,
+    lint_config: Option<&LintConfig>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        errors::hint("Run 'elm-i18n init' to create a new I18n.elm file");
+        exit(1);
+    }
+    if !manifest.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("Key list not found: {}", manifest.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let listed = std::fs::read_to_string(manifest)
+        .with_context(|| format!("Failed to read {}", manifest.display()))?;
+    let key_regex = regex::Regex::new(r"^[A-Za-z]\w*$")?;
+    let mut keys: Vec<String> = Vec::new();
+    let mut invalid = 0;
+    for (i, line) in listed.lines().enumerate() {
+        let key = line.trim().trim_start_matches('\u{feff}');
+        if key.is_empty() || key.starts_with('#') {
+            continue;
+        }
+        if !key_regex.is_match(key) {
+            Coded::new(
+                Code::InvalidArgument,
+                format!(
+                    "{} line {}: '{}' isn't a valid key (letters, numbers and underscores, starting with a letter)",
+                    manifest.display(),
+                    i + 1,
+                    key
+                ),
+            )
+            .key(key)
+            .line(i + 1)
+            .file(manifest.display())
+            .report();
+            invalid += 1;
+            continue;
+        }
+        let key = validate_and_clean_key(key)?;
+        if let Some(lint_config) = lint_config {
+            check_key_naming(&key, lint_config)?;
+        }
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    if invalid > 0 {
+        exit(1);
+    }
+
+    let content = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let (existing, new_keys): (Vec<String>, Vec<String>) = keys
+        .into_iter()
+        .partition(|key| parse_result.translations.contains_key(key));
+    if let Some(lint_config) = lint_config {
+        check_key_count(&parse_result, &new_keys, lint_config);
+    }
+
+    let translations: Vec<Translation> = new_keys
+        .iter()
+        .map(|key| Translation {
+            key: key.clone(),
+            values: languages
+                .iter()
+                .map(|lang| (lang.clone(), String::new()))
+                .collect(),
+            is_function: false,
+            type_signature: None,
+        })
+        .collect();
+    let new_content = add_translations_to_content(&content, &translations, record_name, languages)?;
+
+    if !existing.is_empty() {
+        println!(
+            "{} Skipped {} existing key{}: {}",
+            "ℹ".blue(),
+            existing.len(),
+            if existing.len() == 1 { "" } else { "s" },
+            existing.join(", ")
+        );
+    }
+    let summary = Summary {
+        added: new_keys.len(),
+        skipped: existing.len(),
+        ..Summary::default()
+    };
+    if new_keys.is_empty() {
+        println!("{} No new keys to add to {}", "ℹ".blue(), file.display());
+        summary.print();
+        return Ok(());
+    }
+
+    backup::back_up(file)?;
+    timing::write(file, &new_content)
+        .with_context(|| format!("Failed to write to {}", file.display()))?;
+
+    println!(
+        "{} Added {} key{} with empty values to {}:",
+        "✓".green(),
+        new_keys.len(),
+        if new_keys.len() == 1 { "" } else { "s" },
+        file.display()
+    );
+    for key in &new_keys {
+        println!("  {} {}", "+".green(), key.yellow());
+    }
+    println!(
+        "{} Run 'elm-i18n validate' to list the values still to fill in",
+        "ℹ".blue()
+    );
+    summary.print();
+
+    Ok(())
+}
+
+fn handle_init(
+    file: &Path,
+    languages: &str,
+    record_name: &str,
+    template: Option<&Path>,
+) -> Result<()> {
+    if file.exists() {
+        errors::error_with(
+            Code::AlreadyExists,
+            format!("File already exists: {}", file.display()),
+        );
+        errors::detail("Remove it first if you want to reinitialize.");
+        exit(1);
+    }
+
+    let langs = normalize_languages(languages.split(','));
+
+    let template = match template {
+        Some(path) => {
+            let template = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read template {}", path.display()))?;
+            let rendered = match render_template(&template, &langs, record_name) {
+                Ok(rendered) => rendered,
+                Err(e) => {
+                    errors::error(format!("{}: {}", path.display(), e));
+                    exit(1);
+                }
+            };
+            // Every other command would refuse the file, so don't write it
+            if let Err(e) = parse_i18n_content_with_record_name(&rendered, record_name, &langs) {
+                errors::error_with(
+                    Code::ParseError,
+                    format!(
+                        "The module {} produces can't be read by elm-i18n: {}",
+                        path.display(),
+                        e
+                    ),
+                );
+                errors::suggestion(format!("It needs a `type alias {}` and a record per language; see the built-in template (`elm-i18n init` without --template)", record_name));
+                exit(1);
+            }
+            rendered
+        }
+        None => get_i18n_template_with_record_name(&langs, record_name),
+    };
+    create_i18n_file(file, &template)?;
+
+    println!(
+        "{} Created {} with basic structure",
+        "✓".green(),
+        file.display()
+    );
+    println!("Languages: {}", langs.join(", "));
+
+    Ok(())
+}
+
+/// Add the backup patterns to an existing .gitignore, so backups aren't committed by
+/// accident: with `--gitignore`, or when asked at a terminal
+fn offer_gitignore(without_asking: bool) -> Result<()> {
+    let path = Path::new(".gitignore");
+    if !path.exists() {
+        return Ok(());
+    }
+    let content = std::fs::read_to_string(path)?;
+    let missing = backup::missing_gitignore_patterns(&content);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let patterns = missing.join(" ");
+    if !without_asking {
+        if !io::stdin().is_terminal() {
+            println!(
+                "{} Pass --gitignore to add {} to .gitignore",
+                "ℹ".blue(),
+                patterns
+            );
+            return Ok(());
+        }
+        print!(
+            "Add {} to .gitignore so backups aren't committed? [y/N] ",
+            patterns
+        );
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            return Ok(());
+        }
+    }
+
+    let mut updated = content;
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str("\n# elm-i18n backups\n");
+    for pattern in &missing {
+        updated.push_str(pattern);
+        updated.push('\n');
+    }
+    timing::write(path, updated).context("Failed to write .gitignore")?;
+    println!("{} Added {} to .gitignore", "✓".green(), patterns);
+    Ok(())
+}
+
+/// Remove `keys`, returning the status to exit with: 1 when any is missing, or still
+/// read in `sources` without `force`
+fn handle_remove(
+    file: &Path,
+    keys: &[String],
+    sources: Option<&SourceSet>,
+    force: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<i32> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let mut kept = 0;
+    for (i, key) in keys.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        // Check if key exists first
+        let Some(translation) =
+            check_key_exists_with_record_name(file, key, record_name, languages)?
+        else {
+            println!("{} Translation '{}' not found", "✗".red(), key.yellow());
+            kept += 1;
+            continue;
+        };
+
+        if let Some(sources) = sources {
+            let found = sources.usages([key]).remove(key).unwrap_or_default();
+            if !found.is_empty() {
+                let reads = format!(
+                    "{} place{}",
+                    found.len(),
+                    if found.len() == 1 { "" } else { "s" }
+                );
+                if force {
+                    println!(
+                        "{} '{}' is still read in {}; removing it anyway:",
+                        "⚠".yellow(),
+                        key.yellow(),
+                        reads
+                    );
+                } else {
+                    println!(
+                        "{} Not removing '{}': it's still read in {}:",
+                        "✗".red(),
+                        key.yellow(),
+                        reads
+                    );
+                }
+                for usage in &found {
+                    let place = format!("{}:{}", usage.path.display(), usage.line);
+                    println!("  {}  {}", place.dimmed(), usage.text);
+                }
+                if !force {
+                    println!("{} Remove those reads first, or pass --force", "→".cyan());
+                    kept += 1;
+                    continue;
+                }
+                println!();
+            }
+        }
+
+        // Show what will be removed
+        println!("{} Removing translation '{}':", "ℹ".blue(), key.yellow());
+        for lang in languages {
+            if let Some(val) = translation.values.get(lang) {
+                println!("  {}: {}", lang.to_uppercase().green(), val);
+            }
+        }
+        println!();
+
+        // Remove the translation
+        match remove_translation_with_record_name(file, key, record_name, languages) {
+            Ok(_) => {
+                println!(
+                    "{} Removed translation '{}' from {}",
+                    "✓".green(),
+                    key.yellow(),
+                    file.display()
+                );
+                context::update(file, |contexts| {
+                    contexts.remove(key);
+                })?;
+            }
+            Err(e) => {
+                errors::error(format!("Failed to remove translation: {}", e));
+                exit(1);
+            }
+        }
+    }
+
+    if kept > 0 {
+        if keys.len() > 1 {
+            println!();
+            println!(
+                "Removed {} of {} keys; {} left in place.",
+                keys.len() - kept,
+                keys.len(),
+                kept
+            );
+        }
+        return Ok(1);
+    }
+
+    Ok(0)
+}
+
+/// The Elm code `remove --check-usages` searches: `src_dirs`, or else the
+/// project's source directories that exist
+///
+/// With neither, the check is skipped with a note rather than failing, so a project
+/// without Elm code to search can still remove keys.
+fn usage_check_sources(
+    config: &Config,
+    files: &[(PathBuf, String)],
+    src_dirs: Vec<PathBuf>,
+) -> Result<Option<SourceSet>> {
+    let src_dirs = if src_dirs.is_empty() {
+        let found: Vec<PathBuf> = scan_source_dirs(config, src_dirs)?
+            .into_iter()
+            .filter(|dir| dir.is_dir())
+            .collect();
+        if found.is_empty() {
+            errors::hint("No source directories to search; skipping the usage check");
+            return Ok(None);
+        }
+        found
+    } else {
+        src_dirs
+    };
+    Ok(Some(scan_sources(config, files, &src_dirs, vec![])?))
+}
+
+/// Record `description` for `key` in the context file of `file`
+fn describe_key(file: &Path, key: &str, description: &str) -> Result<()> {
+    let written = context::update(file, |contexts| {
+        contexts.entry(key.to_string()).or_default().description = Some(description.to_string());
+    })?;
+    if let Some(path) = written {
+        println!(
+            "{} Described '{}' in {}",
+            "✓".green(),
+            key.yellow(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Rename a key in `file` and, given the Elm `sources`, the reads of it off
+/// translations there
+///
+/// Reads `SourceSet::rename` can't attribute to translations are listed, not changed.
+fn handle_rename(
+    file: &Path,
+    old_key: &str,
+    new_key: &str,
+    sources: Option<&SourceSet>,
+    variables: &[String],
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let content = read_elm_file(file)?;
+    let new_content =
+        match rename_translation_in_content(&content, old_key, new_key, record_name, languages) {
+            Ok(new_content) => new_content,
+            Err(e) => {
+                errors::error(e);
+                exit(1);
+            }
+        };
+    let renamed_files = sources.map_or_else(Vec::new, |sources| {
+        sources.rename(old_key, new_key, variables, &[record_name.to_string()])
+    });
+
+    if dry_run {
+        print_diff(file, &content, &new_content);
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            let original = sources
+                .and_then(|s| s.files.iter().find(|f| f.path == renamed.path))
+                .map_or("", |f| f.content.as_str());
+            print_diff(renamed.path, original, &renamed.content);
+        }
+        println!();
+    } else {
+        timing::write(file, &new_content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            timing::write(renamed.path, &renamed.content)
+                .with_context(|| format!("Failed to write to {}", renamed.path.display()))?;
+        }
+        context::update(file, |contexts| {
+            if let Some(notes) = contexts.remove(old_key) {
+                contexts.insert(new_key.to_string(), notes);
+            }
+        })?;
+    }
+
+    println!(
+        "{} {} '{}' to '{}' in {}",
+        "✓".green(),
+        if dry_run { "Would rename" } else { "Renamed" },
+        old_key.yellow(),
+        new_key.green(),
+        file.display()
+    );
+    if let Some(sources) = sources {
+        print_usage_updates(&renamed_files, sources, old_key, dry_run);
+    }
+    Ok(())
+}
+
+/// Report the usages of `key` renaming it in `sources` updated, and the reads it
+/// left for checking by hand
+fn print_usage_updates(
+    renamed_files: &[RenamedFile],
+    sources: &SourceSet,
+    key: &str,
+    dry_run: bool,
+) {
+    let updated: Vec<_> = renamed_files
+        .iter()
+        .filter(|r| !r.renamed.is_empty())
+        .collect();
+    if updated.is_empty() {
+        println!(
+            "{} No reads of '{}' off translations in {} Elm files",
+            "ℹ".blue(),
+            key,
+            sources.files.len()
+        );
+    } else {
+        let total: usize = updated.iter().map(|r| r.renamed.len()).sum();
+        println!(
+            "{} {} {} usage{} in {} file{}:",
+            "✓".green(),
+            if dry_run { "Would update" } else { "Updated" },
+            total,
+            if total == 1 { "" } else { "s" },
+            updated.len(),
+            if updated.len() == 1 { "" } else { "s" }
+        );
+        for renamed in &updated {
+            println!(
+                "  {}  {}",
+                renamed.path.display().to_string().dimmed(),
+                renamed.renamed.len()
+            );
+        }
+    }
+
+    let ambiguous: Vec<_> = renamed_files.iter().flat_map(|r| &r.ambiguous).collect();
+    if !ambiguous.is_empty() {
+        println!();
+        println!(
+            "{} Left {} read{} of `{}` that may not be translations:",
+            "⚠".yellow(),
+            ambiguous.len(),
+            if ambiguous.len() == 1 { "" } else { "s" },
+            key
+        );
+        for usage in &ambiguous {
+            let place = format!("{}:{}", usage.path.display(), usage.line);
+            println!("  {}  {}", place.dimmed(), usage.text);
+        }
+        println!(
+            "{} Rename the ones that read translations by hand",
+            "→".cyan()
+        );
+    }
+}
+
+/// Exit listing the renames of `renames` whose new key `keys` already has
+fn refuse_collisions(renames: &[(String, String)], keys: &[&str]) {
+    let collisions = group::collisions(renames, keys);
+    let Some((_, taken)) = collisions.first() else {
+        return;
+    };
+    if collisions.len() == 1 {
+        errors::error_with(
+            Code::AlreadyExists,
+            format!("'{}' already exists", taken.yellow()),
+        );
+    } else {
+        errors::error_with(
+            Code::AlreadyExists,
+            format!("{} of the new keys already exist:", collisions.len()),
+        );
+        for (old, new) in &collisions {
+            eprintln!("  {} → {}", old, new.yellow());
+        }
+    }
+    errors::hint("Nothing was renamed");
+    exit(1);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_rename_prefix(
+    config: &Config,
+    file: &Path,
+    old_prefix: &str,
+    new_prefix: &str,
+    usages: Option<(Vec<PathBuf>, Vec<String>)>,
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let content = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let keys: Vec<&str> = parse_result
+        .type_fields
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    let renames = group::prefix_renames(keys.iter().copied(), old_prefix, new_prefix);
+    if renames.is_empty() {
+        errors::error_with(
+            Code::KeyNotFound,
+            format!("No key starts with '{}'", old_prefix.yellow()),
+        );
+        errors::hint("A key starts with a prefix when it is the prefix or goes on with a new word, like accountTitle for account");
+        exit(1);
+    }
+    refuse_collisions(&renames, &keys);
+
+    let new_content = match renames
+        .iter()
+        .try_fold(content.clone(), |edited, (old, new)| {
+            rename_translation_in_content(&edited, old, new, record_name, languages)
+        }) {
+        Ok(new_content) => new_content,
+        Err(e) => {
+            errors::error(e);
+            exit(1);
+        }
+    };
+
+    let sources = match usages {
+        Some((src_dirs, ignore_path)) => {
+            for (old, _) in &renames {
+                refuse_if_defined_elsewhere(config, file, old, languages)?;
+            }
+            let files = vec![(file.to_path_buf(), record_name.to_string())];
+            let src_dirs = scan_source_dirs(config, src_dirs)?;
+            Some(scan_sources(config, &files, &src_dirs, ignore_path)?)
+        }
+        None => None,
+    };
+    let renamed_files = sources.as_ref().map_or_else(Vec::new, |sources| {
+        sources.rename_each(
+            &renames,
+            &config.scan().variables(),
+            &[record_name.to_string()],
+        )
+    });
+
+    let backup_path = if dry_run {
+        print_diff(file, &content, &new_content);
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            let original = sources
+                .as_ref()
+                .and_then(|s| s.files.iter().find(|f| f.path == renamed.path))
+                .map_or("", |f| f.content.as_str());
+            print_diff(renamed.path, original, &renamed.content);
+        }
+        println!();
+        None
+    } else {
+        let backup_path = backup::write_backup(file, &content)?;
+        timing::write(file, &new_content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            timing::write(renamed.path, &renamed.content)
+                .with_context(|| format!("Failed to write to {}", renamed.path.display()))?;
+        }
+        context::update(file, |contexts| {
+            for (old, new) in &renames {
+                if let Some(notes) = contexts.remove(old) {
+                    contexts.insert(new.clone(), notes);
+                }
+            }
+        })?;
+        Some(backup_path)
+    };
+
+    println!(
+        "{} {} {} key{} from '{}' to '{}' in {}:",
+        "✓".green(),
+        if dry_run { "Would rename" } else { "Renamed" },
+        renames.len(),
+        if renames.len() == 1 { "" } else { "s" },
+        old_prefix.yellow(),
+        new_prefix.green(),
+        file.display()
+    );
+    for (old, new) in &renames {
+        println!("  {} → {}", old, new.green());
+    }
+    if let Some(backup_path) = backup_path {
+        println!(
+            "  {} Backup written to {}",
+            "ℹ".blue(),
+            backup_path.display()
+        );
+    }
+    if let Some(sources) = &sources {
+        print_usage_updates(
+            &renamed_files,
+            sources,
+            &format!("{}*", old_prefix),
+            dry_run,
+        );
+    }
+    Ok(())
+}
+
+/// Where `group rename` or `group move` takes a group
+enum GroupTarget {
+    /// To this name, in the same group
+    Renamed(String),
+    /// Into this group, or to the top level for None
+    Moved(Option<String>),
+}
+
+/// The translations of `content` under the keys `style` gives them, dotted for
+/// nested keys
+fn keyed_translations(
+    content: &str,
+    style: KeyStyle,
+    record_name: &str,
+    languages: &[String],
+) -> Result<Vec<Translation>> {
+    match style {
+        KeyStyle::Nested => nested::translations(content, record_name, languages),
+        KeyStyle::Flat => {
+            let parsed = parse_i18n_content_with_record_name(content, record_name, languages)?;
+            let mut translations: Vec<Translation> = parsed.translations.into_values().collect();
+            translations.sort_by(|a, b| a.key.cmp(&b.key));
+            Ok(translations)
+        }
+    }
+}
+
+fn handle_group_list(
+    file: &Path,
+    json: bool,
+    style: KeyStyle,
+    todo_marker: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    let content = read_elm_file(file)?;
+    let translations = keyed_translations(&content, style, record_name, languages)?;
+    let groups = group::groups(&translations, style, languages, todo_marker);
+    if json {
+        let groups: Vec<serde_json::Value> = groups
+            .iter()
+            .map(|g| {
+                serde_json::json!({
+                    "name": g.name,
+                    "keys": g.keys,
+                    "coverage": g.coverage.iter().map(CoverageEntry::from).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let output = serde_json::json!({ "groups": groups });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if groups.is_empty() {
+        println!("{} No groups of keys in {}", "ℹ".blue(), file.display());
+        if style == KeyStyle::Flat {
+            println!(
+                "{} A group is a first word two keys or more start with, like checkout for checkoutTitle and checkoutPayNow",
+                "→".cyan()
+            );
+        }
+        return Ok(());
+    }
+    println!(
+        "{} {} group{} in {}",
+        "📂".blue(),
+        groups.len(),
+        if groups.len() == 1 { "" } else { "s" },
+        file.display()
+    );
+    println!();
+    let width = groups.iter().map(|g| g.name.len()).max().unwrap_or(0);
+    for g in &groups {
+        let coverage: Vec<String> = g
+            .coverage
+            .iter()
+            .map(|c| {
+                let percent = format!("{} {:.1}%", c.language.to_uppercase(), c.percent());
+                if c.translated == c.total {
+                    percent.green().to_string()
+                } else {
+                    percent.yellow().to_string()
+                }
+            })
+            .collect();
+        println!(
+            "  {:<width$}  {:>4} key{}  {}",
+            g.name,
+            g.keys,
+            if g.keys == 1 { " " } else { "s" },
+            coverage.join("  "),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_group_edit(
+    config: &Config,
+    file: &Path,
+    group: &str,
+    target: GroupTarget,
+    usages: Option<(Vec<PathBuf>, Vec<String>)>,
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let style = config.key_style();
+    let (parent, name) = group.rsplit_once('.').unwrap_or(("", group));
+    let new_group = match &target {
+        GroupTarget::Renamed(new_name) if parent.is_empty() => new_name.clone(),
+        GroupTarget::Renamed(new_name) => format!("{}.{}", parent, new_name),
+        GroupTarget::Moved(Some(into)) => format!("{}.{}", into, name),
+        GroupTarget::Moved(None) => name.to_string(),
+    };
+    if let GroupTarget::Moved(into) = &target {
+        let into = into.as_deref().unwrap_or("");
+        if into == group || into.starts_with(&format!("{}.", group)) {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!("'{}' can't be moved into itself", group.yellow()),
+            );
+            exit(1);
+        }
+        if into == parent {
+            if into.is_empty() {
+                errors::error(format!("'{}' is already at the top level", group.yellow()));
             } else {
-                src_dir
+                errors::error(format!("'{}' is already in '{}'", group.yellow(), into));
+            }
+            exit(1);
+        }
+    }
+
+    let content = read_elm_file(file)?;
+    let translations = keyed_translations(&content, style, record_name, languages)?;
+    let keys: Vec<&str> = translations.iter().map(|t| t.key.as_str()).collect();
+    // Each key of the group with the key it gets
+    let renames: Vec<(String, String)> = match style {
+        KeyStyle::Nested => keys
+            .iter()
+            .filter_map(|key| {
+                let rest = key
+                    .strip_prefix(group)
+                    .filter(|rest| rest.starts_with('.'))?;
+                Some((key.to_string(), format!("{}{}", new_group, rest)))
+            })
+            .collect(),
+        KeyStyle::Flat => group::flat_renames(keys.iter().copied(), group, &new_group),
+    };
+    if style == KeyStyle::Flat {
+        if renames.is_empty() {
+            errors::error_with(
+                Code::KeyNotFound,
+                format!(
+                    "There's no group '{}': no key starts with {}",
+                    group.yellow(),
+                    group::flat_prefix(group)
+                ),
+            );
+            errors::hint("Run `elm-i18n group list` to see the groups");
+            exit(1);
+        }
+        refuse_collisions(&renames, &keys);
+    }
+
+    let edited = match (style, &target) {
+        (KeyStyle::Nested, GroupTarget::Renamed(new_name)) => {
+            nested::rename_group(&content, group, new_name, record_name, languages)
+        }
+        (KeyStyle::Nested, GroupTarget::Moved(into)) => {
+            nested::move_group(&content, group, into.as_deref(), record_name, languages)
+        }
+        (KeyStyle::Flat, _) => renames
+            .iter()
+            .try_fold(content.clone(), |edited, (old, new)| {
+                rename_translation_in_content(&edited, old, new, record_name, languages)
+            }),
+    };
+    let new_content = match edited {
+        Ok(new_content) => new_content,
+        Err(e) => {
+            errors::report(e);
+            exit(1);
+        }
+    };
+
+    let sources = match usages {
+        Some((src_dirs, ignore_path)) => {
+            let read_keys: Vec<&str> = match style {
+                KeyStyle::Nested => group.split('.').take(1).collect(),
+                KeyStyle::Flat => renames.iter().map(|(old, _)| old.as_str()).collect(),
             };
+            for key in read_keys {
+                refuse_if_defined_elsewhere(config, file, key, languages)?;
+            }
+            let files = vec![(file.to_path_buf(), record_name.to_string())];
+            let src_dirs = scan_source_dirs(config, src_dirs)?;
+            Some(scan_sources(config, &files, &src_dirs, ignore_path)?)
+        }
+        None => None,
+    };
+    // Nested keys are read through the group's path, flat ones one by one
+    let read_renames = match style {
+        KeyStyle::Nested => vec![(group.to_string(), new_group.clone())],
+        KeyStyle::Flat => renames.clone(),
+    };
+    let renamed_files = sources.as_ref().map_or_else(Vec::new, |sources| {
+        sources.rename_each(
+            &read_renames,
+            &config.scan().variables(),
+            &[record_name.to_string()],
+        )
+    });
+
+    let backup_path = if dry_run {
+        print_diff(file, &content, &new_content);
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            let original = sources
+                .as_ref()
+                .and_then(|s| s.files.iter().find(|f| f.path == renamed.path))
+                .map_or("", |f| f.content.as_str());
+            print_diff(renamed.path, original, &renamed.content);
+        }
+        println!();
+        None
+    } else {
+        let backup_path = backup::write_backup(file, &content)?;
+        timing::write(file, &new_content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        for renamed in renamed_files.iter().filter(|r| !r.renamed.is_empty()) {
+            timing::write(renamed.path, &renamed.content)
+                .with_context(|| format!("Failed to write to {}", renamed.path.display()))?;
+        }
+        context::update(file, |contexts| {
+            for (old, new) in &renames {
+                if let Some(notes) = contexts.remove(old) {
+                    contexts.insert(new.clone(), notes);
+                }
+            }
+        })?;
+        Some(backup_path)
+    };
+
+    let verb = match (&target, dry_run) {
+        (GroupTarget::Renamed(_), true) => "Would rename",
+        (GroupTarget::Renamed(_), false) => "Renamed",
+        (GroupTarget::Moved(_), true) => "Would move",
+        (GroupTarget::Moved(_), false) => "Moved",
+    };
+    println!(
+        "{} {} group '{}' to '{}' ({} key{}) in {}",
+        "✓".green(),
+        verb,
+        group.yellow(),
+        new_group.green(),
+        renames.len(),
+        if renames.len() == 1 { "" } else { "s" },
+        file.display()
+    );
+    if let Some(backup_path) = backup_path {
+        println!(
+            "  {} Backup written to {}",
+            "ℹ".blue(),
+            backup_path.display()
+        );
+    }
+    if let Some(sources) = &sources {
+        print_usage_updates(&renamed_files, sources, group, dry_run);
+    }
+    Ok(())
+}
+
+/// Exit when another configured translation file also defines `key`, since
+/// `--update-usages` couldn't tell which file's key an untyped `t.key` reads
+fn refuse_if_defined_elsewhere(
+    config: &Config,
+    file: &Path,
+    key: &str,
+    languages: &[String],
+) -> Result<()> {
+    let others: Vec<(PathBuf, String)> = config
+        .translation_files()
+        .into_iter()
+        .filter(|(other, _)| other != file)
+        .collect();
+    if let Some((other, _)) = others.iter().find(|(other, record_name)| {
+        other.exists()
+            && check_key_exists_with_record_name(other, key, record_name, languages)
+                .is_ok_and(|t| t.is_some())
+    }) {
+        errors::error(format!(
+            "'{}' is also defined in {}, so its usages can't be told apart",
+            key.yellow(),
+            other.display()
+        ));
+        errors::hint("Rename without --update-usages and update the reads by hand");
+        exit(1);
+    }
+    Ok(())
+}
+
+fn handle_remove_unused(
+    file: &Path,
+    src_dir: &Path,
+    confirm: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    if confirm {
+        refuse_if_inconsistent(file, record_name, languages)?;
+    }
+
+    println!("{} Scanning for unused translation keys...", "🔍".blue());
+
+    // Find all unused keys
+    let unused_keys = find_unused_keys(file, src_dir, record_name, languages)?;
+
+    if unused_keys.is_empty() {
+        println!("{} All translation keys are in use!", "✓".green());
+        return Ok(());
+    }
+
+    // Show unused keys
+    println!();
+    println!(
+        "{} Found {} unused translation keys:",
+        "⚠".yellow(),
+        unused_keys.len()
+    );
+    for key in &unused_keys {
+        println!("  • {}", key.yellow());
+    }
 
-            // In multi-file mode without a target, process all files
-            if cli.target.is_none() {
-                if let Config::MultiFile { files, .. } = &config {
-                    println!(
-                        "{} Running remove-unused on all translation files...\n",
-                        "🔍".blue()
-                    );
-                    for (shortcut, file_config) in files {
-                        if !file_config.path.exists() {
-                            println!(
-                                "  {} Skipping {} (file not found)\n",
-                                "⚠".yellow(),
-                                shortcut
-                            );
-                            continue;
-                        }
-                        println!(
-                            "{} Processing {} ({})...",
-                            "→".cyan(),
-                            shortcut.yellow(),
-                            file_config.path.display()
-                        );
-                        handle_remove_unused(
-                            &file_config.path,
-                            &actual_src_dir,
-                            confirm,
-                            &file_config.record_name,
-                            languages,
-                        )?;
-                        println!();
-                    }
-                } else {
-                    // Single file mode
-                    handle_remove_unused(
-                        &file_path,
-                        &actual_src_dir,
-                        confirm,
-                        &record_name,
-                        languages,
-                    )?;
-                }
-            } else {
-                // Target was specified, use the determined file
-                let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                    file_path.clone()
-                } else {
-                    file
-                };
-                handle_remove_unused(
-                    &actual_file,
-                    &actual_src_dir,
-                    confirm,
-                    &record_name,
-                    languages,
-                )?;
+    if !confirm {
+        println!();
+        println!(
+            "{} To remove these keys, run with --confirm flag:",
+            "ℹ".blue()
+        );
+        println!("  elm-i18n remove-unused --confirm");
+        return Ok(());
+    }
+
+    // Remove the unused keys
+    println!();
+    println!("{} Removing unused keys...", "🗑".red());
+
+    for key in &unused_keys {
+        match remove_translation_with_record_name(file, key, record_name, languages) {
+            Ok(_) => {
+                println!("  {} Removed: {}", "✓".green(), key);
+            }
+            Err(e) => {
+                eprintln!("  {} Failed to remove {}: {}", "✗".red(), key, e);
             }
         }
+    }
 
-        Commands::List {
-            file,
-            verbose,
-            filter,
-        } => {
-            let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                file_path.clone()
-            } else {
-                file
-            };
-            handle_list(&actual_file, verbose, &filter, &record_name, languages)?
+    println!();
+    println!(
+        "{} Removed {} unused translation keys",
+        "✓".green(),
+        unused_keys.len()
+    );
+
+    Ok(())
+}
+
+/// `todo_marker` is set with `--todo`, to only list keys that still have it
+fn handle_list(
+    file: &Path,
+    verbose: bool,
+    filter: &Option<String>,
+    todo_marker: Option<&str>,
+    diff_base: Option<&Path>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    // Parse the I18n file
+    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+
+    // With a base, removed keys are listed too, as the base has them
+    let mut changes = None;
+    let mut base = None;
+    if let Some(base_file) = diff_base {
+        if !base_file.exists() {
+            errors::error_with(
+                Code::FileNotFound,
+                format!("File not found: {}", base_file.display()),
+            );
+            exit(1);
+        }
+        let base_result = parse_i18n_file_with_record_name(base_file, record_name, languages)?;
+        changes = Some(key_changes(&base_result, &parse_result, languages));
+        base = Some(base_result);
+    }
+    let mut translations: Vec<_> = parse_result.translations.into_iter().collect();
+    if let (Some(changes), Some(base), None) = (&changes, &base, todo_marker) {
+        for (key, _) in &changes.removed {
+            translations.push((key.clone(), base.translations[key].clone()));
         }
+    }
 
-        Commands::DuplicateKeys { file } => {
-            // In multi-file mode without a target, find duplicates across all files
-            if cli.target.is_none() {
-                if let Config::MultiFile { files, .. } = &config {
-                    handle_duplicates_cross_file(files, languages)?;
+    // Apply filter if provided
+    if let Some(pattern) = filter {
+        let pattern_lower = pattern.to_lowercase();
+        translations.retain(|(key, _)| key.to_lowercase().contains(&pattern_lower));
+    }
+    if let Some(marker) = todo_marker {
+        translations
+            .retain(|(_, translation)| !todo_languages(translation, marker, languages).is_empty());
+    }
+
+    // Sort by key
+    translations.sort_by(|a, b| a.0.cmp(&b.0));
+
+    if translations.is_empty() {
+        if let Some(marker) = todo_marker {
+            println!("{} No `{}` markers left", "✓".green(), marker);
+        } else if filter.is_some() {
+            println!(
+                "{} No translations found matching '{}'",
+                "✗".red(),
+                filter.as_ref().unwrap().yellow()
+            );
+        } else {
+            println!("{} No translations found", "✗".red());
+        }
+        return Ok(());
+    }
+
+    // Display results
+    let listed_removed = translations
+        .iter()
+        .filter(|(key, _)| matches!(list_mark(changes.as_ref(), key), ListMark::Removed))
+        .count();
+    let found = translations.len() - listed_removed;
+    println!(
+        "{} Found {} translation{}{}:",
+        "📋".blue(),
+        found,
+        if found == 1 { "" } else { "s" },
+        if listed_removed > 0 {
+            format!(
+                " and {} removed one{}",
+                listed_removed,
+                if listed_removed == 1 { "" } else { "s" }
+            )
+        } else {
+            String::new()
+        }
+    );
+
+    if verbose {
+        println!();
+        for (key, translation) in &translations {
+            let mark = list_mark(changes.as_ref(), key);
+            println!("  {} {}{}", mark.bullet(), key.yellow(), mark.label());
+
+            // Show type unless it's a plain string
+            if let Some(ref type_sig) = translation.type_signature {
+                println!("    {}: {}", "Type".cyan(), type_sig);
+            }
+
+            // Show translations for each language
+            let multi_line = |val: &str| {
+                if val.contains('\n') {
+                    format!(
+                        "\n{}",
+                        val.lines()
+                            .map(|line| format!("      {}", line))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    )
                 } else {
-                    // Single file mode
-                    handle_duplicates(&file_path, &record_name, languages)?;
+                    val.to_string()
+                }
+            };
+            for lang in languages {
+                if let Some(val) = translation.values.get(lang) {
+                    println!("    {}: {}", lang.to_uppercase().green(), multi_line(val));
+                }
+                if let ListMark::Changed(changed) = &mark {
+                    let old = base
+                        .as_ref()
+                        .and_then(|base| base.translations[key].values.get(lang));
+                    if let Some(old) = old.filter(|_| changed.iter().any(|(l, _, _)| l == lang)) {
+                        println!("      {} {}", "was:".dimmed(), multi_line(old).dimmed());
+                    }
                 }
-            } else {
-                // Target was specified, use the determined file
-                let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                    file_path.clone()
-                } else {
-                    file
-                };
-                handle_duplicates(&actual_file, &record_name, languages)?;
             }
+
+            println!();
         }
+    } else {
+        // Simple list
+        for (key, translation) in &translations {
+            let type_info = format!(
+                " ({})",
+                translation
+                    .type_signature
+                    .as_deref()
+                    .unwrap_or("String")
+                    .cyan()
+            );
+            let todo_info = match todo_marker {
+                Some(marker) => format!(
+                    " {} {}",
+                    "TODO:".red(),
+                    todo_languages(translation, marker, languages)
+                        .iter()
+                        .map(|lang| lang.to_uppercase())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => String::new(),
+            };
 
-        Commands::SharedValues { file, suppress } => {
-            if cli.target.is_none() {
-                if let Config::MultiFile { files, .. } = &config {
-                    handle_shared_values_cross_file(files, languages, suppress)?;
-                } else {
-                    handle_shared_values(&file_path, &record_name, languages, suppress)?;
-                }
-            } else {
-                let actual_file = if file.to_str() == Some("src/I18n.elm") {
-                    file_path.clone()
-                } else {
-                    file
-                };
-                handle_shared_values(&actual_file, &record_name, languages, suppress)?;
+            let mark = list_mark(changes.as_ref(), key);
+            println!(
+                "  {} {}{}{}{}",
+                mark.bullet(),
+                key.yellow(),
+                type_info,
+                todo_info,
+                mark.label()
+            );
+        }
+    }
+
+    if let (Some(changes), Some(base_file)) = (&changes, diff_base) {
+        println!();
+        if changes.is_empty() {
+            println!(
+                "{} Same translations as {}",
+                "ℹ".blue(),
+                base_file.display()
+            );
+        } else {
+            println!(
+                "{} Against {}: {} new, {} changed, {} removed",
+                "ℹ".blue(),
+                base_file.display(),
+                changes.added.len(),
+                changes.changed.len(),
+                changes.removed.len()
+            );
+        }
+    }
+
+    if let Some(marker) = todo_marker {
+        println!();
+        print_todo_counts(
+            translations.iter().map(|(_, translation)| translation),
+            marker,
+            languages,
+        );
+    }
+
+    Ok(())
+}
+
+/// How `list --diff-base` marks a key
+enum ListMark<'a> {
+    Unchanged,
+    New,
+    /// With each language whose value changed: (language, old, new)
+    Changed(&'a [(String, String, String)]),
+    Removed,
+}
+
+impl ListMark<'_> {
+    fn bullet(&self) -> ColoredString {
+        match self {
+            ListMark::Unchanged => "•".green(),
+            ListMark::New => "+".green(),
+            ListMark::Changed(_) => "~".yellow(),
+            ListMark::Removed => "-".red(),
+        }
+    }
+
+    /// " new", " changed in FR", " removed", after the rest of the line
+    fn label(&self) -> String {
+        match self {
+            ListMark::Unchanged => String::new(),
+            ListMark::New => format!(" {}", "new".green()),
+            ListMark::Changed(changed) => format!(
+                " {}",
+                format!(
+                    "changed in {}",
+                    changed
+                        .iter()
+                        .map(|(lang, _, _)| lang.to_uppercase())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .yellow()
+            ),
+            ListMark::Removed => format!(" {}", "removed".red()),
+        }
+    }
+}
+
+fn list_mark<'a>(changes: Option<&'a KeyChanges>, key: &str) -> ListMark<'a> {
+    let Some(changes) = changes else {
+        return ListMark::Unchanged;
+    };
+    if changes.added.iter().any(|(k, _)| k == key) {
+        ListMark::New
+    } else if changes.removed.iter().any(|(k, _)| k == key) {
+        ListMark::Removed
+    } else if let Some((_, changed)) = changes.changed.iter().find(|(k, _)| k == key) {
+        ListMark::Changed(changed)
+    } else {
+        ListMark::Unchanged
+    }
+}
+
+/// Languages whose value for this translation still has the TODO marker
+fn todo_languages<'a>(
+    translation: &Translation,
+    todo_marker: &str,
+    languages: &'a [String],
+) -> Vec<&'a String> {
+    languages
+        .iter()
+        .filter(|lang| {
+            value_status(translation.values.get(*lang), todo_marker) == ValueStatus::Todo
+        })
+        .collect()
+}
+
+/// "ℹ 3 `TODO` markers left: FR 2, DE 1"
+fn print_todo_counts<'a>(
+    translations: impl Iterator<Item = &'a Translation>,
+    todo_marker: &str,
+    languages: &[String],
+) {
+    let mut counts = vec![0; languages.len()];
+    for translation in translations {
+        for (i, lang) in languages.iter().enumerate() {
+            if value_status(translation.values.get(lang), todo_marker) == ValueStatus::Todo {
+                counts[i] += 1;
             }
         }
+    }
+    let total: usize = counts.iter().sum();
+    let per_language: Vec<String> = languages
+        .iter()
+        .zip(&counts)
+        .filter(|(_, count)| **count > 0)
+        .map(|(lang, count)| format!("{} {}", lang.to_uppercase(), count))
+        .collect();
+    println!(
+        "{} {} `{}` marker{} left: {}",
+        "ℹ".blue(),
+        total,
+        todo_marker,
+        if total == 1 { "" } else { "s" },
+        per_language.join(", ")
+    );
+}
+
+fn handle_list_across_files(
+    files: &[(PathBuf, String)],
+    verbose: bool,
+    filter: &Option<String>,
+    todo_marker: Option<&str>,
+    languages: &[String],
+) -> Result<()> {
+    // (key, file, translation) across every matched file
+    let mut translations = Vec::new();
+    for (file, record_name) in files {
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        for (key, translation) in parse_result.translations {
+            translations.push((key, file.clone(), translation));
+        }
+    }
 
-        Commands::AddLanguage { new_lang, from } => {
-            handle_add_language(&config, &new_lang, &from)?;
+    if let Some(pattern) = filter {
+        let pattern_lower = pattern.to_lowercase();
+        translations.retain(|(key, _, _)| key.to_lowercase().contains(&pattern_lower));
+    }
+    if let Some(marker) = todo_marker {
+        translations.retain(|(_, _, translation)| {
+            !todo_languages(translation, marker, languages).is_empty()
+        });
+    }
+
+    translations.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    if translations.is_empty() {
+        match todo_marker {
+            Some(marker) => println!("{} No `{}` markers left", "✓".green(), marker),
+            None => println!("{} No translations found", "✗".red()),
         }
+        return Ok(());
+    }
 
-        Commands::Version => unreachable!(),
-        Commands::Status => unreachable!(),
-        Commands::SetupClaude => unreachable!(),
+    println!(
+        "{} Found {} translation{} in {} file{}:",
+        "📋".blue(),
+        translations.len(),
+        if translations.len() == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" }
+    );
+
+    if verbose {
+        println!();
     }
 
-    Ok(())
-}
+    for (key, file, translation) in &translations {
+        let type_info = translation
+            .type_signature
+            .clone()
+            .unwrap_or_else(|| "String".to_string());
+
+        let todo_info = match todo_marker {
+            Some(marker) => format!(
+                " {} {}",
+                "TODO:".red(),
+                todo_languages(translation, marker, languages)
+                    .iter()
+                    .map(|lang| lang.to_uppercase())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        };
 
-/// Determine which file to target based on config and shortcut
-fn determine_target_file(
-    config: &Config,
-    shortcut: &Option<String>,
-    command: &Commands,
-) -> Result<(PathBuf, String)> {
-    // For Init command, we might allow creation of new files
-    let is_init = matches!(command, Commands::Init { .. });
-    // These commands can work without a target (they process all files)
-    let is_remove_unused = matches!(command, Commands::RemoveUnused { .. });
-    let is_duplicates = matches!(command, Commands::DuplicateKeys { .. });
-    let is_shared_values = matches!(command, Commands::SharedValues { .. });
-    let is_add_language = matches!(command, Commands::AddLanguage { .. });
+        println!(
+            "  {} {} ({}) [{}]{}",
+            "•".green(),
+            key.yellow(),
+            type_info.cyan(),
+            file.display(),
+            todo_info
+        );
 
-    match config {
-        Config::SingleFile {
-            file, record_name, ..
-        } => {
-            if shortcut.is_some() {
-                eprintln!(
-                    "{} Warning: File shortcuts are ignored in single-file mode",
-                    "⚠".yellow()
-                );
-            }
-            Ok((file.clone(), record_name.clone()))
-        }
-        Config::MultiFile { files, .. } => {
-            match shortcut {
-                Some(s) => match files.get(s) {
-                    Some(file_config) => {
-                        Ok((file_config.path.clone(), file_config.record_name.clone()))
-                    }
-                    None => {
-                        eprintln!("{} Unknown file shortcut: {}", "✗".red(), s.yellow());
-                        config.print_shortcuts();
-                        std::process::exit(1);
-                    }
-                },
-                None => {
-                    // Some commands can run without a target - they process all files
-                    if is_remove_unused || is_duplicates || is_shared_values || is_add_language {
-                        // Return dummy values - the command handler will iterate all files
-                        Ok((PathBuf::from(""), String::new()))
-                    } else if !is_init {
-                        config.print_shortcuts();
-                        std::process::exit(1);
-                    } else {
-                        // For init, we might allow specifying a new file
-                        eprintln!("{} Multi-file mode requires a file shortcut", "✗".red());
-                        config.print_shortcuts();
-                        std::process::exit(1);
-                    }
+        if verbose {
+            for lang in languages {
+                if let Some(val) = translation.values.get(lang) {
+                    println!("    {}: {}", lang.to_uppercase().green(), val);
                 }
             }
+            println!();
         }
     }
-}
 
-/// Handle the setup-claude command
-fn handle_setup_claude() -> Result<()> {
-    use std::fs;
+    if let Some(marker) = todo_marker {
+        println!();
+        print_todo_counts(
+            translations.iter().map(|(_, _, translation)| translation),
+            marker,
+            languages,
+        );
+    }
 
-    println!(
-        "{} Setting up CLAUDE.md with elm-i18n instructions...",
-        "🤖".blue()
-    );
-    println!();
+    Ok(())
+}
 
-    // Load configuration to understand project setup
-    let config = match Config::load()? {
-        Some(config) => config,
-        None => {
-            eprintln!(
-                "{} No elm-i18n configuration found at {}!",
-                "✗".red(),
-                config_file_path()
-            );
-            eprintln!(
-                "Run {} first to create a configuration.",
-                "elm-i18n setup".green()
-            );
-            std::process::exit(1);
-        }
-    };
+fn handle_duplicates(file: &Path, record_name: &str, languages: &[String]) -> Result<()> {
+    use std::collections::HashMap;
 
-    // Check if CLAUDE.md already exists
-    let claude_path = PathBuf::from("CLAUDE.md");
-    let existing_content = if claude_path.exists() {
-        fs::read_to_string(&claude_path).ok()
-    } else {
-        None
-    };
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
 
-    // Generate elm-i18n specific instructions
-    let elm_i18n_section = generate_claude_instructions(&config);
+    println!("{} Scanning for duplicate translations...", "🔍".blue());
 
-    // Track if we're updating or creating
-    let is_update = existing_content.is_some();
+    // Parse the I18n file
+    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
 
-    // Merge or create CLAUDE.md
-    let final_content = if let Some(existing) = existing_content {
-        // Check if elm-i18n section already exists
-        if existing.contains("## elm-i18n Configuration") {
-            // Replace existing elm-i18n section
-            let before_section = existing
-                .split("## elm-i18n Configuration")
-                .next()
-                .unwrap_or("");
-            let after_section = existing
-                .split("## elm-i18n Configuration")
-                .nth(1)
-                .and_then(|s| s.split("\n## ").nth(1))
-                .map(|s| format!("\n## {}", s))
-                .unwrap_or_default();
+    // Build a map: sorted values -> Vec<key>
+    let mut value_to_keys: HashMap<Vec<(String, String)>, Vec<String>> = HashMap::new();
 
-            format!("{}{}{}", before_section, elm_i18n_section, after_section)
-        } else {
-            // Append elm-i18n section
-            format!("{}\n\n{}", existing.trim(), elm_i18n_section)
+    for (key, translation) in &parse_result.translations {
+        if translation.type_signature.is_some() {
+            continue;
         }
-    } else {
-        // Create new CLAUDE.md with elm-i18n instructions
-        format!(
-            "# Project-Specific Instructions for Claude\n\n{}",
-            elm_i18n_section
-        )
-    };
 
-    // Write the file
-    fs::write(&claude_path, final_content)?;
+        let mut sorted_values: Vec<(String, String)> = translation
+            .values
+            .iter()
+            .map(|(lang, value)| (lang.clone(), value.clone()))
+            .collect();
+        sorted_values.sort();
+        value_to_keys
+            .entry(sorted_values)
+            .or_default()
+            .push(key.clone());
+    }
 
-    println!(
-        "{} CLAUDE.md has been {}",
-        "✓".green(),
-        if is_update { "updated" } else { "created" }
-    );
+    // Filter to only entries with 2+ keys (actual duplicates)
+    let mut duplicates: Vec<_> = value_to_keys
+        .into_iter()
+        .filter(|(_, keys)| keys.len() >= 2)
+        .collect();
 
-    println!();
-    println!("The file contains:");
-    println!("  • elm-i18n configuration details");
-    println!("  • Available translation files and shortcuts");
-    println!("  • Example commands for your specific setup");
-    println!();
-    println!("Claude will use these instructions to help with translations.");
+    if duplicates.is_empty() {
+        println!();
+        println!("{} No duplicate translations found", "✓".green());
+        return Ok(());
+    }
 
-    Ok(())
-}
+    duplicates.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
 
-fn generate_claude_instructions(config: &Config) -> String {
-    let mut instructions = String::from("## elm-i18n Configuration\n\n");
-    instructions.push_str("This project uses elm-i18n for managing translations. ");
+    let total_duplicate_keys: usize = duplicates.iter().map(|(_, keys)| keys.len()).sum();
+    let potential_savings = total_duplicate_keys - duplicates.len();
 
-    match config {
-        Config::SingleFile {
-            file,
-            record_name,
-            languages,
-            ..
-        } => {
-            instructions.push_str(&format!("It's configured in **single-file mode**.\n\n"));
-            instructions.push_str("### Configuration Details\n\n");
-            instructions.push_str(&format!("- **Translation file**: `{}`\n", file.display()));
-            instructions.push_str(&format!("- **Record type**: `{}`\n", record_name));
-            instructions.push_str(&format!("- **Languages**: {}\n", languages.join(", ")));
-            instructions.push_str("\n### Usage Examples\n\n");
-            instructions.push_str("```bash\n");
-            instructions.push_str("# Add a simple translation\n");
-            instructions.push_str(&format!(
-                "elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"\n\n"
-            ));
-            instructions.push_str("# Add a function translation\n");
-            instructions.push_str("elm-i18n add-fn itemCount \\\n");
-            instructions.push_str("  --type-sig \"Int -> String\" \\\n");
-            instructions.push_str("  -t en=\"\\n -> if n == 1 then \\\"1 item\\\" else String.fromInt n ++ \\\" items\\\"\" \\\n");
-            instructions.push_str("  -t fr=\"\\n -> if n == 1 then \\\"1 élément\\\" else String.fromInt n ++ \\\" éléments\\\"\"\n\n");
-            instructions.push_str("# Check if a key exists\n");
-            instructions.push_str("elm-i18n check myKey\n\n");
-            instructions.push_str("# List all translations\n");
-            instructions.push_str("elm-i18n list\n\n");
-            instructions.push_str("# Remove a translation\n");
-            instructions.push_str("elm-i18n remove myKey\n");
-            instructions.push_str("```\n");
-        }
-        Config::MultiFile {
-            files, languages, ..
-        } => {
-            instructions.push_str(&format!(
-                "It's configured in **multi-file mode** with {} translation files.\n\n",
-                files.len()
-            ));
-            instructions.push_str("### Configuration Details\n\n");
-            instructions.push_str(&format!("- **Languages**: {}\n", languages.join(", ")));
-            instructions.push_str("- **Translation files**:\n");
+    println!();
+    println!(
+        "{} Found {} duplicate group{}:",
+        "📋".blue(),
+        duplicates.len(),
+        if duplicates.len() == 1 { "" } else { "s" }
+    );
+    println!();
+
+    for (values, mut keys) in duplicates {
+        keys.sort();
 
-            for (shortcut, file_config) in files {
-                instructions.push_str(&format!(
-                    "  - `--target {}` → `{}` (Record: `{}`)\n",
-                    shortcut,
-                    file_config.path.display(),
-                    file_config.record_name
-                ));
-            }
+        let display: Vec<String> = values
+            .iter()
+            .map(|(_, value)| truncate_for_display(value, 40))
+            .collect();
 
-            instructions.push_str("\n### Usage Examples\n\n");
-            instructions.push_str("```bash\n");
+        println!("  {} {}:", "•".green(), display.join(" / "));
+        for key in &keys {
+            println!("    - {}", key.yellow());
+        }
+        println!();
+    }
 
-            if let Some((first_shortcut, _)) = files.iter().next() {
-                instructions.push_str(&format!(
-                    "# Add a translation to the {} file\n",
-                    first_shortcut
-                ));
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"\n\n",
-                    first_shortcut
-                ));
+    println!(
+        "{} {} keys could potentially be consolidated into {}",
+        "✓".green(),
+        total_duplicate_keys,
+        total_duplicate_keys - potential_savings
+    );
 
-                instructions.push_str(&format!(
-                    "# Add a function translation to the {} file\n",
-                    first_shortcut
-                ));
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} add-fn itemCount \\\n",
-                    first_shortcut
-                ));
-                instructions.push_str("  --type-sig \"Int -> String\" \\\n");
-                instructions.push_str("  -t en=\"\\n -> if n == 1 then \\\"1 item\\\" else String.fromInt n ++ \\\" items\\\"\" \\\n");
-                instructions.push_str("  -t fr=\"\\n -> if n == 1 then \\\"1 élément\\\" else String.fromInt n ++ \\\" éléments\\\"\"\n\n");
+    Ok(())
+}
 
-                instructions.push_str(&format!(
-                    "# Check if a key exists in the {} file\n",
-                    first_shortcut
-                ));
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} check myKey\n\n",
-                    first_shortcut
-                ));
+/// Plain-string keys whose value in `lang` is exactly `text`
+fn keys_with_identical_text(parse_result: &ParseResult, lang: &str, text: &str) -> Vec<String> {
+    let mut keys: Vec<String> = parse_result
+        .translations
+        .values()
+        .filter(|t| t.type_signature.is_none())
+        .filter(|t| {
+            t.values
+                .get(lang)
+                .and_then(|v| plain_string_value(v))
+                .is_some_and(|v| v == text)
+        })
+        .map(|t| t.key.clone())
+        .collect();
+    keys.sort();
+    keys
+}
 
-                instructions.push_str(&format!(
-                    "# List all translations in the {} file\n",
-                    first_shortcut
-                ));
-                instructions.push_str(&format!("elm-i18n --target {} list\n\n", first_shortcut));
+fn handle_duplicates_cross_file(
+    files: &std::collections::HashMap<String, FileConfig>,
+    languages: &[String],
+) -> Result<()> {
+    use std::collections::HashMap;
 
-                instructions.push_str(&format!(
-                    "# Remove a translation from the {} file\n",
-                    first_shortcut
-                ));
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} remove myKey\n",
-                    first_shortcut
-                ));
-            }
+    println!(
+        "{} Scanning for duplicate translations across all files...",
+        "🔍".blue()
+    );
+    println!();
 
-            instructions.push_str("```\n");
+    // Build a map: sorted values -> Vec<(file_shortcut, key)>
+    let mut value_to_keys: HashMap<Vec<(String, String)>, Vec<(String, String)>> = HashMap::new();
+    let mut files_processed = 0;
+    let mut total_keys = 0;
 
-            instructions.push_str("\n### Important Notes\n\n");
-            instructions.push_str(
-                "- **Always specify `--target <shortcut>`** when working with translations\n",
-            );
-            instructions.push_str("- Each file has its own record type and translation set\n");
-            instructions.push_str("- Use `elm-i18n status` to see all available shortcuts\n");
+    for (shortcut, file_config) in files {
+        if !file_config.path.exists() {
+            println!("  {} Skipping {} (file not found)", "⚠".yellow(), shortcut);
+            continue;
         }
-    }
 
-    instructions.push_str("\n### Additional Commands\n\n");
-    instructions.push_str("```bash\n");
-    instructions.push_str("# Show current configuration\n");
-    instructions.push_str("elm-i18n status\n\n");
-    instructions.push_str("# Find and remove unused translations\n");
-    if config.is_multi_file() {
-        if let Config::MultiFile { files, .. } = config {
-            if let Some((shortcut, _)) = files.iter().next() {
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} remove-unused --confirm\n\n",
-                    shortcut
-                ));
+        let parse_result = parse_i18n_file_with_record_name(
+            &file_config.path,
+            &file_config.record_name,
+            languages,
+        )?;
+        files_processed += 1;
+
+        for (key, translation) in &parse_result.translations {
+            if translation.type_signature.is_some() {
+                continue;
             }
+
+            total_keys += 1;
+            let mut sorted_values: Vec<(String, String)> = translation
+                .values
+                .iter()
+                .map(|(lang, value)| (lang.clone(), value.clone()))
+                .collect();
+            sorted_values.sort();
+            value_to_keys
+                .entry(sorted_values)
+                .or_default()
+                .push((shortcut.clone(), key.clone()));
         }
-    } else {
-        instructions.push_str("elm-i18n remove-unused --confirm\n\n");
     }
-    instructions.push_str("# Add translation and replace hardcoded strings\n");
-    if config.is_multi_file() {
-        if let Config::MultiFile { files, .. } = config {
-            if let Some((shortcut, _)) = files.iter().next() {
-                instructions.push_str(&format!(
-                    "elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\" --replace\n",
-                    shortcut
-                ));
-            }
-        }
-    } else {
-        instructions.push_str("elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\" --replace\n");
+
+    println!(
+        "  Processed {} files with {} translation keys",
+        files_processed, total_keys
+    );
+    println!();
+
+    // Filter to entries that span multiple files
+    let cross_file_duplicates: Vec<_> = value_to_keys
+        .into_iter()
+        .filter(|(_, keys)| {
+            let unique_files: std::collections::HashSet<_> = keys.iter().map(|(f, _)| f).collect();
+            unique_files.len() > 1
+        })
+        .collect();
+
+    if cross_file_duplicates.is_empty() {
+        println!("{} No cross-file duplicate translations found", "✓".green());
+        return Ok(());
     }
-    instructions.push_str("```\n");
 
-    instructions.push_str("\n### Key Naming Conventions\n\n");
-    instructions.push_str("- Use camelCase for keys (e.g., `welcomeMessage`, `userProfile`)\n");
-    instructions.push_str("- Keys cannot contain dots (.) as they're reserved for access syntax\n");
-    instructions.push_str("- Elm reserved words will automatically get an underscore suffix\n");
+    let mut duplicates = cross_file_duplicates;
+    duplicates.sort_by(|a, b| {
+        let a_files: std::collections::HashSet<_> = a.1.iter().map(|(f, _)| f).collect();
+        let b_files: std::collections::HashSet<_> = b.1.iter().map(|(f, _)| f).collect();
+        b_files
+            .len()
+            .cmp(&a_files.len())
+            .then_with(|| b.1.len().cmp(&a.1.len()))
+            .then_with(|| a.0.cmp(&b.0))
+    });
 
-    instructions
-}
+    let total_duplicate_keys: usize = duplicates.iter().map(|(_, keys)| keys.len()).sum();
 
-/// Handle the status command
-fn handle_status() -> Result<()> {
-    println!("{} Configuration Status", "🔧".blue());
+    println!(
+        "{} Found {} cross-file duplicate group{}:",
+        "📋".blue(),
+        duplicates.len(),
+        if duplicates.len() == 1 { "" } else { "s" }
+    );
     println!();
 
-    match Config::load()? {
-        Some(config) => match &config {
-            Config::SingleFile {
-                file,
-                record_name,
-                languages,
-                source_dir,
-                ..
-            } => {
-                println!("Mode: {}", "Single-file".green());
-                println!("File: {}", file.display());
-                println!("Record Type: {}", record_name.yellow());
-                println!("Languages: {}", languages.join(", "));
-                println!("Source Directory: {}", source_dir.display());
-                println!();
-                println!("Usage example:");
-                println!("  elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"");
-            }
-            Config::MultiFile {
-                files,
-                languages,
-                source_dir,
-                ..
-            } => {
-                println!("Mode: {}", "Multi-file".green());
-                println!("Languages: {}", languages.join(", "));
-                println!("Source Directory: {}", source_dir.display());
-                println!();
-                println!("Available shortcuts:");
+    for (values, mut keys) in duplicates {
+        keys.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
 
-                let shortcuts = config.get_shortcuts();
-                for (shortcut, path) in &shortcuts {
-                    if let Some(file_config) = files.get(shortcut) {
-                        println!(
-                            "  {} → {}",
-                            format!("--target {}", shortcut).yellow(),
-                            path.display()
-                        );
-                        println!("       Record Type: {}", file_config.record_name.cyan());
-                    }
-                }
+        let display: Vec<String> = values
+            .iter()
+            .map(|(_, value)| truncate_for_display(value, 40))
+            .collect();
 
-                println!();
-                println!("Usage example:");
-                if let Some((shortcut, _)) = shortcuts.first() {
-                    println!(
-                        "  elm-i18n --target {} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"",
-                        shortcut
-                    );
-                }
-            }
-        },
-        None => {
-            println!("{} No configuration found!", "⚠".yellow());
-            println!();
-            println!(
-                "Run {} to create a configuration file.",
-                "elm-i18n setup".green()
-            );
+        let mut current_file = String::new();
+        println!("  {} {}:", "•".green(), display.join(" / "));
+        for (file_shortcut, key) in &keys {
+            if file_shortcut != &current_file {
+                current_file = file_shortcut.clone();
+                println!("    [{}]", file_shortcut.cyan());
+            }
+            println!("      - {}", key.yellow());
         }
+        println!();
     }
 
-    Ok(())
-}
+    println!(
+        "{} {} keys across files share the same translations",
+        "✓".green(),
+        total_duplicate_keys
+    );
+    println!("   Consider consolidating into a shared I18n module");
 
-/// Handle the version command
-fn handle_version() -> Result<()> {
-    println!("elm-i18n v{}", env!("CARGO_PKG_VERSION"));
-    println!("CLI tool for managing Elm I18n translations");
-    println!();
-    println!("New in v0.5.0:");
-    println!("  • Configuration file support ({})", config_file_path());
-    println!("  • Multi-file translation management");
-    println!("  • Custom shortcuts for quick file access");
-    println!("  • Run 'elm-i18n setup' to create configuration");
-    println!();
-    println!("New in v0.4.0:");
-    println!("  • Added 'list' command to view all translations");
-    println!("  • Support for --verbose to see full translation values");
-    println!("  • Filter translations with --filter option");
     Ok(())
 }
 
-/// Handle the setup command
-fn handle_setup() -> Result<()> {
-    if config_exists() {
-        eprintln!(
-            "{} Configuration file already exists: {}",
-            "✗".red(),
-            config_file_path()
+fn handle_shared_values(
+    file: &Path,
+    record_name: &str,
+    languages: &[String],
+    suppress: bool,
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
         );
-        eprintln!("Delete it first if you want to reconfigure.");
-        std::process::exit(1);
+        exit(1);
     }
 
-    println!("{} Welcome to elm-i18n setup!", "🎉".blue());
-    println!();
     println!(
-        "This will create a {} configuration file.",
-        config_file_path()
+        "{} Scanning for values shared by multiple languages within the same key...",
+        "🔍".blue()
     );
-    println!();
 
-    // Ask for mode
-    print!("Choose translation mode:\n");
-    print!("  1) Single-file mode (one I18n.elm file)\n");
-    print!("  2) Multi-file mode (separate files for different parts)\n");
-    print!("\nSelect mode [1-2]: ");
-    io::stdout().flush()?;
+    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+    let findings = find_keys_with_shared_language_values(&parse_result.translations, languages);
+    let suppressed_path = suppressed_entries_path();
+    let suppressions = load_suppressed_entries(&suppressed_path)?;
+    let (visible_findings, suppressed_groups) =
+        filter_suppressed_shared_values(file, findings, &suppressions);
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let mode_choice = input.trim();
+    if suppress {
+        suppress_shared_values(
+            &suppressed_path,
+            collect_shared_value_suppressions(file, &visible_findings),
+            suppressed_groups,
+        )?;
+        return Ok(());
+    }
 
-    let config = if mode_choice == "2" {
-        setup_multi_file_config()?
-    } else {
-        setup_single_file_config()?
-    };
+    print_shared_value_findings(&visible_findings, suppressed_groups);
 
-    config.save()?;
+    Ok(())
+}
 
-    println!();
+fn handle_shared_values_cross_file(
+    files: &std::collections::HashMap<String, FileConfig>,
+    languages: &[String],
+    suppress: bool,
+) -> Result<()> {
     println!(
-        "{} Created {} configuration file",
-        "✓".green(),
-        config_file_path()
+        "{} Scanning for values shared by multiple languages within the same key across all files...",
+        "🔍".blue()
     );
+    println!();
 
-    if config.is_multi_file() {
-        println!();
-        println!("Available shortcuts:");
-        for (shortcut, path) in config.get_shortcuts() {
-            println!(
-                "  {} → {}",
-                format!("--{}", shortcut).yellow(),
-                path.display()
-            );
-        }
-        println!();
-        println!("Example usage:");
-        if let Some((shortcut, _)) = config.get_shortcuts().first() {
-            println!(
-                "  elm-i18n --{} add myKey -t en=\"Hello\" -t fr=\"Bonjour\"",
-                shortcut
-            );
+    let suppressed_path = suppressed_entries_path();
+    let suppressions = load_suppressed_entries(&suppressed_path)?;
+    let mut all_findings = Vec::new();
+    let mut suppressed_groups = 0;
+    let mut files_processed = 0;
+
+    for (shortcut, file_config) in files {
+        if !file_config.path.exists() {
+            println!("  {} Skipping {} (file not found)", "⚠".yellow(), shortcut);
+            continue;
         }
-    } else {
-        println!();
-        println!("Example usage:");
-        println!("  elm-i18n add myKey -t en=\"Hello\" -t fr=\"Bonjour\"");
+
+        let parse_result = parse_i18n_file_with_record_name(
+            &file_config.path,
+            &file_config.record_name,
+            languages,
+        )?;
+        files_processed += 1;
+
+        let findings = find_keys_with_shared_language_values(&parse_result.translations, languages);
+        let (visible_findings, file_suppressed_groups) =
+            filter_suppressed_shared_values(&file_config.path, findings, &suppressions);
+        suppressed_groups += file_suppressed_groups;
+
+        all_findings.extend(visible_findings.into_iter().map(|entry| {
+            FileKeySharedLanguageValues {
+                file_shortcut: shortcut.clone(),
+                file_path: file_config.path.clone(),
+                key: entry.key,
+                groups: entry.groups,
+            }
+        }));
+    }
+
+    println!("  Processed {} files", files_processed);
+    println!();
+
+    all_findings.sort_by(|a, b| {
+        a.file_shortcut
+            .cmp(&b.file_shortcut)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    if suppress {
+        let entries = collect_cross_file_shared_value_suppressions(&all_findings);
+        suppress_shared_values(&suppressed_path, entries, suppressed_groups)?;
+        return Ok(());
     }
 
+    print_cross_file_shared_value_findings(&all_findings, suppressed_groups);
+
     Ok(())
 }
 
-/// Setup single-file configuration
-fn setup_single_file_config() -> Result<Config> {
-    println!();
-    print!("Path to I18n.elm file [src/I18n.elm]: ");
-    io::stdout().flush()?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SharedLanguageValueGroup {
+    value: String,
+    languages: Vec<String>,
+}
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let file_path = if input.trim().is_empty() {
-        PathBuf::from("src/I18n.elm")
-    } else {
-        PathBuf::from(input.trim())
-    };
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeySharedLanguageValues {
+    key: String,
+    groups: Vec<SharedLanguageValueGroup>,
+}
 
-    print!("Record name [Translations]: ");
-    io::stdout().flush()?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileKeySharedLanguageValues {
+    file_shortcut: String,
+    file_path: PathBuf,
+    key: String,
+    groups: Vec<SharedLanguageValueGroup>,
+}
 
-    input.clear();
-    io::stdin().read_line(&mut input)?;
-    let record_name = if input.trim().is_empty() {
-        "Translations".to_string()
-    } else {
-        input.trim().to_string()
-    };
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct SuppressedEntry {
+    check: String,
+    file_path: String,
+    key: String,
+    languages: Vec<String>,
+    value: String,
+}
 
-    print!("Source directory [src]: ");
-    io::stdout().flush()?;
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SuppressedStore {
+    #[serde(default)]
+    entries: Vec<SuppressedEntry>,
+}
 
-    input.clear();
-    io::stdin().read_line(&mut input)?;
-    let source_dir = if input.trim().is_empty() {
-        PathBuf::from("src")
-    } else {
-        PathBuf::from(input.trim())
-    };
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct LocalStateConfig {
+    #[serde(default = "default_local_state_version")]
+    version: u32,
+}
 
-    print!("Languages (comma-separated) [en,fr]: ");
-    io::stdout().flush()?;
+impl Default for LocalStateConfig {
+    fn default() -> Self {
+        Self {
+            version: default_local_state_version(),
+        }
+    }
+}
 
-    input.clear();
-    io::stdin().read_line(&mut input)?;
-    let languages = if input.trim().is_empty() {
-        vec!["en".to_string(), "fr".to_string()]
-    } else {
-        input
-            .trim()
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect()
-    };
+fn default_local_state_version() -> u32 {
+    1
+}
+
+fn find_keys_with_shared_language_values(
+    translations: &std::collections::HashMap<String, Translation>,
+    languages: &[String],
+) -> Vec<KeySharedLanguageValues> {
+    let mut keys_with_shared_values = Vec::new();
+
+    for (key, translation) in translations {
+        let groups = find_shared_language_value_groups(&translation.values, languages);
+        if !groups.is_empty() {
+            keys_with_shared_values.push(KeySharedLanguageValues {
+                key: key.clone(),
+                groups,
+            });
+        }
+    }
 
-    Ok(Config::SingleFile {
-        elm_i18n_version: env!("CARGO_PKG_VERSION").to_string(),
-        languages,
-        source_dir,
-        file: file_path,
-        record_name,
-    })
+    keys_with_shared_values.sort_by(|a, b| a.key.cmp(&b.key));
+    keys_with_shared_values
 }
 
-/// Setup multi-file configuration
-fn setup_multi_file_config() -> Result<Config> {
-    use std::collections::HashMap;
+fn find_shared_language_value_groups(
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) -> Vec<SharedLanguageValueGroup> {
+    let mut value_to_languages: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
 
-    println!();
-    print!("Source directory [src]: ");
-    io::stdout().flush()?;
+    for (lang, value) in values {
+        if value.trim().is_empty() {
+            continue;
+        }
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let source_dir = if input.trim().is_empty() {
-        PathBuf::from("src")
-    } else {
-        PathBuf::from(input.trim())
-    };
+        value_to_languages
+            .entry(value.clone())
+            .or_default()
+            .push(lang.clone());
+    }
 
-    print!("Languages (comma-separated) [en,fr]: ");
-    io::stdout().flush()?;
+    let mut groups = Vec::new();
 
-    input.clear();
-    io::stdin().read_line(&mut input)?;
-    let languages = if input.trim().is_empty() {
-        vec!["en".to_string(), "fr".to_string()]
-    } else {
-        input
-            .trim()
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect()
-    };
+    for (value, mut langs) in value_to_languages {
+        if langs.len() < 2 {
+            continue;
+        }
 
-    let mut files = HashMap::new();
+        langs.sort_by(|a, b| {
+            language_sort_index(a, languages)
+                .cmp(&language_sort_index(b, languages))
+                .then_with(|| a.cmp(b))
+        });
 
-    println!();
-    println!("Now let's configure your translation files.");
-    println!("Enter shortcuts and file paths (empty shortcut to finish):");
+        groups.push(SharedLanguageValueGroup {
+            value,
+            languages: langs,
+        });
+    }
 
-    loop {
-        println!();
-        print!("Shortcut (e.g., 'app', 'landing', 'admin'): ");
-        io::stdout().flush()?;
+    groups.sort_by(|a, b| {
+        b.languages
+            .len()
+            .cmp(&a.languages.len())
+            .then_with(|| a.value.cmp(&b.value))
+            .then_with(|| a.languages.cmp(&b.languages))
+    });
 
-        input.clear();
-        io::stdin().read_line(&mut input)?;
-        let shortcut = input.trim().to_string();
+    groups
+}
 
-        if shortcut.is_empty() {
-            if files.is_empty() {
-                println!("{} At least one file must be configured", "⚠".yellow());
-                continue;
-            }
-            break;
-        }
+fn language_sort_index(lang: &str, languages: &[String]) -> usize {
+    languages
+        .iter()
+        .position(|configured| configured == lang)
+        .unwrap_or(usize::MAX)
+}
 
-        print!("File path (e.g., 'src/I18n/App.elm'): ");
-        io::stdout().flush()?;
+fn format_language_codes(languages: &[String]) -> String {
+    languages
+        .iter()
+        .map(|lang| lang.to_uppercase())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
-        input.clear();
-        io::stdin().read_line(&mut input)?;
-        let path = PathBuf::from(input.trim());
+fn truncate_for_display(value: &str, max_chars: usize) -> String {
+    let char_count = value.chars().count();
+    if char_count <= max_chars {
+        return value.to_string();
+    }
 
-        print!("Record name (e.g., 'AppTranslations'): ");
-        io::stdout().flush()?;
+    if max_chars <= 3 {
+        return ".".repeat(max_chars);
+    }
 
-        input.clear();
-        io::stdin().read_line(&mut input)?;
-        let record_name = input.trim().to_string();
+    let truncated: String = value.chars().take(max_chars - 3).collect();
+    format!("{}...", truncated)
+}
 
-        files.insert(shortcut.clone(), FileConfig { path, record_name });
+fn compact_value_for_display(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-        println!("{} Added: --{}", "✓".green(), shortcut);
+fn shared_values_summary(total_visible_groups: usize) -> String {
+    match total_visible_groups {
+        0 => "I found no errors!".to_string(),
+        1 => "I found 1 error!".to_string(),
+        n => format!("I found {} errors!", n),
     }
-
-    Ok(Config::MultiFile {
-        elm_i18n_version: env!("CARGO_PKG_VERSION").to_string(),
-        languages,
-        source_dir,
-        files,
-    })
 }
 
-fn handle_add(
-    file: &PathBuf,
-    key: &str,
-    values: &std::collections::HashMap<String, String>,
-    is_function: bool,
-    type_sig: Option<String>,
-    replace: bool,
-    src_dir: &PathBuf,
-    record_name: &str,
-    languages: &[String],
-) -> Result<()> {
-    // Check if file exists
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        eprintln!(
-            "{} Run 'elm-i18n init' to create a new I18n.elm file",
-            "ℹ".blue()
-        );
-        std::process::exit(1);
+fn suppressed_errors_summary(suppressed_groups: usize) -> String {
+    match suppressed_groups {
+        0 => String::new(),
+        1 => "There is still 1 suppressed error.".to_string(),
+        n => format!("There are still {} suppressed errors.", n),
     }
+}
 
-    // Check if key already exists
-    match check_key_exists_with_record_name(file, key, record_name, languages)? {
-        Some(existing) => {
-            println!(
-                "{} Translation '{}' already exists:",
-                "ℹ".blue(),
-                key.yellow()
-            );
-            for lang in languages {
-                if let Some(val) = existing.values.get(lang) {
-                    println!("  {}: {}", lang.to_uppercase().green(), val);
-                }
-            }
-            println!();
-            println!(
-                "The existing translations might be sufficient. Consider using a different key."
-            );
-        }
-        None => {
-            // Add the translation
-            let translation = Translation {
-                key: key.to_string(),
-                values: values.clone(),
-                is_function,
-                type_signature: type_sig,
-            };
-
-            add_translation_with_record_name(file, &translation, record_name, languages)?;
-
-            println!(
-                "{} Added translation '{}' to {}",
-                "✓".green(),
-                key.yellow(),
-                file.display()
-            );
-
-            if !is_function {
-                for lang in languages {
-                    if let Some(val) = values.get(lang) {
-                        println!("  {}: {}", lang.to_uppercase().green(), val);
-                    }
-                }
-            }
+fn local_state_config_path() -> PathBuf {
+    PathBuf::from(LOCAL_CONFIG_FILE)
+}
 
-            // Handle string replacement if requested
-            if replace && !is_function {
-                println!();
-                println!(
-                    "{} Searching for hardcoded strings to replace...",
-                    "🔍".blue()
-                );
+fn suppressed_entries_path() -> PathBuf {
+    PathBuf::from(LOCAL_SUPPRESSED_FILE)
+}
 
-                let search_strings: Vec<&str> = values.values().map(|s| s.as_str()).collect();
-                let matches = find_string_occurrences(src_dir, &search_strings)?;
+fn format_local_path(path: &Path) -> String {
+    format!("./{}", path.display())
+}
 
-                if matches.is_empty() {
-                    println!("{} No hardcoded strings found to replace", "ℹ".blue());
-                } else {
-                    // Show what will be replaced for each language
-                    for (lang, value) in values {
-                        let lang_matches: Vec<_> = matches
-                            .iter()
-                            .filter(|m| m.line_content.contains(&format!(r#""{}""#, value)))
-                            .collect();
+fn ensure_local_state_config(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
 
-                        if !lang_matches.is_empty() {
-                            println!();
-                            println!(
-                                "{} Found {} occurrences of \"{}\" ({}):",
-                                "✓".green(),
-                                lang_matches.len(),
-                                value,
-                                lang.to_uppercase()
-                            );
-                            for mat in lang_matches.iter().take(3) {
-                                println!("  {}:{}:", mat.file_path.display(), mat.line_number);
-                                println!("    {}", mat.line_content.trim());
-                            }
-                            if lang_matches.len() > 3 {
-                                println!("  ... and {} more", lang_matches.len() - 3);
-                            }
-                        }
-                    }
+    if path.exists() {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let _: LocalStateConfig = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        return Ok(());
+    }
 
-                    // Perform replacements
-                    println!();
-                    println!("{} Replacing strings with t.{}...", "🔄".blue(), key);
-                    replace_strings(&matches, key, "I18n")?;
+    let content = serde_json::to_string_pretty(&LocalStateConfig::default())
+        .context("Failed to serialize local state config")?;
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
 
-                    println!(
-                        "{} Replaced {} occurrences across {} file(s)",
-                        "✓".green(),
-                        matches.len(),
-                        {
-                            let unique_files: std::collections::HashSet<_> =
-                                matches.iter().map(|m| &m.file_path).collect();
-                            unique_files.len()
-                        }
-                    );
-                }
-            }
-        }
+fn load_suppressed_entries(path: &Path) -> Result<SuppressedStore> {
+    if !path.exists() {
+        return Ok(SuppressedStore::default());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut store: SuppressedStore = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    normalize_suppressed_entries(&mut store);
+    Ok(store)
+}
+
+fn save_suppressed_entries(path: &Path, store: &SuppressedStore) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
     }
 
+    let content =
+        serde_json::to_string_pretty(store).context("Failed to serialize suppressed entries")?;
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
     Ok(())
 }
 
-fn handle_check(file: &PathBuf, key: &str, record_name: &str, languages: &[String]) -> Result<()> {
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+fn normalize_suppressed_entries(store: &mut SuppressedStore) {
+    for entry in &mut store.entries {
+        entry.languages.sort();
     }
 
-    match check_key_exists_with_record_name(file, key, record_name, languages)? {
-        Some(translation) => {
-            println!("{} Translation '{}' exists:", "✓".green(), key.yellow());
-            for lang in languages {
-                if let Some(val) = translation.values.get(lang) {
-                    println!("  {}: {}", lang.to_uppercase().green(), val);
-                }
-            }
+    store.entries.sort_by(|a, b| {
+        a.check
+            .cmp(&b.check)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then_with(|| a.key.cmp(&b.key))
+            .then_with(|| a.languages.cmp(&b.languages))
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    store.entries.dedup();
+}
 
-            if translation.is_function {
-                if let Some(type_sig) = translation.type_signature {
-                    println!("  {}: {}", "Type".cyan(), type_sig);
-                }
+fn normalize_file_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn is_shared_values_suppressed(
+    suppressions: &SuppressedStore,
+    file_path: &str,
+    key: &str,
+    _group: &SharedLanguageValueGroup,
+) -> bool {
+    suppressions.entries.iter().any(|entry| {
+        entry.check == SHARED_VALUES_CHECK_NAME && entry.file_path == file_path && entry.key == key
+    })
+}
+
+fn filter_suppressed_shared_values(
+    file: &Path,
+    findings: Vec<KeySharedLanguageValues>,
+    suppressions: &SuppressedStore,
+) -> (Vec<KeySharedLanguageValues>, usize) {
+    let file_path = normalize_file_path(file);
+    let mut filtered_findings = Vec::new();
+    let mut suppressed_groups = 0;
+
+    for finding in findings {
+        let KeySharedLanguageValues { key, groups } = finding;
+        let mut visible_groups = Vec::new();
+
+        for group in groups {
+            if is_shared_values_suppressed(suppressions, &file_path, &key, &group) {
+                suppressed_groups += 1;
+            } else {
+                visible_groups.push(group);
             }
         }
-        None => {
-            println!("{} Translation '{}' not found", "✗".red(), key.yellow());
+
+        if !visible_groups.is_empty() {
+            filtered_findings.push(KeySharedLanguageValues {
+                key,
+                groups: visible_groups,
+            });
         }
     }
 
-    Ok(())
+    (filtered_findings, suppressed_groups)
 }
 
-fn handle_init(file: &PathBuf, languages: &str, record_name: &str) -> Result<()> {
-    if file.exists() {
-        eprintln!("{} File already exists: {}", "✗".red(), file.display());
-        eprintln!("Remove it first if you want to reinitialize.");
-        std::process::exit(1);
+fn collect_shared_value_suppressions(
+    file: &Path,
+    findings: &[KeySharedLanguageValues],
+) -> Vec<SuppressedEntry> {
+    let file_path = normalize_file_path(file);
+    let mut entries = Vec::new();
+
+    for finding in findings {
+        for group in &finding.groups {
+            entries.push(SuppressedEntry {
+                check: SHARED_VALUES_CHECK_NAME.to_string(),
+                file_path: file_path.clone(),
+                key: finding.key.clone(),
+                languages: group.languages.clone(),
+                value: group.value.clone(),
+            });
+        }
     }
 
-    let langs: Vec<String> = languages
-        .split(',')
-        .map(|s| s.trim().to_lowercase())
-        .collect();
+    entries
+}
 
-    let template = get_i18n_template_with_record_name(&langs, record_name);
-    create_i18n_file(file, &template)?;
+fn collect_cross_file_shared_value_suppressions(
+    findings: &[FileKeySharedLanguageValues],
+) -> Vec<SuppressedEntry> {
+    let mut entries = Vec::new();
 
-    println!(
-        "{} Created {} with basic structure",
-        "✓".green(),
-        file.display()
-    );
-    println!("Languages: {}", langs.join(", "));
+    for finding in findings {
+        for group in &finding.groups {
+            entries.push(SuppressedEntry {
+                check: SHARED_VALUES_CHECK_NAME.to_string(),
+                file_path: normalize_file_path(&finding.file_path),
+                key: finding.key.clone(),
+                languages: group.languages.clone(),
+                value: group.value.clone(),
+            });
+        }
+    }
 
-    Ok(())
+    entries
 }
 
-fn handle_remove(file: &PathBuf, key: &str, record_name: &str, languages: &[String]) -> Result<()> {
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+fn suppress_shared_values(
+    suppressed_path: &Path,
+    new_entries: Vec<SuppressedEntry>,
+    already_suppressed_groups: usize,
+) -> Result<()> {
+    let config_path = local_state_config_path();
+    ensure_local_state_config(&config_path)?;
+
+    if new_entries.is_empty() {
+        println!("{} No new shared-value findings to suppress", "✓".green());
+        if already_suppressed_groups > 0 {
+            println!("{}", suppressed_errors_summary(already_suppressed_groups));
+        }
+        return Ok(());
     }
 
-    // Check if key exists first
-    match check_key_exists_with_record_name(file, key, record_name, languages)? {
-        Some(translation) => {
-            // Show what will be removed
-            println!("{} Removing translation '{}':", "ℹ".blue(), key.yellow());
-            for lang in languages {
-                if let Some(val) = translation.values.get(lang) {
-                    println!("  {}: {}", lang.to_uppercase().green(), val);
-                }
-            }
-            println!();
+    let mut store = load_suppressed_entries(suppressed_path)?;
+    let mut existing_entries: std::collections::HashSet<_> =
+        store.entries.iter().cloned().collect();
+    let mut added_entries = 0;
 
-            // Remove the translation
-            match remove_translation_with_record_name(file, key, record_name, languages) {
-                Ok(_) => {
-                    println!(
-                        "{} Removed translation '{}' from {}",
-                        "✓".green(),
-                        key.yellow(),
-                        file.display()
-                    );
-                }
-                Err(e) => {
-                    eprintln!("{} Failed to remove translation: {}", "✗".red(), e);
-                    std::process::exit(1);
-                }
-            }
-        }
-        None => {
-            println!("{} Translation '{}' not found", "✗".red(), key.yellow());
-            std::process::exit(1);
+    for entry in new_entries {
+        if existing_entries.insert(entry.clone()) {
+            store.entries.push(entry);
+            added_entries += 1;
         }
     }
 
-    Ok(())
-}
+    normalize_suppressed_entries(&mut store);
+    save_suppressed_entries(suppressed_path, &store)?;
 
-fn handle_remove_unused(
-    file: &PathBuf,
-    src_dir: &PathBuf,
-    confirm: bool,
-    record_name: &str,
-    languages: &[String],
-) -> Result<()> {
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+    println!(
+        "{} Suppressed {} error{} in {}",
+        "✓".green(),
+        added_entries,
+        if added_entries == 1 { "" } else { "s" },
+        format_local_path(suppressed_path).cyan()
+    );
+    println!(
+        "{} Local state config is stored in {}",
+        "ℹ".blue(),
+        format_local_path(&config_path).cyan()
+    );
+    if already_suppressed_groups > 0 {
+        println!("{}", suppressed_errors_summary(already_suppressed_groups));
     }
 
-    println!("{} Scanning for unused translation keys...", "🔍".blue());
+    Ok(())
+}
 
-    // Find all unused keys
-    let unused_keys = find_unused_keys(file, src_dir, record_name, languages)?;
+fn print_shared_value_findings(findings: &[KeySharedLanguageValues], suppressed_groups: usize) {
+    let total_groups: usize = findings.iter().map(|entry| entry.groups.len()).sum();
 
-    if unused_keys.is_empty() {
-        println!("{} All translation keys are in use!", "✓".green());
-        return Ok(());
+    println!();
+    println!("{}", shared_values_summary(total_groups));
+    if suppressed_groups > 0 {
+        println!();
+        println!("{}", suppressed_errors_summary(suppressed_groups));
+    }
+
+    if findings.is_empty() {
+        return;
     }
 
-    // Show unused keys
     println!();
-    println!(
-        "{} Found {} unused translation keys:",
-        "⚠".yellow(),
-        unused_keys.len()
-    );
-    for key in &unused_keys {
-        println!("  • {}", key.yellow());
+
+    for entry in findings {
+        println!("  {} {}:", "•".green(), entry.key.yellow());
+        for group in &entry.groups {
+            println!(
+                "    - {}: {}",
+                format_language_codes(&group.languages).cyan(),
+                truncate_for_display(&compact_value_for_display(&group.value), 50)
+            );
+        }
+        println!();
     }
+}
 
-    if !confirm {
+fn print_cross_file_shared_value_findings(
+    findings: &[FileKeySharedLanguageValues],
+    suppressed_groups: usize,
+) {
+    let total_groups: usize = findings.iter().map(|entry| entry.groups.len()).sum();
+
+    println!("{}", shared_values_summary(total_groups));
+    if suppressed_groups > 0 {
         println!();
-        println!(
-            "{} To remove these keys, run with --confirm flag:",
-            "ℹ".blue()
-        );
-        println!("  elm-i18n remove-unused --confirm");
-        return Ok(());
+        println!("{}", suppressed_errors_summary(suppressed_groups));
     }
 
-    // Remove the unused keys
-    println!();
-    println!("{} Removing unused keys...", "🗑".red());
-
-    for key in &unused_keys {
-        match remove_translation_with_record_name(file, key, record_name, languages) {
-            Ok(_) => {
-                println!("  {} Removed: {}", "✓".green(), key);
-            }
-            Err(e) => {
-                eprintln!("  {} Failed to remove {}: {}", "✗".red(), key, e);
-            }
-        }
+    if findings.is_empty() {
+        return;
     }
 
     println!();
-    println!(
-        "{} Removed {} unused translation keys",
-        "✓".green(),
-        unused_keys.len()
-    );
 
-    Ok(())
+    for entry in findings {
+        println!(
+            "  {} [{}] {}:",
+            "•".green(),
+            entry.file_shortcut.cyan(),
+            entry.key.yellow()
+        );
+        for group in &entry.groups {
+            println!(
+                "    - {}: {}",
+                format_language_codes(&group.languages).cyan(),
+                truncate_for_display(&compact_value_for_display(&group.value), 50)
+            );
+        }
+        println!();
+    }
 }
 
-fn handle_list(
-    file: &PathBuf,
-    verbose: bool,
-    filter: &Option<String>,
-    record_name: &str,
+/// Handle the lint command: report problems in one or more translation files
+fn handle_lint(
+    files: &[(PathBuf, String)],
     languages: &[String],
+    lint_config: &LintConfig,
+    fix: bool,
+    dry_run: bool,
+    output: LintOutput,
+    fail_on_warning: bool,
+    stale: bool,
 ) -> Result<()> {
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
-    }
-
-    // Parse the I18n file
-    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
-    let mut translations: Vec<_> = parse_result.translations.into_iter().collect();
+    let mut errors = 0;
+    let mut warnings = 0;
+    let text = output == LintOutput::Text;
+    let mut all_findings = Vec::new();
 
-    // Apply filter if provided
-    if let Some(pattern) = filter {
-        let pattern_lower = pattern.to_lowercase();
-        translations.retain(|(key, _)| key.to_lowercase().contains(&pattern_lower));
-    }
+    let freshness = if stale {
+        if !Path::new(FRESHNESS_FILE).exists() {
+            errors::error(format!(
+                "--stale reads {}, which doesn't exist yet",
+                FRESHNESS_FILE
+            ));
+            errors::suggestion("Run an edit with --track-freshness to start tracking");
+            exit(1);
+        }
+        Some(parse_freshness(&std::fs::read_to_string(FRESHNESS_FILE)?)?)
+    } else {
+        None
+    };
+    let stale_severity = lint_config.severity(STALE_TRANSLATION, Severity::Warning);
 
-    // Sort by key
-    translations.sort_by(|a, b| a.0.cmp(&b.0));
+    let glossary = match &lint_config.glossary {
+        Some(path) => Some(load_glossary(path)?),
+        None => None,
+    };
 
-    if translations.is_empty() {
-        if filter.is_some() {
-            println!(
-                "{} No translations found matching '{}'",
-                "✗".red(),
-                filter.as_ref().unwrap().yellow()
+    for (file, record_name) in files {
+        if !file.exists() {
+            // Machine-readable output owns stdout
+            eprintln!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
             );
-        } else {
-            println!("{} No translations found", "✗".red());
+            continue;
         }
-        return Ok(());
-    }
 
-    // Display results
-    println!(
-        "{} Found {} translation{}:",
-        "📋".blue(),
-        translations.len(),
-        if translations.len() == 1 { "" } else { "s" }
-    );
+        if text {
+            println!("{} Linting {}...", "🔍".blue(), file.display());
+        }
 
-    if verbose {
-        println!();
-        for (key, translation) in &translations {
-            println!("  {} {}", "•".green(), key.yellow());
+        let mut source = read_elm_file(file)?;
 
-            // Show type if it's a function
-            if translation.is_function {
-                if let Some(ref type_sig) = translation.type_signature {
-                    println!("    {}: {}", "Type".cyan(), type_sig);
+        if fix {
+            let outcome = fix_source(&source, record_name, languages, lint_config.todo_marker())?;
+            if !outcome.fixes.is_empty() {
+                for description in &outcome.fixes {
+                    let verb = if dry_run { "Would fix" } else { "Fixed" };
+                    println!("  {} {}: {}", "🔧".blue(), verb, description);
                 }
-            }
 
-            // Show translations for each language
-            for lang in languages {
-                if let Some(val) = translation.values.get(lang) {
+                if dry_run {
+                    println!();
+                    print_diff(file, &source, &outcome.content);
+                } else {
+                    let backup_path = backup::write_backup(file, &source)?;
+                    timing::write(file, &outcome.content)
+                        .with_context(|| format!("Failed to write to {}", file.display()))?;
                     println!(
-                        "    {}: {}",
-                        lang.to_uppercase().green(),
-                        if val.contains('\n') {
-                            format!(
-                                "\n{}",
-                                val.lines()
-                                    .map(|line| format!("      {}", line))
-                                    .collect::<Vec<_>>()
-                                    .join("\n")
-                            )
-                        } else {
-                            val.clone()
-                        }
+                        "  {} Backup written to {}",
+                        "ℹ".blue(),
+                        backup_path.display()
                     );
                 }
             }
+            // Report what is left after fixing (what would be left, for --dry-run)
+            source = outcome.content;
+        }
+
+        let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+        let mut findings = lint_translations(
+            &parse_result,
+            &source,
+            languages,
+            lint_config,
+            glossary.as_ref(),
+        );
+        if let Some(freshness) = freshness
+            .as_ref()
+            .filter(|_| stale_severity != Severity::Off)
+        {
+            let name = file.display().to_string();
+            let stale = stale_values(freshness, &name, &parse_result, languages);
+            findings.extend(stale_findings(&parse_result, &stale, stale_severity));
+            finish_findings(&mut findings, &source, languages);
+        }
+        for finding in &findings {
+            match finding.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+                Severity::Off => {}
+            }
+        }
+
+        if !text {
+            all_findings.extend(findings.into_iter().map(|finding| FileFinding {
+                file: file.clone(),
+                finding,
+            }));
+            continue;
+        }
 
+        if findings.is_empty() {
+            println!("  {} No problems found", "✓".green());
             println!();
+            continue;
         }
-    } else {
-        // Simple list
-        for (key, translation) in &translations {
-            let type_info = if translation.is_function {
-                format!(
-                    " ({})",
-                    translation
-                        .type_signature
-                        .as_ref()
-                        .unwrap_or(&"Function".to_string())
-                        .cyan()
-                )
-            } else {
-                " (String)".cyan().to_string()
-            };
 
-            println!("  {} {}{}", "•".green(), key.yellow(), type_info);
+        let mut current_language = None;
+        for finding in &findings {
+            // Findings come grouped by language; print a heading for each group
+            if current_language != Some(&finding.language) {
+                current_language = Some(&finding.language);
+                let heading = finding
+                    .language
+                    .as_ref()
+                    .map(|lang| lang.to_uppercase())
+                    .unwrap_or_else(|| "All languages".to_string());
+                println!();
+                println!("  {}", heading.bold());
+            }
+            print_lint_finding(finding);
         }
+        println!();
     }
 
-    Ok(())
-}
-
-fn handle_duplicates(file: &PathBuf, record_name: &str, languages: &[String]) -> Result<()> {
-    use std::collections::HashMap;
+    match output {
+        LintOutput::Text => println!(
+            "I found {} error{} and {} warning{}.",
+            errors,
+            if errors == 1 { "" } else { "s" },
+            warnings,
+            if warnings == 1 { "" } else { "s" }
+        ),
+        LintOutput::Github => print!("{}", github_annotations(&all_findings)),
+        LintOutput::Sarif => print!("{}", sarif_log(&all_findings)),
+    }
 
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+    if errors > 0 || (fail_on_warning && warnings > 0) {
+        exit(1);
     }
 
-    println!("{} Scanning for duplicate translations...", "🔍".blue());
+    Ok(())
+}
 
-    // Parse the I18n file
-    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+/// Handle `ci`: run every enabled check on `files`, parsing each file once, print
+/// a section per check and fail if any check found an error
+fn handle_ci(
+    config: &Config,
+    files: &[(PathBuf, String)],
+    src_dirs: &[PathBuf],
+    output: LintOutput,
+    languages: &[String],
+) -> Result<()> {
+    let configured = &config.ci().checks;
+    let mut unknown: Vec<&String> = configured
+        .keys()
+        .filter(|name| Check::from_name(name).is_none())
+        .collect();
+    unknown.sort();
+    if let Some(name) = unknown.first() {
+        errors::error_with(
+            Code::Config,
+            format!(
+                "Unknown check '{}' under ci.checks in {}",
+                name, LOCAL_CONFIG_FILE
+            ),
+        );
+        errors::hint(format!(
+            "The checks are {}",
+            CHECKS.map(Check::name).join(", ")
+        ));
+        exit(1);
+    }
+    let severity = |check: Check| {
+        configured
+            .get(check.name())
+            .copied()
+            .unwrap_or(Severity::Error)
+    };
 
-    // Build a map: sorted values -> Vec<key>
-    let mut value_to_keys: HashMap<Vec<(String, String)>, Vec<String>> = HashMap::new();
+    let lint_config = config.lint();
+    let glossary = match &lint_config.glossary {
+        Some(path) if severity(Check::Lint) != Severity::Off => Some(load_glossary(path)?),
+        _ => None,
+    };
+    let locked = if severity(Check::Lock) != Severity::Off && Path::new(LOCK_FILE).exists() {
+        Some(parse_lock(&std::fs::read_to_string(LOCK_FILE)?)?)
+    } else {
+        None
+    };
+    let sources = if severity(Check::Unused) != Severity::Off && !src_dirs.is_empty() {
+        Some(scan_sources(config, files, src_dirs, Vec::new())?)
+    } else {
+        None
+    };
+    let ignore = &config.scan().ignore;
 
-    for (key, translation) in &parse_result.translations {
-        if translation.is_function {
+    let mut findings: Vec<(Check, FileFinding)> = Vec::new();
+    let mut checked = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
             continue;
         }
+        checked.push(file);
 
-        let mut sorted_values: Vec<(String, String)> = translation
-            .values
-            .iter()
-            .map(|(lang, value)| (lang.clone(), value.clone()))
-            .collect();
-        sorted_values.sort();
-        value_to_keys
-            .entry(sorted_values)
-            .or_default()
-            .push(key.clone());
+        let source = read_elm_file(file)?;
+        let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+        for check in CHECKS {
+            let check_severity = severity(check);
+            if check_severity == Severity::Off {
+                continue;
+            }
+            let found = match check {
+                Check::Lint => {
+                    let mut found = lint_translations(
+                        &parse_result,
+                        &source,
+                        languages,
+                        lint_config,
+                        glossary.as_ref(),
+                    );
+                    // untranslated reports TODO markers along with empty values
+                    if severity(Check::Untranslated) != Severity::Off {
+                        found.retain(|finding| finding.rule != TODO_MARKER);
+                    }
+                    ci::capped(found, check_severity)
+                }
+                Check::Untranslated => ci::untranslated(
+                    &parse_result,
+                    languages,
+                    lint_config.todo_marker(),
+                    check_severity,
+                ),
+                Check::Placeholders => ci::placeholders(&parse_result, languages, check_severity),
+                Check::Lock => match &locked {
+                    Some(locked) => ci::lock_changes(
+                        &file.display().to_string(),
+                        &parse_result,
+                        locked,
+                        languages,
+                        check_severity,
+                    ),
+                    None => continue,
+                },
+                Check::Unused => match &sources {
+                    Some(sources) => {
+                        let kept = kept_keys(&source, &parse_result.type_fields);
+                        let keys: Vec<String> = parse_result
+                            .type_fields
+                            .iter()
+                            .map(|field| field.name.clone())
+                            .collect();
+                        let usages = sources.usages(&keys);
+                        let unused: Vec<String> = keys
+                            .into_iter()
+                            .filter(|key| {
+                                usages[key].is_empty()
+                                    && !kept.contains(key)
+                                    && !ignore.iter().any(|p| key_matches_pattern(p, key))
+                            })
+                            .collect();
+                        ci::unused(&parse_result, &unused, check_severity)
+                    }
+                    None => continue,
+                },
+            };
+            findings.extend(found.into_iter().map(|finding| {
+                (
+                    check,
+                    FileFinding {
+                        file: file.clone(),
+                        finding,
+                    },
+                )
+            }));
+        }
     }
 
-    // Filter to only entries with 2+ keys (actual duplicates)
-    let mut duplicates: Vec<_> = value_to_keys
-        .into_iter()
-        .filter(|(_, keys)| keys.len() >= 2)
-        .collect();
-
-    if duplicates.is_empty() {
-        println!();
-        println!("{} No duplicate translations found", "✓".green());
-        return Ok(());
+    let count = |found: &[&FileFinding], severity: Severity| {
+        found
+            .iter()
+            .filter(|f| f.finding.severity == severity)
+            .count()
+    };
+    let all: Vec<&FileFinding> = findings.iter().map(|(_, f)| f).collect();
+    let errors = count(&all, Severity::Error);
+    let warnings = count(&all, Severity::Warning);
+
+    let text = output == LintOutput::Text;
+    let all: Vec<FileFinding> = all.into_iter().cloned().collect();
+    match output {
+        LintOutput::Text => {}
+        LintOutput::Github => print!("{}", github_annotations(&all)),
+        LintOutput::Sarif => print!("{}", sarif_log(&all)),
     }
+    // Machine-readable output owns stdout, so the summary goes to stderr
+    let say = |line: String| {
+        if text {
+            println!("{}", line)
+        } else {
+            eprintln!("{}", line)
+        }
+    };
 
-    duplicates.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
-
-    let total_duplicate_keys: usize = duplicates.iter().map(|(_, keys)| keys.len()).sum();
-    let potential_savings = total_duplicate_keys - duplicates.len();
-
-    println!();
-    println!(
-        "{} Found {} duplicate group{}:",
-        "📋".blue(),
-        duplicates.len(),
-        if duplicates.len() == 1 { "" } else { "s" }
-    );
-    println!();
-
-    for (values, mut keys) in duplicates {
-        keys.sort();
+    say(format!(
+        "{} Checked {}",
+        "🔍".blue(),
+        checked
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+    for check in CHECKS {
+        let skipped = match check {
+            _ if severity(check) == Severity::Off => Some(format!("off in {}", LOCAL_CONFIG_FILE)),
+            Check::Lock if locked.is_none() => Some(format!(
+                "no {}; run `elm-i18n lock` to start checking it",
+                LOCK_FILE
+            )),
+            Check::Unused if sources.is_none() => {
+                Some("pass --src to check which keys the code reads".to_string())
+            }
+            _ => None,
+        };
+        if let Some(reason) = skipped {
+            say(format!(
+                "{}",
+                format!("– {}: {}", check.name(), reason).dimmed()
+            ));
+            continue;
+        }
 
-        let display: Vec<String> = values
+        let found: Vec<&FileFinding> = findings
             .iter()
-            .map(|(_, value)| truncate_for_display(value, 40))
+            .filter(|(c, _)| *c == check)
+            .map(|(_, f)| f)
             .collect();
+        let check_errors = count(&found, Severity::Error);
+        let check_warnings = count(&found, Severity::Warning);
+        let marker = if check_errors > 0 {
+            "✗".red()
+        } else if check_warnings > 0 {
+            "⚠".yellow()
+        } else {
+            "✓".green()
+        };
+        let mut counts = Vec::new();
+        if check_errors > 0 {
+            counts.push(format!(
+                "{} error{}",
+                check_errors,
+                if check_errors == 1 { "" } else { "s" }
+            ));
+        }
+        if check_warnings > 0 {
+            counts.push(format!(
+                "{} warning{}",
+                check_warnings,
+                if check_warnings == 1 { "" } else { "s" }
+            ));
+        }
+        if counts.is_empty() {
+            say(format!("{} {}", marker, check.name()));
+        } else {
+            say(format!(
+                "{} {}: {}",
+                marker,
+                check.name(),
+                counts.join(", ")
+            ));
+        }
 
-        println!("  {} {}:", "•".green(), display.join(" / "));
-        for key in &keys {
-            println!("    - {}", key.yellow());
+        if text {
+            let mut current_file = None;
+            for finding in found {
+                if checked.len() > 1 && current_file != Some(&finding.file) {
+                    current_file = Some(&finding.file);
+                    println!("  {}", finding.file.display().to_string().dimmed());
+                }
+                print_lint_finding(&finding.finding);
+            }
         }
-        println!();
     }
 
-    println!(
-        "{} {} keys could potentially be consolidated into {}",
-        "✓".green(),
-        total_duplicate_keys,
-        total_duplicate_keys - potential_savings
-    );
-
+    say(String::new());
+    say(format!(
+        "I found {} error{} and {} warning{}.",
+        errors,
+        if errors == 1 { "" } else { "s" },
+        warnings,
+        if warnings == 1 { "" } else { "s" }
+    ));
+    if errors > 0 {
+        exit(1);
+    }
     Ok(())
 }
 
-fn handle_duplicates_cross_file(
-    files: &std::collections::HashMap<String, FileConfig>,
+/// Print each key once, sorted, with nothing else on stdout
+fn handle_keys(
+    files: &[(PathBuf, String)],
+    functions_only: bool,
     languages: &[String],
 ) -> Result<()> {
-    use std::collections::HashMap;
-
-    println!(
-        "{} Scanning for duplicate translations across all files...",
-        "🔍".blue()
-    );
-    println!();
+    let keys = sorted_keys(files, functions_only, languages)?;
 
-    // Build a map: sorted values -> Vec<(file_shortcut, key)>
-    let mut value_to_keys: HashMap<Vec<(String, String)>, Vec<(String, String)>> = HashMap::new();
-    let mut files_processed = 0;
-    let mut total_keys = 0;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for key in keys {
+        // A closed pipe (`elm-i18n keys | head`) isn't an error
+        if writeln!(out, "{}", key).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
 
-    for (shortcut, file_config) in files {
-        if !file_config.path.exists() {
-            println!("  {} Skipping {} (file not found)", "⚠".yellow(), shortcut);
+/// The keys of `files`, or only those of functions with `functions_only`
+fn sorted_keys(
+    files: &[(PathBuf, String)],
+    functions_only: bool,
+    languages: &[String],
+) -> Result<std::collections::BTreeSet<String>> {
+    let mut keys = std::collections::BTreeSet::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
             continue;
         }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        keys.extend(
+            parse_result
+                .translations
+                .into_values()
+                .filter(|translation| !functions_only || translation.is_function)
+                .map(|translation| translation.key),
+        );
+    }
+    Ok(keys)
+}
 
-        let parse_result = parse_i18n_file_with_record_name(
-            &file_config.path,
-            &file_config.record_name,
-            languages,
-        )?;
-        files_processed += 1;
-
-        for (key, translation) in &parse_result.translations {
-            if translation.is_function {
-                continue;
-            }
-
-            total_keys += 1;
-            let mut sorted_values: Vec<(String, String)> = translation
-                .values
-                .iter()
-                .map(|(lang, value)| (lang.clone(), value.clone()))
-                .collect();
-            sorted_values.sort();
-            value_to_keys
-                .entry(sorted_values)
-                .or_default()
-                .push((shortcut.clone(), key.clone()));
-        }
+/// The `--src` directories, or the source-directories of elm.json, or the
+/// configured source directory
+fn scan_source_dirs(config: &Config, src_dirs: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    if !src_dirs.is_empty() {
+        return Ok(src_dirs);
     }
+    Ok(
+        elm_source_directories(Path::new("."))?
+            .unwrap_or_else(|| vec![config.source_dir().clone()]),
+    )
+}
 
-    println!(
-        "  Processed {} files with {} translation keys",
-        files_processed, total_keys
-    );
-    println!();
+/// The Elm files `scan` and `usages` search: everything under `src_dirs` except
+/// the translation files and the `ignore_path` globs, on top of scan.ignorePaths
+fn scan_sources(
+    config: &Config,
+    files: &[(PathBuf, String)],
+    src_dirs: &[PathBuf],
+    ignore_path: Vec<String>,
+) -> Result<SourceSet> {
+    if let Some(dir) = src_dirs.iter().find(|dir| !dir.is_dir()) {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("Source directory not found: {}", dir.display()),
+        );
+        exit(1);
+    }
 
-    // Filter to entries that span multiple files
-    let cross_file_duplicates: Vec<_> = value_to_keys
+    let mut skip: Vec<PathBuf> = config
+        .translation_files()
         .into_iter()
-        .filter(|(_, keys)| {
-            let unique_files: std::collections::HashSet<_> = keys.iter().map(|(f, _)| f).collect();
-            unique_files.len() > 1
-        })
+        .map(|(f, _)| f)
         .collect();
+    skip.extend(files.iter().map(|(f, _)| f.clone()));
+    let mut ignore_paths = config.scan().ignore_paths.clone();
+    ignore_paths.extend(ignore_path);
+    SourceSet::load(src_dirs, &skip, &ignore_paths)
+}
 
-    if cross_file_duplicates.is_empty() {
-        println!("{} No cross-file duplicate translations found", "✓".green());
-        return Ok(());
-    }
-
-    let mut duplicates = cross_file_duplicates;
-    duplicates.sort_by(|a, b| {
-        let a_files: std::collections::HashSet<_> = a.1.iter().map(|(f, _)| f).collect();
-        let b_files: std::collections::HashSet<_> = b.1.iter().map(|(f, _)| f).collect();
-        b_files
-            .len()
-            .cmp(&a_files.len())
-            .then_with(|| b.1.len().cmp(&a.1.len()))
-            .then_with(|| a.0.cmp(&b.0))
-    });
-
-    let total_duplicate_keys: usize = duplicates.iter().map(|(_, keys)| keys.len()).sum();
-
-    println!(
-        "{} Found {} cross-file duplicate group{}:",
-        "📋".blue(),
-        duplicates.len(),
-        if duplicates.len() == 1 { "" } else { "s" }
-    );
-    println!();
+fn scanned_message(sources: &SourceSet, src_dirs: &[PathBuf]) -> String {
+    format!(
+        "{} Scanned {} Elm files in {}",
+        "🔍".blue(),
+        sources.files.len(),
+        src_dirs
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
 
-    for (values, mut keys) in duplicates {
-        keys.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+/// Report the keys of `files` that none of `sources` reads, and return how many
+/// there are
+///
+/// The translation files themselves aren't searched, so a key only its own
+/// module reads still counts as unused. Keys matching an `ignore` pattern are
+/// reported separately and never count.
+fn handle_scan(
+    files: &[(PathBuf, String)],
+    sources: &SourceSet,
+    src_dirs: &[PathBuf],
+    ignore: &[String],
+    json: bool,
+    languages: &[String],
+) -> Result<usize> {
+    let mut reports = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        let kept = kept_keys(&read_elm_file(file)?, &parse_result.type_fields);
+        let keys: Vec<String> = parse_result
+            .type_fields
+            .into_iter()
+            .map(|field| field.name)
+            .collect();
+        let usages = sources.usages(&keys);
+        let (ignored, unused): (Vec<String>, Vec<String>) = keys
+            .into_iter()
+            .filter(|key| usages[key].is_empty())
+            .partition(|key| {
+                kept.contains(key) || ignore.iter().any(|p| key_matches_pattern(p, key))
+            });
+        reports.push((file, parse_result.translations.len(), unused, ignored));
+    }
+    let total_unused = reports.iter().map(|(_, _, unused, _)| unused.len()).sum();
 
-        let display: Vec<String> = values
+    if json {
+        let files: Vec<serde_json::Value> = reports
             .iter()
-            .map(|(_, value)| truncate_for_display(value, 40))
+            .map(|(file, keys, unused, ignored)| {
+                serde_json::json!({
+                    "file": file.display().to_string(),
+                    "keys": keys,
+                    "unused": unused,
+                    "ignored": ignored,
+                })
+            })
             .collect();
+        let output = serde_json::json!({ "sourceFiles": sources.files.len(), "files": files });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(total_unused);
+    }
 
-        let mut current_file = String::new();
-        println!("  {} {}:", "•".green(), display.join(" / "));
-        for (file_shortcut, key) in &keys {
-            if file_shortcut != &current_file {
-                current_file = file_shortcut.clone();
-                println!("    [{}]", file_shortcut.cyan());
+    println!("{}", scanned_message(sources, src_dirs));
+    for (file, keys, unused, ignored) in &reports {
+        println!();
+        if unused.is_empty() {
+            println!(
+                "{} {}: all {} keys are used",
+                "✓".green(),
+                file.display(),
+                keys
+            );
+        } else {
+            println!(
+                "{} {}: {} of {} keys are never read",
+                "⚠".yellow(),
+                file.display(),
+                unused.len(),
+                keys
+            );
+            for key in unused {
+                println!("  • {}", key.yellow());
             }
-            println!("      - {}", key.yellow());
         }
-        println!();
+        if !ignored.is_empty() {
+            println!(
+                "  {} Not read but ignored: {}",
+                "ℹ".blue(),
+                ignored.join(", ")
+            );
+        }
     }
 
-    println!(
-        "{} {} keys across files share the same translations",
-        "✓".green(),
-        total_duplicate_keys
-    );
-    println!("   Consider consolidating into a shared I18n module");
-
-    Ok(())
+    if total_unused > 0 {
+        println!();
+        println!(
+            "{} If a key is read through a dynamic lookup, add it to scan.ignore in {} or pass --ignore",
+            "ℹ".blue(),
+            LOCAL_CONFIG_FILE
+        );
+    }
+    Ok(total_unused)
 }
 
-fn handle_shared_values(
-    file: &PathBuf,
-    record_name: &str,
+/// Handle `prune`: remove the keys of `files` nothing in `sources` reads, except
+/// the ones `keep` matches or a `-- elm-i18n:keep` comment marks
+fn handle_prune(
+    files: &[(PathBuf, String)],
+    sources: &SourceSet,
+    src_dirs: &[PathBuf],
+    keep: &[String],
+    yes: bool,
+    dry_run: bool,
     languages: &[String],
-    suppress: bool,
 ) -> Result<()> {
-    if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+    // (file, content, pruned content, removed keys) for each file with keys to remove
+    let mut edits = Vec::new();
+    let mut kept_count = 0;
+    println!("{}", scanned_message(sources, src_dirs));
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        refuse_if_inconsistent(file, record_name, languages)?;
+
+        let content = read_elm_file(file)?;
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        let marked = kept_keys(&content, &parse_result.type_fields);
+        let keys: Vec<String> = parse_result
+            .type_fields
+            .into_iter()
+            .map(|field| field.name)
+            .collect();
+        let usages = sources.usages(&keys);
+        let (kept, unused): (Vec<String>, Vec<String>) = keys
+            .into_iter()
+            .filter(|key| usages[key].is_empty())
+            .partition(|key| {
+                marked.contains(key) || keep.iter().any(|p| key_matches_pattern(p, key))
+            });
+
+        println!();
+        if unused.is_empty() {
+            println!(
+                "{} {}: all {} keys are used",
+                "✓".green(),
+                file.display(),
+                parse_result.translations.len()
+            );
+        } else {
+            println!(
+                "{} {}: {} of {} keys are never read",
+                "⚠".yellow(),
+                file.display(),
+                unused.len(),
+                parse_result.translations.len()
+            );
+            for key in &unused {
+                println!("  • {}", key.yellow());
+            }
+        }
+        if !kept.is_empty() {
+            println!("  {} Kept though not read: {}", "ℹ".blue(), kept.join(", "));
+        }
+        kept_count += kept.len();
+        if unused.is_empty() {
+            continue;
+        }
+
+        let mut pruned = content.clone();
+        for key in &unused {
+            pruned = remove_translation_from_content(&pruned, key, record_name, languages)?;
+        }
+        edits.push((file, content, pruned, unused));
     }
 
+    println!();
+    let total: usize = edits.iter().map(|(_, _, _, keys)| keys.len()).sum();
+    let counts = Summary {
+        removed: total,
+        skipped: kept_count,
+        ..Summary::default()
+    };
+    if edits.is_empty() {
+        println!("{} Nothing to prune", "✓".green());
+        counts.print();
+        return Ok(());
+    }
     println!(
-        "{} Scanning for values shared by multiple languages within the same key...",
-        "🔍".blue()
+        "{} If a key is read through a dynamic lookup, put `{}` above its field in the type alias, add it to scan.ignore in {} or pass --keep",
+        "ℹ".blue(),
+        KEEP_NEXT_LINE,
+        LOCAL_CONFIG_FILE
+    );
+    println!();
+    let lines: usize = edits
+        .iter()
+        .map(|(_, old, new, _)| old.lines().count() - new.lines().count())
+        .sum();
+    let summary = format!(
+        "{} key{} ({} line{})",
+        total,
+        if total == 1 { "" } else { "s" },
+        lines,
+        if lines == 1 { "" } else { "s" }
     );
 
-    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
-    let findings = find_keys_with_shared_language_values(&parse_result.translations, languages);
-    let suppressed_path = suppressed_entries_path();
-    let suppressions = load_suppressed_entries(&suppressed_path)?;
-    let (visible_findings, suppressed_groups) =
-        filter_suppressed_shared_values(file, findings, &suppressions);
-
-    if suppress {
-        suppress_shared_values(
-            &suppressed_path,
-            collect_shared_value_suppressions(file, &visible_findings),
-            suppressed_groups,
-        )?;
+    if dry_run {
+        for (file, content, pruned, _) in &edits {
+            print_diff(file, content, pruned);
+        }
+        println!();
+        println!("{} Would remove {}", "ℹ".blue(), summary);
+        counts.print();
         return Ok(());
     }
 
-    print_shared_value_findings(&visible_findings, suppressed_groups);
+    if !yes {
+        print!("Remove {}? [y/N] ", summary);
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{} Nothing removed", "ℹ".blue());
+            return Ok(());
+        }
+    }
 
+    for (file, content, pruned, keys) in &edits {
+        let backup_path = backup::write_backup(file, content)?;
+        timing::write(file, pruned)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        let lines = content.lines().count() - pruned.lines().count();
+        println!(
+            "{} Removed {} key{} ({} line{}) from {}; the old file is in {}",
+            "✓".green(),
+            keys.len(),
+            if keys.len() == 1 { "" } else { "s" },
+            lines,
+            if lines == 1 { "" } else { "s" },
+            file.display(),
+            backup_path.display()
+        );
+    }
+    if edits.len() > 1 {
+        println!("{} Removed {} in all", "✓".green(), summary);
+    }
+    counts.print();
     Ok(())
 }
 
-fn handle_shared_values_cross_file(
-    files: &std::collections::HashMap<String, FileConfig>,
+/// List where `key` is read, or where every key is read when it's None
+fn handle_usages(
+    files: &[(PathBuf, String)],
+    sources: &SourceSet,
+    src_dirs: &[PathBuf],
+    key: Option<&str>,
+    json: bool,
     languages: &[String],
-    suppress: bool,
 ) -> Result<()> {
-    println!(
-        "{} Scanning for values shared by multiple languages within the same key across all files...",
-        "🔍".blue()
-    );
-    println!();
-
-    let suppressed_path = suppressed_entries_path();
-    let suppressions = load_suppressed_entries(&suppressed_path)?;
-    let mut all_findings = Vec::new();
-    let mut suppressed_groups = 0;
-    let mut files_processed = 0;
-
-    for (shortcut, file_config) in files {
-        if !file_config.path.exists() {
-            println!("  {} Skipping {} (file not found)", "⚠".yellow(), shortcut);
-            continue;
+    let (keys, _) = defined_keys(files, languages)?;
+    let usages = match key {
+        Some(key) => {
+            if !keys.contains(key) {
+                eprintln!(
+                    "{} '{}' isn't a translation key; looking for reads of it anyway",
+                    "⚠".yellow(),
+                    key
+                );
+            }
+            sources.usages([&key.to_string()])
         }
+        None => sources.usages(&keys),
+    };
 
-        let parse_result = parse_i18n_file_with_record_name(
-            &file_config.path,
-            &file_config.record_name,
-            languages,
-        )?;
-        files_processed += 1;
-
-        let findings = find_keys_with_shared_language_values(&parse_result.translations, languages);
-        let (visible_findings, file_suppressed_groups) =
-            filter_suppressed_shared_values(&file_config.path, findings, &suppressions);
-        suppressed_groups += file_suppressed_groups;
-
-        all_findings.extend(visible_findings.into_iter().map(|entry| {
-            FileKeySharedLanguageValues {
-                file_shortcut: shortcut.clone(),
-                file_path: file_config.path.clone(),
-                key: entry.key,
-                groups: entry.groups,
-            }
-        }));
+    if json {
+        let map: serde_json::Map<String, serde_json::Value> = usages
+            .iter()
+            .map(|(key, found)| {
+                let entries = found
+                    .iter()
+                    .map(|usage| {
+                        serde_json::json!({
+                            "file": usage.path.display().to_string(),
+                            "line": usage.line,
+                            "text": usage.text,
+                        })
+                    })
+                    .collect();
+                (key.clone(), serde_json::Value::Array(entries))
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&map)?);
+        return Ok(());
     }
 
-    println!("  Processed {} files", files_processed);
-    println!();
+    println!("{}", scanned_message(sources, src_dirs));
+    for (key, found) in &usages {
+        println!();
+        let files: std::collections::BTreeSet<&PathBuf> = found.iter().map(|u| &u.path).collect();
+        match found.len() {
+            0 => println!("{} {}: no usages", "⚠".yellow(), key.yellow()),
+            count => println!(
+                "{} {}: {} usage{} in {} file{}",
+                "📋".blue(),
+                key.yellow(),
+                count,
+                if count == 1 { "" } else { "s" },
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            ),
+        }
+        for usage in found {
+            let place = format!("{}:{}", usage.path.display(), usage.line);
+            println!("  {}  {}", place.dimmed(), usage.text);
+        }
+    }
+    Ok(())
+}
 
-    all_findings.sort_by(|a, b| {
-        a.file_shortcut
-            .cmp(&b.file_shortcut)
-            .then_with(|| a.key.cmp(&b.key))
-    });
+/// Report the string literals of `sources` that look like copy to translate
+fn handle_extract(
+    sources: &SourceSet,
+    src_dirs: &[PathBuf],
+    functions: &[String],
+    json: bool,
+) -> Result<()> {
+    let found = extract(sources, functions);
 
-    if suppress {
-        let entries = collect_cross_file_shared_value_suppressions(&all_findings);
-        suppress_shared_values(&suppressed_path, entries, suppressed_groups)?;
+    if json {
+        let entries: Vec<serde_json::Value> = found
+            .iter()
+            .map(|(path, string)| {
+                serde_json::json!({
+                    "file": path.display().to_string(),
+                    "line": string.line,
+                    "function": string.function,
+                    "text": string.text,
+                    "suggestedKey": string.suggested_key,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({ "sourceFiles": sources.files.len(), "strings": entries });
+        println!("{}", serde_json::to_string_pretty(&output)?);
         return Ok(());
     }
 
-    print_cross_file_shared_value_findings(&all_findings, suppressed_groups);
+    println!("{}", scanned_message(sources, src_dirs));
+    if found.is_empty() {
+        println!(
+            "{} No hardcoded strings passed to {}",
+            "✓".green(),
+            functions.join(", ")
+        );
+        return Ok(());
+    }
 
+    println!();
+    for (path, string) in &found {
+        let place = format!("{}:{}", path.display(), string.line);
+        println!(
+            "  {}  {} \"{}\"  {} {}",
+            place.dimmed(),
+            string.function,
+            string.text,
+            "→".cyan(),
+            string.suggested_key
+        );
+    }
+    let files: std::collections::BTreeSet<&Path> = found.iter().map(|(path, _)| *path).collect();
+    println!();
+    println!(
+        "{} Found {} hardcoded string{} in {} file{}",
+        "📋".blue(),
+        found.len(),
+        if found.len() == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" }
+    );
+    println!(
+        "{} Move each into I18n.elm with `elm-i18n rewrite <file> --line <line> --key <key>`, or put `-- elm-i18n:ignore-next-line` above it",
+        "ℹ".blue()
+    );
     Ok(())
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct SharedLanguageValueGroup {
-    value: String,
-    languages: Vec<String>,
-}
+/// Handle `rewrite`: add the string at `source:line` to `file` as `key` and read
+/// it off `accessor` where it was
+#[allow(clippy::too_many_arguments)]
+fn handle_rewrite(
+    file: &Path,
+    source: &Path,
+    line: usize,
+    expected_text: Option<&str>,
+    key: &str,
+    translations: &[String],
+    source_lang: &str,
+    accessor: &str,
+    functions: &[String],
+    variables: &[String],
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+    if !source.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", source.display()),
+        );
+        exit(1);
+    }
+    let accessor_pattern =
+        regex::Regex::new(r"^[a-z_][A-Za-z0-9_]*(?:\.[a-z_][A-Za-z0-9_]*)*$").unwrap();
+    if !accessor_pattern.is_match(accessor) {
+        errors::error_with(
+            Code::InvalidArgument,
+            format!(
+                "Invalid accessor '{}': expected a variable or a field path like model.t",
+                accessor
+            ),
+        );
+        exit(1);
+    }
+    if let Some(given) = translations.iter().find(|arg| {
+        arg.split_once('=')
+            .map(|(lang, _)| configured_language(lang, languages))
+            == Some(source_lang.to_string())
+    }) {
+        errors::error(format!(
+            "The {} value is the string in {}; drop '-t {}'",
+            source_lang.to_uppercase(),
+            source.display(),
+            given
+        ));
+        exit(1);
+    }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct KeySharedLanguageValues {
-    key: String,
-    groups: Vec<SharedLanguageValueGroup>,
-}
+    refuse_if_inconsistent(file, record_name, languages)?;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct FileKeySharedLanguageValues {
-    file_shortcut: String,
-    file_path: PathBuf,
-    key: String,
-    groups: Vec<SharedLanguageValueGroup>,
-}
+    // The string must still be where extract saw it
+    let code = read_elm_file(source)?;
+    let place = format!("{}:{}", source.display(), line);
+    let on_line: Vec<_> = hardcoded_strings(&code, functions)
+        .into_iter()
+        .filter(|string| string.line == line)
+        .collect();
+    let rerun = || {
+        errors::suggestion(format!(
+            "If {} changed since `elm-i18n extract` ran, run it again for the current lines",
+            source.display()
+        ));
+    };
+    let string = match expected_text {
+        Some(expected) => on_line.iter().find(|string| string.text == expected),
+        None if on_line.len() == 1 => on_line.first(),
+        None => None,
+    };
+    let Some(string) = string else {
+        if on_line.is_empty() {
+            errors::error(format!(
+                "No hardcoded string passed to {} at {}",
+                functions.join(", "),
+                place
+            ));
+        } else if let Some(expected) = expected_text {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!("\"{}\" isn't at {}; found:", expected, place),
+            );
+            for string in &on_line {
+                errors::detail(format!("  {} \"{}\"", string.function, string.text));
+            }
+        } else {
+            errors::error(format!("{} hardcoded strings at {}:", on_line.len(), place));
+            for string in &on_line {
+                errors::detail(format!("  {} \"{}\"", string.function, string.text));
+            }
+            errors::suggestion("Pick one with --text");
+            exit(1);
+        }
+        rerun();
+        exit(1);
+    };
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-struct SuppressedEntry {
-    check: String,
-    file_path: String,
-    key: String,
-    languages: Vec<String>,
-    value: String,
-}
+    // The code around the string must have the translations to read the key off
+    let variable = accessor.split('.').next().unwrap_or(accessor);
+    if !in_scope(&code, string.literal.start, variable) {
+        let function = enclosing_definition(&code, string.literal.start);
+        let function = function.as_deref().unwrap_or("the code");
+        errors::error(format!(
+            "No `{}` in scope in `{}` at {}",
+            variable, function, place
+        ));
+        if variable == accessor {
+            errors::suggestion(format!("Add a `{} : {}` argument to `{}` and pass the translations to it from its callers, then run rewrite again", variable,
+                record_name,
+                function));
+        } else {
+            errors::suggestion(format!(
+                "Pass `{}` to `{}` from its callers, then run rewrite again",
+                variable, function
+            ));
+        }
+        errors::suggestion("If the translations go by another name there, give it with --accessor");
+        exit(1);
+    }
+    if variable == accessor
+        && !holds_translations(
+            &code,
+            string.literal.start,
+            variable,
+            variables,
+            &[record_name.to_string()],
+        )
+    {
+        let function = enclosing_definition(&code, string.literal.start);
+        errors::error_with(
+            Code::InvalidArgument,
+            format!(
+                "`{}` in `{}` isn't the translations at {}",
+                variable,
+                function.as_deref().unwrap_or("the code"),
+                place
+            ),
+        );
+        errors::suggestion(
+            "Give the name the translations have there with --accessor, e.g. --accessor model.t",
+        );
+        exit(1);
+    }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
-struct SuppressedStore {
-    #[serde(default)]
-    entries: Vec<SuppressedEntry>,
-}
+    if check_key_exists_with_record_name(file, key, record_name, languages)?.is_some() {
+        Coded::new(
+            Code::AlreadyExists,
+            format!(
+                "Translation '{}' already exists in {}",
+                key.yellow(),
+                file.display()
+            ),
+        )
+        .key(key)
+        .report();
+        errors::suggestion("Pick another --key");
+        exit(1);
+    }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-struct LocalStateConfig {
-    #[serde(default = "default_local_state_version")]
-    version: u32,
-}
+    let mut arguments = translations.to_vec();
+    arguments.push(format!("{}={}", source_lang, string.text));
+    let values = parse_translation_args(&arguments, languages)?;
+    let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+    for existing in keys_with_identical_text(&parse_result, source_lang, &string.text) {
+        println!(
+            "{} Identical text exists under key '{}'",
+            "ℹ".blue(),
+            existing.yellow()
+        );
+    }
 
-impl Default for LocalStateConfig {
-    fn default() -> Self {
-        Self {
-            version: default_local_state_version(),
-        }
+    let content = read_elm_file(file)?;
+    let translation = Translation {
+        key: key.to_string(),
+        values: values.clone(),
+        is_function: false,
+        type_signature: None,
+    };
+    let new_content = add_translation_to_content(&content, &translation, record_name, languages)?;
+    let read = format!("{}.{}", accessor, key);
+    let new_code = format!(
+        "{}{}{}",
+        &code[..string.literal.start],
+        read,
+        &code[string.literal.end..]
+    );
+
+    if dry_run {
+        print_diff(file, &content, &new_content);
+        print_diff(source, &code, &new_code);
+        println!();
+    } else {
+        timing::write(file, &new_content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        timing::write(source, &new_code)
+            .with_context(|| format!("Failed to write to {}", source.display()))?;
     }
-}
 
-fn default_local_state_version() -> u32 {
-    1
+    println!(
+        "{} {} translation '{}' to {}",
+        "✓".green(),
+        if dry_run { "Would add" } else { "Added" },
+        key.yellow(),
+        file.display()
+    );
+    for lang in languages {
+        if let Some(value) = values.get(lang) {
+            println!("  {}: {}", lang.to_uppercase().green(), value);
+        }
+    }
+    println!(
+        "{} {} \"{}\" with {} at {}",
+        "✓".green(),
+        if dry_run { "Would replace" } else { "Replaced" },
+        string.text,
+        read.green(),
+        place.dimmed()
+    );
+    Ok(())
 }
 
-fn find_keys_with_shared_language_values(
-    translations: &std::collections::HashMap<String, Translation>,
+/// Every key `files` define, and the record names they're parsed with
+fn defined_keys(
+    files: &[(PathBuf, String)],
     languages: &[String],
-) -> Vec<KeySharedLanguageValues> {
-    let mut keys_with_shared_values = Vec::new();
-
-    for (key, translation) in translations {
-        let groups = find_shared_language_value_groups(&translation.values, languages);
-        if !groups.is_empty() {
-            keys_with_shared_values.push(KeySharedLanguageValues {
-                key: key.clone(),
-                groups,
-            });
+) -> Result<(std::collections::BTreeSet<String>, Vec<String>)> {
+    let mut keys = std::collections::BTreeSet::new();
+    let mut record_names = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
         }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        keys.extend(parse_result.translations.into_keys());
+        record_names.push(record_name.clone());
     }
-
-    keys_with_shared_values.sort_by(|a, b| a.key.cmp(&b.key));
-    keys_with_shared_values
+    Ok((keys, record_names))
 }
 
-fn find_shared_language_value_groups(
-    values: &std::collections::HashMap<String, String>,
+/// Report reads of keys that none of `files` defines, and return how many there are
+///
+/// Only fields read off values that look like translations are checked: the
+/// `variables`, anything annotated with one of the files' record types, and
+/// calls to a variable such as `(translations lang).key`.
+fn handle_scan_missing(
+    files: &[(PathBuf, String)],
+    sources: &SourceSet,
+    src_dirs: &[PathBuf],
+    variables: &[String],
+    json: bool,
     languages: &[String],
-) -> Vec<SharedLanguageValueGroup> {
-    let mut value_to_languages: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
+) -> Result<usize> {
+    let (keys, record_names) = defined_keys(files, languages)?;
+    let missing: Vec<_> = sources
+        .missing(&keys, variables, &record_names)
+        .into_iter()
+        .map(|(source, access)| {
+            let suggestion = closest_key(&access.key, &keys);
+            (source, access, suggestion)
+        })
+        .collect();
 
-    for (lang, value) in values {
-        if value.trim().is_empty() {
-            continue;
-        }
+    if json {
+        let entries: Vec<serde_json::Value> = missing
+            .iter()
+            .map(|(source, access, suggestion)| {
+                serde_json::json!({
+                    "file": source.display().to_string(),
+                    "line": access.line,
+                    "variable": access.variable,
+                    "key": access.key,
+                    "suggestion": suggestion,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({ "sourceFiles": sources.files.len(), "missing": entries });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(missing.len());
+    }
 
-        value_to_languages
-            .entry(value.clone())
-            .or_default()
-            .push(lang.clone());
+    println!("{}", scanned_message(sources, src_dirs));
+    if missing.is_empty() {
+        println!(
+            "{} Every key read off translations exists ({} keys)",
+            "✓".green(),
+            keys.len()
+        );
+        return Ok(0);
     }
 
-    let mut groups = Vec::new();
+    println!();
+    for (source, access, suggestion) in &missing {
+        let hint = suggestion
+            .as_ref()
+            .map(|key| format!(" (did you mean `{}`?)", key))
+            .unwrap_or_default();
+        println!(
+            "  {} {}:{}: `{}.{}` isn't a translation key{}",
+            "✗".red(),
+            source.display(),
+            access.line,
+            access.variable,
+            access.key.yellow(),
+            hint
+        );
+    }
+    println!();
+    println!(
+        "I found {} read{} of keys that don't exist.",
+        missing.len(),
+        if missing.len() == 1 { "" } else { "s" }
+    );
+    println!(
+        "{} If one isn't a translations record, put `-- elm-i18n:ignore-next-line` above it, or add its file to scan.ignorePaths in {}",
+        "ℹ".blue(),
+        LOCAL_CONFIG_FILE
+    );
+    Ok(missing.len())
+}
 
-    for (value, mut langs) in value_to_languages {
-        if langs.len() < 2 {
-            continue;
-        }
+/// Width in characters of each bar of `stats --by-language`
+const STATS_BAR_WIDTH: usize = 10;
 
-        langs.sort_by(|a, b| {
-            language_sort_index(a, languages)
-                .cmp(&language_sort_index(b, languages))
-                .then_with(|| a.cmp(b))
-        });
+fn handle_stats(
+    files: &[(PathBuf, String)],
+    lint_config: &LintConfig,
+    languages: &[String],
+    by_language: bool,
+    json: bool,
+) -> Result<()> {
+    let todo_marker = lint_config.todo_marker();
+    // (translated, empty, todo) of a language, over all the keys of a file
+    let counts = |parse_result: &ParseResult, lang: &String| {
+        let (mut translated, mut empty, mut todo) = (0usize, 0usize, 0usize);
+        for translation in parse_result.translations.values() {
+            match value_status(translation.values.get(lang), todo_marker) {
+                ValueStatus::Translated => translated += 1,
+                ValueStatus::Missing | ValueStatus::Empty => empty += 1,
+                ValueStatus::Todo => todo += 1,
+            }
+        }
+        (translated, empty, todo)
+    };
 
-        groups.push(SharedLanguageValueGroup {
-            value,
-            languages: langs,
-        });
+    if json {
+        let mut entries = Vec::new();
+        for (file, record_name) in files.iter().filter(|(file, _)| file.exists()) {
+            let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+            let total = parse_result.translations.len();
+            let languages: Vec<serde_json::Value> = languages
+                .iter()
+                .map(|lang| {
+                    let (translated, empty, todo) = counts(&parse_result, lang);
+                    serde_json::json!({
+                        "language": lang,
+                        "translated": translated,
+                        "empty": empty,
+                        "todo": todo,
+                        "percent": (translated * 100).checked_div(total).unwrap_or(100),
+                    })
+                })
+                .collect();
+            let prefixes: Vec<serde_json::Value> =
+                keys_by_prefix(parse_result.translations.keys().map(String::as_str))
+                    .into_iter()
+                    .map(|(prefix, count)| serde_json::json!({ "prefix": prefix, "keys": count }))
+                    .collect();
+            entries.push(serde_json::json!({
+                "file": file.display().to_string(),
+                "keys": total,
+                "languages": languages,
+                "prefixes": prefixes,
+            }));
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({ "files": entries }))?
+        );
+        return Ok(());
     }
 
-    groups.sort_by(|a, b| {
-        b.languages
-            .len()
-            .cmp(&a.languages.len())
-            .then_with(|| a.value.cmp(&b.value))
-            .then_with(|| a.languages.cmp(&b.languages))
-    });
-
-    groups
-}
+    let chart = io::stdout().is_terminal();
+    for (file, record_name) in files {
+        if !file.exists() {
+            println!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
 
-fn language_sort_index(lang: &str, languages: &[String]) -> usize {
-    languages
-        .iter()
-        .position(|configured| configured == lang)
-        .unwrap_or(usize::MAX)
-}
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        let total = parse_result.translations.len();
+        if by_language {
+            println!(
+                "{} {}: {} key{}",
+                "📊".blue(),
+                file.display(),
+                total,
+                if total == 1 { "" } else { "s" }
+            );
+            println!();
+            for lang in languages {
+                let (translated, _, _) = counts(&parse_result, lang);
+                let percent = (translated * 100).checked_div(total).unwrap_or(100);
+                if chart {
+                    println!(
+                        "  {:<6}{} {:>4}%",
+                        lang.to_uppercase(),
+                        completeness_bar(translated, total, STATS_BAR_WIDTH).green(),
+                        percent
+                    );
+                } else {
+                    println!("  {:<6}{}%", lang.to_uppercase(), percent);
+                }
+            }
+            println!();
+            continue;
+        }
 
-fn format_language_codes(languages: &[String]) -> String {
-    languages
-        .iter()
-        .map(|lang| lang.to_uppercase())
-        .collect::<Vec<_>>()
-        .join(", ")
-}
+        let limit = match lint_config.max_keys {
+            Some(max_keys) if total > max_keys => {
+                format!(" {}", format!("(over the limit of {})", max_keys).yellow())
+            }
+            Some(max_keys) => format!(" (limit {})", max_keys),
+            None => String::new(),
+        };
+        println!(
+            "{} {}: {} key{}{}",
+            "📊".blue(),
+            file.display(),
+            total,
+            if total == 1 { "" } else { "s" },
+            limit
+        );
+        println!();
+        println!(
+            "  {}",
+            format!(
+                "{:<10}{:<18}{:<8}{}",
+                "Language", "Translated", "Empty", todo_marker
+            )
+            .bold()
+        );
 
-fn truncate_for_display(value: &str, max_chars: usize) -> String {
-    let char_count = value.chars().count();
-    if char_count <= max_chars {
-        return value.to_string();
-    }
+        for lang in languages {
+            let (translated, empty, todo) = counts(&parse_result, lang);
+            let percent = (translated * 100).checked_div(total).unwrap_or(100);
+            println!(
+                "  {:<10}{:<18}{:<8}{}",
+                lang.to_uppercase(),
+                format!("{} ({}%)", translated, percent),
+                empty,
+                todo
+            );
+        }
 
-    if max_chars <= 3 {
-        return ".".repeat(max_chars);
+        println!();
+        println!("  {}", format!("{:<20}{}", "Prefix", "Keys").bold());
+        for (prefix, count) in keys_by_prefix(parse_result.translations.keys().map(String::as_str))
+        {
+            println!("  {:<20}{}", prefix.unwrap_or("(no prefix)"), count);
+        }
+        println!();
     }
 
-    let truncated: String = value.chars().take(max_chars - 3).collect();
-    format!("{}...", truncated)
+    Ok(())
 }
 
-fn compact_value_for_display(value: &str) -> String {
-    value.split_whitespace().collect::<Vec<_>>().join(" ")
+/// Where `coverage --badge` writes badges, and how it colors them
+struct BadgeOptions {
+    out: Option<PathBuf>,
+    red_under: f64,
+    yellow_under: f64,
 }
 
-fn shared_values_summary(total_visible_groups: usize) -> String {
-    match total_visible_groups {
-        0 => "I found no errors!".to_string(),
-        1 => "I found 1 error!".to_string(),
-        n => format!("I found {} errors!", n),
+fn handle_coverage(
+    files: &[(PathBuf, String)],
+    reported: &[String],
+    fail_under: Option<f64>,
+    json: bool,
+    badges: Option<BadgeOptions>,
+    todo_marker: &str,
+    languages: &[String],
+) -> Result<()> {
+    let mut percentages = vec![("--fail-under", fail_under)];
+    if let Some(options) = &badges {
+        percentages.push(("--red-under", Some(options.red_under)));
+        percentages.push(("--yellow-under", Some(options.yellow_under)));
     }
-}
-
-fn suppressed_errors_summary(suppressed_groups: usize) -> String {
-    match suppressed_groups {
-        0 => String::new(),
-        1 => "There is still 1 suppressed error.".to_string(),
-        n => format!("There are still {} suppressed errors.", n),
+    for (flag, value) in percentages {
+        if let Some(threshold) = value.filter(|t| !(0.0..=100.0).contains(t)) {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "{} {} isn't a percentage; use a number from 0 to 100",
+                    flag, threshold
+                ),
+            );
+            exit(1);
+        }
+    }
+    if let Some(options) = badges.as_ref().filter(|o| o.red_under > o.yellow_under) {
+        errors::error(format!(
+            "--red-under {} is above --yellow-under {}, so no badge would be yellow",
+            options.red_under, options.yellow_under
+        ));
+        exit(1);
+    }
+    if badges.as_ref().is_some_and(|o| o.out.is_none()) && reported.len() > 1 {
+        errors::error(format!(
+            "{} badges can't all go to stdout; pass --out DIR to write one file per language",
+            reported.len()
+        ));
+        exit(1);
     }
-}
-
-fn local_state_config_path() -> PathBuf {
-    PathBuf::from(LOCAL_CONFIG_FILE)
-}
-
-fn suppressed_entries_path() -> PathBuf {
-    PathBuf::from(LOCAL_SUPPRESSED_FILE)
-}
-
-fn format_local_path(path: &Path) -> String {
-    format!("./{}", path.display())
-}
 
-fn ensure_local_state_config(path: &Path) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    let mut parse_results = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        parse_results.push(parse_i18n_file_with_record_name(
+            file,
+            record_name,
+            languages,
+        )?);
+    }
+    let parse_results: Vec<&ParseResult> = parse_results.iter().collect();
+    let covered = coverage::coverage(&parse_results, reported, todo_marker);
+    let failing =
+        fail_under.map_or_else(Vec::new, |threshold| coverage::below(&covered, threshold));
+
+    if let Some(options) = &badges {
+        write_badges(&covered, options)?;
+    } else if json {
+        let languages: Vec<CoverageEntry> = covered.iter().map(CoverageEntry::from).collect();
+        let output = serde_json::json!({
+            "languages": languages,
+            "failUnder": fail_under,
+            "passed": failing.is_empty(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        let keys = covered.first().map_or(0, |c| c.total);
+        println!(
+            "{} Translation coverage: {} key{}",
+            "📊".blue(),
+            keys,
+            if keys == 1 { "" } else { "s" }
+        );
+        println!();
+        let chart = io::stdout().is_terminal();
+        for c in &covered {
+            let bar = if chart {
+                format!(
+                    "  {}",
+                    completeness_bar(c.translated, c.total, STATS_BAR_WIDTH)
+                )
+            } else {
+                String::new()
+            };
+            let bar = if c.meets(fail_under.unwrap_or(0.0)) {
+                bar.green()
+            } else {
+                bar.red()
+            };
+            println!(
+                "  {:<6}{:>6.1}%  {:>9}{}",
+                c.language.to_uppercase(),
+                c.percent(),
+                format!("{}/{}", c.translated, c.total),
+                bar
+            );
+        }
+        println!();
     }
 
-    if path.exists() {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-        let _: LocalStateConfig = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let Some(threshold) = fail_under else {
+        return Ok(());
+    };
+    if failing.is_empty() {
+        if !json && badges.as_ref().is_none_or(|o| o.out.is_some()) {
+            println!(
+                "{} Every language is at least {}% translated",
+                "✓".green(),
+                threshold
+            );
+        }
         return Ok(());
     }
+    for c in &failing {
+        errors::error(format!(
+            "{} is {:.1}% translated, under the {}% threshold",
+            c.language.to_uppercase(),
+            c.percent(),
+            threshold
+        ));
+    }
+    exit(1);
+}
 
-    let content = serde_json::to_string_pretty(&LocalStateConfig::default())
-        .context("Failed to serialize local state config")?;
-    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+/// Write a badge per language to `options.out`, or print the only one
+fn write_badges(covered: &[coverage::LanguageCoverage], options: &BadgeOptions) -> Result<()> {
+    let badges = covered
+        .iter()
+        .map(|c| coverage::badge(c, options.red_under, options.yellow_under));
+    let Some(out) = &options.out else {
+        for badge in badges {
+            println!("{}", serde_json::to_string_pretty(&badge)?);
+        }
+        return Ok(());
+    };
+
+    for (c, badge) in covered.iter().zip(badges) {
+        let path = if covered.len() == 1 && out.extension().is_some() {
+            out.clone()
+        } else {
+            out.join(format!("{}.json", c.language))
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let json = format!("{}\n", serde_json::to_string_pretty(&badge)?);
+        timing::write(&path, &json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!(
+            "{} Wrote {} ({}, {})",
+            "✓".green(),
+            path.display(),
+            badge.message,
+            badge.color
+        );
+    }
     Ok(())
 }
 
-fn load_suppressed_entries(path: &Path) -> Result<SuppressedStore> {
-    if !path.exists() {
-        return Ok(SuppressedStore::default());
+/// Write the status page to `html`, or print its data as JSON without one
+fn handle_report(
+    files: &[(PathBuf, String)],
+    html: Option<&Path>,
+    todo_marker: &str,
+    languages: &[String],
+) -> Result<()> {
+    let mut parse_results = Vec::new();
+    for (file, record_name) in files {
+        if !file.exists() {
+            eprintln!(
+                "{} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+        let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
+        parse_results.push((file, parse_result));
     }
 
-    let content = std::fs::read_to_string(path)
-        .with_context(|| format!("Failed to read {}", path.display()))?;
-    let mut store: SuppressedStore = serde_json::from_str(&content)
-        .with_context(|| format!("Failed to parse {}", path.display()))?;
-    normalize_suppressed_entries(&mut store);
-    Ok(store)
-}
+    let parsed: Vec<&ParseResult> = parse_results.iter().map(|(_, p)| p).collect();
+    let report = StatusReport {
+        coverage: coverage::coverage(&parsed, languages, todo_marker)
+            .iter()
+            .map(CoverageEntry::from)
+            .collect(),
+        files: parse_results
+            .iter()
+            .map(|(file, parse_result)| {
+                file_status(
+                    &file.display().to_string(),
+                    parse_result,
+                    languages,
+                    todo_marker,
+                )
+            })
+            .collect(),
+    };
 
-fn save_suppressed_entries(path: &Path, store: &SuppressedStore) -> Result<()> {
-    if let Some(parent) = path.parent() {
+    let Some(html) = html else {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    };
+    if let Some(parent) = html.parent().filter(|p| !p.as_os_str().is_empty()) {
         std::fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create {}", parent.display()))?;
     }
+    std::fs::write(html, render_html(&report))
+        .with_context(|| format!("Failed to write {}", html.display()))?;
 
-    let content =
-        serde_json::to_string_pretty(store).context("Failed to serialize suppressed entries")?;
-    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    let keys: usize = report.files.iter().map(|f| f.keys.len()).sum();
+    println!(
+        "{} Wrote {} ({} key{}, {} language{})",
+        "✓".green(),
+        html.display(),
+        keys,
+        if keys == 1 { "" } else { "s" },
+        languages.len(),
+        if languages.len() == 1 { "" } else { "s" }
+    );
     Ok(())
 }
 
-fn normalize_suppressed_entries(store: &mut SuppressedStore) {
-    for entry in &mut store.entries {
-        entry.languages.sort();
+/// `translated` out of `total` as a bar of `width` blocks, `████████░░` for 82%
+///
+/// A block is filled for each full step reached, so a language missing one key
+/// never shows a full bar.
+fn completeness_bar(translated: usize, total: usize, width: usize) -> String {
+    let filled = (translated * width).checked_div(total).unwrap_or(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// `format`, or `canonicalize` when `canonical`
+fn handle_format(
+    files: &[(PathBuf, String)],
+    languages: &[String],
+    check: bool,
+    canonical: bool,
+) -> Result<()> {
+    let (command, formatted) = if canonical {
+        ("canonicalize", "canonical")
+    } else {
+        ("format", "formatted")
+    };
+    let mut unformatted = 0;
+
+    for (file, record_name) in files {
+        if !file.exists() {
+            println!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
+
+        let source = read_elm_file(file)?;
+        let outcome = if canonical {
+            canonicalize(&source, record_name, languages)?
+        } else {
+            layout_translation_blocks(&source, record_name, languages)?
+        };
+        for block in &outcome.skipped {
+            println!(
+                "  {} Left {} in {} as it is (layout too unusual to rewrite safely)",
+                "⚠".yellow(),
+                block,
+                file.display()
+            );
+        }
+
+        if outcome.content == source {
+            println!("{} {} is {}", "✓".green(), file.display(), formatted);
+            continue;
+        }
+
+        unformatted += 1;
+        if check {
+            println!("{} {} isn't {}:", "✗".red(), file.display(), formatted);
+            print_diff(file, &source, &outcome.content);
+            println!();
+            continue;
+        }
+
+        let backup_path = backup::write_backup(file, &source)?;
+        timing::write(file, &outcome.content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+        println!(
+            "{} {} {}",
+            "✓".green(),
+            if canonical {
+                "Canonicalized"
+            } else {
+                "Formatted"
+            },
+            file.display()
+        );
+        println!(
+            "  {} Backup written to {}",
+            "ℹ".blue(),
+            backup_path.display()
+        );
     }
 
-    store.entries.sort_by(|a, b| {
-        a.check
-            .cmp(&b.check)
-            .then_with(|| a.file_path.cmp(&b.file_path))
-            .then_with(|| a.key.cmp(&b.key))
-            .then_with(|| a.languages.cmp(&b.languages))
-            .then_with(|| a.value.cmp(&b.value))
-    });
-    store.entries.dedup();
-}
+    if check && unformatted > 0 {
+        errors::error(format!(
+            "{} file{} need{} {}; run 'elm-i18n {}'",
+            unformatted,
+            if unformatted == 1 { "" } else { "s" },
+            if unformatted == 1 { "s" } else { "" },
+            if canonical {
+                "canonicalizing"
+            } else {
+                "formatting"
+            },
+            command
+        ));
+        exit(1);
+    }
 
-fn normalize_file_path(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
+    Ok(())
 }
 
-fn is_shared_values_suppressed(
-    suppressions: &SuppressedStore,
-    file_path: &str,
-    key: &str,
-    _group: &SharedLanguageValueGroup,
-) -> bool {
-    suppressions.entries.iter().any(|entry| {
-        entry.check == SHARED_VALUES_CHECK_NAME && entry.file_path == file_path && entry.key == key
-    })
-}
+/// Validate `files`, returning the status to exit with: 1 when there are problems,
+/// or warnings and `fail_on_warning`
+fn handle_validate(
+    files: &[(PathBuf, String)],
+    languages: &[String],
+    strict: bool,
+    require_descriptions: bool,
+    placeholder_style: Option<PlaceholderStyle>,
+    fail_on_warning: bool,
+) -> Result<i32> {
+    let mut problems = 0;
+    let mut warnings = 0;
+    let mut checked = 0;
+
+    for (file, record_name) in files {
+        if !file.exists() {
+            println!(
+                "  {} Skipping {} (file not found)",
+                "⚠".yellow(),
+                file.display()
+            );
+            continue;
+        }
 
-fn filter_suppressed_shared_values(
-    file: &Path,
-    findings: Vec<KeySharedLanguageValues>,
-    suppressions: &SuppressedStore,
-) -> (Vec<KeySharedLanguageValues>, usize) {
-    let file_path = normalize_file_path(file);
-    let mut filtered_findings = Vec::new();
-    let mut suppressed_groups = 0;
+        println!("{} Validating {}...", "🔍".blue(), file.display());
 
-    for finding in findings {
-        let KeySharedLanguageValues { key, groups } = finding;
-        let mut visible_groups = Vec::new();
+        let source = read_elm_file(file)?;
+        let duplicates = duplicate_records(&source, record_name, languages);
+        if !duplicates.is_empty() {
+            for duplicate in &duplicates {
+                let starts: Vec<usize> =
+                    duplicate.definitions.iter().map(|d| d.start + 1).collect();
+                let line = format!("line {}: ", starts[1]);
+                println!(
+                    "  {} {}`{}` is defined {} times (lines {})",
+                    "✗".red(),
+                    line.dimmed(),
+                    duplicate.name,
+                    starts.len(),
+                    line_list(&starts)
+                );
+            }
+            println!(
+                "      {} Run `elm-i18n doctor --merge-duplicates` to merge them",
+                "→".cyan()
+            );
+            problems += duplicates.len();
+            continue;
+        }
+        let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+        let issues = validate_translations(&parse_result, languages, strict);
+        let style_issues = placeholder_style
+            .map(|style| placeholder_style_issues(&parse_result, languages, style))
+            .unwrap_or_default();
+        let dispatcher_problems: Vec<_> = dispatcher_check(&source, record_name, languages)
+            .into_iter()
+            .chain(round_trip_check(&source, languages))
+            .flat_map(|check| check.problems)
+            .collect();
+        checked += parse_result.translations.len();
 
-        for group in groups {
-            if is_shared_values_suppressed(suppressions, &file_path, &key, &group) {
-                suppressed_groups += 1;
-            } else {
-                visible_groups.push(group);
+        // An empty source is worth its own warning, ahead of the general list
+        let empty_sources = empty_source_keys(&parse_result, languages);
+        if let Some(default_language) = languages.first().filter(|_| !empty_sources.is_empty()) {
+            println!(
+                "  {} {}",
+                "⚠".yellow(),
+                format!(
+                    "{} key{} {} an empty {} value, the source the other languages are translated from:",
+                    empty_sources.len(),
+                    if empty_sources.len() == 1 { "" } else { "s" },
+                    if empty_sources.len() == 1 { "has" } else { "have" },
+                    default_language.to_uppercase()
+                )
+                .yellow()
+                .bold()
+            );
+            for key in &empty_sources {
+                let line = field_line(&parse_result, default_language, key)
+                    .map(|line| format!("  line {}", line))
+                    .unwrap_or_default();
+                println!("      {}{}", key.yellow(), line.dimmed());
+            }
+            println!(
+                "      {} An empty source is usually a mistake; exports will carry it as an empty string",
+                "→".cyan()
+            );
+        }
+
+        let undescribed = if require_descriptions {
+            context::undescribed_keys(&context::load(file)?, &parse_result)
+        } else {
+            Vec::new()
+        };
+        if !undescribed.is_empty() {
+            println!(
+                "  {} {}",
+                "⚠".yellow(),
+                format!(
+                    "{} key{} no description for translators:",
+                    undescribed.len(),
+                    if undescribed.len() == 1 {
+                        " has"
+                    } else {
+                        "s have"
+                    }
+                )
+                .yellow()
+                .bold()
+            );
+            for key in &undescribed {
+                println!("      {}", key.yellow());
             }
+            println!(
+                "      {} Describe them in {}, or pass --description when adding a key",
+                "→".cyan(),
+                context::context_path(file).display()
+            );
         }
 
-        if !visible_groups.is_empty() {
-            filtered_findings.push(KeySharedLanguageValues {
-                key,
-                groups: visible_groups,
-            });
+        for problem in &dispatcher_problems {
+            let line = format!("line {}: ", problem.line);
+            println!("  {} {}{}", "✗".red(), line.dimmed(), problem.message);
+            println!("      {} {}", "→".cyan(), problem.suggestion);
         }
+        if issues.is_empty() && dispatcher_problems.is_empty() {
+            println!(
+                "  {} All {} translations are valid",
+                "✓".green(),
+                parse_result.translations.len()
+            );
+        }
+        let marked = issues
+            .iter()
+            .map(|issue| ("✗".red(), issue))
+            .chain(style_issues.iter().map(|issue| ("⚠".yellow(), issue)));
+        for (marker, issue) in marked {
+            let language = issue
+                .language
+                .as_ref()
+                .map(|lang| format!(" [{}]", lang.to_uppercase()))
+                .unwrap_or_default();
+            println!(
+                "  {} {}{}: {}",
+                marker,
+                issue.key.yellow(),
+                language.cyan(),
+                issue.message
+            );
+        }
+        problems += issues.len() + dispatcher_problems.len();
+        warnings += empty_sources.len() + undescribed.len() + style_issues.len();
+        println!();
     }
 
-    (filtered_findings, suppressed_groups)
+    if problems > 0 {
+        println!(
+            "I found {} problem{} in {} translations.",
+            problems,
+            if problems == 1 { "" } else { "s" },
+            checked
+        );
+        return Ok(1);
+    }
+    if fail_on_warning && warnings > 0 {
+        println!(
+            "I found {} warning{} in {} translations, and --fail-on-warning makes {} fail the run.",
+            warnings,
+            if warnings == 1 { "" } else { "s" },
+            checked,
+            if warnings == 1 { "it" } else { "them" }
+        );
+        return Ok(1);
+    }
+
+    Ok(0)
 }
 
-fn collect_shared_value_suppressions(
+/// Handle the generate command: append the helpers to the module, or refresh the
+/// ones between the markers
+fn handle_generate(
     file: &Path,
-    findings: &[KeySharedLanguageValues],
-) -> Vec<SuppressedEntry> {
-    let file_path = normalize_file_path(file);
-    let mut entries = Vec::new();
+    helpers: &GenerateHelpers,
+    languages: &[String],
+    todo_marker: &str,
+    style: KeyStyle,
+    record_name: &str,
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+    let source = read_elm_file(file)?;
+    if testgen::language_variants(&source).is_empty() {
+        errors::error(format!(
+            "No `type Language` in {}; the helpers take a Language",
+            file.display()
+        ));
+        errors::hint("Generate them in the module that declares it, with --file");
+        exit(1);
+    }
 
-    for finding in findings {
-        for group in &finding.groups {
-            entries.push(SuppressedEntry {
-                check: SHARED_VALUES_CHECK_NAME.to_string(),
-                file_path: file_path.clone(),
-                key: finding.key.clone(),
-                languages: group.languages.clone(),
-                value: group.value.clone(),
-            });
+    // relativeTime reads its texts from translations, added once so what the
+    // translators change is kept when the block is refreshed
+    let mut with_texts = source.clone();
+    let mut added = Vec::new();
+    if *helpers == GenerateHelpers::RelativeTime {
+        refuse_if_inconsistent(file, record_name, languages)?;
+        for translation in relative_time::translations(languages, style, todo_marker)? {
+            if nested::lookup(&with_texts, &translation.key, record_name, languages)?.is_none() {
+                with_texts =
+                    add_translation_to_content(&with_texts, &translation, record_name, languages)?;
+                added.push(translation.key);
+            }
         }
     }
 
-    entries
-}
-
-fn collect_cross_file_shared_value_suppressions(
-    findings: &[FileKeySharedLanguageValues],
-) -> Vec<SuppressedEntry> {
-    let mut entries = Vec::new();
+    let Some(content) = with_generated_helpers(&with_texts, helpers, languages, todo_marker, style)
+    else {
+        errors::error(format!(
+            "{} has `{}` but no `{}` after it, so I can't tell where the helpers end",
+            file.display(),
+            generated::begin_marker(helpers.block()),
+            generated::end_marker(helpers.block())
+        ));
+        errors::hint(
+            "Put the end marker back, or delete what's left of the block and run this again",
+        );
+        exit(1);
+    };
 
-    for finding in findings {
-        for group in &finding.groups {
-            entries.push(SuppressedEntry {
-                check: SHARED_VALUES_CHECK_NAME.to_string(),
-                file_path: normalize_file_path(&finding.file_path),
-                key: finding.key.clone(),
-                languages: group.languages.clone(),
-                value: group.value.clone(),
-            });
+    let unknown: Vec<&str> = languages
+        .iter()
+        .filter(|lang| match helpers {
+            GenerateHelpers::NumberFormat => !number_format::separators(lang).2,
+            GenerateHelpers::DateFormat { .. } => date_format::date_names(lang).is_none(),
+            GenerateHelpers::CurrencyFormat { .. } => {
+                !number_format::separators(lang).2 || !currency_format::placement(lang).2
+            }
+            GenerateHelpers::RelativeTime => {
+                !added.is_empty() && relative_time::texts(lang).is_none()
+            }
+        })
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        match helpers {
+            GenerateHelpers::NumberFormat => println!(
+                "{} No separators known for {}, so {} English's (1,234.56)",
+                "⚠".yellow(),
+                unknown.join(", "),
+                if unknown.len() == 1 {
+                    "it gets"
+                } else {
+                    "they get"
+                }
+            ),
+            GenerateHelpers::DateFormat { .. } => println!(
+                "{} No month and weekday names built in for {}; fill in {} below the block (each name starts with `{}` until you do)",
+                "⚠".yellow(),
+                unknown.join(", "),
+                unknown
+                    .iter()
+                    .map(|lang| date_format::names_record(lang))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                todo_marker
+            ),
+            GenerateHelpers::CurrencyFormat { .. } => println!(
+                "{} No currency format known for {}, so {} English's ($1,234.00)",
+                "⚠".yellow(),
+                unknown.join(", "),
+                if unknown.len() == 1 {
+                    "it gets"
+                } else {
+                    "they get"
+                }
+            ),
+            GenerateHelpers::RelativeTime => println!(
+                "{} No relative-time texts built in for {}, so {} English's, each starting with `{}` until translated",
+                "⚠".yellow(),
+                unknown.join(", "),
+                if unknown.len() == 1 {
+                    "it gets"
+                } else {
+                    "they get"
+                },
+                todo_marker
+            ),
         }
     }
-
-    entries
-}
-
-fn suppress_shared_values(
-    suppressed_path: &Path,
-    new_entries: Vec<SuppressedEntry>,
-    already_suppressed_groups: usize,
-) -> Result<()> {
-    let config_path = local_state_config_path();
-    ensure_local_state_config(&config_path)?;
-
-    if new_entries.is_empty() {
-        println!("{} No new shared-value findings to suppress", "✓".green());
-        if already_suppressed_groups > 0 {
-            println!("{}", suppressed_errors_summary(already_suppressed_groups));
+    if let GenerateHelpers::CurrencyFormat { currencies } = helpers {
+        let no_symbol: Vec<&str> = currencies
+            .iter()
+            .filter(|code| currency_format::symbol(code).is_none())
+            .map(String::as_str)
+            .collect();
+        if !no_symbol.is_empty() {
+            println!(
+                "{} No symbol known for {}, so formatCurrency writes the code instead",
+                "⚠".yellow(),
+                no_symbol.join(", ")
+            );
         }
-        return Ok(());
     }
-
-    let mut store = load_suppressed_entries(suppressed_path)?;
-    let mut existing_entries: std::collections::HashSet<_> =
-        store.entries.iter().cloned().collect();
-    let mut added_entries = 0;
-
-    for entry in new_entries {
-        if existing_entries.insert(entry.clone()) {
-            store.entries.push(entry);
-            added_entries += 1;
+    if *helpers == GenerateHelpers::NumberFormat {
+        let no_ordinals: Vec<&str> = languages
+            .iter()
+            .filter(|lang| !number_format::has_ordinals(lang))
+            .map(String::as_str)
+            .collect();
+        if !no_ordinals.is_empty() {
+            println!(
+                "{} No ordinal forms known for {}, so formatOrdinal writes 1., 2., 3. there (marked `{}` in the block)",
+                "⚠".yellow(),
+                no_ordinals.join(", "),
+                todo_marker
+            );
         }
     }
 
-    normalize_suppressed_entries(&mut store);
-    save_suppressed_entries(suppressed_path, &store)?;
-
+    if content == source {
+        println!(
+            "{} The {} helpers in {} are up to date",
+            "✓".green(),
+            helpers.block(),
+            file.display()
+        );
+        return Ok(());
+    }
+    let refreshed = generated::has_block(&source, helpers.block());
+    let backup_path = backup::write_backup(file, &source)?;
+    timing::write(file, &content)
+        .with_context(|| format!("Failed to write to {}", file.display()))?;
     println!(
-        "{} Suppressed {} error{} in {}",
+        "{} {} {} for {} in {}",
         "✓".green(),
-        added_entries,
-        if added_entries == 1 { "" } else { "s" },
-        format_local_path(suppressed_path).cyan()
+        if refreshed { "Refreshed" } else { "Added" },
+        helpers.functions(),
+        languages.join(", "),
+        file.display()
     );
+    if !added.is_empty() {
+        println!(
+            "  {} Added {} translations for its texts ({} to {}); translators can change them like any other",
+            "ℹ".blue(),
+            added.len(),
+            added[0],
+            added[added.len() - 1]
+        );
+    }
     println!(
-        "{} Local state config is stored in {}",
+        "  {} Backup written to {}",
         "ℹ".blue(),
-        format_local_path(&config_path).cyan()
+        backup_path.display()
     );
-    if already_suppressed_groups > 0 {
-        println!("{}", suppressed_errors_summary(already_suppressed_groups));
-    }
 
+    let exposes_all = source
+        .lines()
+        .find(|line| line.starts_with("module "))
+        .is_some_and(|line| line.contains("exposing (..)"));
+    if !exposes_all && !refreshed {
+        println!(
+            "  {} Add {} to the module's `exposing` list to use them from other modules",
+            "ℹ".blue(),
+            helpers.functions()
+        );
+    }
     Ok(())
 }
 
-fn print_shared_value_findings(findings: &[KeySharedLanguageValues], suppressed_groups: usize) {
-    let total_groups: usize = findings.iter().map(|entry| entry.groups.len()).sum();
+/// Handle the render command: write the module with its `${NAME}` variables
+/// expanded to `output`
+fn handle_render(
+    file: &Path,
+    record_name: &str,
+    languages: &[String],
+    output: &Path,
+    module: Option<&str>,
+    env_files: &[PathBuf],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+    if output == file || (output.exists() && output.canonicalize()? == file.canonicalize()?) {
+        errors::error(format!(
+            "--output is {} itself; render writes a copy so the source keeps its `${{...}}` placeholders",
+            file.display()
+        ));
+        exit(1);
+    }
 
-    println!();
-    println!("{}", shared_values_summary(total_groups));
-    if suppressed_groups > 0 {
-        println!();
-        println!("{}", suppressed_errors_summary(suppressed_groups));
+    let mut from_files = std::collections::BTreeMap::new();
+    for env_file in env_files {
+        from_files.extend(render::read_env_file(env_file)?);
     }
+    let source = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+    let rendered = render::render(&source, &parse_result, |name| {
+        std::env::var(name)
+            .ok()
+            .or_else(|| from_files.get(name).cloned())
+    });
 
-    if findings.is_empty() {
-        return;
+    if !rendered.missing.is_empty() {
+        for (name, users) in &rendered.missing {
+            // "contact [EN, FR]"
+            let mut by_key: Vec<(&String, Vec<String>)> = Vec::new();
+            for (key, lang) in users {
+                match by_key.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, langs)) => langs.push(lang.to_uppercase()),
+                    None => by_key.push((key, vec![lang.to_uppercase()])),
+                }
+            }
+            let users: Vec<String> = by_key
+                .iter()
+                .map(|(key, langs)| format!("{} [{}]", key, langs.join(", ")))
+                .collect();
+            errors::error(format!(
+                "${{{}}} isn't set (used by {})",
+                name,
+                users.join(", ")
+            ));
+        }
+        errors::hint(if env_files.is_empty() {
+            "Set them in the environment, or pass --env-file with a NAME=value file"
+        } else {
+            "Set them in the environment or add them to an --env-file"
+        });
+        exit(1);
     }
 
-    println!();
+    let mut content = format!(
+        "-- Generated by `elm-i18n render` from {}; edit that file instead.\n{}",
+        file.display(),
+        rendered.content
+    );
+    if let Some(module) = module {
+        content = render::rename_module(&content, module);
+    }
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    timing::write(output, &content)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
 
-    for entry in findings {
-        println!("  {} {}:", "•".green(), entry.key.yellow());
-        for group in &entry.groups {
-            println!(
-                "    - {}: {}",
-                format_language_codes(&group.languages).cyan(),
-                truncate_for_display(&compact_value_for_display(&group.value), 50)
-            );
-        }
-        println!();
+    if rendered.expanded.is_empty() {
+        println!(
+            "{} Wrote {} (no `${{...}}` variables in {})",
+            "✓".green(),
+            output.display(),
+            file.display()
+        );
+    } else {
+        let expanded: Vec<String> = rendered
+            .expanded
+            .iter()
+            .map(|(name, count)| format!("{} ×{}", name, count))
+            .collect();
+        println!(
+            "{} Rendered {} into {} ({})",
+            "✓".green(),
+            file.display(),
+            output.display(),
+            expanded.join(", ")
+        );
     }
+    Ok(())
 }
 
-fn print_cross_file_shared_value_findings(
-    findings: &[FileKeySharedLanguageValues],
-    suppressed_groups: usize,
-) {
-    let total_groups: usize = findings.iter().map(|entry| entry.groups.len()).sum();
-
-    println!("{}", shared_values_summary(total_groups));
-    if suppressed_groups > 0 {
-        println!();
-        println!("{}", suppressed_errors_summary(suppressed_groups));
+fn handle_gen_test(
+    file: &Path,
+    record_name: &str,
+    languages: &[String],
+    output: Option<PathBuf>,
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
     }
 
-    if findings.is_empty() {
-        return;
+    let source = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&source, record_name, languages)?;
+    let i18n_module = match testgen::module_name(&source) {
+        Some(name) => name,
+        None => {
+            errors::error(format!(
+                "No `module ... exposing` line in {}",
+                file.display()
+            ));
+            exit(1);
+        }
+    };
+
+    let output = output.unwrap_or_else(|| testgen::default_test_path(&i18n_module));
+    let test_module = testgen::test_module_name(&output);
+    let content = testgen::generate_test_module(
+        &source,
+        &parse_result,
+        &i18n_module,
+        &test_module,
+        record_name,
+        languages,
+    );
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
     }
+    std::fs::write(&output, content)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
 
-    println!();
+    println!(
+        "{} Wrote {} ({} translations, {} languages)",
+        "✓".green(),
+        output.display(),
+        parse_result.type_fields.len(),
+        languages.len()
+    );
+    println!(
+        "  {} Run `elm-test` (or `elm make {}`) to check the translations compile",
+        "ℹ".blue(),
+        output.display()
+    );
 
-    for entry in findings {
-        println!(
-            "  {} [{}] {}:",
-            "•".green(),
-            entry.file_shortcut.cyan(),
-            entry.key.yellow()
-        );
-        for group in &entry.groups {
-            println!(
-                "    - {}: {}",
-                format_language_codes(&group.languages).cyan(),
-                truncate_for_display(&compact_value_for_display(&group.value), 50)
-            );
+    Ok(())
+}
+
+fn print_diff(file: &Path, old: &str, new: &str) {
+    println!("{}", format!("--- {}", file.display()).red());
+    println!("{}", format!("+++ {}", file.display()).green());
+    for line in unified_diff(old, new, 3) {
+        match line {
+            DiffLine::Hunk(header) => println!("{}", header.cyan()),
+            DiffLine::Context(text) => println!(" {}", text),
+            DiffLine::Removed(text) => println!("{}", format!("-{}", text).red()),
+            DiffLine::Added(text) => println!("{}", format!("+{}", text).green()),
         }
-        println!();
     }
 }
 
+fn print_lint_finding(finding: &LintFinding) {
+    let marker = match finding.severity {
+        Severity::Error => "✗".red(),
+        _ => "⚠".yellow(),
+    };
+    let language = finding
+        .language
+        .as_ref()
+        .map(|lang| format!(" [{}]", lang.to_uppercase()))
+        .unwrap_or_default();
+    let line = finding
+        .line
+        .map(|line| format!(" line {}", line))
+        .unwrap_or_default();
+    let key = if finding.key.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", finding.key)
+    };
+
+    println!(
+        "  {}{}{}{} {}: {}",
+        marker,
+        key.yellow(),
+        language.cyan(),
+        line.dimmed(),
+        finding.rule.dimmed(),
+        finding.message
+    );
+}
+
 /// Handle the modify command: update specific language values for an existing key
 fn handle_modify(
-    file: &PathBuf,
+    file: &Path,
     key: &str,
     values: &std::collections::HashMap<String, String>,
     record_name: &str,
     languages: &[String],
 ) -> Result<()> {
     if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
     }
 
+    refuse_if_inconsistent(file, record_name, languages)?;
+
     // Check if key exists
     match check_key_exists_with_record_name(file, key, record_name, languages)? {
-        Some(existing) => {
-            // Parse the file to find field locations
-            let content = std::fs::read_to_string(file)?;
-            let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-            let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
-
-            // For each language we want to modify
-            for (lang, new_value) in values {
-                // Find the language record bounds
-                if let Some((_, start, end)) =
-                    parse_result.lang_bounds.iter().find(|(l, _, _)| l == lang)
-                {
-                    // Find the field within this language record
-                    let is_function = existing.is_function;
-                    let mut field_start = None;
-                    let mut field_end = None;
-
-                    let field_regex =
-                        regex::Regex::new(&format!(r"^\s*,?\s*{}\s*=", regex::escape(key)))?;
-                    let next_field_regex = regex::Regex::new(r"^\s*,?\s*\w+\s*=")?;
-
-                    let mut i = *start + 1;
-                    while i <= *end {
-                        if field_regex.is_match(&lines[i]) {
-                            field_start = Some(i);
-                            // Find the end of this field
-                            if is_function {
-                                let mut j = i + 1;
-                                while j <= *end {
-                                    let line = &lines[j];
-                                    let trimmed = line.trim();
-                                    if trimmed.starts_with('}') || next_field_regex.is_match(line) {
-                                        break;
-                                    }
-                                    j += 1;
-                                }
-                                field_end = Some(j - 1);
-                            } else {
-                                field_end = Some(i);
-                            }
-                            break;
-                        }
-                        i += 1;
-                    }
-
-                    if let (Some(fs), Some(fe)) = (field_start, field_end) {
-                        // Detect if it's the first field (uses { key = instead of , key =)
-                        let is_first = lines[fs].trim_start().starts_with('{');
-                        let prefix = if is_first { "    { " } else { "    , " };
-
-                        // Remove old field lines
-                        for _ in fs..=fe {
-                            lines.remove(fs);
-                        }
-
-                        // Insert new field
-                        if is_function {
-                            let new_lines: Vec<String> =
-                                format!("{}{} = {}", prefix, key, new_value)
-                                    .lines()
-                                    .map(|l| l.to_string())
-                                    .collect();
-                            for (idx, line) in new_lines.iter().enumerate() {
-                                lines.insert(fs + idx, line.clone());
-                            }
-                        } else {
-                            let escaped = new_value
-                                .replace('\\', "\\\\")
-                                .replace('"', "\\\"")
-                                .replace('\n', "\\n");
-                            lines.insert(fs, format!("{}{}= \"{}\"", prefix, key, escaped));
-                        }
-                    }
-                }
-            }
-
-            // Write back
-            let new_content = lines.join("\n");
-            std::fs::write(file, new_content)?;
+        Some(_) => {
+            let content = read_elm_file(file)?;
+            let new_content =
+                modify_translation_in_content(&content, key, values, record_name, languages)?;
+            timing::write(file, new_content)?;
 
             println!(
                 "{} Modified translation '{}' in {}",
@@ -2509,183 +11853,744 @@ fn handle_modify(
             }
         }
         None => {
-            eprintln!(
-                "{} Translation '{}' not found in {}",
-                "✗".red(),
+            errors::error_with(
+                Code::KeyNotFound,
+                format!(
+                    "Translation '{}' not found in {}",
+                    key.yellow(),
+                    file.display()
+                ),
+            );
+            exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the add-case command: add a branch to a function translation's `case`
+/// in each language given, or in none if any of them can't take it
+fn handle_add_case(
+    file: &Path,
+    key: &str,
+    branches: &std::collections::HashMap<String, String>,
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let content = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    match parse_result.translations.get(key) {
+        Some(translation) if translation.is_function => {}
+        Some(_) => {
+            errors::error_with(
+                Code::InvalidArgument,
+                format!(
+                    "'{}' isn't a function translation, so it has no `case` to add to",
+                    key.yellow()
+                ),
+            );
+            exit(1);
+        }
+        None => {
+            errors::error_with(
+                Code::KeyNotFound,
+                format!(
+                    "Translation '{}' not found in {}",
+                    key.yellow(),
+                    file.display()
+                ),
+            );
+            exit(1);
+        }
+    }
+
+    let mut updated = content.clone();
+    let mut added = Vec::new();
+    let mut patterns = Vec::new();
+    for lang in languages {
+        let Some(branch) = branches.get(lang) else {
+            continue;
+        };
+        let Some((pattern, body)) = branch
+            .split_once("->")
+            .filter(|(pattern, body)| !pattern.trim().is_empty() && !body.trim().is_empty())
+        else {
+            errors::error_with(Code::InvalidArgument, format!("Invalid branch for {}: '{}'. Expected PATTERN -> VALUE (e.g., Few -> \"quelques\")", lang.to_uppercase(),
+                branch));
+            exit(1);
+        };
+
+        // Each insertion moves the lines after it, so find the field afresh
+        let parse_result = parse_i18n_content_with_record_name(&updated, record_name, languages)?;
+        let Some(lines) = field_lines(&parse_result, lang, key) else {
+            continue;
+        };
+        match add_case_branch(&updated, lines, pattern, body) {
+            Ok(content) => updated = content,
+            Err(e) => {
+                errors::error(format!(
+                    "The {} value of '{}' {}",
+                    lang.to_uppercase(),
+                    key.yellow(),
+                    e
+                ));
+                exit(1);
+            }
+        }
+        added.push(lang.to_uppercase());
+        if !patterns.contains(&pattern.trim()) {
+            patterns.push(pattern.trim());
+        }
+    }
+
+    if dry_run {
+        print_diff(file, &content, &updated);
+        println!();
+    } else {
+        timing::write(file, &updated)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+    }
+    println!(
+        "{} {} the `{}` branch to '{}' ({})",
+        "✓".green(),
+        if dry_run { "Would add" } else { "Added" },
+        patterns.join("`/`"),
+        key.yellow(),
+        added.join(", ")
+    );
+    let skipped: Vec<String> = languages
+        .iter()
+        .filter(|lang| !branches.contains_key(*lang))
+        .map(|lang| lang.to_uppercase())
+        .collect();
+    if !skipped.is_empty() {
+        println!(
+            "{} No branch given for {}; left as it was",
+            "⚠".yellow(),
+            skipped.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Handle the rename-placeholder command: rename `{old}` to `{new}` in each
+/// language of a key, warning about languages that don't use it
+fn handle_rename_placeholder(
+    file: &Path,
+    key: &str,
+    old: &str,
+    new: &str,
+    dry_run: bool,
+    record_name: &str,
+    languages: &[String],
+) -> Result<()> {
+    if !file.exists() {
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
+    }
+
+    // Accept `{name}` as well as `name`
+    let old = old
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .trim();
+    let new = new
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .trim();
+    let identifier = regex::Regex::new(r"^[a-z_][A-Za-z0-9_]*$")?;
+    if !identifier.is_match(old) {
+        errors::error_with(
+            Code::InvalidArgument,
+            format!("'{}' isn't a placeholder name", old.yellow()),
+        );
+        exit(1);
+    }
+    // The new name may become a function argument, so it must be one Elm accepts
+    if !identifier.is_match(new) || ELM_RESERVED_WORDS.contains(&new) {
+        errors::error_with(
+            Code::InvalidArgument,
+            format!(
+                "'{}' can't be a placeholder name; use a lowercase Elm name such as userName",
+                new.yellow()
+            ),
+        );
+        exit(1);
+    }
+    if old == new {
+        errors::hint(format!("The placeholder is already called '{}'", old));
+        return Ok(());
+    }
+
+    refuse_if_inconsistent(file, record_name, languages)?;
+
+    let content = read_elm_file(file)?;
+    let parse_result = parse_i18n_content_with_record_name(&content, record_name, languages)?;
+    let Some(translation) = parse_result.translations.get(key) else {
+        errors::error_with(
+            Code::KeyNotFound,
+            format!(
+                "Translation '{}' not found in {}",
                 key.yellow(),
                 file.display()
+            ),
+        );
+        exit(1);
+    };
+    // Renaming onto a name the value already uses would merge two placeholders
+    for lang in languages {
+        let Some(value) = translation.values.get(lang) else {
+            continue;
+        };
+        if placeholder::rename_in_value(value, new, new).1 > 0
+            || placeholder::lambda_arguments(value)
+                .iter()
+                .any(|arg| arg == new)
+        {
+            errors::error_with(
+                Code::AlreadyExists,
+                format!(
+                    "The {} value of '{}' already uses '{}'",
+                    lang.to_uppercase(),
+                    key.yellow(),
+                    new
+                ),
             );
-            std::process::exit(1);
+            exit(1);
         }
     }
 
+    let rename = placeholder::rename_placeholder_in_content(
+        &content,
+        key,
+        old,
+        new,
+        record_name,
+        languages,
+    )?;
+    let (found, missing): (Vec<_>, Vec<_>) =
+        rename.renamed.iter().partition(|(_, count)| *count > 0);
+    if found.is_empty() {
+        errors::error(format!(
+            "No value of '{}' has a {{{}}} placeholder",
+            key.yellow(),
+            old
+        ));
+        exit(1);
+    }
+
+    if dry_run {
+        print_diff(file, &content, &rename.content);
+        println!();
+    } else {
+        timing::write(file, &rename.content)
+            .with_context(|| format!("Failed to write to {}", file.display()))?;
+    }
+
+    let languages_done: Vec<String> = found.iter().map(|(lang, _)| lang.to_uppercase()).collect();
+    println!(
+        "{} {} {{{}}} to {{{}}} in '{}' ({})",
+        "✓".green(),
+        if dry_run { "Would rename" } else { "Renamed" },
+        old,
+        new.green(),
+        key.yellow(),
+        languages_done.join(", ")
+    );
+    for (lang, _) in &missing {
+        println!(
+            "{} The {} value has no {{{}}}{}",
+            "⚠".yellow(),
+            lang.to_uppercase(),
+            old,
+            if rename.record_field {
+                ""
+            } else {
+                "; left as it was"
+            }
+        );
+    }
+    if rename.record_field {
+        println!(
+            "{} '{}' takes {{ {} = … }} now; update the code that calls it",
+            "→".cyan(),
+            key.yellow(),
+            new
+        );
+    }
+    if !missing.is_empty() {
+        println!(
+            "{} Run 'elm-i18n validate' to check the placeholders agree",
+            "→".cyan()
+        );
+    }
     Ok(())
 }
 
 /// Handle the modify-bulk command: update all translations for one language from a JSON file
+/// Whether `modify-bulk` writes, or only shows what it would change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BulkPreview {
+    Off,
+    Text,
+    Json,
+}
+
+impl BulkPreview {
+    fn from_flags(dry_run: bool, json: bool) -> Self {
+        match (dry_run, json) {
+            (_, true) => BulkPreview::Json,
+            (true, false) => BulkPreview::Text,
+            (false, false) => BulkPreview::Off,
+        }
+    }
+}
+
+/// What `modify-bulk` does with one key of the JSON file
+#[derive(Debug)]
+enum BulkOperation {
+    Update {
+        key: String,
+        /// The lines the field takes, from its name to the end of its value
+        lines: (usize, usize),
+        from: Option<String>,
+        to: String,
+    },
+    /// The key already has the JSON's value, so there's nothing to write
+    Unchanged {
+        key: String,
+    },
+    Skip {
+        key: String,
+        reason: String,
+    },
+}
+
+/// Everything `modify-bulk` would do, worked out before the file is touched
+struct BulkPlan {
+    operations: Vec<BulkOperation>,
+    /// Keys of the record the JSON has no value for
+    absent: usize,
+    functions: usize,
+    still_todo: usize,
+    empty: usize,
+    unchanged: usize,
+    /// Updates that replace a TODO marker
+    cleared: usize,
+}
+
+impl BulkPlan {
+    fn summary(&self) -> Summary {
+        let updates = self
+            .operations
+            .iter()
+            .filter(|operation| matches!(operation, BulkOperation::Update { .. }))
+            .count();
+        Summary {
+            changed: updates,
+            skipped: self.operations.len() - updates,
+            ..Summary::default()
+        }
+    }
+
+    /// The plan as `--dry-run --json` prints it
+    fn to_json(&self, lang: &str) -> serde_json::Value {
+        let entries: Vec<serde_json::Value> = self
+            .operations
+            .iter()
+            .map(|operation| match operation {
+                BulkOperation::Update { key, from, to, .. } => serde_json::json!({
+                    "op": "update",
+                    "key": key,
+                    "from": { lang: from },
+                    "to": { lang: to },
+                }),
+                BulkOperation::Unchanged { key } => serde_json::json!({
+                    "op": "unchanged",
+                    "key": key,
+                }),
+                BulkOperation::Skip { key, reason } => serde_json::json!({
+                    "op": "skip",
+                    "key": key,
+                    "reason": reason,
+                }),
+            })
+            .collect();
+        serde_json::json!({ "operations": entries, "summary": self.summary() })
+    }
+}
+
+/// Plan what importing `translations_map` into the `lang` record does to each key
+fn plan_modify_bulk(
+    parse_result: &ParseResult,
+    lang: &str,
+    translations_map: &std::collections::HashMap<String, String>,
+    json_file: &Path,
+    todo_marker: &str,
+    allow_partial: bool,
+) -> Result<BulkPlan> {
+    // The fields of the target language record
+    let fields = parse_result.record_fields.get(lang).ok_or_else(|| {
+        Coded::new(
+            Code::UnknownLanguage,
+            format!("Language '{}' record not found in file", lang),
+        )
+    })?;
+
+    let mut plan = BulkPlan {
+        operations: Vec::new(),
+        absent: 0,
+        functions: 0,
+        still_todo: 0,
+        empty: 0,
+        unchanged: 0,
+        cleared: 0,
+    };
+    for field in fields {
+        let key = field.name.clone();
+        let new_value = match translations_map.get(&key) {
+            Some(new_value) => new_value,
+            None => {
+                plan.absent += 1;
+                continue;
+            }
+        };
+        let translation = parse_result.translations.get(&key);
+
+        // Skip non-String translations in bulk mode (JSON values are strings)
+        if translation.is_some_and(|t| t.type_signature.is_some()) {
+            plan.functions += 1;
+            plan.operations.push(BulkOperation::Skip {
+                key,
+                reason: "is a function or non-String value".to_string(),
+            });
+            continue;
+        }
+
+        // A value the translator hasn't done yet mustn't replace a real one
+        if new_value.trim().starts_with(todo_marker) {
+            plan.still_todo += 1;
+            plan.operations.push(BulkOperation::Skip {
+                key,
+                reason: format!(
+                    "is still marked `{}` in {}",
+                    todo_marker,
+                    json_file.display()
+                ),
+            });
+            continue;
+        }
+
+        // A key the translator left blank keeps the value it has
+        if allow_partial && new_value.trim().is_empty() {
+            plan.empty += 1;
+            plan.operations.push(BulkOperation::Skip {
+                key,
+                reason: format!("is empty in {}", json_file.display()),
+            });
+            continue;
+        }
+
+        let old_value = translation.and_then(|t| t.values.get(lang));
+        let from = old_value.map(|v| plain_string_value(v).unwrap_or_else(|| v.trim().to_string()));
+        // Elm escape sequences in the JSON (\n, \", ...) stand for what they escape
+        if old_value.and_then(|v| plain_string_value(v)) == Some(escaping::unescape(new_value)) {
+            plan.unchanged += 1;
+            plan.operations.push(BulkOperation::Unchanged { key });
+            continue;
+        }
+        if value_status(old_value, todo_marker) == ValueStatus::Todo {
+            plan.cleared += 1;
+        }
+        plan.operations.push(BulkOperation::Update {
+            from,
+            to: new_value.clone(),
+            lines: (field.line, field.end_line.max(field.line)),
+            key,
+        });
+    }
+
+    let mut unknown: Vec<&String> = translations_map
+        .keys()
+        .filter(|key| !parse_result.translations.contains_key(*key))
+        .collect();
+    unknown.sort();
+    for key in unknown {
+        plan.operations.push(BulkOperation::Skip {
+            key: key.clone(),
+            reason: "isn't in the file (modify-bulk doesn't add keys)".to_string(),
+        });
+    }
+    Ok(plan)
+}
+
 fn handle_modify_bulk(
-    file: &PathBuf,
+    file: &Path,
     lang: &str,
-    json_file: &PathBuf,
+    json_file: &Path,
+    todo_marker: &str,
+    preview: BulkPreview,
+    allow_partial: bool,
     record_name: &str,
     languages: &[String],
 ) -> Result<()> {
     use std::collections::HashMap;
 
     if !file.exists() {
-        eprintln!("{} File not found: {}", "✗".red(), file.display());
-        std::process::exit(1);
+        errors::error_with(
+            Code::FileNotFound,
+            format!("File not found: {}", file.display()),
+        );
+        exit(1);
     }
 
+    refuse_if_inconsistent(file, record_name, languages)?;
+
     if !json_file.exists() {
-        eprintln!("{} JSON file not found: {}", "✗".red(), json_file.display());
-        std::process::exit(1);
+        errors::error_with(
+            Code::FileNotFound,
+            format!("JSON file not found: {}", json_file.display()),
+        );
+        exit(1);
     }
 
-    let lang = lang.to_lowercase();
+    let lang = configured_language(lang, languages);
     if !languages.contains(&lang) {
-        eprintln!(
-            "{} Language '{}' is not in configured languages: {}",
-            "✗".red(),
-            lang.yellow(),
-            languages.join(", ")
+        errors::error_with(
+            Code::UnknownLanguage,
+            format!(
+                "Language '{}' is not in configured languages: {}",
+                lang.yellow(),
+                languages.join(", ")
+            ),
         );
-        std::process::exit(1);
+        exit(1);
     }
 
     // Read the JSON translations
     let json_content = std::fs::read_to_string(json_file)?;
-    let translations_map: HashMap<String, String> = serde_json::from_str(&json_content)
-        .map_err(|e| anyhow::anyhow!("Failed to parse JSON file {}: {}", json_file.display(), e))?;
+    let translations_map: HashMap<String, String> =
+        serde_json::from_str(&json_content).map_err(|e| {
+            Coded::new(
+                Code::ParseError,
+                format!("Failed to parse JSON file {}: {}", json_file.display(), e),
+            )
+        })?;
 
     if translations_map.is_empty() {
-        println!("{} No translations in JSON file", "ℹ".blue());
+        if preview == BulkPreview::Json {
+            let output = serde_json::json!({ "operations": [], "summary": Summary::default() });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("{} No translations in JSON file", "ℹ".blue());
+            Summary::default().print();
+        }
         return Ok(());
     }
 
-    println!(
-        "{} Applying {} translations for '{}' to {}...",
-        "→".cyan(),
-        translations_map.len(),
-        lang.to_uppercase().yellow(),
-        file.display()
-    );
+    if preview != BulkPreview::Json {
+        println!(
+            "{} {} {} translations for '{}' {} {}...",
+            "→".cyan(),
+            if preview == BulkPreview::Off {
+                "Applying"
+            } else {
+                "Checking"
+            },
+            translations_map.len(),
+            lang.to_uppercase().yellow(),
+            if preview == BulkPreview::Off {
+                "to"
+            } else {
+                "against"
+            },
+            file.display()
+        );
+    }
 
     // Parse the file to find the language record
     let parse_result = parse_i18n_file_with_record_name(file, record_name, languages)?;
-    let content = std::fs::read_to_string(file)?;
+    let content = read_elm_file(file)?;
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
-    // Find the target language record bounds
-    let (_, lang_start, lang_end) = parse_result
-        .lang_bounds
-        .iter()
-        .find(|(l, _, _)| *l == lang)
-        .ok_or_else(|| anyhow::anyhow!("Language '{}' record not found in file", lang))?;
-
-    let field_regex = regex::Regex::new(r"^\s*[,{]\s*(\w+)\s*=")?;
-    let mut modified = 0;
-    let mut skipped = 0;
-
-    // Iterate through the language record and replace values
-    let mut i = *lang_start + 1;
-    while i < *lang_end {
-        if let Some(captures) = field_regex.captures(&lines[i].clone()) {
-            let key = captures[1].to_string();
-
-            if let Some(new_value) = translations_map.get(&key) {
-                // Check if this is a function (multiline) translation
-                let is_function = parse_result
-                    .translations
-                    .get(&key)
-                    .map(|t| t.is_function)
-                    .unwrap_or(false);
-
-                if is_function {
-                    // Skip function translations in bulk mode (too complex for JSON)
-                    skipped += 1;
-                    i += 1;
-                    continue;
+    // Plan every change before touching the file, so --dry-run shows exactly
+    // what a real run would do
+    let plan = plan_modify_bulk(
+        &parse_result,
+        &lang,
+        &translations_map,
+        json_file,
+        todo_marker,
+        allow_partial,
+    )?;
+    let summary = plan.summary();
+    match preview {
+        BulkPreview::Json => {
+            println!("{}", serde_json::to_string_pretty(&plan.to_json(&lang))?);
+            return Ok(());
+        }
+        BulkPreview::Text => {
+            for operation in &plan.operations {
+                match operation {
+                    BulkOperation::Update { key, from, to, .. } => println!(
+                        "  {} {}: {} → {}",
+                        "~".yellow(),
+                        key.yellow(),
+                        from.as_deref()
+                            .map(|v| format!("{:?}", v))
+                            .unwrap_or_else(|| "(none)".to_string()),
+                        format!("{:?}", to).green()
+                    ),
+                    BulkOperation::Unchanged { key } => {
+                        println!("  {} {} {}", "=".dimmed(), key, "is unchanged".dimmed())
+                    }
+                    BulkOperation::Skip { key, reason } => {
+                        println!("  {} {} {}", "-".dimmed(), key, reason.dimmed())
+                    }
                 }
+            }
+            println!("{} Dry run: nothing was written", "ℹ".blue());
+            summary.print();
+            return Ok(());
+        }
+        BulkPreview::Off => {}
+    }
 
-                // Detect prefix (first field uses "{ ", others use ", ")
-                let line = &lines[i];
-                let prefix = if line.trim_start().starts_with('{') {
-                    "    { "
-                } else {
-                    "    , "
-                };
+    // From the bottom up, so the lines of the fields above stay where they were
+    // when a value over several lines becomes one
+    let mut modified = 0;
+    for operation in plan.operations.iter().rev() {
+        let (key, (line, end_line), new_value) = match operation {
+            BulkOperation::Update { key, lines, to, .. } => (key, *lines, to),
+            BulkOperation::Unchanged { .. } | BulkOperation::Skip { .. } => continue,
+        };
 
-                // Replace the line with the new value
-                // Preserve Elm escape sequences (\n, \t, \r, \\) while escaping other chars
-                let escaped = new_value
-                    .replace("\\\\", "\x00BACKSLASH\x00") // Protect existing \\
-                    .replace("\\n", "\x00NEWLINE\x00") // Protect \n
-                    .replace("\\t", "\x00TAB\x00") // Protect \t
-                    .replace("\\r", "\x00CR\x00") // Protect \r
-                    .replace("\\\"", "\x00QUOTE\x00") // Protect \"
-                    .replace('\\', "\\\\") // Escape remaining backslashes
-                    .replace('"', "\\\"") // Escape quotes
-                    .replace('\n', "\\n") // Escape actual newlines
-                    .replace("\x00BACKSLASH\x00", "\\\\") // Restore \\
-                    .replace("\x00NEWLINE\x00", "\\n") // Restore \n
-                    .replace("\x00TAB\x00", "\\t") // Restore \t
-                    .replace("\x00CR\x00", "\\r") // Restore \r
-                    .replace("\x00QUOTE\x00", "\\\""); // Restore \"
-                lines[i] = format!("{}{} = \"{}\"", prefix, key, escaped);
-                modified += 1;
-            }
-        }
-        i += 1;
+        // Keep the field's indentation and its `{` or `,`
+        let field = lines[line].trim_start();
+        let indentation = lines[line][..lines[line].len() - field.len()].to_string();
+        let separator = if field.starts_with('{') { '{' } else { ',' };
+
+        // Replace the field, all of its lines, with the new value
+        // Elm escape sequences already in the value (\n, \", ...) are kept as they are
+        let escaped = escaping::escape(&escaping::unescape(new_value));
+        lines.splice(
+            line..=end_line,
+            [format!(
+                "{}{} {} = \"{}\"",
+                indentation, separator, key, escaped
+            )],
+        );
+        modified += 1;
+    }
+    // Write back
+    let new_content = lines.join("\n");
+    timing::write(file, new_content)?;
+
+    if allow_partial {
+        let left = [
+            (plan.empty, format!("empty in {}", json_file.display())),
+            (plan.absent, "not in it".to_string()),
+            (plan.functions, "functions".to_string()),
+            (plan.still_todo, format!("still marked `{}`", todo_marker)),
+            (plan.unchanged, "unchanged".to_string()),
+        ];
+        let reasons: Vec<String> = left
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, reason)| format!("{} {}", count, reason))
+            .collect();
+        let total: usize = left.iter().map(|(count, _)| count).sum();
+        println!(
+            "{} Updated {} translations, left {} as they were{}",
+            "✓".green(),
+            modified.to_string().yellow(),
+            total,
+            if reasons.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", reasons.join(", "))
+            }
+        );
+    } else {
+        println!(
+            "{} Modified {} translations, skipped {} function translations{}",
+            "✓".green(),
+            modified.to_string().yellow(),
+            plan.functions,
+            if plan.unchanged > 0 {
+                format!(", {} already up to date", plan.unchanged)
+            } else {
+                String::new()
+            }
+        );
+    }
+    if plan.cleared > 0 {
+        println!(
+            "{} Cleared {} `{}` marker{}",
+            "✓".green(),
+            plan.cleared,
+            todo_marker,
+            if plan.cleared == 1 { "" } else { "s" }
+        );
+    }
+    if plan.still_todo > 0 && !allow_partial {
+        println!(
+            "{} Skipped {} value{} still marked `{}` in {}",
+            "ℹ".blue(),
+            plan.still_todo,
+            if plan.still_todo == 1 { "" } else { "s" },
+            todo_marker,
+            json_file.display()
+        );
     }
-
-    // Write back
-    let new_content = lines.join("\n");
-    std::fs::write(file, new_content)?;
-
-    println!(
-        "{} Modified {} translations, skipped {} function translations",
-        "✓".green(),
-        modified.to_string().yellow(),
-        skipped
-    );
+    summary.print();
 
     Ok(())
 }
 
 /// Handle the add-language command: add a new language by duplicating an existing one
 fn handle_add_language(config: &Config, new_lang: &str, from_lang: &str) -> Result<()> {
-    use std::fs;
-
-    let new_lang = new_lang.to_lowercase();
-    let from_lang = from_lang.to_lowercase();
+    let new_lang = normalize_languages([new_lang]).remove(0);
+    let from_lang = normalize_languages([from_lang]).remove(0);
     let languages = config.languages();
 
     // Validate
     if !languages.contains(&from_lang) {
-        eprintln!(
-            "{} Source language '{}' is not configured. Available: {}",
-            "✗".red(),
-            from_lang.yellow(),
-            languages.join(", ")
+        errors::error_with(
+            Code::UnknownLanguage,
+            format!(
+                "Source language '{}' is not configured. Available: {}",
+                from_lang.yellow(),
+                languages.join(", ")
+            ),
         );
-        std::process::exit(1);
+        exit(1);
     }
     if languages.contains(&new_lang) {
-        eprintln!(
-            "{} Language '{}' already exists in configuration",
-            "✗".red(),
-            new_lang.yellow()
+        errors::error_with(
+            Code::AlreadyExists,
+            format!(
+                "Language '{}' already exists in configuration",
+                new_lang.yellow()
+            ),
         );
-        std::process::exit(1);
-    }
-
-    fn capitalize_first(s: &str) -> String {
-        let mut chars = s.chars();
-        match chars.next() {
-            None => String::new(),
-            Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
-        }
+        exit(1);
     }
 
     // Get all translation files to process
@@ -2714,136 +12619,37 @@ fn handle_add_language(config: &Config, new_lang: &str, from_lang: &str) -> Resu
 
         println!("{} Processing {}...", "→".cyan(), file_path.display());
 
-        let content = fs::read_to_string(file_path)?;
-        let mut new_content = content.clone();
-
-        // 1. Add new variant to Language type
-        let from_upper = from_lang.to_uppercase();
-        let new_upper = new_lang.to_uppercase();
-        // Find the last language variant and add after it
-        if let Some(pos) = new_content.find(&format!("| {}\n", from_upper)) {
-            let insert_pos = pos + format!("| {}\n", from_upper).len();
-            new_content.insert_str(insert_pos, &format!("    | {}\n", new_upper));
-        } else if let Some(pos) = new_content.find(&format!("= {}\n", from_upper)) {
-            let insert_pos = pos + format!("= {}\n", from_upper).len();
-            new_content.insert_str(insert_pos, &format!("    | {}\n", new_upper));
-        } else {
-            // Add after the last variant we can find
-            let mut last_variant_end = None;
-            for lang in languages {
-                let upper = lang.to_uppercase();
-                if let Some(pos) = new_content.find(&format!("| {}\n", upper)) {
-                    let end = pos + format!("| {}\n", upper).len();
-                    last_variant_end = Some(end);
-                } else if let Some(pos) = new_content.find(&format!("= {}\n", upper)) {
-                    let end = pos + format!("= {}\n", upper).len();
-                    last_variant_end = Some(end);
-                }
-            }
-            if let Some(pos) = last_variant_end {
-                new_content.insert_str(pos, &format!("    | {}\n", new_upper));
-            }
-        }
-
-        // 2. Duplicate the source language's translation record
-        let from_cap = capitalize_first(&from_lang);
-        let new_cap = capitalize_first(&new_lang);
-        let from_fn_name = format!("translations{}", from_cap);
-        let new_fn_name = format!("translations{}", new_cap);
-
-        // Find the source translation record (type annotation + implementation)
-        if let Some(type_start) = new_content.find(&format!("{} : {}", from_fn_name, record_name)) {
-            // Find the end of the record (closing brace followed by blank line or next definition)
-            let after_type = &new_content[type_start..];
-            if let Some(brace_pos) = find_closing_brace(after_type) {
-                let record_end = type_start + brace_pos + 1;
-                let record_text = &new_content[type_start..record_end];
-
-                // Create the new record by replacing the function name
-                let new_record = record_text.replace(&from_fn_name, &new_fn_name);
-
-                // Insert after the source record (with spacing)
-                let insert_text = format!("\n\n{}", new_record);
-                new_content.insert_str(record_end, &insert_text);
-            }
-        }
-
-        // 3. Update languageToString: add new case
-        let lang_to_str_case = format!("        {} ->\n            \"{}\"", new_upper, new_lang);
-        // Try to insert after the last existing case before the function ends
-        if let Some(pos) = new_content.find(&format!(
-            "        {} ->\n            \"{}\"",
-            from_upper, from_lang
-        )) {
-            let case_end =
-                pos + format!("        {} ->\n            \"{}\"", from_upper, from_lang).len();
-            new_content.insert_str(case_end, &format!("\n\n{}", lang_to_str_case));
-        } else {
-            // from_lang might not have an explicit case; find the last explicit case in languageToString
-            // Insert before the closing of the function by finding the last case branch
-            let mut last_case_end = None;
-            for lang in languages {
-                let upper = lang.to_uppercase();
-                let pattern = format!("        {} ->\n            \"{}\"", upper, lang);
-                if let Some(pos) = new_content.find(&pattern) {
-                    let end = pos + pattern.len();
-                    if last_case_end.map_or(true, |prev| end > prev) {
-                        last_case_end = Some(end);
-                    }
-                }
-            }
-            if let Some(end) = last_case_end {
-                new_content.insert_str(end, &format!("\n\n{}", lang_to_str_case));
-            }
-        }
-
-        // 4. Update stringToLanguage: add new case before the default (_ ->) case
-        let str_to_lang_case = format!("        \"{}\" ->\n            {}", new_lang, new_upper);
-        if let Some(pos) = new_content.find(&format!(
-            "        \"{}\" ->\n            {}",
-            from_lang, from_upper
-        )) {
-            let case_end =
-                pos + format!("        \"{}\" ->\n            {}", from_lang, from_upper).len();
-            new_content.insert_str(case_end, &format!("\n\n{}", str_to_lang_case));
-        } else {
-            // from_lang is likely the default case (_ -> FROM_UPPER), insert before it
-            if let Some(pos) = new_content.find("        _ ->\n") {
-                // Find the stringToLanguage function context by checking we're in the right function
-                new_content.insert_str(pos, &format!("{}\n\n", str_to_lang_case));
-            }
+        let content = read_elm_file(file_path)?;
+        let edit = add_language_to_content(&content, &from_lang, &new_lang, record_name);
+        if !edit.missed.is_empty() {
+            println!(
+                "  {} Not found, so not updated: {}",
+                "ℹ".blue(),
+                edit.missed.join(", ")
+            );
         }
-
-        // 5. Update translations function: add new case
-        let translations_case = format!("        {} ->\n            {}", new_upper, new_fn_name);
-        if let Some(pos) = new_content.find(&format!(
-            "        {} ->\n            {}",
-            from_upper, from_fn_name
-        )) {
-            let case_end =
-                pos + format!("        {} ->\n            {}", from_upper, from_fn_name).len();
-            new_content.insert_str(case_end, &format!("\n\n{}", translations_case));
-        } else {
-            // from_lang is the default; find the last explicit case in translations function
-            let mut last_case_end = None;
-            for lang in languages {
-                let upper = lang.to_uppercase();
-                let cap = capitalize_first(lang);
-                let fn_name = format!("translations{}", cap);
-                let pattern = format!("        {} ->\n            {}", upper, fn_name);
-                if let Some(pos) = new_content.find(&pattern) {
-                    let end = pos + pattern.len();
-                    if last_case_end.map_or(true, |prev| end > prev) {
-                        last_case_end = Some(end);
-                    }
-                }
-            }
-            if let Some(end) = last_case_end {
-                new_content.insert_str(end, &format!("\n\n{}", translations_case));
+        let mut content = edit.content;
+        let mut with_new = languages.to_vec();
+        with_new.push(new_lang.clone());
+        for helpers in GenerateHelpers::in_source(&content) {
+            let todo_marker = config.lint().todo_marker();
+            if let Some(refreshed) = with_generated_helpers(
+                &content,
+                &helpers,
+                &with_new,
+                todo_marker,
+                config.key_style(),
+            ) {
+                content = refreshed;
+                println!(
+                    "  {} Refreshed the {} helpers",
+                    "✓".green(),
+                    helpers.block()
+                );
             }
         }
 
-        fs::write(file_path, new_content)?;
+        timing::write(file_path, content)?;
         println!(
             "  {} Added language '{}' (copied from '{}')",
             "✓".green(),
@@ -2875,30 +12681,179 @@ fn handle_add_language(config: &Config, new_lang: &str, from_lang: &str) -> Resu
     Ok(())
 }
 
-/// Find the position of the closing brace that ends a record definition
-fn find_closing_brace(text: &str) -> Option<usize> {
-    let mut brace_count = 0;
-    let mut found_open = false;
-    for (i, c) in text.char_indices() {
-        if c == '{' {
-            brace_count += 1;
-            found_open = true;
-        } else if c == '}' {
-            brace_count -= 1;
-            if found_open && brace_count == 0 {
-                return Some(i);
-            }
-        }
-    }
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
     use tempfile::TempDir;
 
+    #[test]
+    fn rewrites_export_format_flags() {
+        let args = |args: &[&str]| expand_flags(args.iter().map(|a| a.to_string()));
+        assert_eq!(
+            args(&["elm-i18n", "export", "--format", "md", "--output", "t.md"]),
+            ["elm-i18n", "export", "md", "--output", "t.md"]
+        );
+        assert_eq!(
+            args(&["elm-i18n", "export", "--format=toml"]),
+            ["elm-i18n", "export", "toml"]
+        );
+        assert_eq!(
+            args(&["elm-i18n", "export", "dart"]),
+            ["elm-i18n", "export", "dart"]
+        );
+        // The global --format that runs elm-format takes no value
+        assert_eq!(
+            args(&["elm-i18n", "add", "k", "--format", "-t", "en=md"]),
+            ["elm-i18n", "add", "k", "--format", "-t", "en=md"]
+        );
+        assert_eq!(
+            args(&["elm-i18n", "export", "md", "--format"]),
+            ["elm-i18n", "export", "md", "--format"]
+        );
+    }
+
+    #[test]
+    fn parses_changelog_format_flags() {
+        // Parsing the whole command line needs more stack than a test thread has
+        let parse = |args: &'static [&'static str]| {
+            std::thread::Builder::new()
+                .stack_size(64 * 1024 * 1024)
+                .spawn(move || {
+                    let cli = Cli::try_parse_from(expand_flags(args.iter().map(|a| a.to_string())))
+                        .unwrap();
+                    match cli.command {
+                        Commands::Changelog { from, json, .. } => (from, json, cli.format),
+                        _ => panic!("not changelog"),
+                    }
+                })
+                .unwrap()
+                .join()
+                .unwrap()
+        };
+        assert_eq!(
+            parse(&[
+                "elm-i18n",
+                "changelog",
+                "--from",
+                "v2.3.0",
+                "--format",
+                "markdown"
+            ]),
+            ("v2.3.0".to_string(), false, false)
+        );
+        assert_eq!(
+            parse(&["elm-i18n", "changelog", "--format=json", "--from", "v2.3.0"]),
+            ("v2.3.0".to_string(), true, false)
+        );
+        // Followed by anything else, it's still the global --format
+        assert_eq!(
+            parse(&["elm-i18n", "changelog", "--from", "v2.3.0", "--format"]),
+            ("v2.3.0".to_string(), false, true)
+        );
+    }
+
+    #[test]
+    fn rewrites_plural_form_flags() {
+        let args = |args: &[&str]| plural_form_flags(args.iter().map(|a| a.to_string()));
+        assert_eq!(
+            args(&[
+                "elm-i18n",
+                "add-plural",
+                "items",
+                "--en-one",
+                "1 item",
+                "--pt-BR-other={count} itens",
+                "--file",
+                "I18n.elm"
+            ]),
+            [
+                "elm-i18n",
+                "add-plural",
+                "items",
+                "-t",
+                "en.one=1 item",
+                "-t",
+                "pt-BR.other={count} itens",
+                "--file",
+                "I18n.elm"
+            ]
+        );
+        assert_eq!(
+            args(&[
+                "elm-i18n",
+                "add-plural",
+                "items",
+                "--fr-0",
+                "Aucun",
+                "--",
+                "--en-one"
+            ]),
+            [
+                "elm-i18n",
+                "add-plural",
+                "items",
+                "-t",
+                "fr.0=Aucun",
+                "--",
+                "--en-one"
+            ]
+        );
+        // Only add-plural has them
+        assert_eq!(
+            args(&["elm-i18n", "add", "items", "--en-one", "x"]),
+            ["elm-i18n", "add", "items", "--en-one", "x"]
+        );
+    }
+
+    #[test]
+    fn draws_completeness_bars() {
+        assert_eq!(completeness_bar(82, 100, 10), "████████░░");
+        assert_eq!(completeness_bar(999, 1000, 10), "█████████░");
+        assert_eq!(completeness_bar(3, 3, 10), "██████████");
+        assert_eq!(completeness_bar(0, 7, 4), "░░░░");
+        // A file with no keys has nothing left to translate
+        assert_eq!(completeness_bar(0, 0, 4), "████");
+    }
+
+    #[test]
+    fn finds_keys_with_identical_source_text() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            r#"type alias Translations =
+    { cancel : String
+    , btnCancel : String
+    , cancelled : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { cancel = "Cancel"
+    , btnCancel = "Cancel"
+    , cancelled = "Cancelled"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { cancel = "Annuler"
+    , btnCancel = "Annuler"
+    , cancelled = "Annulé"
+    }
+"#,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keys_with_identical_text(&parse_result, "en", "Cancel"),
+            vec!["btnCancel".to_string(), "cancel".to_string()]
+        );
+        assert!(keys_with_identical_text(&parse_result, "en", "cancel").is_empty());
+    }
+
     #[test]
     fn finds_shared_language_value_groups() {
         let languages = vec![
@@ -3158,4 +13113,429 @@ translationsEs =
         assert_eq!(loaded, store);
         assert!(suppressed_path.exists());
     }
+
+    #[test]
+    fn marks_listed_keys_against_a_base() {
+        let plain = |text: String| {
+            regex::Regex::new(r"\x1b\[[0-9;]*m")
+                .unwrap()
+                .replace_all(&text, "")
+                .into_owned()
+        };
+        let changes = KeyChanges {
+            added: vec![("contact".to_string(), Default::default())],
+            removed: vec![("loading".to_string(), Default::default())],
+            changed: vec![(
+                "welcome".to_string(),
+                vec![
+                    (
+                        "fr".to_string(),
+                        "Bienvenue".to_string(),
+                        "Salut".to_string(),
+                    ),
+                    (
+                        "de".to_string(),
+                        "Willkommen".to_string(),
+                        "Hallo".to_string(),
+                    ),
+                ],
+            )],
+        };
+        let marks: Vec<(String, String)> = ["contact", "loading", "welcome", "save"]
+            .iter()
+            .map(|key| {
+                let mark = list_mark(Some(&changes), key);
+                (plain(mark.bullet().to_string()), plain(mark.label()))
+            })
+            .collect();
+        assert_eq!(
+            marks,
+            vec![
+                ("+".to_string(), " new".to_string()),
+                ("-".to_string(), " removed".to_string()),
+                ("~".to_string(), " changed in FR, DE".to_string()),
+                ("•".to_string(), String::new()),
+            ]
+        );
+        assert!(matches!(list_mark(None, "contact"), ListMark::Unchanged));
+    }
+
+    #[test]
+    fn leaves_keys_a_partial_import_has_no_value_for() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { save : String\n    , cancel : String\n    , title : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    , title = \"Profile\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { save = \"Sauver\"\n    , cancel = \"Annuler\"\n    , title = \"Profil\"\n    }\n",
+        )
+        .unwrap();
+        let json = temp_dir.path().join("fr.json");
+        std::fs::write(&json, r#"{"save": "Enregistrer", "cancel": " "}"#).unwrap();
+
+        let import = |allow_partial: bool| {
+            handle_modify_bulk(
+                &file,
+                "fr",
+                &json,
+                "TODO",
+                BulkPreview::Off,
+                allow_partial,
+                "Translations",
+                &languages,
+            )
+            .unwrap();
+            parse_i18n_file_with_record_name(&file, "Translations", &languages)
+                .unwrap()
+                .translations
+        };
+        let fr = |translations: &HashMap<String, Translation>, key: &str| {
+            translations[key].values["fr"].clone()
+        };
+        let translations = import(true);
+        assert_eq!(fr(&translations, "save"), "\"Enregistrer\"");
+        assert_eq!(fr(&translations, "cancel"), "\"Annuler\"");
+        assert_eq!(fr(&translations, "title"), "\"Profil\"");
+        // Without it the blank replaces the translation, as before
+        assert_eq!(fr(&import(false), "cancel"), "\" \"");
+    }
+
+    #[test]
+    fn bulk_replaces_the_whole_of_a_value_over_several_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { intro : String\n    , save : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { intro =\n        \"Welcome to \"\n            ++ \"the app\"\n    , save = \"Save\"\n    }\n",
+        )
+        .unwrap();
+        let json = temp_dir.path().join("en.json");
+        std::fs::write(&json, r#"{"intro": "Hello", "save": "Keep"}"#).unwrap();
+
+        handle_modify_bulk(
+            &file,
+            "en",
+            &json,
+            "TODO",
+            BulkPreview::Off,
+            false,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        // Not just the line with its name, leaving the rest of the old value behind
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(content.contains("    { intro = \"Hello\"\n    , save = \"Keep\"\n    }"));
+        assert!(!content.contains("the app"));
+    }
+
+    #[test]
+    fn bulk_dry_run_lists_values_already_there_as_unchanged() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            "type alias Translations =\n    { save : String\n    , cancel : String\n    , quote : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    , quote = \"Say \\\"hi\\\"\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { save = \"Enregistrer\"\n    , cancel = \"Annuler\"\n    , quote = \"Dis \\\"salut\\\"\"\n    }\n",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let json: HashMap<String, String> = serde_json::from_str(
+            r#"{"save": "Enregistrer", "cancel": "Abandonner", "quote": "Dis \\\"salut\\\"", "extra": "En plus"}"#,
+        )
+        .unwrap();
+
+        let plan = plan_modify_bulk(
+            &parse_result,
+            "fr",
+            &json,
+            Path::new("fr.json"),
+            "TODO",
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            plan.to_json("fr")["operations"],
+            serde_json::json!([
+                { "op": "unchanged", "key": "save" },
+                {
+                    "op": "update",
+                    "key": "cancel",
+                    "from": { "fr": "Annuler" },
+                    "to": { "fr": "Abandonner" }
+                },
+                { "op": "unchanged", "key": "quote" },
+                {
+                    "op": "skip",
+                    "key": "extra",
+                    "reason": "isn't in the file (modify-bulk doesn't add keys)"
+                }
+            ])
+        );
+        assert_eq!(plan.unchanged, 2);
+    }
+
+    #[test]
+    fn bulk_summary_counts_only_values_that_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { save : String\n    , cancel : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { save = \"Save\"\n    , cancel = \"Cancel\"\n    }\n",
+        )
+        .unwrap();
+        let json = temp_dir.path().join("en.json");
+        std::fs::write(&json, r#"{"save": "Save", "cancel": "Dismiss"}"#).unwrap();
+        let summary = || {
+            let parse_result =
+                parse_i18n_file_with_record_name(&file, "Translations", &languages).unwrap();
+            let map: HashMap<String, String> =
+                serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+            plan_modify_bulk(&parse_result, "en", &map, &json, "TODO", false)
+                .unwrap()
+                .summary()
+        };
+
+        assert_eq!(summary().to_string(), "+0 -0 ~1 skipped 1");
+        handle_modify_bulk(
+            &file,
+            "en",
+            &json,
+            "TODO",
+            BulkPreview::Off,
+            false,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        // Importing the same file again changes nothing
+        assert_eq!(summary().to_string(), "+0 -0 ~0 skipped 2");
+    }
+
+    #[test]
+    fn bulk_keeps_the_indentation_of_the_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n  { save : String\n  , cancel : String\n  }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n  { save = \"Save\"\n  , cancel = \"Cancel\"\n  }\n",
+        )
+        .unwrap();
+        let json = temp_dir.path().join("en.json");
+        std::fs::write(&json, r#"{"save": "Keep", "cancel": "Dismiss"}"#).unwrap();
+
+        handle_modify_bulk(
+            &file,
+            "en",
+            &json,
+            "TODO",
+            BulkPreview::Off,
+            false,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert!(std::fs::read_to_string(&file)
+            .unwrap()
+            .contains("  { save = \"Keep\"\n  , cancel = \"Dismiss\"\n  }"));
+    }
+
+    #[test]
+    fn validate_fails_on_warnings_only_when_asked() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { greeting : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { greeting = \"Hello %s\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { greeting = \"Bonjour %s\"\n    }\n",
+        )
+        .unwrap();
+        let files = vec![(file, "Translations".to_string())];
+        let status = |fail_on_warning: bool| {
+            handle_validate(
+                &files,
+                &languages,
+                false,
+                false,
+                Some(PlaceholderStyle::Braces),
+                fail_on_warning,
+            )
+            .unwrap()
+        };
+
+        // A `%s` in a braces project is a warning
+        assert_eq!(status(false), 0);
+        assert_eq!(status(true), 1);
+    }
+
+    #[test]
+    fn check_prints_one_language_or_all_as_json() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            "type alias Translations =\n    { welcome : String\n    , count : Int -> String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { welcome = \"Say \\\"hi\\\"\"\n    , count = \\n -> String.fromInt n\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { welcome = \"Bienvenue\"\n    , count = \\n -> String.fromInt n\n    }\n",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let welcome = parse_result.translations.get("welcome");
+
+        // --language prints the text alone, a function as its code
+        assert_eq!(value_text(&welcome.unwrap().values["en"]), "Say \"hi\"");
+        assert_eq!(
+            value_text(&parse_result.translations["count"].values["fr"]),
+            "\\n -> String.fromInt n"
+        );
+        assert_eq!(
+            check_json("welcome", welcome, Some("fr"), &languages),
+            serde_json::json!({ "key": "welcome", "language": "fr", "value": "Bienvenue" })
+        );
+        assert_eq!(
+            check_json("welcome", welcome, None, &languages),
+            serde_json::json!({
+                "key": "welcome",
+                "values": { "en": "Say \"hi\"", "fr": "Bienvenue" }
+            })
+        );
+        assert_eq!(
+            check_json("missing", None, Some("fr"), &languages),
+            serde_json::json!({ "key": "missing", "found": false })
+        );
+    }
+
+    #[test]
+    fn scaffold_adds_only_the_keys_not_already_there() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let manifest = temp_dir.path().join("keys.txt");
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { greeting : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { greeting = \"Hello\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { greeting = \"Bonjour\"\n    }\n",
+        )
+        .unwrap();
+        std::fs::write(&manifest, "# checkout\ngreeting\nfarewell\ngreeting\n").unwrap();
+
+        handle_scaffold(&file, &manifest, None, "Translations", &languages).unwrap();
+        let parse_result =
+            parse_i18n_file_with_record_name(&file, "Translations", &languages).unwrap();
+        assert_eq!(parse_result.translations.len(), 2);
+        assert_eq!(
+            parse_result.translations["greeting"].values["en"],
+            "\"Hello\""
+        );
+        assert_eq!(
+            parse_result.translations["greeting"].values["fr"],
+            "\"Bonjour\""
+        );
+        assert_eq!(parse_result.translations["farewell"].values["en"], "\"\"");
+        assert_eq!(parse_result.translations["farewell"].values["fr"], "\"\"");
+
+        // Run again, every key is there and the file is left alone
+        let scaffolded = std::fs::read_to_string(&file).unwrap();
+        handle_scaffold(&file, &manifest, None, "Translations", &languages).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), scaffolded);
+    }
+
+    #[test]
+    fn keys_lists_functions_only_when_asked() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { welcome : String\n    , itemCount : Int -> String\n    , about : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { welcome = \"Welcome\"\n    , itemCount = \\n -> String.fromInt n ++ \" items\"\n    , about = \"About\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { welcome = \"Bienvenue\"\n    , itemCount = \\n -> String.fromInt n ++ \" articles\"\n    , about = \"À propos\"\n    }\n",
+        )
+        .unwrap();
+        let files = vec![(file, "Translations".to_string())];
+
+        let keys = sorted_keys(&files, false, &languages).unwrap();
+        assert_eq!(
+            keys.into_iter().collect::<Vec<_>>(),
+            ["about", "itemCount", "welcome"]
+        );
+        let keys = sorted_keys(&files, true, &languages).unwrap();
+        assert_eq!(keys.into_iter().collect::<Vec<_>>(), ["itemCount"]);
+    }
+
+    #[test]
+    fn remove_check_usages_keeps_keys_still_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("I18n.elm");
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        std::fs::write(
+            &file,
+            "type alias Translations =\n    { welcome : String\n    , about : String\n    , footer : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { welcome = \"Welcome\"\n    , about = \"About\"\n    , footer = \"Footer\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { welcome = \"Bienvenue\"\n    , about = \"À propos\"\n    , footer = \"Pied de page\"\n    }\n",
+        )
+        .unwrap();
+        let sources = SourceSet {
+            files: vec![scan::SourceFile {
+                path: PathBuf::from("src/Main.elm"),
+                content: "view t =\n    text t.welcome\n".to_string(),
+            }],
+        };
+        let keys = vec!["welcome".to_string(), "about".to_string()];
+        let remaining = || {
+            parse_i18n_file_with_record_name(&file, "Translations", &languages)
+                .unwrap()
+                .translations
+                .into_keys()
+                .collect::<std::collections::BTreeSet<_>>()
+        };
+
+        // `about` isn't read and goes; `welcome` is and stays
+        let status = handle_remove(
+            &file,
+            &keys,
+            Some(&sources),
+            false,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(status, 1);
+        assert_eq!(
+            remaining(),
+            ["footer".to_string(), "welcome".to_string()].into()
+        );
+
+        let status = handle_remove(
+            &file,
+            &keys[..1],
+            Some(&sources),
+            true,
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(status, 0);
+        assert_eq!(remaining(), ["footer".to_string()].into());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn verify_keeps_the_edit_when_it_cant_check_it() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("I18n.elm");
+        fs::write(&file, "module I18n exposing (..)\n").unwrap();
+        let watcher = EditWatcher::new(std::slice::from_ref(&file));
+        let config_watcher = EditWatcher::new(&[]);
+        fs::write(&file, "module I18n exposing (..)\nBROKEN\n").unwrap();
+        let edited = || fs::read_to_string(&file).unwrap().contains("BROKEN");
+
+        // No elm binary
+        let missing = dir.path().join("no-elm");
+        verify_edited_files(&watcher, &config_watcher, Some(&missing)).unwrap();
+        assert!(edited());
+
+        // An elm that would fail anything, but no elm.json to run it in
+        let elm = dir.path().join("elm");
+        fs::write(&elm, "#!/bin/sh\nexit 1\n").unwrap();
+        fs::set_permissions(&elm, fs::Permissions::from_mode(0o755)).unwrap();
+        verify_edited_files(&watcher, &config_watcher, Some(&elm)).unwrap();
+        assert!(edited());
+    }
 }
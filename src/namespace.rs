@@ -0,0 +1,76 @@
+/// The namespace word a key starts with: its leading lowercase letters, when more
+/// of the key follows (`checkout` for `checkoutTitle` or `checkout_title`, none for
+/// `title`)
+pub fn key_prefix(key: &str) -> Option<&str> {
+    let end = key
+        .char_indices()
+        .find(|(_, c)| !c.is_lowercase())
+        .map(|(i, _)| i)?;
+    let next = key[end..].chars().next()?;
+    if end > 0 && (next.is_uppercase() || next.is_ascii_digit() || next == '_') {
+        Some(&key[..end])
+    } else {
+        None
+    }
+}
+
+/// How many keys share each prefix, largest group first
+///
+/// Keys without a prefix are counted under `None`, after the prefixes.
+pub fn keys_by_prefix<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+) -> Vec<(Option<&'a str>, usize)> {
+    let mut groups: Vec<(Option<&str>, usize)> = Vec::new();
+    for key in keys {
+        let prefix = key_prefix(key);
+        match groups.iter_mut().find(|(p, _)| *p == prefix) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((prefix, 1)),
+        }
+    }
+    groups.sort_by(|a, b| {
+        a.0.is_none()
+            .cmp(&b.0.is_none())
+            .then_with(|| b.1.cmp(&a.1))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_namespace_word() {
+        assert_eq!(key_prefix("checkoutTitle"), Some("checkout"));
+        assert_eq!(key_prefix("checkout_title"), Some("checkout"));
+        assert_eq!(key_prefix("home2Banner"), Some("home"));
+        assert_eq!(key_prefix("title"), None);
+        assert_eq!(key_prefix("Title"), None);
+        assert_eq!(key_prefix(""), None);
+    }
+
+    #[test]
+    fn counts_keys_per_prefix_largest_first() {
+        let keys = [
+            "homeBanner",
+            "save",
+            "checkoutTitle",
+            "homeTitle",
+            "cancel",
+            "checkoutSubmit",
+            "checkoutTotal",
+            "aboutTitle",
+        ];
+        assert_eq!(
+            keys_by_prefix(keys),
+            vec![
+                (Some("checkout"), 3),
+                (Some("home"), 2),
+                (Some("about"), 1),
+                (None, 2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+
+use crate::config::{NamingConfig, NamingPreset};
+use crate::namespace::key_prefix;
+
+pub const KEY_PATTERN: &str = "key-pattern";
+pub const KEY_LENGTH: &str = "key-length";
+pub const KEY_PREFIX: &str = "key-prefix";
+pub const KEY_BANNED: &str = "key-banned";
+
+const CAMEL_CASE_STRICT: &str = r"^[a-z][a-z0-9]*(?:[A-Z][a-z0-9]+)*[A-Z]?$";
+
+/// A key naming rule a key breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingViolation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// The naming rules of a config, with their patterns compiled
+#[derive(Debug, Clone)]
+pub struct NamingRules {
+    preset: Option<NamingPreset>,
+    pattern: Option<Regex>,
+    max_length: Option<usize>,
+    prefixes: Vec<String>,
+    banned: Vec<String>,
+}
+
+impl NamingRules {
+    pub fn new(config: &NamingConfig) -> Result<Self> {
+        let pattern = match &config.pattern {
+            Some(pattern) => Some(
+                Regex::new(pattern)
+                    .with_context(|| format!("pattern `{}` isn't a valid regex", pattern))?,
+            ),
+            None => None,
+        };
+        if config.max_length == Some(0) {
+            bail!("maxLength must be at least 1");
+        }
+        if config.prefixes.iter().any(|p| p.is_empty()) {
+            bail!("prefixes can't be empty");
+        }
+        if config.banned.iter().any(|b| b.is_empty()) {
+            bail!("banned entries can't be empty");
+        }
+
+        Ok(NamingRules {
+            preset: config.preset,
+            pattern,
+            max_length: config.max_length,
+            prefixes: config.prefixes.clone(),
+            banned: config.banned.clone(),
+        })
+    }
+
+    /// Every rule `key` breaks, in the order the rules are listed above
+    pub fn violations(&self, key: &str) -> Vec<NamingViolation> {
+        let mut violations = Vec::new();
+        let mut violation = |rule, message| violations.push(NamingViolation { rule, message });
+
+        if self.preset == Some(NamingPreset::CamelCaseStrict)
+            && !Regex::new(CAMEL_CASE_STRICT).unwrap().is_match(key)
+        {
+            violation(
+                KEY_PATTERN,
+                "isn't strict camelCase (lowercase first letter, no underscores or runs of capitals)"
+                    .to_string(),
+            );
+        }
+        if let Some(pattern) = self.pattern.as_ref().filter(|p| !p.is_match(key)) {
+            violation(
+                KEY_PATTERN,
+                format!("doesn't match the pattern `{}`", pattern.as_str()),
+            );
+        }
+
+        let length = key.chars().count();
+        if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+            violation(
+                KEY_LENGTH,
+                format!("is {} characters long; the limit is {}", length, max_length),
+            );
+        }
+
+        if !self.prefixes.is_empty() {
+            if !self.prefixes.iter().any(|p| has_namespace(key, p)) {
+                violation(
+                    KEY_PREFIX,
+                    format!(
+                        "doesn't start with one of the prefixes {}",
+                        self.prefixes
+                            .iter()
+                            .map(|p| format!("`{}`", p))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+        } else if self.preset == Some(NamingPreset::PrefixRequired) && key_prefix(key).is_none() {
+            violation(
+                KEY_PREFIX,
+                "has no namespace prefix (e.g., `checkoutTitle` rather than `title`)".to_string(),
+            );
+        }
+
+        for banned in self.banned.iter().filter(|b| key.contains(b.as_str())) {
+            violation(KEY_BANNED, format!("contains `{}`", banned));
+        }
+
+        violations
+    }
+}
+
+/// Whether `key` is `prefix` followed by more of the key (`checkoutTitle` for
+/// `checkout`, but not `checkouts` or `checkout` itself)
+fn has_namespace(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(config: NamingConfig) -> NamingRules {
+        NamingRules::new(&config).unwrap()
+    }
+
+    fn broken(rules: &NamingRules, key: &str) -> Vec<&'static str> {
+        rules.violations(key).iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn checks_each_configured_rule() {
+        let rules = rules(NamingConfig {
+            pattern: Some("^[a-z][a-zA-Z0-9]*$".to_string()),
+            max_length: Some(20),
+            prefixes: vec!["checkout".to_string(), "home".to_string()],
+            banned: vec!["Temp".to_string()],
+            ..NamingConfig::default()
+        });
+
+        assert!(broken(&rules, "checkoutSubmit").is_empty());
+        assert!(broken(&rules, "home2Banner").is_empty());
+        assert_eq!(broken(&rules, "checkout_title"), vec![KEY_PATTERN]);
+        assert_eq!(
+            broken(&rules, "checkoutShippingAddressHint"),
+            vec![KEY_LENGTH]
+        );
+        assert_eq!(broken(&rules, "checkouts"), vec![KEY_PREFIX]);
+        assert_eq!(broken(&rules, "title"), vec![KEY_PREFIX]);
+        assert_eq!(broken(&rules, "homeTempBanner"), vec![KEY_BANNED]);
+        assert_eq!(
+            rules.violations("checkoutShippingAddressHint")[0].message,
+            "is 27 characters long; the limit is 20"
+        );
+
+        assert!(NamingRules::new(&NamingConfig {
+            pattern: Some("[a-z".to_string()),
+            ..NamingConfig::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn applies_the_built_in_presets() {
+        let camel_case = rules(NamingConfig {
+            preset: Some(NamingPreset::CamelCaseStrict),
+            ..NamingConfig::default()
+        });
+        for key in ["save", "saveUrl", "itemCount2", "pageA"] {
+            assert!(broken(&camel_case, key).is_empty(), "{}", key);
+        }
+        for key in ["saveURL", "save_url", "SaveUrl"] {
+            assert_eq!(broken(&camel_case, key), vec![KEY_PATTERN], "{}", key);
+        }
+
+        let prefix_required = rules(NamingConfig {
+            preset: Some(NamingPreset::PrefixRequired),
+            ..NamingConfig::default()
+        });
+        assert!(broken(&prefix_required, "checkoutTitle").is_empty());
+        assert_eq!(broken(&prefix_required, "title"), vec![KEY_PREFIX]);
+        assert_eq!(broken(&prefix_required, "Title"), vec![KEY_PREFIX]);
+    }
+}
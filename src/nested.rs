@@ -0,0 +1,1167 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+
+use crate::errors::{Code, Coded};
+use crate::escaping::escape;
+use crate::generator::{indent, indent_width, remove_record_field, remove_type_field};
+use crate::parser::{
+    is_function_type, nested_record, parse_i18n_content_with_record_name, record_type_alias,
+};
+use crate::types::{RecordField, Translation, TypeField};
+
+/// What a dotted key like `checkout.payment.cardNumber` names
+#[derive(Debug)]
+pub enum Entry {
+    Translation(Translation),
+    /// A group of nested keys, with the dotted key of each translation in it
+    Group(Vec<String>),
+}
+
+/// One record type of the nesting: the `Translations` type, or the type alias of
+/// a group in it, with the record of each language at that place
+struct Level {
+    alias: String,
+    /// From the `type alias` line to its closing brace
+    type_bounds: (usize, usize),
+    type_fields: Vec<TypeField>,
+    records: Vec<(String, (usize, usize), Vec<RecordField>)>,
+}
+
+enum Child {
+    Missing,
+    Translation(TypeField),
+    Group(Level),
+}
+
+fn top(content: &str, record_name: &str, languages: &[String]) -> Result<Level> {
+    let parse_result = parse_i18n_content_with_record_name(content, record_name, languages)?;
+    let records = parse_result
+        .lang_bounds
+        .iter()
+        .map(|(lang, start, end)| {
+            let fields = parse_result.record_fields[lang].clone();
+            (lang.clone(), (*start, *end), fields)
+        })
+        .collect();
+    Ok(Level {
+        alias: record_name.to_string(),
+        type_bounds: (parse_result.type_start_line, parse_result.type_end_line),
+        type_fields: parse_result.type_fields,
+        records,
+    })
+}
+
+/// The type alias a field's annotation names, when it's a plain type name
+fn alias_of(annotation: &str) -> Option<&str> {
+    let name = annotation.trim();
+    (name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    .then_some(name)
+}
+
+/// The field `name` of `level`; `path` is its dotted key, for messages
+fn child(lines: &[&str], level: &Level, name: &str, path: &str) -> Result<Child> {
+    let Some(field) = level.type_fields.iter().find(|f| f.name == name) else {
+        return Ok(Child::Missing);
+    };
+    let Some((type_bounds, type_fields)) =
+        alias_of(&field.type_annotation).and_then(|alias| record_type_alias(lines, alias))
+    else {
+        return Ok(Child::Translation(field.clone()));
+    };
+    let mut records = Vec::new();
+    for (lang, _, fields) in &level.records {
+        let Some(value) = fields.iter().find(|f| f.name == name) else {
+            bail!(Coded::new(
+                Code::ParseError,
+                format!(
+                    "the {} record has no `{}` for the '{}' group; run 'elm-i18n lint --fix' first",
+                    lang.to_uppercase(),
+                    name,
+                    path
+                ),
+            ));
+        };
+        match nested_record(lines, value.line) {
+            Ok((bounds, fields)) => records.push((lang.clone(), bounds, fields)),
+            Err(problem) => bail!(Coded::new(
+                Code::ParseError,
+                format!(
+                    "the {} value of '{}' (line {}) {}, so elm-i18n can't edit it",
+                    lang.to_uppercase(),
+                    path,
+                    value.line + 1,
+                    problem
+                ),
+            )),
+        }
+    }
+    Ok(Child::Group(Level {
+        alias: field.type_annotation.trim().to_string(),
+        type_bounds,
+        type_fields,
+        records,
+    }))
+}
+
+/// What `key` names, following its dots through the nested records; a key
+/// without dots can name a group too
+pub fn lookup(
+    content: &str,
+    key: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<Option<Entry>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let segments: Vec<&str> = key.split('.').collect();
+    let mut level = top(content, record_name, languages)?;
+    for (i, segment) in segments.iter().enumerate() {
+        match child(&lines, &level, segment, &segments[..=i].join("."))? {
+            Child::Missing => return Ok(None),
+            Child::Translation(_) if i + 1 < segments.len() => return Ok(None),
+            Child::Translation(field) => {
+                return Ok(Some(Entry::Translation(translation_at(
+                    &level, &field, key,
+                ))));
+            }
+            Child::Group(group) => level = group,
+        }
+    }
+    Ok(Some(Entry::Group(translation_keys(&lines, &level, key)?)))
+}
+
+/// The translation `field` of `level` is, under the dotted `key`
+fn translation_at(level: &Level, field: &TypeField, key: &str) -> Translation {
+    let values = level
+        .records
+        .iter()
+        .filter_map(|(lang, _, fields)| {
+            let value = fields.iter().find(|f| f.name == field.name)?;
+            Some((lang.clone(), value.value.clone()))
+        })
+        .collect();
+    Translation {
+        key: key.to_string(),
+        values,
+        is_function: is_function_type(&field.type_annotation),
+        type_signature: (field.type_annotation != "String").then(|| field.type_annotation.clone()),
+    }
+}
+
+/// Every translation of the module under its dotted key, in the order of the
+/// types, those of the groups included
+pub fn translations(
+    content: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<Vec<Translation>> {
+    fn collect(
+        lines: &[&str],
+        level: &Level,
+        prefix: &str,
+        found: &mut Vec<Translation>,
+    ) -> Result<()> {
+        for field in &level.type_fields {
+            let path = join_path(prefix, &field.name);
+            match child(lines, level, &field.name, &path)? {
+                Child::Group(group) => collect(lines, &group, &path, found)?,
+                _ => found.push(translation_at(level, field, &path)),
+            }
+        }
+        Ok(())
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let mut found = Vec::new();
+    collect(
+        &lines,
+        &top(content, record_name, languages)?,
+        "",
+        &mut found,
+    )?;
+    Ok(found)
+}
+
+/// `name` under the dotted `prefix`, or `name` alone at the top
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Whether `key` names a group of nested keys
+pub fn is_group(content: &str, key: &str, record_name: &str, languages: &[String]) -> bool {
+    matches!(
+        lookup(content, key, record_name, languages),
+        Ok(Some(Entry::Group(_)))
+    )
+}
+
+/// The dotted keys of the translations in the group `level`, at `prefix`
+fn translation_keys(lines: &[&str], level: &Level, prefix: &str) -> Result<Vec<String>> {
+    let mut keys = Vec::new();
+    for field in &level.type_fields {
+        let path = format!("{}.{}", prefix, field.name);
+        match child(lines, level, &field.name, &path)? {
+            Child::Group(group) => keys.extend(translation_keys(lines, &group, &path)?),
+            _ => keys.push(path),
+        }
+    }
+    Ok(keys)
+}
+
+/// The type alias elm-i18n names the group at `path`: `CheckoutPaymentTranslations`
+/// for `checkout.payment` in `Translations`
+pub fn group_type_name(path: &[&str], record_name: &str) -> String {
+    let mut name: String = path
+        .iter()
+        .map(|segment| {
+            let mut chars = segment.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect();
+    name.push_str(record_name);
+    name
+}
+
+/// The column of the opening brace of the record between `bounds`
+fn brace_column(lines: &[String], bounds: (usize, usize)) -> usize {
+    (bounds.0..=bounds.1)
+        .map(|i| &lines[i])
+        .find(|line| line.trim_start().starts_with('{') && !line.trim_start().starts_with("{-"))
+        .map_or(indent_width(), |line| line.len() - line.trim_start().len())
+}
+
+/// The lines of the field for `segments` in a record whose fields are at
+/// `column`: the translation itself, or the nested records down to it
+fn record_field_lines(column: usize, segments: &[&str], value: &str, raw: bool) -> Vec<String> {
+    let value = if raw {
+        value.to_string()
+    } else {
+        format!("\"{}\"", escape(value))
+    };
+    field_lines(column, segments, &|pad, name| {
+        value
+            .lines()
+            .enumerate()
+            .map(|(i, line)| match i {
+                0 => format!("{}, {} = {}", pad, name, line),
+                _ if line.is_empty() => String::new(),
+                _ => format!("{}{}{}", pad, indent(), line),
+            })
+            .collect()
+    })
+}
+
+/// The lines of the field for `segments` at `column`, the nested records down to
+/// the last segment around the lines `leaf` writes for it, given its padding and name
+fn field_lines(
+    column: usize,
+    segments: &[&str],
+    leaf: &dyn Fn(&str, &str) -> Vec<String>,
+) -> Vec<String> {
+    let pad = " ".repeat(column);
+    let Some((name, rest)) = segments.split_first() else {
+        return Vec::new();
+    };
+    if rest.is_empty() {
+        return leaf(&pad, name);
+    }
+    let mut inner = field_lines(column + indent_width(), rest, leaf);
+    inner[0] = inner[0].replacen(", ", "{ ", 1);
+    let mut lines = vec![format!("{}, {} =", pad, name)];
+    lines.extend(inner);
+    lines.push(format!("{}{}}}", pad, indent()));
+    lines
+}
+
+fn declares_type(lines: &[&str], name: &str) -> bool {
+    let declaration =
+        Regex::new(&format!(r"^type\s+(alias\s+)?{}\b", regex::escape(name))).unwrap();
+    lines.iter().any(|line| declaration.is_match(line))
+}
+
+/// `content` with `translation` added under its dotted key, making the type alias
+/// and the nested record in each language for each group that doesn't exist yet
+///
+/// A key that would put a translation where a group is, or a group under a
+/// translation, is refused.
+pub fn add(
+    content: &str,
+    translation: &Translation,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let key = translation.key.as_str();
+    let segments: Vec<&str> = key.split('.').collect();
+    let leaf = segments.len() - 1;
+
+    let (level, depth) = deepest_group(content, &lines, &segments, record_name, languages)?;
+    if depth == leaf {
+        match child(&lines, &level, segments[leaf], key)? {
+            Child::Missing => {}
+            Child::Translation(_) => bail!(Coded::key_exists(key)),
+            Child::Group(group) => bail!(Coded::new(
+                Code::AlreadyExists,
+                format!(
+                    "'{}' is a group of nested keys ({}), so it can't also be a translation",
+                    key,
+                    translation_keys(&lines, &group, key)?.join(", ")
+                ),
+            )
+            .key(key)),
+        }
+    }
+
+    let leaf_type = translation
+        .type_signature
+        .clone()
+        .unwrap_or_else(|| "String".to_string());
+    let raw = translation.type_signature.is_some();
+    insert(
+        content,
+        &level,
+        depth,
+        &segments,
+        &leaf_type,
+        record_name,
+        &|lang, column, segments| {
+            let value = translation.values.get(lang).map_or("", String::as_str);
+            record_field_lines(column, segments, value, raw)
+        },
+    )
+}
+
+/// The deepest group that exists on the way to the last of `segments`, and how
+/// many of the segments lead to it
+fn deepest_group(
+    content: &str,
+    lines: &[&str],
+    segments: &[&str],
+    record_name: &str,
+    languages: &[String],
+) -> Result<(Level, usize)> {
+    let key = segments.join(".");
+    let leaf = segments.len() - 1;
+    let mut level = top(content, record_name, languages)?;
+    let mut depth = 0;
+    while depth < leaf {
+        let path = segments[..=depth].join(".");
+        match child(lines, &level, segments[depth], &path)? {
+            Child::Missing => break,
+            Child::Translation(_) => bail!(Coded::new(
+                Code::AlreadyExists,
+                format!(
+                    "'{}' is a translation, so it can't hold nested keys like '{}'",
+                    path, key
+                ),
+            )
+            .key(&key)),
+            Child::Group(group) => {
+                level = group;
+                depth += 1;
+            }
+        }
+    }
+    Ok((level, depth))
+}
+
+/// `content` with a field for the last of `segments` in the group `level`, the
+/// one `depth` of them lead to, making the groups between; its type is
+/// `leaf_type`, and `record_lines` writes it and those groups into the record of
+/// a language at a column
+fn insert(
+    content: &str,
+    level: &Level,
+    depth: usize,
+    segments: &[&str],
+    leaf_type: &str,
+    record_name: &str,
+    record_lines: &dyn Fn(&str, usize, &[&str]) -> Vec<String>,
+) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let leaf = segments.len() - 1;
+    let new_groups: Vec<String> = (depth..leaf)
+        .map(|i| group_type_name(&segments[..=i], record_name))
+        .collect();
+    if let Some(taken) = new_groups.iter().find(|name| declares_type(&lines, name)) {
+        bail!(Coded::new(
+            Code::AlreadyExists,
+            format!(
+                "the type of the '{}' group would be {}, but the module already declares a type of that name",
+                segments[..=depth + new_groups.iter().position(|n| n == taken).unwrap_or(0)].join("."),
+                taken
+            ),
+        ));
+    }
+    let type_at = |i: usize| {
+        new_groups
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| leaf_type.to_string())
+    };
+
+    let mut output: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    // Each insertion goes before a line; they're made from the bottom up so the
+    // lines above stay where the parser found them
+    let mut insertions: Vec<(usize, Vec<String>)> = Vec::new();
+    let field_column = brace_column(&output, level.type_bounds);
+    insertions.push((
+        level.type_bounds.1,
+        vec![format!(
+            "{}, {} : {}",
+            " ".repeat(field_column),
+            segments[depth],
+            type_at(0)
+        )],
+    ));
+    let mut declarations = Vec::new();
+    for (i, name) in new_groups.iter().enumerate() {
+        declarations.extend([
+            String::new(),
+            String::new(),
+            format!("type alias {} =", name),
+            format!(
+                "{}{{ {} : {}",
+                indent(),
+                segments[depth + i + 1],
+                type_at(i + 1)
+            ),
+            format!("{}}}", indent()),
+        ]);
+    }
+    if !declarations.is_empty() {
+        insertions.push((level.type_bounds.1 + 1, declarations));
+    }
+    for (lang, bounds, _) in &level.records {
+        insertions.push((
+            bounds.1,
+            record_lines(lang, brace_column(&output, *bounds), &segments[depth..]),
+        ));
+    }
+    insertions.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    for (at, new_lines) in insertions {
+        output.splice(at..at, new_lines);
+    }
+    Ok(join(output, content.ends_with('\n')))
+}
+
+/// `content` without the translation or group `key`; a group left with nothing
+/// in it goes too, and so does the type alias of each group removed, unless
+/// something else uses it
+pub fn remove(content: &str, key: &str, record_name: &str, languages: &[String]) -> Result<String> {
+    remove_keeping(content, key, record_name, languages, false)
+}
+
+/// `remove`, leaving the type aliases of a group removed when `keep_aliases`
+/// because a copy of it still uses them
+fn remove_keeping(
+    content: &str,
+    key: &str,
+    record_name: &str,
+    languages: &[String],
+    keep_aliases: bool,
+) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let segments: Vec<&str> = key.split('.').collect();
+    let mut levels = vec![top(content, record_name, languages)?];
+    let mut removed_group = None;
+    for (i, segment) in segments.iter().enumerate() {
+        let path = segments[..=i].join(".");
+        match child(&lines, &levels[i], segment, &path)? {
+            Child::Missing => bail!(Coded::key_not_found(key)),
+            Child::Translation(_) if i + 1 < segments.len() => bail!(Coded::new(
+                Code::KeyNotFound,
+                format!(
+                    "Translation '{}' not found; '{}' is a translation, not a group",
+                    key, path
+                ),
+            )
+            .key(key)),
+            Child::Translation(_) => {}
+            Child::Group(group) if i + 1 < segments.len() => levels.push(group),
+            Child::Group(group) => removed_group = Some(group),
+        }
+    }
+
+    // Up to the outermost group the removal leaves empty
+    let mut at = segments.len() - 1;
+    while at > 0 && levels[at].type_fields.len() == 1 {
+        at -= 1;
+    }
+    let mut aliases: Vec<(usize, usize)> = Vec::new();
+    for level in &levels[at + 1..] {
+        aliases.push(level.type_bounds);
+    }
+    if let Some(group) = removed_group.as_ref().filter(|_| !keep_aliases) {
+        collect_aliases(&lines, group, key, &mut aliases)?;
+    }
+    let level = &levels[at];
+    let uses = |name: &str| {
+        Regex::new(&format!(r"\b{}\b", regex::escape(name)))
+            .unwrap()
+            .find_iter(content)
+            .count()
+    };
+    // Its declaration and the field that's removed; any more and it's shared
+    aliases.retain(|(start, _)| {
+        let name = lines[*start]
+            .split_whitespace()
+            .nth(2)
+            .unwrap_or_default()
+            .to_string();
+        name != level.alias && uses(&name) <= 2
+    });
+
+    enum Removal {
+        TypeField(usize),
+        RecordField(usize),
+        Declaration(usize),
+    }
+    let mut removals: Vec<(usize, Removal)> = Vec::new();
+    if let Some(field) = level.type_fields.iter().find(|f| f.name == segments[at]) {
+        removals.push((field.line, Removal::TypeField(field.end_line)));
+    }
+    for (_, _, fields) in &level.records {
+        if let Some(field) = fields.iter().find(|f| f.name == segments[at]) {
+            removals.push((field.line, Removal::RecordField(field.end_line)));
+        }
+    }
+    for (start, end) in aliases {
+        removals.push((start, Removal::Declaration(end)));
+    }
+    removals.sort_by_key(|(line, _)| std::cmp::Reverse(*line));
+
+    let mut output: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    for (line, removal) in removals {
+        match removal {
+            Removal::TypeField(end) => remove_type_field(&mut output, line, end),
+            Removal::RecordField(end) => remove_record_field(&mut output, line, end),
+            Removal::Declaration(end) => {
+                let mut start = line;
+                while start > 0 && output[start - 1].trim().is_empty() {
+                    start -= 1;
+                }
+                output.drain(start..=end);
+            }
+        }
+    }
+    Ok(join(output, content.ends_with('\n')))
+}
+
+/// The groups from the top down to the group `path`, the top level first
+fn group_chain(
+    content: &str,
+    lines: &[&str],
+    path: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<Vec<Level>> {
+    let mut levels = vec![top(content, record_name, languages)?];
+    let segments: Vec<&str> = path.split('.').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        let at = segments[..=i].join(".");
+        match child(lines, &levels[i], segment, &at)? {
+            Child::Missing => {
+                bail!(Coded::new(Code::KeyNotFound, format!("There's no group '{}'", at)).key(&at))
+            }
+            Child::Translation(_) => bail!(Coded::new(
+                Code::InvalidArgument,
+                format!("'{}' is a translation, not a group", at)
+            )
+            .key(&at)),
+            Child::Group(group) => levels.push(group),
+        }
+    }
+    Ok(levels)
+}
+
+/// The dotted path and type alias of `group`, at `path`, and of each group in it
+fn group_aliases(
+    lines: &[&str],
+    group: &Level,
+    path: &str,
+    aliases: &mut Vec<(String, String)>,
+) -> Result<()> {
+    aliases.push((path.to_string(), group.alias.clone()));
+    for field in &group.type_fields {
+        let inner_path = format!("{}.{}", path, field.name);
+        if let Child::Group(inner) = child(lines, group, &field.name, &inner_path)? {
+            group_aliases(lines, &inner, &inner_path, aliases)?;
+        }
+    }
+    Ok(())
+}
+
+/// `content` with the type aliases of groups that moved from `from` to `to` renamed
+/// after their new place, when they have the name elm-i18n would have given them
+/// and the new name is free
+fn rename_aliases(
+    content: &str,
+    aliases: &[(String, String)],
+    from: &str,
+    to: &str,
+    record_name: &str,
+) -> String {
+    let mut content = content.to_string();
+    for (path, alias) in aliases {
+        let old_segments: Vec<&str> = path.split('.').collect();
+        if *alias != group_type_name(&old_segments, record_name) {
+            continue;
+        }
+        let new_path = format!("{}{}", to, &path[from.len()..]);
+        let new_segments: Vec<&str> = new_path.split('.').collect();
+        let new_alias = group_type_name(&new_segments, record_name);
+        let lines: Vec<&str> = content.lines().collect();
+        if declares_type(&lines, &new_alias) {
+            continue;
+        }
+        let name = Regex::new(&format!(r"\b{}\b", regex::escape(alias))).unwrap();
+        content = name.replace_all(&content, new_alias.as_str()).into_owned();
+    }
+    content
+}
+
+/// `content` with the group `path` named `new_name` in its type and in the
+/// record of each language, and the type aliases of it and its groups renamed to
+/// match where elm-i18n named them
+pub fn rename_group(
+    content: &str,
+    path: &str,
+    new_name: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let levels = group_chain(content, &lines, path, record_name, languages)?;
+    let (old_name, parent_path) = match path.rsplit_once('.') {
+        Some((parent, name)) => (name, parent),
+        None => (path, ""),
+    };
+    let new_path = join_path(parent_path, new_name);
+    let parent = &levels[levels.len() - 2];
+    if parent.type_fields.iter().any(|f| f.name == new_name) {
+        bail!(Coded::new(
+            Code::AlreadyExists,
+            format!("'{}' already exists", new_path)
+        ));
+    }
+    let mut aliases = Vec::new();
+    group_aliases(&lines, &levels[levels.len() - 1], path, &mut aliases)?;
+
+    let field = Regex::new(&format!(
+        r"^(\s*(?:[{{,]\s*)?){}(\s*[:=])",
+        regex::escape(old_name)
+    ))?;
+    let field_lines = parent
+        .type_fields
+        .iter()
+        .filter(|f| f.name == old_name)
+        .map(|f| f.line)
+        .chain(parent.records.iter().flat_map(|(_, _, fields)| {
+            fields.iter().filter(|f| f.name == old_name).map(|f| f.line)
+        }));
+    let mut output: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    for line in field_lines {
+        output[line] = field
+            .replace(&output[line], format!("${{1}}{}${{2}}", new_name))
+            .into_owned();
+    }
+    let renamed = join(output, content.ends_with('\n'));
+    Ok(rename_aliases(
+        &renamed,
+        &aliases,
+        path,
+        &new_path,
+        record_name,
+    ))
+}
+
+/// `content` with the group `path` moved into the group `into`, made if it
+/// doesn't exist, or to the top level for None; the fields keep their values and
+/// layout, and a group the move leaves empty goes
+pub fn move_group(
+    content: &str,
+    path: &str,
+    into: Option<&str>,
+    record_name: &str,
+    languages: &[String],
+) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let levels = group_chain(content, &lines, path, record_name, languages)?;
+    let (name, parent_path) = match path.rsplit_once('.') {
+        Some((parent, name)) => (name, parent),
+        None => (path, ""),
+    };
+    let target = into.unwrap_or("");
+    if target == path || target.starts_with(&format!("{}.", path)) {
+        bail!(Coded::new(
+            Code::InvalidArgument,
+            format!("'{}' can't be moved into itself", path)
+        ));
+    }
+    if target == parent_path {
+        match into {
+            Some(into) => bail!(Coded::new(
+                Code::InvalidArgument,
+                format!("'{}' is already in '{}'", path, into)
+            )),
+            None => bail!(Coded::new(
+                Code::InvalidArgument,
+                format!("'{}' is already at the top level", path)
+            )),
+        }
+    }
+    let new_path = join_path(target, name);
+    let segments: Vec<&str> = new_path.split('.').collect();
+    let (level, depth) = deepest_group(content, &lines, &segments, record_name, languages)?;
+    if depth + 1 == segments.len() && level.type_fields.iter().any(|f| f.name == name) {
+        bail!(Coded::new(
+            Code::AlreadyExists,
+            format!("'{}' already exists", new_path)
+        ));
+    }
+
+    let group = &levels[levels.len() - 1];
+    let parent = &levels[levels.len() - 2];
+    // Each language's value of the group, with its lines' common indentation taken off
+    let mut blocks: Vec<(String, Vec<String>)> = Vec::new();
+    for (lang, _, fields) in &parent.records {
+        let Some(field) = fields.iter().find(|f| f.name == name) else {
+            continue;
+        };
+        let Some((_, (_, end), _)) = group.records.iter().find(|(l, _, _)| l == lang) else {
+            continue;
+        };
+        let mut value: Vec<&str> = lines[field.line + 1..=*end].to_vec();
+        let same_line = lines[field.line]
+            .split_once('=')
+            .map_or("", |(_, rest)| rest.trim());
+        let margin = value
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let mut block: Vec<String> = Vec::new();
+        if !same_line.is_empty() {
+            block.push(same_line.to_string());
+        }
+        block.extend(
+            value
+                .drain(..)
+                .map(|line| line.get(margin..).unwrap_or("").to_string()),
+        );
+        blocks.push((lang.clone(), block));
+    }
+
+    let with_copy = insert(
+        content,
+        &level,
+        depth,
+        &segments,
+        &group.alias,
+        record_name,
+        &|lang, column, segments| {
+            let block = blocks
+                .iter()
+                .find(|(l, _)| l == lang)
+                .map_or(&[][..], |(_, block)| block.as_slice());
+            field_lines(column, segments, &|pad, name| {
+                let mut lines = vec![format!("{}, {} =", pad, name)];
+                lines.extend(block.iter().map(|line| {
+                    if line.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{}{}{}", pad, indent(), line)
+                    }
+                }));
+                lines
+            })
+        },
+    )?;
+    let mut aliases = Vec::new();
+    group_aliases(&lines, group, path, &mut aliases)?;
+    let moved = remove_keeping(&with_copy, path, record_name, languages, true)?;
+    Ok(rename_aliases(
+        &moved,
+        &aliases,
+        path,
+        &new_path,
+        record_name,
+    ))
+}
+
+fn collect_aliases(
+    lines: &[&str],
+    group: &Level,
+    path: &str,
+    aliases: &mut Vec<(usize, usize)>,
+) -> Result<()> {
+    aliases.push(group.type_bounds);
+    for field in &group.type_fields {
+        let path = format!("{}.{}", path, field.name);
+        if let Child::Group(inner) = child(lines, group, &field.name, &path)? {
+            collect_aliases(lines, &inner, &path, aliases)?;
+        }
+    }
+    Ok(())
+}
+
+fn join(lines: Vec<String>, trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::add_translation_to_content;
+    use std::collections::HashMap;
+
+    const FLAT: &str = r#"module I18n exposing (..)
+
+
+type alias Translations =
+    { welcome : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { welcome = "Bienvenue"
+    }
+"#;
+
+    const NESTED: &str = r#"module I18n exposing (..)
+
+
+type alias Translations =
+    { welcome : String
+    , checkout : CheckoutTranslations
+    }
+
+
+type alias CheckoutTranslations =
+    { title : String
+    , payment : CheckoutPaymentTranslations
+    }
+
+
+type alias CheckoutPaymentTranslations =
+    { cardNumber : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome"
+    , checkout =
+        { title = "Checkout"
+        , payment =
+            { cardNumber = "Card number"
+            }
+        }
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { welcome = "Bienvenue"
+    , checkout =
+        { title = "Paiement"
+        , payment =
+            { cardNumber = "Numéro de carte"
+            }
+        }
+    }
+"#;
+
+    fn languages() -> Vec<String> {
+        vec!["en".to_string(), "fr".to_string()]
+    }
+
+    fn translation(key: &str, en: &str, fr: &str) -> Translation {
+        Translation {
+            key: key.to_string(),
+            values: HashMap::from([
+                ("en".to_string(), en.to_string()),
+                ("fr".to_string(), fr.to_string()),
+            ]),
+            is_function: false,
+            type_signature: None,
+        }
+    }
+
+    fn add_all(source: &str, translations: &[Translation]) -> String {
+        translations.iter().fold(source.to_string(), |content, t| {
+            add_translation_to_content(&content, t, "Translations", &languages()).unwrap()
+        })
+    }
+
+    #[test]
+    fn builds_two_levels_of_groups_and_reads_them_back() {
+        let nested = add_all(
+            FLAT,
+            &[
+                translation(
+                    "checkout.payment.cardNumber",
+                    "Card number",
+                    "Numéro de carte",
+                ),
+                translation("checkout.title", "Checkout", "Paiement"),
+            ],
+        );
+        // The type and the records list the groups' fields in the order they came
+        let expected = NESTED
+            .replace(
+                "    { title : String\n    , payment : CheckoutPaymentTranslations\n",
+                "    { payment : CheckoutPaymentTranslations\n    , title : String\n",
+            )
+            .replace(
+                "        { title = \"Checkout\"\n        , payment =\n            { cardNumber = \"Card number\"\n            }\n",
+                "        { payment =\n            { cardNumber = \"Card number\"\n            }\n        , title = \"Checkout\"\n",
+            )
+            .replace(
+                "        { title = \"Paiement\"\n        , payment =\n            { cardNumber = \"Numéro de carte\"\n            }\n",
+                "        { payment =\n            { cardNumber = \"Numéro de carte\"\n            }\n        , title = \"Paiement\"\n",
+            );
+        assert_eq!(nested, expected);
+
+        // The parser keeps to each record's own fields
+        let parse_result =
+            parse_i18n_content_with_record_name(NESTED, "Translations", &languages()).unwrap();
+        let names: Vec<&str> = parse_result.record_fields["fr"]
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, ["welcome", "checkout"]);
+
+        let Some(Entry::Translation(card)) = lookup(
+            NESTED,
+            "checkout.payment.cardNumber",
+            "Translations",
+            &languages(),
+        )
+        .unwrap() else {
+            panic!("checkout.payment.cardNumber isn't a translation");
+        };
+        assert_eq!(card.values["fr"], "\"Numéro de carte\"");
+        let Some(Entry::Group(keys)) =
+            lookup(NESTED, "checkout", "Translations", &languages()).unwrap()
+        else {
+            panic!("checkout isn't a group");
+        };
+        assert_eq!(keys, ["checkout.title", "checkout.payment.cardNumber"]);
+        assert!(
+            lookup(NESTED, "checkout.total", "Translations", &languages())
+                .unwrap()
+                .is_none()
+        );
+
+        // A flat key goes after the group, not into it
+        let flat = add_all(NESTED, &[translation("goodbye", "Bye", "Salut")]);
+        assert!(flat.contains("            }\n        }\n    , goodbye = \"Salut\"\n    }\n"));
+        assert!(flat.contains("    , checkout : CheckoutTranslations\n    , goodbye : String\n"));
+    }
+
+    #[test]
+    fn refuses_a_translation_and_a_group_in_one_place() {
+        let add = |key: &str| {
+            add(
+                NESTED,
+                &translation(key, "x", "y"),
+                "Translations",
+                &languages(),
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!(
+            add("checkout.title.short"),
+            "'checkout.title' is a translation, so it can't hold nested keys like 'checkout.title.short'"
+        );
+        assert_eq!(
+            add("welcome.short"),
+            "'welcome' is a translation, so it can't hold nested keys like 'welcome.short'"
+        );
+        assert_eq!(
+            add("checkout.payment"),
+            "'checkout.payment' is a group of nested keys (checkout.payment.cardNumber), so it can't also be a translation"
+        );
+        assert_eq!(
+            add("checkout.title"),
+            "Translation 'checkout.title' already exists"
+        );
+        let taken = format!("{}\n\ntype alias ShopTranslations =\n    {{}}\n", FLAT);
+        assert_eq!(
+            super::add(
+                &taken,
+                &translation("shop.title", "Shop", "Boutique"),
+                "Translations",
+                &languages()
+            )
+            .unwrap_err()
+            .to_string(),
+            "the type of the 'shop' group would be ShopTranslations, but the module already declares a type of that name"
+        );
+    }
+
+    #[test]
+    fn removes_emptied_groups_with_their_types() {
+        let remove = |source: &str, key: &str| remove(source, key, "Translations", &languages());
+
+        let without_card = remove(NESTED, "checkout.payment.cardNumber").unwrap();
+        assert!(!without_card.contains("Payment"));
+        assert!(without_card.contains("    { title : String\n    }\n"));
+        assert!(without_card.contains("        { title = \"Paiement\"\n        }\n    }\n"));
+        assert_eq!(remove(&without_card, "checkout.title").unwrap(), FLAT);
+        assert_eq!(remove(NESTED, "checkout").unwrap(), FLAT);
+        assert_eq!(
+            remove(NESTED, "checkout.total").unwrap_err().to_string(),
+            "Translation 'checkout.total' not found"
+        );
+    }
+
+    #[test]
+    fn renames_and_moves_groups_with_their_types() {
+        let rename =
+            |path: &str, name: &str| rename_group(NESTED, path, name, "Translations", &languages());
+        assert_eq!(
+            rename("checkout.payment", "billing").unwrap(),
+            NESTED
+                .replace("payment", "billing")
+                .replace("CheckoutPayment", "CheckoutBilling")
+        );
+        assert_eq!(
+            rename("checkout", "welcome").unwrap_err().to_string(),
+            "'welcome' already exists"
+        );
+        assert_eq!(
+            rename("welcome", "hello").unwrap_err().to_string(),
+            "'welcome' is a translation, not a group"
+        );
+
+        let moved = move_group(
+            NESTED,
+            "checkout.payment",
+            None,
+            "Translations",
+            &languages(),
+        )
+        .unwrap();
+        assert!(moved.contains(
+            "    , checkout : CheckoutTranslations\n    , payment : PaymentTranslations\n    }\n"
+        ));
+        assert!(
+            moved.contains("type alias PaymentTranslations =\n    { cardNumber : String\n    }\n")
+        );
+        assert!(moved.contains(
+            "        { title = \"Paiement\"\n        }\n    , payment =\n        { cardNumber = \"Numéro de carte\"\n        }\n    }\n"
+        ));
+        let keys: Vec<String> = translations(&moved, "Translations", &languages())
+            .unwrap()
+            .into_iter()
+            .map(|t| t.key)
+            .collect();
+        assert_eq!(keys, ["welcome", "checkout.title", "payment.cardNumber"]);
+
+        // Moving into a group that isn't there makes it
+        let archived = move_group(
+            NESTED,
+            "checkout.payment",
+            Some("archive.old"),
+            "Translations",
+            &languages(),
+        )
+        .unwrap();
+        assert!(archived.contains(
+            "    , archive =\n        { old =\n            { payment =\n                { cardNumber = \"Card number\"\n                }\n            }\n        }\n    }\n"
+        ));
+        assert!(archived.contains("type alias ArchiveOldPaymentTranslations =\n"));
+        assert!(!archived.contains("CheckoutPayment"));
+        assert_eq!(
+            move_group(
+                NESTED,
+                "checkout",
+                Some("checkout.payment"),
+                "Translations",
+                &languages()
+            )
+            .unwrap_err()
+            .to_string(),
+            "'checkout' can't be moved into itself"
+        );
+    }
+
+    #[test]
+    fn failures_have_codes_for_errors_as_json() {
+        let code = |result: Result<String>| {
+            result
+                .unwrap_err()
+                .downcast_ref::<Coded>()
+                .map(|coded| coded.code)
+        };
+        let rename =
+            |path: &str, name: &str| rename_group(NESTED, path, name, "Translations", &languages());
+        assert_eq!(
+            code(rename("welcome", "hello")),
+            Some(Code::InvalidArgument)
+        );
+        assert_eq!(code(rename("basket", "cart")), Some(Code::KeyNotFound));
+        assert_eq!(
+            code(move_group(
+                NESTED,
+                "checkout",
+                Some("checkout.payment"),
+                "Translations",
+                &languages()
+            )),
+            Some(Code::InvalidArgument)
+        );
+        assert_eq!(
+            code(move_group(
+                NESTED,
+                "checkout.payment",
+                Some("checkout"),
+                "Translations",
+                &languages()
+            )),
+            Some(Code::InvalidArgument)
+        );
+        // A key where a group is, and a key under a translation, collide alike
+        let collide = |key: &str| {
+            code(add(
+                NESTED,
+                &translation(key, "x", "y"),
+                "Translations",
+                &languages(),
+            ))
+        };
+        assert_eq!(collide("checkout.payment"), Some(Code::AlreadyExists));
+        assert_eq!(collide("welcome.short"), Some(Code::AlreadyExists));
+        let taken = format!("{}\n\ntype alias ShopTranslations =\n    {{}}\n", FLAT);
+        assert_eq!(
+            code(add(
+                &taken,
+                &translation("shop.title", "Shop", "Boutique"),
+                "Translations",
+                &languages()
+            )),
+            Some(Code::AlreadyExists)
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use crate::generated::{begin_marker, end_marker};
+use crate::languages::language_variant;
+
+/// The name of the block `generate number-format` writes, in its marker comments
+pub const BLOCK: &str = "number-format";
+
+const NARROW_NO_BREAK_SPACE: &str = "\\u{202F}";
+const NO_BREAK_SPACE: &str = "\\u{00A0}";
+
+/// The thousands separator and decimal mark of a language, as Elm string
+/// contents, and whether the table has it (English's are used when it doesn't)
+///
+/// A region wins over its language ("de-CH" groups with `’`, "pt-PT" with a
+/// space), and spaces are no-break spaces so a number never wraps.
+pub fn separators(code: &str) -> (&'static str, &'static str, bool) {
+    let lower = code.to_ascii_lowercase();
+    let region = match lower.as_str() {
+        "de-ch" | "it-ch" | "de-li" => Some(("’", ".")),
+        "pt-pt" | "pt-ao" | "pt-mz" => Some((NO_BREAK_SPACE, ",")),
+        "es-mx" | "es-us" | "es-419" => Some((",", ".")),
+        "en-za" => Some((NO_BREAK_SPACE, ",")),
+        "en-in" | "hi-in" => Some((",", ".")),
+        _ => None,
+    };
+    if let Some((thousands, decimal)) = region {
+        return (thousands, decimal, true);
+    }
+    let base = lower.split('-').next().unwrap_or_default();
+    let (thousands, decimal) = match base {
+        "en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ga" | "cy" | "mt" | "sw" | "ms"
+        | "fil" | "tl" | "ar" | "fa" | "ur" | "bn" | "ta" => (",", "."),
+        "de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl"
+        | "sr" | "bs" | "mk" | "vi" | "ca" | "is" | "az" | "ka" => (".", ","),
+        "fr" => (NARROW_NO_BREAK_SPACE, ","),
+        "ru" | "uk" | "be" | "pl" | "cs" | "sk" | "bg" | "fi" | "sv" | "nb" | "no" | "nn"
+        | "hu" | "lt" | "lv" | "et" | "kk" | "hy" | "sq" => (NO_BREAK_SPACE, ","),
+        _ => return (",", ".", false),
+    };
+    (thousands, decimal, true)
+}
+
+/// How a language writes the ordinal of `number`, as the lines of an Elm
+/// expression, and its feminine form when that's written differently; None for
+/// languages the table doesn't have
+fn ordinal_forms(code: &str) -> Option<(Vec<String>, Option<Vec<String>>)> {
+    let lower = code.to_ascii_lowercase();
+    let base = lower.split('-').next().unwrap_or_default();
+    let same = |suffix: &str| Some((suffix_chain(&[], suffix), None));
+    let gendered = |masculine: &str, feminine: &str| {
+        Some((
+            suffix_chain(&[], masculine),
+            Some(suffix_chain(&[], feminine)),
+        ))
+    };
+    match base {
+        "en" => Some((
+            by_last_two_digits(
+                &[
+                    ("modBy 10 lastTwo == 1 && lastTwo /= 11", "st"),
+                    ("modBy 10 lastTwo == 2 && lastTwo /= 12", "nd"),
+                    ("modBy 10 lastTwo == 3 && lastTwo /= 13", "rd"),
+                ],
+                "th",
+            ),
+            None,
+        )),
+        "sv" => Some((
+            by_last_two_digits(
+                &[(
+                    "List.member (modBy 10 lastTwo) [ 1, 2 ] && lastTwo /= 11 && lastTwo /= 12",
+                    ":a",
+                )],
+                ":e",
+            ),
+            None,
+        )),
+        "fr" => Some((
+            suffix_chain(&[("number == 1", "er")], "e"),
+            Some(suffix_chain(&[("number == 1", "re")], "e")),
+        )),
+        "ca" => Some((
+            suffix_chain(
+                &[
+                    ("number == 1 || number == 3", "r"),
+                    ("number == 2", "n"),
+                    ("number == 4", "t"),
+                ],
+                "è",
+            ),
+            Some(suffix_chain(&[], "a")),
+        )),
+        "es" | "gl" => gendered(".º", ".ª"),
+        "it" | "pt" => gendered("º", "ª"),
+        "ru" | "uk" | "be" => gendered("-й", "-я"),
+        "nl" => same("e"),
+        "de" | "da" | "nb" | "nn" | "no" | "fi" | "et" | "is" | "cs" | "sk" | "pl" | "hu"
+        | "tr" | "hr" | "sr" | "bs" | "sl" | "lv" | "lt" => same("."),
+        "ja" | "zh" => Some((vec!["\"第\" ++ String.fromInt number".to_string()], None)),
+        "ko" => same("번째"),
+        _ => None,
+    }
+}
+
+/// Whether `ordinal_forms` has the language, rather than the TODO placeholder
+pub fn has_ordinals(code: &str) -> bool {
+    ordinal_forms(code).is_some()
+}
+
+/// `String.fromInt number` with the suffix of the first condition that holds, or
+/// `other`, as an elm-format if/else chain
+fn suffix_chain(cases: &[(&str, &str)], other: &str) -> Vec<String> {
+    let with_suffix = |suffix: &str| format!("String.fromInt number ++ \"{}\"", suffix);
+    let mut lines = Vec::new();
+    for (i, (condition, suffix)) in cases.iter().enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+            lines.push(format!("else if {} then", condition));
+        } else {
+            lines.push(format!("if {} then", condition));
+        }
+        lines.push(format!("    {}", with_suffix(suffix)));
+    }
+    if cases.is_empty() {
+        lines.push(with_suffix(other));
+    } else {
+        lines.push(String::new());
+        lines.push("else".to_string());
+        lines.push(format!("    {}", with_suffix(other)));
+    }
+    lines
+}
+
+/// `suffix_chain` for conditions on `lastTwo`, the last two digits of the number
+fn by_last_two_digits(cases: &[(&str, &str)], other: &str) -> Vec<String> {
+    let mut lines = vec![
+        "let".to_string(),
+        "    lastTwo =".to_string(),
+        "        modBy 100 (abs number)".to_string(),
+        "in".to_string(),
+    ];
+    lines.extend(suffix_chain(cases, other));
+    lines
+}
+
+fn case_branch(lang: &str, lines: &[String]) -> String {
+    let body: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("            {}", line)
+            }
+        })
+        .collect();
+    format!("        {} ->\n{}", language_variant(lang), body.join("\n"))
+}
+
+/// The `formatInt`, `formatNumber` and `formatOrdinal` helpers for `languages`,
+/// between the markers, using only elm/core. A language without ordinal forms
+/// gets `1.` with a `todo_marker` comment.
+pub fn helpers_block(languages: &[String], todo_marker: &str) -> String {
+    let mut branches = Vec::new();
+    let mut ordinals = Vec::new();
+    let mut feminine = Vec::new();
+    for lang in languages {
+        let (thousands, decimal, _) = separators(lang);
+        branches.push(format!(
+            "        {} ->\n            {{ thousands = \"{}\", decimal = \"{}\" }}",
+            language_variant(lang),
+            thousands,
+            decimal
+        ));
+        match ordinal_forms(lang) {
+            Some((masculine, feminine_form)) => {
+                ordinals.push(case_branch(lang, &masculine));
+                if let Some(form) = feminine_form {
+                    feminine.push(case_branch(lang, &form));
+                }
+            }
+            None => {
+                let mut lines = vec![format!(
+                    "-- {} ordinals in {}: this writes 1., 2., 3. until elm-i18n knows them",
+                    todo_marker,
+                    lang.to_uppercase()
+                )];
+                lines.extend(suffix_chain(&[], "."));
+                ordinals.push(case_branch(lang, &lines));
+            }
+        }
+    }
+    let feminine_body = if feminine.is_empty() {
+        "    formatOrdinal lang number".to_string()
+    } else {
+        feminine.push("        _ ->\n            formatOrdinal lang number".to_string());
+        format!("    case lang of\n{}", feminine.join("\n\n"))
+    };
+    format!(
+        r#"{begin}
+-- Written by `elm-i18n generate number-format`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this.
+
+
+{{-| The thousands separator and decimal mark of a language
+-}}
+numberSeparators : Language -> {{ thousands : String, decimal : String }}
+numberSeparators lang =
+    case lang of
+{branches}
+
+
+{{-| An Int with the language's thousands separator: 1,234 in English, 1 234 in French
+-}}
+formatInt : Language -> Int -> String
+formatInt lang number =
+    let
+        sign =
+            if number < 0 then
+                "-"
+
+            else
+                ""
+    in
+    sign ++ groupDigits (numberSeparators lang).thousands (String.fromInt (abs number))
+
+
+{{-| A Float with the language's separators: 1,234.56 in English, 1 234,56 in French
+
+It shows the digits `String.fromFloat` does, so round first for a fixed number of
+decimals. NaN, infinities and numbers written with an exponent only get the
+decimal mark.
+
+-}}
+formatNumber : Language -> Float -> String
+formatNumber lang number =
+    let
+        separators =
+            numberSeparators lang
+
+        sign =
+            if number < 0 then
+                "-"
+
+            else
+                ""
+    in
+    case String.split "." (String.fromFloat (abs number)) of
+        [ whole ] ->
+            if String.all Char.isDigit whole then
+                sign ++ groupDigits separators.thousands whole
+
+            else
+                String.fromFloat number
+
+        [ whole, fraction ] ->
+            if String.all Char.isDigit (whole ++ fraction) then
+                sign ++ groupDigits separators.thousands whole ++ separators.decimal ++ fraction
+
+            else
+                String.replace "." separators.decimal (String.fromFloat number)
+
+        _ ->
+            String.fromFloat number
+
+
+{{-| An ordinal number: 1st, 2nd, 3rd, 11th in English, 1er, 2e in French
+-}}
+formatOrdinal : Language -> Int -> String
+formatOrdinal lang number =
+    case lang of
+{ordinals}
+
+
+{{-| The ordinal for a feminine noun: 1re in French, 1.ª in Spanish, and the same as
+`formatOrdinal` in languages that don't inflect it
+-}}
+formatOrdinalFeminine : Language -> Int -> String
+formatOrdinalFeminine lang number =
+{feminine_body}
+
+
+groupDigits : String -> String -> String
+groupDigits separator digits =
+    if String.length digits <= 3 then
+        digits
+
+    else
+        groupDigits separator (String.dropRight 3 digits) ++ separator ++ String.right 3 digits
+
+
+{end}"#,
+        begin = begin_marker(BLOCK),
+        end = end_marker(BLOCK),
+        branches = branches.join("\n\n"),
+        ordinals = ordinals.join("\n\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::upsert_block;
+
+    #[test]
+    fn writes_one_block_and_refreshes_it_in_place() {
+        assert_eq!(separators("en"), (",", ".", true));
+        assert_eq!(separators("fr-CA"), ("\\u{202F}", ",", true));
+        assert_eq!(separators("de"), (".", ",", true));
+        assert_eq!(separators("de-CH"), ("’", ".", true));
+        assert_eq!(separators("pt-BR"), (".", ",", true));
+        assert_eq!(separators("pt-PT"), ("\\u{00A0}", ",", true));
+        assert_eq!(separators("xx"), (",", ".", false));
+
+        let source = "module I18n exposing (..)\n\n\ntype Language\n    = EN\n    | FR\n";
+        let two = vec!["en".to_string(), "fr".to_string()];
+        let block = helpers_block(&two, "TODO");
+        assert!(block
+            .contains("        FR ->\n            { thousands = \"\\u{202F}\", decimal = \",\" }"));
+
+        let generated = upsert_block(source, BLOCK, &block).unwrap();
+        assert!(generated.starts_with(source));
+        assert!(generated.ends_with(&format!("{}\n", end_marker(BLOCK))));
+        // Running again changes nothing, and a new language updates the same block
+        assert_eq!(upsert_block(&generated, BLOCK, &block).unwrap(), generated);
+        let three = vec!["en".to_string(), "fr".to_string(), "de".to_string()];
+        let refreshed = upsert_block(&generated, BLOCK, &helpers_block(&three, "TODO")).unwrap();
+        assert_eq!(refreshed.matches(&begin_marker(BLOCK)).count(), 1);
+        assert!(refreshed.contains("        DE ->\n            { thousands = \".\""));
+        assert!(upsert_block(&begin_marker(BLOCK), BLOCK, &block).is_none());
+    }
+
+    #[test]
+    fn writes_ordinals_by_language() {
+        let languages = vec!["en".to_string(), "fr".to_string(), "eo".to_string()];
+        let block = helpers_block(&languages, "TODO");
+        assert!(block.contains(
+            "        EN ->
+            let
+                lastTwo =
+                    modBy 100 (abs number)
+            in
+            if modBy 10 lastTwo == 1 && lastTwo /= 11 then
+                String.fromInt number ++ \"st\"
+
+            else if modBy 10 lastTwo == 2 && lastTwo /= 12 then
+                String.fromInt number ++ \"nd\"
+
+            else if modBy 10 lastTwo == 3 && lastTwo /= 13 then
+                String.fromInt number ++ \"rd\"
+
+            else
+                String.fromInt number ++ \"th\"
+
+        FR ->
+            if number == 1 then
+                String.fromInt number ++ \"er\"
+
+            else
+                String.fromInt number ++ \"e\"
+
+        EO ->
+            -- TODO ordinals in EO: this writes 1., 2., 3. until elm-i18n knows them
+            String.fromInt number ++ \".\"
+"
+        ));
+        assert!(block.contains(
+            "formatOrdinalFeminine lang number =
+    case lang of
+        FR ->
+            if number == 1 then
+                String.fromInt number ++ \"re\"
+
+            else
+                String.fromInt number ++ \"e\"
+
+        _ ->
+            formatOrdinal lang number
+"
+        ));
+        assert!(has_ordinals("sv-FI"));
+        assert!(!has_ordinals("eo"));
+
+        let english = helpers_block(&["en".to_string()], "TODO");
+        assert!(english
+            .contains("formatOrdinalFeminine lang number =\n    formatOrdinal lang number\n"));
+    }
+}
@@ -1,19 +1,76 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
 
+use crate::errors::{self, Code, Coded};
+use crate::language_edit::blank_comments;
+use crate::languages::translations_record_name;
+use crate::lexer::{tokenize, Token, TokenKind};
+use crate::timing::{self, Phase};
 use crate::types::{ParseResult, RecordField, Translation, TypeField};
 
+/// The UTF-8 byte-order mark some Windows editors put at the start of a file
+const BOM: char = '\u{feff}';
+
+/// Read an Elm source file, dropping a leading byte-order mark
+///
+/// Invalid UTF-8 is reported with the file and the offset of the first bad byte
+/// rather than as a bare I/O error.
+pub fn read_elm_file(path: &Path) -> Result<String> {
+    let _read = timing::phase(Phase::Read);
+    let bytes = fs::read(path).map_err(|e| {
+        let code = if e.kind() == std::io::ErrorKind::NotFound {
+            Code::FileNotFound
+        } else {
+            Code::ParseError
+        };
+        anyhow::Error::new(e).context(Coded::new(
+            code,
+            format!("Failed to read file: {}", path.display()),
+        ))
+    })?;
+    let content = String::from_utf8(bytes).map_err(|e| {
+        Coded::new(
+            Code::ParseError,
+            format!(
+                "{} is not valid UTF-8 (invalid byte at offset {}); save it as UTF-8 and try again",
+                path.display(),
+                e.utf8_error().valid_up_to()
+            ),
+        )
+    })?;
+    Ok(match content.strip_prefix(BOM) {
+        Some(rest) => rest.to_string(),
+        None => content,
+    })
+}
+
+/// Whether a file starts with a UTF-8 byte-order mark
+pub fn starts_with_bom(path: &Path) -> bool {
+    fs::read(path).is_ok_and(|bytes| bytes.starts_with("\u{feff}".as_bytes()))
+}
+
 pub fn parse_i18n_file_with_record_name(
     path: &Path,
     record_name: &str,
     languages: &[String],
 ) -> Result<ParseResult> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    let content = read_elm_file(path)?;
 
+    parse_i18n_content_with_record_name(&content, record_name, languages)
+        .map_err(|e| errors::in_file(e, path))
+}
+
+/// Parse I18n source that has already been read into memory
+pub fn parse_i18n_content_with_record_name(
+    content: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<ParseResult> {
+    let _parse = timing::phase(Phase::Parse);
     let lines: Vec<&str> = content.lines().collect();
 
     // Find the type definition with custom record name
@@ -24,13 +81,22 @@ pub fn parse_i18n_file_with_record_name(
     let mut lang_fields: HashMap<String, Vec<RecordField>> = HashMap::new();
 
     for lang in languages {
-        let capitalized = capitalize_first(lang);
-        let bounds = find_translation_record_with_type(
-            &lines,
-            &format!("translations{}", capitalized),
-            record_name,
-        )
-        .or_else(|_| find_translation_record_with_type(&lines, lang, record_name))?;
+        let bounds = find_language_record(&lines, lang, record_name)?;
+        let name = lines[bounds.0]
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .next();
+        let definitions = record_declarations(&lines, name.unwrap_or(lang), record_name);
+        if definitions.len() > 1 {
+            anyhow::bail!(Coded::new(
+                Code::ParseError,
+                format!(
+                    "{} is defined {} times (lines {}); merge them with `elm-i18n doctor --merge-duplicates`",
+                    name.unwrap_or(lang),
+                    definitions.len(),
+                    line_list(&definitions.iter().map(|(start, _)| start + 1).collect::<Vec<_>>())
+                ),
+            ));
+        }
         let fields = parse_record_fields(&lines, bounds.0, bounds.1)?;
         lang_bounds.push((lang.clone(), bounds.0, bounds.1));
         lang_fields.insert(lang.clone(), fields);
@@ -53,7 +119,7 @@ pub fn parse_i18n_file_with_record_name(
             values.insert(lang.clone(), value);
         }
 
-        let is_function = type_field.type_annotation.contains("->");
+        let is_function = is_function_type(&type_field.type_annotation);
 
         translations.insert(
             type_field.name.clone(),
@@ -61,10 +127,10 @@ pub fn parse_i18n_file_with_record_name(
                 key: type_field.name.clone(),
                 values,
                 is_function,
-                type_signature: if is_function {
-                    Some(type_field.type_annotation.clone())
-                } else {
+                type_signature: if type_field.type_annotation == "String" {
                     None
+                } else {
+                    Some(type_field.type_annotation.clone())
                 },
             },
         );
@@ -75,38 +141,222 @@ pub fn parse_i18n_file_with_record_name(
         type_end_line: type_bounds.1,
         lang_bounds,
         translations,
+        type_fields,
+        record_fields: lang_fields,
     })
 }
 
-fn capitalize_first(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
-    }
+/// What a line's code does to record nesting, leaving out braces in string and
+/// char literals and in comments
+#[derive(Debug, Clone, Copy, Default)]
+struct LineCode {
+    opens: usize,
+    closes: usize,
+    /// The line starts inside a `{- -}` comment or a `"""` string
+    continued: bool,
+    /// The line has something other than comments and whitespace
+    code: bool,
 }
 
-fn find_type_definition_with_name(lines: &[&str], record_name: &str) -> Result<(usize, usize)> {
-    let mut start = None;
-    let mut brace_count = 0;
+fn scan_code(lines: &[&str]) -> Vec<LineCode> {
+    let mut comment_depth = 0;
+    let mut in_multiline_string = false;
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.contains(&format!("type alias {}", record_name)) {
-            start = Some(i);
-            continue;
-        }
+    lines
+        .iter()
+        .map(|line| {
+            let mut scanned = LineCode {
+                continued: comment_depth > 0 || in_multiline_string,
+                ..LineCode::default()
+            };
+            let chars: Vec<char> = line.chars().collect();
+            let triple_quote = |i: usize| chars[i..].starts_with(&['"', '"', '"']);
+            let mut in_string = false;
+            let mut i = 0;
+            while i < chars.len() {
+                let next = chars.get(i + 1).copied();
+                if comment_depth > 0 {
+                    match (chars[i], next) {
+                        ('{', Some('-')) => {
+                            comment_depth += 1;
+                            i += 1;
+                        }
+                        ('-', Some('}')) => {
+                            comment_depth -= 1;
+                            i += 1;
+                        }
+                        _ => {}
+                    }
+                } else if in_string || in_multiline_string {
+                    scanned.code = true;
+                    match chars[i] {
+                        '\\' => i += 1,
+                        '"' if in_string => in_string = false,
+                        '"' if triple_quote(i) => {
+                            in_multiline_string = false;
+                            i += 2;
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match (chars[i], next) {
+                        ('-', Some('-')) => break,
+                        ('{', Some('-')) => {
+                            comment_depth += 1;
+                            i += 1;
+                        }
+                        ('"', _) => {
+                            scanned.code = true;
+                            if triple_quote(i) {
+                                in_multiline_string = true;
+                                i += 2;
+                            } else {
+                                in_string = true;
+                            }
+                        }
+                        // A char literal such as '{', '\'' or '\u{007B}'
+                        ('\'', Some('\\')) => {
+                            scanned.code = true;
+                            i = (i + 3..chars.len())
+                                .find(|&j| chars[j] == '\'')
+                                .unwrap_or(chars.len());
+                        }
+                        ('\'', Some(_)) if chars.get(i + 2) == Some(&'\'') => {
+                            scanned.code = true;
+                            i += 2;
+                        }
+                        ('{', _) => {
+                            scanned.code = true;
+                            scanned.opens += 1;
+                        }
+                        ('}', _) => {
+                            scanned.code = true;
+                            scanned.closes += 1;
+                        }
+                        (c, _) if !c.is_whitespace() => scanned.code = true,
+                        _ => {}
+                    }
+                }
+                i += 1;
+            }
+            scanned
+        })
+        .collect()
+}
+
+/// `lines` with their `--` and `{- -}` comments blanked out column for column, so
+/// the regexes that find fields see only code; nested block comments included
+pub fn comment_free<S: AsRef<str>>(lines: &[S]) -> Vec<String> {
+    let source: Vec<&str> = lines.iter().map(AsRef::as_ref).collect();
+    blank_comments(&source.join("\n"))
+        .split('\n')
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `line`, whose comment-free code is `code`, has only comments on it
+pub fn is_comment_only(line: &str, code: &str) -> bool {
+    code.trim().is_empty() && !line.trim().is_empty()
+}
 
-        if let Some(_) = start {
-            brace_count += line.matches('{').count();
-            brace_count -= line.matches('}').count();
+/// Whether line `i` starts a top-level declaration: code in the first column
+fn is_declaration(lines: &[&str], code: &[LineCode], i: usize) -> bool {
+    code[i].code && !code[i].continued && !lines[i].starts_with(char::is_whitespace)
+}
 
-            if brace_count == 0 && line.contains('}') {
-                return Ok((start.unwrap(), i));
+/// The line that closes the record of the declaration starting at `start`, the
+/// type alias or the record's annotation; or why that record can't be edited
+///
+/// `definition` is the name of the `name =` line that follows an annotation; the
+/// type alias has none, and mustn't be an extensible record.
+fn record_end(
+    lines: &[&str],
+    code: &[LineCode],
+    start: usize,
+    definition: Option<&str>,
+) -> Result<usize, &'static str> {
+    let extension = Regex::new(r"\{\s*[a-z]\w*\s*\|").unwrap();
+    let mut depth = 0;
+    let mut opened = false;
+
+    for i in start..lines.len() {
+        let heading = i == start || definition.is_some_and(|name| declares(lines[i], name));
+        if i > start && !heading && is_declaration(lines, code, i) {
+            break;
+        }
+        if !opened && !code[i].continued {
+            // The record is the whole body: the first code after `=` opens it
+            let body = if heading {
+                lines[i].split_once('=').map_or("", |(_, body)| body)
+            } else {
+                lines[i]
+            }
+            .trim_start();
+            let comment = body.starts_with("--") || body.starts_with("{-");
+            if !body.is_empty() && !comment {
+                if !body.starts_with('{') {
+                    return Err("isn't a record written out field by field");
+                }
+                // elm-format puts the `| field : ...` of an extensible record on the next line
+                let opening = lines[i..(i + 2).min(lines.len())].join(" ");
+                if definition.is_none() && extension.is_match(&opening) {
+                    return Err("extends another record (`{ base | ... }`)");
+                }
+                opened = true;
             }
         }
+        depth += code[i].opens;
+        depth = depth.saturating_sub(code[i].closes);
+        if opened && depth == 0 {
+            return if lines[i].trim_start().starts_with('}') {
+                Ok(i)
+            } else {
+                Err("closes its record on a line with fields rather than on one of its own")
+            };
+        }
+    }
+
+    if opened {
+        Err("opens a record that never closes")
+    } else {
+        Err("isn't a record written out field by field")
     }
+}
+
+/// Whether `line` starts with the name `name`: `translationsFr`, but not `translationsFrCa`
+fn declares(line: &str, name: &str) -> bool {
+    line.strip_prefix(name)
+        .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
 
-    anyhow::bail!("Could not find {} type definition", record_name)
+fn find_type_definition_with_name(lines: &[&str], record_name: &str) -> Result<(usize, usize)> {
+    let alias = Regex::new(&format!(
+        r"^type\s+alias\s+{}\b",
+        regex::escape(record_name)
+    ))?;
+    let code = scan_code(lines);
+    let start = (0..lines.len())
+        .find(|&i| !code[i].continued && alias.is_match(lines[i]))
+        .ok_or_else(|| {
+            Coded::new(
+                Code::ParseError,
+                format!("Could not find {} type definition", record_name),
+            )
+        })?;
+
+    match record_end(lines, &code, start, None) {
+        Ok(end) => Ok((start, end)),
+        Err(problem) => anyhow::bail!(Coded::new(
+            Code::ParseError,
+            format!(
+                "type alias {} (line {}) {}, so elm-i18n can't safely edit it",
+                record_name,
+                start + 1,
+                problem
+            ),
+        )
+        .line(start + 1)),
+    }
 }
 
 fn find_translation_record_with_type(
@@ -114,129 +364,337 @@ fn find_translation_record_with_type(
     name: &str,
     record_type: &str,
 ) -> Result<(usize, usize)> {
-    let mut start = None;
-    let mut brace_count = 0;
+    match record_declarations(lines, name, record_type).first() {
+        Some((start, Ok(end))) => Ok((*start, *end)),
+        Some((start, Err(problem))) => anyhow::bail!(Coded::new(
+            Code::ParseError,
+            format!(
+                "{} (line {}) {}, so elm-i18n can't safely edit it",
+                name,
+                start + 1,
+                problem
+            ),
+        )
+        .line(start + 1)),
+        None => anyhow::bail!(Coded::new(
+            Code::ParseError,
+            format!("Could not find {} definition", name)
+        )),
+    }
+}
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.starts_with(name) && line.contains(record_type) {
-            start = Some(i);
-            continue;
-        }
+/// The bounds of the record of `lang`, named `translationsFr` or else `fr`
+fn find_language_record(lines: &[&str], lang: &str, record_type: &str) -> Result<(usize, usize)> {
+    let primary = translations_record_name(lang);
+    if record_declarations(lines, &primary, record_type).is_empty() {
+        find_translation_record_with_type(lines, lang, record_type)
+    } else {
+        find_translation_record_with_type(lines, &primary, record_type)
+    }
+}
 
-        if let Some(_) = start {
-            brace_count += line.matches('{').count();
-            brace_count -= line.matches('}').count();
+/// Whether `content` has a record for `lang`, under either name the parser accepts
+pub fn has_translation_record(content: &str, record_name: &str, lang: &str) -> bool {
+    let lines: Vec<&str> = content.lines().collect();
+    find_language_record(&lines, lang, record_name).is_ok()
+}
 
-            if brace_count == 0 && line.trim().starts_with('}') {
-                return Ok((start.unwrap(), i));
-            }
+/// Every declaration named `name` annotated with `record_type` (type variables
+/// allowed), by line of the annotation, with the line closing its record or why
+/// it can't be edited; more than one means a bad merge
+fn record_declarations(
+    lines: &[&str],
+    name: &str,
+    record_type: &str,
+) -> Vec<(usize, Result<usize, &'static str>)> {
+    let code = scan_code(lines);
+    (0..lines.len())
+        .filter(|&i| !code[i].continued && declares(lines[i], name))
+        .filter(|&i| {
+            lines[i][name.len()..]
+                .trim_start()
+                .strip_prefix(':')
+                .is_some_and(|annotation| {
+                    annotation.split_whitespace().next() == Some(record_type)
+                        && !annotation.contains("->")
+                })
+        })
+        .map(|i| (i, record_end(lines, &code, i, Some(name))))
+        .collect()
+}
+
+/// (start, end) of every record named `name` annotated with `record_type`, from
+/// the annotation to the closing brace
+fn translation_records_with_type(
+    lines: &[&str],
+    name: &str,
+    record_type: &str,
+) -> Vec<(usize, usize)> {
+    record_declarations(lines, name, record_type)
+        .into_iter()
+        .filter_map(|(start, end)| Some((start, end.ok()?)))
+        .collect()
+}
+
+/// One definition of a language record
+#[derive(Debug, Clone)]
+pub struct RecordDefinition {
+    /// 0-based line of the annotation
+    pub start: usize,
+    /// 0-based line of the closing brace
+    pub end: usize,
+    pub fields: Vec<RecordField>,
+}
+
+/// A language record defined more than once, such as two `translationsFr` after a
+/// bad merge, which Elm rejects and the parser would otherwise read only the first of
+#[derive(Debug, Clone)]
+pub struct DuplicateRecord {
+    pub name: String,
+    /// In source order
+    pub definitions: Vec<RecordDefinition>,
+}
+
+/// The language records of `content` that are defined more than once
+pub fn duplicate_records(
+    content: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Vec<DuplicateRecord> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut duplicates = Vec::new();
+    for lang in languages {
+        let primary = translations_record_name(lang);
+        let (name, bounds) =
+            match translation_records_with_type(&lines, &primary, record_name).as_slice() {
+                [] => (
+                    lang.clone(),
+                    translation_records_with_type(&lines, lang, record_name),
+                ),
+                bounds => (primary, bounds.to_vec()),
+            };
+        if bounds.len() < 2 {
+            continue;
         }
+        let definitions = bounds
+            .into_iter()
+            .map(|(start, end)| RecordDefinition {
+                start,
+                end,
+                fields: parse_record_fields(&lines, start, end).unwrap_or_default(),
+            })
+            .collect();
+        duplicates.push(DuplicateRecord { name, definitions });
+    }
+    duplicates
+}
+
+/// Line numbers as "30 and 52", or "30, 52 and 70"
+pub fn line_list(numbers: &[usize]) -> String {
+    let lines: Vec<String> = numbers.iter().map(usize::to_string).collect();
+    match lines.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => lines.concat(),
     }
+}
 
-    anyhow::bail!("Could not find {} definition", name)
+/// A top-level field of a record in the source: its name, and the byte span of
+/// what follows its `=` or `:` up to the comma or brace that ends it
+struct FieldSpan {
+    name: String,
+    /// 0-based line of the name
+    line: usize,
+    value: Range<usize>,
+    /// 0-based line of the last token of the value
+    end_line: usize,
 }
 
-fn parse_type_fields(lines: &[&str], start: usize, end: usize) -> Result<Vec<TypeField>> {
+/// The fields of the record on `lines[start..=end]`, from the first `{` after the
+/// declaration's `=` to the brace that closes it; written `name = value` in a
+/// record, or `name : type` with `separator` ":" in a type
+///
+/// Fields are found on the tokens rather than the lines, so an `=`, a brace or a
+/// comma in a string, a comment or a nested record never starts or ends one. What
+/// isn't a field, like the `base |` of a record update, is skipped.
+fn record_field_spans(
+    lines: &[&str],
+    start: usize,
+    end: usize,
+    separator: &str,
+) -> (String, Vec<FieldSpan>) {
+    let end = end.min(lines.len().saturating_sub(1));
+    if start > end {
+        return (String::new(), Vec::new());
+    }
+    let source = lines[start..=end].join("\n");
+    let tokens: Vec<Token> = tokenize(&source)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .collect();
+    let is = |token: &Token, kind: TokenKind, text: &str| {
+        token.kind == kind && token.text(&source) == text
+    };
+    let opening = tokens
+        .iter()
+        .position(|token| is(token, TokenKind::Operator, "="))
+        .and_then(|equals| (equals..tokens.len()).find(|&i| is(&tokens[i], TokenKind::Open, "{")))
+        .or_else(|| {
+            tokens
+                .iter()
+                .position(|token| is(token, TokenKind::Open, "{"))
+        });
+    let Some(opening) = opening else {
+        return (source, Vec::new());
+    };
+
     let mut fields = Vec::new();
-    let field_regex = Regex::new(r"^\s*,?\s*(\w+)\s*:\s*(.+)$")?;
-
-    // Track brace depth to only capture top-level fields
-    // Depth 0 = before first {, Depth 1 = inside top-level record, Depth 2+ = inside nested records
-    let mut brace_depth = 0;
-
-    for i in (start + 1)..end {
-        let line = lines[i];
-
-        // Update brace depth BEFORE checking for field
-        // Count opening braces
-        let open_braces = line.matches('{').count();
-        let close_braces = line.matches('}').count();
-
-        // Only capture fields at depth 1 (top level of the record)
-        // We need to be at depth 1 before the line's braces are processed
-        let current_depth = brace_depth;
-
-        // Update depth after capturing current depth
-        brace_depth += open_braces;
-        brace_depth = brace_depth.saturating_sub(close_braces);
-
-        // Only capture fields at the top level (depth 1)
-        // Note: first line with { puts us at depth 1, so fields are at depth 1
-        if current_depth == 1 || (current_depth == 0 && open_braces > 0) {
-            let normalized_line = strip_leading_record_brace(line);
-            if let Some(captures) = field_regex.captures(normalized_line) {
-                fields.push(TypeField {
-                    name: captures[1].to_string(),
-                    type_annotation: captures[2].trim().to_string(),
-                });
+    let mut current: Option<FieldSpan> = None;
+    let mut expecting = true;
+    let mut depth = 0;
+    let mut i = opening;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        match token.kind {
+            TokenKind::Open => depth += 1,
+            TokenKind::Close => depth -= 1,
+            _ => {}
+        }
+        let ends_field = match token.kind {
+            TokenKind::Close => depth == 0,
+            TokenKind::Comma => depth == 1,
+            _ => false,
+        };
+        if ends_field {
+            if let Some(mut field) = current.take() {
+                field.value.end = token.start;
+                fields.push(field);
             }
+            if depth == 0 {
+                break;
+            }
+            expecting = true;
+        } else if expecting && depth == 1 && i > opening {
+            expecting = false;
+            let named = token.kind == TokenKind::Name && !token.text(&source).contains('.');
+            if let (true, Some(next)) = (named, tokens.get(i + 1)) {
+                if is(next, TokenKind::Operator, separator) {
+                    current = Some(FieldSpan {
+                        name: token.text(&source).to_string(),
+                        line: start + token.line,
+                        value: next.end..source.len(),
+                        end_line: start + next.line,
+                    });
+                    i += 2;
+                    continue;
+                }
+            }
+        } else if let Some(field) = current.as_mut() {
+            field.end_line = start + token.line + token.text(&source).matches('\n').count();
         }
+        i += 1;
+    }
+    if let Some(field) = current {
+        fields.push(field);
     }
+    (source, fields)
+}
 
-    Ok(fields)
+fn parse_type_fields(lines: &[&str], start: usize, end: usize) -> Result<Vec<TypeField>> {
+    let (source, spans) = record_field_spans(lines, start, end, ":");
+    let code = blank_comments(&source);
+    Ok(spans
+        .into_iter()
+        .map(|span| {
+            // An annotation over several lines reads as one
+            let annotation: Vec<&str> = code[span.value]
+                .split('\n')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .collect();
+            TypeField {
+                name: span.name,
+                type_annotation: annotation.join(" "),
+                line: span.line,
+                end_line: span.end_line,
+            }
+        })
+        .collect())
 }
 
 fn parse_record_fields(lines: &[&str], start: usize, end: usize) -> Result<Vec<RecordField>> {
-    let mut fields = Vec::new();
-    let field_regex = Regex::new(r"^\s*,?\s*(\w+)\s*=\s*(.*)$")?;
-    // Regex to detect if a line starts a new field (starts with optional comma then identifier = ...)
-    let new_field_regex = Regex::new(r"^\s*,?\s*\w+\s*=")?;
-
-    let mut i = start + 1;
-    while i < end {
-        let line = lines[i];
-        let normalized_line = strip_leading_record_brace(line);
-
-        if let Some(captures) = field_regex.captures(normalized_line) {
-            let name = captures[1].to_string();
-            let mut value = captures[2].to_string();
-
-            // Check if this is a multiline value (function or case expression)
-            // Only treat as multiline if the next line doesn't start a new field
-            if (value.starts_with('\\') || value.contains("case")) && i + 1 < end {
-                // Check if next line is a continuation (not a new field)
-                let next_line = lines[i + 1];
-                if !new_field_regex.is_match(next_line) {
-                    let mut j = i + 1;
-
-                    // Collect all lines until we find a new field
-                    while j < end {
-                        let current = lines[j];
-
-                        // Stop if this line starts a new field
-                        if new_field_regex.is_match(current) {
-                            break;
-                        }
-
-                        // Add this line to the value
-                        value.push('\n');
-                        value.push_str(&format!("        {}", current.trim_start()));
-
-                        j += 1;
-                    }
+    let (source, spans) = record_field_spans(lines, start, end, "=");
+    let code = blank_comments(&source);
+    // Where `"""` strings are, whose lines are kept as they are
+    let literals: Vec<Range<usize>> = tokenize(&source)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Literal)
+        .map(|token| token.start..token.end)
+        .collect();
+    let in_literal = |offset: usize| literals.iter().any(|literal| literal.contains(&offset));
 
-                    // Position i at the last line we consumed
-                    i = j - 1;
+    Ok(spans
+        .into_iter()
+        .map(|span| {
+            let mut value = String::new();
+            let mut offset = span.value.start;
+            let parts = source[span.value.clone()]
+                .split('\n')
+                .zip(code[span.value.clone()].split('\n'));
+            for (i, (line, current)) in parts.enumerate() {
+                // The newline before a line in a `"""` string is part of it
+                let in_string = i > 0 && in_literal(offset - 1);
+                offset += line.len() + 1;
+                if i == 0 {
+                    value.push_str(current);
+                    continue;
                 }
+                if in_string {
+                    value.push('\n');
+                    value.push_str(line);
+                    continue;
+                }
+                // Comments between the lines aren't part of the value, but the
+                // one marking an ordinal plural is what tells it apart
+                if is_comment_only(line, current) && line.trim() != crate::plural::ORDINAL_MARKER {
+                    continue;
+                }
+                let current = if current.trim().is_empty() {
+                    line
+                } else {
+                    current
+                };
+                value.push('\n');
+                value.push_str(&format!("        {}", current.trim_start()));
             }
-
-            fields.push(RecordField {
-                name,
+            RecordField {
+                name: span.name,
                 value: value.trim().to_string(),
-            });
-        }
-
-        i += 1;
-    }
+                line: span.line,
+                end_line: span.end_line,
+            }
+        })
+        .collect())
+}
 
-    Ok(fields)
+/// The bounds and fields of the record type alias `name`, if `lines` declare one
+/// elm-i18n can read
+pub fn record_type_alias(lines: &[&str], name: &str) -> Option<((usize, usize), Vec<TypeField>)> {
+    let bounds = find_type_definition_with_name(lines, name).ok()?;
+    let fields = parse_type_fields(lines, bounds.0, bounds.1).ok()?;
+    Some((bounds, fields))
 }
 
-fn strip_leading_record_brace(line: &str) -> &str {
-    line.trim_start()
-        .strip_prefix('{')
-        .map(str::trim_start)
-        .unwrap_or(line)
+/// The record that is the value of the field on line `field_line`, from that
+/// line to its closing brace, with the record's fields
+pub fn nested_record(
+    lines: &[&str],
+    field_line: usize,
+) -> Result<((usize, usize), Vec<RecordField>), &'static str> {
+    let code = scan_code(lines);
+    let end = record_end(lines, &code, field_line, None)?;
+    let fields = parse_record_fields(lines, field_line, end)
+        .map_err(|_| "has fields elm-i18n can't read")?;
+    Ok(((field_line, end), fields))
 }
 
 pub fn check_key_exists_with_record_name(
@@ -245,6 +703,341 @@ pub fn check_key_exists_with_record_name(
     record_name: &str,
     languages: &[String],
 ) -> Result<Option<Translation>> {
+    if key.contains('.') {
+        let content = read_elm_file(path)?;
+        return Ok(
+            match crate::nested::lookup(&content, key, record_name, languages)? {
+                Some(crate::nested::Entry::Translation(translation)) => Some(translation),
+                _ => None,
+            },
+        );
+    }
     let result = parse_i18n_file_with_record_name(path, record_name, languages)?;
     Ok(result.translations.get(key).cloned())
 }
+
+/// Whether a field's type annotation is a function's: it has an `->` outside
+/// parentheses and records, so `Maybe (String -> msg)` and `List (Int -> String)`
+/// are values
+pub fn is_function_type(annotation: &str) -> bool {
+    function_arity(annotation) > 0
+}
+
+/// Number of arguments a translation function takes, i.e. `->` outside parentheses or records
+pub fn function_arity(signature: &str) -> usize {
+    let mut depth = 0i32;
+    let mut count = 0;
+    let chars: Vec<char> = signature.chars().collect();
+
+    for i in 0..chars.len() {
+        match chars[i] {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            '-' if depth == 0 && chars.get(i + 1) == Some(&'>') => count += 1,
+            _ => {}
+        }
+    }
+
+    count
+}
+
+/// Collect `-- i18n-max-length: N` annotations, keyed by the field that follows them
+///
+/// The annotation may sit above a field in the type alias or in any language record.
+/// Read `-- i18n-ignore: rule, other-rule` comments placed above a field
+///
+/// Returns the rules to skip for each annotated key.
+pub fn parse_lint_suppressions(content: &str) -> HashMap<String, Vec<String>> {
+    let annotation_regex = Regex::new(r"^\s*--\s*i18n-ignore:\s*(.+?)\s*$").unwrap();
+    let field_regex = Regex::new(r"^\s*[{,]?\s*(\w+)\s*[:=]").unwrap();
+
+    let mut suppressions: HashMap<String, Vec<String>> = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+
+    for line in content.lines() {
+        if let Some(captures) = annotation_regex.captures(line) {
+            pending.extend(
+                captures[1]
+                    .split(',')
+                    .map(|rule| rule.trim().to_string())
+                    .filter(|rule| !rule.is_empty()),
+            );
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+
+        if !pending.is_empty() {
+            if let Some(captures) = field_regex.captures(line) {
+                suppressions
+                    .entry(captures[1].to_string())
+                    .or_default()
+                    .append(&mut pending);
+            }
+            pending.clear();
+        }
+    }
+
+    suppressions
+}
+
+pub fn parse_max_length_annotations(content: &str) -> HashMap<String, usize> {
+    let annotation_regex = Regex::new(r"^\s*--\s*i18n-max-length:\s*(\d+)\s*$").unwrap();
+    let field_regex = Regex::new(r"^\s*[{,]?\s*(\w+)\s*[:=]").unwrap();
+
+    let mut budgets = HashMap::new();
+    let mut pending = None;
+
+    for line in content.lines() {
+        if let Some(captures) = annotation_regex.captures(line) {
+            pending = captures[1].parse::<usize>().ok();
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("--") {
+            continue;
+        }
+
+        if let Some(budget) = pending.take() {
+            if let Some(captures) = field_regex.captures(line) {
+                budgets.insert(captures[1].to_string(), budget);
+            }
+        }
+    }
+
+    budgets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn strips_a_leading_byte_order_mark() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/bom/I18n.elm");
+        assert!(starts_with_bom(&fixture));
+
+        let content = read_elm_file(&fixture).unwrap();
+        assert!(content.starts_with("module I18n"));
+
+        let parse_result =
+            parse_i18n_file_with_record_name(&fixture, "Translations", &["en".to_string()])
+                .unwrap();
+        assert_eq!(parse_result.type_start_line, 3);
+        assert_eq!(
+            parse_result.translations["welcome"].values["en"],
+            "\"Welcome!\""
+        );
+    }
+
+    fn real_world(name: &str) -> String {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/real-world");
+        fs::read_to_string(dir.join(name)).unwrap()
+    }
+
+    #[test]
+    fn finds_the_translations_among_other_declarations() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse = |name: &str| {
+            parse_i18n_content_with_record_name(&real_world(name), "Translations", &languages)
+                .unwrap()
+        };
+
+        // Not the alias in the doc comment, nor `TranslationsConfig`, and the braces in
+        // strings, chars and comments don't end the records early
+        let ports = parse("ports.elm");
+        assert_eq!(ports.type_start_line, 33);
+        let mut keys: Vec<&str> = ports.translations.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["braces", "itemCount", "openBrace", "save"]);
+        assert_eq!(ports.translations["openBrace"].values["fr"], "'{'");
+        assert_eq!(ports.translations["save"].values["fr"], "\"Enregistrer\"");
+
+        let type_variables = parse("type-variables.elm");
+        assert_eq!(type_variables.lang_bounds.len(), 2);
+        assert_eq!(
+            type_variables.translations["termsLink"]
+                .type_signature
+                .as_deref(),
+            Some("(String -> msg) -> Html msg")
+        );
+    }
+
+    #[test]
+    fn tells_functions_from_values_with_qualified_and_parameterized_types() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result = parse_i18n_content_with_record_name(
+            &real_world("qualified-types.elm"),
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        let classified = |key: &str| {
+            let translation = &parse_result.translations[key];
+            (
+                translation.type_signature.as_deref().unwrap_or("String"),
+                translation.is_function,
+            )
+        };
+
+        // An `->` only makes a function outside parentheses and records
+        assert_eq!(classified("heading"), ("String", false));
+        assert_eq!(classified("tooltip"), ("Html.Attribute msg", false));
+        assert_eq!(classified("footer"), ("List (Html msg)", false));
+        assert_eq!(classified("onHelp"), ("Maybe (String -> msg)", false));
+        assert_eq!(classified("formatters"), ("List (Int -> String)", false));
+        assert_eq!(
+            classified("shortcuts"),
+            ("Dict.Dict String ( String, Int -> String )", false)
+        );
+        assert_eq!(
+            classified("greet"),
+            ("{ name : String } -> Html.Html msg", true)
+        );
+        let link = classified("link");
+        assert_eq!(
+            link,
+            (
+                "(String -> msg) -> List (Html.Attribute msg) -> Html msg",
+                true
+            )
+        );
+        assert_eq!(function_arity(link.0), 2);
+        assert_eq!(
+            parse_result.translations["tooltip"].values["fr"],
+            "title \"Ouvrir l’aide\""
+        );
+    }
+
+    #[test]
+    fn skips_nested_block_comments_in_and_around_the_records() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let source = real_world("block-comments.elm");
+        let parse_result =
+            parse_i18n_content_with_record_name(&source, "Translations", &languages).unwrap();
+
+        // Not the alias or the French record that are commented out, nor the
+        // fields in the comments inside the records
+        assert_eq!(parse_result.type_start_line, 16);
+        assert_eq!(parse_result.lang_bounds.len(), 2);
+        let mut keys: Vec<&str> = parse_result
+            .translations
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["farewell", "greeting", "itemCount"]);
+        assert_eq!(
+            parse_result.translations["farewell"].type_signature, None,
+            "the comment after `String` isn't part of the type"
+        );
+        let values = |key: &str| &parse_result.translations[key].values;
+        assert_eq!(values("greeting")["en"], "\"Hello\"");
+        assert_eq!(values("greeting")["fr"], "\"Bonjour\"");
+        assert_eq!(values("farewell")["fr"], "\"Au revoir\"");
+        assert!(!values("itemCount")["en"].contains("none"));
+        assert!(values("itemCount")["en"].ends_with("\"{count} items\""));
+    }
+
+    #[test]
+    fn keeps_the_ordinal_marker_in_a_value() {
+        let languages = vec!["en".to_string()];
+        // Unlike the other comments in a value
+        let ordinal = format!(
+            "type alias Translations =\n    {{ place : Int -> String\n    }}\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    {{ place =\n        \\n ->\n            -- a note\n            {}\n            String.fromInt n ++ \"th\"\n    }}\n",
+            crate::plural::ORDINAL_MARKER
+        );
+        let parse_result =
+            parse_i18n_content_with_record_name(&ordinal, "Translations", &languages).unwrap();
+        let place = &parse_result.translations["place"].values["en"];
+        assert!(place.contains(crate::plural::ORDINAL_MARKER));
+        assert!(!place.contains("a note"));
+        assert!(place.ends_with("String.fromInt n ++ \"th\""));
+    }
+
+    #[test]
+    fn reads_values_and_types_that_go_on_over_lines() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let source = real_world("multiline-values.elm");
+        let parse_result =
+            parse_i18n_content_with_record_name(&source, "Translations", &languages).unwrap();
+        let values = |key: &str| &parse_result.translations[key].values;
+
+        // An `=`, a comma or a brace in a string or a comment ends nothing
+        assert_eq!(values("eq")["en"], "\"a = b, { c }\"");
+        assert_eq!(values("eq")["fr"], "\"a = b\"");
+        // A value is read to the end, not just its first line
+        assert_eq!(
+            values("joined")["fr"],
+            "\"Bonjour, \"\n        ++ \"monde\""
+        );
+        // The lines of a `\"\"\"` string are kept as they are
+        assert_eq!(values("poem")["en"], "\"\"\"Roses\n, are = red\n\"\"\"");
+        let wrapped = &parse_result.translations["wrapped"];
+        assert_eq!(wrapped.type_signature.as_deref(), Some("Int -> String"));
+        assert!(wrapped.is_function);
+
+        let fields = &parse_result.record_fields["en"];
+        let spans: Vec<(&str, usize, usize)> = fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.line, field.end_line))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("eq", 19, 19),
+                ("joined", 20, 22),
+                ("poem", 23, 25),
+                ("wrapped", 26, 26)
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_records_it_cannot_edit() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let error = |source: &str| {
+            parse_i18n_content_with_record_name(source, "Translations", &languages)
+                .unwrap_err()
+                .to_string()
+        };
+
+        // The record would be read as French, and edits would go into it
+        let wrapped = real_world("not-a-record.elm");
+        assert_eq!(
+            error(&wrapped),
+            "translationsFr (line 17) isn't a record written out field by field, so elm-i18n can't safely edit it"
+        );
+        let alias = wrapped.replace(
+            "    withFallback translationsEn\n        { save = Just \"Enregistrer\"\n        , cancel = Nothing\n        }",
+            "    translationsEn",
+        );
+        assert_eq!(
+            error(&alias),
+            "translationsFr (line 17) isn't a record written out field by field, so elm-i18n can't safely edit it"
+        );
+    }
+
+    #[test]
+    fn reports_the_offset_of_invalid_utf8() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("I18n.elm");
+        // "Caf\xe9" is Latin-1, not UTF-8
+        fs::write(&file, b"module I18n exposing (..)\n-- Caf\xe9\n").unwrap();
+
+        let error = read_elm_file(&file).unwrap_err().to_string();
+        assert!(
+            error.ends_with(
+                "is not valid UTF-8 (invalid byte at offset 32); save it as UTF-8 and try again"
+            ),
+            "{}",
+            error
+        );
+        assert!(!starts_with_bom(&file));
+    }
+}
@@ -0,0 +1,741 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Range;
+
+use crate::escaping::escape;
+use crate::lint::field_lines;
+use crate::parser::parse_i18n_content_with_record_name;
+
+/// `source` with a key's `{old}` placeholder renamed to `{new}` in every language
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlaceholderRename {
+    pub content: String,
+    /// How many placeholders were renamed in each language, zero included
+    pub renamed: Vec<(String, usize)>,
+    /// Whether `old` was a field of the function's record, renamed in the type
+    /// alias too, so callers now pass the new name
+    pub record_field: bool,
+}
+
+/// Rename `{old}` to `{new}` in the values of `key`, editing each field where it's
+/// written so the rest of the file stays as it was
+pub fn rename_placeholder_in_content(
+    source: &str,
+    key: &str,
+    old: &str,
+    new: &str,
+    record_name: &str,
+    languages: &[String],
+) -> Result<PlaceholderRename> {
+    let parse_result = parse_i18n_content_with_record_name(source, record_name, languages)?;
+    if !parse_result.translations.contains_key(key) {
+        anyhow::bail!("Translation '{}' not found", key);
+    }
+
+    let mut lines: Vec<String> = source.split_inclusive('\n').map(str::to_string).collect();
+    let mut renamed = Vec::new();
+    let mut record_field = false;
+    for (lang, _, _) in &parse_result.lang_bounds {
+        let Some(Range { start, end }) = field_lines(&parse_result, lang, key) else {
+            continue;
+        };
+        let text = lines[start..end].concat();
+        let Some(prefix) = text.find('=').map(|i| i + 1) else {
+            continue;
+        };
+
+        let (value, count) = rename_in_value(&text[prefix..], old, new);
+        // A record's field is renamed even where it's unused, as the type names it
+        let in_record = record_pattern_fields(&text[prefix..])
+            .iter()
+            .any(|field| field == old);
+        record_field |= in_record;
+        if count > 0 || in_record {
+            replace_lines(
+                &mut lines,
+                start..end,
+                format!("{}{}", &text[..prefix], value),
+            );
+        }
+        renamed.push((lang.clone(), count));
+    }
+
+    // The record's field is part of the function's type
+    if let Some(field) = parse_result
+        .type_fields
+        .iter()
+        .find(|field| field.name == key)
+        .filter(|_| record_field)
+    {
+        let text = lines[field.line..=field.end_line].concat();
+        if let Some(prefix) = text.find(':').map(|i| i + 1) {
+            let record_field =
+                Regex::new(&format!(r"([{{,]\s*){}(\s*:)", regex::escape(old))).unwrap();
+            let annotation =
+                record_field.replace_all(&text[prefix..], format!("${{1}}{}${{2}}", new));
+            replace_lines(
+                &mut lines,
+                field.line..field.end_line + 1,
+                format!("{}{}", &text[..prefix], annotation),
+            );
+        }
+    }
+
+    Ok(PlaceholderRename {
+        content: lines.concat(),
+        renamed,
+        record_field,
+    })
+}
+
+/// Put `text` on the first of `range`, emptying the others
+fn replace_lines(lines: &mut [String], range: Range<usize>, text: String) {
+    lines[range.start] = text;
+    for line in &mut lines[range.start + 1..range.end] {
+        line.clear();
+    }
+}
+
+/// `value` (an Elm expression) with `{old}` renamed to `{new}` in its string
+/// literals, and how many placeholders were renamed
+///
+/// When the value is a function taking an argument named `old`, the argument and
+/// every use of it are renamed too, so a body like `\name -> "Hi " ++ name` stays
+/// in step with its `{name}`. Comments and field accesses (`.old`) are left alone.
+/// A field of a record pattern, as in `\{ name } -> "Hi " ++ name`, is how `add`
+/// writes a placeholder, so each use of it in the body counts as one.
+pub fn rename_in_value(value: &str, old: &str, new: &str) -> (String, usize) {
+    let placeholder = Regex::new(&format!(r"\{{\s*{}\s*\}}", regex::escape(old))).unwrap();
+    let rename_argument = lambda_arguments(value).iter().any(|arg| arg == old);
+    let is_field = record_pattern_fields(value)
+        .iter()
+        .any(|field| field == old);
+    let body_start = match value.find("->") {
+        Some(arrow) if is_field => value[..arrow].chars().count() + 2,
+        _ => usize::MAX,
+    };
+
+    let chars: Vec<char> = value.chars().collect();
+    let mut renamed = String::with_capacity(value.len());
+    let mut count = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest_starts = |s: &str| chars[i..].iter().take(s.len()).copied().eq(s.chars());
+
+        if c == '-' && rest_starts("--") {
+            // A line comment runs to the end of the line
+            while i < chars.len() && chars[i] != '\n' {
+                renamed.push(chars[i]);
+                i += 1;
+            }
+        } else if c == '\'' {
+            // Character literals like '"' must not open a string
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            renamed.extend(&chars[start..i]);
+        } else if c == '"' {
+            let delimiter = if rest_starts("\"\"\"") { 3 } else { 1 };
+            let start = i + delimiter;
+            let mut end = start;
+            while end < chars.len() {
+                if chars[end] == '\\' {
+                    end += 2;
+                    continue;
+                }
+                let closes = if delimiter == 3 {
+                    chars[end..].starts_with(&['"', '"', '"'])
+                } else {
+                    chars[end] == '"'
+                };
+                if closes {
+                    break;
+                }
+                end += 1;
+            }
+            let end = end.min(chars.len());
+            let literal: String = chars[start..end].iter().collect();
+            count += placeholder.find_iter(&literal).count();
+            renamed.extend(&chars[i..start]);
+            renamed.push_str(&placeholder.replace_all(&literal, format!("{{{}}}", new)));
+            i = (end + delimiter).min(chars.len());
+            renamed.extend(&chars[end..i]);
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let field_access = start > 0 && chars[start - 1] == '.';
+            if rename_argument && word == old && !field_access {
+                renamed.push_str(new);
+                if start >= body_start {
+                    count += 1;
+                }
+            } else {
+                renamed.push_str(&word);
+            }
+        } else {
+            renamed.push(c);
+            i += 1;
+        }
+    }
+    (renamed, count)
+}
+
+/// The argument names of a `\a b -> …` value, or none if it isn't a lambda
+///
+/// The fields of a record pattern count as arguments, like the `name` of the
+/// `\{ name } -> …` that `add` writes for a `{name}` placeholder; tuples and other
+/// patterns don't.
+pub fn lambda_arguments(value: &str) -> Vec<String> {
+    let (mut arguments, fields) = lambda_names(value);
+    arguments.extend(fields);
+    arguments
+}
+
+/// The fields of the record patterns among a lambda's arguments
+pub fn record_pattern_fields(value: &str) -> Vec<String> {
+    lambda_names(value).1
+}
+
+/// The plain arguments and the record pattern fields of a `\a { b, c } -> …` value
+fn lambda_names(value: &str) -> (Vec<String>, Vec<String>) {
+    let Some(rest) = value.trim_start().strip_prefix('\\') else {
+        return (Vec::new(), Vec::new());
+    };
+    let Some((arguments, _)) = rest.split_once("->") else {
+        return (Vec::new(), Vec::new());
+    };
+    let is_name =
+        |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    // Split on the spaces between arguments, not those inside a pattern
+    let mut patterns = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    for c in arguments.chars() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            _ => {}
+        }
+        if c.is_whitespace() && depth == 0 {
+            if !current.is_empty() {
+                patterns.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        patterns.push(current);
+    }
+
+    let mut plain = Vec::new();
+    let mut fields = Vec::new();
+    for pattern in patterns {
+        if is_name(&pattern) {
+            plain.push(pattern);
+        } else if let Some(inner) = pattern
+            .strip_prefix('{')
+            .and_then(|pattern| pattern.strip_suffix('}'))
+        {
+            fields.extend(
+                inner
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|field| is_name(field))
+                    .map(str::to_string),
+            );
+        }
+    }
+    (plain, fields)
+}
+
+/// The types a `{placeholder}` can be given with `--param`, and how each is
+/// turned into text
+const PARAM_TYPES: [(&str, Option<&str>); 3] = [
+    ("String", None),
+    ("Int", Some("String.fromInt")),
+    ("Float", Some("String.fromFloat")),
+];
+
+/// A `--param name:Type` argument as the placeholder's name and type
+pub fn parse_param(input: &str) -> Result<(String, String)> {
+    let Some((name, kind)) = input.split_once(':') else {
+        bail!("--param {} should be NAME:TYPE, e.g. count:Int", input);
+    };
+    let (name, kind) = (name.trim().trim_matches(['{', '}']), kind.trim());
+    if !PARAM_TYPES.iter().any(|(known, _)| *known == kind) {
+        let supported: Vec<&str> = PARAM_TYPES.iter().map(|(known, _)| *known).collect();
+        bail!(
+            "--param {}: `{}` isn't a type a placeholder can have; use one of {}",
+            input,
+            kind,
+            supported.join(", ")
+        );
+    }
+    Ok((name.to_string(), kind.to_string()))
+}
+
+/// The function type and each language's implementation for plain values with
+/// `{name}` placeholders, or None when no value has one
+///
+/// The function takes a record of the placeholders, those in `params` first and in
+/// their order, then the others in the order the first language uses them. They're
+/// Strings unless `params` says otherwise, and the text is joined with `++`:
+/// `"Hello, {name}!"` becomes `\{ name } -> "Hello, " ++ name ++ "!"`. Every
+/// language must have the same placeholders, except those still holding
+/// `todo_marker`, which return it.
+pub fn placeholder_function(
+    values: &HashMap<String, String>,
+    languages: &[String],
+    params: &[(String, String)],
+    todo_marker: &str,
+) -> Result<Option<(String, HashMap<String, String>)>> {
+    let is_todo = |value: &str| !todo_marker.is_empty() && value.starts_with(todo_marker);
+    let split: Vec<(&String, Vec<Piece>)> = languages
+        .iter()
+        .filter_map(|lang| Some((lang, values.get(lang)?)))
+        .filter(|(_, value)| !is_todo(value))
+        .map(|(lang, value)| (lang, pieces(value)))
+        .collect();
+
+    let names = |pieces: &[Piece]| -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for piece in pieces {
+            if let Piece::Placeholder(name) = piece {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    };
+    let Some((base_lang, base_pieces)) = split.iter().find(|(_, p)| !names(p).is_empty()) else {
+        if let Some((name, _)) = params.first() {
+            bail!("--param {} names no {{placeholder}} of the values", name);
+        }
+        return Ok(None);
+    };
+    let mut fields: Vec<String> = Vec::new();
+    for (name, _) in params {
+        if fields.contains(name) {
+            bail!("--param {} is given more than once", name);
+        }
+        fields.push(name.clone());
+    }
+    let used = names(base_pieces);
+    if let Some((name, _)) = params.iter().find(|(name, _)| !used.contains(name)) {
+        bail!(
+            "--param {} names no {{placeholder}} of the values, which have {}",
+            name,
+            shown(&used)
+        );
+    }
+    for name in used {
+        if !fields.contains(&name) {
+            fields.push(name);
+        }
+    }
+    let base: BTreeSet<&String> = fields.iter().collect();
+    for (lang, pieces) in &split {
+        let found = names(pieces);
+        let set: BTreeSet<&String> = found.iter().collect();
+        if set != base {
+            return Err(PlaceholderMismatch(format!(
+                "{} placeholders differ from {}: {} has {}, {} has {}",
+                lang.to_uppercase(),
+                base_lang.to_uppercase(),
+                base_lang.to_uppercase(),
+                shown(&fields),
+                lang.to_uppercase(),
+                shown(&found)
+            ))
+            .into());
+        }
+    }
+    for name in &fields {
+        if crate::ELM_RESERVED_WORDS.contains(&name.as_str()) {
+            bail!(
+                "{{{}}} can't be a placeholder, as `{}` is an Elm keyword; rename it, e.g. {{{}Name}}",
+                name,
+                name,
+                name
+            );
+        }
+    }
+    let kind = |name: &str| {
+        params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map_or("String", |(_, kind)| kind.as_str())
+    };
+    let pattern = format!("\\{{ {} }} ->", fields.join(", "));
+    let mut implementations = HashMap::new();
+    for lang in languages {
+        let Some(value) = values.get(lang) else {
+            continue;
+        };
+        let body = match split.iter().find(|(l, _)| *l == lang) {
+            Some((_, pieces)) => concatenation(pieces, &kind),
+            None => {
+                implementations.insert(lang.clone(), format!("\\_ -> \"{}\"", escape(value)));
+                continue;
+            }
+        };
+        implementations.insert(lang.clone(), format!("{} {}", pattern, body));
+    }
+    let signature: Vec<String> = fields
+        .iter()
+        .map(|name| format!("{} : {}", name, kind(name)))
+        .collect();
+    Ok(Some((
+        format!("{{ {} }} -> String", signature.join(", ")),
+        implementations,
+    )))
+}
+
+/// Why [`placeholder_function`] refused values whose languages have different
+/// placeholders, the mismatch the `placeholders` check reports
+#[derive(Debug)]
+pub struct PlaceholderMismatch(String);
+
+impl std::fmt::Display for PlaceholderMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PlaceholderMismatch {}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Placeholder(String),
+}
+
+/// A plain value split into text and `{name}` placeholders; braces around
+/// anything that can't be an Elm name, like `{0}`, stay text
+fn pieces(value: &str) -> Vec<Piece> {
+    let placeholder = Regex::new(r"\{\s*([a-z][A-Za-z0-9_]*)\s*\}").unwrap();
+    let mut pieces = Vec::new();
+    let mut last = 0;
+    for captures in placeholder.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        if whole.start() > last {
+            pieces.push(Piece::Text(value[last..whole.start()].to_string()));
+        }
+        pieces.push(Piece::Placeholder(captures[1].to_string()));
+        last = whole.end();
+    }
+    if last < value.len() {
+        pieces.push(Piece::Text(value[last..].to_string()));
+    }
+    pieces
+}
+
+/// The pieces joined with `++`, each placeholder turned into text for its type
+fn concatenation<'a>(pieces: &[Piece], kind: &impl Fn(&str) -> &'a str) -> String {
+    let joined: Vec<String> = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => format!("\"{}\"", escape(text)),
+            Piece::Placeholder(name) => {
+                match PARAM_TYPES.iter().find(|(known, _)| *known == kind(name)) {
+                    Some((_, Some(to_string))) => format!("{} {}", to_string, name),
+                    _ => name.clone(),
+                }
+            }
+        })
+        .collect();
+    if joined.is_empty() {
+        "\"\"".to_string()
+    } else {
+        joined.join(" ++ ")
+    }
+}
+
+fn shown(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names
+            .iter()
+            .map(|name| format!("{{{}}}", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_a_function_of_values_with_placeholders() {
+        let languages = vec!["en".to_string(), "fr".to_string(), "de".to_string()];
+        let values = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(l, v)| (l.to_string(), v.to_string()))
+                .collect()
+        };
+        let params = [parse_param("count:Int").unwrap()];
+        let (signature, implementations) = placeholder_function(
+            &values(&[
+                ("en", "{count} \"new\" messages for { name }"),
+                ("fr", "{name} a {count} nouveaux messages"),
+                ("de", "TODO"),
+            ]),
+            &languages,
+            &params,
+            "TODO",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(signature, "{ count : Int, name : String } -> String");
+        assert_eq!(
+            implementations["en"],
+            r#"\{ count, name } -> String.fromInt count ++ " \"new\" messages for " ++ name"#
+        );
+        assert_eq!(
+            implementations["fr"],
+            r#"\{ count, name } -> name ++ " a " ++ String.fromInt count ++ " nouveaux messages""#
+        );
+        assert_eq!(implementations["de"], r#"\_ -> "TODO""#);
+
+        // Braces around anything but an Elm name are text
+        let plain = values(&[("en", "Step {1}"), ("fr", "Étape {1}")]);
+        assert_eq!(
+            placeholder_function(&plain, &languages, &[], "TODO").unwrap(),
+            None
+        );
+
+        let error = placeholder_function(
+            &values(&[("en", "Hi {name}"), ("fr", "Salut {nom}")]),
+            &languages,
+            &[],
+            "TODO",
+        )
+        .unwrap_err();
+        assert!(error.downcast_ref::<PlaceholderMismatch>().is_some());
+        assert_eq!(
+            error.to_string(),
+            "FR placeholders differ from EN: EN has {name}, FR has {nom}"
+        );
+    }
+
+    #[test]
+    fn params_come_first_in_their_order() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let inbox: HashMap<String, String> = [
+            (
+                "en",
+                "Hi {name}, you have {unread} new messages from {sender}",
+            ),
+            ("fr", "{sender} vous a envoyé {unread} messages, {name}"),
+        ]
+        .iter()
+        .map(|(l, v)| (l.to_string(), v.to_string()))
+        .collect();
+        let params = [
+            parse_param("unread:Int").unwrap(),
+            parse_param("{name}:String").unwrap(),
+        ];
+        let (signature, implementations) =
+            placeholder_function(&inbox, &languages, &params, "TODO")
+                .unwrap()
+                .unwrap();
+        assert_eq!(
+            signature,
+            "{ unread : Int, name : String, sender : String } -> String"
+        );
+        assert_eq!(
+            implementations["fr"],
+            r#"\{ unread, name, sender } -> sender ++ " vous a envoyé " ++ String.fromInt unread ++ " messages, " ++ name"#
+        );
+
+        // The same --params the other way round swap the record's fields
+        let swapped = [params[1].clone(), params[0].clone()];
+        let (signature, _) = placeholder_function(&inbox, &languages, &swapped, "TODO")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            signature,
+            "{ name : String, unread : Int, sender : String } -> String"
+        );
+
+        let twice = [params[0].clone(), params[0].clone()];
+        assert_eq!(
+            placeholder_function(&inbox, &languages, &twice, "TODO")
+                .unwrap_err()
+                .to_string(),
+            "--param unread is given more than once"
+        );
+        let unused = [parse_param("count:Int").unwrap()];
+        assert_eq!(
+            placeholder_function(&inbox, &languages, &unused, "TODO")
+                .unwrap_err()
+                .to_string(),
+            "--param count names no {placeholder} of the values, which have {name}, {unread}, {sender}"
+        );
+    }
+
+    #[test]
+    fn refuses_params_of_unknown_types() {
+        assert_eq!(
+            parse_param("count:Bool").unwrap_err().to_string(),
+            "--param count:Bool: `Bool` isn't a type a placeholder can have; use one of String, Int, Float"
+        );
+        // Types are Elm's, so case matters
+        assert!(parse_param("count:int").is_err());
+        assert!(parse_param("count:").is_err());
+        assert_eq!(
+            parse_param("count").unwrap_err().to_string(),
+            "--param count should be NAME:TYPE, e.g. count:Int"
+        );
+        assert_eq!(
+            parse_param(" count : Float ").unwrap(),
+            ("count".to_string(), "Float".to_string())
+        );
+    }
+
+    #[test]
+    fn renames_placeholders_and_the_matching_argument() {
+        assert_eq!(
+            rename_in_value(r#""Hello { name }, {name}!""#, "name", "userName"),
+            (r#""Hello {userName}, {userName}!""#.to_string(), 2)
+        );
+        // `{nameless}` and text outside literals aren't placeholders
+        assert_eq!(
+            rename_in_value(r#""{nameless} name""#, "name", "userName"),
+            (r#""{nameless} name""#.to_string(), 0)
+        );
+
+        let function = "\\name count ->\n        -- greet name\n        \"Bonjour {name} \" ++ name ++ user.name";
+        assert_eq!(
+            rename_in_value(function, "name", "userName"),
+            (
+                "\\userName count ->\n        -- greet name\n        \"Bonjour {userName} \" ++ userName ++ user.name"
+                    .to_string(),
+                1
+            )
+        );
+        // Without an argument of that name, only the literal changes
+        assert_eq!(
+            rename_in_value("\\n -> name ++ \"{name}\"", "name", "who"),
+            ("\\n -> name ++ \"{who}\"".to_string(), 1)
+        );
+        assert_eq!(lambda_arguments("\\a (b, c) d -> a"), vec!["a", "d"]);
+    }
+
+    #[test]
+    fn renames_a_placeholder_in_every_language() {
+        let source = r#"module I18n exposing (..)
+
+type alias Translations =
+    { greeting : String -> String
+    , title : String
+    }
+
+translationsEn : Translations
+translationsEn =
+    { greeting =
+        \name ->
+            "Hello {name}"
+    , title = "{name}"
+    }
+
+translationsFr : Translations
+translationsFr =
+    { greeting = \nom -> "Bonjour " ++ nom
+    , title = "Titre"
+    }
+"#;
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let result = rename_placeholder_in_content(
+            source,
+            "greeting",
+            "name",
+            "userName",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert_eq!(
+            result.content,
+            source.replace(
+                "\\name ->\n            \"Hello {name}\"",
+                "\\userName ->\n            \"Hello {userName}\""
+            )
+        );
+        assert_eq!(
+            result.renamed,
+            vec![("en".to_string(), 1), ("fr".to_string(), 0)]
+        );
+
+        assert!(rename_placeholder_in_content(
+            source,
+            "missing",
+            "name",
+            "userName",
+            "Translations",
+            &languages
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn renames_the_record_field_of_a_key_add_made() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let values: HashMap<String, String> = [
+            ("en".to_string(), "Hello {name}, {name}!".to_string()),
+            ("fr".to_string(), "Bonjour {name} !".to_string()),
+        ]
+        .into();
+        let (signature, implementations) = placeholder_function(&values, &languages, &[], "TODO")
+            .unwrap()
+            .unwrap();
+        let source = format!(
+            "module I18n exposing (..)\n\ntype alias Translations =\n    {{ greet : {}\n    }}\n\ntranslationsEn : Translations\ntranslationsEn =\n    {{ greet = {}\n    }}\n\ntranslationsFr : Translations\ntranslationsFr =\n    {{ greet = \\{{ name }} -> \"Salut\"\n    }}\n",
+            signature, implementations["en"]
+        );
+
+        let result = rename_placeholder_in_content(
+            &source,
+            "greet",
+            "name",
+            "user",
+            "Translations",
+            &languages,
+        )
+        .unwrap();
+        assert!(result.record_field);
+        assert_eq!(
+            result.renamed,
+            vec![("en".to_string(), 2), ("fr".to_string(), 0)]
+        );
+        assert!(result
+            .content
+            .contains("{ greet : { user : String } -> String\n"));
+        assert!(result
+            .content
+            .contains(r#"{ greet = \{ user } -> "Hello " ++ user ++ ", " ++ user ++ "!""#));
+        // Unused in FR, but the type names it, so it's renamed there too
+        assert!(result.content.contains(r#"{ greet = \{ user } -> "Salut""#));
+        assert_eq!(
+            lambda_arguments(r"\{ name, count } n -> n"),
+            vec!["n", "name", "count"]
+        );
+    }
+}
@@ -0,0 +1,1176 @@
+use std::collections::BTreeMap;
+
+use crate::escaping::unescape;
+
+/// The CLDR plural categories, in the order CLDR lists them
+pub const CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// How a language picks the CLDR cardinal plural category of a whole number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluralRules {
+    /// Categories with the Elm condition (`{n}` for the number) a number must meet to
+    /// have it, tested in order
+    pub conditions: Vec<(&'static str, &'static str)>,
+    /// The category of the numbers none of the conditions match
+    pub fallback: &'static str,
+}
+
+impl PluralRules {
+    /// The categories a whole number can have in the language, in CLDR order
+    pub fn categories(&self) -> Vec<&'static str> {
+        CATEGORIES
+            .into_iter()
+            .filter(|category| {
+                *category == self.fallback || self.conditions.iter().any(|(c, _)| c == category)
+            })
+            .collect()
+    }
+
+    /// The category of `n`
+    pub fn category(&self, n: i64) -> &'static str {
+        self.conditions
+            .iter()
+            .find(|(_, condition)| {
+                evaluate_condition(&condition.replace("{n}", "n"), "n", n) == Some(true)
+            })
+            .map_or(self.fallback, |(category, _)| category)
+    }
+}
+
+const ONE: (&str, &str) = ("one", "{n} == 1");
+const ZERO_OR_ONE: (&str, &str) = ("one", "{n} == 0 || {n} == 1");
+/// `one` for 1, 21, 31… but not 11, as in Russian or Croatian
+const ONE_BUT_ELEVEN: (&str, &str) = ("one", "modBy 10 {n} == 1 && modBy 100 {n} /= 11");
+/// `few` for 2–4, 22–24… but not 12–14, as in Russian or Polish
+const FEW_SLAVIC: (&str, &str) = (
+    "few",
+    "List.member (modBy 10 {n}) [ 2, 3, 4 ] && not (List.member (modBy 100 {n}) [ 12, 13, 14 ])",
+);
+
+/// The cardinal plural rules of `lang` for whole numbers, from CLDR; `None` for
+/// languages elm-i18n has no rules for
+///
+/// Only the categories a whole number can have are listed: Russian's `other` is for
+/// fractions, so its whole numbers are `one`, `few` or `many`. The `many` CLDR gives
+/// exact millions in French, Spanish, Italian, Portuguese and Catalan is left out, so
+/// those take `other` as they did before CLDR added it.
+pub fn rules(lang: &str) -> Option<PluralRules> {
+    let lang = lang.to_lowercase().replace('_', "-");
+    let (base, region) = lang.split_once('-').unwrap_or((&lang, ""));
+    let (conditions, fallback) = match base {
+        "pt" if region == "pt" => (vec![ONE], "other"),
+        "fr" | "pt" | "hy" | "hi" | "bn" | "gu" | "kn" | "fa" | "zu" | "am" => {
+            (vec![ZERO_OR_ONE], "other")
+        }
+        "en" | "de" | "nl" | "sv" | "da" | "nb" | "nn" | "no" | "it" | "es" | "ca" | "el"
+        | "fi" | "et" | "hu" | "tr" | "bg" | "eu" | "gl" | "af" | "sq" | "az" | "ka" | "kk"
+        | "ky" | "mn" | "ne" | "ta" | "te" | "ur" | "uz" | "sw" | "ml" | "mr" | "fo" | "lb" => {
+            (vec![ONE], "other")
+        }
+        "ru" | "uk" | "be" => (vec![ONE_BUT_ELEVEN, FEW_SLAVIC], "many"),
+        "pl" => (vec![ONE, FEW_SLAVIC], "many"),
+        "hr" | "sr" | "bs" => (vec![ONE_BUT_ELEVEN, FEW_SLAVIC], "other"),
+        "cs" | "sk" => (vec![ONE, ("few", "{n} >= 2 && {n} <= 4")], "other"),
+        "is" | "mk" => (vec![ONE_BUT_ELEVEN], "other"),
+        "sl" => (
+            vec![
+                ("one", "modBy 100 {n} == 1"),
+                ("two", "modBy 100 {n} == 2"),
+                ("few", "modBy 100 {n} == 3 || modBy 100 {n} == 4"),
+            ],
+            "other",
+        ),
+        "lt" => (
+            vec![
+                (
+                    "one",
+                    "modBy 10 {n} == 1 && not (modBy 100 {n} >= 11 && modBy 100 {n} <= 19)",
+                ),
+                (
+                    "few",
+                    "modBy 10 {n} >= 2 && not (modBy 100 {n} >= 11 && modBy 100 {n} <= 19)",
+                ),
+            ],
+            "other",
+        ),
+        "lv" => (
+            vec![
+                (
+                    "zero",
+                    "modBy 10 {n} == 0 || (modBy 100 {n} >= 11 && modBy 100 {n} <= 19)",
+                ),
+                ONE_BUT_ELEVEN,
+            ],
+            "other",
+        ),
+        "ro" => (
+            vec![
+                ONE,
+                (
+                    "few",
+                    "{n} == 0 || (modBy 100 {n} >= 1 && modBy 100 {n} <= 19)",
+                ),
+            ],
+            "other",
+        ),
+        "ar" => (
+            vec![
+                ("zero", "{n} == 0"),
+                ONE,
+                ("two", "{n} == 2"),
+                ("few", "modBy 100 {n} >= 3 && modBy 100 {n} <= 10"),
+                ("many", "modBy 100 {n} >= 11"),
+            ],
+            "other",
+        ),
+        "he" => (vec![ONE, ("two", "{n} == 2")], "other"),
+        "ga" => (
+            vec![
+                ONE,
+                ("two", "{n} == 2"),
+                ("few", "{n} >= 3 && {n} <= 6"),
+                ("many", "{n} >= 7 && {n} <= 10"),
+            ],
+            "other",
+        ),
+        "cy" => (
+            vec![
+                ("zero", "{n} == 0"),
+                ONE,
+                ("two", "{n} == 2"),
+                ("few", "{n} == 3"),
+                ("many", "{n} == 6"),
+            ],
+            "other",
+        ),
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" | "km" => {
+            (Vec::new(), "other")
+        }
+        _ => return None,
+    };
+    Some(PluralRules {
+        conditions,
+        fallback,
+    })
+}
+
+/// The comment `add-plural --ordinal` puts at the top of each implementation, so
+/// the plural-categories lint checks it against the ordinal rules
+pub const ORDINAL_MARKER: &str = "-- elm-i18n:ordinal";
+
+/// The ordinal plural rules of `lang` (1st, 2nd, 3rd, 4th in English), from CLDR;
+/// `None` for languages elm-i18n has no rules for
+///
+/// Most languages write every ordinal the same way and only have `other`.
+pub fn ordinal_rules(lang: &str) -> Option<PluralRules> {
+    let lang = lang.to_lowercase().replace('_', "-");
+    let base = lang.split('-').next().unwrap_or_default();
+    let (conditions, fallback) = match base {
+        "en" => (
+            vec![
+                ("one", "modBy 10 {n} == 1 && modBy 100 {n} /= 11"),
+                ("two", "modBy 10 {n} == 2 && modBy 100 {n} /= 12"),
+                ("few", "modBy 10 {n} == 3 && modBy 100 {n} /= 13"),
+            ],
+            "other",
+        ),
+        "fr" | "ga" | "ro" | "ms" | "vi" | "hy" | "fil" | "tl" | "lo" => (vec![ONE], "other"),
+        "sv" => (
+            vec![(
+                "one",
+                "List.member (modBy 10 {n}) [ 1, 2 ] && not (List.member (modBy 100 {n}) [ 11, 12 ])",
+            )],
+            "other",
+        ),
+        "it" => (
+            vec![("many", "List.member {n} [ 8, 11, 80, 800 ]")],
+            "other",
+        ),
+        "ca" => (
+            vec![
+                ("one", "{n} == 1 || {n} == 3"),
+                ("two", "{n} == 2"),
+                ("few", "{n} == 4"),
+            ],
+            "other",
+        ),
+        "hu" => (vec![("one", "{n} == 1 || {n} == 5")], "other"),
+        "uk" => (
+            vec![("few", "modBy 10 {n} == 3 && modBy 100 {n} /= 13")],
+            "other",
+        ),
+        "kk" => (
+            vec![(
+                "many",
+                "modBy 10 {n} == 6 || modBy 10 {n} == 9 || (modBy 10 {n} == 0 && {n} /= 0)",
+            )],
+            "other",
+        ),
+        "sq" => (
+            vec![ONE, ("many", "modBy 10 {n} == 4 && modBy 100 {n} /= 14")],
+            "other",
+        ),
+        "mk" => (
+            vec![
+                ONE_BUT_ELEVEN,
+                ("two", "modBy 10 {n} == 2 && modBy 100 {n} /= 12"),
+                (
+                    "many",
+                    "List.member (modBy 10 {n}) [ 7, 8 ] && not (List.member (modBy 100 {n}) [ 17, 18 ])",
+                ),
+            ],
+            "other",
+        ),
+        "hi" | "bn" | "gu" => (
+            vec![
+                ONE,
+                ("two", "{n} == 2 || {n} == 3"),
+                ("few", "{n} == 4"),
+                ("many", "{n} == 6"),
+            ],
+            "other",
+        ),
+        "cy" => (
+            vec![
+                ("zero", "List.member {n} [ 0, 7, 8, 9 ]"),
+                ONE,
+                ("two", "{n} == 2"),
+                ("few", "{n} == 3 || {n} == 4"),
+                ("many", "{n} == 5 || {n} == 6"),
+            ],
+            "other",
+        ),
+        "ne" => (vec![("one", "{n} >= 1 && {n} <= 4")], "other"),
+        "de" | "nl" | "da" | "nb" | "nn" | "no" | "es" | "pt" | "el" | "fi" | "et" | "tr"
+        | "bg" | "ru" | "be" | "pl" | "cs" | "sk" | "hr" | "sr" | "bs" | "sl" | "lt" | "lv"
+        | "is" | "he" | "ar" | "fa" | "ja" | "zh" | "ko" | "th" | "id" | "eu" | "gl" | "af"
+        | "ta" | "te" | "ur" | "sw" | "ml" | "mr" | "lb" => (Vec::new(), "other"),
+        _ => return None,
+    };
+    Some(PluralRules {
+        conditions,
+        fallback,
+    })
+}
+
+/// Why a plural given `given` categories in `lang` doesn't match the language's
+/// rules, its ordinal ones with `ordinal`, if it doesn't: every category it has
+/// is needed, and no other is allowed
+pub fn category_problem(lang: &str, given: &[&str], ordinal: bool) -> Option<String> {
+    let found = if ordinal {
+        ordinal_rules(lang)
+    } else {
+        rules(lang)
+    };
+    let Some(rules) = found else {
+        let extra: Vec<&str> = given.iter().copied().filter(|c| *c != "other").collect();
+        return match (given.contains(&"other"), extra.is_empty()) {
+            (true, true) => None,
+            _ => Some(format!(
+                "elm-i18n has no {} rules for {}, so it takes only `other` (and exact counts)",
+                if ordinal { "ordinal" } else { "plural" },
+                lang.to_uppercase()
+            )),
+        };
+    };
+    let categories = rules.categories();
+    let missing: Vec<&str> = categories
+        .iter()
+        .copied()
+        .filter(|c| !given.contains(c))
+        .collect();
+    let unexpected: Vec<&str> = given
+        .iter()
+        .copied()
+        .filter(|c| !categories.contains(c))
+        .collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        return None;
+    }
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("is missing {}", listed(&missing, "and")));
+    }
+    if !unexpected.is_empty() {
+        problems.push(format!(
+            "has {}, which {} whole numbers don't have",
+            listed(&unexpected, "and"),
+            lang.to_uppercase()
+        ));
+    }
+    Some(format!(
+        "{} {}; its categories are {}",
+        lang.to_uppercase(),
+        problems.join(" and "),
+        listed(&categories, "and")
+    ))
+}
+
+/// Counts tried on a plural function: enough to cover the `modBy 100` rules, and a
+/// few large ones
+fn sample_counts() -> impl Iterator<Item = i64> {
+    (0..=200).chain([1000, 1001, 1002, 1005, 1011, 1021, 1_000_000])
+}
+
+/// What's wrong with the branches of `value` for the categories of `lang`, when it's
+/// a plural: a function of `signature` taking one Int, alone or in a record, that
+/// branches on it. A category may have no branch of its own, or a count may take the
+/// branch of another category's counts.
+///
+/// The function is run on sample counts, which works for bodies that are `if`
+/// chains testing the count with comparisons, `modBy`, `remainderBy` and
+/// `List.member`, or a `case` on it with number patterns. Anything else, and any
+/// function that doesn't branch at all, gets no problems. A value with the
+/// `ORDINAL_MARKER` comment is checked against the ordinal rules.
+pub fn coverage_problems(signature: &str, value: &str, lang: &str) -> Vec<String> {
+    let found = if value.contains(ORDINAL_MARKER) {
+        ordinal_rules(lang)
+    } else {
+        rules(lang)
+    };
+    let Some(rules) = found.filter(|rules| rules.categories().len() > 1) else {
+        return Vec::new();
+    };
+    let Some(count) = count_argument(signature) else {
+        return Vec::new();
+    };
+    let Some((argument, tree)) = plural_tree(value, count.as_deref()) else {
+        return Vec::new();
+    };
+    if tree.leaves() < 2 {
+        return Vec::new();
+    }
+
+    // The counts of each category that reach each branch
+    let categories = rules.categories();
+    let mut reached: BTreeMap<usize, Vec<(&str, i64)>> = BTreeMap::new();
+    for n in sample_counts() {
+        let Some(branch) = tree.branch(&argument, n) else {
+            return Vec::new();
+        };
+        reached
+            .entry(branch)
+            .or_default()
+            .push((rules.category(n), n));
+    }
+    // A branch is for the category most of its counts have
+    let owners: BTreeMap<usize, &str> = reached
+        .iter()
+        .map(|(branch, counts)| {
+            let owner = categories
+                .iter()
+                .copied()
+                .max_by_key(|category| {
+                    let count = counts.iter().filter(|(c, _)| c == category).count();
+                    // The first category in CLDR order wins a tie
+                    (
+                        count,
+                        std::cmp::Reverse(categories.iter().position(|c| c == category)),
+                    )
+                })
+                .unwrap_or(rules.fallback);
+            (*branch, owner)
+        })
+        .collect();
+
+    let mut problems = Vec::new();
+    // 0 only when nothing else has the category, as `zero`
+    let example = |category: &str, branch: Option<usize>| {
+        let mut counts = reached
+            .iter()
+            .filter(|(b, _)| branch.is_none_or(|branch| **b == branch))
+            .flat_map(|(_, counts)| counts)
+            .filter(|(c, _)| *c == category)
+            .map(|(_, n)| *n);
+        let first = counts.next().unwrap_or_default();
+        if first == 0 {
+            counts.next().unwrap_or(0)
+        } else {
+            first
+        }
+    };
+    let without_branch: Vec<String> = categories
+        .iter()
+        .filter(|category| !owners.values().any(|owner| owner == *category))
+        .map(|category| format!("`{}` (e.g. {})", category, example(category, None)))
+        .collect();
+    if !without_branch.is_empty() {
+        problems.push(format!(
+            "has no branch of its own for {}; {} needs {}",
+            listed(&without_branch, "or"),
+            lang.to_uppercase(),
+            listed(&categories, "and")
+        ));
+    }
+
+    // A category with a branch of its own whose counts don't all go there, like 21
+    // in Russian with `if count == 1`
+    for category in &categories {
+        if !owners.values().any(|owner| owner == category) {
+            continue;
+        }
+        let stray = reached.iter().find_map(|(branch, counts)| {
+            let owner = owners[branch];
+            let n = counts.iter().find(|(c, _)| c == category)?.1;
+            (owner != *category).then_some((n, owner, *branch))
+        });
+        if let Some((n, owner, branch)) = stray {
+            problems.push(format!(
+                "{} is `{}` in {} but gets the branch of {} (`{}`)",
+                n,
+                category,
+                lang.to_uppercase(),
+                example(owner, Some(branch)),
+                owner
+            ));
+        }
+    }
+    problems
+}
+
+/// What a plural `value` of `signature` says for each category of `lang`, for
+/// `check` to show rather than the code: a count of the category (the first one
+/// above 0 if it has any) and the text it gets. Small counts with a branch of
+/// their own, like `0 -> "No items"`, come first, under their number.
+///
+/// None when `value` isn't a plural `coverage_problems` can run, or one of its
+/// branches isn't plain text and the count joined with `++`.
+pub fn plural_forms(signature: &str, value: &str, lang: &str) -> Option<Vec<(String, String)>> {
+    let rules = if value.contains(ORDINAL_MARKER) {
+        ordinal_rules(lang)
+    } else {
+        rules(lang)
+    }?;
+    let count = count_argument(signature)?;
+    let (argument, tree) = plural_tree(value, count.as_deref())?;
+    if tree.leaves() < 2 {
+        return None;
+    }
+
+    let categories = rules.categories();
+    let example = |category: &str| {
+        let mut counts = sample_counts().filter(|n| rules.category(*n) == category);
+        let first = counts.next()?;
+        Some(if first == 0 {
+            counts.next().unwrap_or(0)
+        } else {
+            first
+        })
+    };
+    let examples: Vec<(&str, i64)> = categories
+        .iter()
+        .filter_map(|category| Some((*category, example(category)?)))
+        .collect();
+
+    let mut forms = Vec::new();
+    for n in 0..=10 {
+        let (branch, tokens) = tree.leaf(&argument, n)?;
+        let category = rules.category(n);
+        let usual = examples
+            .iter()
+            .find(|(c, _)| *c == category)
+            .and_then(|(_, example)| tree.branch(&argument, *example));
+        if usual.is_some_and(|usual| usual != branch) {
+            forms.push((n.to_string(), branch_text(tokens, &argument, n)?));
+        }
+    }
+    for (category, n) in examples {
+        let (_, tokens) = tree.leaf(&argument, n)?;
+        forms.push((category.to_string(), branch_text(tokens, &argument, n)?));
+    }
+    Some(forms)
+}
+
+fn listed(items: &[impl AsRef<str>], conjunction: &str) -> String {
+    let items: Vec<&str> = items.iter().map(AsRef::as_ref).collect();
+    match items.as_slice() {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [init @ .., last] => format!("{} {} {}", init.join(", "), conjunction, last),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Name(String),
+    Symbol(String),
+    /// A string literal's body, as written
+    Text(String),
+    /// A character literal, or anything else a condition can't contain
+    Other,
+}
+
+fn tokenize(code: &str) -> Vec<Token> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            tokens.push(digits.parse().map_or(Token::Other, Token::Int));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "_.".contains(chars[i])) {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if c == '"' || c == '\'' {
+            let triple = c == '"' && chars[i..].starts_with(&['"', '"', '"']);
+            i += if triple { 3 } else { 1 };
+            let start = i;
+            let mut end = chars.len();
+            while i < chars.len() {
+                if chars[i] == '\\' {
+                    i += 2;
+                } else if triple && chars[i..].starts_with(&['"', '"', '"']) {
+                    end = i;
+                    i += 3;
+                    break;
+                } else if !triple && chars[i] == c {
+                    end = i;
+                    i += 1;
+                    break;
+                } else {
+                    i += 1;
+                }
+            }
+            tokens.push(if c == '"' {
+                Token::Text(chars[start..end.min(chars.len())].iter().collect())
+            } else {
+                Token::Other
+            });
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if ["==", "/=", "<=", ">=", "&&", "||", "->", "++"].contains(&two.as_str()) {
+                tokens.push(Token::Symbol(two));
+                i += 2;
+            } else {
+                tokens.push(Token::Symbol(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Bool(bool),
+    List(Vec<i64>),
+}
+
+/// Evaluates a condition on whole numbers, such as `modBy 10 n == 1 && n /= 11`
+struct Evaluator<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    argument: &'a str,
+    n: i64,
+}
+
+impl Evaluator<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol.to_string())) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Option<Value> {
+        let mut value = self.and()?;
+        while self.symbol("||") {
+            let right = self.and()?;
+            value = Value::Bool(as_bool(&value)? || as_bool(&right)?);
+        }
+        Some(value)
+    }
+
+    fn and(&mut self) -> Option<Value> {
+        let mut value = self.comparison()?;
+        while self.symbol("&&") {
+            let right = self.comparison()?;
+            value = Value::Bool(as_bool(&value)? && as_bool(&right)?);
+        }
+        Some(value)
+    }
+
+    fn comparison(&mut self) -> Option<Value> {
+        let left = self.sum()?;
+        for operator in ["==", "/=", "<=", ">=", "<", ">"] {
+            if self.symbol(operator) {
+                let right = self.sum()?;
+                let result = match (&left, &right) {
+                    (Value::Int(a), Value::Int(b)) => match operator {
+                        "==" => a == b,
+                        "/=" => a != b,
+                        "<=" => a <= b,
+                        ">=" => a >= b,
+                        "<" => a < b,
+                        _ => a > b,
+                    },
+                    (Value::Bool(a), Value::Bool(b)) if operator == "==" => a == b,
+                    (Value::Bool(a), Value::Bool(b)) if operator == "/=" => a != b,
+                    _ => return None,
+                };
+                return Some(Value::Bool(result));
+            }
+        }
+        Some(left)
+    }
+
+    fn sum(&mut self) -> Option<Value> {
+        let mut value = self.product()?;
+        loop {
+            let sign = if self.symbol("+") {
+                1
+            } else if self.symbol("-") {
+                -1
+            } else {
+                return Some(value);
+            };
+            let right = as_int(&self.product()?)?;
+            value = Value::Int(as_int(&value)? + sign * right);
+        }
+    }
+
+    fn product(&mut self) -> Option<Value> {
+        let mut value = self.application()?;
+        while self.symbol("*") {
+            let right = as_int(&self.application()?)?;
+            value = Value::Int(as_int(&value)? * right);
+        }
+        Some(value)
+    }
+
+    fn application(&mut self) -> Option<Value> {
+        let function = match self.peek() {
+            Some(Token::Name(name)) => name.clone(),
+            _ => return self.atom(),
+        };
+        match function.as_str() {
+            "modBy" | "remainderBy" => {
+                self.pos += 1;
+                let divisor = as_int(&self.atom()?)?;
+                let dividend = as_int(&self.atom()?)?;
+                if divisor == 0 {
+                    return None;
+                }
+                Some(Value::Int(if function == "modBy" {
+                    dividend.rem_euclid(divisor)
+                } else {
+                    dividend % divisor
+                }))
+            }
+            "not" => {
+                self.pos += 1;
+                Some(Value::Bool(!as_bool(&self.atom()?)?))
+            }
+            "abs" => {
+                self.pos += 1;
+                Some(Value::Int(as_int(&self.atom()?)?.abs()))
+            }
+            "List.member" => {
+                self.pos += 1;
+                let item = as_int(&self.atom()?)?;
+                match self.atom()? {
+                    Value::List(items) => Some(Value::Bool(items.contains(&item))),
+                    _ => None,
+                }
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Option<Value> {
+        match self.peek()?.clone() {
+            Token::Int(n) => {
+                self.pos += 1;
+                Some(Value::Int(n))
+            }
+            Token::Name(name) if name == self.argument => {
+                self.pos += 1;
+                Some(Value::Int(self.n))
+            }
+            Token::Name(name) if name == "True" || name == "False" => {
+                self.pos += 1;
+                Some(Value::Bool(name == "True"))
+            }
+            Token::Symbol(s) if s == "(" => {
+                self.pos += 1;
+                let value = self.or()?;
+                self.symbol(")").then_some(value)
+            }
+            Token::Symbol(s) if s == "-" => {
+                self.pos += 1;
+                Some(Value::Int(-as_int(&self.atom()?)?))
+            }
+            Token::Symbol(s) if s == "[" => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if self.symbol("]") {
+                    return Some(Value::List(items));
+                }
+                loop {
+                    items.push(as_int(&self.or()?)?);
+                    if self.symbol("]") {
+                        return Some(Value::List(items));
+                    }
+                    if !self.symbol(",") {
+                        return None;
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        _ => None,
+    }
+}
+
+fn as_int(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn evaluate(tokens: &[Token], argument: &str, n: i64) -> Option<Value> {
+    let mut evaluator = Evaluator {
+        tokens,
+        pos: 0,
+        argument,
+        n,
+    };
+    let value = evaluator.or()?;
+    (evaluator.pos == tokens.len()).then_some(value)
+}
+
+/// Whether `condition` holds when `argument` is `n`, or None if it isn't a
+/// condition elm-i18n can evaluate
+fn evaluate_condition(condition: &str, argument: &str, n: i64) -> Option<bool> {
+    as_bool(&evaluate(&tokenize(condition), argument, n)?)
+}
+
+/// The branches of a plural function's body, numbered in source order
+#[derive(Debug)]
+enum Tree {
+    /// A branch's number, and its expression
+    Leaf(usize, Vec<Token>),
+    If(Vec<Token>, Box<Tree>, Box<Tree>),
+    /// A `case` on the count: number patterns, or None for `_`
+    Case(Vec<(Option<i64>, Tree)>),
+}
+
+impl Tree {
+    fn leaves(&self) -> usize {
+        match self {
+            Tree::Leaf(..) => 1,
+            Tree::If(_, then, otherwise) => then.leaves() + otherwise.leaves(),
+            Tree::Case(branches) => branches.iter().map(|(_, tree)| tree.leaves()).sum(),
+        }
+    }
+
+    /// The branch `n` takes
+    fn branch(&self, argument: &str, n: i64) -> Option<usize> {
+        self.leaf(argument, n).map(|(id, _)| id)
+    }
+
+    /// The number and expression of the branch `n` takes
+    fn leaf(&self, argument: &str, n: i64) -> Option<(usize, &[Token])> {
+        match self {
+            Tree::Leaf(id, tokens) => Some((*id, tokens)),
+            Tree::If(condition, then, otherwise) => {
+                if as_bool(&evaluate(condition, argument, n)?)? {
+                    then.leaf(argument, n)
+                } else {
+                    otherwise.leaf(argument, n)
+                }
+            }
+            Tree::Case(branches) => branches
+                .iter()
+                .find(|(pattern, _)| pattern.is_none_or(|p| p == n))
+                .and_then(|(_, tree)| tree.leaf(argument, n)),
+        }
+    }
+}
+
+/// The text a branch's expression gives for `n`, when it only joins string
+/// literals and `String.fromInt` of the count with `++`
+fn branch_text(tokens: &[Token], argument: &str, n: i64) -> Option<String> {
+    let tokens = match tokens {
+        [Token::Symbol(open), inner @ .., Token::Symbol(close)]
+            if open == "(" && close == ")" && encloses(tokens) =>
+        {
+            inner
+        }
+        _ => tokens,
+    };
+    let mut text = String::new();
+    for part in tokens.split(|token| *token == Token::Symbol("++".to_string())) {
+        match part {
+            [Token::Text(literal)] => text.push_str(&unescape(literal)),
+            [Token::Name(function), Token::Name(count)]
+                if function == "String.fromInt" && count == argument =>
+            {
+                text.push_str(&n.to_string())
+            }
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+
+/// The count a plural function of `signature` takes: None for a lone `Int`, and the
+/// field's name for the one Int field of a record
+fn count_argument(signature: &str) -> Option<Option<String>> {
+    let signature: Vec<&str> = signature.split_whitespace().collect();
+    let signature = signature.join(" ");
+    if signature == "Int -> String" {
+        return Some(None);
+    }
+    let fields = signature
+        .strip_prefix('{')?
+        .strip_suffix("} -> String")?
+        .split(',')
+        .map(|field| field.split_once(':'))
+        .collect::<Option<Vec<_>>>()?;
+    let mut ints = fields.iter().filter(|(_, kind)| kind.trim() == "Int");
+    match (ints.next(), ints.next()) {
+        (Some((name, _)), None) => Some(Some(name.trim().to_string())),
+        _ => None,
+    }
+}
+
+/// The count's name and the branches of a plural function: `\n -> …` when `count` is
+/// None, or `\{ count, … } -> …`
+fn plural_tree(value: &str, count: Option<&str>) -> Option<(String, Tree)> {
+    let tokens = tokenize(value);
+    let symbol = |token: &Token, s: &str| *token == Token::Symbol(s.to_string());
+    let (argument, body) = match (tokens.as_slice(), count) {
+        ([backslash, Token::Name(name), arrow, body @ ..], None)
+            if symbol(backslash, "\\") && symbol(arrow, "->") =>
+        {
+            (name.clone(), body)
+        }
+        ([backslash, open, rest @ ..], Some(count))
+            if symbol(backslash, "\\") && symbol(open, "{") =>
+        {
+            let close = rest.iter().position(|t| symbol(t, "}"))?;
+            let names = &rest[..close];
+            // Names separated by commas, one of them the count
+            let well_formed = names.iter().enumerate().all(|(i, token)| match token {
+                Token::Name(_) => i % 2 == 0,
+                token => i % 2 == 1 && symbol(token, ","),
+            });
+            let has_count = names.contains(&Token::Name(count.to_string()));
+            match &rest[close + 1..] {
+                [arrow, body @ ..] if well_formed && has_count && symbol(arrow, "->") => {
+                    (count.to_string(), body)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    let mut next_leaf = 0;
+    let tree = tree(body, &argument, &mut next_leaf)?;
+    Some((argument, tree))
+}
+
+fn tree(tokens: &[Token], argument: &str, next_leaf: &mut usize) -> Option<Tree> {
+    let is_name = |token: &Token, name: &str| *token == Token::Name(name.to_string());
+    let tokens = match tokens {
+        [Token::Symbol(open), inner @ .., Token::Symbol(close)]
+            if open == "(" && close == ")" && encloses(tokens) =>
+        {
+            inner
+        }
+        _ => tokens,
+    };
+    match tokens.first() {
+        Some(first) if is_name(first, "if") => {
+            let then_at = tokens.iter().position(|t| is_name(t, "then"))?;
+            let condition = tokens[1..then_at].to_vec();
+            if condition.iter().any(|t| is_name(t, "if")) {
+                return None;
+            }
+            // Each `if` nested in the branch has an `else` of its own
+            let mut depth = 0;
+            let mut else_at = None;
+            for (i, token) in tokens.iter().enumerate().skip(then_at + 1) {
+                if is_name(token, "if") {
+                    depth += 1;
+                } else if is_name(token, "else") {
+                    if depth == 0 {
+                        else_at = Some(i);
+                        break;
+                    }
+                    depth -= 1;
+                }
+            }
+            let else_at = else_at?;
+            let then = tree(&tokens[then_at + 1..else_at], argument, next_leaf)?;
+            let otherwise = tree(&tokens[else_at + 1..], argument, next_leaf)?;
+            Some(Tree::If(condition, Box::new(then), Box::new(otherwise)))
+        }
+        Some(first) if is_name(first, "case") => {
+            let [_, Token::Name(scrutinee), of, rest @ ..] = tokens else {
+                return None;
+            };
+            if scrutinee != argument || !is_name(of, "of") {
+                return None;
+            }
+            let arrow = Token::Symbol("->".to_string());
+            let starts: Vec<(usize, Option<i64>)> = (0..rest.len())
+                .filter_map(|i| match (&rest[i], rest.get(i + 1)) {
+                    (Token::Int(n), Some(next)) if *next == arrow => Some((i, Some(*n))),
+                    (Token::Name(name), Some(next)) if name == "_" && *next == arrow => {
+                        Some((i, None))
+                    }
+                    _ => None,
+                })
+                .collect();
+            if starts.first().map(|(i, _)| *i) != Some(0) {
+                return None;
+            }
+            let mut branches = Vec::new();
+            for (index, (start, pattern)) in starts.iter().enumerate() {
+                let end = starts.get(index + 1).map_or(rest.len(), |(i, _)| *i);
+                let body = &rest[start + 2..end];
+                if body.iter().any(|t| is_name(t, "case")) {
+                    return None;
+                }
+                branches.push((*pattern, tree(body, argument, next_leaf)?));
+            }
+            Some(Tree::Case(branches))
+        }
+        Some(first) if is_name(first, "let") => None,
+        _ => {
+            *next_leaf += 1;
+            Some(Tree::Leaf(*next_leaf - 1, tokens.to_vec()))
+        }
+    }
+}
+
+/// Whether the parenthesis `tokens` open with is the one they end with
+fn encloses(tokens: &[Token]) -> bool {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Symbol(s) if s == "(" => depth += 1,
+            Token::Symbol(s) if s == ")" => {
+                depth -= 1;
+                if depth == 0 && i + 1 < tokens.len() {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_cldr_category_of_whole_numbers() {
+        let categories = |lang: &str, counts: &[i64]| -> Vec<&'static str> {
+            let rules = rules(lang).unwrap();
+            counts.iter().map(|n| rules.category(*n)).collect()
+        };
+        assert_eq!(
+            categories("ru", &[1, 2, 5, 11, 12, 21, 22, 25, 111, 1001]),
+            ["one", "few", "many", "many", "many", "one", "few", "many", "many", "one"]
+        );
+        assert_eq!(
+            categories("pl", &[1, 2, 5, 12, 21, 22]),
+            ["one", "few", "many", "many", "many", "few"]
+        );
+        assert_eq!(
+            categories("ar", &[0, 1, 2, 3, 10, 11, 99, 100, 102, 111]),
+            ["zero", "one", "two", "few", "few", "many", "many", "other", "other", "many"]
+        );
+        assert_eq!(categories("fr", &[0, 1, 2]), ["one", "one", "other"]);
+        assert_eq!(categories("pt-PT", &[0, 1]), ["other", "one"]);
+        assert_eq!(rules("ar").unwrap().categories().len(), 6);
+        assert_eq!(rules("ru").unwrap().categories(), ["one", "few", "many"]);
+        assert!(rules("tlh").is_none());
+
+        assert_eq!(category_problem("en", &["one", "other"], false), None);
+        assert_eq!(
+            category_problem("ru", &["one", "other"], false).unwrap(),
+            "RU is missing few and many and has other, which RU whole numbers don't have; its categories are one, few and many"
+        );
+        assert!(category_problem("tlh", &["other"], false).is_none());
+        assert!(category_problem("tlh", &["one", "other"], false).is_some());
+    }
+
+    #[test]
+    fn picks_the_cldr_ordinal_category() {
+        let categories = |lang: &str, counts: &[i64]| -> Vec<&'static str> {
+            let rules = ordinal_rules(lang).unwrap();
+            counts.iter().map(|n| rules.category(*n)).collect()
+        };
+        let edge_cases = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 101, 111, 112, 113];
+        assert_eq!(
+            categories("en", &edge_cases),
+            [
+                "one", "two", "few", "other", "other", "other", "other", "one", "two", "few",
+                "one", "other", "other", "other"
+            ]
+        );
+        assert_eq!(
+            categories("fr", &[1, 2, 11, 21, 101, 111]),
+            ["one", "other", "other", "other", "other", "other"]
+        );
+        assert_eq!(
+            categories("sv", &[1, 2, 3, 11, 12, 21, 22, 101, 111]),
+            ["one", "one", "other", "other", "other", "one", "one", "one", "other"]
+        );
+        assert_eq!(
+            categories("it", &[8, 11, 21, 80, 111]),
+            ["many", "many", "other", "many", "other"]
+        );
+        assert_eq!(ordinal_rules("de").unwrap().categories(), ["other"]);
+        assert!(ordinal_rules("tlh").is_none());
+
+        assert_eq!(
+            category_problem("en", &["one", "two", "few", "other"], true),
+            None
+        );
+        assert_eq!(
+            category_problem("en", &["one", "other"], true).unwrap(),
+            "EN is missing two and few; its categories are one, two, few and other"
+        );
+
+        // Checked against the ordinal rules only with the marker
+        let english = format!("\\{{ rank }} ->\n    {}\n    if modBy 10 rank == 1 && modBy 100 rank /= 11 then\n        String.fromInt rank ++ \"st\"\n\n    else if modBy 10 rank == 2 && modBy 100 rank /= 12 then\n        String.fromInt rank ++ \"nd\"\n\n    else if modBy 10 rank == 3 && modBy 100 rank /= 13 then\n        String.fromInt rank ++ \"rd\"\n\n    else\n        String.fromInt rank ++ \"th\"", ORDINAL_MARKER);
+        assert!(coverage_problems("{ rank : Int } -> String", &english, "en").is_empty());
+        let unmarked = english.replace(ORDINAL_MARKER, "");
+        assert!(!coverage_problems("{ rank : Int } -> String", &unmarked, "en").is_empty());
+        let naive = format!("\\{{ rank }} ->\n    {}\n    if rank == 1 then\n        \"1st\"\n\n    else\n        String.fromInt rank ++ \"th\"", ORDINAL_MARKER);
+        assert_eq!(
+            coverage_problems("{ rank : Int } -> String", &naive, "en")[0],
+            "has no branch of its own for `two` (e.g. 2) or `few` (e.g. 3); EN needs one, two, few and other"
+        );
+    }
+
+    #[test]
+    fn finds_plural_functions_that_miss_a_category() {
+        let naive = "\\count ->\n    if count == 1 then\n        \"1 файл\"\n\n    else\n        String.fromInt count ++ \" файлов\"";
+        assert_eq!(
+            coverage_problems("Int -> String", naive, "ru"),
+            vec![
+                "has no branch of its own for `few` (e.g. 2); RU needs one, few and many"
+                    .to_string(),
+                "21 is `one` in RU but gets the branch of 5 (`many`)".to_string(),
+            ]
+        );
+        assert!(coverage_problems("Int -> String", naive, "en").is_empty());
+
+        let french = "\\n -> if n == 1 then \"1 fichier\" else String.fromInt n ++ \" fichiers\"";
+        assert_eq!(
+            coverage_problems("Int -> String", french, "fr"),
+            vec!["0 is `one` in FR but gets the branch of 2 (`other`)".to_string()]
+        );
+
+        // What elm-i18n generates is right, `=0` branch and all
+        let generated = "\\{ count } ->\n    if count == 0 then\n        \"Нет файлов\"\n\n    else if modBy 10 count == 1 && modBy 100 count /= 11 then\n        String.fromInt count ++ \" файл\"\n\n    else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then\n        String.fromInt count ++ \" файла\"\n\n    else\n        String.fromInt count ++ \" файлов\"";
+        assert!(coverage_problems("{ count : Int } -> String", generated, "ru").is_empty());
+
+        let case = "\\n ->\n    case n of\n        1 ->\n            \"one\"\n\n        _ ->\n            \"many\"";
+        assert_eq!(
+            coverage_problems("Int -> String", case, "pl"),
+            vec!["has no branch of its own for `few` (e.g. 2); PL needs one, few and many"]
+        );
+        // Bodies it can't read, or that don't branch, are left alone
+        assert!(coverage_problems("Int -> String", "\\n -> String.fromInt n", "ru").is_empty());
+        assert!(coverage_problems(
+            "Int -> String",
+            "\\n -> if isOne n then \"a\" else \"b\"",
+            "ru"
+        )
+        .is_empty());
+        // A record with the count and a String, as add-plural writes for `{name}`
+        let record = "\\{ count, name } -> if count == 1 then name ++ \" a 1 fichier\" else name ++ \" a \" ++ String.fromInt count ++ \" fichiers\"";
+        assert_eq!(
+            coverage_problems("{ count : Int, name : String } -> String", record, "fr").len(),
+            1
+        );
+        assert!(
+            coverage_problems("{ count : Int, total : Int } -> String", record, "fr").is_empty()
+        );
+    }
+
+    #[test]
+    fn reads_what_each_plural_form_says() {
+        let generated = "\\{ count } ->\n    if count == 0 then\n        \"Нет \\\"файлов\\\"\"\n\n    else if modBy 10 count == 1 && modBy 100 count /= 11 then\n        String.fromInt count ++ \" файл\"\n\n    else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then\n        (String.fromInt count ++ \" файла\")\n\n    else\n        String.fromInt count ++ \" файлов\"";
+        let forms = plural_forms("{ count : Int } -> String", generated, "ru").unwrap();
+        let forms: Vec<(&str, &str)> = forms
+            .iter()
+            .map(|(form, text)| (form.as_str(), text.as_str()))
+            .collect();
+        assert_eq!(
+            forms,
+            [
+                ("0", "Нет \"файлов\""),
+                ("one", "1 файл"),
+                ("few", "2 файла"),
+                ("many", "5 файлов"),
+            ]
+        );
+
+        // Branches that aren't plain text are shown as code
+        let record = "\\{ count, name } -> if count == 1 then name ++ \" a 1 fichier\" else name ++ \" a \" ++ String.fromInt count ++ \" fichiers\"";
+        assert_eq!(
+            plural_forms("{ count : Int, name : String } -> String", record, "fr"),
+            None
+        );
+        assert_eq!(plural_forms("String", "\"Files\"", "en"), None);
+
+        // An ordinal is read with the ordinal categories
+        let ordinal = format!(
+            "\\{{ count }} ->\n    {}\n    if modBy 10 count == 1 && modBy 100 count /= 11 then\n        String.fromInt count ++ \"st\"\n\n    else if modBy 10 count == 2 && modBy 100 count /= 12 then\n        String.fromInt count ++ \"nd\"\n\n    else if modBy 10 count == 3 && modBy 100 count /= 13 then\n        String.fromInt count ++ \"rd\"\n\n    else\n        String.fromInt count ++ \"th\"",
+            ORDINAL_MARKER
+        );
+        let forms = plural_forms("{ count : Int } -> String", &ordinal, "en").unwrap();
+        let forms: Vec<(&str, &str)> = forms
+            .iter()
+            .map(|(form, text)| (form.as_str(), text.as_str()))
+            .collect();
+        assert_eq!(
+            forms,
+            [
+                ("one", "1st"),
+                ("two", "2nd"),
+                ("few", "3rd"),
+                ("other", "4th")
+            ]
+        );
+    }
+}
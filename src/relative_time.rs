@@ -0,0 +1,467 @@
+use anyhow::Result;
+
+use crate::config::KeyStyle;
+use crate::generated::{begin_marker, end_marker};
+use crate::icu::{self, Selector};
+use crate::languages::{language_variant, translations_record_name};
+use crate::plural;
+use crate::types::Translation;
+
+/// The name of the block `generate relative-time` writes, in its marker comments
+pub const BLOCK: &str = "relative-time";
+
+/// The units `relativeTime` counts in, with the seconds in one of them
+const UNITS: [(&str, i64); 5] = [
+    ("Seconds", 1),
+    ("Minutes", 60),
+    ("Hours", 3_600),
+    ("Days", 86_400),
+    ("Weeks", 604_800),
+];
+
+/// How a language says how long ago, or how long from now, something is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeTexts {
+    /// Under 10 seconds either way
+    pub now: &'static str,
+    /// The past, with `{}` for the count and its unit ("{} ago")
+    pub past: &'static str,
+    /// The future, with `{}` for the count and its unit ("in {}")
+    pub future: &'static str,
+    /// Seconds to weeks, each with a form for every plural category the language
+    /// has, `#` being the count
+    pub units: [&'static [(&'static str, &'static str)]; 5],
+}
+
+const ENGLISH: RelativeTexts = RelativeTexts {
+    now: "just now",
+    past: "{} ago",
+    future: "in {}",
+    units: [
+        &[("one", "# second"), ("other", "# seconds")],
+        &[("one", "# minute"), ("other", "# minutes")],
+        &[("one", "# hour"), ("other", "# hours")],
+        &[("one", "# day"), ("other", "# days")],
+        &[("one", "# week"), ("other", "# weeks")],
+    ],
+};
+
+/// The built-in texts of `lang`; `None` for languages the table doesn't have
+pub fn texts(lang: &str) -> Option<RelativeTexts> {
+    let lower = lang.to_lowercase();
+    let base = lower.split(['-', '_']).next().unwrap_or_default();
+    let texts = match base {
+        "en" => ENGLISH,
+        "fr" => RelativeTexts {
+            now: "à l’instant",
+            past: "il y a {}",
+            future: "dans {}",
+            units: [
+                &[("one", "# seconde"), ("other", "# secondes")],
+                &[("one", "# minute"), ("other", "# minutes")],
+                &[("one", "# heure"), ("other", "# heures")],
+                &[("one", "# jour"), ("other", "# jours")],
+                &[("one", "# semaine"), ("other", "# semaines")],
+            ],
+        },
+        // Both take the dative: "vor 2 Tagen", "in 2 Tagen"
+        "de" => RelativeTexts {
+            now: "gerade eben",
+            past: "vor {}",
+            future: "in {}",
+            units: [
+                &[("one", "# Sekunde"), ("other", "# Sekunden")],
+                &[("one", "# Minute"), ("other", "# Minuten")],
+                &[("one", "# Stunde"), ("other", "# Stunden")],
+                &[("one", "# Tag"), ("other", "# Tagen")],
+                &[("one", "# Woche"), ("other", "# Wochen")],
+            ],
+        },
+        "es" => RelativeTexts {
+            now: "ahora mismo",
+            past: "hace {}",
+            future: "dentro de {}",
+            units: [
+                &[("one", "# segundo"), ("other", "# segundos")],
+                &[("one", "# minuto"), ("other", "# minutos")],
+                &[("one", "# hora"), ("other", "# horas")],
+                &[("one", "# día"), ("other", "# días")],
+                &[("one", "# semana"), ("other", "# semanas")],
+            ],
+        },
+        "it" => RelativeTexts {
+            now: "proprio ora",
+            past: "{} fa",
+            future: "tra {}",
+            units: [
+                &[("one", "# secondo"), ("other", "# secondi")],
+                &[("one", "# minuto"), ("other", "# minuti")],
+                &[("one", "# ora"), ("other", "# ore")],
+                &[("one", "# giorno"), ("other", "# giorni")],
+                &[("one", "# settimana"), ("other", "# settimane")],
+            ],
+        },
+        "pt" => RelativeTexts {
+            now: "agora mesmo",
+            past: "há {}",
+            future: "em {}",
+            units: [
+                &[("one", "# segundo"), ("other", "# segundos")],
+                &[("one", "# minuto"), ("other", "# minutos")],
+                &[("one", "# hora"), ("other", "# horas")],
+                &[("one", "# dia"), ("other", "# dias")],
+                &[("one", "# semana"), ("other", "# semanas")],
+            ],
+        },
+        "nl" => RelativeTexts {
+            now: "zojuist",
+            past: "{} geleden",
+            future: "over {}",
+            units: [
+                &[("one", "# seconde"), ("other", "# seconden")],
+                &[("one", "# minuut"), ("other", "# minuten")],
+                &[("one", "# uur"), ("other", "# uur")],
+                &[("one", "# dag"), ("other", "# dagen")],
+                &[("one", "# week"), ("other", "# weken")],
+            ],
+        },
+        "sv" => RelativeTexts {
+            now: "just nu",
+            past: "för {} sedan",
+            future: "om {}",
+            units: [
+                &[("one", "# sekund"), ("other", "# sekunder")],
+                &[("one", "# minut"), ("other", "# minuter")],
+                &[("one", "# timme"), ("other", "# timmar")],
+                &[("one", "# dag"), ("other", "# dagar")],
+                &[("one", "# vecka"), ("other", "# veckor")],
+            ],
+        },
+        // The accusative, both ways: "1 minutę temu", "za 1 minutę"
+        "pl" => RelativeTexts {
+            now: "przed chwilą",
+            past: "{} temu",
+            future: "za {}",
+            units: [
+                &[
+                    ("one", "# sekundę"),
+                    ("few", "# sekundy"),
+                    ("many", "# sekund"),
+                ],
+                &[
+                    ("one", "# minutę"),
+                    ("few", "# minuty"),
+                    ("many", "# minut"),
+                ],
+                &[
+                    ("one", "# godzinę"),
+                    ("few", "# godziny"),
+                    ("many", "# godzin"),
+                ],
+                &[("one", "# dzień"), ("few", "# dni"), ("many", "# dni")],
+                &[
+                    ("one", "# tydzień"),
+                    ("few", "# tygodnie"),
+                    ("many", "# tygodni"),
+                ],
+            ],
+        },
+        "ru" => RelativeTexts {
+            now: "только что",
+            past: "{} назад",
+            future: "через {}",
+            units: [
+                &[
+                    ("one", "# секунду"),
+                    ("few", "# секунды"),
+                    ("many", "# секунд"),
+                ],
+                &[
+                    ("one", "# минуту"),
+                    ("few", "# минуты"),
+                    ("many", "# минут"),
+                ],
+                &[("one", "# час"), ("few", "# часа"), ("many", "# часов")],
+                &[("one", "# день"), ("few", "# дня"), ("many", "# дней")],
+                &[
+                    ("one", "# неделю"),
+                    ("few", "# недели"),
+                    ("many", "# недель"),
+                ],
+            ],
+        },
+        "ja" => RelativeTexts {
+            now: "たった今",
+            past: "{}前",
+            future: "{}後",
+            units: [
+                &[("other", "#秒")],
+                &[("other", "#分")],
+                &[("other", "#時間")],
+                &[("other", "#日")],
+                &[("other", "#週間")],
+            ],
+        },
+        "zh" => RelativeTexts {
+            now: "刚刚",
+            past: "{}前",
+            future: "{}后",
+            units: [
+                &[("other", "#秒")],
+                &[("other", "#分钟")],
+                &[("other", "#小时")],
+                &[("other", "#天")],
+                &[("other", "#周")],
+            ],
+        },
+        _ => return None,
+    };
+    Some(texts)
+}
+
+/// The key of one of the texts, `now`, `minutesAgo` or `inMinutes`: a field of
+/// the `relativeTime` record with nested keys, or `relativeTimeMinutesAgo` with
+/// flat ones
+pub fn key(style: KeyStyle, name: &str) -> String {
+    match style {
+        KeyStyle::Nested => format!("relativeTime.{}", name),
+        KeyStyle::Flat => {
+            let mut chars = name.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            format!(
+                "relativeTime{}{}",
+                first.unwrap_or_default(),
+                chars.as_str()
+            )
+        }
+    }
+}
+
+/// The names `key` takes: `now`, then each unit in the past and in the future
+fn names() -> Vec<String> {
+    let mut names = vec!["now".to_string()];
+    for (unit, _) in UNITS {
+        names.push(format!("{}Ago", unit.to_lowercase()));
+    }
+    for (unit, _) in UNITS {
+        names.push(format!("in{}", unit));
+    }
+    names
+}
+
+/// The translations `relativeTime` reads, with each language's built-in texts; a
+/// language without any gets English's behind `todo_marker`, in the forms of its
+/// own plural categories
+pub fn translations(
+    languages: &[String],
+    style: KeyStyle,
+    todo_marker: &str,
+) -> Result<Vec<Translation>> {
+    let texts: Vec<(&String, RelativeTexts, &str)> = languages
+        .iter()
+        .map(|lang| match texts(lang) {
+            Some(texts) => (lang, texts, ""),
+            None => (lang, ENGLISH, todo_marker),
+        })
+        .collect();
+    let mark = |marker: &str, text: String| {
+        if marker.is_empty() {
+            text
+        } else {
+            format!("{} {}", marker, text)
+        }
+    };
+
+    let names = names();
+    let mut translations = vec![Translation {
+        key: key(style, &names[0]),
+        values: texts
+            .iter()
+            .map(|(lang, texts, marker)| (lang.to_string(), mark(marker, texts.now.to_string())))
+            .collect(),
+        is_function: false,
+        type_signature: None,
+    }];
+    for (i, name) in names[1..].iter().enumerate() {
+        let unit = i % UNITS.len();
+        let branches: Vec<(String, Vec<(Selector, String)>)> = texts
+            .iter()
+            .map(|(lang, texts, marker)| {
+                let pattern = if i < UNITS.len() {
+                    texts.past
+                } else {
+                    texts.future
+                };
+                let forms = texts.units[unit];
+                let categories = if marker.is_empty() {
+                    forms.iter().map(|(category, _)| *category).collect()
+                } else {
+                    plural::rules(lang).map_or(vec!["other"], |rules| rules.categories())
+                };
+                let branch = categories
+                    .into_iter()
+                    .map(|category| {
+                        let form = forms
+                            .iter()
+                            .find(|(c, _)| *c == category)
+                            .or(forms.last())
+                            .map_or("#", |(_, form)| form);
+                        (
+                            Selector::Category(category.to_string()),
+                            mark(marker, pattern.replace("{}", form)),
+                        )
+                    })
+                    .collect();
+                (lang.to_string(), branch)
+            })
+            .collect();
+        let (type_signature, values) = icu::compile_plural("count", &branches, false)?;
+        translations.push(Translation {
+            key: key(style, name),
+            values,
+            is_function: true,
+            type_signature: Some(type_signature),
+        });
+    }
+    Ok(translations)
+}
+
+/// `relativeTime`, between the markers, reading its texts from the translations
+/// of `languages`: from `translations lang` when the module defines it, or from
+/// each language's record
+pub fn helpers_block(languages: &[String], style: KeyStyle, translations_fn: bool) -> String {
+    let field = |name: &str| format!("texts.{}", key(style, name));
+    let texts = if translations_fn {
+        "translations lang".to_string()
+    } else {
+        let cases: Vec<String> = languages
+            .iter()
+            .map(|lang| {
+                format!(
+                    "                {} ->\n                    {}",
+                    language_variant(lang),
+                    translations_record_name(lang)
+                )
+            })
+            .collect();
+        format!("case lang of\n{}", cases.join("\n\n"))
+    };
+    let units: Vec<String> = UNITS
+        .iter()
+        .enumerate()
+        .map(|(i, (unit, seconds))| {
+            let tuple = format!(
+                "( {}, {}, {} )",
+                field(&format!("{}Ago", unit.to_lowercase())),
+                field(&format!("in{}", unit)),
+                seconds
+            );
+            match UNITS.get(i + 1) {
+                Some((_, next)) if i == 0 => {
+                    format!("if elapsed < {} then\n                {}", next, tuple)
+                }
+                Some((_, next)) => {
+                    format!("else if elapsed < {} then\n                {}", next, tuple)
+                }
+                None => format!("else\n                {}", tuple),
+            }
+        })
+        .collect();
+    format!(
+        r#"{begin}
+-- Written by `elm-i18n generate relative-time`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this. The texts are translations
+-- ({now}, {minutes_ago}…), so translators change them there.
+
+
+{{-| How long ago something happened, from the seconds since: "3 minutes ago"
+for 180, "in 3 minutes" for -180, and "just now" under 10 seconds either way
+
+Seconds count up to a minute, then minutes, hours, days and weeks, rounded down.
+
+-}}
+relativeTime : Language -> Int -> String
+relativeTime lang seconds =
+    let
+        texts =
+            {texts}
+
+        elapsed =
+            abs seconds
+
+        ( ago, ahead, unit ) =
+            {units}
+    in
+    if elapsed < 10 then
+        {now_field}
+
+    else if seconds > 0 then
+        ago {{ count = elapsed // unit }}
+
+    else
+        ahead {{ count = elapsed // unit }}
+
+
+{end}"#,
+        begin = begin_marker(BLOCK),
+        end = end_marker(BLOCK),
+        now = key(style, "now"),
+        minutes_ago = key(style, "minutesAgo"),
+        texts = texts,
+        units = units.join("\n\n            "),
+        now_field = field("now"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::upsert_block;
+    use crate::generator::add_translation_to_content;
+
+    const SOURCE: &str = "module I18n exposing (..)\n\n\ntype Language\n    = EN\n    | FR\n    | PL\n\n\ntype alias Translations =\n    { appTitle : String\n    }\n\n\ntranslationsEn : Translations\ntranslationsEn =\n    { appTitle = \"App\"\n    }\n\n\ntranslationsFr : Translations\ntranslationsFr =\n    { appTitle = \"Appli\"\n    }\n\n\ntranslationsPl : Translations\ntranslationsPl =\n    { appTitle = \"Aplikacja\"\n    }\n\n\ntranslations : Language -> Translations\ntranslations lang =\n    case lang of\n        EN ->\n            translationsEn\n\n        FR ->\n            translationsFr\n\n        PL ->\n            translationsPl\n";
+
+    #[test]
+    fn writes_the_module_the_snapshot_has() {
+        let languages: Vec<String> = ["en", "fr", "pl"].iter().map(|l| l.to_string()).collect();
+        let mut content = SOURCE.to_string();
+        for translation in translations(&languages, KeyStyle::Flat, "TODO").unwrap() {
+            content =
+                add_translation_to_content(&content, &translation, "Translations", &languages)
+                    .unwrap();
+        }
+        let block = helpers_block(&languages, KeyStyle::Flat, true);
+        let generated = upsert_block(&content, BLOCK, &block).unwrap();
+        assert_eq!(
+            generated,
+            include_str!("../tests/fixtures/relative-time/I18n.elm")
+        );
+    }
+
+    #[test]
+    fn gives_each_language_the_forms_of_its_plural_categories() {
+        for lang in [
+            "en", "fr", "de", "es", "it", "pt", "nl", "sv", "pl", "ru", "ja", "zh",
+        ] {
+            let categories = plural::rules(lang).unwrap().categories();
+            for forms in texts(lang).unwrap().units {
+                let given: Vec<&str> = forms.iter().map(|(category, _)| *category).collect();
+                assert_eq!(given, categories, "{}", lang);
+            }
+        }
+
+        // A language without texts gets English's, marked, in its own categories
+        let languages = vec!["en".to_string(), "uk".to_string()];
+        let translations = translations(&languages, KeyStyle::Nested, "TODO").unwrap();
+        assert_eq!(translations.len(), 11);
+        assert_eq!(translations[0].key, "relativeTime.now");
+        assert_eq!(translations[0].values["uk"], "TODO just now");
+        assert_eq!(translations[2].key, "relativeTime.minutesAgo");
+        assert!(translations[2].values["uk"]
+            .contains("\"TODO \" ++ String.fromInt count ++ \" minutes ago\""));
+        assert!(translations[2].values["uk"].contains("modBy 10 count"));
+        assert_eq!(translations[7].key, "relativeTime.inMinutes");
+        assert!(helpers_block(&languages, KeyStyle::Nested, false)
+            .contains("( texts.relativeTime.minutesAgo, texts.relativeTime.inMinutes, 60 )"));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::export::escape_text;
+use crate::lint::key_matches_pattern;
+use crate::revision::KeyChanges;
+
+/// `changes` without the keys matching any of `patterns` (`*` matches any characters)
+pub fn without_ignored(mut changes: KeyChanges, patterns: &[String]) -> KeyChanges {
+    let kept = |key: &String| {
+        !patterns
+            .iter()
+            .any(|pattern| key_matches_pattern(pattern, key))
+    };
+    changes.added.retain(|(key, _)| kept(key));
+    changes.changed.retain(|(key, _)| kept(key));
+    changes.removed.retain(|(key, _)| kept(key));
+    changes
+}
+
+/// A Markdown section of release notes: the keys added, changed and removed in each
+/// file between `from` and `to`, with their values in `languages`
+///
+/// Files are under a heading of their own when there are several. With one language
+/// each key is a single bullet; with more, each language is a bullet under it, and a
+/// changed key lists only the languages whose value changed.
+pub fn render_markdown(
+    from: &str,
+    to: &str,
+    files: &[(String, KeyChanges)],
+    languages: &[String],
+) -> String {
+    let mut notes = format!("## Translation changes from {} to {}\n", from, to);
+    let changed: Vec<&(String, KeyChanges)> = files
+        .iter()
+        .filter(|(_, changes)| !changes.is_empty())
+        .collect();
+    if changed.is_empty() {
+        notes.push_str("\nNo translations were added, changed or removed.\n");
+        return notes;
+    }
+
+    let one_language = languages.len() == 1;
+    let shown = |values: &std::collections::BTreeMap<String, String>| -> Vec<(String, String)> {
+        languages
+            .iter()
+            .map(|lang| {
+                (
+                    lang.clone(),
+                    quote(values.get(lang).map_or("", String::as_str)),
+                )
+            })
+            .collect()
+    };
+    for (file, changes) in changed {
+        let level = if files.len() > 1 {
+            notes.push_str(&format!("\n### {}\n", file));
+            "####"
+        } else {
+            "###"
+        };
+
+        let added: Vec<String> = changes
+            .added
+            .iter()
+            .map(|(key, values)| bullet(key, shown(values), one_language))
+            .collect();
+        let modified: Vec<String> = changes
+            .changed
+            .iter()
+            .map(|(key, values)| {
+                let values = values
+                    .iter()
+                    .map(|(lang, old, new)| {
+                        (lang.clone(), format!("{} → {}", quote(old), quote(new)))
+                    })
+                    .collect();
+                bullet(key, values, one_language)
+            })
+            .collect();
+        let removed: Vec<String> = changes
+            .removed
+            .iter()
+            .map(|(key, values)| bullet(key, shown(values), one_language))
+            .collect();
+
+        for (title, bullets) in [
+            ("Added", added),
+            ("Changed", modified),
+            ("Removed", removed),
+        ] {
+            if !bullets.is_empty() {
+                notes.push_str(&format!("\n{} {}\n\n{}", level, title, bullets.concat()));
+            }
+        }
+    }
+    notes
+}
+
+/// A bullet for `key`, with its value on the same line when only one language is
+/// shown, and a bullet per language under it otherwise
+fn bullet(key: &str, values: Vec<(String, String)>, one_language: bool) -> String {
+    match values.as_slice() {
+        [(_, value)] if one_language => format!("- `{}`: {}\n", key, value),
+        _ => {
+            let mut bullet = format!("- `{}`\n", key);
+            for (lang, value) in &values {
+                bullet.push_str(&format!("  - {}: {}\n", lang.to_uppercase(), value));
+            }
+            bullet
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    if value.is_empty() {
+        "*(empty)*".to_string()
+    } else {
+        format!("\"{}\"", escape_text(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+    use crate::revision::{file_at_revision, key_changes};
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    #[test]
+    fn writes_release_notes_between_two_tags() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .current_dir(dir.path())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+        };
+        if git(&["init", "-q"]).is_err() {
+            // No git to test with
+            return;
+        }
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/changelog");
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        for tag in ["v2.3.0", "v2.4.0"] {
+            fs::copy(
+                fixtures.join(format!("I18n.{}.elm", tag)),
+                dir.path().join("src/I18n.elm"),
+            )
+            .unwrap();
+            git(&["add", "."]).unwrap();
+            git(&["commit", "-q", "-m", tag, "--no-gpg-sign"]).unwrap();
+            git(&["tag", tag]).unwrap();
+        }
+
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let changes = |shown: &[String]| {
+            let parse = |tag: &str| {
+                let source = file_at_revision(dir.path(), tag, Path::new("src/I18n.elm")).unwrap();
+                parse_i18n_content_with_record_name(&source, "Translations", &languages).unwrap()
+            };
+            let changes = key_changes(&parse("v2.3.0"), &parse("v2.4.0"), shown);
+            vec![(
+                "src/I18n.elm".to_string(),
+                without_ignored(changes, &["debug*".to_string()]),
+            )]
+        };
+
+        assert_eq!(
+            render_markdown(
+                "v2.3.0",
+                "v2.4.0",
+                &changes(&languages[..1]),
+                &languages[..1]
+            ),
+            fs::read_to_string(fixtures.join("v2.3.0-to-v2.4.0.md")).unwrap()
+        );
+        assert_eq!(
+            render_markdown("v2.3.0", "v2.4.0", &changes(&languages), &languages),
+            fs::read_to_string(fixtures.join("v2.3.0-to-v2.4.0.all.md")).unwrap()
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::escaping::escape;
+use crate::types::ParseResult;
+
+/// A `${NAME}` in a value, with the name an environment variable can have
+fn variable() -> Regex {
+    Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap()
+}
+
+/// What `render` did to a module
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Rendered {
+    pub content: String,
+    /// Each variable that was expanded, with how many values used it
+    pub expanded: BTreeMap<String, usize>,
+    /// Each variable that has no value, with the keys and languages that use it
+    pub missing: BTreeMap<String, Vec<(String, String)>>,
+}
+
+/// `source` with each `${NAME}` inside the String literals of the language records
+/// replaced by `lookup(NAME)`, escaped for Elm
+///
+/// Only the records are touched, so a `${...}` in other code of the module stays
+/// as it is. `$${NAME}` is written as a literal `${NAME}`.
+pub fn render(
+    source: &str,
+    parse_result: &ParseResult,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Rendered {
+    let variable = variable();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_at = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+    let mut rendered = Rendered::default();
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+    for (lang, start, end) in &parse_result.lang_bounds {
+        let from = line_starts[*start];
+        let to = line_starts.get(end + 1).copied().unwrap_or(source.len());
+        let fields = &parse_result.record_fields[lang];
+        for (literal_start, literal_end) in string_literals(&source[from..to]) {
+            let literal = &source[from + literal_start..from + literal_end];
+            for captures in variable.captures_iter(literal) {
+                let whole = captures.get(0).unwrap();
+                let at = from + literal_start + whole.start();
+                if at > 0 && source[..at].ends_with('$') {
+                    replacements.push((at - 1, at, String::new()));
+                    continue;
+                }
+                let name = captures[1].to_string();
+                match lookup(&name) {
+                    Some(value) => {
+                        *rendered.expanded.entry(name).or_default() += 1;
+                        replacements.push((at, at + whole.len(), escape(&value)));
+                    }
+                    None => {
+                        let line = line_at(at);
+                        let key = fields
+                            .iter()
+                            .rev()
+                            .find(|field| field.line <= line)
+                            .map_or_else(String::new, |field| field.name.clone());
+                        let users = rendered.missing.entry(name).or_default();
+                        if !users.contains(&(key.clone(), lang.clone())) {
+                            users.push((key, lang.clone()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    replacements.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+    let mut content = source.to_string();
+    for (start, end, value) in replacements {
+        content.replace_range(start..end, &value);
+    }
+    rendered.content = content;
+    rendered
+}
+
+/// The byte ranges of the String literals' contents in `code`, skipping comments
+fn string_literals(code: &str) -> Vec<(usize, usize)> {
+    let mut literals = Vec::new();
+    let bytes = code.as_bytes();
+    let mut i = 0;
+    let mut comment_depth = 0usize;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if comment_depth > 0 {
+            if rest.starts_with(b"-}") {
+                comment_depth -= 1;
+                i += 2;
+            } else if rest.starts_with(b"{-") {
+                comment_depth += 1;
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        if rest.starts_with(b"{-") {
+            comment_depth = 1;
+            i += 2;
+        } else if rest.starts_with(b"--") {
+            i += rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        } else if rest.starts_with(b"'") {
+            // A Char literal, which can be '"'
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'\'' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else if rest.starts_with(b"\"") {
+            let quotes: &[u8] = if rest.starts_with(b"\"\"\"") {
+                b"\"\"\""
+            } else {
+                b"\""
+            };
+            let start = i + quotes.len();
+            i = start;
+            while i < bytes.len() && !bytes[i..].starts_with(quotes) {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            literals.push((start, i.min(bytes.len())));
+            i += quotes.len();
+        } else {
+            i += 1;
+        }
+    }
+    literals
+}
+
+/// The `KEY=VALUE` lines of a dotenv or secrets file; blank lines, `#` comments
+/// and an `export ` prefix are allowed, and quotes around a value are removed
+pub fn read_env_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut values = BTreeMap::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            anyhow::bail!(
+                "{}:{}: expected NAME=value, got `{}`",
+                path.display(),
+                number + 1,
+                line
+            );
+        };
+        let value = value.trim();
+        let unquoted = ['"', '\'']
+            .iter()
+            .find_map(|quote| {
+                value
+                    .strip_prefix(*quote)
+                    .and_then(|v| v.strip_suffix(*quote))
+            })
+            .unwrap_or(value);
+        values.insert(name.trim().to_string(), unquoted.to_string());
+    }
+    Ok(values)
+}
+
+/// `source` declaring `module` instead of the name it has
+pub fn rename_module(source: &str, module: &str) -> String {
+    let module_line = Regex::new(r"(?m)^((?:port\s+|effect\s+)?module\s+)[A-Z][\w.]*").unwrap();
+    module_line
+        .replace(source, |captures: &regex::Captures| {
+            format!("{}{}", &captures[1], module)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn expands_variables_in_the_records_only() {
+        let source = r#"module I18n exposing (..)
+
+
+type alias Translations =
+    { contact : String
+    , docs : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { contact = "Write to ${SUPPORT_EMAIL} -- or call"
+    , docs = "See ${DOCS_URL}, not $${HOME}"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    -- ${SUPPORT_EMAIL} in a comment stays
+    { contact = "Écrivez à ${SUPPORT_EMAIL}"
+    , docs = """Voir ${DOCS_URL}"""
+    }
+
+
+template : String
+template =
+    "${SUPPORT_EMAIL}"
+"#;
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result =
+            parse_i18n_content_with_record_name(source, "Translations", &languages).unwrap();
+        let lookup = |name: &str| (name == "SUPPORT_EMAIL").then(|| "help@\"acme\".io".to_string());
+        let rendered = render(source, &parse_result, lookup);
+
+        assert!(rendered
+            .content
+            .contains(r#"{ contact = "Write to help@\"acme\".io -- or call""#));
+        assert!(rendered
+            .content
+            .contains(r#"contact = "Écrivez à help@\"acme\".io""#));
+        assert!(rendered
+            .content
+            .contains("-- ${SUPPORT_EMAIL} in a comment stays"));
+        assert!(rendered.content.contains(r#", not ${HOME}""#));
+        assert!(rendered.content.ends_with("    \"${SUPPORT_EMAIL}\"\n"));
+        assert_eq!(rendered.expanded["SUPPORT_EMAIL"], 2);
+        assert_eq!(
+            rendered.missing["DOCS_URL"],
+            vec![
+                ("docs".to_string(), "en".to_string()),
+                ("docs".to_string(), "fr".to_string())
+            ]
+        );
+
+        assert_eq!(
+            rename_module(source, "I18n.Production").lines().next(),
+            Some("module I18n.Production exposing (..)")
+        );
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let env = dir.path().join(".env.production");
+        fs::write(
+            &env,
+            "# support\nSUPPORT_EMAIL=\"help@acme.io\"\nexport DOCS_URL=https://docs.acme.io\n\n",
+        )
+        .unwrap();
+        let values = read_env_file(&env).unwrap();
+        assert_eq!(values["SUPPORT_EMAIL"], "help@acme.io");
+        assert_eq!(values["DOCS_URL"], "https://docs.acme.io");
+    }
+}
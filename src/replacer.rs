@@ -1,5 +1,6 @@
-use crate::parser::parse_i18n_file_with_record_name;
-use anyhow::{Context, Result};
+use crate::escaping;
+use crate::parser::{parse_i18n_file_with_record_name, read_elm_file};
+use anyhow::Result;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -40,8 +41,8 @@ pub fn find_string_occurrences(
     let patterns: Vec<Regex> = search_strings
         .iter()
         .map(|s| {
-            // Escape special regex characters and create pattern for quoted strings
-            let escaped = regex::escape(s);
+            // The text as it's written in a literal, escaped for the regex
+            let escaped = regex::escape(&escaping::escape(s));
             // Match the string when it appears as a complete string literal
             Regex::new(&format!(r#""{}""#, escaped)).unwrap()
         })
@@ -56,14 +57,13 @@ pub fn find_string_occurrences(
         let path = entry.path();
 
         // Only process .elm files
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "elm") {
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "elm") {
             // Skip I18n.elm itself
-            if path.file_name().map_or(false, |name| name == "I18n.elm") {
+            if path.file_name().is_some_and(|name| name == "I18n.elm") {
                 continue;
             }
 
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+            let content = read_elm_file(path)?;
 
             // Check each line
             for (line_idx, line) in content.lines().enumerate() {
@@ -73,7 +73,7 @@ pub fn find_string_occurrences(
                 }
 
                 // Check each pattern
-                for (_pattern_idx, pattern) in patterns.iter().enumerate() {
+                for pattern in patterns.iter() {
                     for mat in pattern.find_iter(line) {
                         matches.push(StringMatch {
                             file_path: path.to_path_buf(),
@@ -105,13 +105,13 @@ pub fn replace_strings(matches: &[StringMatch], key: &str, _i18n_module: &str) -
     for mat in matches {
         files_to_update
             .entry(mat.file_path.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(mat);
     }
 
     // Process each file
     for (file_path, file_matches) in files_to_update {
-        let content = fs::read_to_string(&file_path)?;
+        let content = read_elm_file(&file_path)?;
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
         // First, analyze the file to find functions that will use t.key
@@ -299,7 +299,7 @@ fn analyze_elm_file(lines: &[String]) -> (HashMap<String, FunctionInfo>, Vec<Fun
             }
 
             // Look for any known function names in this line
-            for (func_name, _) in &function_infos {
+            for func_name in function_infos.keys() {
                 if func_name != caller && line.contains(func_name) {
                     // Verify it's actually a function call
                     if is_function_call(trimmed, func_name) {
@@ -417,7 +417,7 @@ fn propagate_translations_requirement(
 
 /// Apply all modifications to the file
 fn apply_modifications(
-    lines: &mut Vec<String>,
+    lines: &mut [String],
     matches: &[&StringMatch],
     key: &str,
     function_infos: &HashMap<String, FunctionInfo>,
@@ -466,7 +466,7 @@ fn apply_modifications(
     }
 
     // Sort modifications by line number in reverse order
-    lines_to_modify.sort_by(|a, b| b.0.cmp(&a.0));
+    lines_to_modify.sort_by_key(|b| std::cmp::Reverse(b.0));
 
     // Apply line modifications
     for (idx, new_content) in lines_to_modify {
@@ -574,7 +574,7 @@ fn add_t_to_function_call(line: &str, func_name: &str) -> String {
             if !line.contains(&format!("{} t ", func_name))
                 && !line.contains(&format!("{} t)", func_name))
             {
-                return re.replace(line, format!("$1 t $2")).to_string();
+                return re.replace(line, "$1 t $2").to_string();
             }
         }
     }
@@ -623,7 +623,7 @@ fn add_t_to_function_call(line: &str, func_name: &str) -> String {
     let standalone_pattern = format!(r"^(\s*)({})\s*$", regex::escape(func_name));
     if let Ok(re) = Regex::new(&standalone_pattern) {
         if re.is_match(line) {
-            return re.replace(line, format!("$1$2 t")).to_string();
+            return re.replace(line, "$1$2 t").to_string();
         }
     }
 
@@ -644,6 +644,30 @@ pub fn find_unused_keys(
     // Find all uses of translation keys in the codebase
     let mut used_keys = HashSet::new();
 
+    // Look for patterns like <var>.<key> where <var> is a short identifier
+    // This catches t.key, tlp.key, tcs.key, translations.key, etc.
+    // Pattern: word boundary, 1-12 char identifier, dot, then the field name
+    // Uses \p{L} for Unicode letters and \p{N} for Unicode numbers to support accented chars
+    let field_access_pattern =
+        Regex::new(r"(?u)\b[\p{L}_][\p{L}\p{N}_]{0,11}\.([\p{L}_][\p{L}\p{N}_]*)\b").unwrap();
+
+    // Also look for record field accessor functions like .fieldName
+    // In Elm, .fieldName is a function that extracts that field from a record
+    // Pattern: space/comma/etc followed by .identifier (the accessor function)
+    let accessor_pattern =
+        Regex::new(r"(?u)(?:^|[,=\[\({\s])\.([\p{L}_][\p{L}\p{N}_]*)\b").unwrap();
+
+    // Also look for field access after closing paren, like (expression).fieldName
+    // This catches patterns like (I18n.App.translations lang).keyName
+    let paren_access_pattern = Regex::new(r"(?u)\)\.([\p{L}_][\p{L}\p{N}_]*)\b").unwrap();
+
+    // Also look for extensible record type signatures like:
+    // { t | keyName : Type, keyName2 : Type, ... }
+    // This catches keys that are required by type constraints even if not accessed with dot notation
+    // We look for patterns after | in record types, and also keys followed by " : " which is
+    // common in Elm type signatures but NOT in regular assignments (which use " = ")
+    let record_type_pattern = Regex::new(r"(?u)[\|\{,]\s*([\p{L}_][\p{L}\p{N}_]*)\s*:\s*(?:String|Int|Bool|Float|List|Maybe|Dict|Array)").unwrap();
+
     // Walk through all Elm files
     for entry in WalkDir::new(src_dir)
         .into_iter()
@@ -653,22 +677,13 @@ pub fn find_unused_keys(
         let path = entry.path();
 
         // Only process .elm files
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "elm") {
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "elm") {
             // We scan ALL .elm files including I18n files themselves because:
             // 1. I18n files may use their own keys internally (e.g., error-to-string functions)
             // 2. The regex pattern only matches dot-access patterns (t.key, tlp.key)
             //    NOT record field definitions (key = "value")
 
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read file: {}", path.display()))?;
-
-            // Look for patterns like <var>.<key> where <var> is a short identifier
-            // This catches t.key, tlp.key, tcs.key, translations.key, etc.
-            // Pattern: word boundary, 1-12 char identifier, dot, then the field name
-            // Uses \p{L} for Unicode letters and \p{N} for Unicode numbers to support accented chars
-            let field_access_pattern =
-                Regex::new(r"(?u)\b[\p{L}_][\p{L}\p{N}_]{0,11}\.([\p{L}_][\p{L}\p{N}_]*)\b")
-                    .unwrap();
+            let content = read_elm_file(path)?;
 
             // Find all matches
             for captures in field_access_pattern.captures_iter(&content) {
@@ -677,35 +692,21 @@ pub fn find_unused_keys(
                 }
             }
 
-            // Also look for record field accessor functions like .fieldName
-            // In Elm, .fieldName is a function that extracts that field from a record
-            // Pattern: space/comma/etc followed by .identifier (the accessor function)
-            let accessor_pattern =
-                Regex::new(r"(?u)(?:^|[,=\[\({\s])\.([\p{L}_][\p{L}\p{N}_]*)\b").unwrap();
             for captures in accessor_pattern.captures_iter(&content) {
                 if let Some(key) = captures.get(1) {
                     used_keys.insert(key.as_str().to_string());
                 }
             }
 
-            // Also look for field access after closing paren, like (expression).fieldName
-            // This catches patterns like (I18n.App.translations lang).keyName
-            let paren_access_pattern = Regex::new(r"(?u)\)\.([\p{L}_][\p{L}\p{N}_]*)\b").unwrap();
             for captures in paren_access_pattern.captures_iter(&content) {
                 if let Some(key) = captures.get(1) {
                     used_keys.insert(key.as_str().to_string());
                 }
             }
 
-            // Also look for extensible record type signatures like:
-            // { t | keyName : Type, keyName2 : Type, ... }
-            // This catches keys that are required by type constraints even if not accessed with dot notation
-            // We look for patterns after | in record types, and also keys followed by " : " which is
-            // common in Elm type signatures but NOT in regular assignments (which use " = ")
             // Skip I18n files to avoid matching the type alias definitions themselves
-            let is_i18n_file = path.to_str().map_or(false, |s| s.contains("I18n"));
+            let is_i18n_file = path.to_str().is_some_and(|s| s.contains("I18n"));
             if !is_i18n_file {
-                let record_type_pattern = Regex::new(r"(?u)[\|\{,]\s*([\p{L}_][\p{L}\p{N}_]*)\s*:\s*(?:String|Int|Bool|Float|List|Maybe|Dict|Array)").unwrap();
                 for captures in record_type_pattern.captures_iter(&content) {
                     if let Some(key) = captures.get(1) {
                         used_keys.insert(key.as_str().to_string());
@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::lint::plain_string_value;
+use crate::types::ParseResult;
+
+/// Why a file couldn't be read at a git revision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevisionError {
+    /// git isn't installed, or the directory isn't inside a repository
+    NotARepository,
+    UnknownRevision,
+    /// The revision exists, but the path wasn't in it
+    MissingPath,
+}
+
+/// The content of `path` at `revision` of the repository `dir` is in
+///
+/// `path` is relative to `dir` (or absolute), not to the top of the repository,
+/// so this works from any directory of it.
+pub fn file_at_revision(dir: &Path, revision: &str, path: &Path) -> Result<String, RevisionError> {
+    let git = |args: &[&str]| Command::new("git").current_dir(dir).args(args).output();
+
+    let top = match git(&["rev-parse", "--show-toplevel"]) {
+        Ok(output) if output.status.success() => {
+            PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())
+        }
+        _ => return Err(RevisionError::NotARepository),
+    };
+    let commit = format!("{}^{{commit}}", revision);
+    match git(&["rev-parse", "--verify", "--quiet", &commit]) {
+        Ok(output) if output.status.success() => {}
+        _ => return Err(RevisionError::UnknownRevision),
+    }
+
+    let path = repository_path(dir, &top, path).ok_or(RevisionError::MissingPath)?;
+    let object = format!("{}:{}", revision, path);
+    match git(&["show", &object]) {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        _ => Err(RevisionError::MissingPath),
+    }
+}
+
+/// `path`, relative to `dir` unless it's absolute, as git names it from the top
+/// of the repository `top`; `None` when it's outside the repository
+fn repository_path(dir: &Path, top: &Path, path: &Path) -> Option<String> {
+    let top = top.canonicalize().ok()?;
+    let mut absolute = PathBuf::new();
+    for component in dir.canonicalize().ok()?.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            component => absolute.push(component),
+        }
+    }
+    let relative = absolute.strip_prefix(&top).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// The commit that last changed a line, as `git blame` reports it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlameLine {
+    /// All zeros for a line that isn't committed yet
+    pub commit: String,
+    pub author: String,
+    /// Seconds since the Unix epoch
+    pub author_time: u64,
+    pub summary: String,
+    pub text: String,
+}
+
+impl BlameLine {
+    pub fn is_committed(&self) -> bool {
+        self.commit.chars().any(|c| c != '0')
+    }
+}
+
+/// `git blame` of every line of `path`, in order, for the repository `dir` is in
+pub fn blame_file(dir: &Path, path: &Path) -> Result<Vec<BlameLine>, RevisionError> {
+    let git = |args: &[&str]| Command::new("git").current_dir(dir).args(args).output();
+
+    match git(&["rev-parse", "--is-inside-work-tree"]) {
+        Ok(output) if output.status.success() => {}
+        _ => return Err(RevisionError::NotARepository),
+    }
+    let path = path.to_string_lossy();
+    let output = match git(&["blame", "--porcelain", "--", &path]) {
+        Ok(output) if output.status.success() => output,
+        // Untracked files, and repositories without a commit yet
+        _ => return Err(RevisionError::MissingPath),
+    };
+    Ok(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The lines of `git blame --porcelain` output, which describes each commit only
+/// the first time one of its lines appears
+fn parse_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut commits: HashMap<String, BlameLine> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for line in output.lines() {
+        if let Some(text) = line.strip_prefix('\t') {
+            if let Some(commit) = commits.get(&current) {
+                lines.push(BlameLine {
+                    text: text.to_string(),
+                    ..commit.clone()
+                });
+            }
+            continue;
+        }
+        let (field, value) = line.split_once(' ').unwrap_or((line, ""));
+        if field.len() == 40 && field.chars().all(|c| c.is_ascii_hexdigit()) {
+            current = field.to_string();
+            commits.entry(current.clone()).or_insert_with(|| BlameLine {
+                commit: current.clone(),
+                ..BlameLine::default()
+            });
+            continue;
+        }
+        let Some(commit) = commits.get_mut(&current) else {
+            continue;
+        };
+        match field {
+            "author" => commit.author = value.to_string(),
+            "author-time" => commit.author_time = value.parse().unwrap_or(0),
+            "summary" => commit.summary = value.to_string(),
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// How the translations of a file differ between two versions of it
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeyChanges {
+    /// Keys only the new version has, with their value per language
+    pub added: Vec<(String, BTreeMap<String, String>)>,
+    /// Keys only the old version has, with their value per language
+    pub removed: Vec<(String, BTreeMap<String, String>)>,
+    /// Keys both have, with each language whose value changed: (language, old, new)
+    pub changed: Vec<(String, Vec<(String, String, String)>)>,
+}
+
+impl KeyChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare the `languages` values of two parses of a file, keys sorted
+///
+/// Values are shown as their text when they're plain strings and as written
+/// otherwise, so a change in escaping alone doesn't count as a change.
+pub fn key_changes(base: &ParseResult, current: &ParseResult, languages: &[String]) -> KeyChanges {
+    let values = |parse_result: &ParseResult, key: &str| -> BTreeMap<String, String> {
+        languages
+            .iter()
+            .filter_map(|lang| {
+                let value = parse_result.translations[key].values.get(lang)?;
+                let shown = plain_string_value(value).unwrap_or_else(|| value.trim().to_string());
+                Some((lang.clone(), shown))
+            })
+            .collect()
+    };
+
+    let mut changes = KeyChanges::default();
+    let mut keys: Vec<&String> = current.translations.keys().collect();
+    keys.sort();
+    for key in keys {
+        let now = values(current, key);
+        if !base.translations.contains_key(key) {
+            changes.added.push((key.clone(), now));
+            continue;
+        }
+        let before = values(base, key);
+        let changed: Vec<(String, String, String)> = languages
+            .iter()
+            .filter_map(|lang| {
+                let old = before.get(lang).cloned().unwrap_or_default();
+                let new = now.get(lang).cloned().unwrap_or_default();
+                (old != new).then(|| (lang.clone(), old, new))
+            })
+            .collect();
+        if !changed.is_empty() {
+            changes.changed.push((key.clone(), changed));
+        }
+    }
+
+    let mut removed: Vec<&String> = base
+        .translations
+        .keys()
+        .filter(|key| !current.translations.contains_key(*key))
+        .collect();
+    removed.sort();
+    for key in removed {
+        changes.removed.push((key.clone(), values(base, key)));
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+    use std::fs;
+
+    fn module(fields: &[(&str, &str, &str)]) -> String {
+        let mut source = String::from("module I18n exposing (..)\n\ntype alias Translations =\n");
+        for (i, (key, _, _)) in fields.iter().enumerate() {
+            let leader = if i == 0 { '{' } else { ',' };
+            source.push_str(&format!("    {} {} : String\n", leader, key));
+        }
+        source.push_str("    }\n");
+        for (n, lang) in ["En", "Fr"].iter().enumerate() {
+            source.push_str(&format!(
+                "\ntranslations{0} : Translations\ntranslations{0} =\n",
+                lang
+            ));
+            for (i, field) in fields.iter().enumerate() {
+                let leader = if i == 0 { '{' } else { ',' };
+                let value = if n == 0 { field.1 } else { field.2 };
+                source.push_str(&format!("    {} {} = \"{}\"\n", leader, field.0, value));
+            }
+            source.push_str("    }\n");
+        }
+        source
+    }
+
+    #[test]
+    fn lists_added_removed_and_changed_keys() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse = |source: &str| {
+            parse_i18n_content_with_record_name(source, "Translations", &languages).unwrap()
+        };
+        let base = parse(&module(&[
+            ("save", "Save", "Sauver"),
+            ("legacy", "Old", "Vieux"),
+            ("title", "Title", "Titre"),
+        ]));
+        let current = parse(&module(&[
+            ("save", "Save", "Enregistrer"),
+            ("title", "Title", "Titre"),
+            ("welcome", "Welcome", "Bienvenue"),
+        ]));
+
+        let changes = key_changes(&base, &current, &languages);
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0].0, "welcome");
+        assert_eq!(changes.added[0].1["fr"], "Bienvenue");
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.removed[0].0, "legacy");
+        assert_eq!(
+            changes.changed,
+            vec![(
+                "save".to_string(),
+                vec![(
+                    "fr".to_string(),
+                    "Sauver".to_string(),
+                    "Enregistrer".to_string()
+                )]
+            )]
+        );
+
+        // Only English: the French edit doesn't count
+        let english = key_changes(&base, &current, &languages[..1]);
+        assert!(english.changed.is_empty());
+        assert!(key_changes(&base, &base, &languages).is_empty());
+    }
+
+    #[test]
+    fn reads_each_commit_once_from_blame_output() {
+        let output = "\
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 1 1 2
+author Ada
+author-time 1700000000
+summary Add greeting
+filename src/I18n.elm
+\tmodule I18n exposing (..)
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 2 2
+\t
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-time 1800000000
+summary Version of src/I18n.elm from src/I18n.elm
+\t    { greeting = \"Hi\"
+";
+        let lines = parse_porcelain(output);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].author, "Ada");
+        assert_eq!(lines[1].summary, "Add greeting");
+        assert_eq!(lines[1].author_time, 1_700_000_000);
+        assert_eq!(lines[2].text, "    { greeting = \"Hi\"");
+        assert!(lines[0].is_committed());
+        assert!(!lines[2].is_committed());
+    }
+
+    #[test]
+    fn reads_a_file_at_a_revision_from_a_subdirectory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .current_dir(dir.path())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+        };
+        if git(&["init", "-q"]).is_err() {
+            // No git to test with
+            return;
+        }
+        let project = dir.path().join("frontend");
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(project.join("src/I18n.elm"), "v1").unwrap();
+        git(&["add", "."]).unwrap();
+        git(&["commit", "-q", "-m", "first", "--no-gpg-sign"]).unwrap();
+        git(&["tag", "v1"]).unwrap();
+
+        let file = Path::new("src/I18n.elm");
+        assert_eq!(file_at_revision(&project, "v1", file), Ok("v1".to_string()));
+        assert_eq!(
+            file_at_revision(&project, "v2", file),
+            Err(RevisionError::UnknownRevision)
+        );
+        assert_eq!(
+            file_at_revision(&project, "v1", Path::new("src/Moved.elm")),
+            Err(RevisionError::MissingPath)
+        );
+
+        // The same file from the top of the repository, from below the project and
+        // by its absolute path
+        assert_eq!(
+            file_at_revision(dir.path(), "v1", Path::new("frontend/src/I18n.elm")),
+            Ok("v1".to_string())
+        );
+        assert_eq!(
+            file_at_revision(&project.join("src"), "v1", Path::new("../src/I18n.elm")),
+            Ok("v1".to_string())
+        );
+        assert_eq!(
+            file_at_revision(&project.join("src"), "v1", Path::new("./I18n.elm")),
+            Ok("v1".to_string())
+        );
+        assert_eq!(
+            file_at_revision(&project.join("src"), "v1", &project.join("src/I18n.elm")),
+            Ok("v1".to_string())
+        );
+
+        let elsewhere = tempfile::TempDir::new().unwrap();
+        assert_eq!(
+            file_at_revision(elsewhere.path(), "v1", file),
+            Err(RevisionError::NotARepository)
+        );
+    }
+}
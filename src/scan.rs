@@ -0,0 +1,1079 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::discovery::path_matches_glob;
+use crate::language_edit::blank_comments;
+use crate::parser::read_elm_file;
+use crate::types::TypeField;
+
+/// Variable names taken to hold translations when the config doesn't list any
+pub const DEFAULT_VARIABLES: [&str; 2] = ["t", "translations"];
+
+/// The comment that keeps the line below it out of `scan --missing` and `extract`
+pub const IGNORE_NEXT_LINE: &str = "-- elm-i18n:ignore-next-line";
+
+/// The comment that keeps the field below it in the type alias counted as used,
+/// for keys read through a dynamic lookup
+pub const KEEP_NEXT_LINE: &str = "-- elm-i18n:keep";
+
+#[derive(Deserialize)]
+struct ElmJson {
+    #[serde(default, rename = "source-directories")]
+    source_directories: Vec<PathBuf>,
+}
+
+/// The `source-directories` of the `elm.json` in `project`, relative to it
+///
+/// None when there is no `elm.json` or it lists no source directories (packages
+/// don't), so the caller can fall back to the configured source directory.
+pub fn elm_source_directories(project: &Path) -> Result<Option<Vec<PathBuf>>> {
+    let elm_json = project.join("elm.json");
+    if !elm_json.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&elm_json)
+        .with_context(|| format!("Failed to read {}", elm_json.display()))?;
+    let parsed: ElmJson = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", elm_json.display()))?;
+    if parsed.source_directories.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        parsed
+            .source_directories
+            .into_iter()
+            .map(|dir| {
+                // `src` rather than `./src` for the current directory
+                let joined = project.join(dir);
+                match joined.strip_prefix(".") {
+                    Ok(relative) => relative.to_path_buf(),
+                    Err(_) => joined,
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// Every `.elm` file under `dirs`, except the files in `skip`, files matching an
+/// `ignore_paths` glob (e.g., `src/Generated/**`) and hidden directories
+pub fn elm_files(dirs: &[PathBuf], skip: &[PathBuf], ignore_paths: &[String]) -> Vec<PathBuf> {
+    let skip: HashSet<PathBuf> = skip.iter().filter_map(|f| f.canonicalize().ok()).collect();
+    let mut files: Vec<PathBuf> = dirs
+        .iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .into_iter()
+                .filter_entry(|e| {
+                    e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.')
+                })
+                .filter_map(|e| e.ok())
+        })
+        .map(|e| e.into_path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "elm"))
+        .filter(|path| {
+            path.canonicalize()
+                .map_or(true, |absolute| !skip.contains(&absolute))
+        })
+        .filter(|path| {
+            !ignore_paths
+                .iter()
+                .any(|glob| path_matches_glob(glob, path))
+        })
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// The Elm code `scan` and `usages` search, read once
+#[derive(Debug, Clone)]
+pub struct SourceSet {
+    pub files: Vec<SourceFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// A place where a key is read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub path: PathBuf,
+    /// 1-based line
+    pub line: usize,
+    /// The line's source, trimmed
+    pub text: String,
+}
+
+impl SourceSet {
+    /// Read every file `elm_files` finds
+    pub fn load(dirs: &[PathBuf], skip: &[PathBuf], ignore_paths: &[String]) -> Result<Self> {
+        let files = elm_files(dirs, skip, ignore_paths)
+            .into_iter()
+            .map(|path| {
+                let content = read_elm_file(&path)?;
+                Ok(SourceFile { path, content })
+            })
+            .collect::<Result<_>>()?;
+        Ok(SourceSet { files })
+    }
+
+    /// Where each of `keys` is read, as found by `field_reads`; keys nothing reads
+    /// map to an empty list
+    pub fn usages<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) -> BTreeMap<String, Vec<Usage>> {
+        let mut usages: BTreeMap<String, Vec<Usage>> = keys
+            .into_iter()
+            .map(|key| (key.clone(), Vec::new()))
+            .collect();
+        for file in &self.files {
+            let lines: Vec<&str> = file.content.lines().collect();
+            let mut reads = field_reads(&file.content);
+            reads.dedup();
+            for read in reads {
+                if let Some(found) = usages.get_mut(&read.field) {
+                    found.push(Usage {
+                        path: file.path.clone(),
+                        line: read.line,
+                        text: lines
+                            .get(read.line - 1)
+                            .map_or("", |l| l.trim())
+                            .to_string(),
+                    });
+                }
+            }
+        }
+        usages
+    }
+
+    /// Fields read off translations (see `translation_accesses`) that aren't in `keys`
+    pub fn missing(
+        &self,
+        keys: &BTreeSet<String>,
+        variables: &[String],
+        record_names: &[String],
+    ) -> Vec<(&Path, TranslationAccess)> {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                translation_accesses(&file.content, variables, record_names)
+                    .into_iter()
+                    .filter(|access| !keys.contains(&access.key))
+                    .map(|access| (file.path.as_path(), access))
+            })
+            .collect()
+    }
+}
+
+/// What renaming a key does to one source file
+#[derive(Debug)]
+pub struct RenamedFile<'a> {
+    pub path: &'a Path,
+    /// The file's source with the key's translation accesses renamed
+    pub content: String,
+    /// 1-based lines of the renamed accesses, one entry per access
+    pub renamed: Vec<usize>,
+    /// Reads of the key that aren't off translations as far as
+    /// `translation_accesses` can tell, left as they are
+    pub ambiguous: Vec<Usage>,
+}
+
+impl SourceSet {
+    /// Rename the reads of `old_key` that `translation_accesses` attributes to
+    /// translations, and list the other reads of it (`List.map .key`, `{ r | key : String }`,
+    /// fields of records it can't tell apart) so they can be checked by hand
+    ///
+    /// Files with neither are left out. A dotted `old_key` is a path into nested
+    /// records, so `checkout.legacy` renames the `t.checkout.legacy` in
+    /// `t.checkout.legacy.title`.
+    pub fn rename(
+        &self,
+        old_key: &str,
+        new_key: &str,
+        variables: &[String],
+        record_names: &[String],
+    ) -> Vec<RenamedFile<'_>> {
+        let first = old_key.split('.').next().unwrap_or(old_key);
+        let mut renamed_files = Vec::new();
+        for file in &self.files {
+            let line_starts: Vec<usize> = std::iter::once(0)
+                .chain(file.content.match_indices('\n').map(|(i, _)| i + 1))
+                .collect();
+            let reads_path = |start: usize| {
+                let rest = &file.content[start..];
+                rest.starts_with(old_key)
+                    && !rest[old_key.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            };
+            let accesses: Vec<TranslationAccess> =
+                translation_accesses(&file.content, variables, record_names)
+                    .into_iter()
+                    .filter(|access| {
+                        access.key == first
+                            && reads_path(line_starts[access.line - 1] + access.column)
+                    })
+                    .collect();
+
+            let mut content = file.content.clone();
+            for access in accesses.iter().rev() {
+                let start = line_starts[access.line - 1] + access.column;
+                content.replace_range(start..start + old_key.len(), new_key);
+            }
+
+            // A line with more reads of the key than renamed accesses has a read
+            // nothing ties to translations
+            let mut reads_per_line: BTreeMap<usize, usize> = BTreeMap::new();
+            if old_key.contains('.') {
+                let code = blank_strings(&blank_comments(&file.content));
+                let path = Regex::new(&format!(r"\.{}\b", regex::escape(old_key))).unwrap();
+                for found in path.find_iter(&code) {
+                    let line = line_starts.partition_point(|&start| start <= found.start());
+                    *reads_per_line.entry(line).or_default() += 1;
+                }
+            } else {
+                for read in field_reads(&file.content) {
+                    if read.field == old_key {
+                        *reads_per_line.entry(read.line).or_default() += 1;
+                    }
+                }
+            }
+            let lines: Vec<&str> = file.content.lines().collect();
+            let ambiguous: Vec<Usage> = reads_per_line
+                .into_iter()
+                .filter(|(line, reads)| {
+                    *reads > accesses.iter().filter(|a| a.line == *line).count()
+                })
+                .map(|(line, _)| Usage {
+                    path: file.path.clone(),
+                    line,
+                    text: lines.get(line - 1).map_or("", |l| l.trim()).to_string(),
+                })
+                .collect();
+
+            if !accesses.is_empty() || !ambiguous.is_empty() {
+                renamed_files.push(RenamedFile {
+                    path: &file.path,
+                    content,
+                    renamed: accesses.iter().map(|a| a.line).collect(),
+                    ambiguous,
+                });
+            }
+        }
+        renamed_files
+    }
+
+    /// `rename` for each of `renames` in turn, each on the sources the ones
+    /// before it left, as one renamed file per source file
+    pub fn rename_each(
+        &self,
+        renames: &[(String, String)],
+        variables: &[String],
+        record_names: &[String],
+    ) -> Vec<RenamedFile<'_>> {
+        let mut current = self.clone();
+        let mut renamed_files: Vec<RenamedFile<'_>> = Vec::new();
+        for (old_key, new_key) in renames {
+            let step: Vec<(PathBuf, String, Vec<usize>, Vec<Usage>)> = current
+                .rename(old_key, new_key, variables, record_names)
+                .into_iter()
+                .map(|r| (r.path.to_path_buf(), r.content, r.renamed, r.ambiguous))
+                .collect();
+            for (path, content, renamed, ambiguous) in step {
+                if let Some(file) = current.files.iter_mut().find(|f| f.path == path) {
+                    file.content = content.clone();
+                }
+                match renamed_files.iter_mut().find(|r| r.path == path) {
+                    Some(existing) => {
+                        existing.content = content;
+                        existing.renamed.extend(renamed);
+                        existing.ambiguous.extend(ambiguous);
+                    }
+                    None => {
+                        let Some(original) = self.files.iter().find(|f| f.path == path) else {
+                            continue;
+                        };
+                        renamed_files.push(RenamedFile {
+                            path: &original.path,
+                            content,
+                            renamed,
+                            ambiguous,
+                        });
+                    }
+                }
+            }
+        }
+        renamed_files
+    }
+}
+
+/// A record field read, and the 1-based line it's read on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRead {
+    pub line: usize,
+    pub field: String,
+}
+
+/// The record fields a piece of Elm code reads, in source order
+///
+/// A field counts as read when it is accessed on a value (`t.welcome`,
+/// `model.t.welcome`, `(translations model.lang).welcome`), used as an accessor
+/// function (`List.map .welcome`), or required by an extensible record type
+/// (`{ t | welcome : String }`). Qualified names such as `I18n.translations`
+/// aren't fields, and comments and string literals are skipped.
+pub fn field_reads(source: &str) -> Vec<FieldRead> {
+    let code = blank_strings(&blank_comments(source));
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+    let mut reads = Vec::new();
+
+    let access = Regex::new(r"(?u)\.([\p{Ll}_][\p{L}\p{N}_]*)").unwrap();
+    for captures in access.captures_iter(&code) {
+        let dot = captures.get(0).unwrap().start();
+        // The identifier segment right before the dot, if any
+        let segment_start = code[..dot]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+            .last()
+            .map_or(dot, |(i, _)| i);
+        let qualified = code[segment_start..dot]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_uppercase());
+        if !qualified {
+            reads.push(FieldRead {
+                line: line_of(dot),
+                field: captures[1].to_string(),
+            });
+        }
+    }
+
+    let extensible = Regex::new(r"(?u)\{\s*[\p{Ll}_][\p{L}\p{N}_]*\s*\|([^{}]*)\}").unwrap();
+    let field_type = Regex::new(r"(?u)([\p{Ll}_][\p{L}\p{N}_]*)\s*:").unwrap();
+    for captures in extensible.captures_iter(&code) {
+        let body = captures.get(1).unwrap();
+        for field in field_type.captures_iter(body.as_str()) {
+            reads.push(FieldRead {
+                line: line_of(body.start() + field.get(1).unwrap().start()),
+                field: field[1].to_string(),
+            });
+        }
+    }
+
+    reads.sort_by_key(|read| read.line);
+    reads
+}
+
+/// A field read off a value that holds translations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationAccess {
+    /// 1-based line of the access
+    pub line: usize,
+    /// Byte offset of the key within the line
+    pub column: usize,
+    /// What the field is read from (`t`, or `translations` for `(translations lang).key`)
+    pub variable: String,
+    pub key: String,
+}
+
+/// Fields read off values that look like translations: variables named in
+/// `variables`, variables and arguments annotated with one of `record_names`,
+/// and calls like `(I18n.translations model.lang)`, the dispatcher being in
+/// `variables` by default. Lines below an `-- elm-i18n:ignore-next-line`
+/// comment are skipped.
+///
+/// Which names hold translations is worked out for each top-level definition (see
+/// `translation_scopes`), so `other : { saveButton : String } -> String` with
+/// `other t = t.saveButton` isn't read as translations.
+pub fn translation_accesses(
+    source: &str,
+    variables: &[String],
+    record_names: &[String],
+) -> Vec<TranslationAccess> {
+    let code = blank_strings(&blank_comments(source));
+    let scopes = translation_scopes(&code, variables, record_names);
+
+    let source_lines: Vec<&str> = source.lines().collect();
+    let access = Regex::new(r"(?u)\.([\p{Ll}_][\p{L}\p{N}_]*)").unwrap();
+    let call = Regex::new(r"^\s*(?:[A-Z]\w*\.)*([\p{Ll}_][\p{L}\p{N}_]*)").unwrap();
+    let mut accesses = Vec::new();
+
+    for (i, line) in code.lines().enumerate() {
+        if i > 0 && source_lines[i - 1].trim() == IGNORE_NEXT_LINE {
+            continue;
+        }
+        let Some(scope) = scopes.iter().find(|scope| scope.lines.contains(&i)) else {
+            continue;
+        };
+        for captures in access.captures_iter(line) {
+            let dot = captures.get(0).unwrap().start();
+            let before = &line[..dot];
+            let variable = if before.ends_with(')') {
+                // `(translations lang).key`: the function the parentheses call
+                matching_paren(before)
+                    .and_then(|open| call.captures(&before[open + 1..before.len() - 1]))
+                    .map(|c| c[1].to_string())
+            } else {
+                let segment_start = before
+                    .char_indices()
+                    .rev()
+                    .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+                    .last()
+                    .map(|(i, _)| i);
+                segment_start.map(|start| before[start..].to_string())
+            };
+            if let Some(variable) = variable.filter(|v| scope.names.contains(v)) {
+                accesses.push(TranslationAccess {
+                    line: i + 1,
+                    column: captures.get(1).unwrap().start(),
+                    variable,
+                    key: captures[1].to_string(),
+                });
+            }
+        }
+    }
+    accesses
+}
+
+/// Whether `variable` holds translations at `offset` of `source`, by the rules of
+/// `translation_scopes` with `variable` taken to hold them along with `variables`
+/// unless its definition says otherwise
+pub fn holds_translations(
+    source: &str,
+    offset: usize,
+    variable: &str,
+    variables: &[String],
+    record_names: &[String],
+) -> bool {
+    let code = blank_strings(&blank_comments(source));
+    let line = code[..offset.min(code.len())].matches('\n').count();
+    let mut variables = variables.to_vec();
+    variables.push(variable.to_string());
+    translation_scopes(&code, &variables, record_names)
+        .iter()
+        .find(|scope| scope.lines.contains(&line))
+        .is_some_and(|scope| scope.names.contains(variable))
+}
+
+/// The 0-based lines of a top-level definition, with its annotation, and the
+/// names in it that hold translations
+#[derive(Debug)]
+struct Scope {
+    lines: std::ops::Range<usize>,
+    names: HashSet<String>,
+}
+
+/// The names holding translations in each top-level definition of `code`
+///
+/// Each starts from `variables`. A name annotated with one of `record_names`, as
+/// `t : Translations` in a `let` or `t` in `view : I18n.Translations -> Model -> Html msg`
+/// followed by `view t model =`, is added. A name annotated with another type, or
+/// bound by a `let` to anything but a call of one of `variables` (such as
+/// `t = model.theme`), is taken out: it's something else in that definition. An
+/// extensible record like `{ t | welcome : String }` can be either, and changes
+/// nothing.
+fn translation_scopes(code: &str, variables: &[String], record_names: &[String]) -> Vec<Scope> {
+    let lines: Vec<&str> = code.lines().collect();
+    let top_level = Regex::new(r"^([\p{Ll}_][\p{L}\p{N}_]*)\s*(:)?").unwrap();
+
+    // A definition starts at each unindented line, except its own after an annotation
+    let mut starts = vec![0];
+    let mut annotated: Option<&str> = None;
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() || line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let captures = top_level.captures(line);
+        let name = captures.as_ref().map(|c| c.get(1).unwrap().as_str());
+        if annotated.is_none() || annotated != name {
+            starts.push(i);
+        }
+        annotated = captures.filter(|c| c.get(2).is_some()).and(name);
+    }
+    starts.dedup();
+    starts.push(lines.len());
+
+    starts
+        .windows(2)
+        .map(|range| Scope {
+            lines: range[0]..range[1],
+            names: scope_names(&lines[range[0]..range[1]], variables, record_names),
+        })
+        .collect()
+}
+
+/// The names holding translations in one definition's `lines`
+fn scope_names(lines: &[&str], variables: &[String], record_names: &[String]) -> HashSet<String> {
+    let annotation = Regex::new(r"^(\s*)([\p{Ll}_][\p{L}\p{N}_]*)\s*:(.*)$").unwrap();
+    let binding = Regex::new(r"^\s+([\p{Ll}_][\p{L}\p{N}_]*)\s*=([^=].*)?$").unwrap();
+    let call = Regex::new(r"^\s*(?:[A-Z]\w*\.)*([\p{Ll}_][\p{L}\p{N}_]*)").unwrap();
+    let is_record = |text: &str| {
+        let text = text
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim();
+        let name = text.rsplit('.').next().unwrap_or(text);
+        record_names.iter().any(|record| record == name)
+            && text[..text.len() - name.len()]
+                .split('.')
+                .all(|module| module.is_empty() || module.starts_with(char::is_uppercase))
+    };
+
+    // `{ t | welcome : String }` takes the translations as well as other records
+    let extensible = Regex::new(r"^\s*\{\s*[\p{Ll}_][\p{L}\p{N}_]*\s*\|").unwrap();
+    let bind = |names: &mut HashSet<String>, name: &str, part: &str| {
+        if is_record(part) {
+            names.insert(name.to_string());
+        } else if !extensible.is_match(part) {
+            names.remove(name);
+        }
+    };
+
+    let mut names: HashSet<String> = variables.iter().cloned().collect();
+    let mut annotated = HashSet::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = annotation.captures(line) else {
+            continue;
+        };
+        let (indent, name) = (&captures[1], &captures[2]);
+        annotated.insert(name.to_string());
+        // The signature goes on over the more indented lines below it
+        let mut signature = captures[3].to_string();
+        let mut rest = lines[i + 1..].iter();
+        let definition = loop {
+            match rest.next() {
+                Some(l) if l.trim().is_empty() => continue,
+                Some(l) if l.strip_prefix(indent).is_some_and(|l| l.starts_with(name)) => {
+                    break Some(&l[indent.len() + name.len()..]);
+                }
+                Some(l) if l.len() - l.trim_start().len() > indent.len() => {
+                    signature.push(' ');
+                    signature.push_str(l.trim());
+                }
+                _ => break None,
+            }
+        };
+
+        let parts = top_level_arrows(&signature);
+        if parts.len() == 1 {
+            bind(&mut names, name, parts[0]);
+            continue;
+        }
+        let arguments = definition
+            .and_then(|rest| rest.split_once('='))
+            .map(|(arguments, _)| arguments.split_whitespace().collect::<Vec<_>>());
+        for (n, argument) in arguments.unwrap_or_default().into_iter().enumerate() {
+            let simple = argument.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && argument.starts_with(|c: char| c.is_lowercase() || c == '_');
+            if let Some(part) = parts.get(n).filter(|_| simple) {
+                bind(&mut names, argument, part);
+            }
+        }
+    }
+
+    // `let t = model.theme`: an unannotated binding of something other than the
+    // translations, the value being on the line or the ones below it
+    for (i, line) in lines.iter().enumerate() {
+        let Some(captures) = binding.captures(line) else {
+            continue;
+        };
+        let name = &captures[1];
+        if !names.contains(name) || annotated.contains(name) {
+            continue;
+        }
+        let value = captures
+            .get(2)
+            .map(|value| value.as_str())
+            .filter(|value| !value.trim().is_empty())
+            .or_else(|| {
+                lines[i + 1..]
+                    .iter()
+                    .copied()
+                    .find(|l| !l.trim().is_empty())
+            })
+            .unwrap_or("");
+        let translations = call
+            .captures(value)
+            .is_some_and(|c| variables.iter().any(|variable| *variable == c[1]));
+        if !translations {
+            names.remove(name);
+        }
+    }
+    names
+}
+
+/// A type signature split at the `->`s outside parentheses and braces
+fn top_level_arrows(signature: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in signature.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth -= 1,
+            '-' if depth == 0 && signature[i..].starts_with("->") => {
+                parts.push(&signature[start..i]);
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&signature[start..]);
+    parts
+}
+
+/// Offset of the `(` matching the `)` that `text` ends with
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Keys whose field in the type alias sits right below a `-- elm-i18n:keep` comment
+pub fn kept_keys(content: &str, type_fields: &[TypeField]) -> HashSet<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    type_fields
+        .iter()
+        .filter(|field| field.line > 0 && lines[field.line - 1].trim() == KEEP_NEXT_LINE)
+        .map(|field| field.name.clone())
+        .collect()
+}
+
+/// The key closest to a misspelled one, if it's only a couple of edits away
+pub fn closest_key<'a>(
+    key: &str,
+    keys: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a String> {
+    keys.into_iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The code with the contents of its string and character literals replaced by
+/// spaces, so text like "see example.com" isn't read as a field access
+pub fn blank_strings(code: &str) -> String {
+    let mut output = String::with_capacity(code.len());
+    let mut chars = code.char_indices();
+    let mut string: Option<&str> = None;
+
+    while let Some((i, c)) = chars.next() {
+        let rest = &code[i..];
+        match string {
+            Some(delimiter) if rest.starts_with(delimiter) => {
+                output.push_str(delimiter);
+                for _ in 1..delimiter.len() {
+                    chars.next();
+                }
+                string = None;
+            }
+            Some(_) => {
+                if c == '\\' {
+                    output.push(' ');
+                    if let Some((_, escaped)) = chars.next() {
+                        output.push_str(&" ".repeat(escaped.len_utf8()));
+                    }
+                } else if c == '\n' {
+                    output.push(c);
+                } else {
+                    output.push_str(&" ".repeat(c.len_utf8()));
+                }
+            }
+            None if rest.starts_with("\"\"\"") => {
+                string = Some("\"\"\"");
+                output.push_str("\"\"\"");
+                chars.next();
+                chars.next();
+            }
+            None if c == '"' => {
+                string = Some("\"");
+                output.push(c);
+            }
+            None if c == '\'' => {
+                // A character literal such as '.' or '\''
+                output.push(c);
+                while let Some((_, next)) = chars.next() {
+                    if next == '\'' || next == '\n' {
+                        output.push(next);
+                        break;
+                    }
+                    output.push_str(&" ".repeat(next.len_utf8()));
+                    if next == '\\' {
+                        if let Some((_, escaped)) = chars.next() {
+                            output.push_str(&" ".repeat(escaped.len_utf8()));
+                        }
+                    }
+                }
+            }
+            None => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(reads: Vec<FieldRead>) -> Vec<String> {
+        let mut fields: Vec<_> = reads.into_iter().map(|read| read.field).collect();
+        fields.sort();
+        fields.dedup();
+        fields
+    }
+
+    #[test]
+    fn finds_each_way_of_reading_a_field() {
+        let source = r#"module Page exposing (view)
+
+import I18n
+
+
+header : { t | pageTitle : String, pageSubtitle : String } -> Html msg
+header t =
+    h1 [] [ text t.pageTitle, text (String.toUpper t.pageSubtitle) ]
+
+
+view model =
+    let
+        tr =
+            (I18n.translations model.lang).buttonSave
+    in
+    -- t.commentedOut
+    div [ title "t.inString" ]
+        [ text model.t.welcome
+        , text (String.fromFloat 1.5)
+        , ul [] (List.map .itemLabel model.items)
+        , text tr
+        , text {- t.blockComment -} (String.fromChar '.')
+        ]
+"#;
+
+        assert_eq!(
+            sorted(field_reads(source)),
+            vec![
+                "buttonSave",
+                "itemLabel",
+                "items",
+                "lang",
+                "pageSubtitle",
+                "pageTitle",
+                "t",
+                "welcome"
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_fields_read_off_translations() {
+        let source = r#"module Page exposing (view)
+
+import I18n exposing (Translations)
+
+
+view : I18n.Translations -> Model -> Html msg
+view tr model =
+    let
+        shared : Translations
+        shared =
+            I18n.translations model.lang
+    in
+    div []
+        [ text tr.pageTitle
+        , text shared.pageSubtitle
+        , text model.t.welcome
+        , text (I18n.translations model.lang).buttonSave
+        -- elm-i18n:ignore-next-line
+        , text t.dynamic
+        , text model.name
+        ]
+"#;
+        let variables: Vec<String> = DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect();
+        let accesses: Vec<(usize, String, String)> =
+            translation_accesses(source, &variables, &["Translations".to_string()])
+                .into_iter()
+                .map(|a| (a.line, a.variable, a.key))
+                .collect();
+        assert_eq!(
+            accesses,
+            vec![
+                (14, "tr".to_string(), "pageTitle".to_string()),
+                (15, "shared".to_string(), "pageSubtitle".to_string()),
+                (16, "t".to_string(), "welcome".to_string()),
+                (17, "translations".to_string(), "buttonSave".to_string()),
+            ]
+        );
+
+        let keys = vec!["welcome".to_string(), "pageTitle".to_string()];
+        assert_eq!(closest_key("welcom", &keys), Some(&keys[0]));
+        assert_eq!(closest_key("buttonSave", &keys), None);
+    }
+
+    #[test]
+    fn leaves_a_t_that_is_another_record_to_be_checked_by_hand() {
+        let source = "view : Translations -> Html msg\nview t =\n    text t.saveButton\n\n\nother : { saveButton : String } -> String\nother t =\n    t.saveButton\n\n\nthemed model =\n    let\n        t =\n            model.theme\n    in\n    t.saveButton\n\n\nlocal model =\n    let\n        t = I18n.translations model.lang\n    in\n    t.saveButton\n";
+        let sources = SourceSet {
+            files: vec![SourceFile {
+                path: PathBuf::from("src/Page.elm"),
+                content: source.to_string(),
+            }],
+        };
+        let variables: Vec<String> = DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect();
+        let record_names = vec!["Translations".to_string()];
+
+        let renamed = sources.rename("saveButton", "save", &variables, &record_names);
+        assert_eq!(renamed[0].renamed, vec![3, 23]);
+        let ambiguous: Vec<usize> = renamed[0].ambiguous.iter().map(|u| u.line).collect();
+        assert_eq!(ambiguous, vec![8, 16]);
+        assert!(renamed[0].content.contains("other t =\n    t.saveButton\n"));
+
+        let holds_at = |text: &str| {
+            let offset = source.find(text).unwrap();
+            holds_translations(source, offset, "t", &variables, &record_names)
+        };
+        assert!(holds_at("text t"));
+        assert!(!holds_at("    t.saveButton\n\n\nthemed"));
+        assert!(!holds_at("model.theme"));
+        assert!(holds_at("I18n.translations"));
+    }
+
+    #[test]
+    fn renames_only_reads_off_translations() {
+        let source = "view : Translations -> Model -> Html msg\nview tr model =\n    div []\n        [ text tr.welcome, text (I18n.translations model.lang).welcome\n        , text model.user.welcome\n        , ul [] (List.map .welcome model.pages)\n        ]\n";
+        let sources = SourceSet {
+            files: vec![
+                SourceFile {
+                    path: PathBuf::from("src/Home.elm"),
+                    content: source.to_string(),
+                },
+                SourceFile {
+                    path: PathBuf::from("src/Other.elm"),
+                    content: "title t = t.pageTitle\n".to_string(),
+                },
+            ],
+        };
+        let variables: Vec<String> = DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect();
+
+        let renamed = sources.rename(
+            "welcome",
+            "greeting",
+            &variables,
+            &["Translations".to_string()],
+        );
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0].path, Path::new("src/Home.elm"));
+        assert_eq!(renamed[0].renamed, vec![4, 4]);
+        assert_eq!(
+            renamed[0].content,
+            source
+                .replace("tr.welcome", "tr.greeting")
+                .replace(").welcome", ").greeting")
+        );
+        let ambiguous: Vec<usize> = renamed[0].ambiguous.iter().map(|u| u.line).collect();
+        assert_eq!(ambiguous, vec![5, 6]);
+    }
+
+    #[test]
+    fn renames_paths_into_nested_records_and_keys_in_turn() {
+        let source =
+            "view t =\n    t.checkout.legacy.note ++ t.checkout.legacyNote ++ t.checkout.title\n";
+        let sources = SourceSet {
+            files: vec![SourceFile {
+                path: PathBuf::from("src/Home.elm"),
+                content: source.to_string(),
+            }],
+        };
+        let variables: Vec<String> = DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect();
+
+        let renamed = sources.rename("checkout.legacy", "archive.legacy", &variables, &[]);
+        assert_eq!(
+            renamed[0].content,
+            "view t =\n    t.archive.legacy.note ++ t.checkout.legacyNote ++ t.checkout.title\n"
+        );
+        assert_eq!(renamed[0].renamed, vec![2]);
+
+        let renames = [
+            ("checkout".to_string(), "payment".to_string()),
+            ("payment.title".to_string(), "payment.heading".to_string()),
+        ];
+        let renamed = sources.rename_each(&renames, &variables, &[]);
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(
+            renamed[0].content,
+            "view t =\n    t.payment.legacy.note ++ t.payment.legacyNote ++ t.payment.heading\n"
+        );
+        assert_eq!(renamed[0].renamed, vec![2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn keeps_fields_under_a_keep_comment() {
+        let content = "type alias Translations =\n    -- elm-i18n:keep\n    { errorCode404 : String\n    , welcome : String\n      -- elm-i18n:keep\n    , errorCode500 : String\n    -- elm-i18n:keep (see Errors.elm)\n    , retry : String\n    }\n";
+        let field = |name: &str, line| TypeField {
+            name: name.to_string(),
+            type_annotation: "String".to_string(),
+            line,
+            end_line: line,
+        };
+        let fields = [
+            field("errorCode404", 2),
+            field("welcome", 3),
+            field("errorCode500", 5),
+            field("retry", 7),
+        ];
+        let kept = kept_keys(content, &fields);
+        assert_eq!(
+            kept,
+            HashSet::from(["errorCode404".to_string(), "errorCode500".to_string()])
+        );
+    }
+
+    #[test]
+    fn scans_the_fixture_project() {
+        let project = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/scan-project");
+        let dirs = elm_source_directories(&project).unwrap().unwrap();
+        let i18n = project.join("src/I18n.elm");
+        let sources = SourceSet::load(&dirs, std::slice::from_ref(&i18n), &[]).unwrap();
+        let relative = |path: &Path| {
+            path.strip_prefix(&project)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+
+        let keys: BTreeSet<String> = ["appTitle", "legacyBanner", "signOut", "welcomeMessage"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let usages: Vec<(String, Vec<(String, usize)>)> = sources
+            .usages(&keys)
+            .into_iter()
+            .map(|(key, found)| {
+                let places = found.iter().map(|u| (relative(&u.path), u.line)).collect();
+                (key, places)
+            })
+            .collect();
+        assert_eq!(
+            usages,
+            vec![
+                (
+                    "appTitle".to_string(),
+                    vec![("src/Main.elm".to_string(), 15)]
+                ),
+                ("legacyBanner".to_string(), vec![]),
+                (
+                    "signOut".to_string(),
+                    vec![("src/Page/Home.elm".to_string(), 11)]
+                ),
+                ("welcomeMessage".to_string(), vec![]),
+            ]
+        );
+        assert_eq!(
+            sources.usages(&keys)["signOut"][0].text,
+            ", button [] [ text tr.signOut ]"
+        );
+
+        let variables: Vec<String> = DEFAULT_VARIABLES.iter().map(|v| v.to_string()).collect();
+        let record_names = vec!["Translations".to_string()];
+        let missing = |sources: &SourceSet| -> Vec<(String, usize, String)> {
+            sources
+                .missing(&keys, &variables, &record_names)
+                .into_iter()
+                .map(|(path, access)| (relative(path), access.line, access.key))
+                .collect()
+        };
+        assert_eq!(
+            missing(&sources),
+            vec![
+                (
+                    "src/Generated/Routes.elm".to_string(),
+                    6,
+                    "routeHome".to_string()
+                ),
+                (
+                    "src/Page/Home.elm".to_string(),
+                    10,
+                    "welcomeMesage".to_string()
+                ),
+            ]
+        );
+
+        let without_generated =
+            SourceSet::load(&dirs, &[i18n], &["**/Generated/**".to_string()]).unwrap();
+        assert_eq!(without_generated.files.len(), 2);
+        assert_eq!(
+            missing(&without_generated),
+            vec![(
+                "src/Page/Home.elm".to_string(),
+                10,
+                "welcomeMesage".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reads_source_directories_from_elm_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(elm_source_directories(dir.path()).unwrap(), None);
+
+        fs::write(
+            dir.path().join("elm.json"),
+            r#"{ "type": "application", "source-directories": ["src", "../shared"] }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            elm_source_directories(dir.path()).unwrap(),
+            Some(vec![dir.path().join("src"), dir.path().join("../shared")])
+        );
+
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/I18n.elm"), "").unwrap();
+        fs::write(dir.path().join("src/Main.elm"), "").unwrap();
+        assert_eq!(
+            elm_files(
+                &[dir.path().join("src")],
+                &[dir.path().join("src/I18n.elm")],
+                &[]
+            ),
+            vec![dir.path().join("src/Main.elm")]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::escaping::escape;
+use crate::language_edit::blank_comments;
+
+/// The variant that matches what the others don't, as in ICU's `select`
+pub const OTHER: &str = "other";
+
+/// What the argument of a select is matched against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Patterns {
+    /// String literals, with `_` for `other`
+    Strings,
+    /// The constructors of a custom type
+    Constructors(Vec<String>),
+}
+
+/// The constructors of `type name` in `source`, if it declares it; None too when a
+/// constructor takes arguments, since a variant can't say what they are
+pub fn custom_type_constructors(source: &str, name: &str) -> Option<Vec<String>> {
+    let code = blank_comments(source);
+    let declaration = Regex::new(&format!(
+        r"(?m)^type\s+{}\s*=((?:[^\n]*\n?)(?:[ \t]+[^\n]*\n?|[ \t]*\n)*)",
+        regex::escape(name)
+    ))
+    .unwrap();
+    let body = declaration.captures(&code)?.get(1)?.as_str();
+    let constructors: Vec<String> = body
+        .split('|')
+        .map(str::trim)
+        .map(|constructor| {
+            let mut words = constructor.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(name), None) if name.starts_with(char::is_uppercase) => {
+                    Some(name.to_string())
+                }
+                _ => None,
+            }
+        })
+        .collect::<Option<_>>()?;
+    (!constructors.is_empty()).then_some(constructors)
+}
+
+/// The modules `source` imports exposing the constructors of `name`, with
+/// `exposing (Name(..))` or `exposing (..)`
+pub fn modules_exposing(source: &str, name: &str) -> Vec<String> {
+    let code = blank_comments(source);
+    let import = Regex::new(r"(?m)^import\s+([A-Z][\w.]*)(?:\s+as\s+\w+)?\s+exposing\s*\(([^()]*(?:\([^()]*\)[^()]*)*)\)").unwrap();
+    let exposes = Regex::new(&format!(
+        r"(^|,)\s*({}\s*\(\s*\.\.\s*\)|\.\.)\s*(,|$)",
+        regex::escape(name)
+    ))
+    .unwrap();
+    import
+        .captures_iter(&code)
+        .filter(|captures| exposes.is_match(captures[2].trim()))
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// The Elm function type and each language's implementation for a key picking its
+/// text by `argument`, of type `arg_type`, with a `case` over `variants`
+///
+/// `values` has each language's text for every variant. With `Patterns::Strings` the
+/// variants are matched as String literals and `other` is the `_` branch, which a
+/// case on a String needs. With constructors, each variant matches the constructor
+/// it names (ignoring case) and `other` covers the constructors no variant names.
+pub fn select_function(
+    argument: &str,
+    arg_type: &str,
+    patterns: &Patterns,
+    variants: &[String],
+    values: &[(String, HashMap<String, String>)],
+) -> Result<(String, HashMap<String, String>)> {
+    let mut branches: Vec<(String, &String)> = Vec::new();
+    match patterns {
+        Patterns::Strings => {
+            if !variants.iter().any(|v| v == OTHER) {
+                bail!(
+                    "A select over Strings needs an `{}` variant for the values none of the others match",
+                    OTHER
+                );
+            }
+            for variant in variants.iter().filter(|v| *v != OTHER) {
+                branches.push((format!("\"{}\"", escape(variant)), variant));
+            }
+        }
+        Patterns::Constructors(constructors) => {
+            let mut covered = Vec::new();
+            for variant in variants.iter().filter(|v| *v != OTHER) {
+                let Some(constructor) = constructors
+                    .iter()
+                    .find(|c| c.eq_ignore_ascii_case(variant))
+                else {
+                    bail!(
+                        "`{}` isn't a constructor of {}, which has {}",
+                        variant,
+                        arg_type,
+                        constructors.join(", ")
+                    );
+                };
+                covered.push(constructor);
+                branches.push((constructor.clone(), variant));
+            }
+            let uncovered: Vec<&String> = constructors
+                .iter()
+                .filter(|c| !covered.contains(c))
+                .collect();
+            let has_other = variants.iter().any(|v| v == OTHER);
+            match (uncovered.is_empty(), has_other) {
+                (false, false) => bail!(
+                    "No variant is for {} of {}; add {} to --variants, or an `{}` variant for the rest",
+                    uncovered.iter().map(|c| format!("`{}`", c)).collect::<Vec<_>>().join(", "),
+                    arg_type,
+                    if uncovered.len() == 1 { "it" } else { "them" },
+                    OTHER
+                ),
+                (true, true) => bail!(
+                    "The `{}` variant would never be used: every constructor of {} has a variant of its own",
+                    OTHER,
+                    arg_type
+                ),
+                _ => {}
+            }
+        }
+    }
+    if variants.iter().any(|v| v == OTHER) {
+        branches.push((
+            "_".to_string(),
+            variants.iter().find(|v| *v == OTHER).unwrap(),
+        ));
+    }
+
+    let mut implementations = HashMap::new();
+    for (lang, texts) in values {
+        let mut lines = vec![
+            format!("\\{} ->", argument),
+            format!("case {} of", argument),
+        ];
+        for (i, (pattern, variant)) in branches.iter().enumerate() {
+            if i > 0 {
+                lines.push(String::new());
+            }
+            lines.push(format!("    {} ->", pattern));
+            lines.push(format!("        \"{}\"", escape(&texts[*variant])));
+        }
+        implementations.insert(lang.clone(), lines.join("\n"));
+    }
+    Ok((format!("{} -> String", arg_type), implementations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(langs: &[(&str, &[(&str, &str)])]) -> Vec<(String, HashMap<String, String>)> {
+        langs
+            .iter()
+            .map(|(lang, texts)| {
+                let texts = texts
+                    .iter()
+                    .map(|(v, t)| (v.to_string(), t.to_string()))
+                    .collect();
+                (lang.to_string(), texts)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn picks_the_text_by_constructor_or_string() {
+        let source = "module I18n exposing (..)\n\nimport Account exposing (Account, Role(..))\n\n\ntype Gender\n    = Male\n    | Female\n    -- | Unknown\n    | NonBinary\n\n\ntype Shape = Square | Circle Float\n";
+        assert_eq!(
+            custom_type_constructors(source, "Gender"),
+            Some(vec![
+                "Male".to_string(),
+                "Female".to_string(),
+                "NonBinary".to_string()
+            ])
+        );
+        assert_eq!(custom_type_constructors(source, "Shape"), None);
+        assert_eq!(custom_type_constructors(source, "Gend"), None);
+        assert_eq!(modules_exposing(source, "Role"), vec!["Account"]);
+        assert_eq!(
+            modules_exposing(
+                "module I18n exposing (..)\n\nimport Account.Role exposing (Role(..))\n",
+                "Role"
+            ),
+            vec!["Account.Role"]
+        );
+        assert!(modules_exposing(source, "Account").is_empty());
+
+        let variants: Vec<String> = ["male", "female", "other"].map(String::from).to_vec();
+        let fr = values(&[(
+            "fr",
+            &[
+                ("male", "Bienvenu"),
+                ("female", "Bienvenue"),
+                ("other", "Bienvenu·e"),
+            ],
+        )]);
+        let constructors =
+            Patterns::Constructors(custom_type_constructors(source, "Gender").unwrap());
+        let (signature, implementations) =
+            select_function("gender", "Gender", &constructors, &variants, &fr).unwrap();
+        assert_eq!(signature, "Gender -> String");
+        assert_eq!(
+            implementations["fr"],
+            "\\gender ->\ncase gender of\n    Male ->\n        \"Bienvenu\"\n\n    Female ->\n        \"Bienvenue\"\n\n    _ ->\n        \"Bienvenu·e\""
+        );
+        let (signature, implementations) =
+            select_function("gender", "String", &Patterns::Strings, &variants, &fr).unwrap();
+        assert_eq!(signature, "String -> String");
+        assert!(implementations["fr"].contains("    \"male\" ->\n        \"Bienvenu\""));
+
+        let two: Vec<String> = ["male", "female"].map(String::from).to_vec();
+        assert_eq!(
+            select_function("gender", "Gender", &constructors, &two, &fr)
+                .unwrap_err()
+                .to_string(),
+            "No variant is for `NonBinary` of Gender; add it to --variants, or an `other` variant for the rest"
+        );
+        assert!(select_function("gender", "String", &Patterns::Strings, &two, &fr).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use serde::Serialize;
+
+use crate::coverage::LanguageCoverage;
+use crate::lint::plain_string_value;
+use crate::types::ParseResult;
+use crate::validate::{value_status, ValueStatus};
+
+/// Where each language of each key stands, as `report` shows it in HTML and JSON
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub coverage: Vec<CoverageEntry>,
+    pub files: Vec<FileStatus>,
+}
+
+/// One language's coverage, as `coverage --json` and `report` print it
+#[derive(Debug, Serialize)]
+pub struct CoverageEntry {
+    pub language: String,
+    pub translated: usize,
+    pub total: usize,
+    pub percent: f64,
+}
+
+impl From<&LanguageCoverage> for CoverageEntry {
+    fn from(covered: &LanguageCoverage) -> Self {
+        CoverageEntry {
+            language: covered.language.clone(),
+            translated: covered.translated,
+            total: covered.total,
+            percent: covered.percent(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileStatus {
+    pub file: String,
+    /// In type order
+    pub keys: Vec<KeyStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyStatus {
+    pub key: String,
+    /// In the order of the configured languages
+    pub values: Vec<ValueEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueEntry {
+    pub language: String,
+    /// "translated", "missing", "empty" or "todo"
+    pub status: &'static str,
+    /// The text of a plain string, or the expression as written otherwise
+    pub text: String,
+    /// Whether `text` is an Elm expression rather than a string's text
+    pub code: bool,
+}
+
+/// The status of every key of `parse_result` in each of `languages`
+pub fn file_status(
+    file: &str,
+    parse_result: &ParseResult,
+    languages: &[String],
+    todo_marker: &str,
+) -> FileStatus {
+    let keys = parse_result
+        .type_fields
+        .iter()
+        .filter_map(|field| parse_result.translations.get(&field.name))
+        .map(|translation| KeyStatus {
+            key: translation.key.clone(),
+            values: languages
+                .iter()
+                .map(|lang| {
+                    let value = translation.values.get(lang);
+                    let status = match value_status(value, todo_marker) {
+                        ValueStatus::Translated => "translated",
+                        ValueStatus::Missing => "missing",
+                        ValueStatus::Empty => "empty",
+                        ValueStatus::Todo => "todo",
+                    };
+                    let value = value.map(|v| v.trim()).unwrap_or_default();
+                    let (text, code) = match plain_string_value(value) {
+                        Some(text) => (text, false),
+                        None => (value.to_string(), !value.is_empty()),
+                    };
+                    ValueEntry {
+                        language: lang.clone(),
+                        status,
+                        text,
+                        code,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+    FileStatus {
+        file: file.to_string(),
+        keys,
+    }
+}
+
+/// The report as a single HTML page, with its styles and the filtering script
+/// inlined so it can be mailed or kept as a CI artifact
+pub fn render_html(report: &StatusReport) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Translation status</title>\n");
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str("<h1>Translation status</h1>\n");
+
+    html.push_str("<section class=\"coverage\">\n");
+    for entry in &report.coverage {
+        html.push_str(&format!(
+            "<div class=\"language\"><span class=\"code\">{}</span> <strong>{:.1}%</strong> <span class=\"count\">{}/{}</span><div class=\"bar\"><div style=\"width: {:.1}%\"></div></div></div>\n",
+            escape(&entry.language.to_uppercase()),
+            entry.percent,
+            entry.translated,
+            entry.total,
+            entry.percent
+        ));
+    }
+    html.push_str("</section>\n");
+
+    html.push_str("<form class=\"filters\" onsubmit=\"return false\">\n");
+    html.push_str("<input id=\"prefix\" type=\"search\" placeholder=\"Key prefix\">\n");
+    html.push_str("<select id=\"language\">\n<option value=\"\">All languages</option>\n");
+    for entry in &report.coverage {
+        html.push_str(&format!(
+            "<option value=\"{0}\">{1}</option>\n",
+            escape(&entry.language),
+            escape(&entry.language.to_uppercase())
+        ));
+    }
+    html.push_str("</select>\n");
+    html.push_str(
+        "<label><input id=\"untranslated\" type=\"checkbox\"> Only untranslated</label>\n",
+    );
+    html.push_str("</form>\n");
+
+    for file in &report.files {
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n<thead>\n<tr><th>Key</th>",
+            escape(&file.file)
+        ));
+        for entry in &report.coverage {
+            html.push_str(&format!(
+                "<th data-lang=\"{}\">{}</th>",
+                escape(&entry.language),
+                escape(&entry.language.to_uppercase())
+            ));
+        }
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+        for key in &file.keys {
+            html.push_str(&format!(
+                "<tr data-key=\"{0}\"><td class=\"key\">{0}</td>",
+                escape(&key.key)
+            ));
+            for value in &key.values {
+                let text = if value.code {
+                    format!("<code>{}</code>", escape(&value.text))
+                } else {
+                    escape(&value.text)
+                };
+                html.push_str(&format!(
+                    "<td data-lang=\"{}\" class=\"{}\">{}</td>",
+                    escape(&value.language),
+                    value.status,
+                    text
+                ));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    html.push_str(&format!(
+        "<script>\n{}</script>\n</body>\n</html>\n",
+        SCRIPT
+    ));
+    html
+}
+
+/// Text safe in HTML content and quoted attributes
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = r#"body { font-family: -apple-system, "Segoe UI", sans-serif; margin: 2rem; color: #222; }
+.coverage { display: flex; flex-wrap: wrap; gap: 1rem; margin-bottom: 1.5rem; }
+.language { border: 1px solid #ddd; border-radius: 6px; padding: 0.6rem 0.9rem; min-width: 9rem; }
+.language .code { font-weight: 600; }
+.language .count { color: #777; font-size: 0.85em; }
+.bar { background: #eee; border-radius: 3px; height: 6px; margin-top: 0.4rem; }
+.bar div { background: #3a9d5d; border-radius: 3px; height: 100%; }
+.filters { display: flex; gap: 1rem; align-items: center; margin-bottom: 1rem; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2rem; }
+th, td { border: 1px solid #e3e3e3; padding: 0.35rem 0.6rem; text-align: left; vertical-align: top; }
+th { background: #f6f6f6; }
+td.key { font-family: monospace; white-space: nowrap; }
+td.missing, td.empty { background: #fde2e1; }
+td.todo { background: #fff4cc; }
+code { white-space: pre-wrap; }
+"#;
+
+const SCRIPT: &str = r#"const prefix = document.getElementById("prefix");
+const language = document.getElementById("language");
+const untranslated = document.getElementById("untranslated");
+
+function filter() {
+  const lang = language.value;
+  document.querySelectorAll("[data-lang]").forEach((cell) => {
+    cell.hidden = lang !== "" && cell.dataset.lang !== lang;
+  });
+  document.querySelectorAll("tr[data-key]").forEach((row) => {
+    const cells = [...row.querySelectorAll("td[data-lang]")].filter((cell) => !cell.hidden);
+    const open = cells.some((cell) => cell.className !== "translated");
+    row.hidden = !row.dataset.key.startsWith(prefix.value) || (untranslated.checked && !open);
+  });
+}
+
+[prefix, language, untranslated].forEach((input) => input.addEventListener("input", filter));
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coverage::coverage;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/report/I18n.elm");
+
+    #[test]
+    fn renders_the_report_page() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result =
+            parse_i18n_content_with_record_name(FIXTURE, "Translations", &languages).unwrap();
+        let report = StatusReport {
+            coverage: coverage(&[&parse_result], &languages, "TODO")
+                .iter()
+                .map(CoverageEntry::from)
+                .collect(),
+            files: vec![file_status(
+                "src/I18n.elm",
+                &parse_result,
+                &languages,
+                "TODO",
+            )],
+        };
+
+        assert_eq!(
+            render_html(&report),
+            include_str!("../tests/fixtures/report/report.html")
+        );
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["coverage"][1]["percent"], 25.0);
+        assert_eq!(json["files"][0]["keys"][1]["values"][1]["status"], "todo");
+    }
+}
@@ -0,0 +1,52 @@
+use colored::Colorize;
+use serde::Serialize;
+use std::fmt;
+
+/// What a bulk command did to the keys, for the one line `scaffold`, `prune` and
+/// `modify-bulk` end with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub skipped: usize,
+}
+
+impl Summary {
+    /// Print the summary line
+    pub fn print(&self) {
+        println!("{} {}", "Summary:".bold(), self);
+    }
+}
+
+/// `+12 -3 ~5 skipped 2`, every count shown so logs can be grepped alike
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "+{} -{} ~{} skipped {}",
+            self.added, self.removed, self.changed, self.skipped
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_every_count_on_one_line() {
+        let summary = Summary {
+            added: 12,
+            removed: 3,
+            changed: 5,
+            skipped: 2,
+        };
+        assert_eq!(summary.to_string(), "+12 -3 ~5 skipped 2");
+        assert_eq!(Summary::default().to_string(), "+0 -0 ~0 skipped 0");
+        assert_eq!(
+            serde_json::to_value(summary).unwrap(),
+            serde_json::json!({ "added": 12, "removed": 3, "changed": 5, "skipped": 2 })
+        );
+    }
+}
@@ -1,3 +1,82 @@
+use anyhow::Result;
+use regex::{Captures, Regex};
+
+use crate::generator::{indent_width, DEFAULT_INDENT};
+use crate::languages::{language_variant, translations_record_name};
+
+/// A user's `init --template` filled in for `languages`
+///
+/// `{{recordName}}`, `{{languages}}` (e.g. "en, fr") and `{{defaultLanguage}}` are
+/// replaced anywhere. The text between `{{#each language}}` and `{{/each}}` is
+/// repeated for each language, with `{{code}}` ("pt-BR"), `{{variant}}` ("PT_BR"),
+/// `{{record}}` ("translationsPtBR") and `{{separator}}` (`=` for the first
+/// language and `|` after, for the `type Language` variants). A block tag alone
+/// on its line takes the line with it. Any other `{{name}}` is an error.
+pub fn render_template(template: &str, languages: &[String], record_name: &str) -> Result<String> {
+    let global = |name: &str| match name {
+        "recordName" => Some(record_name.to_string()),
+        "languages" => Some(languages.join(", ")),
+        "defaultLanguage" => languages.first().cloned(),
+        _ => None,
+    };
+
+    // Standalone tags first, so their lines go; then tags within a line
+    let blocks = [
+        r"(?ms)^[ \t]*\{\{#each language\}\}[ \t]*\n(.*?)^[ \t]*\{\{/each\}\}[ \t]*(?:\n|\z)",
+        r"(?s)\{\{#each language\}\}(.*?)\{\{/each\}\}",
+    ];
+    let mut rendered = template.to_string();
+    for block in blocks {
+        let block = Regex::new(block).unwrap();
+        let mut error = None;
+        rendered = block
+            .replace_all(&rendered, |captures: &Captures| {
+                let mut repeated = String::new();
+                for (i, lang) in languages.iter().enumerate() {
+                    let per_language = |name: &str| match name {
+                        "code" => Some(lang.clone()),
+                        "variant" => Some(language_variant(lang)),
+                        "record" => Some(translations_record_name(lang)),
+                        "separator" => Some(if i == 0 { "=" } else { "|" }.to_string()),
+                        _ => global(name),
+                    };
+                    match fill(&captures[1], per_language) {
+                        Ok(text) => repeated.push_str(&text),
+                        Err(e) => error = Some(e),
+                    }
+                }
+                repeated
+            })
+            .into_owned();
+        if let Some(e) = error {
+            return Err(e);
+        }
+    }
+    if rendered.contains("{{#each") || rendered.contains("{{/each}}") {
+        anyhow::bail!("The template has a {{{{#each language}}}} without a matching {{{{/each}}}}");
+    }
+    fill(&rendered, global)
+}
+
+/// `text` with each `{{name}}` replaced by `value(name)`
+fn fill(text: &str, value: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let placeholder = Regex::new(r"\{\{\s*([A-Za-z]+)\s*\}\}").unwrap();
+    let mut unknown = None;
+    let filled = placeholder.replace_all(text, |captures: &Captures| {
+        value(&captures[1]).unwrap_or_else(|| {
+            unknown.get_or_insert_with(|| captures[1].to_string());
+            String::new()
+        })
+    });
+    match unknown {
+        Some(name) => anyhow::bail!(
+            "Unknown placeholder {{{{{}}}}} in the template (known: recordName, languages, defaultLanguage, and code, variant, record and separator inside {{{{#each language}}}})",
+            name
+        ),
+        None => Ok(filled.into_owned()),
+    }
+}
+
 pub fn get_i18n_template_with_record_name(languages: &[String], record_name: &str) -> String {
     let mut template = String::from(
         r#"module I18n exposing (..)
@@ -20,9 +99,9 @@ type Language
     } else {
         for (i, lang) in languages.iter().enumerate() {
             if i == 0 {
-                template.push_str(&format!("    = {}\n", lang.to_uppercase()));
+                template.push_str(&format!("    = {}\n", language_variant(lang)));
             } else {
-                template.push_str(&format!("    | {}\n", lang.to_uppercase()));
+                template.push_str(&format!("    | {}\n", language_variant(lang)));
             }
         }
     }
@@ -55,8 +134,8 @@ type alias {} =
 
     for lang in &langs {
         template.push_str(&format!(
-            r#"translations{} : {}
-translations{} =
+            r#"{} : {}
+{} =
     {{ appTitle = "{}"
     , appName = "My App"
     , welcome = "{}"
@@ -65,9 +144,9 @@ translations{} =
 
 
 "#,
-            capitalize_first(lang),
+            translations_record_name(lang),
             record_name,
-            capitalize_first(lang),
+            translations_record_name(lang),
             get_default_title(lang),
             get_default_welcome(lang),
             get_default_loading(lang),
@@ -75,19 +154,19 @@ translations{} =
     }
 
     // Add helper functions
-    template.push_str(&format!(
-        r#"{{-| Convert Language to String for storage
--}}
+    template.push_str(
+        r#"{-| Convert Language to String for storage
+-}
 languageToString : Language -> String
 languageToString lang =
     case lang of
-"#
-    ));
+"#,
+    );
 
     for lang in &langs {
         template.push_str(&format!(
             "        {} ->\n            \"{}\"\n\n",
-            lang.to_uppercase(),
+            language_variant(lang),
             lang
         ));
     }
@@ -101,20 +180,20 @@ stringToLanguage : String -> Language
 stringToLanguage str =
     case str of
 "#,
-        langs[0].to_uppercase()
+        language_variant(&langs[0])
     ));
 
     for lang in &langs[1..] {
         template.push_str(&format!(
             "        \"{}\" ->\n            {}\n\n",
             lang,
-            lang.to_uppercase()
+            language_variant(lang)
         ));
     }
 
     template.push_str(&format!(
         "        _ ->\n            {}\n\n\n",
-        langs[0].to_uppercase()
+        language_variant(&langs[0])
     ));
 
     // Add translations function
@@ -130,21 +209,28 @@ translations lang =
 
     for lang in &langs {
         template.push_str(&format!(
-            "        {} ->\n            translations{}\n\n",
-            lang.to_uppercase(),
-            capitalize_first(lang)
+            "        {} ->\n            {}\n\n",
+            language_variant(lang),
+            translations_record_name(lang)
         ));
     }
 
-    template
+    reindent(&template, indent_width())
 }
 
-fn capitalize_first(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
-    }
+/// `text` with each step of `DEFAULT_INDENT` leading spaces made `width` spaces
+fn reindent(text: &str, width: usize) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let spaces = line.len() - line.trim_start_matches(' ').len();
+            let steps = spaces / DEFAULT_INDENT;
+            format!(
+                "{}{}",
+                " ".repeat(steps * width + spaces % DEFAULT_INDENT),
+                &line[spaces..]
+            )
+        })
+        .collect()
 }
 
 fn get_default_title(lang: &str) -> &'static str {
@@ -173,3 +259,61 @@ fn get_default_loading(lang: &str) -> &'static str {
         _ => "Loading...",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    #[test]
+    fn fills_a_template_for_each_language() {
+        let template = r#"module I18n exposing (..)
+
+-- Languages: {{languages}}, falling back to {{ defaultLanguage }}
+
+
+type Language
+{{#each language}}
+    {{separator}} {{variant}}
+{{/each}}
+
+
+type alias {{recordName}} =
+    { save : String
+    }
+
+{{#each language}}
+
+{{record}} : {{recordName}}
+{{record}} =
+    { save = "TODO ({{code}})"
+    }
+{{/each}}
+
+
+languageCodes : List String
+languageCodes =
+    [ {{#each language}}"{{code}}", {{/each}}]
+"#;
+        let languages = vec!["en".to_string(), "pt-BR".to_string()];
+        let rendered = render_template(template, &languages, "Messages").unwrap();
+
+        assert!(rendered.contains("-- Languages: en, pt-BR, falling back to en\n"));
+        assert!(
+            rendered.contains("type Language\n    = EN\n    | PT_BR\n\n\ntype alias Messages =")
+        );
+        assert!(rendered.contains(
+            "\n\ntranslationsPtBR : Messages\ntranslationsPtBR =\n    { save = \"TODO (pt-BR)\"\n    }\n"
+        ));
+        assert!(rendered.contains(r#"[ "en", "pt-BR", ]"#));
+        let parsed =
+            parse_i18n_content_with_record_name(&rendered, "Messages", &languages).unwrap();
+        assert_eq!(parsed.translations.len(), 1);
+
+        let typo = render_template("{{recordname}}", &languages, "Messages").unwrap_err();
+        assert!(typo
+            .to_string()
+            .starts_with("Unknown placeholder {{recordname}}"));
+        assert!(render_template("{{#each language}}{{code}}", &languages, "Messages").is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use regex::Regex;
+use std::path::PathBuf;
+
+use crate::languages::{language_variant, translations_record_name};
+use crate::types::ParseResult;
+
+/// Read the module name from `module Foo.Bar exposing (..)`
+pub fn module_name(source: &str) -> Option<String> {
+    let module_regex = Regex::new(r"^(?:port\s+)?module\s+([A-Z][\w.]*)").unwrap();
+    source
+        .lines()
+        .find_map(|line| module_regex.captures(line).map(|c| c[1].to_string()))
+}
+
+/// Where the test for a module goes by default: `Feature.I18n` → `tests/Feature/I18nTest.elm`
+pub fn default_test_path(module: &str) -> PathBuf {
+    PathBuf::from(format!("tests/{}Test.elm", module.replace('.', "/")))
+}
+
+/// The module name for an Elm file under `tests/` (e.g., `tests/Feature/I18nTest.elm`)
+pub fn test_module_name(path: &std::path::Path) -> String {
+    let relative = path.strip_prefix("tests").unwrap_or(path);
+    relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Constructors of `type Language` declared in the module, in order
+pub fn language_variants(source: &str) -> Vec<String> {
+    let mut variants = Vec::new();
+    let mut in_type = false;
+    let constructor = Regex::new(r"^\s*[=|]\s*([A-Z]\w*)").unwrap();
+
+    for line in source.lines() {
+        if line.starts_with("type Language") {
+            in_type = true;
+            // `type Language = EN | FR` on a single line
+            if let Some((_, rest)) = line.split_once('=') {
+                variants.extend(
+                    rest.split('|')
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty()),
+                );
+                break;
+            }
+            continue;
+        }
+        if in_type {
+            match constructor.captures(line) {
+                Some(captures) => variants.push(captures[1].to_string()),
+                None if line.trim().is_empty() => continue,
+                None => break,
+            }
+        }
+    }
+
+    variants
+}
+
+/// Generate an elm-test module that touches every translation in every language
+///
+/// Every field is bound with its declared type, so `elm make`/`elm-test` fails to
+/// compile when a record misses a field or gives it the wrong type, and the test
+/// fails when a plain string is empty.
+pub fn generate_test_module(
+    source: &str,
+    parse_result: &ParseResult,
+    i18n_module: &str,
+    test_module: &str,
+    record_name: &str,
+    languages: &[String],
+) -> String {
+    let mut output = format!(
+        "module {} exposing (suite)
+
+{{-| Generated by `elm-i18n gen-test` from {}. Run it again after changing the
+translations instead of editing this file by hand.
+-}}
+
+import Expect
+import {} exposing (..)
+import Test exposing (Test)
+",
+        test_module, i18n_module, i18n_module
+    );
+
+    // Field types may mention types the I18n module imports
+    for line in source.lines().filter(|l| l.starts_with("import ")) {
+        let imported = line.split_whitespace().nth(1).unwrap_or("");
+        if !["Expect", "Test", i18n_module].contains(&imported) {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    // Prefer `translations lang` when the module defines it; feature modules may
+    // only have the per-language records
+    let variants = language_variants(source);
+    let has_translations_fn = source
+        .lines()
+        .any(|l| l.starts_with("translations : Language ->"));
+    let cases: Vec<(String, String)> = if has_translations_fn && !variants.is_empty() {
+        variants
+            .iter()
+            .map(|v| (v.clone(), format!("translations {}", v)))
+            .collect()
+    } else {
+        languages
+            .iter()
+            .map(|lang| (language_variant(lang), translations_record_name(lang)))
+            .collect()
+    };
+
+    output.push_str(&format!(
+        "
+
+suite : Test
+suite =
+    Test.describe \"{}\"
+",
+        i18n_module
+    ));
+    for (i, (name, expression)) in cases.iter().enumerate() {
+        output.push_str(&format!(
+            "        {} Test.test \"{} translations are complete\" <|
+            \\_ ->
+                checkTranslations ({})
+",
+            if i == 0 { "[" } else { "," },
+            name,
+            expression
+        ));
+    }
+    output.push_str("        ]\n");
+
+    output.push_str(&format!(
+        "
+
+checkTranslations : {} -> Expect.Expectation
+checkTranslations t =
+    let
+",
+        record_name
+    ));
+    for field in &parse_result.type_fields {
+        output.push_str(&format!(
+            "        {name}_ : {annotation}
+        {name}_ =
+            t.{name}
+
+",
+            name = field.name,
+            annotation = field.type_annotation
+        ));
+    }
+    output.truncate(output.trim_end().len());
+    output.push_str("\n    in\n");
+
+    let strings: Vec<String> = parse_result
+        .type_fields
+        .iter()
+        .filter(|f| f.type_annotation == "String")
+        .map(|f| format!("( \"{name}\", {name}_ )", name = f.name))
+        .collect();
+    if strings.is_empty() {
+        output.push_str("    Expect.pass\n");
+    } else {
+        for (i, entry) in strings.iter().enumerate() {
+            output.push_str(&format!(
+                "    {} {}\n",
+                if i == 0 { "[" } else { "," },
+                entry
+            ));
+        }
+        output.push_str(
+            "    ]
+        |> List.filter (Tuple.second >> String.isEmpty)
+        |> List.map Tuple.first
+        |> Expect.equal []
+",
+        );
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_i18n_content_with_record_name;
+
+    const SOURCE: &str = "module I18n exposing (..)
+
+import Ticket
+
+
+type Language
+    = EN
+    | FR
+
+
+type alias Translations =
+    { save : String
+    , status : Ticket.Status -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = \"Save\"
+    , status = \\_ -> \"Status\"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = \"Enregistrer\"
+    , status = \\_ -> \"Statut\"
+    }
+
+
+translations : Language -> Translations
+translations lang =
+    case lang of
+        EN ->
+            translationsEn
+
+        FR ->
+            translationsFr
+";
+
+    #[test]
+    fn generates_a_test_touching_every_field() {
+        let languages = vec!["en".to_string(), "fr".to_string()];
+        let parse_result =
+            parse_i18n_content_with_record_name(SOURCE, "Translations", &languages).unwrap();
+
+        let module = generate_test_module(
+            SOURCE,
+            &parse_result,
+            "I18n",
+            "I18nTest",
+            "Translations",
+            &languages,
+        );
+
+        assert_eq!(
+            module,
+            "module I18nTest exposing (suite)
+
+{-| Generated by `elm-i18n gen-test` from I18n. Run it again after changing the
+translations instead of editing this file by hand.
+-}
+
+import Expect
+import I18n exposing (..)
+import Test exposing (Test)
+import Ticket
+
+
+suite : Test
+suite =
+    Test.describe \"I18n\"
+        [ Test.test \"EN translations are complete\" <|
+            \\_ ->
+                checkTranslations (translations EN)
+        , Test.test \"FR translations are complete\" <|
+            \\_ ->
+                checkTranslations (translations FR)
+        ]
+
+
+checkTranslations : Translations -> Expect.Expectation
+checkTranslations t =
+    let
+        save_ : String
+        save_ =
+            t.save
+
+        status_ : Ticket.Status -> String
+        status_ =
+            t.status
+    in
+    [ ( \"save\", save_ )
+    ]
+        |> List.filter (Tuple.second >> String.isEmpty)
+        |> List.map Tuple.first
+        |> Expect.equal []
+"
+        );
+    }
+
+    #[test]
+    fn derives_module_names_and_paths() {
+        assert_eq!(module_name(SOURCE), Some("I18n".to_string()));
+        assert_eq!(
+            default_test_path("Feature.I18n"),
+            PathBuf::from("tests/Feature/I18nTest.elm")
+        );
+        assert_eq!(
+            test_module_name(std::path::Path::new("tests/Feature/I18nTest.elm")),
+            "Feature.I18nTest"
+        );
+        assert_eq!(
+            language_variants("type Language = EN | FR\n"),
+            vec!["EN", "FR"]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// A kind of work `--timing` reports separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading files from disk
+    Read,
+    /// Parsing an I18n module
+    Parse,
+    /// Changing a module's source in memory
+    Edit,
+    /// Writing files to disk
+    Write,
+}
+
+const PHASES: [Phase; 4] = [Phase::Read, Phase::Parse, Phase::Edit, Phase::Write];
+
+impl Phase {
+    fn label(self) -> &'static str {
+        match self {
+            Phase::Read => "read",
+            Phase::Parse => "parse",
+            Phase::Edit => "edit",
+            Phase::Write => "write",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Timer {
+    started: Option<Instant>,
+    /// Time and calls per phase, in `PHASES` order
+    totals: [(Duration, usize); 4],
+    /// Time spent in phases nested inside each running phase
+    nested: Vec<Duration>,
+}
+
+thread_local! {
+    static TIMER: RefCell<Timer> = RefCell::new(Timer::default());
+}
+
+/// Start collecting timings; until this is called, `phase` records nothing
+pub fn enable() {
+    TIMER.with(|timer| timer.borrow_mut().started = Some(Instant::now()));
+}
+
+/// Count the time until the returned guard is dropped towards `phase`
+///
+/// Time spent in a phase started while this one runs (like the parse inside an
+/// edit) counts towards that phase only, so the phases add up to the time measured.
+pub fn phase(phase: Phase) -> PhaseGuard {
+    let enabled = TIMER.with(|timer| {
+        let mut timer = timer.borrow_mut();
+        if timer.started.is_some() {
+            timer.nested.push(Duration::ZERO);
+        }
+        timer.started.is_some()
+    });
+    PhaseGuard {
+        phase,
+        start: enabled.then(Instant::now),
+    }
+}
+
+/// Returned by `phase`; records the phase's time when dropped
+pub struct PhaseGuard {
+    phase: Phase,
+    /// None when timing isn't enabled
+    start: Option<Instant>,
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let elapsed = start.elapsed();
+        TIMER.with(|timer| {
+            let mut timer = timer.borrow_mut();
+            let nested = timer.nested.pop().unwrap_or_default();
+            if let Some(parent) = timer.nested.last_mut() {
+                *parent += elapsed;
+            }
+            let index = PHASES.iter().position(|p| *p == self.phase).unwrap();
+            let (time, calls) = &mut timer.totals[index];
+            *time += elapsed.saturating_sub(nested);
+            *calls += 1;
+        });
+    }
+}
+
+/// `fs::write`, timed as `Phase::Write`
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let _write = phase(Phase::Write);
+    fs::write(path, contents)
+}
+
+/// The collected timings, or None when `enable` wasn't called
+pub fn report() -> Option<String> {
+    TIMER.with(|timer| {
+        let timer = timer.borrow();
+        let total = timer.started?.elapsed();
+        let mut lines = vec![format!("Timing (total {})", format_duration(total))];
+        let mut measured = Duration::ZERO;
+        for (phase, (time, calls)) in PHASES.iter().zip(timer.totals) {
+            if calls == 0 {
+                continue;
+            }
+            measured += time;
+            lines.push(format!(
+                "  {:<6} {:>10}  ({}×)",
+                phase.label(),
+                format_duration(time),
+                calls
+            ));
+        }
+        lines.push(format!(
+            "  {:<6} {:>10}",
+            "other",
+            format_duration(total.saturating_sub(measured))
+        ));
+        Some(lines.join("\n"))
+    })
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_nested_phases_once() {
+        drop(phase(Phase::Edit));
+        assert!(report().is_none());
+
+        enable();
+        {
+            let _edit = phase(Phase::Edit);
+            let _parse = phase(Phase::Parse);
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let totals = TIMER.with(|timer| timer.borrow().totals);
+        let (parse, edit) = (totals[1], totals[2]);
+        assert_eq!((parse.1, edit.1), (1, 1));
+        assert!(parse.0 >= Duration::from_millis(20));
+        assert!(edit.0 < Duration::from_millis(20));
+
+        let report = report().unwrap();
+        assert!(report.contains("  parse "), "{}", report);
+        assert!(!report.contains("write"), "{}", report);
+    }
+}
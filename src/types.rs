@@ -7,6 +7,8 @@ pub struct Translation {
     pub key: String,
     pub values: HashMap<String, String>,
     pub is_function: bool,
+    /// The field's type unless it is `String`; such values are Elm expressions
+    /// and are written without quoting
     pub type_signature: Option<String>,
 }
 
@@ -15,6 +17,10 @@ pub struct Translation {
 pub struct TypeField {
     pub name: String,
     pub type_annotation: String,
+    /// 0-based line of the field in the file
+    pub line: usize,
+    /// 0-based line where its type ends
+    pub end_line: usize,
 }
 
 /// Represents a field in a record
@@ -22,6 +28,10 @@ pub struct TypeField {
 pub struct RecordField {
     pub name: String,
     pub value: String,
+    /// 0-based line where the field starts
+    pub line: usize,
+    /// 0-based line where its value ends
+    pub end_line: usize,
 }
 
 /// Result of parsing an I18n file
@@ -32,4 +42,8 @@ pub struct ParseResult {
     /// (lang_code, start_line, end_line) for each language record
     pub lang_bounds: Vec<(String, usize, usize)>,
     pub translations: HashMap<String, Translation>,
+    /// Fields of the type alias, in source order
+    pub type_fields: Vec<TypeField>,
+    /// Fields of each language record, in source order
+    pub record_fields: HashMap<String, Vec<RecordField>>,
 }
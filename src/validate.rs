@@ -0,0 +1,943 @@
+use regex::Regex;
+use std::collections::BTreeSet;
+
+use crate::config::PlaceholderStyle;
+use crate::lint::{plain_string_value, string_literal_contents};
+use crate::parser::function_arity;
+use crate::placeholder::record_pattern_fields;
+use crate::scan::blank_strings;
+use crate::types::{ParseResult, Translation};
+
+/// A problem reported by `validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub key: String,
+    pub language: Option<String>,
+    pub message: String,
+}
+
+/// How far one language's value is from being translated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueStatus {
+    /// The language's record doesn't define the key
+    Missing,
+    Empty,
+    /// A string starting with the TODO marker, like the placeholders `lint --fix` inserts
+    Todo,
+    Translated,
+}
+
+impl ValueStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            ValueStatus::Missing => "missing",
+            ValueStatus::Empty => "empty",
+            ValueStatus::Todo => "TODO",
+            ValueStatus::Translated => "✓",
+        }
+    }
+}
+
+/// `todo_marker` is the configured marker (e.g., "TODO" or "__TODO__")
+pub fn value_status(value: Option<&String>, todo_marker: &str) -> ValueStatus {
+    let value = match value {
+        Some(value) => value.trim(),
+        None => return ValueStatus::Missing,
+    };
+    if value.is_empty() || value == "\"\"" {
+        return ValueStatus::Empty;
+    }
+    let literals = string_literal_contents(value);
+    if literals
+        .iter()
+        .any(|literal| literal.trim().starts_with(todo_marker))
+    {
+        ValueStatus::Todo
+    } else {
+        ValueStatus::Translated
+    }
+}
+
+/// How one language's function body treats its arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LambdaShape {
+    /// Argument names (or patterns) in order
+    arguments: Vec<String>,
+    /// Whether each argument is used in the body
+    used: Vec<bool>,
+    /// Everything after the `->`
+    body: String,
+}
+
+/// Keys whose value in the default language, the first configured one, is an empty
+/// or blank string, sorted
+///
+/// Every other language is translated from that value, so an empty one ends up as an
+/// empty source in exports; it's usually a mistake rather than a pending translation.
+pub fn empty_source_keys(parse_result: &ParseResult, languages: &[String]) -> Vec<String> {
+    let Some(default_language) = languages.first() else {
+        return Vec::new();
+    };
+    let mut keys: Vec<String> = parse_result
+        .translations
+        .values()
+        .filter(|translation| !translation.is_function)
+        .filter(
+            |translation| match translation.values.get(default_language) {
+                Some(value) => {
+                    value.trim().is_empty()
+                        || plain_string_value(value).is_some_and(|text| text.trim().is_empty())
+                }
+                None => false,
+            },
+        )
+        .map(|translation| translation.key.clone())
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Check that every key has a value in every language
+///
+/// With `strict`, also check that function bodies take the arguments their type
+/// promises and use them consistently, and that `{placeholder}`s in plain strings
+/// match across languages.
+pub fn validate_translations(
+    parse_result: &ParseResult,
+    languages: &[String],
+    strict: bool,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut keys: Vec<&String> = parse_result.translations.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let translation = &parse_result.translations[key];
+
+        for lang in languages {
+            let missing = translation
+                .values
+                .get(lang)
+                .is_none_or(|value| value.trim().is_empty() || value.trim() == "\"\"");
+            if missing {
+                issues.push(ValidationIssue {
+                    key: key.clone(),
+                    language: Some(lang.clone()),
+                    message: "has no value".to_string(),
+                });
+            }
+        }
+
+        if let Some(signature) = translation
+            .type_signature
+            .as_ref()
+            .filter(|_| !translation.is_function)
+        {
+            for lang in languages {
+                let message = translation
+                    .values
+                    .get(lang)
+                    .and_then(|value| literal_type_mismatch(signature, value));
+                if let Some(message) = message {
+                    issues.push(ValidationIssue {
+                        key: key.clone(),
+                        language: Some(lang.clone()),
+                        message,
+                    });
+                }
+            }
+        }
+
+        if strict {
+            issues.extend(consistency_issues(key, translation, languages));
+        }
+    }
+
+    issues
+}
+
+/// The `--strict` checks for one translation: a function's arguments, or a plain
+/// string's `{placeholder}`s, are the same in every language
+pub fn consistency_issues(
+    key: &str,
+    translation: &Translation,
+    languages: &[String],
+) -> Vec<ValidationIssue> {
+    match &translation.type_signature {
+        Some(signature) if translation.is_function => {
+            check_function_arguments(key, signature, &translation.values, languages)
+        }
+        Some(_) => Vec::new(),
+        None => check_placeholders(key, &translation.values, languages),
+    }
+}
+
+/// Why a literal can't have a simple type, e.g. `"1"` for an `Int`
+///
+/// Only literals are judged; any other expression is left to the compiler.
+pub fn literal_type_mismatch(type_annotation: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    let literal = if value.starts_with('"') {
+        "String"
+    } else if value == "True" || value == "False" {
+        "Bool"
+    } else if Regex::new(r"^-?\d+$").unwrap().is_match(value) {
+        "Int"
+    } else if Regex::new(r"^-?\d+(\.\d+)?(e-?\d+)?$")
+        .unwrap()
+        .is_match(value)
+    {
+        "Float"
+    } else {
+        return None;
+    };
+
+    let accepted = match type_annotation.trim() {
+        "Int" => literal == "Int",
+        // Elm number literals without a dot are also Floats
+        "Float" => literal == "Int" || literal == "Float",
+        "Bool" => literal == "Bool",
+        "String" => literal == "String",
+        _ => return None,
+    };
+    if accepted {
+        None
+    } else {
+        Some(format!(
+            "`{}` is a {} literal, but the type is {}",
+            value,
+            literal,
+            type_annotation.trim()
+        ))
+    }
+}
+
+/// How many arguments a lambda implementation takes, counting chained lambdas
+/// (`\a -> \b -> ...`) together
+///
+/// `None` for anything else, such as a point-free `String.fromInt` or a lambda
+/// without `->`, whose arity can't be read off the source.
+pub fn lambda_arity(value: &str) -> Option<usize> {
+    let mut shape = lambda_shape(value)?;
+    let mut arity = shape.arguments.len();
+    while shape.body.trim_start().starts_with('\\') {
+        shape = lambda_shape(&shape.body)?;
+        arity += shape.arguments.len();
+    }
+    Some(arity)
+}
+
+/// Why a lambda implementation doesn't fit its signature, e.g. `\count -> ...`
+/// for `Int -> Int -> String`
+pub fn arity_mismatch(signature: &str, value: &str) -> Option<String> {
+    let arguments = lambda_arity(value)?;
+    let arity = function_arity(signature);
+    if arguments == arity {
+        return None;
+    }
+    Some(format!(
+        "takes {} argument{} but `{}` has {}",
+        arguments,
+        if arguments == 1 { "" } else { "s" },
+        signature.trim(),
+        arity
+    ))
+}
+
+/// Structural problems in a function implementation given to `add-fn`: unbalanced
+/// brackets or quotes, tabs, a lambda without `->`, or a lambda whose arguments
+/// don't match the signature's arrows
+///
+/// This is deliberately shallow, so an implementation it accepts may still not
+/// compile.
+pub fn implementation_problems(signature: &str, value: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    if value.contains('\t') {
+        problems.push("contains a tab; Elm only allows spaces".to_string());
+    }
+
+    let chars: Vec<char> = value.chars().collect();
+    let mut open: Vec<char> = Vec::new();
+    // Whether `n` double quotes start at `at`
+    let is_quotes =
+        |at: usize, n: usize| chars.len() >= at + n && chars[at..at + n] == ['"'; 3][..n];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '"' => {
+                let delimiter = if is_quotes(i, 3) { 3 } else { 1 };
+                i += delimiter;
+                while !is_quotes(i, delimiter) {
+                    match chars.get(i) {
+                        None => {
+                            problems.push("has a string that is never closed".to_string());
+                            return problems;
+                        }
+                        Some('\\') => i += 2,
+                        Some(_) => i += 1,
+                    }
+                }
+                i += delimiter;
+                continue;
+            }
+            '\'' => {
+                // A character literal such as '(', '\'' or '\u{00A0}'
+                let start = if chars.get(i + 1) == Some(&'\\') {
+                    i + 3
+                } else {
+                    i + 2
+                };
+                match (start..chars.len()).find(|&j| chars[j] == '\'') {
+                    Some(end) => i = end,
+                    None => {
+                        problems.push("has a character literal that is never closed".to_string());
+                        return problems;
+                    }
+                }
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                // A line comment: skip to the end of the line
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            c @ ('(' | '[' | '{') => open.push(c),
+            c @ (')' | ']' | '}') => {
+                let opener = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                match open.pop() {
+                    Some(o) if o == opener => {}
+                    Some(o) => {
+                        problems.push(format!("`{}` closes `{}`", c, o));
+                        return problems;
+                    }
+                    None => {
+                        problems.push(format!("`{}` has no matching `{}`", c, opener));
+                        return problems;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if let Some(o) = open.last() {
+        problems.push(format!("`{}` is never closed", o));
+        return problems;
+    }
+
+    if value.trim_start().starts_with('\\') && lambda_arity(value).is_none() {
+        problems.push("starts a lambda (`\\`) without a `->` after its arguments".to_string());
+    }
+    problems.extend(arity_mismatch(signature, value));
+
+    problems
+}
+
+fn check_function_arguments(
+    key: &str,
+    signature: &str,
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) -> Vec<ValidationIssue> {
+    let arity = function_arity(signature);
+    let mut issues = Vec::new();
+
+    // Bodies written point-free (e.g. `String.fromInt`) can't be inspected
+    let shapes: Vec<(&String, LambdaShape)> = languages
+        .iter()
+        .filter_map(|lang| {
+            let value = values.get(lang)?;
+            lambda_shape(value).map(|shape| (lang, shape))
+        })
+        .collect();
+
+    for (lang, _) in &shapes {
+        if let Some(message) = arity_mismatch(signature, &values[*lang]) {
+            issues.push(ValidationIssue {
+                key: key.to_string(),
+                language: Some((*lang).clone()),
+                message,
+            });
+        }
+    }
+
+    // The placeholders `add` makes fields of a record, each of which every
+    // language should use
+    let records: Vec<(&String, BTreeSet<String>)> = languages
+        .iter()
+        .filter_map(|lang| Some((lang, record_placeholders(values.get(lang)?)?)))
+        .collect();
+    issues.extend(differing_placeholders(key, &records));
+
+    // An argument one language uses but another ignores usually means a
+    // forgotten interpolation (e.g. "items" instead of "{n} items")
+    for position in 0..arity {
+        let users: Vec<&String> = shapes
+            .iter()
+            .filter(|(_, shape)| shape.used.get(position) == Some(&true))
+            .map(|(lang, _)| *lang)
+            .collect();
+        if users.is_empty() {
+            continue;
+        }
+
+        for (lang, shape) in &shapes {
+            if shape.used.get(position) == Some(&false) {
+                issues.push(ValidationIssue {
+                    key: key.to_string(),
+                    language: Some((*lang).clone()),
+                    message: format!(
+                        "ignores argument {} (`{}`), which {} uses",
+                        position + 1,
+                        shape.arguments[position],
+                        users
+                            .iter()
+                            .map(|l| l.to_uppercase())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+fn check_placeholders(
+    key: &str,
+    values: &std::collections::HashMap<String, String>,
+    languages: &[String],
+) -> Vec<ValidationIssue> {
+    let sets: Vec<(&String, BTreeSet<String>)> = languages
+        .iter()
+        .filter_map(|lang| {
+            let value = values.get(lang).filter(|v| !v.trim().is_empty())?;
+            Some((lang, placeholders(value)))
+        })
+        .collect();
+    differing_placeholders(key, &sets)
+}
+
+/// An issue for each language whose placeholders differ from the first one's
+fn differing_placeholders(key: &str, sets: &[(&String, BTreeSet<String>)]) -> Vec<ValidationIssue> {
+    let (base_lang, base) = match sets.first() {
+        Some(first) => first,
+        None => return vec![],
+    };
+
+    sets.iter()
+        .skip(1)
+        .filter(|(_, set)| set != base)
+        .map(|(lang, set)| ValidationIssue {
+            key: key.to_string(),
+            language: Some((*lang).clone()),
+            message: format!(
+                "placeholders differ from {}: {} has {}, {} has {}",
+                base_lang.to_uppercase(),
+                base_lang.to_uppercase(),
+                format_placeholders(base),
+                lang.to_uppercase(),
+                format_placeholders(set)
+            ),
+        })
+        .collect()
+}
+
+/// The fields of a value's record pattern its body uses, as in the
+/// `\{ name } -> "Hi " ++ name` that `add` writes for `{name}`, or None when the
+/// value doesn't take a record
+fn record_placeholders(value: &str) -> Option<BTreeSet<String>> {
+    let fields = record_pattern_fields(value);
+    if fields.is_empty() {
+        return None;
+    }
+    let shape = lambda_shape(value)?;
+    let body = blank_strings(&shape.body);
+    Some(
+        fields
+            .into_iter()
+            .filter(|field| {
+                Regex::new(&format!(r"(^|[^.\w]){}\b", regex::escape(field)))
+                    .is_ok_and(|r| r.is_match(&body))
+            })
+            .collect(),
+    )
+}
+
+/// `{name}` style placeholders used in a value's string literals
+fn placeholders(value: &str) -> BTreeSet<String> {
+    let placeholder_regex = Regex::new(r"\{\s*(\w+)\s*\}").unwrap();
+    string_literal_contents(value)
+        .iter()
+        .flat_map(|literal| {
+            placeholder_regex
+                .captures_iter(literal)
+                .map(|c| c[1].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Placeholders other systems write, which a value copied from one of them may
+/// still have, with what to call each in a message
+const FOREIGN_PLACEHOLDERS: [(&str, &str); 4] = [
+    (
+        r"%(\d+\$)?[-+0#]*\d*(\.\d+)?([sdif]\b|@)",
+        "a printf placeholder",
+    ),
+    (r"%\{\w+\}", "a Ruby placeholder"),
+    (r"\{\{\s*[\w.]+\s*\}\}", "a Mustache placeholder"),
+    (r"\{\d+\}", "a numbered placeholder"),
+];
+
+/// An ICU argument with a format, like `{count, plural, ...}`, which the `braces`
+/// style doesn't have
+const ICU_ARGUMENT: &str =
+    r"\{\s*\w+\s*,\s*(plural|select|selectordinal|number|date|time|spellout|ordinal|duration)\b";
+
+/// The placeholders in `parse_result`'s string literals that aren't written in
+/// `style`: a `%s` or `{{name}}` from another system with either style, and an ICU
+/// argument like `{count, plural, ...}` with `braces`
+pub fn placeholder_style_issues(
+    parse_result: &ParseResult,
+    languages: &[String],
+    style: PlaceholderStyle,
+) -> Vec<ValidationIssue> {
+    let mut patterns: Vec<(Regex, &str)> = FOREIGN_PLACEHOLDERS
+        .iter()
+        .map(|(pattern, name)| (Regex::new(pattern).unwrap(), *name))
+        .collect();
+    if style == PlaceholderStyle::Braces {
+        patterns.push((Regex::new(ICU_ARGUMENT).unwrap(), "ICU syntax"));
+    }
+    let expected = match style {
+        PlaceholderStyle::Braces => "{name}",
+        PlaceholderStyle::Icu => "ICU's {name}",
+    };
+
+    let mut keys: Vec<&String> = parse_result.translations.keys().collect();
+    keys.sort();
+    let mut issues = Vec::new();
+    for key in keys {
+        let values = &parse_result.translations[key].values;
+        for lang in languages {
+            let Some(value) = values.get(lang) else {
+                continue;
+            };
+            for literal in string_literal_contents(value) {
+                for (pattern, name) in &patterns {
+                    for found in pattern.find_iter(&literal) {
+                        issues.push(ValidationIssue {
+                            key: key.clone(),
+                            language: Some(lang.clone()),
+                            message: format!(
+                                "`{}` is {}; placeholders here are written {}",
+                                found.as_str(),
+                                name,
+                                expected
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    issues
+}
+
+fn format_placeholders(set: &BTreeSet<String>) -> String {
+    if set.is_empty() {
+        "none".to_string()
+    } else {
+        set.iter()
+            .map(|p| format!("{{{}}}", p))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Split a `\a b -> body` lambda into its arguments and record which are used
+fn lambda_shape(value: &str) -> Option<LambdaShape> {
+    let rest = value.trim_start().strip_prefix('\\')?;
+
+    // Find the `->` that ends the argument list, skipping nested patterns
+    let chars: Vec<char> = rest.chars().collect();
+    let mut depth = 0i32;
+    let mut arrow = None;
+    for i in 0..chars.len() {
+        match chars[i] {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            '-' if depth == 0 && chars.get(i + 1) == Some(&'>') => {
+                arrow = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let arrow = arrow?;
+    let params: String = chars[..arrow].iter().collect();
+    let body: String = chars[arrow + 2..].iter().collect();
+
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    for c in params.chars() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            _ => {}
+        }
+        if c.is_whitespace() && depth == 0 {
+            if !current.is_empty() {
+                arguments.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        arguments.push(current);
+    }
+
+    let used = arguments
+        .iter()
+        .map(|argument| {
+            if argument.starts_with('_') {
+                false
+            } else if argument.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                Regex::new(&format!(r"\b{}\b", regex::escape(argument)))
+                    .is_ok_and(|r| r.is_match(&body))
+            } else {
+                // Tuple or record patterns: assume they are used
+                true
+            }
+        })
+        .collect();
+
+    Some(LambdaShape {
+        arguments,
+        used,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Translation;
+    use std::collections::HashMap;
+
+    fn parse_result_with(translations: Vec<Translation>) -> ParseResult {
+        ParseResult {
+            type_start_line: 0,
+            type_end_line: 0,
+            lang_bounds: vec![],
+            translations: translations
+                .into_iter()
+                .map(|t| (t.key.clone(), t))
+                .collect(),
+            type_fields: vec![],
+            record_fields: HashMap::new(),
+        }
+    }
+
+    fn translation(key: &str, signature: Option<&str>, en: &str, fr: &str) -> Translation {
+        Translation {
+            key: key.to_string(),
+            values: HashMap::from([
+                ("en".to_string(), en.to_string()),
+                ("fr".to_string(), fr.to_string()),
+            ]),
+            is_function: signature.is_some_and(|s| s.contains("->")),
+            type_signature: signature.map(str::to_string),
+        }
+    }
+
+    fn languages() -> Vec<String> {
+        vec!["en".to_string(), "fr".to_string()]
+    }
+
+    #[test]
+    fn reports_missing_values_in_any_mode() {
+        let parse_result = parse_result_with(vec![translation("save", None, r#""Save""#, "")]);
+
+        let issues = validate_translations(&parse_result, &languages(), false);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].language.as_deref(), Some("fr"));
+        assert_eq!(issues[0].message, "has no value");
+
+        assert_eq!(value_status(None, "TODO"), ValueStatus::Missing);
+        assert_eq!(
+            value_status(Some(&" \"\" ".to_string()), "TODO"),
+            ValueStatus::Empty
+        );
+        assert_eq!(
+            value_status(Some(&r#"\_ -> "TODO""#.to_string()), "TODO"),
+            ValueStatus::Todo
+        );
+        assert_eq!(
+            value_status(Some(&r#""Todo list""#.to_string()), "TODO"),
+            ValueStatus::Translated
+        );
+        assert_eq!(
+            value_status(Some(&r#""__TODO__""#.to_string()), "__TODO__"),
+            ValueStatus::Todo
+        );
+    }
+
+    #[test]
+    fn lists_keys_with_an_empty_default_language_value() {
+        let parse_result = parse_result_with(vec![
+            translation("title", None, r#""""#, r#""Titre""#),
+            translation("save", None, r#""  ""#, r#""Sauver""#),
+            translation("cancel", None, r#""Cancel""#, r#""""#),
+            translation("count", Some("Int -> String"), r#"\_ -> """#, r#"\_ -> """#),
+        ]);
+        assert_eq!(
+            empty_source_keys(&parse_result, &languages()),
+            vec!["save", "title"]
+        );
+        // With French first, only its empty value counts
+        let french_first = vec!["fr".to_string(), "en".to_string()];
+        assert_eq!(
+            empty_source_keys(&parse_result, &french_first),
+            vec!["cancel"]
+        );
+    }
+
+    #[test]
+    fn strict_mode_checks_function_arguments() {
+        let parse_result = parse_result_with(vec![
+            translation(
+                "itemCount",
+                Some("Int -> String"),
+                r#"\n -> String.fromInt n ++ " items""#,
+                r#"\_ -> "des éléments""#,
+            ),
+            translation(
+                "greeting",
+                Some("String -> String -> String"),
+                r#"\first last -> "Hi " ++ first ++ " " ++ last"#,
+                r#"\name -> "Salut " ++ name"#,
+            ),
+        ]);
+
+        assert!(validate_translations(&parse_result, &languages(), false).is_empty());
+
+        let messages: Vec<(String, String)> =
+            validate_translations(&parse_result, &languages(), true)
+                .into_iter()
+                .map(|i| (i.key, i.message))
+                .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    "greeting".to_string(),
+                    "takes 1 argument but `String -> String -> String` has 2".to_string()
+                ),
+                (
+                    "itemCount".to_string(),
+                    "ignores argument 1 (`_`), which EN uses".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_mode_compares_placeholder_names() {
+        let parse_result = parse_result_with(vec![translation(
+            "welcome",
+            None,
+            r#""Welcome {name}""#,
+            r#""Bienvenue {nom}""#,
+        )]);
+
+        let issues = validate_translations(&parse_result, &languages(), true);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "placeholders differ from EN: EN has {name}, FR has {nom}"
+        );
+    }
+
+    #[test]
+    fn strict_mode_compares_the_fields_a_record_placeholder_uses() {
+        let languages = languages();
+        let values = HashMap::from([
+            ("en".to_string(), "Hi {name}, {count} new".to_string()),
+            ("fr".to_string(), "{count} nouveaux, {name}".to_string()),
+        ]);
+        let (signature, implementations) =
+            crate::placeholder::placeholder_function(&values, &languages, &[], "TODO")
+                .unwrap()
+                .unwrap();
+        let mut inbox = translation(
+            "inbox",
+            Some(&signature),
+            &implementations["en"],
+            &implementations["fr"],
+        );
+        let parse_result = parse_result_with(vec![inbox.clone()]);
+        assert!(validate_translations(&parse_result, &languages, true).is_empty());
+
+        // FR still takes `name` but drops it from the text
+        inbox.values.insert(
+            "fr".to_string(),
+            r#"\{ name, count } -> String.fromInt count ++ " nouveaux, name""#.to_string(),
+        );
+        let issues = validate_translations(&parse_result_with(vec![inbox]), &languages, true);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].language.as_deref(), Some("fr"));
+        assert_eq!(
+            issues[0].message,
+            "placeholders differ from EN: EN has {count}, {name}, FR has {count}"
+        );
+    }
+
+    #[test]
+    fn flags_placeholders_written_in_another_style() {
+        let parse_result = parse_result_with(vec![
+            translation(
+                "greeting",
+                None,
+                r#""Hello, %s!""#,
+                r#""Bonjour, {{name}} !""#,
+            ),
+            translation("discount", None, r#""50% off""#, r#""50 % de réduction""#),
+            translation(
+                "itemCount",
+                None,
+                r#""{count, plural, one {# item} other {# items}}""#,
+                r#""{0} articles""#,
+            ),
+            translation(
+                "welcome",
+                Some("String -> String"),
+                r#"\name -> "Welcome, %1$s " ++ name"#,
+                r#"\name -> "Bienvenue, " ++ name"#,
+            ),
+        ]);
+
+        let flagged = |style| -> Vec<(String, String, String)> {
+            placeholder_style_issues(&parse_result, &languages(), style)
+                .into_iter()
+                .map(|i| (i.key, i.language.unwrap(), i.message))
+                .collect()
+        };
+        let braces = flagged(PlaceholderStyle::Braces);
+        assert_eq!(
+            braces
+                .iter()
+                .map(|(key, lang, _)| format!("{} {}", key, lang))
+                .collect::<Vec<_>>(),
+            [
+                "greeting en",
+                "greeting fr",
+                "itemCount en",
+                "itemCount fr",
+                "welcome en"
+            ]
+        );
+        assert_eq!(
+            braces[0].2,
+            "`%s` is a printf placeholder; placeholders here are written {name}"
+        );
+        assert_eq!(
+            braces[2].2,
+            "`{count, plural` is ICU syntax; placeholders here are written {name}"
+        );
+
+        // ICU itself is fine in an ICU project; the rest still isn't
+        let icu = flagged(PlaceholderStyle::Icu);
+        assert_eq!(icu.len(), 4);
+        assert!(icu.iter().all(|(key, _, _)| key != "discount"));
+        assert!(!icu
+            .iter()
+            .any(|(_, _, message)| message.contains("ICU syntax")));
+    }
+
+    #[test]
+    fn reports_literals_of_the_wrong_type() {
+        let mut first_day = translation("firstDayOfWeek", None, "1", r#""1""#);
+        first_day.type_signature = Some("Int".to_string());
+        let parse_result = parse_result_with(vec![first_day]);
+
+        let issues = validate_translations(&parse_result, &languages(), false);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].language.as_deref(), Some("fr"));
+        assert_eq!(
+            issues[0].message,
+            r#"`"1"` is a String literal, but the type is Int"#
+        );
+
+        assert_eq!(literal_type_mismatch("Float", "2"), None);
+        assert_eq!(literal_type_mismatch("Bool", "isRtl"), None);
+        assert!(literal_type_mismatch("Bool", "1").is_some());
+    }
+
+    #[test]
+    fn catches_broken_function_implementations() {
+        let ok = [
+            r#"\n -> String.fromInt n ++ " items (total)""#,
+            r#"\a b -> String.join ", " [ a, b ]"#,
+            r#"\a -> \b -> a ++ ":)" ++ b"#,
+            r#"\{ name } -> "Hi " ++ name"#,
+            "String.fromInt",
+            r#"\c -> String.fromChar '(' ++ c"#,
+        ];
+        for value in ok {
+            let signature = if value.contains("a b") || value.contains("\\b") {
+                "String -> String -> String"
+            } else {
+                "Int -> String"
+            };
+            assert!(
+                implementation_problems(signature, value).is_empty(),
+                "{}",
+                value
+            );
+        }
+
+        let problems = |value| implementation_problems("Int -> String", value);
+        assert_eq!(
+            problems(r#"\n -> String.fromInt (n ++ " items""#),
+            vec!["`(` is never closed"]
+        );
+        assert_eq!(
+            problems(r#"\n -> ")" ++ n)"#),
+            vec!["`)` has no matching `(`"]
+        );
+        assert_eq!(problems(r#"\n -> [ n )"#), vec!["`)` closes `[`"]);
+        assert_eq!(
+            problems(r#"\n -> "items"#),
+            vec!["has a string that is never closed"]
+        );
+        assert_eq!(
+            problems(r#"\n String.fromInt n"#),
+            vec!["starts a lambda (`\\`) without a `->` after its arguments"]
+        );
+        assert_eq!(
+            problems(r#"\a b -> a"#),
+            vec!["takes 2 arguments but `Int -> String` has 1"]
+        );
+        assert_eq!(
+            implementation_problems("Int -> Int -> String", r"\count -> String.fromInt count"),
+            vec!["takes 1 argument but `Int -> Int -> String` has 2"]
+        );
+        assert_eq!(lambda_arity(r"\( a, b ) -> \c -> a ++ b ++ c"), Some(2));
+        assert_eq!(lambda_arity("String.fromInt"), None);
+        assert_eq!(
+            problems("\\n ->\tString.fromInt n"),
+            vec!["contains a tab; Elm only allows spaces"]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What happened when a module was type-checked with `elm make`
+#[derive(Debug)]
+pub enum VerifyOutcome {
+    Compiles,
+    /// Elm's report, passed through as-is
+    Fails(String),
+}
+
+/// Locate the elm binary: `--elm-path` if given, otherwise the first `elm` on PATH
+pub fn find_elm(elm_path: Option<&Path>) -> Option<PathBuf> {
+    find_executable("elm", elm_path)
+}
+
+/// Locate a binary: the explicit path if given, otherwise the first `name` on PATH
+pub fn find_executable(name: &str, explicit: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = explicit {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let names: Vec<String> = if cfg!(windows) {
+        vec![
+            format!("{}.exe", name),
+            format!("{}.cmd", name),
+            name.to_string(),
+        ]
+    } else {
+        vec![name.to_string()]
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// The directory holding the `elm.json` that owns a file
+pub fn find_elm_project(file: &Path) -> Option<PathBuf> {
+    let absolute = file.canonicalize().ok()?;
+    absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("elm.json").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Type-check a module with `elm make --output=/dev/null`
+///
+/// The module doesn't need a `main`: `elm make` compiles any module in the
+/// project's source directories, including everything it imports.
+pub fn verify_module(elm: &Path, file: &Path) -> Result<VerifyOutcome> {
+    let project = find_elm_project(file).with_context(|| {
+        format!(
+            "No elm.json found above {}; run verify from inside an Elm project",
+            file.display()
+        )
+    })?;
+    let absolute = file.canonicalize()?;
+    let relative = absolute.strip_prefix(&project).unwrap_or(&absolute);
+    let null_output = if cfg!(windows) { "NUL" } else { "/dev/null" };
+
+    let output = Command::new(elm)
+        .arg("make")
+        .arg(relative)
+        .arg(format!("--output={}", null_output))
+        .current_dir(&project)
+        .output()
+        .with_context(|| format!("Failed to run {}", elm.display()))?;
+
+    if output.status.success() {
+        Ok(VerifyOutcome::Compiles)
+    } else {
+        // Elm writes its errors to stderr; older versions used stdout
+        let mut report = String::from_utf8_lossy(&output.stderr).into_owned();
+        if report.trim().is_empty() {
+            report = String::from_utf8_lossy(&output.stdout).into_owned();
+        }
+        Ok(VerifyOutcome::Fails(report))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    /// A stand-in for elm that fails when the module contains `BROKEN`
+    fn fake_elm(dir: &Path) -> PathBuf {
+        let path = dir.join("elm");
+        fs::write(
+            &path,
+            "#!/bin/sh\nif grep -q BROKEN \"$2\"; then echo '-- TYPE MISMATCH' >&2; exit 1; fi\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn runs_elm_make_from_the_project_root() {
+        let dir = TempDir::new().unwrap();
+        let elm = fake_elm(dir.path());
+        fs::write(dir.path().join("elm.json"), "{}").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        let file = dir.path().join("src/I18n.elm");
+
+        fs::write(&file, "module I18n exposing (..)\n").unwrap();
+        assert!(matches!(
+            verify_module(&elm, &file).unwrap(),
+            VerifyOutcome::Compiles
+        ));
+
+        fs::write(&file, "module I18n exposing (..)\nBROKEN\n").unwrap();
+        match verify_module(&elm, &file).unwrap() {
+            VerifyOutcome::Fails(report) => assert_eq!(report.trim(), "-- TYPE MISMATCH"),
+            VerifyOutcome::Compiles => panic!("expected a failure"),
+        }
+    }
+
+    #[test]
+    fn explicit_elm_path_must_exist() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(find_elm(Some(&dir.path().join("missing"))), None);
+        let elm = fake_elm(dir.path());
+        assert_eq!(find_elm(Some(&elm)), Some(elm));
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// How often `watch` looks at the files
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long the files must stay the same after a change before `watch` runs the
+/// commands, so an editor's save-rename-touch sequence counts as one change
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The size and modification time of each watched file, missing ones included;
+/// two snapshots differ when a file was saved, created or deleted in between
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot(Vec<(PathBuf, Option<(SystemTime, u64)>)>);
+
+/// Take a snapshot of `files` and of the Elm files under `dirs`
+pub fn snapshot(files: &[PathBuf], dirs: &[PathBuf]) -> Snapshot {
+    let stamp = |path: &PathBuf| {
+        fs::metadata(path)
+            .ok()
+            .map(|m| (m.modified().unwrap_or(UNIX_EPOCH), m.len()))
+    };
+    let mut entries: Vec<_> = files.iter().map(|f| (f.clone(), stamp(f))).collect();
+    for dir in dirs {
+        for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if entry.file_type().is_file() && path.extension().is_some_and(|ext| ext == "elm") {
+                let path = path.to_path_buf();
+                let stamp = stamp(&path);
+                entries.push((path, stamp));
+            }
+        }
+    }
+    entries.sort();
+    entries.dedup();
+    Snapshot(entries)
+}
+
+/// The commands in a `--run` list such as `lint,export --format json --out public`
+///
+/// Commands are separated by commas. A comma inside a command's arguments, as in
+/// `check --languages fr,de`, stays in it: a new command only starts where the
+/// next word is one of `command_names`. Words are split at spaces, and single or
+/// double quotes keep a value with spaces together.
+pub fn split_commands(spec: &str, command_names: &[String]) -> Result<Vec<Vec<String>>> {
+    let mut commands: Vec<String> = Vec::new();
+    for part in spec.split(',') {
+        let first_word = part.split_whitespace().next().unwrap_or("");
+        match commands.last_mut() {
+            Some(command) if !command_names.iter().any(|name| name == first_word) => {
+                command.push(',');
+                command.push_str(part);
+            }
+            _ => commands.push(part.to_string()),
+        }
+    }
+
+    let mut split = Vec::new();
+    for command in commands {
+        let words = split_words(&command)?;
+        match words.first() {
+            None => anyhow::bail!("Empty command in --run \"{}\"", spec),
+            Some(word) if !command_names.contains(word) => {
+                // `lint,lnt` reads as one word; name the part that isn't a command
+                let name = word
+                    .split(',')
+                    .find(|part| !command_names.iter().any(|name| name == part))
+                    .unwrap_or(word);
+                anyhow::bail!("Unknown command '{}' in --run \"{}\"", name, spec)
+            }
+            Some(_) => split.push(words),
+        }
+    }
+    Ok(split)
+}
+
+/// `command` split into words at spaces, outside single and double quotes
+fn split_words(command: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(open) = quote {
+        anyhow::bail!("Unclosed {} in \"{}\"", open, command.trim());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_run_list_into_commands() {
+        let names: Vec<String> = ["lint", "export", "check", "validate"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(
+            split_commands(
+                "lint, export --format json --out 'public/my locales',check --languages fr,de",
+                &names
+            )
+            .unwrap(),
+            vec![
+                vec!["lint"],
+                vec!["export", "--format", "json", "--out", "public/my locales"],
+                vec!["check", "--languages", "fr,de"],
+            ]
+        );
+        assert_eq!(
+            split_commands("validate --strict", &names).unwrap(),
+            vec![vec!["validate", "--strict"]]
+        );
+        assert_eq!(
+            split_commands("lint,deploy", &names)
+                .unwrap_err()
+                .to_string(),
+            "Unknown command 'deploy' in --run \"lint,deploy\""
+        );
+        assert!(split_commands("lint,,export", &names).is_err());
+        assert!(split_commands("export --out \"public", &names).is_err());
+    }
+
+    #[test]
+    fn notices_saved_created_and_deleted_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let i18n = dir.path().join("I18n.elm");
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(&i18n, "module I18n exposing (..)").unwrap();
+        let files = [i18n.clone()];
+        let dirs = [src.clone()];
+
+        let before = snapshot(&files, &dirs);
+        assert_eq!(snapshot(&files, &dirs), before);
+
+        fs::write(src.join("Main.elm"), "module Main exposing (main)").unwrap();
+        fs::write(src.join("notes.txt"), "not Elm").unwrap();
+        let created = snapshot(&files, &dirs);
+        assert_ne!(created, before);
+        assert_eq!(created.0.len(), 2);
+
+        fs::write(&i18n, "module I18n exposing (..)\n").unwrap();
+        let saved = snapshot(&files, &dirs);
+        assert_ne!(saved, created);
+
+        fs::remove_file(&i18n).unwrap();
+        assert_ne!(snapshot(&files, &dirs), saved);
+    }
+}
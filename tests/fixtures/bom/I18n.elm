@@ -0,0 +1,12 @@
+﻿module I18n exposing (..)
+
+
+type alias Translations =
+    { welcome : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome!"
+    }
@@ -0,0 +1,27 @@
+module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    , legacyBanner : String
+    , title : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = "Save"
+    , cancel = "Cancel"
+    , legacyBanner = "This page is moving soon"
+    , title = "Profile"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = "Sauver"
+    , cancel = "Annuler"
+    , legacyBanner = "Cette page change bientôt d'adresse"
+    , title = "Profil"
+    }
@@ -0,0 +1,30 @@
+module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    , title : String
+    , welcome : String
+    , debugPanel : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = "Save changes"
+    , cancel = "Cancel"
+    , title = "Profile"
+    , welcome = "Welcome, *friend*!"
+    , debugPanel = "Debug"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { save = "Enregistrer"
+    , cancel = "Annuler"
+    , title = "Votre profil"
+    , welcome = "Bienvenue, *ami* !"
+    , debugPanel = "Débogage"
+    }
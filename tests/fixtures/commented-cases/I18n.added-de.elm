@@ -0,0 +1,91 @@
+module I18n exposing (..)
+
+{-| Translations for the app.
+
+Adding a language means a new `| XX` variant below and a branch in each
+helper, e.g. `FR -> "fr"`.
+
+-}
+
+
+type Language
+    = EN -- English, the default
+    | FR -- French
+    | DE
+    {- | DE, once the copy is ready -}
+
+
+type alias Translations =
+    { welcome : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome -- glad you're here"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    -- Reviewed by the Paris office
+    { welcome = "Bienvenue"
+    }
+
+
+translationsDe : Translations
+translationsDe =
+    -- Reviewed by the Paris office
+    { welcome = "Bienvenue"
+    }
+
+
+{-| Convert Language to String for storage
+-}
+languageToString : Language -> String
+languageToString lang =
+    case lang of
+        -- Codes are ISO 639-1
+        EN ->
+            "en"
+
+        -- FR ->
+        --     "fr-FR"
+        FR ->
+            -- Lowercase, like the others
+            "fr"
+
+        DE ->
+            "de"
+
+
+{-| Convert String to Language with fallback to EN
+-}
+stringToLanguage : String -> Language
+stringToLanguage str =
+    case str of
+        "fr" ->
+            FR
+
+        "de" ->
+            DE
+
+        -- Anything else, including "en"
+        _ ->
+            EN
+
+
+{-| Get translations for a given language
+-}
+translations : Language -> Translations
+translations lang =
+    case lang of
+        EN ->
+            translationsEn
+
+        {- French -}
+        FR ->
+            translationsFr
+
+        DE ->
+            translationsDe
@@ -0,0 +1,137 @@
+module I18n exposing (..)
+
+
+type Language
+    = EN
+    | FR
+    | DE_CH
+    | PT_BR
+
+
+-- elm-i18n:begin currency-format
+-- Written by `elm-i18n generate currency-format`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this.
+
+
+{-| The currencies `formatCurrency` writes
+-}
+type Currency
+    = USD
+    | EUR
+    | GBP
+    | CHF
+    | XTS
+
+
+{-| The symbol of a currency: $ for USD, € for EUR
+-}
+currencySymbol : Currency -> String
+currencySymbol currency =
+    case currency of
+        USD ->
+            "$"
+
+        EUR ->
+            "€"
+
+        GBP ->
+            "£"
+
+        CHF ->
+            "CHF"
+
+        XTS ->
+            "XTS"
+
+
+{-| How a language writes an amount of money: its separators, and where the
+symbol goes
+-}
+currencyStyle : Language -> { thousands : String, decimal : String, symbolFirst : Bool, space : String }
+currencyStyle lang =
+    case lang of
+        EN ->
+            { thousands = ",", decimal = ".", symbolFirst = True, space = "" }
+
+        FR ->
+            { thousands = "\u{202F}", decimal = ",", symbolFirst = False, space = "\u{00A0}" }
+
+        DE_CH ->
+            { thousands = "’", decimal = ".", symbolFirst = True, space = "\u{00A0}" }
+
+        PT_BR ->
+            { thousands = ".", decimal = ",", symbolFirst = True, space = "\u{00A0}" }
+
+
+{-| An amount of money in the language's format: $1,234.00 in English, 1 234,00 €
+in French
+
+The amount is rounded to cents, halves away from zero on the digits it's written
+with, so 2.005 is 2.01 even though the Float is a little under it.
+
+-}
+formatCurrency : Language -> { amount : Float, currency : Currency } -> String
+formatCurrency lang { amount, currency } =
+    let
+        style =
+            currencyStyle lang
+
+        cents =
+            roundedCents (abs amount)
+
+        number =
+            groupCurrencyDigits style.thousands (String.fromInt (cents // 100))
+                ++ style.decimal
+                ++ String.padLeft 2 '0' (String.fromInt (modBy 100 cents))
+
+        sign =
+            if amount < 0 && cents > 0 then
+                "-"
+
+            else
+                ""
+    in
+    if style.symbolFirst then
+        sign ++ currencySymbol currency ++ style.space ++ number
+
+    else
+        sign ++ number ++ style.space ++ currencySymbol currency
+
+
+roundedCents : Float -> Int
+roundedCents amount =
+    case String.split "." (String.fromFloat amount) of
+        [ whole ] ->
+            case String.toInt whole of
+                Just units ->
+                    units * 100
+
+                Nothing ->
+                    round (amount * 100)
+
+        [ whole, fraction ] ->
+            case ( String.toInt whole, String.toInt (String.left 2 (fraction ++ "00")) ) of
+                ( Just units, Just hundredths ) ->
+                    if String.slice 2 3 fraction >= "5" then
+                        units * 100 + hundredths + 1
+
+                    else
+                        units * 100 + hundredths
+
+                _ ->
+                    round (amount * 100)
+
+        _ ->
+            round (amount * 100)
+
+
+groupCurrencyDigits : String -> String -> String
+groupCurrencyDigits separator digits =
+    if String.length digits <= 3 then
+        digits
+
+    else
+        groupCurrencyDigits separator (String.dropRight 3 digits) ++ separator ++ String.right 3 digits
+
+
+-- elm-i18n:end currency-format
@@ -0,0 +1,85 @@
+module I18n exposing (..)
+
+
+type alias Translations =
+    { appName : String
+    , greeting : { name : String } -> String
+    , itemCount : { count : Int } -> String
+    , lastSeen : { name : String, days : Int } -> String
+    , invitation : { host : String, guests : Int, gender : String } -> String
+    , quoted : { shortcut : String } -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { appName = "Shop"
+    , greeting = \{ name } -> "Hello, " ++ name ++ "!"
+    , itemCount = \{ count } ->
+        if count == 0 then
+            "No items"
+
+        else if count == 1 then
+            String.fromInt count ++ " item"
+
+        else
+            String.fromInt count ++ " items"
+    , lastSeen = \{ name, days } ->
+        if days == 1 then
+            name ++ " was here yesterday"
+
+        else
+            name ++ " was here " ++ String.fromInt days ++ " days ago"
+    , invitation = \{ host, guests, gender } ->
+        if guests == 1 then
+            case gender of
+                "female" ->
+                    host ++ " invited a guest to her party"
+
+                "male" ->
+                    host ++ " invited a guest to his party"
+
+                _ ->
+                    host ++ " invited a guest to their party"
+
+        else
+            case gender of
+                "female" ->
+                    host ++ " invited " ++ String.fromInt guests ++ " guests to her party"
+
+                "male" ->
+                    host ++ " invited " ++ String.fromInt guests ++ " guests to his party"
+
+                _ ->
+                    host ++ " invited " ++ String.fromInt guests ++ " guests to their party"
+    , quoted = \{ shortcut } -> "Type {" ++ shortcut ++ "} to search, it's quick"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { appName = "Boutique"
+    , greeting = \{ name } -> "Bonjour, " ++ name ++ " !"
+    , itemCount = \{ count } ->
+        if count == 0 then
+            "Aucun article"
+
+        else if count == 0 || count == 1 then
+            String.fromInt count ++ " article"
+
+        else
+            String.fromInt count ++ " articles"
+    , lastSeen = \{ name, days } ->
+        if days == 1 then
+            name ++ " était là hier"
+
+        else
+            name ++ " était là il y a " ++ String.fromInt days ++ " jours"
+    , invitation = \{ host, guests, gender } ->
+        if guests == 0 || guests == 1 then
+            host ++ " a invité " ++ String.fromInt guests ++ " personne à sa fête"
+
+        else
+            host ++ " a invité " ++ String.fromInt guests ++ " personnes à sa fête"
+    , quoted = \{ shortcut } -> "Tapez {" ++ shortcut ++ "} pour chercher"
+    }
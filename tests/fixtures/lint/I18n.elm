@@ -0,0 +1,25 @@
+module I18n exposing (..)
+
+
+type Language
+    = EN
+    | FR
+
+
+type alias Translations =
+    { welcome : String
+    , terms : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { welcome = "Welcome, <b>friend</b>!"
+    , terms = "Read the terms"   
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { welcome = "Bienvenue, <b>ami !"
+    }
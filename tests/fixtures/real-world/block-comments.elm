@@ -0,0 +1,82 @@
+module I18n exposing (Language(..), Translations, translations)
+
+{- {- Block comments nest -} and can hold { braces }, = signs and , commas -}
+
+
+type Language
+    = En
+    | Fr
+
+
+{- type alias Translations =
+    { old : String
+    }
+-}
+
+
+type alias Translations =
+    { greeting : String
+    {- Retired:
+    , removed : String
+    {- , alsoRemoved : String -}
+    -}
+    , farewell : String {- { -}
+    , itemCount : Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    {- { greeting = "Nope" } -}
+    { greeting = "Hello" {- = , } -}
+    {- , removed = "Gone"
+    , alsoRemoved = {- } -} "x"
+    -}
+    , farewell = "Bye"
+    , itemCount =
+        \n ->
+            case n of
+                {- 0 ->
+                    "none"
+                -}
+                1 ->
+                    "{count} item"
+
+                _ ->
+                    "{count} items"
+    {- , removed = "Gone"
+    -}
+    }
+
+
+{- translationsFr : Translations
+translationsFr =
+    { greeting = "Non" }
+-}
+
+
+translationsFr : Translations
+translationsFr =
+    { greeting = "Bonjour"
+    , farewell = {- inline -} "Au revoir"
+    , itemCount =
+        \n ->
+            if n == 1 then
+                "{count} élément"
+
+            else
+                "{count} éléments"
+    }
+
+
+translations : Language -> Translations
+translations lang =
+    case lang of
+        En ->
+            translationsEn
+
+        {- De ->
+            translationsDe
+        -}
+        Fr ->
+            translationsFr
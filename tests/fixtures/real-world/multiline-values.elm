@@ -0,0 +1,38 @@
+module I18n exposing (Translations, translationsEn, translationsFr)
+
+
+type Language
+    = EN
+    | FR
+
+
+type alias Translations =
+    { eq : String
+    , joined : String
+    , poem : String
+    , wrapped :
+        Int -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { eq = "a = b, { c }"
+    , joined =
+        "Hello, "
+            ++ "world"
+    , poem = """Roses
+, are = red
+"""
+    , wrapped = \n -> String.fromInt n ++ " items"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { eq = "a = b" -- note, with = and ,
+    , joined = "Bonjour, "
+        ++ "monde"
+    , poem = """Roses"""
+    , wrapped = \n -> String.fromInt n ++ " articles"
+    }
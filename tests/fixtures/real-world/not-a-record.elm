@@ -0,0 +1,29 @@
+module I18n exposing (..)
+
+
+type alias Translations =
+    { save : String
+    , cancel : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = "Save"
+    , cancel = "Cancel"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    withFallback translationsEn
+        { save = Just "Enregistrer"
+        , cancel = Nothing
+        }
+
+
+withFallback : Translations -> { save : Maybe String, cancel : Maybe String } -> Translations
+withFallback fallback partial =
+    { save = Maybe.withDefault fallback.save partial.save
+    , cancel = Maybe.withDefault fallback.cancel partial.cancel
+    }
@@ -0,0 +1,88 @@
+port module I18n exposing (Language(..), Translations, autosave, save, translations)
+
+{-| Translations for the app, shaped like
+
+type alias Translations =
+    { notAField : String }
+
+-}
+
+import Json.Encode as Encode
+
+
+port save : Encode.Value -> Cmd msg
+
+
+port languageChanged : (String -> msg) -> Sub msg
+
+
+type Language
+    = En
+    | Fr
+
+
+type alias TranslationsConfig =
+    { fallback : Language
+    }
+
+
+autosave : Bool
+autosave =
+    True
+
+
+type alias Translations =
+    { save : String
+    , itemCount : Int -> String
+    , braces : String
+    , openBrace : Char
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { save = "Save" -- not "}"
+    , itemCount =
+        \n ->
+            case n of
+                1 ->
+                    "{count} item"
+
+                _ ->
+                    "{count} items"
+    , braces = "Put { and } around placeholders"
+    , openBrace = '{'
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    {- Used to be:
+    , save = "Sauver"
+    -}
+    { save = "Enregistrer"
+    , itemCount =
+        \n ->
+            if n == 1 then
+                "{count} élément"
+
+            else
+                "{count} éléments"
+    , braces = "Mettez { et } autour des variables"
+    , openBrace = '{'
+    }
+
+
+translationsFrCa : Translations
+translationsFrCa =
+    { translationsFr | save = "Sauvegarder" }
+
+
+translations : Language -> Translations
+translations lang =
+    case lang of
+        En ->
+            translationsEn
+
+        Fr ->
+            translationsFr
@@ -0,0 +1,43 @@
+module I18n exposing (Translations, translationsEn, translationsFr)
+
+import Dict exposing (Dict)
+import Html exposing (Html, a, text)
+import Html.Attributes exposing (href, title)
+
+
+type alias Translations msg =
+    { heading : String
+    , tooltip : Html.Attribute msg
+    , footer : List (Html msg)
+    , onHelp : Maybe (String -> msg)
+    , formatters : List (Int -> String)
+    , shortcuts : Dict.Dict String ( String, Int -> String )
+    , greet : { name : String } -> Html.Html msg
+    , link : (String -> msg) -> List (Html.Attribute msg) -> Html msg
+    }
+
+
+translationsEn : Translations msg
+translationsEn =
+    { heading = "Help"
+    , tooltip = title "Open the help"
+    , footer = [ text "Made with Elm" ]
+    , onHelp = Nothing
+    , formatters = [ String.fromInt ]
+    , shortcuts = Dict.fromList [ ( "n", ( "New", String.fromInt ) ) ]
+    , greet = \{ name } -> text ("Hello, " ++ name)
+    , link = \toMsg attributes -> a (href "/help" :: attributes) [ text "Help" ]
+    }
+
+
+translationsFr : Translations msg
+translationsFr =
+    { heading = "Aide"
+    , tooltip = title "Ouvrir l’aide"
+    , footer = [ text "Fait avec Elm" ]
+    , onHelp = Nothing
+    , formatters = [ String.fromInt ]
+    , shortcuts = Dict.fromList [ ( "n", ( "Nouveau", String.fromInt ) ) ]
+    , greet = \{ name } -> text ("Bonjour, " ++ name)
+    , link = \toMsg attributes -> a (href "/aide" :: attributes) [ text "Aide" ]
+    }
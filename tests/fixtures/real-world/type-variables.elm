@@ -0,0 +1,27 @@
+module I18n exposing (Translations, translationsEn, translationsFr)
+
+import Html exposing (Html, a, text)
+import Html.Attributes exposing (href)
+
+
+type alias Translations msg =
+    { greeting : String
+    , termsLink : (String -> msg) -> Html msg
+    , field : { label : String, hint : String }
+    }
+
+
+translationsEn : Translations msg
+translationsEn =
+    { greeting = "Hello"
+    , termsLink = \toMsg -> a [ href "/terms" ] [ text "Terms" ]
+    , field = { label = "Name", hint = "Your {full} name" }
+    }
+
+
+translationsFr : Translations msg
+translationsFr =
+    { greeting = "Bonjour"
+    , termsLink = \toMsg -> a [ href "/conditions" ] [ text "Conditions" ]
+    , field = { label = "Nom", hint = "Votre nom {complet}" }
+    }
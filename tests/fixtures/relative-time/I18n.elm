@@ -0,0 +1,317 @@
+module I18n exposing (..)
+
+
+type Language
+    = EN
+    | FR
+    | PL
+
+
+type alias Translations =
+    { appTitle : String
+    , relativeTimeNow : String
+    , relativeTimeSecondsAgo : { count : Int } -> String
+    , relativeTimeMinutesAgo : { count : Int } -> String
+    , relativeTimeHoursAgo : { count : Int } -> String
+    , relativeTimeDaysAgo : { count : Int } -> String
+    , relativeTimeWeeksAgo : { count : Int } -> String
+    , relativeTimeInSeconds : { count : Int } -> String
+    , relativeTimeInMinutes : { count : Int } -> String
+    , relativeTimeInHours : { count : Int } -> String
+    , relativeTimeInDays : { count : Int } -> String
+    , relativeTimeInWeeks : { count : Int } -> String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { appTitle = "App"
+    , relativeTimeNow = "just now"
+    , relativeTimeSecondsAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " second ago"
+
+        else
+            String.fromInt count ++ " seconds ago"
+    , relativeTimeMinutesAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " minute ago"
+
+        else
+            String.fromInt count ++ " minutes ago"
+    , relativeTimeHoursAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " hour ago"
+
+        else
+            String.fromInt count ++ " hours ago"
+    , relativeTimeDaysAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " day ago"
+
+        else
+            String.fromInt count ++ " days ago"
+    , relativeTimeWeeksAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " week ago"
+
+        else
+            String.fromInt count ++ " weeks ago"
+    , relativeTimeInSeconds = \{ count } ->
+        if count == 1 then
+            "in " ++ String.fromInt count ++ " second"
+
+        else
+            "in " ++ String.fromInt count ++ " seconds"
+    , relativeTimeInMinutes = \{ count } ->
+        if count == 1 then
+            "in " ++ String.fromInt count ++ " minute"
+
+        else
+            "in " ++ String.fromInt count ++ " minutes"
+    , relativeTimeInHours = \{ count } ->
+        if count == 1 then
+            "in " ++ String.fromInt count ++ " hour"
+
+        else
+            "in " ++ String.fromInt count ++ " hours"
+    , relativeTimeInDays = \{ count } ->
+        if count == 1 then
+            "in " ++ String.fromInt count ++ " day"
+
+        else
+            "in " ++ String.fromInt count ++ " days"
+    , relativeTimeInWeeks = \{ count } ->
+        if count == 1 then
+            "in " ++ String.fromInt count ++ " week"
+
+        else
+            "in " ++ String.fromInt count ++ " weeks"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { appTitle = "Appli"
+    , relativeTimeNow = "à l’instant"
+    , relativeTimeSecondsAgo = \{ count } ->
+        if count == 0 || count == 1 then
+            "il y a " ++ String.fromInt count ++ " seconde"
+
+        else
+            "il y a " ++ String.fromInt count ++ " secondes"
+    , relativeTimeMinutesAgo = \{ count } ->
+        if count == 0 || count == 1 then
+            "il y a " ++ String.fromInt count ++ " minute"
+
+        else
+            "il y a " ++ String.fromInt count ++ " minutes"
+    , relativeTimeHoursAgo = \{ count } ->
+        if count == 0 || count == 1 then
+            "il y a " ++ String.fromInt count ++ " heure"
+
+        else
+            "il y a " ++ String.fromInt count ++ " heures"
+    , relativeTimeDaysAgo = \{ count } ->
+        if count == 0 || count == 1 then
+            "il y a " ++ String.fromInt count ++ " jour"
+
+        else
+            "il y a " ++ String.fromInt count ++ " jours"
+    , relativeTimeWeeksAgo = \{ count } ->
+        if count == 0 || count == 1 then
+            "il y a " ++ String.fromInt count ++ " semaine"
+
+        else
+            "il y a " ++ String.fromInt count ++ " semaines"
+    , relativeTimeInSeconds = \{ count } ->
+        if count == 0 || count == 1 then
+            "dans " ++ String.fromInt count ++ " seconde"
+
+        else
+            "dans " ++ String.fromInt count ++ " secondes"
+    , relativeTimeInMinutes = \{ count } ->
+        if count == 0 || count == 1 then
+            "dans " ++ String.fromInt count ++ " minute"
+
+        else
+            "dans " ++ String.fromInt count ++ " minutes"
+    , relativeTimeInHours = \{ count } ->
+        if count == 0 || count == 1 then
+            "dans " ++ String.fromInt count ++ " heure"
+
+        else
+            "dans " ++ String.fromInt count ++ " heures"
+    , relativeTimeInDays = \{ count } ->
+        if count == 0 || count == 1 then
+            "dans " ++ String.fromInt count ++ " jour"
+
+        else
+            "dans " ++ String.fromInt count ++ " jours"
+    , relativeTimeInWeeks = \{ count } ->
+        if count == 0 || count == 1 then
+            "dans " ++ String.fromInt count ++ " semaine"
+
+        else
+            "dans " ++ String.fromInt count ++ " semaines"
+    }
+
+
+translationsPl : Translations
+translationsPl =
+    { appTitle = "Aplikacja"
+    , relativeTimeNow = "przed chwilą"
+    , relativeTimeSecondsAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " sekundę temu"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            String.fromInt count ++ " sekundy temu"
+
+        else
+            String.fromInt count ++ " sekund temu"
+    , relativeTimeMinutesAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " minutę temu"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            String.fromInt count ++ " minuty temu"
+
+        else
+            String.fromInt count ++ " minut temu"
+    , relativeTimeHoursAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " godzinę temu"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            String.fromInt count ++ " godziny temu"
+
+        else
+            String.fromInt count ++ " godzin temu"
+    , relativeTimeDaysAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " dzień temu"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            String.fromInt count ++ " dni temu"
+
+        else
+            String.fromInt count ++ " dni temu"
+    , relativeTimeWeeksAgo = \{ count } ->
+        if count == 1 then
+            String.fromInt count ++ " tydzień temu"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            String.fromInt count ++ " tygodnie temu"
+
+        else
+            String.fromInt count ++ " tygodni temu"
+    , relativeTimeInSeconds = \{ count } ->
+        if count == 1 then
+            "za " ++ String.fromInt count ++ " sekundę"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            "za " ++ String.fromInt count ++ " sekundy"
+
+        else
+            "za " ++ String.fromInt count ++ " sekund"
+    , relativeTimeInMinutes = \{ count } ->
+        if count == 1 then
+            "za " ++ String.fromInt count ++ " minutę"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            "za " ++ String.fromInt count ++ " minuty"
+
+        else
+            "za " ++ String.fromInt count ++ " minut"
+    , relativeTimeInHours = \{ count } ->
+        if count == 1 then
+            "za " ++ String.fromInt count ++ " godzinę"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            "za " ++ String.fromInt count ++ " godziny"
+
+        else
+            "za " ++ String.fromInt count ++ " godzin"
+    , relativeTimeInDays = \{ count } ->
+        if count == 1 then
+            "za " ++ String.fromInt count ++ " dzień"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            "za " ++ String.fromInt count ++ " dni"
+
+        else
+            "za " ++ String.fromInt count ++ " dni"
+    , relativeTimeInWeeks = \{ count } ->
+        if count == 1 then
+            "za " ++ String.fromInt count ++ " tydzień"
+
+        else if List.member (modBy 10 count) [ 2, 3, 4 ] && not (List.member (modBy 100 count) [ 12, 13, 14 ]) then
+            "za " ++ String.fromInt count ++ " tygodnie"
+
+        else
+            "za " ++ String.fromInt count ++ " tygodni"
+    }
+
+
+translations : Language -> Translations
+translations lang =
+    case lang of
+        EN ->
+            translationsEn
+
+        FR ->
+            translationsFr
+
+        PL ->
+            translationsPl
+
+
+-- elm-i18n:begin relative-time
+-- Written by `elm-i18n generate relative-time`. Run it again rather than editing
+-- down to the end marker: it rewrites all of this. The texts are translations
+-- (relativeTimeNow, relativeTimeMinutesAgo…), so translators change them there.
+
+
+{-| How long ago something happened, from the seconds since: "3 minutes ago"
+for 180, "in 3 minutes" for -180, and "just now" under 10 seconds either way
+
+Seconds count up to a minute, then minutes, hours, days and weeks, rounded down.
+
+-}
+relativeTime : Language -> Int -> String
+relativeTime lang seconds =
+    let
+        texts =
+            translations lang
+
+        elapsed =
+            abs seconds
+
+        ( ago, ahead, unit ) =
+            if elapsed < 60 then
+                ( texts.relativeTimeSecondsAgo, texts.relativeTimeInSeconds, 1 )
+
+            else if elapsed < 3600 then
+                ( texts.relativeTimeMinutesAgo, texts.relativeTimeInMinutes, 60 )
+
+            else if elapsed < 86400 then
+                ( texts.relativeTimeHoursAgo, texts.relativeTimeInHours, 3600 )
+
+            else if elapsed < 604800 then
+                ( texts.relativeTimeDaysAgo, texts.relativeTimeInDays, 86400 )
+
+            else
+                ( texts.relativeTimeWeeksAgo, texts.relativeTimeInWeeks, 604800 )
+    in
+    if elapsed < 10 then
+        texts.relativeTimeNow
+
+    else if seconds > 0 then
+        ago { count = elapsed // unit }
+
+    else
+        ahead { count = elapsed // unit }
+
+
+-- elm-i18n:end relative-time
@@ -0,0 +1,27 @@
+module I18n exposing (..)
+
+
+type alias Translations =
+    { checkoutTitle : String
+    , checkoutPay : String
+    , itemCount : Int -> String
+    , terms : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { checkoutTitle = "Checkout"
+    , checkoutPay = "Pay now"
+    , itemCount = \n -> String.fromInt n ++ " items"
+    , terms = "Read the <terms> & conditions"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { checkoutTitle = "Paiement"
+    , checkoutPay = "TODO: Payer"
+    , itemCount = \_ -> "TODO"
+    , terms = ""
+    }
@@ -0,0 +1,6 @@
+module Generated.Routes exposing (label)
+
+
+label : { t | routeHome : String } -> String
+label t =
+    t.routeHome
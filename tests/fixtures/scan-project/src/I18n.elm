@@ -0,0 +1,42 @@
+module I18n exposing (..)
+
+
+type Language
+    = EN
+    | FR
+
+
+type alias Translations =
+    { appTitle : String
+    , welcomeMessage : String
+    , signOut : String
+    , legacyBanner : String
+    }
+
+
+translationsEn : Translations
+translationsEn =
+    { appTitle = "Shop"
+    , welcomeMessage = "Welcome back"
+    , signOut = "Sign out"
+    , legacyBanner = "We moved!"
+    }
+
+
+translationsFr : Translations
+translationsFr =
+    { appTitle = "Boutique"
+    , welcomeMessage = "Bon retour"
+    , signOut = "Se déconnecter"
+    , legacyBanner = "Nous avons déménagé !"
+    }
+
+
+translations : Language -> Translations
+translations lang =
+    case lang of
+        EN ->
+            translationsEn
+
+        FR ->
+            translationsFr
@@ -0,0 +1,17 @@
+module Main exposing (main)
+
+import Html exposing (Html, h1, text)
+import I18n exposing (Language(..))
+import Page.Home
+
+
+main : Html msg
+main =
+    let
+        t =
+            I18n.translations EN
+    in
+    Html.div []
+        [ h1 [] [ text t.appTitle ]
+        , Page.Home.view t
+        ]
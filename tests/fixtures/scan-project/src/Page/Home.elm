@@ -0,0 +1,14 @@
+module Page.Home exposing (view)
+
+import Html exposing (Html, button, p, text)
+import I18n exposing (Translations)
+
+
+view : Translations -> Html msg
+view tr =
+    Html.div []
+        [ p [] [ text tr.welcomeMesage ]
+        , button [] [ text tr.signOut ]
+
+        -- The banner is gone: text tr.legacyBanner
+        ]